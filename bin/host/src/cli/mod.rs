@@ -1,9 +1,14 @@
 //! This module contains all CLI-specific code for the host binary.
 
+use crate::kv::{DiskKeyValueStore, MemoryKeyValueStore, SharedKeyValueStore};
 use alloy_primitives::B256;
+use anyhow::Result;
 use clap::{ArgAction, Parser};
 use serde::Serialize;
-use std::path::PathBuf;
+use std::{
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
 
 mod parser;
 pub(crate) use parser::parse_b256;
@@ -72,3 +77,16 @@ pub struct HostCli {
     #[clap(long)]
     pub server: bool,
 }
+
+impl HostCli {
+    /// Constructs the [SharedKeyValueStore] selected by the host configuration. A
+    /// [DiskKeyValueStore] within the data directory is used if one is set, otherwise preimages
+    /// are kept in a [MemoryKeyValueStore].
+    pub(crate) fn construct_kv_store(&self) -> Result<SharedKeyValueStore> {
+        let kv_store: SharedKeyValueStore = match self.data_dir {
+            Some(ref data_dir) => Arc::new(RwLock::new(DiskKeyValueStore::new(data_dir)?)),
+            None => Arc::new(RwLock::new(MemoryKeyValueStore::default())),
+        };
+        Ok(kv_store)
+    }
+}
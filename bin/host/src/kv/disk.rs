@@ -0,0 +1,102 @@
+//! Contains a concrete implementation of the [KeyValueStore] trait that stores data on disk, one
+//! file per key.
+
+use super::KeyValueStore;
+use alloy_primitives::{hex, keccak256, B256};
+use anyhow::Result;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+use tracing::warn;
+
+/// A simple, synchronous key-value store that writes each value to its own file within a data
+/// directory. Each file holds the [keccak256] checksum of the value followed by the value, so
+/// that corrupted entries can be detected on read and treated as missing.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct DiskKeyValueStore {
+    data_dir: PathBuf,
+}
+
+impl DiskKeyValueStore {
+    /// Create a new [DiskKeyValueStore] within the given data directory, creating the directory
+    /// if it does not exist.
+    pub(crate) fn new(data_dir: impl AsRef<Path>) -> Result<Self> {
+        let data_dir = data_dir.as_ref().to_path_buf();
+        fs::create_dir_all(&data_dir)?;
+        Ok(Self { data_dir })
+    }
+
+    /// Returns the path of the file that stores the value for the given key.
+    fn key_path(&self, key: B256) -> PathBuf {
+        self.data_dir.join(hex::encode(key))
+    }
+}
+
+impl KeyValueStore for DiskKeyValueStore {
+    fn get(&self, key: B256) -> Option<Vec<u8>> {
+        let entry = fs::read(self.key_path(key)).ok()?;
+        if entry.len() < B256::len_bytes() {
+            warn!("Truncated entry for key {key} in the disk key-value store");
+            return None;
+        }
+
+        let (checksum, value) = entry.split_at(B256::len_bytes());
+        if keccak256(value).as_slice() != checksum {
+            warn!("Checksum mismatch for key {key} in the disk key-value store");
+            return None;
+        }
+        Some(value.to_vec())
+    }
+
+    fn set(&mut self, key: B256, value: Vec<u8>) -> Result<()> {
+        let mut entry = Vec::with_capacity(B256::len_bytes() + value.len());
+        entry.extend_from_slice(keccak256(&value).as_slice());
+        entry.extend_from_slice(&value);
+
+        // Write to a temporary file first so that a crash never leaves a partial entry behind.
+        let path = self.key_path(key);
+        let tmp_path = path.with_extension("tmp");
+        fs::write(&tmp_path, entry)?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_disk_kv_roundtrip() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut kv_store = DiskKeyValueStore::new(data_dir.path()).unwrap();
+
+        let key = B256::repeat_byte(0xFF);
+        assert_eq!(kv_store.get(key), None);
+        kv_store.set(key, vec![0xDD; 100]).unwrap();
+        assert_eq!(kv_store.get(key), Some(vec![0xDD; 100]));
+        kv_store.set(key, vec![]).unwrap();
+        assert_eq!(kv_store.get(key), Some(vec![]));
+    }
+
+    #[test]
+    fn test_disk_kv_corrupted_entry_is_missing() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let mut kv_store = DiskKeyValueStore::new(data_dir.path()).unwrap();
+
+        let key = B256::repeat_byte(0xFF);
+        kv_store.set(key, vec![0xDD; 100]).unwrap();
+
+        let mut entry = fs::read(kv_store.key_path(key)).unwrap();
+        *entry.last_mut().unwrap() ^= 0x01;
+        fs::write(kv_store.key_path(key), &entry).unwrap();
+        assert_eq!(kv_store.get(key), None);
+
+        fs::write(kv_store.key_path(key), [0xDD; 8]).unwrap();
+        assert_eq!(kv_store.get(key), None);
+
+        kv_store.set(key, vec![0xDD; 100]).unwrap();
+        assert_eq!(kv_store.get(key), Some(vec![0xDD; 100]));
+    }
+}
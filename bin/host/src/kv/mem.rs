@@ -0,0 +1,24 @@
+//! Contains a concrete implementation of the [KeyValueStore] trait that stores data in memory.
+
+use super::KeyValueStore;
+use alloy_primitives::B256;
+use anyhow::Result;
+use std::collections::HashMap;
+
+/// A simple, synchronous key-value store that stores data in memory. This is useful for testing
+/// and development purposes.
+#[derive(Default, Clone, Debug, Eq, PartialEq)]
+pub(crate) struct MemoryKeyValueStore {
+    store: HashMap<B256, Vec<u8>>,
+}
+
+impl KeyValueStore for MemoryKeyValueStore {
+    fn get(&self, key: B256) -> Option<Vec<u8>> {
+        self.store.get(&key).cloned()
+    }
+
+    fn set(&mut self, key: B256, value: Vec<u8>) -> Result<()> {
+        self.store.insert(key, value);
+        Ok(())
+    }
+}
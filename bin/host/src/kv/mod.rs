@@ -0,0 +1,25 @@
+//! This module contains the [KeyValueStore] trait and its implementations, used by the host to
+//! store preimages that have been fetched from upstream sources.
+
+use alloy_primitives::B256;
+use anyhow::Result;
+use std::sync::{Arc, RwLock};
+
+mod mem;
+pub(crate) use mem::MemoryKeyValueStore;
+
+mod disk;
+pub(crate) use disk::DiskKeyValueStore;
+
+/// A type alias for a [KeyValueStore] that can be shared between the hint-serving and
+/// preimage-serving tasks.
+pub(crate) type SharedKeyValueStore = Arc<RwLock<dyn KeyValueStore + Send + Sync>>;
+
+/// Describes the interface of a simple, synchronous key-value store.
+pub(crate) trait KeyValueStore {
+    /// Get the value associated with the given key, or `None` if the key is not present.
+    fn get(&self, key: B256) -> Option<Vec<u8>>;
+
+    /// Set the value associated with the given key.
+    fn set(&mut self, key: B256, value: Vec<u8>) -> Result<()>;
+}
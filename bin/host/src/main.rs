@@ -1,13 +1,28 @@
-use crate::cli::{init_tracing_subscriber, HostCli};
+use crate::{
+    cli::{init_tracing_subscriber, HostCli},
+    server::{start_server, OfflineFetcher},
+};
 use anyhow::Result;
 use clap::Parser;
+use kona_common::FileDescriptor;
+use kona_preimage::PipeHandle;
 
 mod cli;
+mod kv;
+mod server;
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let HostCli { v: tracing_verbosity, .. } = HostCli::parse();
-    let _ = init_tracing_subscriber(tracing_verbosity);
+    let cfg = HostCli::parse();
+    let _ = init_tracing_subscriber(cfg.v);
     tracing::info!("host telemetry initialized");
+
+    let kv_store = cfg.construct_kv_store()?;
+    if cfg.server {
+        let preimage_pipe =
+            PipeHandle::new(FileDescriptor::PreimageRead, FileDescriptor::PreimageWrite);
+        let hint_pipe = PipeHandle::new(FileDescriptor::HintRead, FileDescriptor::HintWrite);
+        start_server(kv_store, OfflineFetcher, preimage_pipe, hint_pipe).await?;
+    }
     Ok(())
 }
@@ -0,0 +1,224 @@
+//! This module contains the preimage server of the host. It serves the preimage requests of the
+//! client program from the host's [KeyValueStore], falling back to an upstream [Fetcher] for the
+//! preimages missing from it, and acknowledges its hints.
+//!
+//! The host has no RPC backed fetchers yet, so it runs with the [OfflineFetcher]: only the
+//! preimages already held by the store, such as those persisted in the data directory by a
+//! previous run, can be served.
+//!
+//! [KeyValueStore]: crate::kv::KeyValueStore
+
+use crate::kv::SharedKeyValueStore;
+use alloy_primitives::B256;
+use anyhow::{anyhow, Result};
+use kona_preimage::{
+    HintReader, HintReaderServer, OracleServer, Pipe, PreimageKey, PreimageOracleServer,
+    PreimageStore,
+};
+use tracing::{debug, warn};
+
+/// A [Fetcher] fetches the preimages missing from the host's [KeyValueStore] from an upstream
+/// source, such as an L1 or L2 RPC.
+///
+/// [KeyValueStore]: crate::kv::KeyValueStore
+pub(crate) trait Fetcher {
+    /// Fetches the preimage of the `key` from upstream.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Vec<u8>))` if the preimage was fetched.
+    /// - `Ok(None)` if the upstream source does not know the preimage.
+    /// - `Err(_)` if the upstream source could not be reached.
+    fn fetch(&self, key: PreimageKey) -> Result<Option<Vec<u8>>>;
+}
+
+/// The [Fetcher] of a host without upstream sources, which knows no preimages.
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct OfflineFetcher;
+
+impl Fetcher for OfflineFetcher {
+    fn fetch(&self, _: PreimageKey) -> Result<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+/// A [PreimageStore] over the host's [SharedKeyValueStore], keyed by the raw [PreimageKey]. The
+/// preimages missing from the key-value store, or that no longer match their key, are fetched
+/// with the [Fetcher] and written through to the key-value store, so that they are served from it
+/// on the next run.
+#[derive(Clone)]
+pub(crate) struct KeyValuePreimageStore<F = OfflineFetcher> {
+    /// The key-value store holding the fetched preimages.
+    kv_store: SharedKeyValueStore,
+    /// The upstream source of the preimages missing from the key-value store.
+    fetcher: F,
+}
+
+impl<F> KeyValuePreimageStore<F> {
+    /// Creates a new [KeyValuePreimageStore] over the `kv_store`, fetching missing preimages with
+    /// the `fetcher`.
+    pub(crate) fn new(kv_store: SharedKeyValueStore, fetcher: F) -> Self {
+        Self { kv_store, fetcher }
+    }
+}
+
+impl<F: Fetcher> PreimageStore for KeyValuePreimageStore<F> {
+    fn get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+        let stored = self
+            .kv_store
+            .read()
+            .map_err(|e| anyhow!("{e}"))?
+            .get(B256::from(<[u8; 32]>::from(key)));
+        match stored {
+            Some(value) if key.validate_preimage(&value).is_ok() => return Ok(Some(value)),
+            Some(_) => warn!(target: "host_server", "Refetching corrupted preimage {key:?}"),
+            None => {}
+        }
+
+        let Some(value) = self.fetcher.fetch(key)? else { return Ok(None) };
+        self.put(key, value.clone())?;
+        Ok(Some(value))
+    }
+
+    fn put(&self, key: PreimageKey, value: Vec<u8>) -> Result<()> {
+        key.validate_preimage(&value).map_err(|e| anyhow!(e))?;
+        let mut kv_store = self.kv_store.write().map_err(|e| anyhow!("{e}"))?;
+        kv_store.set(B256::from(<[u8; 32]>::from(key)), value)
+    }
+}
+
+/// Serves the preimage requests received over `preimage_pipe` from the `kv_store`, fetching the
+/// missing preimages with the `fetcher`, and acknowledges the hints received over `hint_pipe`,
+/// until either pipe fails.
+pub(crate) async fn start_server<F, P>(
+    kv_store: SharedKeyValueStore,
+    fetcher: F,
+    preimage_pipe: P,
+    hint_pipe: P,
+) -> Result<()>
+where
+    F: Fetcher + Send + 'static,
+    P: Pipe + Send + 'static,
+{
+    // The pipes are blocking, so each side is served on its own blocking thread.
+    let store = KeyValuePreimageStore::new(kv_store, fetcher);
+    let preimage_server = tokio::task::spawn_blocking(move || {
+        OracleServer::new(preimage_pipe).serve_preimage_requests_from(&store)
+    });
+    let hint_server = tokio::task::spawn_blocking(move || {
+        let hint_reader = HintReader::new(hint_pipe);
+        loop {
+            hint_reader.next_hint(|hint| {
+                debug!(target: "host_server", "Received hint: {hint}");
+                Ok(())
+            })?;
+        }
+    });
+
+    let (preimage_result, hint_result): (Result<()>, Result<()>) =
+        tokio::try_join!(preimage_server, hint_server)?;
+    preimage_result.and(hint_result)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::kv::{DiskKeyValueStore, MemoryKeyValueStore};
+    use alloy_primitives::keccak256;
+    use kona_preimage::{
+        BidirectionalPipe, HintWriter, HintWriterClient, OracleReader, PreimageKeyType,
+        PreimageOracleClient,
+    };
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, RwLock,
+    };
+
+    /// A [Fetcher] serving `value-{i}` for the keys of [test_keys], counting its upstream calls.
+    #[derive(Default)]
+    struct CountingFetcher(AtomicUsize);
+
+    impl Fetcher for &CountingFetcher {
+        fn fetch(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+            self.0.fetch_add(1, Ordering::SeqCst);
+            Ok(test_keys().find(|(k, _)| *k == key).map(|(_, value)| value))
+        }
+    }
+
+    /// Returns the keys of 8 test preimages, with their preimages.
+    fn test_keys() -> impl Iterator<Item = (PreimageKey, Vec<u8>)> {
+        (0..8).map(|i| {
+            let value = format!("value-{i}").into_bytes();
+            (PreimageKey::new(*keccak256(&value), PreimageKeyType::Keccak256), value)
+        })
+    }
+
+    /// Gets every test preimage from a [KeyValuePreimageStore] over the `kv_store`, and returns
+    /// the number of upstream calls made.
+    fn fetch_pass(kv_store: SharedKeyValueStore) -> usize {
+        let fetcher = CountingFetcher::default();
+        let store = KeyValuePreimageStore::new(kv_store, &fetcher);
+        for (key, value) in test_keys() {
+            assert_eq!(store.get(key).unwrap(), Some(value));
+        }
+        fetcher.0.load(Ordering::SeqCst)
+    }
+
+    #[test]
+    fn test_second_pass_served_from_memory() {
+        let kv_store: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::default()));
+        assert_eq!(fetch_pass(kv_store.clone()), 8);
+        assert_eq!(fetch_pass(kv_store), 0);
+    }
+
+    #[test]
+    fn test_second_run_served_from_disk() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let open = || -> SharedKeyValueStore {
+            Arc::new(RwLock::new(DiskKeyValueStore::new(data_dir.path()).unwrap()))
+        };
+        assert_eq!(fetch_pass(open()), 8);
+        // A new store over the same data directory, as opened by the next run of the host.
+        assert_eq!(fetch_pass(open()), 0);
+    }
+
+    #[test]
+    fn test_corrupted_preimage_refetched() {
+        let (key, value) = test_keys().next().unwrap();
+        let kv_store: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::default()));
+        kv_store
+            .write()
+            .unwrap()
+            .set(B256::from(<[u8; 32]>::from(key)), b"corrupted".to_vec())
+            .unwrap();
+
+        let fetcher = CountingFetcher::default();
+        let store = KeyValuePreimageStore::new(kv_store, &fetcher);
+        assert_eq!(store.get(key).unwrap(), Some(value.clone()));
+        assert_eq!(store.get(key).unwrap(), Some(value));
+        assert_eq!(fetcher.0.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_serve_preimages_from_kv_store() {
+        const MOCK_DATA: &[u8] = b"1234567890";
+        let key = PreimageKey::new(*keccak256(MOCK_DATA), PreimageKeyType::Keccak256);
+        let kv_store: SharedKeyValueStore = Arc::new(RwLock::new(MemoryKeyValueStore::default()));
+        let store = KeyValuePreimageStore::new(kv_store.clone(), OfflineFetcher);
+        store.put(key, MOCK_DATA.to_vec()).unwrap();
+        assert!(store.put(key, b"0987654321".to_vec()).is_err());
+
+        let (preimage_client, preimage_host) = BidirectionalPipe::pair();
+        let (hint_client, hint_host) = BidirectionalPipe::pair();
+        let server = tokio::spawn(start_server(kv_store, OfflineFetcher, preimage_host, hint_host));
+
+        let client = tokio::task::spawn_blocking(move || {
+            HintWriter::new(hint_client).write("l1-block-header 0x00").unwrap();
+            let reader = OracleReader::new(preimage_client);
+            assert_eq!(reader.get(key).unwrap(), MOCK_DATA);
+            // A preimage missing from the store, and unknown upstream, fails the server.
+            assert!(reader.get(PreimageKey::new_local(1)).is_err());
+        });
+        client.await.unwrap();
+        assert!(server.await.unwrap().is_err());
+    }
+}
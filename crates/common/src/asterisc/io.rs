@@ -1,7 +1,8 @@
 use crate::{asterisc::syscall, BasicKernelInterface, FileDescriptor};
 use anyhow::Result;
 
-/// Concrete implementation of the [`KernelIO`] trait for the `riscv64` target architecture.
+/// Concrete implementation of the [BasicKernelInterface] trait for the `riscv64` target
+/// architecture.
 #[derive(Debug)]
 pub struct AsteriscIO;
 
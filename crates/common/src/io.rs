@@ -63,6 +63,7 @@ mod native_io {
     use std::{
         fs::File,
         io::{Read, Seek, SeekFrom, Write},
+        mem::ManuallyDrop,
         os::fd::FromRawFd,
     };
 
@@ -73,30 +74,32 @@ mod native_io {
     impl BasicKernelInterface for NativeIO {
         fn write(fd: FileDescriptor, buf: &[u8]) -> Result<usize> {
             let raw_fd: usize = fd.into();
-            let mut file = unsafe { File::from_raw_fd(raw_fd as i32) };
+            // The file descriptor is borrowed, so the `Drop` impl must not close it, even when
+            // the write fails.
+            let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(raw_fd as i32) });
             let n = file
                 .write(buf)
                 .map_err(|e| anyhow!("Error writing to buffer to file descriptor: {e}"))?;
 
             // Reset the cursor back to before the data we just wrote for the reader's consumption.
-            file.seek(SeekFrom::Current(-(buf.len() as i64)))
-                .map_err(|e| anyhow!("Failed to reset file cursor to 0: {e}"))?;
-
-            // forget the file descriptor so that the `Drop` impl doesn't close it.
-            std::mem::forget(file);
+            // Pipes are not seekable, and the reader consumes the data from the other end.
+            let is_file = file.metadata().map(|m| m.is_file()).unwrap_or_default();
+            if is_file {
+                file.seek(SeekFrom::Current(-(n as i64)))
+                    .map_err(|e| anyhow!("Failed to reset file cursor to 0: {e}"))?;
+            }
 
             Ok(n)
         }
 
         fn read(fd: FileDescriptor, buf: &mut [u8]) -> Result<usize> {
             let raw_fd: usize = fd.into();
-            let mut file = unsafe { File::from_raw_fd(raw_fd as i32) };
+            // The file descriptor is borrowed, so the `Drop` impl must not close it, even when
+            // the read fails.
+            let mut file = ManuallyDrop::new(unsafe { File::from_raw_fd(raw_fd as i32) });
             let n =
                 file.read(buf).map_err(|e| anyhow!("Error reading from file descriptor: {e}"))?;
 
-            // forget the file descriptor so that the `Drop` impl doesn't close it.
-            std::mem::forget(file);
-
             Ok(n)
         }
 
@@ -105,3 +108,38 @@ mod native_io {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    extern crate std;
+
+    use super::*;
+    use std::os::fd::RawFd;
+
+    /// Creates an OS pipe, returning its read and write ends as [FileDescriptor]s.
+    fn pipe() -> (FileDescriptor, FileDescriptor) {
+        let mut fds: [RawFd; 2] = [0; 2];
+        assert_eq!(unsafe { libc::pipe(fds.as_mut_ptr()) }, 0);
+        (FileDescriptor::Wildcard(fds[0] as usize), FileDescriptor::Wildcard(fds[1] as usize))
+    }
+
+    fn close(fd: FileDescriptor) {
+        let raw: usize = fd.into();
+        assert_eq!(unsafe { libc::close(raw as RawFd) }, 0);
+    }
+
+    #[test]
+    fn test_wildcard_pipe_roundtrip() {
+        let (read_fd, write_fd) = pipe();
+
+        let data = b"hello from the other side";
+        assert_eq!(write(write_fd, data).unwrap(), data.len());
+
+        let mut buf = [0u8; 25];
+        assert_eq!(read(read_fd, &mut buf).unwrap(), data.len());
+        assert_eq!(&buf, data);
+
+        close(write_fd);
+        close(read_fd);
+    }
+}
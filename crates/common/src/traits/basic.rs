@@ -11,6 +11,10 @@ use anyhow::Result;
 ///
 /// In cases where the set of system calls defined in this trait need to be extended, an additional
 /// trait should be created that extends this trait.
+///
+/// Targets other than the ones supported by this crate can implement this trait on their own type
+/// outside of this crate. File descriptors beyond the fixed set of [FileDescriptor] are passed
+/// through as [FileDescriptor::Wildcard].
 pub trait BasicKernelInterface {
     /// Write the given buffer to the given file descriptor.
     fn write(fd: FileDescriptor, buf: &[u8]) -> Result<usize>;
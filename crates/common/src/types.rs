@@ -1,7 +1,10 @@
 //! This module contains the local types for the `kona-common` crate.
 
 /// File descriptors available to the `client` within the FPVM kernel.
-#[derive(Debug, Clone, Copy)]
+///
+/// The numbering of the standard streams and the preimage oracle pipes is fixed. Any other raw
+/// file descriptor exposed by a target can be addressed with [FileDescriptor::Wildcard].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum FileDescriptor {
     /// Read-only standard input stream.
     StdIn,
@@ -17,7 +20,8 @@ pub enum FileDescriptor {
     PreimageRead,
     /// Write-only. Used to request pre-images.
     PreimageWrite,
-    /// Other file descriptor, usually used for testing purposes.
+    /// Any other raw file descriptor, e.g. one exposed by a target beyond the fixed set above, or
+    /// an OS pipe in native tests.
     Wildcard(usize),
 }
 
@@ -35,3 +39,44 @@ impl From<FileDescriptor> for usize {
         }
     }
 }
+
+impl From<usize> for FileDescriptor {
+    /// Creates a [FileDescriptor] from a raw file descriptor. Raw descriptors of the fixed set map
+    /// to their named variant, all others to [FileDescriptor::Wildcard].
+    fn from(fd: usize) -> Self {
+        match fd {
+            0 => FileDescriptor::StdIn,
+            1 => FileDescriptor::StdOut,
+            2 => FileDescriptor::StdErr,
+            3 => FileDescriptor::HintRead,
+            4 => FileDescriptor::HintWrite,
+            5 => FileDescriptor::PreimageRead,
+            6 => FileDescriptor::PreimageWrite,
+            value => FileDescriptor::Wildcard(value),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_file_descriptor_numbering() {
+        let fixed = [
+            FileDescriptor::StdIn,
+            FileDescriptor::StdOut,
+            FileDescriptor::StdErr,
+            FileDescriptor::HintRead,
+            FileDescriptor::HintWrite,
+            FileDescriptor::PreimageRead,
+            FileDescriptor::PreimageWrite,
+        ];
+        for (raw, fd) in fixed.into_iter().enumerate() {
+            assert_eq!(usize::from(fd), raw);
+            assert_eq!(FileDescriptor::from(raw), fd);
+        }
+        assert_eq!(FileDescriptor::from(7), FileDescriptor::Wildcard(7));
+        assert_eq!(usize::from(FileDescriptor::Wildcard(42)), 42);
+    }
+}
@@ -2,8 +2,11 @@
 
 use crate::{
     stages::NextAttributes,
-    traits::{OriginAdvancer, ResettableStage},
-    types::{StageError, StageResult},
+    traits::{
+        CheckpointableStage, FlushableStage, OriginAdvancer, OriginProvider, Pipeline,
+        ResettableStage,
+    },
+    types::{PipelineCheckpoint, Signal, StageError, StageResult, StepResult},
 };
 use alloc::{boxed::Box, collections::VecDeque};
 use async_trait::async_trait;
@@ -23,7 +26,13 @@ pub trait ResetProvider {
 /// The derivation pipeline is responsible for deriving L2 inputs from L1 data.
 #[derive(Debug)]
 pub struct DerivationPipeline<
-    S: NextAttributes + ResettableStage + OriginAdvancer + Debug + Send,
+    S: NextAttributes
+        + ResettableStage
+        + FlushableStage
+        + OriginAdvancer
+        + OriginProvider
+        + Debug
+        + Send,
     R: ResetProvider + Send,
 > {
     /// A handle to the next attributes.
@@ -32,40 +41,33 @@ pub struct DerivationPipeline<
     pub reset: R,
     /// A list of prepared [L2AttributesWithParent] to be used by the derivation pipeline consumer.
     pub prepared: VecDeque<L2AttributesWithParent>,
-    /// A flag to tell the pipeline to reset.
-    pub needs_reset: bool,
-    /// A cursor for the [L2BlockInfo] parent to be used when pulling the next attributes.
-    pub cursor: L2BlockInfo,
 }
 
 impl<
-        S: NextAttributes + ResettableStage + OriginAdvancer + Debug + Send,
+        S: NextAttributes
+            + ResettableStage
+            + FlushableStage
+            + OriginAdvancer
+            + OriginProvider
+            + Debug
+            + Send,
         R: ResetProvider + Send,
     > DerivationPipeline<S, R>
 {
     /// Creates a new instance of the [DerivationPipeline].
-    pub fn new(attributes: S, reset: R, cursor: L2BlockInfo) -> Self {
-        Self { attributes, prepared: VecDeque::new(), reset, needs_reset: false, cursor }
+    pub fn new(attributes: S, reset: R) -> Self {
+        Self { attributes, prepared: VecDeque::new(), reset }
     }
 
-    /// Set the [L2BlockInfo] cursor to be used when pulling the next attributes.
-    pub fn set_cursor(&mut self, cursor: L2BlockInfo) {
-        self.cursor = cursor;
-    }
-
-    /// Returns the next [L2AttributesWithParent] from the pipeline.
-    pub fn next_attributes(&mut self) -> Option<L2AttributesWithParent> {
-        self.prepared.pop_front()
-    }
-
-    /// Flags the pipeline to reset on the next [DerivationPipeline::step] call.
-    pub fn reset(&mut self) {
-        self.needs_reset = true;
-    }
-
-    /// Resets the pipeline.
-    async fn reset_pipe(&mut self, bi: BlockInfo, sc: &SystemConfig) -> StageResult<()> {
-        match self.attributes.reset(bi, sc).await {
+    /// Resets every stage of the pipeline to the given L1 origin, to continue derivation on top
+    /// of the given L2 safe head.
+    async fn reset_pipe(
+        &mut self,
+        bi: BlockInfo,
+        safe_head: L2BlockInfo,
+        sc: &SystemConfig,
+    ) -> StageResult<()> {
+        match self.attributes.reset(bi, safe_head, sc).await {
             Ok(()) => {
                 tracing::info!("Stages reset");
             }
@@ -79,42 +81,202 @@ impl<
         }
         Ok(())
     }
+}
+
+impl<
+        S: NextAttributes
+            + ResettableStage
+            + FlushableStage
+            + CheckpointableStage
+            + OriginAdvancer
+            + OriginProvider
+            + Debug
+            + Send,
+        R: ResetProvider + Send,
+    > DerivationPipeline<S, R>
+{
+    /// Takes a [PipelineCheckpoint] of the state of every stage, along with the prepared
+    /// attributes that were not consumed yet.
+    pub fn checkpoint(&self) -> PipelineCheckpoint {
+        let mut checkpoint = PipelineCheckpoint::default();
+        self.attributes.checkpoint(&mut checkpoint);
+        checkpoint.prepared = self.prepared.iter().cloned().collect();
+        checkpoint
+    }
+
+    /// Restores a [PipelineCheckpoint] onto a freshly built stack of stages, which supplies the
+    /// providers that are not part of the checkpoint. The restored pipeline continues derivation
+    /// where the checkpointed pipeline left off, and must not be reset before stepping it.
+    pub async fn restore(
+        checkpoint: PipelineCheckpoint,
+        attributes: S,
+        reset: R,
+    ) -> StageResult<Self> {
+        let mut pipeline = Self::new(attributes, reset);
+        pipeline.attributes.restore(&checkpoint).await?;
+        pipeline.prepared = checkpoint.prepared.into();
+        Ok(pipeline)
+    }
+}
+
+impl<
+        S: NextAttributes
+            + ResettableStage
+            + FlushableStage
+            + OriginAdvancer
+            + OriginProvider
+            + Debug
+            + Send,
+        R: ResetProvider + Send,
+    > OriginProvider for DerivationPipeline<S, R>
+{
+    fn origin(&self) -> Option<&BlockInfo> {
+        self.attributes.origin()
+    }
+}
+
+#[async_trait]
+impl<
+        S: NextAttributes
+            + ResettableStage
+            + FlushableStage
+            + OriginAdvancer
+            + OriginProvider
+            + Debug
+            + Send,
+        R: ResetProvider + Send,
+    > Pipeline for DerivationPipeline<S, R>
+{
+    fn next_attributes(&mut self) -> Option<L2AttributesWithParent> {
+        self.prepared.pop_front()
+    }
 
     /// Attempts to progress the pipeline.
-    /// A [StageError::Eof] is returned if the pipeline is blocked by waiting for new L1 data.
-    /// Any other error is critical and the derivation pipeline should be reset.
-    /// An error is expected when the underlying source closes.
-    /// When [DerivationPipeline::step] returns [Ok(())], it should be called again, to continue the
-    /// derivation process.
-    pub async fn step(&mut self) -> StageResult<()> {
+    ///
+    /// A [StepResult::AdvancedOrigin] is returned when the pipeline exhausted the data for the
+    /// current L1 origin and moved on to the next one. A [StepResult::OriginAdvanceErr] with a
+    /// [StageError::Eof] means the pipeline is blocked waiting for new L1 data. Any
+    /// [StepResult::StepFailed] is critical and the pipeline should be reset with a
+    /// [Signal::Reset].
+    async fn step(&mut self, cursor: L2BlockInfo) -> StepResult {
         tracing::info!("DerivationPipeline::step");
 
-        // Reset the pipeline if needed.
-        if self.needs_reset {
-            let block_info = self.reset.block_info().await;
-            let system_config = self.reset.system_config().await;
-            self.reset_pipe(block_info, &system_config).await?;
-            self.needs_reset = false;
-        }
-
-        match self.attributes.next_attributes(self.cursor).await {
+        match self.attributes.next_attributes(cursor).await {
             Ok(a) => {
                 tracing::info!("attributes queue stage step returned l2 attributes");
                 tracing::info!("prepared L2 attributes: {:?}", a);
                 self.prepared.push_back(a);
-                return Ok(());
+                StepResult::PreparedAttributes
             }
             Err(StageError::Eof) => {
                 tracing::info!("attributes queue stage complete");
-                self.attributes.advance_origin().await?;
+                match self.attributes.advance_origin().await {
+                    Ok(()) => StepResult::AdvancedOrigin,
+                    Err(e) => StepResult::OriginAdvanceErr(e),
+                }
             }
             // TODO: match on the EngineELSyncing error here and log
             Err(err) => {
                 tracing::error!("attributes queue stage failed: {:?}", err);
-                return Err(err);
+                StepResult::StepFailed(err)
             }
         }
+    }
 
+    /// Propagates the [Signal] through every stage of the pipeline.
+    ///
+    /// A [Signal::Reset] clears all buffered data, including any prepared attributes, and
+    /// re-opens the data source at the given L1 origin.
+    async fn signal(&mut self, signal: Signal) -> StageResult<()> {
+        match signal {
+            Signal::Reset { l2_safe_head, l1_origin } => {
+                tracing::info!(
+                    "resetting pipeline to l1 origin {} at l2 safe head {}",
+                    l1_origin.number,
+                    l2_safe_head.block_info.number
+                );
+                let system_config = self.reset.system_config().await;
+                self.reset_pipe(l1_origin, l2_safe_head, &system_config).await?;
+                self.prepared.clear();
+            }
+            Signal::FlushChannel => {
+                tracing::info!("flushing pipeline channel data");
+                self.attributes.flush_channel().await?;
+            }
+        }
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        stages::{
+            test_utils::{new_attributes_provider, MockAttributesBuilder, MockAttributesProvider},
+            AttributesQueue,
+        },
+        types::{RollupConfig, SingleBatch},
+    };
+    use alloc::{vec, vec::Vec};
+    use alloy_primitives::B256;
+
+    #[derive(Debug, Default)]
+    struct TestResetProvider {
+        system_config: SystemConfig,
+    }
+
+    #[async_trait]
+    impl ResetProvider for TestResetProvider {
+        async fn block_info(&self) -> BlockInfo {
+            BlockInfo::default()
+        }
+
+        async fn system_config(&self) -> SystemConfig {
+            self.system_config
+        }
+    }
+
+    fn new_test_pipeline(
+        batches: Vec<StageResult<SingleBatch>>,
+    ) -> DerivationPipeline<
+        AttributesQueue<MockAttributesProvider, MockAttributesBuilder>,
+        TestResetProvider,
+    > {
+        let provider = new_attributes_provider(Some(BlockInfo::default()), batches);
+        let queue = AttributesQueue::new(
+            RollupConfig::default(),
+            provider,
+            MockAttributesBuilder::default(),
+        );
+        DerivationPipeline::new(queue, TestResetProvider::default())
+    }
+
+    #[tokio::test]
+    async fn test_step_advances_origin_on_eof() {
+        let mut pipeline = new_test_pipeline(vec![]);
+        let result = pipeline.step(L2BlockInfo::default()).await;
+        assert_eq!(result, StepResult::AdvancedOrigin);
+        assert!(pipeline.next_attributes().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_step_failed() {
+        let batch =
+            SingleBatch { parent_hash: B256::left_padding_from(&[0xFF]), ..Default::default() };
+        let mut pipeline = new_test_pipeline(vec![Ok(batch)]);
+        let result = pipeline.step(L2BlockInfo::default()).await;
+        assert!(matches!(result, StepResult::StepFailed(StageError::Reset(_))));
+    }
+
+    #[tokio::test]
+    async fn test_flush_channel_drops_buffered_batch() {
+        let stale = SingleBatch::default();
+        let mut pipeline = new_test_pipeline(vec![Ok(stale)]);
+        let result = pipeline.step(L2BlockInfo::default()).await;
+        assert!(matches!(result, StepResult::StepFailed(StageError::AttributesBuild(_))));
+        pipeline.signal(Signal::FlushChannel).await.unwrap();
+        let result = pipeline.step(L2BlockInfo::default()).await;
+        assert_eq!(result, StepResult::AdvancedOrigin);
+    }
+}
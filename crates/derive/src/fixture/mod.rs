@@ -0,0 +1,79 @@
+//! A replay harness for derivation test fixtures.
+//!
+//! A [DerivationFixture] describes an L1 chain, the rollup config of the L2 chain derived from
+//! it, and the L2 blocks that the pipeline is expected to derive, similar to the test vectors
+//! produced by the op-e2e action tests. [DerivationFixture::replay] runs the full derivation
+//! pipeline over the fixture and checks every set of payload attributes it produces against the
+//! expected ones, reporting the first divergence down to the field.
+//!
+//! Test vectors laid out as in `op-test-vectors` are loaded as an [OpTestVectorsFixture], and
+//! converted into a [DerivationFixture] to be replayed.
+
+use crate::types::{Blob, BlockInfo, L2BlockInfo, L2PayloadAttributes, RollupConfig, SystemConfig};
+use alloc::vec::Vec;
+use alloy_consensus::Receipt;
+use alloy_primitives::{Bytes, B256};
+use serde::{Deserialize, Serialize};
+
+mod op_test_vectors;
+pub use op_test_vectors::{OpTestVectorsFixture, OpTestVectorsL1Block};
+
+mod providers;
+pub use providers::{
+    FixtureBlobProvider, FixtureChainProvider, FixtureL2ChainProvider, FixtureResetProvider,
+};
+
+mod replay;
+pub use replay::{AttributesDiff, FixtureError};
+
+/// A derivation test fixture.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DerivationFixture {
+    /// The rollup config of the L2 chain. Its genesis is the starting point of the replay.
+    pub rollup_config: RollupConfig,
+    /// The L1 chain, in order, starting at the L1 genesis of the rollup.
+    pub l1_blocks: Vec<FixtureL1Block>,
+    /// The L2 blocks following the L2 genesis, in the order they are expected to be derived.
+    pub expected: Vec<FixtureL2Block>,
+}
+
+impl DerivationFixture {
+    /// Returns the L2 genesis block that the fixture is derived on top of.
+    pub fn l2_genesis(&self) -> L2BlockInfo {
+        let genesis = &self.rollup_config.genesis;
+        L2BlockInfo::new(
+            BlockInfo::new(genesis.l2.hash, genesis.l2.number, B256::ZERO, genesis.timestamp),
+            genesis.l1,
+            0,
+        )
+    }
+}
+
+/// An L1 block of a [DerivationFixture].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixtureL1Block {
+    /// The RLP encoded block header.
+    pub header: Bytes,
+    /// The EIP-2718 encoded transactions of the block.
+    #[serde(default)]
+    pub transactions: Vec<Bytes>,
+    /// The receipts of the block.
+    #[serde(default)]
+    pub receipts: Vec<Receipt>,
+    /// The blobs of the block, ordered by their index in the block.
+    /// Blocks before Ecotone carry no blobs and may omit this field.
+    #[serde(default)]
+    pub blobs: Vec<Blob>,
+}
+
+/// An expected L2 block of a [DerivationFixture].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FixtureL2Block {
+    /// The L2 block, which becomes the safe head once its attributes match.
+    pub block: L2BlockInfo,
+    /// The payload attributes the block is expected to be built from.
+    pub attributes: L2PayloadAttributes,
+    /// The [SystemConfig] after the block, if it changed since the previous block.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_config: Option<SystemConfig>,
+}
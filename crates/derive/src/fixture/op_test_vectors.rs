@@ -0,0 +1,180 @@
+//! Loads derivation test vectors in the format of `op-test-vectors`, which are generated from the
+//! op-e2e action tests, into a [DerivationFixture].
+
+use super::{DerivationFixture, FixtureError, FixtureL1Block, FixtureL2Block};
+use crate::types::{Blob, L2BlockInfo, L2PayloadAttributes, RollupConfig, SystemConfig};
+use alloc::{collections::BTreeMap, vec::Vec};
+use alloy_consensus::{Header, Receipt};
+use alloy_primitives::Bytes;
+use serde::{Deserialize, Serialize};
+
+/// A derivation test vector, as generated by the op-e2e action tests into `op-test-vectors`.
+///
+/// The expected L2 blocks are keyed by number, and span the blocks after the
+/// [OpTestVectorsFixture::l2_cursor_start] up to the [OpTestVectorsFixture::l2_cursor_end].
+/// It is replayed by converting it into a [DerivationFixture].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpTestVectorsFixture {
+    /// The rollup config of the L2 chain.
+    pub rollup_config: RollupConfig,
+    /// The L1 chain, in order, starting at the L1 genesis of the rollup.
+    pub l1_blocks: Vec<OpTestVectorsL1Block>,
+    /// The expected payload attributes, keyed by L2 block number.
+    pub l2_payloads: BTreeMap<u64, L2PayloadAttributes>,
+    /// The [SystemConfig] after each L2 block, keyed by L2 block number.
+    #[serde(default)]
+    pub l2_system_configs: BTreeMap<u64, SystemConfig>,
+    /// The expected L2 blocks, keyed by L2 block number.
+    pub l2_block_infos: BTreeMap<u64, L2BlockInfo>,
+    /// The L2 block derivation starts on top of.
+    pub l2_cursor_start: u64,
+    /// The last L2 block expected to be derived.
+    pub l2_cursor_end: u64,
+}
+
+/// An L1 block of an [OpTestVectorsFixture].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpTestVectorsL1Block {
+    /// The block header.
+    pub header: Header,
+    /// The EIP-2718 encoded transactions of the block.
+    #[serde(default)]
+    pub transactions: Vec<Bytes>,
+    /// The blobs of the block, ordered by their index in the block.
+    #[serde(default)]
+    pub blobs: Vec<Blob>,
+    /// The receipts of the block.
+    #[serde(default)]
+    pub receipts: Vec<Receipt>,
+}
+
+impl From<OpTestVectorsL1Block> for FixtureL1Block {
+    fn from(block: OpTestVectorsL1Block) -> Self {
+        Self {
+            header: alloy_rlp::encode(&block.header).into(),
+            transactions: block.transactions,
+            receipts: block.receipts,
+            blobs: block.blobs,
+        }
+    }
+}
+
+impl TryFrom<OpTestVectorsFixture> for DerivationFixture {
+    type Error = FixtureError;
+
+    fn try_from(mut vectors: OpTestVectorsFixture) -> Result<Self, Self::Error> {
+        // The replay always starts at the L2 genesis of the rollup.
+        if vectors.l2_cursor_start != vectors.rollup_config.genesis.l2.number {
+            return Err(FixtureError::UnsupportedCursorStart(vectors.l2_cursor_start));
+        }
+
+        let mut system_config = vectors.rollup_config.genesis.system_config;
+        let expected = (vectors.l2_cursor_start + 1..=vectors.l2_cursor_end)
+            .map(|number| {
+                let block = vectors
+                    .l2_block_infos
+                    .remove(&number)
+                    .ok_or(FixtureError::MissingL2Block(number))?;
+                let attributes = vectors
+                    .l2_payloads
+                    .remove(&number)
+                    .ok_or(FixtureError::MissingL2Block(number))?;
+                // The fixture only records the system config when it changes.
+                let changed = vectors
+                    .l2_system_configs
+                    .get(&number)
+                    .filter(|config| **config != system_config)
+                    .copied();
+                if let Some(config) = changed {
+                    system_config = config;
+                }
+                Ok(FixtureL2Block { block, attributes, system_config: changed })
+            })
+            .collect::<Result<Vec<_>, FixtureError>>()?;
+
+        Ok(Self {
+            rollup_config: vectors.rollup_config,
+            l1_blocks: vectors.l1_blocks.into_iter().map(Into::into).collect(),
+            expected,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_rlp::Decodable;
+
+    fn fixture() -> DerivationFixture {
+        let raw = include_str!("../../testdata/fixtures/ecotone_activation.json");
+        serde_json::from_str(raw).unwrap()
+    }
+
+    /// Lays out the given fixture as a test vector, recording the system config after every block.
+    fn to_vectors(fixture: &DerivationFixture) -> OpTestVectorsFixture {
+        let l1_blocks = fixture
+            .l1_blocks
+            .iter()
+            .map(|block| OpTestVectorsL1Block {
+                header: Header::decode(&mut block.header.as_ref()).unwrap(),
+                transactions: block.transactions.clone(),
+                blobs: block.blobs.clone(),
+                receipts: block.receipts.clone(),
+            })
+            .collect();
+        let mut system_config = fixture.rollup_config.genesis.system_config;
+        let mut vectors = OpTestVectorsFixture {
+            rollup_config: fixture.rollup_config.clone(),
+            l1_blocks,
+            l2_payloads: BTreeMap::new(),
+            l2_system_configs: BTreeMap::new(),
+            l2_block_infos: BTreeMap::new(),
+            l2_cursor_start: fixture.rollup_config.genesis.l2.number,
+            l2_cursor_end: fixture.expected.last().unwrap().block.block_info.number,
+        };
+        for expected in &fixture.expected {
+            let number = expected.block.block_info.number;
+            system_config = expected.system_config.unwrap_or(system_config);
+            vectors.l2_payloads.insert(number, expected.attributes.clone());
+            vectors.l2_system_configs.insert(number, system_config);
+            vectors.l2_block_infos.insert(number, expected.block);
+        }
+        vectors
+    }
+
+    #[tokio::test]
+    async fn test_replay_op_test_vectors() {
+        let fixture = fixture();
+        let raw = serde_json::to_string(&to_vectors(&fixture)).unwrap();
+        for field in ["l1Blocks", "l2Payloads", "l2SystemConfigs", "l2BlockInfos", "l2CursorEnd"] {
+            assert!(raw.contains(field), "missing field {field}");
+        }
+
+        let vectors: OpTestVectorsFixture = serde_json::from_str(&raw).unwrap();
+        let converted = DerivationFixture::try_from(vectors).unwrap();
+        assert_eq!(converted, fixture);
+        assert_eq!(converted.replay().await, Ok(fixture.expected.len()));
+    }
+
+    #[test]
+    fn test_op_test_vectors_missing_block() {
+        let fixture = fixture();
+        let mut vectors = to_vectors(&fixture);
+        let number = fixture.expected[1].block.block_info.number;
+        vectors.l2_payloads.remove(&number);
+        assert_eq!(DerivationFixture::try_from(vectors), Err(FixtureError::MissingL2Block(number)));
+    }
+
+    #[test]
+    fn test_op_test_vectors_cursor_start() {
+        let mut vectors = to_vectors(&fixture());
+        vectors.l2_cursor_start += 1;
+        let start = vectors.l2_cursor_start;
+        assert_eq!(
+            DerivationFixture::try_from(vectors),
+            Err(FixtureError::UnsupportedCursorStart(start))
+        );
+    }
+}
@@ -0,0 +1,221 @@
+//! Providers serving the data of a [DerivationFixture] to the pipeline.
+
+use super::{DerivationFixture, FixtureError, FixtureL1Block, FixtureL2Block};
+use crate::{
+    builder::ResetProvider,
+    traits::{BlobProvider, ChainProvider, L2ChainProvider},
+    types::{
+        Blob, BlobProviderError, BlockInfo, IndexedBlobHash, L2BlockInfo,
+        L2ExecutionPayloadEnvelope, RollupConfig, SystemConfig,
+    },
+};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
+use alloy_consensus::{Header, Receipt, TxEnvelope};
+use alloy_primitives::B256;
+use alloy_rlp::Decodable;
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+
+/// A decoded [FixtureL1Block].
+#[derive(Debug, Clone)]
+struct L1Block {
+    header: Header,
+    info: BlockInfo,
+    transactions: Vec<TxEnvelope>,
+    receipts: Vec<Receipt>,
+    blobs: Vec<Blob>,
+}
+
+/// A [ChainProvider] serving the L1 chain of a [DerivationFixture].
+#[derive(Debug, Clone)]
+pub struct FixtureChainProvider {
+    blocks: Arc<Vec<L1Block>>,
+}
+
+impl FixtureChainProvider {
+    /// Creates a new [FixtureChainProvider], decoding the headers and transactions of the given
+    /// blocks.
+    pub fn try_new(blocks: &[FixtureL1Block]) -> Result<Self, FixtureError> {
+        let blocks = blocks
+            .iter()
+            .enumerate()
+            .map(|(i, block)| {
+                let header = Header::decode(&mut block.header.as_ref())
+                    .map_err(|e| FixtureError::InvalidL1Block(i, e))?;
+                let transactions = block
+                    .transactions
+                    .iter()
+                    .map(|tx| TxEnvelope::decode(&mut tx.as_ref()))
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| FixtureError::InvalidL1Block(i, e))?;
+                let info = BlockInfo::new(
+                    header.hash_slow(),
+                    header.number,
+                    header.parent_hash,
+                    header.timestamp,
+                );
+                Ok(L1Block {
+                    header,
+                    info,
+                    transactions,
+                    receipts: block.receipts.clone(),
+                    blobs: block.blobs.clone(),
+                })
+            })
+            .collect::<Result<Vec<_>, FixtureError>>()?;
+        Ok(Self { blocks: Arc::new(blocks) })
+    }
+
+    /// Returns a [FixtureBlobProvider] serving the blobs of the same L1 chain.
+    pub fn blob_provider(&self) -> FixtureBlobProvider {
+        FixtureBlobProvider { blocks: self.blocks.clone() }
+    }
+
+    fn block_by_hash(&self, hash: B256) -> Result<&L1Block> {
+        self.blocks
+            .iter()
+            .find(|b| b.info.hash == hash)
+            .ok_or_else(|| anyhow!("L1 block {hash} is not part of the fixture"))
+    }
+}
+
+#[async_trait]
+impl ChainProvider for FixtureChainProvider {
+    async fn header_by_hash(&mut self, hash: B256) -> Result<Header> {
+        Ok(self.block_by_hash(hash)?.header.clone())
+    }
+
+    async fn block_info_by_number(&mut self, number: u64) -> Result<BlockInfo> {
+        self.blocks
+            .iter()
+            .find(|b| b.info.number == number)
+            .map(|b| b.info)
+            .ok_or_else(|| anyhow!("L1 block {number} is not part of the fixture"))
+    }
+
+    async fn receipts_by_hash(&mut self, hash: B256) -> Result<Vec<Receipt>> {
+        Ok(self.block_by_hash(hash)?.receipts.clone())
+    }
+
+    async fn block_info_and_transactions_by_hash(
+        &mut self,
+        hash: B256,
+    ) -> Result<(BlockInfo, Vec<TxEnvelope>)> {
+        let block = self.block_by_hash(hash)?;
+        Ok((block.info, block.transactions.clone()))
+    }
+}
+
+/// A [BlobProvider] serving the blobs of a [DerivationFixture].
+#[derive(Debug, Clone)]
+pub struct FixtureBlobProvider {
+    blocks: Arc<Vec<L1Block>>,
+}
+
+#[async_trait]
+impl BlobProvider for FixtureBlobProvider {
+    async fn get_blobs(
+        &mut self,
+        block_ref: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Blob>, BlobProviderError> {
+        let block =
+            self.blocks.iter().find(|b| b.info.hash == block_ref.hash).ok_or_else(|| {
+                BlobProviderError::Custom(anyhow!(
+                    "L1 block {block_ref} is not part of the fixture"
+                ))
+            })?;
+        blob_hashes
+            .iter()
+            .map(|h| {
+                block.blobs.get(h.index).copied().ok_or_else(|| {
+                    BlobProviderError::Custom(anyhow!(
+                        "blob {} of L1 block {} is not part of the fixture",
+                        h.index,
+                        block_ref.number
+                    ))
+                })
+            })
+            .collect()
+    }
+}
+
+/// An [L2ChainProvider] serving the expected L2 chain of a [DerivationFixture].
+///
+/// Execution payloads are not part of a fixture, so span batches that overlap the safe chain
+/// cannot be validated against it.
+#[derive(Debug, Clone)]
+pub struct FixtureL2ChainProvider {
+    genesis: L2BlockInfo,
+    genesis_system_config: SystemConfig,
+    blocks: Arc<Vec<FixtureL2Block>>,
+}
+
+impl FixtureL2ChainProvider {
+    /// Creates a new [FixtureL2ChainProvider] from the given fixture.
+    pub fn new(fixture: &DerivationFixture) -> Self {
+        Self {
+            genesis: fixture.l2_genesis(),
+            genesis_system_config: fixture.rollup_config.genesis.system_config,
+            blocks: Arc::new(fixture.expected.clone()),
+        }
+    }
+}
+
+#[async_trait]
+impl L2ChainProvider for FixtureL2ChainProvider {
+    async fn l2_block_info_by_number(&mut self, number: u64) -> Result<L2BlockInfo> {
+        if number == self.genesis.block_info.number {
+            return Ok(self.genesis);
+        }
+        self.blocks
+            .iter()
+            .find(|b| b.block.block_info.number == number)
+            .map(|b| b.block)
+            .ok_or_else(|| anyhow!("L2 block {number} is not part of the fixture"))
+    }
+
+    async fn payload_by_number(&mut self, number: u64) -> Result<L2ExecutionPayloadEnvelope> {
+        Err(anyhow!("the execution payload of L2 block {number} is not part of the fixture"))
+    }
+
+    async fn system_config_by_number(
+        &mut self,
+        number: u64,
+        _: Arc<RollupConfig>,
+    ) -> Result<SystemConfig> {
+        Ok(self
+            .blocks
+            .iter()
+            .take_while(|b| b.block.block_info.number <= number)
+            .filter_map(|b| b.system_config)
+            .last()
+            .unwrap_or(self.genesis_system_config))
+    }
+}
+
+/// A [ResetProvider] returning the L1 genesis [BlockInfo] and genesis [SystemConfig] of a
+/// [DerivationFixture].
+#[derive(Debug, Clone, Copy)]
+pub struct FixtureResetProvider {
+    block_info: BlockInfo,
+    system_config: SystemConfig,
+}
+
+impl FixtureResetProvider {
+    /// Creates a new [FixtureResetProvider].
+    pub fn new(block_info: BlockInfo, system_config: SystemConfig) -> Self {
+        Self { block_info, system_config }
+    }
+}
+
+#[async_trait]
+impl ResetProvider for FixtureResetProvider {
+    async fn block_info(&self) -> BlockInfo {
+        self.block_info
+    }
+
+    async fn system_config(&self) -> SystemConfig {
+        self.system_config
+    }
+}
@@ -0,0 +1,470 @@
+//! Replays a [DerivationFixture] through the derivation pipeline.
+
+use super::{
+    DerivationFixture, FixtureBlobProvider, FixtureChainProvider, FixtureL2ChainProvider,
+    FixtureResetProvider,
+};
+use crate::{
+    builder::DerivationPipeline,
+    sources::EthereumDataSource,
+    stages::{
+        AttributesQueue, BatchQueue, ChannelBank, ChannelReader, FrameQueue, L1Retrieval,
+        L1Traversal, StatefulAttributesBuilder,
+    },
+    traits::{ChainProvider, Pipeline},
+    types::{L2PayloadAttributes, PipelineCheckpoint, Signal, StageError, StepResult},
+};
+use alloc::{
+    format,
+    string::{String, ToString},
+    sync::Arc,
+};
+use core::fmt::{Debug, Display};
+
+/// The stages of the pipeline a [DerivationFixture] is replayed through.
+type FixtureStages = AttributesQueue<
+    BatchQueue<
+        ChannelReader<
+            ChannelBank<
+                FrameQueue<
+                    L1Retrieval<
+                        EthereumDataSource<FixtureChainProvider, FixtureBlobProvider>,
+                        L1Traversal<FixtureChainProvider>,
+                    >,
+                >,
+            >,
+        >,
+        FixtureL2ChainProvider,
+    >,
+    StatefulAttributesBuilder<FixtureChainProvider, FixtureL2ChainProvider>,
+>;
+
+/// The maximum number of pipeline steps taken to derive a single L2 block before the replay
+/// gives up.
+const MAX_STEPS_PER_BLOCK: usize = 10_000;
+
+/// An error returned when replaying a [DerivationFixture].
+#[derive(Debug, PartialEq)]
+pub enum FixtureError {
+    /// The L1 block at the given index of the fixture could not be decoded.
+    InvalidL1Block(usize, alloy_rlp::Error),
+    /// The L1 genesis block of the rollup is not part of the fixture.
+    MissingL1Genesis(u64),
+    /// The expected L2 block with the given number is missing from the test vectors.
+    MissingL2Block(u64),
+    /// The test vectors start derivation on top of the given L2 block, rather than the L2
+    /// genesis of the rollup.
+    UnsupportedCursorStart(u64),
+    /// The L1 chain of the fixture was exhausted before the expected L2 block at the given index
+    /// was derived.
+    MissingAttributes(usize),
+    /// The pipeline took too many steps to derive the expected L2 block at the given index.
+    StepLimit(usize),
+    /// The pipeline failed while deriving the expected L2 block at the given index.
+    Pipeline(usize, StageError),
+    /// The attributes derived for the expected L2 block at the given index diverge from the
+    /// expected attributes.
+    Divergence(usize, AttributesDiff),
+}
+
+impl Display for FixtureError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FixtureError::InvalidL1Block(index, err) => {
+                write!(f, "Failed to decode L1 block {index} of the fixture: {err}")
+            }
+            FixtureError::MissingL1Genesis(number) => {
+                write!(f, "L1 genesis block {number} is not part of the fixture")
+            }
+            FixtureError::MissingL2Block(number) => {
+                write!(f, "Expected L2 block {number} is missing from the test vectors")
+            }
+            FixtureError::UnsupportedCursorStart(number) => {
+                write!(f, "Test vectors start on top of L2 block {number}, not the L2 genesis")
+            }
+            FixtureError::MissingAttributes(index) => {
+                write!(f, "L1 chain exhausted before expected L2 block {index} was derived")
+            }
+            FixtureError::StepLimit(index) => {
+                write!(f, "Step limit reached while deriving expected L2 block {index}")
+            }
+            FixtureError::Pipeline(index, err) => {
+                write!(f, "Pipeline failed while deriving expected L2 block {index}: {err}")
+            }
+            FixtureError::Divergence(index, diff) => {
+                write!(f, "Attributes of expected L2 block {index} diverge: {diff}")
+            }
+        }
+    }
+}
+
+/// The first field in which derived payload attributes differ from the expected attributes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AttributesDiff {
+    /// The name of the field.
+    pub field: String,
+    /// The expected value of the field.
+    pub expected: String,
+    /// The derived value of the field.
+    pub actual: String,
+}
+
+impl AttributesDiff {
+    /// Compares the `actual` attributes against the `expected` attributes, returning the first
+    /// field that differs, if any.
+    pub fn between(expected: &L2PayloadAttributes, actual: &L2PayloadAttributes) -> Option<Self> {
+        Self::field("timestamp", &expected.timestamp, &actual.timestamp)
+            .or_else(|| Self::field("prev_randao", &expected.prev_randao, &actual.prev_randao))
+            .or_else(|| {
+                Self::field("fee_recipient", &expected.fee_recipient, &actual.fee_recipient)
+            })
+            .or_else(|| Self::transactions(expected, actual))
+            .or_else(|| Self::field("no_tx_pool", &expected.no_tx_pool, &actual.no_tx_pool))
+            .or_else(|| Self::field("gas_limit", &expected.gas_limit, &actual.gas_limit))
+            .or_else(|| Self::field("withdrawals", &expected.withdrawals, &actual.withdrawals))
+            .or_else(|| {
+                Self::field(
+                    "parent_beacon_block_root",
+                    &expected.parent_beacon_block_root,
+                    &actual.parent_beacon_block_root,
+                )
+            })
+            .or_else(|| {
+                Self::field("eip_1559_params", &expected.eip_1559_params, &actual.eip_1559_params)
+            })
+    }
+
+    fn field<T: PartialEq + Debug>(field: &str, expected: &T, actual: &T) -> Option<Self> {
+        (expected != actual).then(|| Self {
+            field: field.to_string(),
+            expected: format!("{:?}", expected),
+            actual: format!("{:?}", actual),
+        })
+    }
+
+    fn transactions(expected: &L2PayloadAttributes, actual: &L2PayloadAttributes) -> Option<Self> {
+        expected
+            .transactions
+            .iter()
+            .zip(actual.transactions.iter())
+            .position(|(e, a)| e != a)
+            .map(|i| Self {
+                field: format!("transactions[{i}]"),
+                expected: expected.transactions[i].0.to_string(),
+                actual: actual.transactions[i].0.to_string(),
+            })
+            .or_else(|| {
+                Self::field(
+                    "transactions.len",
+                    &expected.transactions.len(),
+                    &actual.transactions.len(),
+                )
+            })
+    }
+}
+
+impl Display for AttributesDiff {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "`{}` expected {}, got {}", self.field, self.expected, self.actual)
+    }
+}
+
+impl DerivationFixture {
+    /// Replays the fixture through a derivation pipeline reading from the fixture's L1 chain.
+    ///
+    /// Starting at the L2 genesis, the attributes of every expected L2 block are derived and
+    /// compared against the expected attributes, after which the block becomes the safe head.
+    /// Returns the number of L2 blocks derived, or the first error or divergence encountered.
+    pub async fn replay(&self) -> Result<usize, FixtureError> {
+        self.replay_inner(None).await
+    }
+
+    /// Replays the fixture like [DerivationFixture::replay], but every time the pipeline
+    /// advances its L1 origin, it is checkpointed and replaced by a freshly built pipeline
+    /// restored from the checkpoint.
+    ///
+    /// The `roundtrip` closure receives every [PipelineCheckpoint] and returns the checkpoint to
+    /// restore, which allows passing it through serialization on the way.
+    pub async fn replay_with_checkpoints<F>(&self, mut roundtrip: F) -> Result<usize, FixtureError>
+    where
+        F: FnMut(PipelineCheckpoint) -> PipelineCheckpoint,
+    {
+        self.replay_inner(Some(&mut roundtrip)).await
+    }
+
+    async fn replay_inner(
+        &self,
+        mut roundtrip: Option<&mut dyn FnMut(PipelineCheckpoint) -> PipelineCheckpoint>,
+    ) -> Result<usize, FixtureError> {
+        let mut chain_provider = FixtureChainProvider::try_new(&self.l1_blocks)?;
+        let l2_chain_provider = FixtureL2ChainProvider::new(self);
+        let mut cursor = self.l2_genesis();
+        let l1_origin = chain_provider
+            .block_info_by_number(cursor.l1_origin.number)
+            .await
+            .map_err(|_| FixtureError::MissingL1Genesis(cursor.l1_origin.number))?;
+        let reset = FixtureResetProvider::new(l1_origin, self.rollup_config.genesis.system_config);
+        let mut pipeline =
+            DerivationPipeline::new(self.stages(&chain_provider, &l2_chain_provider), reset);
+        pipeline
+            .signal(Signal::Reset { l2_safe_head: cursor, l1_origin })
+            .await
+            .map_err(|e| FixtureError::Pipeline(0, e))?;
+
+        for (index, expected) in self.expected.iter().enumerate() {
+            let mut steps = 0;
+            let derived = loop {
+                if let Some(attributes) = pipeline.next_attributes() {
+                    break attributes;
+                }
+                if steps == MAX_STEPS_PER_BLOCK {
+                    return Err(FixtureError::StepLimit(index));
+                }
+                steps += 1;
+
+                match pipeline.step(cursor).await {
+                    StepResult::AdvancedOrigin => {
+                        if let Some(roundtrip) = roundtrip.as_mut() {
+                            let checkpoint = roundtrip(pipeline.checkpoint());
+                            let stages = self.stages(&chain_provider, &l2_chain_provider);
+                            pipeline = DerivationPipeline::restore(checkpoint, stages, reset)
+                                .await
+                                .map_err(|e| FixtureError::Pipeline(index, e))?;
+                        }
+                    }
+                    StepResult::PreparedAttributes |
+                    StepResult::StepFailed(StageError::NotEnoughData) => {}
+                    // The traversal fails to fetch the block after the last L1 block.
+                    StepResult::OriginAdvanceErr(StageError::Eof) |
+                    StepResult::OriginAdvanceErr(StageError::BlockInfoFetch(_)) => {
+                        return Err(FixtureError::MissingAttributes(index));
+                    }
+                    StepResult::OriginAdvanceErr(e) | StepResult::StepFailed(e) => {
+                        return Err(FixtureError::Pipeline(index, e));
+                    }
+                }
+            };
+
+            if let Some(diff) = AttributesDiff::between(&expected.attributes, &derived.attributes) {
+                return Err(FixtureError::Divergence(index, diff));
+            }
+            cursor = expected.block;
+        }
+
+        Ok(self.expected.len())
+    }
+
+    /// Builds the stages of the pipeline on top of the given providers.
+    fn stages(
+        &self,
+        chain_provider: &FixtureChainProvider,
+        l2_chain_provider: &FixtureL2ChainProvider,
+    ) -> FixtureStages {
+        let cfg = Arc::new(self.rollup_config);
+        let dap = EthereumDataSource::new(
+            chain_provider.clone(),
+            chain_provider.blob_provider(),
+            cfg.as_ref(),
+        );
+        let builder = StatefulAttributesBuilder::new(
+            cfg.clone(),
+            l2_chain_provider.clone(),
+            chain_provider.clone(),
+        );
+        let l1_traversal = L1Traversal::new(chain_provider.clone(), cfg.clone());
+        let l1_retrieval = L1Retrieval::new(l1_traversal, dap);
+        let frame_queue = FrameQueue::new(l1_retrieval);
+        let channel_bank = ChannelBank::new(cfg.clone(), frame_queue);
+        let channel_reader = ChannelReader::new(channel_bank, cfg.clone());
+        let batch_queue = BatchQueue::new(cfg.clone(), channel_reader, l2_chain_provider.clone());
+        AttributesQueue::new(*cfg, batch_queue, builder)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        builder::ResetProvider,
+        traits::OriginProvider,
+        types::{L2AttributesWithParent, L2BlockInfo, RawTransaction},
+    };
+    use alloc::{vec, vec::Vec};
+    use alloy_primitives::{Bytes, B256};
+
+    fn fixture() -> DerivationFixture {
+        let raw = include_str!("../../testdata/fixtures/ecotone_activation.json");
+        serde_json::from_str(raw).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_replay_ecotone_activation() {
+        let fixture = fixture();
+        let ecotone_time = fixture.rollup_config.ecotone_time.unwrap();
+        assert!(fixture.l1_blocks.iter().all(|b| b.blobs.is_empty()));
+        assert!(fixture.expected.iter().any(|b| b.block.block_info.timestamp == ecotone_time));
+        assert_eq!(fixture.replay().await, Ok(fixture.expected.len()));
+    }
+
+    #[tokio::test]
+    async fn test_replay_with_checkpoints() {
+        let raw = include_str!("../../testdata/fixtures/checkpoint_replay.json");
+        let fixture: DerivationFixture = serde_json::from_str(raw).unwrap();
+        assert_eq!(fixture.l1_blocks.len(), 50);
+        assert_eq!(fixture.replay().await, Ok(fixture.expected.len()));
+
+        // Every checkpoint goes through serialization before it is restored, and the restored
+        // pipelines derive the same attributes as the pipeline that ran straight through.
+        let mut origins = Vec::new();
+        let mut buffered_channels = 0;
+        let derived = fixture
+            .replay_with_checkpoints(|checkpoint| {
+                origins.push(checkpoint.origin().unwrap().number);
+                buffered_channels += checkpoint.channels.len();
+                let serialized = serde_json::to_vec(&checkpoint).unwrap();
+                serde_json::from_slice(&serialized).unwrap()
+            })
+            .await;
+        assert_eq!(derived, Ok(fixture.expected.len()));
+
+        // A checkpoint was taken at every L1 block, some of them with channels whose frames are
+        // spread over the following L1 blocks.
+        let genesis = fixture.rollup_config.genesis.l1.number;
+        assert_eq!(origins, (genesis + 1..genesis + 1 + origins.len() as u64).collect::<Vec<_>>());
+        assert!(buffered_channels > 0);
+    }
+
+    /// Steps the pipeline on top of the cursor until it prepared the next attributes.
+    async fn derive_next(
+        pipeline: &mut DerivationPipeline<FixtureStages, FixtureResetProvider>,
+        cursor: L2BlockInfo,
+    ) -> L2AttributesWithParent {
+        for _ in 0..MAX_STEPS_PER_BLOCK {
+            if let Some(attributes) = pipeline.next_attributes() {
+                return attributes;
+            }
+            match pipeline.step(cursor).await {
+                StepResult::OriginAdvanceErr(e) => panic!("failed to advance origin: {e:?}"),
+                StepResult::StepFailed(e) if e != StageError::NotEnoughData => {
+                    panic!("step failed: {e:?}")
+                }
+                _ => {}
+            }
+        }
+        panic!("no attributes derived on top of {cursor:?}");
+    }
+
+    #[tokio::test]
+    async fn test_reorg_reset_rederives_from_safe_head() {
+        let raw = include_str!("../../testdata/fixtures/checkpoint_replay.json");
+        let fixture: DerivationFixture = serde_json::from_str(raw).unwrap();
+        let mut chain_provider = FixtureChainProvider::try_new(&fixture.l1_blocks).unwrap();
+        let l2_chain_provider = FixtureL2ChainProvider::new(&fixture);
+        let genesis = fixture.l2_genesis();
+        let l1_genesis =
+            chain_provider.block_info_by_number(genesis.l1_origin.number).await.unwrap();
+        let reset =
+            FixtureResetProvider::new(l1_genesis, fixture.rollup_config.genesis.system_config);
+        let mut pipeline =
+            DerivationPipeline::new(fixture.stages(&chain_provider, &l2_chain_provider), reset);
+        let l1_origin = pipeline.reset.block_info().await;
+        pipeline.signal(Signal::Reset { l2_safe_head: genesis, l1_origin }).await.unwrap();
+
+        // Derive the first blocks, and leave the attributes of the next one prepared while the
+        // stages buffer the batches and channels that follow it.
+        let mut cursor = genesis;
+        for expected in &fixture.expected[..8] {
+            let derived = derive_next(&mut pipeline, cursor).await;
+            assert_eq!(derived.attributes, expected.attributes);
+            cursor = expected.block;
+        }
+        for _ in 0..MAX_STEPS_PER_BLOCK {
+            if !pipeline.prepared.is_empty() {
+                break;
+            }
+            pipeline.step(cursor).await;
+        }
+        assert_eq!(pipeline.prepared.len(), 1);
+        let stale_origin = *pipeline.origin().unwrap();
+
+        // The L2 chain reorgs back to genesis, so the driver resets the pipeline to the L1
+        // origin of the genesis block.
+        pipeline.signal(Signal::Reset { l2_safe_head: genesis, l1_origin }).await.unwrap();
+        assert!(pipeline.prepared.is_empty());
+        assert_eq!(pipeline.origin(), Some(&l1_origin));
+        assert!(stale_origin.number > l1_origin.number);
+
+        // None of the stale data survives the reset, as the pipeline derives the same blocks on
+        // top of the reset safe head.
+        let mut cursor = genesis;
+        for expected in &fixture.expected[..10] {
+            let derived = derive_next(&mut pipeline, cursor).await;
+            assert_eq!(derived.parent, cursor);
+            assert_eq!(derived.attributes, expected.attributes);
+            cursor = expected.block;
+        }
+    }
+
+    #[tokio::test]
+    async fn test_replay_reports_divergence() {
+        let mut fixture = fixture();
+        let ecotone_time = fixture.rollup_config.ecotone_time.unwrap();
+        let index = fixture
+            .expected
+            .iter()
+            .position(|b| b.block.block_info.timestamp == ecotone_time)
+            .unwrap();
+        let root = fixture.expected[index].attributes.parent_beacon_block_root.take();
+
+        let err = fixture.replay().await.unwrap_err();
+        let FixtureError::Divergence(i, diff) = err else { panic!("expected a divergence") };
+        assert_eq!(i, index);
+        assert_eq!(diff.field, "parent_beacon_block_root");
+        assert_eq!(diff.expected, "None");
+        assert_eq!(diff.actual, format!("{:?}", root));
+    }
+
+    #[tokio::test]
+    async fn test_replay_missing_attributes() {
+        let mut fixture = fixture();
+        let mut extra = fixture.expected.last().unwrap().clone();
+        extra.block.block_info.number += 1;
+        fixture.expected.push(extra);
+        let index = fixture.expected.len() - 1;
+        assert_eq!(fixture.replay().await, Err(FixtureError::MissingAttributes(index)));
+    }
+
+    #[test]
+    fn test_attributes_diff_transactions() {
+        let txs: Vec<RawTransaction> =
+            vec![Bytes::from_static(&[0x7E, 0x01]).into(), Bytes::from_static(&[0x02]).into()];
+        let expected = L2PayloadAttributes { transactions: txs.clone(), ..Default::default() };
+        assert_eq!(AttributesDiff::between(&expected, &expected), None);
+
+        let mut actual = expected.clone();
+        actual.transactions[1] = Bytes::from_static(&[0x03]).into();
+        let diff = AttributesDiff::between(&expected, &actual).unwrap();
+        assert_eq!(diff.field, "transactions[1]");
+        assert_eq!(diff.expected, "0x02");
+        assert_eq!(diff.actual, "0x03");
+
+        actual.transactions = txs[..1].to_vec();
+        let diff = AttributesDiff::between(&expected, &actual).unwrap();
+        assert_eq!(diff.field, "transactions.len");
+        assert_eq!(diff.to_string(), "`transactions.len` expected 2, got 1");
+    }
+
+    #[test]
+    fn test_attributes_diff_first_field() {
+        let expected = L2PayloadAttributes { timestamp: 2, ..Default::default() };
+        let actual = L2PayloadAttributes {
+            timestamp: 4,
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..Default::default()
+        };
+        let diff = AttributesDiff::between(&expected, &actual).unwrap();
+        assert_eq!(diff.field, "timestamp");
+        assert_eq!(diff.expected, "2");
+        assert_eq!(diff.actual, "4");
+    }
+}
@@ -8,9 +8,11 @@ extern crate alloc;
 
 mod params;
 pub use params::{
-    ChannelID, CHANNEL_ID_LENGTH, CONFIG_UPDATE_EVENT_VERSION_0, CONFIG_UPDATE_TOPIC,
-    DERIVATION_VERSION_0, FRAME_OVERHEAD, MAX_CHANNEL_BANK_SIZE, MAX_FRAME_LEN,
-    MAX_RLP_BYTES_PER_CHANNEL, MAX_SPAN_BATCH_BYTES, SEQUENCER_FEE_VAULT_ADDRESS,
+    ChannelID, CHANNEL_ID_LENGTH, CHANNEL_VERSION_BROTLI, CONFIG_UPDATE_EVENT_VERSION_0,
+    CONFIG_UPDATE_TOPIC, DERIVATION_VERSION_0, FRAME_OVERHEAD, MAX_CHANNEL_BANK_SIZE,
+    MAX_CHANNEL_BANK_SIZE_FJORD, MAX_FRAME_LEN, MAX_RLP_BYTES_PER_CHANNEL,
+    MAX_RLP_BYTES_PER_CHANNEL_FJORD, MAX_SPAN_BATCH_BYTES, SEQUENCER_FEE_VAULT_ADDRESS,
+    ZLIB_DEFLATE_COMPRESSION_METHOD, ZLIB_RESERVED_COMPRESSION_METHOD,
 };
 
 pub mod builder;
@@ -21,6 +23,9 @@ pub mod stages;
 pub mod traits;
 pub mod types;
 
+#[cfg(all(feature = "serde", any(test, feature = "test-utils")))]
+pub mod fixture;
+
 #[cfg(feature = "online")]
 pub mod online;
 #[cfg(feature = "online")]
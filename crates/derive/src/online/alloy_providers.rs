@@ -4,8 +4,8 @@
 use crate::{
     traits::{ChainProvider, L2ChainProvider},
     types::{
-        Block, BlockInfo, L2BlockInfo, L2ExecutionPayloadEnvelope, OpBlock, RollupConfig,
-        SystemConfig,
+        Block, BlockInfo, ChainProviderError, L2BlockInfo, L2ExecutionPayloadEnvelope, OpBlock,
+        RollupConfig, SystemConfig,
     },
 };
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
@@ -54,6 +54,59 @@ impl<T: Provider<Http<reqwest::Client>>> AlloyChainProvider<T> {
             ),
         }
     }
+
+    /// Drops all cached data for blocks above the given `block_number`. This should be called
+    /// after a reorg is detected, so that the pipeline does not derive from stale L1 data.
+    pub fn purge_above(&mut self, block_number: u64) {
+        let stale_numbers = self
+            .block_info_by_number_cache
+            .iter()
+            .filter(|(n, _)| **n > block_number)
+            .map(|(n, _)| *n)
+            .collect::<Vec<_>>();
+        stale_numbers.iter().for_each(|n| {
+            self.block_info_by_number_cache.pop(n);
+        });
+
+        let stale_headers = self
+            .header_by_hash_cache
+            .iter()
+            .filter(|(_, h)| h.number > block_number)
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        stale_headers.iter().for_each(|hash| {
+            self.header_by_hash_cache.pop(hash);
+        });
+
+        let stale_blocks = self
+            .block_info_and_transactions_by_hash_cache
+            .iter()
+            .filter(|(_, (info, _))| info.number > block_number)
+            .map(|(hash, _)| *hash)
+            .collect::<Vec<_>>();
+        stale_blocks.iter().for_each(|hash| {
+            self.block_info_and_transactions_by_hash_cache.pop(hash);
+            self.receipts_by_hash_cache.pop(hash);
+        });
+    }
+
+    /// Checks the parent hash of the given [BlockInfo] against the previously cached block at
+    /// `number - 1`, returning a [ChainProviderError::ReorgDetected] if they disagree. On a
+    /// reorg, [ChainProvider::block_info_by_number] purges the cached parent and the blocks above
+    /// it.
+    fn check_parent_linkage(&mut self, block_info: &BlockInfo) -> Result<()> {
+        let Some(parent_number) = block_info.number.checked_sub(1) else { return Ok(()) };
+        match self.block_info_by_number_cache.peek(&parent_number) {
+            Some(parent) if parent.hash != block_info.parent_hash => {
+                Err(anyhow!(ChainProviderError::ReorgDetected {
+                    number: parent_number,
+                    old_hash: parent.hash,
+                    new_hash: block_info.parent_hash,
+                }))
+            }
+            _ => Ok(()),
+        }
+    }
 }
 
 #[async_trait]
@@ -69,7 +122,9 @@ impl<T: Provider<Http<reqwest::Client>>> ChainProvider for AlloyChainProvider<T>
             .request("debug_getRawHeader", [hash])
             .await
             .map_err(|e| anyhow!(e))?;
-        Header::decode(&mut raw_header.as_ref()).map_err(|e| anyhow!(e))
+        let header = Header::decode(&mut raw_header.as_ref()).map_err(|e| anyhow!(e))?;
+        self.header_by_hash_cache.put(hash, header.clone());
+        Ok(header)
     }
 
     async fn block_info_by_number(&mut self, number: u64) -> Result<BlockInfo> {
@@ -91,6 +146,11 @@ impl<T: Provider<Http<reqwest::Client>>> ChainProvider for AlloyChainProvider<T>
             parent_hash: header.parent_hash,
             timestamp: header.timestamp,
         };
+        if let Err(e) = self.check_parent_linkage(&block_info) {
+            // The cached parent was reorged out, along with any block cached above it.
+            self.purge_above(number.saturating_sub(2));
+            return Err(e);
+        }
         self.block_info_by_number_cache.put(number, block_info);
         Ok(block_info)
     }
@@ -168,7 +228,7 @@ pub struct AlloyL2ChainProvider<T: Provider<Http<reqwest::Client>>> {
     payload_by_number_cache: LruCache<u64, L2ExecutionPayloadEnvelope>,
     /// `l2_block_info_by_number` LRU cache.
     l2_block_info_by_number_cache: LruCache<u64, L2BlockInfo>,
-    /// `system_config_by_l2_hash` LRU cache.
+    /// `system_config_by_number` LRU cache.
     system_config_by_number_cache: LruCache<u64, SystemConfig>,
 }
 
@@ -183,6 +243,25 @@ impl<T: Provider<Http<reqwest::Client>>> AlloyL2ChainProvider<T> {
             system_config_by_number_cache: LruCache::new(NonZeroUsize::new(CACHE_SIZE).unwrap()),
         }
     }
+
+    /// Drops all cached data for L2 blocks above the given `safe_head` number. This should be
+    /// called when the pipeline is reset or an L2 reorg is detected, as the unsafe blocks above
+    /// the safe head, and the [SystemConfig]s derived from them, may have been replaced.
+    pub fn purge_above(&mut self, safe_head: u64) {
+        let stale_numbers = self
+            .payload_by_number_cache
+            .iter()
+            .map(|(n, _)| *n)
+            .chain(self.l2_block_info_by_number_cache.iter().map(|(n, _)| *n))
+            .chain(self.system_config_by_number_cache.iter().map(|(n, _)| *n))
+            .filter(|n| *n > safe_head)
+            .collect::<Vec<_>>();
+        stale_numbers.iter().for_each(|n| {
+            self.payload_by_number_cache.pop(n);
+            self.l2_block_info_by_number_cache.pop(n);
+            self.system_config_by_number_cache.pop(n);
+        });
+    }
 }
 
 #[async_trait]
@@ -226,6 +305,218 @@ impl<T: Provider<Http<reqwest::Client>>> L2ChainProvider for AlloyL2ChainProvide
         }
 
         let envelope = self.payload_by_number(number).await?;
-        envelope.to_system_config(&rollup_config)
+        let system_config = envelope.to_system_config(&rollup_config)?;
+        self.system_config_by_number_cache.put(number, system_config);
+        Ok(system_config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{online::test_utils::http_provider, types::L2ExecutionPayload};
+    use alloc::{
+        format,
+        string::{String, ToString},
+        vec,
+    };
+    use alloy_primitives::{b256, hex, U256};
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    fn new_test_provider() -> AlloyChainProvider<alloy_provider::ReqwestProvider> {
+        // The provider is never queried, only the caches are exercised.
+        AlloyChainProvider::new(http_provider("http://127.0.0.1:1"))
+    }
+
+    fn new_test_l2_provider() -> AlloyL2ChainProvider<alloy_provider::ReqwestProvider> {
+        // The provider is never queried, only the caches are exercised.
+        AlloyL2ChainProvider::new(
+            http_provider("http://127.0.0.1:1"),
+            Arc::new(RollupConfig::default()),
+        )
+    }
+
+    fn block(number: u64, hash: B256, parent_hash: B256) -> BlockInfo {
+        BlockInfo { number, hash, parent_hash, timestamp: number * 12 }
+    }
+
+    #[test]
+    fn test_parent_linkage_reorg_detected() {
+        let mut provider = new_test_provider();
+        let old_hash = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+        let new_hash = b256!("2222222222222222222222222222222222222222222222222222222222222222");
+        provider.block_info_by_number_cache.put(9, block(9, old_hash, B256::ZERO));
+
+        // The provider now answers with a block 10 built on top of a different block 9.
+        let reorged = block(10, B256::ZERO, new_hash);
+        let err = provider.check_parent_linkage(&reorged).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ChainProviderError>(),
+            Some(&ChainProviderError::ReorgDetected { number: 9, old_hash, new_hash })
+        );
+
+        // After purging the reorged block, the new chain is accepted.
+        provider.purge_above(8);
+        assert!(provider.block_info_by_number_cache.peek(&9).is_none());
+        assert!(provider.check_parent_linkage(&reorged).is_ok());
+    }
+
+    /// Spawns a mock JSON-RPC endpoint that answers the requests it receives, in order, with the
+    /// RLP encoding of the given `headers`, and returns its URL.
+    async fn spawn_raw_header_rpc(headers: Vec<Header>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for header in headers {
+                let (mut stream, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                let body_start = loop {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    assert_ne!(n, 0, "Connection closed mid-request");
+                    request.extend_from_slice(&buf[..n]);
+                    if let Some(i) = request.windows(4).position(|w| w == b"\r\n\r\n") {
+                        break i + 4;
+                    }
+                };
+                let head = String::from_utf8_lossy(&request[..body_start]).to_lowercase();
+                let body_len = head
+                    .lines()
+                    .find_map(|line| line.strip_prefix("content-length:"))
+                    .and_then(|len| len.trim().parse::<usize>().ok())
+                    .unwrap();
+                while request.len() < body_start + body_len {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    assert_ne!(n, 0, "Connection closed mid-request");
+                    request.extend_from_slice(&buf[..n]);
+                }
+
+                let request: serde_json::Value =
+                    serde_json::from_slice(&request[body_start..]).unwrap();
+                let raw_header = format!("0x{}", hex::encode(alloy_rlp::encode(&header)));
+                let body = serde_json::json!({
+                    "jsonrpc": "2.0",
+                    "id": request["id"].clone(),
+                    "result": raw_header,
+                })
+                .to_string();
+                let head = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(body.as_bytes()).await.unwrap();
+                stream.shutdown().await.unwrap();
+            }
+        });
+        url
+    }
+
+    #[tokio::test]
+    async fn test_block_info_by_number_reorg_purges_cache() {
+        let header_8 = Header { number: 8, ..Default::default() };
+        let header_9 =
+            Header { number: 9, parent_hash: header_8.hash_slow(), ..Default::default() };
+        let reorged_9 = Header { number: 9, timestamp: 1, ..header_9.clone() };
+        let header_10 =
+            Header { number: 10, parent_hash: reorged_9.hash_slow(), ..Default::default() };
+        let url = spawn_raw_header_rpc(vec![header_8.clone(), header_9.clone(), header_10]).await;
+        let mut provider = AlloyChainProvider::new(http_provider(&url));
+
+        assert_eq!(provider.block_info_by_number(8).await.unwrap().hash, header_8.hash_slow());
+        assert_eq!(provider.block_info_by_number(9).await.unwrap().hash, header_9.hash_slow());
+
+        // Block 10 is built on top of a different block 9 than the cached one.
+        let err = provider.block_info_by_number(10).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<ChainProviderError>(),
+            Some(&ChainProviderError::ReorgDetected {
+                number: 9,
+                old_hash: header_9.hash_slow(),
+                new_hash: reorged_9.hash_slow(),
+            })
+        );
+        assert!(provider.block_info_by_number_cache.contains(&8));
+        assert!(!provider.block_info_by_number_cache.contains(&9));
+        assert!(!provider.block_info_by_number_cache.contains(&10));
+    }
+
+    #[test]
+    fn test_parent_linkage_matches() {
+        let mut provider = new_test_provider();
+        let hash = b256!("1111111111111111111111111111111111111111111111111111111111111111");
+        provider.block_info_by_number_cache.put(9, block(9, hash, B256::ZERO));
+        assert!(provider.check_parent_linkage(&block(10, B256::ZERO, hash)).is_ok());
+        assert!(provider.check_parent_linkage(&block(0, B256::ZERO, B256::ZERO)).is_ok());
+    }
+
+    #[test]
+    fn test_purge_above() {
+        let mut provider = new_test_provider();
+        for number in 0..5u64 {
+            let hash = B256::with_last_byte(number as u8);
+            let info = block(number, hash, B256::ZERO);
+            provider.block_info_by_number_cache.put(number, info);
+            provider.header_by_hash_cache.put(hash, Header { number, ..Default::default() });
+            provider.block_info_and_transactions_by_hash_cache.put(hash, (info, Vec::new()));
+            provider.receipts_by_hash_cache.put(hash, Vec::new());
+        }
+
+        provider.purge_above(2);
+
+        for number in 0..5u64 {
+            let hash = B256::with_last_byte(number as u8);
+            let cached = number <= 2;
+            assert_eq!(provider.block_info_by_number_cache.contains(&number), cached);
+            assert_eq!(provider.header_by_hash_cache.contains(&hash), cached);
+            assert_eq!(provider.block_info_and_transactions_by_hash_cache.contains(&hash), cached);
+            assert_eq!(provider.receipts_by_hash_cache.contains(&hash), cached);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_system_config_by_number_cached() {
+        let mut provider = new_test_l2_provider();
+        let system_config =
+            SystemConfig { gas_limit: U256::from(30_000_000), ..Default::default() };
+        provider.system_config_by_number_cache.put(7, system_config);
+
+        // The cached config is served without querying the unreachable RPC.
+        let rollup_config = Arc::new(RollupConfig::default());
+        assert_eq!(
+            provider.system_config_by_number(7, rollup_config.clone()).await.unwrap(),
+            system_config
+        );
+        assert!(provider.system_config_by_number(8, rollup_config).await.is_err());
+    }
+
+    #[test]
+    fn test_l2_purge_above() {
+        let mut provider = new_test_l2_provider();
+        for number in 0..5u64 {
+            let payload = L2ExecutionPayloadEnvelope {
+                parent_beacon_block_root: None,
+                execution_payload: L2ExecutionPayload {
+                    block_number: number,
+                    ..Default::default()
+                },
+            };
+            provider.payload_by_number_cache.put(number, payload);
+            provider.l2_block_info_by_number_cache.put(number, L2BlockInfo::default());
+            provider.system_config_by_number_cache.put(number, SystemConfig::default());
+        }
+
+        provider.purge_above(2);
+
+        for number in 0..5u64 {
+            let cached = number <= 2;
+            assert_eq!(provider.payload_by_number_cache.contains(&number), cached);
+            assert_eq!(provider.l2_block_info_by_number_cache.contains(&number), cached);
+            assert_eq!(provider.system_config_by_number_cache.contains(&number), cached);
+        }
     }
 }
@@ -1,13 +1,17 @@
 //! Contains an online implementation of the [BeaconClient] trait.
 
 use crate::types::{
-    APIConfigResponse, APIGenesisResponse, APIGetBlobSidecarsResponse, IndexedBlobHash,
+    APIConfigResponse, APIGenesisResponse, APIGetBlobSidecarsResponse, APIVersionResponse,
+    BlobProviderError, IndexedBlobHash,
+};
+use alloc::{
+    boxed::Box,
+    format,
+    string::{String, ToString},
+    vec::Vec,
 };
-use alloc::{boxed::Box, string::String};
-use alloy_provider::Provider;
-use alloy_transport_http::Http;
 use async_trait::async_trait;
-use reqwest::Client;
+use reqwest::{Client, StatusCode};
 
 /// The node version engine api method.
 pub(crate) const VERSION_METHOD: &str = "eth/v1/node/version";
@@ -34,42 +38,62 @@ pub trait BeaconClient {
     async fn beacon_genesis(&self) -> anyhow::Result<APIGenesisResponse>;
 
     /// Fetches blob sidecars that were confirmed in the specified L1 block with the given indexed
-    /// hashes. Order of the returned sidecars is guaranteed to be that of the hashes. Blob data is
-    /// not checked for validity.
+    /// hashes. The returned sidecars are not guaranteed to be in the order of the hashes, and
+    /// blob data is not checked for validity.
+    ///
+    /// A [BlobProviderError::NotFound] is returned if the beacon node has no sidecars for the
+    /// slot, either because the slot was missed or because the sidecars have been pruned.
     async fn beacon_blob_side_cars(
         &self,
         fetch_all_sidecars: bool,
         slot: u64,
         hashes: &[IndexedBlobHash],
-    ) -> anyhow::Result<APIGetBlobSidecarsResponse>;
+    ) -> Result<APIGetBlobSidecarsResponse, BlobProviderError>;
 }
 
-/// An online implementation of the [BeaconClient] trait.
+/// An online implementation of the [BeaconClient] trait, querying the Beacon API over HTTP.
 #[derive(Debug, Clone)]
-pub struct OnlineBeaconClient<T: Provider<Http<Client>>> {
-    /// The inner Ethereum JSON-RPC provider.
-    inner: T,
+pub struct OnlineBeaconClient {
+    /// The base URL of the beacon API.
+    base: String,
+    /// The inner reqwest client.
+    inner: Client,
 }
 
-impl<T: Provider<Http<Client>>> OnlineBeaconClient<T> {
-    /// Creates a new instance of the [OnlineBeaconClient].
-    pub fn new(inner: T) -> Self {
-        Self { inner }
+impl OnlineBeaconClient {
+    /// Creates a new instance of the [OnlineBeaconClient] with the given base URL, e.g. that
+    /// of a beacon node or a blob archiver.
+    pub fn new_http(base: String) -> Self {
+        Self { base: base.trim_end_matches('/').into(), inner: Client::new() }
+    }
+
+    /// Performs a GET request for the given beacon API method, decoding the JSON response.
+    async fn get<T: serde::de::DeserializeOwned>(&self, method: &str) -> anyhow::Result<T> {
+        self.inner
+            .get(format!("{}/{}", self.base, method))
+            .send()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))?
+            .error_for_status()
+            .map_err(|e| anyhow::anyhow!(e))?
+            .json::<T>()
+            .await
+            .map_err(|e| anyhow::anyhow!(e))
     }
 }
 
 #[async_trait]
-impl<T: Provider<Http<Client>> + Send> BeaconClient for OnlineBeaconClient<T> {
+impl BeaconClient for OnlineBeaconClient {
     async fn node_version(&self) -> anyhow::Result<String> {
-        self.inner.client().request(VERSION_METHOD, ()).await.map_err(|e| anyhow::anyhow!(e))
+        self.get::<APIVersionResponse>(VERSION_METHOD).await.map(|r| r.data.version)
     }
 
     async fn config_spec(&self) -> anyhow::Result<APIConfigResponse> {
-        self.inner.client().request(SPEC_METHOD, ()).await.map_err(|e| anyhow::anyhow!(e))
+        self.get(SPEC_METHOD).await
     }
 
     async fn beacon_genesis(&self) -> anyhow::Result<APIGenesisResponse> {
-        self.inner.client().request(GENESIS_METHOD, ()).await.map_err(|e| anyhow::anyhow!(e))
+        self.get(GENESIS_METHOD).await
     }
 
     async fn beacon_blob_side_cars(
@@ -77,12 +101,151 @@ impl<T: Provider<Http<Client>> + Send> BeaconClient for OnlineBeaconClient<T> {
         fetch_all_sidecars: bool,
         slot: u64,
         hashes: &[IndexedBlobHash],
-    ) -> anyhow::Result<APIGetBlobSidecarsResponse> {
-        let method = alloc::format!("{}{}", SIDECARS_METHOD_PREFIX, slot);
-        self.inner
-            .client()
-            .request(method, (fetch_all_sidecars, hashes))
+    ) -> Result<APIGetBlobSidecarsResponse, BlobProviderError> {
+        let mut url = format!("{}/{}{}", self.base, SIDECARS_METHOD_PREFIX, slot);
+        if !fetch_all_sidecars && !hashes.is_empty() {
+            let indices = hashes.iter().map(|h| h.index.to_string()).collect::<Vec<_>>();
+            url = format!("{}?indices={}", url, indices.join(","));
+        }
+
+        let response = self
+            .inner
+            .get(url)
+            .send()
             .await
-            .map_err(|e| anyhow::anyhow!(e))
+            .map_err(|e| BlobProviderError::Custom(anyhow::anyhow!(e)))?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(BlobProviderError::NotFound(slot));
+        }
+        response
+            .error_for_status()
+            .map_err(|e| BlobProviderError::Custom(anyhow::anyhow!(e)))?
+            .json::<APIGetBlobSidecarsResponse>()
+            .await
+            .map_err(|e| BlobProviderError::Custom(anyhow::anyhow!(e)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+    use crate::{
+        online::{
+            test_utils::spawn_anvil, OnlineBlobProvider, OnlineBlobProviderWithFallback,
+            SimpleSlotDerivation,
+        },
+        traits::BlobProvider,
+        types::BlockInfo,
+    };
+    use alloc::{sync::Arc, vec};
+    use alloy_primitives::b256;
+    use std::sync::Mutex;
+    use tokio::{
+        io::{AsyncReadExt, AsyncWriteExt},
+        net::TcpListener,
+    };
+
+    const SIDECARS: &[u8] = include_bytes!("testdata/eth_v1_beacon_sidecars_goerli.json");
+
+    /// Spawns a mock beacon API that answers every request with the given status and body,
+    /// returning its base URL and the paths of the requests it received.
+    async fn spawn_beacon_api(
+        status: u16,
+        body: &'static [u8],
+    ) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let paths = Arc::new(Mutex::new(Vec::new()));
+        let received = paths.clone();
+        tokio::spawn(async move {
+            while let Ok((mut stream, _)) = listener.accept().await {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = stream.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                let request = String::from_utf8_lossy(&request);
+                let path = request.split_whitespace().nth(1).unwrap_or_default();
+                received.lock().unwrap().push(path.to_string());
+
+                let reason = if status == 200 { "OK" } else { "Not Found" };
+                let head = format!(
+                    "HTTP/1.1 {status} {reason}\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                stream.write_all(head.as_bytes()).await.unwrap();
+                stream.write_all(body).await.unwrap();
+                stream.shutdown().await.unwrap();
+            }
+        });
+        (base, paths)
+    }
+
+    fn blob_hashes() -> Vec<IndexedBlobHash> {
+        vec![
+            IndexedBlobHash {
+                index: 1,
+                hash: b256!("010a9e10aab79bab62e10a5b83c164a91451b6ef56d31ac95a9514ffe6d6b4e6"),
+            },
+            IndexedBlobHash {
+                index: 3,
+                hash: b256!("01df1f9ae707f5847513c9c430b683182079edf2b1f94ee12e4daae7f3c8c309"),
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_blob_sidecars_requests_indices() {
+        let (base, paths) = spawn_beacon_api(200, SIDECARS).await;
+        let client = OnlineBeaconClient::new_http(base);
+        let response = client.beacon_blob_side_cars(false, 42, &blob_hashes()).await.unwrap();
+        assert_eq!(response.data.len(), 5);
+        assert_eq!(*paths.lock().unwrap(), ["/eth/v1/beacon/blob_sidecars/42?indices=1,3"]);
+    }
+
+    #[tokio::test]
+    async fn test_blob_sidecars_fetch_all_omits_indices() {
+        let (base, paths) = spawn_beacon_api(200, SIDECARS).await;
+        let client = OnlineBeaconClient::new_http(base);
+        client.beacon_blob_side_cars(true, 42, &blob_hashes()).await.unwrap();
+        assert_eq!(*paths.lock().unwrap(), ["/eth/v1/beacon/blob_sidecars/42"]);
+    }
+
+    #[tokio::test]
+    async fn test_blob_sidecars_not_found() {
+        let (base, _) = spawn_beacon_api(404, b"{}").await;
+        let client = OnlineBeaconClient::new_http(base);
+        let result = client.beacon_blob_side_cars(false, 42, &blob_hashes()).await;
+        assert_eq!(result.unwrap_err(), BlobProviderError::NotFound(42));
+    }
+
+    #[tokio::test]
+    async fn test_get_blobs_not_found_uses_archiver() {
+        let (provider, _anvil) = spawn_anvil();
+        let (beacon, beacon_paths) = spawn_beacon_api(404, b"{}").await;
+        let (archiver, archiver_paths) = spawn_beacon_api(200, SIDECARS).await;
+        let primary: OnlineBlobProvider<_, _, SimpleSlotDerivation> = OnlineBlobProvider::new(
+            provider,
+            false,
+            OnlineBeaconClient::new_http(beacon),
+            Some(10),
+            Some(12),
+        );
+        let mut blob_provider = OnlineBlobProviderWithFallback::new(
+            primary,
+            Some(OnlineBeaconClient::new_http(archiver)),
+        );
+        let block_ref = BlockInfo { timestamp: 34, ..Default::default() };
+        let blobs = blob_provider.get_blobs(&block_ref, &blob_hashes()).await.unwrap();
+        assert_eq!(blobs.len(), 2);
+        assert_eq!(*beacon_paths.lock().unwrap(), ["/eth/v1/beacon/blob_sidecars/2?indices=1,3"]);
+        assert_eq!(*archiver_paths.lock().unwrap(), ["/eth/v1/beacon/blob_sidecars/2?indices=1,3"]);
     }
 }
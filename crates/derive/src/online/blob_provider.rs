@@ -11,7 +11,7 @@ use alloy_transport_http::Http;
 use async_trait::async_trait;
 use core::marker::PhantomData;
 use reqwest::Client;
-use tracing::debug;
+use tracing::{debug, warn};
 
 /// Specifies the derivation of a slot from a timestamp.
 pub trait SlotDerivation {
@@ -83,8 +83,49 @@ impl<T: Provider<Http<Client>>, B: BeaconClient, S: SlotDerivation> OnlineBlobPr
             .beacon_blob_side_cars(self.fetch_all_sidecars, slot, hashes)
             .await
             .map(|r| r.data)
-            .map_err(|e| e.into())
     }
+
+    /// Computes the slot for the given timestamp, using the loaded beacon configs.
+    pub fn slot(&self, timestamp: u64) -> Result<u64, BlobProviderError> {
+        let genesis = self.genesis_time.ok_or(BlobProviderError::Slot(anyhow::anyhow!(
+            "beacon genesis has not been loaded"
+        )))?;
+        let interval = self
+            .slot_interval
+            .ok_or(BlobProviderError::Slot(anyhow::anyhow!("config spec has not been loaded")))?;
+        S::slot(genesis, interval, timestamp).map_err(BlobProviderError::Slot)
+    }
+}
+
+/// Matches the given sidecars to the requested [IndexedBlobHash]es by their index, verifying
+/// their versioned hashes before returning the [Blob]s in the order of the requested hashes.
+pub(crate) fn filter_and_verify_sidecars(
+    sidecars: Vec<APIBlobSidecar>,
+    blob_hashes: &[IndexedBlobHash],
+) -> Result<Vec<Blob>, BlobProviderError> {
+    // The beacon node is not required to return the sidecars in the order of the requested
+    // indices, so each requested blob hash is matched to the sidecar with the same index.
+    let mut sidecars = sidecars.into_iter().map(|s| s.inner).collect::<Vec<BlobSidecar>>();
+    let matched = blob_hashes
+        .iter()
+        .filter_map(|hash| {
+            let position = sidecars.iter().position(|s| s.index as usize == hash.index)?;
+            Some((hash, sidecars.swap_remove(position)))
+        })
+        .collect::<Vec<_>>();
+
+    // Validate that a sidecar was retrieved for every requested blob hash.
+    if blob_hashes.len() != matched.len() {
+        return Err(BlobProviderError::SidecarLengthMismatch(blob_hashes.len(), matched.len()));
+    }
+
+    // Validate the blob sidecars straight away with the `IndexedBlobHash`es.
+    let blobs = matched
+        .into_iter()
+        .map(|(hash, sidecar)| sidecar.verify_blob(hash).map(|_| sidecar.blob))
+        .collect::<anyhow::Result<Vec<Blob>>>()?;
+
+    Ok(blobs)
 }
 
 /// Minimal slot derivation implementation.
@@ -127,44 +168,89 @@ where
         // [BeaconGenesis] and [ConfigSpec] if not previously loaded.
         self.load_configs().await?;
 
-        // Extract the genesis timestamp and slot interval from the loaded configs.
-        let genesis = self.genesis_time.expect("Genesis Config Loaded");
-        let interval = self.slot_interval.expect("Config Spec Loaded");
-
         // Calculate the slot for the given timestamp.
-        let slot =
-            S::slot(genesis, interval, block_ref.timestamp).map_err(BlobProviderError::Slot)?;
+        let slot = self.slot(block_ref.timestamp)?;
 
         // Fetch blob sidecars for the slot using the given blob hashes.
         let sidecars = self.fetch_sidecars(slot, blob_hashes).await?;
 
-        // Filter blob sidecars that match the indicies in the specified list.
-        let blob_hash_indicies = blob_hashes.iter().map(|b| b.index).collect::<Vec<_>>();
-        let filtered = sidecars
-            .into_iter()
-            .filter(|s| blob_hash_indicies.contains(&(s.inner.index as usize)))
-            .collect::<Vec<_>>();
+        filter_and_verify_sidecars(sidecars, blob_hashes)
+    }
+}
 
-        // Validate the correct number of blob sidecars were retrieved.
-        if blob_hashes.len() != filtered.len() {
-            return Err(BlobProviderError::SidecarLengthMismatch(blob_hashes.len(), filtered.len()));
-        }
+/// An [OnlineBlobProvider] with a fallback [BeaconClient], such as a blob archiver, which is
+/// queried when the primary beacon node does not have the requested blob sidecars, e.g. because
+/// they have been pruned after the blob retention period of ~18 days.
+#[derive(Debug, Clone)]
+pub struct OnlineBlobProviderWithFallback<
+    T: Provider<Http<Client>>,
+    B: BeaconClient,
+    F: BeaconClient,
+    S: SlotDerivation,
+> {
+    /// The primary blob provider.
+    primary: OnlineBlobProvider<T, B, S>,
+    /// The fallback beacon client.
+    fallback: Option<F>,
+}
 
-        // Validate the blob sidecars straight away with the `IndexedBlobHash`es.
-        let sidecars = filtered.into_iter().map(|s| s.inner).collect::<Vec<BlobSidecar>>();
-        let blobs = sidecars
-            .into_iter()
-            .enumerate()
-            .map(|(i, sidecar)| {
-                let hash = blob_hashes.get(i).ok_or(anyhow::anyhow!("failed to get blob hash"))?;
-                match sidecar.verify_blob(hash) {
-                    Ok(_) => Ok(sidecar.blob),
-                    Err(e) => Err(e),
-                }
-            })
-            .collect::<anyhow::Result<Vec<Blob>>>()?;
-
-        Ok(blobs)
+impl<T, B, F, S> OnlineBlobProviderWithFallback<T, B, F, S>
+where
+    T: Provider<Http<Client>>,
+    B: BeaconClient,
+    F: BeaconClient,
+    S: SlotDerivation,
+{
+    /// Creates a new instance of the [OnlineBlobProviderWithFallback].
+    pub fn new(primary: OnlineBlobProvider<T, B, S>, fallback: Option<F>) -> Self {
+        Self { primary, fallback }
+    }
+
+    /// Fetches blob sidecars from the fallback [BeaconClient], if one is configured.
+    async fn fallback_sidecars(
+        &self,
+        block_ref: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<APIBlobSidecar>, BlobProviderError> {
+        let fallback = self.fallback.as_ref().ok_or_else(|| {
+            BlobProviderError::Custom(anyhow::anyhow!("no fallback beacon client configured"))
+        })?;
+        let slot = self.primary.slot(block_ref.timestamp)?;
+        fallback
+            .beacon_blob_side_cars(self.primary.fetch_all_sidecars, slot, blob_hashes)
+            .await
+            .map(|r| r.data)
+    }
+}
+
+#[async_trait]
+impl<T, B, F, S> BlobProvider for OnlineBlobProviderWithFallback<T, B, F, S>
+where
+    T: Provider<Http<Client>> + Send,
+    B: BeaconClient + Send + Sync,
+    F: BeaconClient + Send + Sync,
+    S: SlotDerivation + Send + Sync,
+{
+    /// Fetches blobs from the primary beacon node, falling back to the secondary
+    /// [BeaconClient] if the primary no longer has the sidecars for the slot.
+    async fn get_blobs(
+        &mut self,
+        block_ref: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Blob>, BlobProviderError> {
+        match self.primary.get_blobs(block_ref, blob_hashes).await {
+            Ok(blobs) => Ok(blobs),
+            // A pruned beacon node either returns a 404 or an empty list of sidecars.
+            Err(e @ BlobProviderError::NotFound(_)) |
+            Err(e @ BlobProviderError::SidecarLengthMismatch(_, _))
+                if self.fallback.is_some() =>
+            {
+                warn!("Primary beacon node failed to serve blobs, using fallback: {e}");
+                let sidecars = self.fallback_sidecars(block_ref, blob_hashes).await?;
+                filter_and_verify_sidecars(sidecars, blob_hashes)
+            }
+            Err(e) => Err(e),
+        }
     }
 }
 
@@ -341,14 +427,14 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_get_blobs_invalid_ordering() {
+    async fn test_get_blobs_matches_sidecars_by_index() {
         let (provider, _anvil) = spawn_anvil();
         let json_bytes = include_bytes!("testdata/eth_v1_beacon_sidecars_goerli.json");
         let sidecars: APIGetBlobSidecarsResponse = serde_json::from_slice(json_bytes).unwrap();
         let beacon_client = MockBeaconClient {
             beacon_genesis: Some(APIGenesisResponse::new(10)),
             config_spec: Some(APIConfigResponse::new(12)),
-            blob_sidecars: Some(sidecars),
+            blob_sidecars: Some(sidecars.clone()),
             ..Default::default()
         };
         let blob_hashes = vec![
@@ -376,13 +462,9 @@ mod tests {
         let mut blob_provider: OnlineBlobProvider<_, _, SimpleSlotDerivation> =
             OnlineBlobProvider::new(provider, true, beacon_client, None, None);
         let block_ref = BlockInfo { timestamp: 15, ..Default::default() };
-        let result = blob_provider.get_blobs(&block_ref, &blob_hashes).await;
-        assert_eq!(
-            result.unwrap_err(),
-            BlobProviderError::Custom(anyhow::anyhow!(
-                "invalid sidecar ordering, blob hash index 4 does not match sidecar index 0"
-            ))
-        );
+        let blobs = blob_provider.get_blobs(&block_ref, &blob_hashes).await.unwrap();
+        let expected = [4, 0, 1, 2, 3].map(|i| sidecars.data[i].inner.blob);
+        assert_eq!(blobs, expected);
     }
 
     #[tokio::test]
@@ -431,4 +513,92 @@ mod tests {
             BlobProviderError::Custom(anyhow::anyhow!("blob at index 0 failed verification"))
         );
     }
+
+    #[tokio::test]
+    async fn test_get_blobs_filters_requested_indices() {
+        let (provider, _anvil) = spawn_anvil();
+        let json_bytes = include_bytes!("testdata/eth_v1_beacon_sidecars_goerli.json");
+        let sidecars: APIGetBlobSidecarsResponse = serde_json::from_slice(json_bytes).unwrap();
+        let beacon_client = MockBeaconClient {
+            beacon_genesis: Some(APIGenesisResponse::new(10)),
+            config_spec: Some(APIConfigResponse::new(12)),
+            blob_sidecars: Some(sidecars),
+            ..Default::default()
+        };
+        let blob_hashes = vec![
+            IndexedBlobHash {
+                index: 1,
+                hash: b256!("010a9e10aab79bab62e10a5b83c164a91451b6ef56d31ac95a9514ffe6d6b4e6"),
+            },
+            IndexedBlobHash {
+                index: 3,
+                hash: b256!("01df1f9ae707f5847513c9c430b683182079edf2b1f94ee12e4daae7f3c8c309"),
+            },
+        ];
+        let mut blob_provider: OnlineBlobProvider<_, _, SimpleSlotDerivation> =
+            OnlineBlobProvider::new(provider, true, beacon_client, None, None);
+        let block_ref = BlockInfo { timestamp: 15, ..Default::default() };
+        let blobs = blob_provider.get_blobs(&block_ref, &blob_hashes).await.unwrap();
+        assert_eq!(blobs.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_get_blobs_pruned_without_fallback() {
+        let (provider, _anvil) = spawn_anvil();
+        let beacon_client = MockBeaconClient {
+            beacon_genesis: Some(APIGenesisResponse::new(10)),
+            config_spec: Some(APIConfigResponse::new(12)),
+            pruned: true,
+            ..Default::default()
+        };
+        let primary: OnlineBlobProvider<_, _, SimpleSlotDerivation> =
+            OnlineBlobProvider::new(provider, true, beacon_client, None, None);
+        let mut blob_provider =
+            OnlineBlobProviderWithFallback::new(primary, None::<MockBeaconClient>);
+        let block_ref = BlockInfo { timestamp: 34, ..Default::default() };
+        let blob_hashes = vec![IndexedBlobHash::default()];
+        let result = blob_provider.get_blobs(&block_ref, &blob_hashes).await;
+        assert_eq!(result.unwrap_err(), BlobProviderError::NotFound(2));
+    }
+
+    #[tokio::test]
+    async fn test_get_blobs_pruned_slot_uses_fallback() {
+        let (provider, _anvil) = spawn_anvil();
+        let json_bytes = include_bytes!("testdata/eth_v1_beacon_sidecars_goerli.json");
+        let sidecars: APIGetBlobSidecarsResponse = serde_json::from_slice(json_bytes).unwrap();
+        let beacon_client = MockBeaconClient {
+            beacon_genesis: Some(APIGenesisResponse::new(10)),
+            config_spec: Some(APIConfigResponse::new(12)),
+            pruned: true,
+            ..Default::default()
+        };
+        let archiver = MockBeaconClient { blob_sidecars: Some(sidecars), ..Default::default() };
+        let primary: OnlineBlobProvider<_, _, SimpleSlotDerivation> =
+            OnlineBlobProvider::new(provider, true, beacon_client, None, None);
+        let mut blob_provider = OnlineBlobProviderWithFallback::new(primary, Some(archiver));
+        let block_ref = BlockInfo { timestamp: 15, ..Default::default() };
+        let blob_hashes = vec![IndexedBlobHash {
+            index: 0,
+            hash: b256!("011075cbb20f3235b3179a5dff22689c410cd091692180f4b6a12be77ea0f586"),
+        }];
+        let blobs = blob_provider.get_blobs(&block_ref, &blob_hashes).await.unwrap();
+        assert_eq!(blobs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_get_blobs_fallback_not_used_for_other_errors() {
+        let (provider, _anvil) = spawn_anvil();
+        let beacon_client = MockBeaconClient::default();
+        let archiver = MockBeaconClient::default();
+        let primary: OnlineBlobProvider<_, _, SimpleSlotDerivation> =
+            OnlineBlobProvider::new(provider, true, beacon_client, None, None);
+        let mut blob_provider = OnlineBlobProviderWithFallback::new(primary, Some(archiver));
+        let block_ref = BlockInfo::default();
+        let blob_hashes = vec![IndexedBlobHash::default()];
+        let result = blob_provider.get_blobs(&block_ref, &blob_hashes).await;
+        assert_eq!(
+            result.unwrap_err(),
+            BlobProviderError::Custom(anyhow::anyhow!("failed to get beacon genesis"))
+        );
+    }
 }
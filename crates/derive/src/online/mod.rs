@@ -2,11 +2,11 @@
 
 use crate::{
     stages::{
-        AttributesQueue, BatchQueue, ChannelBank, ChannelReader, FrameQueue, L1Retrieval,
+        AttributesQueue, BatchQueue, ChannelBank, ChannelReader, FrameQueue, L1Retrieval, L1Tip,
         L1Traversal, StatefulAttributesBuilder,
     },
     traits::DataAvailabilityProvider,
-    types::RollupConfig,
+    types::{DriverConfig, RollupConfig},
 };
 use alloc::sync::Arc;
 use alloy_provider::ReqwestProvider;
@@ -32,9 +32,12 @@ pub type OnlineAttributesQueue<DAP> = AttributesQueue<
     OnlineAttributesBuilder,
 >;
 
-/// Creates a new online stack.
+/// Creates a new online stack. The L1 origin of the stack is bounded by the [DriverConfig], using
+/// the L1 head and finalized block the driver keeps updating through the [L1Tip].
 pub fn new_online_stack<DAP>(
     rollup_config: Arc<RollupConfig>,
+    driver_config: DriverConfig,
+    l1_tip: L1Tip,
     chain_provider: AlloyChainProvider<ReqwestProvider>,
     dap_source: DAP,
     fetcher: AlloyL2ChainProvider<ReqwestProvider>,
@@ -43,7 +46,12 @@ pub fn new_online_stack<DAP>(
 where
     DAP: DataAvailabilityProvider + Debug + Send,
 {
-    let l1_traversal = L1Traversal::new(chain_provider, rollup_config.clone());
+    let l1_traversal = L1Traversal::with_driver_config(
+        chain_provider,
+        rollup_config.clone(),
+        driver_config,
+        l1_tip,
+    );
     let l1_retrieval = L1Retrieval::new(l1_traversal, dap_source);
     let frame_queue = FrameQueue::new(l1_retrieval);
     let channel_bank = ChannelBank::new(rollup_config.clone(), frame_queue);
@@ -62,4 +70,6 @@ mod alloy_providers;
 pub use alloy_providers::{AlloyChainProvider, AlloyL2ChainProvider};
 
 mod blob_provider;
-pub use blob_provider::{OnlineBlobProvider, SimpleSlotDerivation};
+pub use blob_provider::{
+    OnlineBlobProvider, OnlineBlobProviderWithFallback, SimpleSlotDerivation, SlotDerivation,
+};
@@ -2,7 +2,8 @@
 
 use super::BeaconClient;
 use crate::types::{
-    APIConfigResponse, APIGenesisResponse, APIGetBlobSidecarsResponse, IndexedBlobHash,
+    APIConfigResponse, APIGenesisResponse, APIGetBlobSidecarsResponse, BlobProviderError,
+    IndexedBlobHash,
 };
 use alloc::{boxed::Box, string::String};
 use alloy_node_bindings::{Anvil, AnvilInstance};
@@ -41,6 +42,8 @@ pub struct MockBeaconClient {
     pub beacon_genesis: Option<APIGenesisResponse>,
     /// The blob sidecars.
     pub blob_sidecars: Option<APIGetBlobSidecarsResponse>,
+    /// Whether the blob sidecars have been pruned, returning a not found error.
+    pub pruned: bool,
 }
 
 #[async_trait]
@@ -60,9 +63,14 @@ impl BeaconClient for MockBeaconClient {
     async fn beacon_blob_side_cars(
         &self,
         _fetch_all_sidecars: bool,
-        _slot: u64,
+        slot: u64,
         _hashes: &[IndexedBlobHash],
-    ) -> anyhow::Result<APIGetBlobSidecarsResponse> {
-        self.blob_sidecars.clone().ok_or_else(|| anyhow::anyhow!("blob_sidecars not set"))
+    ) -> Result<APIGetBlobSidecarsResponse, BlobProviderError> {
+        if self.pruned {
+            return Err(BlobProviderError::NotFound(slot));
+        }
+        self.blob_sidecars
+            .clone()
+            .ok_or_else(|| BlobProviderError::Custom(anyhow::anyhow!("blob_sidecars not set")))
     }
 }
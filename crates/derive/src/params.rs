@@ -21,9 +21,25 @@ pub const MAX_SPAN_BATCH_BYTES: u64 = MAX_RLP_BYTES_PER_CHANNEL;
 /// a channel. This limit is set when decoding the RLP.
 pub const MAX_RLP_BYTES_PER_CHANNEL: u64 = 10_000_000;
 
+/// [MAX_RLP_BYTES_PER_CHANNEL_FJORD] is the maximum amount of bytes that will be read from
+/// a channel after the Fjord hardfork.
+pub const MAX_RLP_BYTES_PER_CHANNEL_FJORD: u64 = 100_000_000;
+
+/// The zlib deflate compression method, found in the lower nibble of the first channel byte.
+pub const ZLIB_DEFLATE_COMPRESSION_METHOD: u8 = 8;
+
+/// The reserved zlib compression method, found in the lower nibble of the first channel byte.
+pub const ZLIB_RESERVED_COMPRESSION_METHOD: u8 = 15;
+
+/// The channel version byte prefixing brotli compressed channel data, valid from Fjord onwards.
+pub const CHANNEL_VERSION_BROTLI: u8 = 0x01;
+
 /// The maximum size of a channel bank.
 pub const MAX_CHANNEL_BANK_SIZE: usize = 100_000_000;
 
+/// The maximum size of a channel bank after the Fjord hardfork.
+pub const MAX_CHANNEL_BANK_SIZE_FJORD: usize = 1_000_000_000;
+
 /// [CHANNEL_ID_LENGTH] is the length of the channel ID.
 pub const CHANNEL_ID_LENGTH: usize = 16;
 
@@ -33,7 +49,7 @@ pub type ChannelID = [u8; CHANNEL_ID_LENGTH];
 /// Frames cannot be larger than 1MB.
 /// Data transactions that carry frames are generally not larger than 128 KB due to L1 network
 /// conditions, but we leave space to grow larger anyway (gas limit allows for more data).
-pub const MAX_FRAME_LEN: usize = 1000;
+pub const MAX_FRAME_LEN: usize = 1_000_000;
 
 /// `keccak256("ConfigUpdate(uint256,uint8,bytes)")`
 pub const CONFIG_UPDATE_TOPIC: B256 =
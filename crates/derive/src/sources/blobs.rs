@@ -2,16 +2,37 @@
 
 use crate::{
     traits::{AsyncIterator, BlobProvider, ChainProvider, SignedRecoverable},
-    types::{BlobData, BlockInfo, IndexedBlobHash, StageError, StageResult},
+    types::{
+        BlobData, BlockInfo, DataKind, DataSourceItem, IndexedBlobHash, StageError, StageResult,
+    },
 };
-use alloc::{boxed::Box, vec::Vec};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_consensus::{Transaction, TxEip4844Variant, TxEnvelope, TxType};
-use alloy_primitives::{Address, Bytes, TxKind};
+use alloy_primitives::{Address, TxKind, B256};
 use anyhow::Result;
 use async_trait::async_trait;
+use spin::Mutex;
 use tracing::warn;
 
+/// The data of an L1 block loaded by a [BlobSource].
+#[derive(Debug, Default)]
+struct BlobSourceData {
+    /// Whether the transactions of the block have been loaded.
+    open: bool,
+    /// Data, along with the hash of the transaction that carried it and its kind.
+    /// The data of a blob is empty until the blob has been fetched.
+    data: Vec<(B256, DataKind, BlobData)>,
+    /// The hashes of the blobs carried by the batcher transactions of the block.
+    hashes: Vec<IndexedBlobHash>,
+}
+
 /// A data iterator that reads from a blob.
+///
+/// The transactions of the block are loaded on the first call to [AsyncIterator::next], and the
+/// blobs of the block are fetched with a single request once the iterator reaches the first of
+/// them. Everything loaded is kept when the [BlobProvider] fails, so that a retry only fetches what
+/// is still missing. The loaded data is
+/// shared by all clones of the source, each of which iterates from its own position.
 #[derive(Debug, Clone)]
 pub struct BlobSource<F, B>
 where
@@ -28,10 +49,10 @@ where
     block_ref: BlockInfo,
     /// The L1 Signer.
     signer: Address,
-    /// Data.
-    data: Vec<BlobData>,
-    /// Whether the source is open.
-    open: bool,
+    /// The data loaded for the block.
+    loaded: Arc<Mutex<BlobSourceData>>,
+    /// The index of the next data to return.
+    cursor: usize,
 }
 
 impl<F, B> BlobSource<F, B>
@@ -53,29 +74,54 @@ where
             batcher_address,
             block_ref,
             signer,
-            data: Vec::new(),
-            open: false,
+            loaded: Arc::new(Mutex::new(BlobSourceData::default())),
+            cursor: 0,
         }
     }
 
-    fn extract_blob_data(&self, txs: Vec<TxEnvelope>) -> (Vec<BlobData>, Vec<IndexedBlobHash>) {
+    /// Clears the data loaded for the block and rewinds the source, so that the next call to
+    /// [AsyncIterator::next] loads the transactions and blobs of the block again. This also
+    /// clears the data of all clones of the source.
+    ///
+    /// Failing to fetch data does not clear the source, this is only needed when the origin
+    /// changes.
+    pub fn clear(&mut self) {
+        *self.loaded.lock() = BlobSourceData::default();
+        self.cursor = 0;
+    }
+
+    fn extract_blob_data(
+        &self,
+        txs: Vec<TxEnvelope>,
+    ) -> (Vec<(B256, DataKind, BlobData)>, Vec<IndexedBlobHash>) {
         let mut index = 0;
         let mut data = Vec::new();
         let mut hashes = Vec::new();
         for tx in txs {
-            let (tx_kind, calldata, blob_hashes) = match &tx {
-                TxEnvelope::Legacy(tx) => (tx.tx().to(), tx.tx().input.clone(), None),
-                TxEnvelope::Eip2930(tx) => (tx.tx().to(), tx.tx().input.clone(), None),
-                TxEnvelope::Eip1559(tx) => (tx.tx().to(), tx.tx().input.clone(), None),
-                TxEnvelope::Eip4844(blob_tx_wrapper) => match blob_tx_wrapper.tx() {
-                    TxEip4844Variant::TxEip4844(tx) => {
-                        (tx.to(), tx.input.clone(), Some(tx.blob_versioned_hashes.clone()))
-                    }
-                    TxEip4844Variant::TxEip4844WithSidecar(tx) => {
-                        let tx = tx.tx();
-                        (tx.to(), tx.input.clone(), Some(tx.blob_versioned_hashes.clone()))
+            let (tx_kind, calldata, blob_hashes, hash) = match &tx {
+                TxEnvelope::Legacy(tx) => (tx.tx().to(), tx.tx().input.clone(), None, *tx.hash()),
+                TxEnvelope::Eip2930(tx) => (tx.tx().to(), tx.tx().input.clone(), None, *tx.hash()),
+                TxEnvelope::Eip1559(tx) => (tx.tx().to(), tx.tx().input.clone(), None, *tx.hash()),
+                TxEnvelope::Eip4844(blob_tx_wrapper) => {
+                    let hash = *blob_tx_wrapper.hash();
+                    match blob_tx_wrapper.tx() {
+                        TxEip4844Variant::TxEip4844(tx) => (
+                            tx.to(),
+                            tx.input.clone(),
+                            Some(tx.blob_versioned_hashes.clone()),
+                            hash,
+                        ),
+                        TxEip4844Variant::TxEip4844WithSidecar(tx) => {
+                            let tx = tx.tx();
+                            (
+                                tx.to(),
+                                tx.input.clone(),
+                                Some(tx.blob_versioned_hashes.clone()),
+                                hash,
+                            )
+                        }
                     }
-                },
+                }
                 _ => continue,
             };
             let TxKind::Call(to) = tx_kind else { continue };
@@ -90,17 +136,10 @@ where
             }
             if tx.tx_type() != TxType::Eip4844 {
                 let blob_data = BlobData { data: None, calldata: Some(calldata.to_vec().into()) };
-                data.push(blob_data);
+                data.push((hash, DataKind::Calldata, blob_data));
                 continue;
             }
             if !calldata.is_empty() {
-                let hash = match &tx {
-                    TxEnvelope::Legacy(tx) => Some(tx.hash()),
-                    TxEnvelope::Eip2930(tx) => Some(tx.hash()),
-                    TxEnvelope::Eip1559(tx) => Some(tx.hash()),
-                    TxEnvelope::Eip4844(blob_tx_wrapper) => Some(blob_tx_wrapper.hash()),
-                    _ => None,
-                };
                 warn!("Blob tx has calldata, which will be ignored: {hash:?}");
             }
             let blob_hashes = if let Some(b) = blob_hashes {
@@ -111,62 +150,70 @@ where
             for blob in blob_hashes {
                 let indexed = IndexedBlobHash { hash: blob, index };
                 hashes.push(indexed);
-                data.push(BlobData::default());
+                data.push((hash, DataKind::Blob(index), BlobData::default()));
                 index += 1;
             }
         }
         (data, hashes)
     }
 
-    /// Loads blob data into the source if it is not open.
-    async fn load_blobs(&mut self) -> Result<()> {
-        if self.open {
+    /// Loads the transactions of the block into the source if it is not open.
+    async fn load_transactions(&mut self) -> Result<()> {
+        if self.loaded.lock().open {
             return Ok(());
         }
 
         let info =
             self.chain_provider.block_info_and_transactions_by_hash(self.block_ref.hash).await?;
+        let (data, hashes) = self.extract_blob_data(info.1);
 
-        let (mut data, blob_hashes) = self.extract_blob_data(info.1);
+        *self.loaded.lock() = BlobSourceData { open: true, data, hashes };
+        Ok(())
+    }
 
-        // If there are no hashes, set the calldata and return.
-        if blob_hashes.is_empty() {
-            self.open = true;
-            self.data = data;
+    /// Fetches all the blobs of the block that are still missing with a single request, and
+    /// fills their data. The blobs already fetched are kept.
+    async fn fetch_missing_blobs(&mut self) -> Result<()> {
+        let missing = {
+            let loaded = self.loaded.lock();
+            loaded
+                .hashes
+                .iter()
+                .filter(|h| {
+                    loaded
+                        .data
+                        .iter()
+                        .any(|(_, kind, blob)| *kind == DataKind::Blob(h.index) && blob.is_empty())
+                })
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+        if missing.is_empty() {
             return Ok(());
         }
 
-        let blobs =
-            self.blob_fetcher.get_blobs(&self.block_ref, &blob_hashes).await.map_err(|e| {
-                warn!("Failed to fetch blobs: {e}");
-                anyhow::anyhow!("Failed to fetch blobs: {e}")
-            })?;
-
-        // Fill the blob pointers.
-        let mut blob_index = 0;
-        for blob in data.iter_mut() {
-            match blob.fill(&blobs, blob_index) {
-                Ok(_) => {
-                    blob_index += 1;
-                }
-                Err(e) => {
-                    return Err(e);
-                }
-            }
-        }
+        let blobs = self.blob_fetcher.get_blobs(&self.block_ref, &missing).await.map_err(|e| {
+            warn!("Failed to fetch {} blobs: {e}", missing.len());
+            anyhow::anyhow!("Failed to fetch {} blobs: {e}", missing.len())
+        })?;
 
-        self.open = true;
-        self.data = data;
+        let mut loaded = self.loaded.lock();
+        for (i, hash) in missing.iter().enumerate() {
+            let blob = loaded
+                .data
+                .iter_mut()
+                .find_map(|(_, kind, blob)| (*kind == DataKind::Blob(hash.index)).then_some(blob))
+                .ok_or_else(|| anyhow::anyhow!("Unknown blob index {}", hash.index))?;
+            blob.fill(&blobs, i)?;
+        }
         Ok(())
     }
 
     /// Extracts the next data from the source.
-    fn next_data(&mut self) -> Result<BlobData, Option<Result<Bytes, StageError>>> {
-        if self.data.is_empty() {
-            return Err(Some(Err(StageError::Eof)));
-        }
-
-        Ok(self.data.remove(0))
+    fn next_data(
+        &self,
+    ) -> Result<(B256, DataKind, BlobData), Option<Result<DataSourceItem, StageError>>> {
+        self.loaded.lock().data.get(self.cursor).cloned().ok_or(Some(Err(StageError::Eof)))
     }
 }
 
@@ -176,29 +223,113 @@ where
     F: ChainProvider + Send,
     B: BlobProvider + Send,
 {
-    type Item = Bytes;
+    type Item = DataSourceItem;
 
     async fn next(&mut self) -> Option<StageResult<Self::Item>> {
-        if self.load_blobs().await.is_err() {
+        if self.load_transactions().await.is_err() {
             return Some(Err(StageError::BlockFetch(self.block_ref.hash)));
         }
 
-        let next_data = match self.next_data() {
+        let (mut hash, mut kind, mut next_data) = match self.next_data() {
             Ok(d) => d,
             Err(e) => return e,
         };
-        if next_data.calldata.is_some() {
-            return Some(Ok(next_data.calldata.unwrap()));
+        if matches!(kind, DataKind::Blob(_)) && next_data.is_empty() {
+            if self.fetch_missing_blobs().await.is_err() {
+                return Some(Err(StageError::BlockFetch(self.block_ref.hash)));
+            }
+            (hash, kind, next_data) = match self.next_data() {
+                Ok(d) => d,
+                Err(e) => return e,
+            };
+        }
+        self.cursor += 1;
+
+        if let Some(calldata) = next_data.calldata {
+            return Some(Ok(DataSourceItem::new(calldata, hash, kind)));
         }
 
         // Decode the blob data to raw bytes.
         // Otherwise, ignore blob and recurse next.
         match next_data.decode() {
-            Ok(d) => Some(Ok(d)),
+            Ok(d) => Some(Ok(DataSourceItem::new(d, hash, kind))),
             Err(_) => {
-                warn!("Failed to decode blob data, skipping");
+                warn!("Failed to decode {kind} of tx {hash}, skipping");
                 self.next().await
             }
         }
     }
 }
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{
+        traits::test_utils::{TestBlobProvider, TestChainProvider},
+        types::Blob,
+    };
+    use alloc::vec;
+    use alloy_primitives::{address, hex};
+    use alloy_rlp::Decodable;
+
+    /// The batcher address, which is also the address of the batcher inbox.
+    pub(crate) const BATCHER: Address = address!("f39fd6e51aad88f6f4ce6ab8827279cfffb92266");
+
+    /// An EIP-4844 transaction to the batcher inbox carrying three blobs, signed by [BATCHER].
+    const BLOB_TX: [u8; 214] = hex!("03f8d382038480843b9aca008477359400830f424094f39fd6e51aad88f6f4ce6ab8827279cfffb922668080c001f863a00101010101010101010101010101010101010101010101010101010101010101a00102020202020202020202020202020202020202020202020202020202020202a0010303030303030303030303030303030303030303030303030303030303030301a07b758c1171fe2ad39337e17f6a3bdf1e32f2b0d3c0816f807c2e282e35276427a0488b9be806807a30b43021896d629d79c9826ff4cc943d8b1833b48d81a367fa");
+
+    /// Returns an L1 block containing [BLOB_TX], along with providers serving the block and its
+    /// blobs.
+    pub(crate) fn blob_block() -> (BlockInfo, TestChainProvider, TestBlobProvider) {
+        let block = BlockInfo { hash: B256::repeat_byte(0xAA), number: 1, ..Default::default() };
+        let mut chain_provider = TestChainProvider::default();
+        chain_provider.insert_block(block.number, block);
+        let tx = TxEnvelope::decode(&mut BLOB_TX.as_slice()).unwrap();
+        chain_provider.insert_transactions(block.hash, vec![tx]);
+
+        let mut blob_provider = TestBlobProvider::default();
+        for i in 1..=3 {
+            let mut hash = B256::repeat_byte(i);
+            hash[0] = 0x01;
+            blob_provider.insert_blob(hash, Blob::ZERO);
+        }
+        (block, chain_provider, blob_provider)
+    }
+
+    #[tokio::test]
+    async fn test_blob_source_retries_failed_blob() {
+        let (block, chain_provider, blob_provider) = blob_block();
+        blob_provider.fail_once.lock().push(1);
+        let mut source =
+            BlobSource::new(chain_provider, blob_provider.clone(), BATCHER, block, BATCHER);
+
+        // The blobs of the block are fetched with a single request, which is retried as a whole.
+        assert_eq!(source.next().await, Some(Err(StageError::BlockFetch(block.hash))));
+        for i in 0..3 {
+            assert_eq!(source.next().await.unwrap().unwrap().kind, DataKind::Blob(i));
+        }
+        assert_eq!(source.next().await, Some(Err(StageError::Eof)));
+        assert_eq!(*blob_provider.requests.lock(), vec![vec![0, 1, 2], vec![0, 1, 2]]);
+    }
+
+    #[tokio::test]
+    async fn test_blob_source_clear() {
+        let (block, chain_provider, blob_provider) = blob_block();
+        let mut source =
+            BlobSource::new(chain_provider, blob_provider.clone(), BATCHER, block, BATCHER);
+        let mut clone = source.clone();
+
+        let item = source.next().await.unwrap().unwrap();
+        assert_eq!(
+            item,
+            DataSourceItem::new(Default::default(), item.origin_tx, DataKind::Blob(0))
+        );
+        // Clones share the fetched blobs.
+        assert_eq!(clone.next().await.unwrap().unwrap(), item);
+        assert_eq!(*blob_provider.requests.lock(), vec![vec![0, 1, 2]]);
+
+        source.clear();
+        assert_eq!(source.next().await.unwrap().unwrap(), item);
+        assert_eq!(*blob_provider.requests.lock(), vec![vec![0, 1, 2], vec![0, 1, 2]]);
+    }
+}
@@ -2,14 +2,18 @@
 
 use crate::{
     traits::{AsyncIterator, ChainProvider, SignedRecoverable},
-    types::{BlockInfo, StageError, StageResult},
+    types::{BlockInfo, DataKind, DataSourceItem, StageError, StageResult},
 };
-use alloc::{boxed::Box, collections::VecDeque};
+use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_consensus::{Transaction, TxEnvelope};
-use alloy_primitives::{Address, Bytes, TxKind};
+use alloy_primitives::{Address, TxKind};
 use async_trait::async_trait;
+use spin::Mutex;
 
 /// A data iterator that reads from calldata.
+///
+/// The calldata of the block is loaded on the first call to [AsyncIterator::next], and is shared
+/// by all clones of the source, each of which iterates from its own position.
 #[derive(Debug, Clone)]
 pub struct CalldataSource<CP>
 where
@@ -23,10 +27,10 @@ where
     block_ref: BlockInfo,
     /// The L1 Signer.
     signer: Address,
-    /// Current calldata.
-    calldata: VecDeque<Bytes>,
-    /// Whether the calldata source is open.
-    open: bool,
+    /// The calldata of the block, if it has been loaded.
+    calldata: Arc<Mutex<Option<Vec<DataSourceItem>>>>,
+    /// The index of the next calldata to return.
+    cursor: usize,
 }
 
 impl<CP: ChainProvider + Send> CalldataSource<CP> {
@@ -42,27 +46,38 @@ impl<CP: ChainProvider + Send> CalldataSource<CP> {
             batcher_address,
             block_ref,
             signer,
-            calldata: VecDeque::new(),
-            open: false,
+            calldata: Arc::new(Mutex::new(None)),
+            cursor: 0,
         }
     }
 
+    /// Clears the calldata loaded for the block and rewinds the source, so that the next call to
+    /// [AsyncIterator::next] loads the transactions of the block again. This also clears the
+    /// calldata of all clones of the source.
+    ///
+    /// Failing to fetch the block does not clear the source, this is only needed when the origin
+    /// changes.
+    pub fn clear(&mut self) {
+        *self.calldata.lock() = None;
+        self.cursor = 0;
+    }
+
     /// Loads the calldata into the source if it is not open.
     async fn load_calldata(&mut self) -> anyhow::Result<()> {
-        if self.open {
+        if self.calldata.lock().is_some() {
             return Ok(());
         }
 
         let (_, txs) =
             self.chain_provider.block_info_and_transactions_by_hash(self.block_ref.hash).await?;
 
-        self.calldata = txs
+        let calldata = txs
             .iter()
             .filter_map(|tx| {
-                let (tx_kind, data) = match tx {
-                    TxEnvelope::Legacy(tx) => (tx.tx().to(), tx.tx().input()),
-                    TxEnvelope::Eip2930(tx) => (tx.tx().to(), tx.tx().input()),
-                    TxEnvelope::Eip1559(tx) => (tx.tx().to(), tx.tx().input()),
+                let (tx_kind, data, hash) = match tx {
+                    TxEnvelope::Legacy(tx) => (tx.tx().to(), tx.tx().input(), *tx.hash()),
+                    TxEnvelope::Eip2930(tx) => (tx.tx().to(), tx.tx().input(), *tx.hash()),
+                    TxEnvelope::Eip1559(tx) => (tx.tx().to(), tx.tx().input(), *tx.hash()),
                     _ => return None,
                 };
                 let TxKind::Call(to) = tx_kind else { return None };
@@ -73,24 +88,27 @@ impl<CP: ChainProvider + Send> CalldataSource<CP> {
                 if tx.recover_public_key().ok()? != self.signer {
                     return None;
                 }
-                Some(data.to_vec().into())
+                Some(DataSourceItem::new(data.to_vec().into(), hash, DataKind::Calldata))
             })
-            .collect::<VecDeque<_>>();
-
-        self.open = true;
+            .collect::<Vec<_>>();
 
+        *self.calldata.lock() = Some(calldata);
         Ok(())
     }
 }
 
 #[async_trait]
 impl<CP: ChainProvider + Send> AsyncIterator for CalldataSource<CP> {
-    type Item = Bytes;
+    type Item = DataSourceItem;
 
     async fn next(&mut self) -> Option<StageResult<Self::Item>> {
         if self.load_calldata().await.is_err() {
             return Some(Err(StageError::BlockFetch(self.block_ref.hash)));
         }
-        Some(self.calldata.pop_front().ok_or(StageError::Eof))
+        let next = self.calldata.lock().as_ref().and_then(|c| c.get(self.cursor).cloned());
+        if next.is_some() {
+            self.cursor += 1;
+        }
+        Some(next.ok_or(StageError::Eof))
     }
 }
@@ -4,19 +4,39 @@
 use crate::{
     sources::{BlobSource, CalldataSource, EthereumDataSourceVariant},
     traits::{BlobProvider, ChainProvider, DataAvailabilityProvider},
-    types::{BlockInfo, RollupConfig},
+    types::{BlockInfo, DataSourceItem, RollupConfig, RollupConfigError},
 };
-use alloc::{boxed::Box, fmt::Debug};
-use alloy_primitives::{Address, Bytes};
+use alloc::{boxed::Box, fmt::Debug, sync::Arc};
+use alloy_primitives::Address;
 use anyhow::Result;
 use async_trait::async_trait;
+use spin::Mutex;
+
+/// The data iterator last opened by an [EthereumDataSource].
+#[derive(Debug)]
+struct OpenedData<C, B>
+where
+    C: ChainProvider + Send,
+    B: BlobProvider + Send,
+{
+    /// The block the data was opened for.
+    block_ref: BlockInfo,
+    /// The batcher address the data was opened with.
+    batcher_address: Address,
+    /// The data iterator, positioned at the start of the block.
+    data: EthereumDataSourceVariant<C, B>,
+}
 
 /// A factory for creating an Ethereum data source provider.
-#[derive(Debug, Clone, Copy)]
+///
+/// Opening the data of the same block twice in a row returns the same data iterator, rewound to
+/// the start of the block, so that a pipeline reset does not fetch the data of the block again.
+/// The clones of the factory share the data iterator last opened, so the factory is not [Copy].
+#[derive(Debug, Clone)]
 pub struct EthereumDataSource<C, B>
 where
     C: ChainProvider + Send + Clone,
-    B: BlobProvider + Clone,
+    B: BlobProvider + Send + Clone,
 {
     /// The chain provider to use for the factory.
     pub chain_provider: C,
@@ -26,12 +46,14 @@ where
     pub ecotone_timestamp: Option<u64>,
     /// The L1 Signer.
     pub signer: Address,
+    /// The data iterator last opened by the factory.
+    opened: Arc<Mutex<Option<OpenedData<C, B>>>>,
 }
 
 impl<C, B> EthereumDataSource<C, B>
 where
     C: ChainProvider + Send + Clone + Debug,
-    B: BlobProvider + Clone + Debug,
+    B: BlobProvider + Send + Clone + Debug,
 {
     /// Creates a new factory.
     pub fn new(provider: C, blobs: B, cfg: &RollupConfig) -> Self {
@@ -40,8 +62,15 @@ where
             blob_provider: blobs,
             ecotone_timestamp: cfg.ecotone_time,
             signer: cfg.genesis.system_config.batcher_addr,
+            opened: Arc::new(Mutex::new(None)),
         }
     }
+
+    /// Creates a new factory, returning an error if the [RollupConfig] is inconsistent.
+    pub fn try_new(provider: C, blobs: B, cfg: &RollupConfig) -> Result<Self, RollupConfigError> {
+        cfg.check()?;
+        Ok(Self::new(provider, blobs, cfg))
+    }
 }
 
 #[async_trait]
@@ -50,7 +79,7 @@ where
     C: ChainProvider + Send + Sync + Clone + Debug,
     B: BlobProvider + Send + Sync + Clone + Debug,
 {
-    type Item = Bytes;
+    type Item = DataSourceItem;
     type DataIter = EthereumDataSourceVariant<C, B>;
 
     async fn open_data(
@@ -58,23 +87,89 @@ where
         block_ref: &BlockInfo,
         batcher_address: Address,
     ) -> Result<Self::DataIter> {
+        let mut opened = self.opened.lock();
+        if let Some(o) = opened.as_ref() {
+            if o.block_ref == *block_ref && o.batcher_address == batcher_address {
+                return Ok(o.data.clone());
+            }
+        }
+
         let ecotone_enabled =
             self.ecotone_timestamp.map(|e| block_ref.timestamp >= e).unwrap_or(false);
-        if ecotone_enabled {
-            Ok(EthereumDataSourceVariant::Blob(BlobSource::new(
+        let data = if ecotone_enabled {
+            EthereumDataSourceVariant::Blob(BlobSource::new(
                 self.chain_provider.clone(),
                 self.blob_provider.clone(),
                 batcher_address,
                 *block_ref,
                 self.signer,
-            )))
+            ))
         } else {
-            Ok(EthereumDataSourceVariant::Calldata(CalldataSource::new(
+            EthereumDataSourceVariant::Calldata(CalldataSource::new(
                 self.chain_provider.clone(),
                 batcher_address,
                 *block_ref,
                 self.signer,
-            )))
+            ))
+        };
+        *opened = Some(OpenedData { block_ref: *block_ref, batcher_address, data: data.clone() });
+        Ok(data)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        sources::blobs::tests::{blob_block, BATCHER},
+        traits::AsyncIterator,
+        types::{DataKind, StageError},
+    };
+    use alloc::vec;
+    use alloy_primitives::B256;
+
+    fn rollup_config() -> RollupConfig {
+        let mut cfg = RollupConfig { ecotone_time: Some(0), ..Default::default() };
+        cfg.genesis.system_config.batcher_addr = BATCHER;
+        cfg
+    }
+
+    #[tokio::test]
+    async fn test_open_data_resumes_failed_blob_fetch() {
+        let (block, chain_provider, blob_provider) = blob_block();
+        blob_provider.fail_once.lock().push(1);
+        let source = EthereumDataSource::new(chain_provider, blob_provider, &rollup_config());
+
+        let mut data = source.open_data(&block, BATCHER).await.unwrap();
+        assert_eq!(data.next().await, Some(Err(StageError::BlockFetch(block.hash))));
+        let mut data = source.open_data(&block, BATCHER).await.unwrap();
+        assert_eq!(data.next().await.unwrap().unwrap().kind, DataKind::Blob(0));
+
+        // Reopening the block, as a pipeline reset does, starts at the first blob again without
+        // fetching the blobs again.
+        let mut data = source.open_data(&block, BATCHER).await.unwrap();
+        for i in 0..3 {
+            assert_eq!(data.next().await.unwrap().unwrap().kind, DataKind::Blob(i));
         }
+        assert_eq!(data.next().await, Some(Err(StageError::Eof)));
+        assert_eq!(*source.blob_provider.requests.lock(), vec![vec![0, 1, 2], vec![0, 1, 2]]);
+    }
+
+    #[tokio::test]
+    async fn test_open_data_other_block() {
+        let (block, mut chain_provider, blob_provider) = blob_block();
+        let other = BlockInfo { hash: B256::repeat_byte(0xBB), number: 2, ..block };
+        chain_provider.insert_block(other.number, other);
+        let source = EthereumDataSource::new(chain_provider, blob_provider, &rollup_config());
+
+        let mut data = source.open_data(&block, BATCHER).await.unwrap();
+        assert_eq!(data.next().await.unwrap().unwrap().kind, DataKind::Blob(0));
+        let mut data = source.open_data(&other, BATCHER).await.unwrap();
+        assert_eq!(data.next().await, Some(Err(StageError::Eof)));
+
+        // The data of the first block is fetched again once another block has been opened.
+        let mut data = source.open_data(&block, BATCHER).await.unwrap();
+        assert_eq!(data.next().await.unwrap().unwrap().kind, DataKind::Blob(0));
+        assert_eq!(*source.blob_provider.requests.lock(), vec![vec![0, 1, 2], vec![0, 1, 2]]);
     }
 }
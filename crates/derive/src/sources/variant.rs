@@ -3,10 +3,9 @@
 use crate::{
     sources::{BlobSource, CalldataSource},
     traits::{AsyncIterator, BlobProvider, ChainProvider},
-    types::StageResult,
+    types::{DataSourceItem, StageResult},
 };
 use alloc::boxed::Box;
-use alloy_primitives::Bytes;
 use async_trait::async_trait;
 
 /// An enum over the various data sources.
@@ -28,7 +27,7 @@ where
     CP: ChainProvider + Send,
     B: BlobProvider + Send,
 {
-    type Item = Bytes;
+    type Item = DataSourceItem;
 
     async fn next(&mut self) -> Option<StageResult<Self::Item>> {
         match self {
@@ -1,10 +1,13 @@
 //! Contains the logic for the `AttributesQueue` stage.
 
 use crate::{
-    traits::{OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
+    traits::{
+        CheckpointableStage, FlushableStage, OriginAdvancer, OriginProvider, PreviousStage,
+        ResettableStage,
+    },
     types::{
-        BlockInfo, L2AttributesWithParent, L2BlockInfo, L2PayloadAttributes, ResetError,
-        RollupConfig, SingleBatch, StageError, StageResult, SystemConfig,
+        BlockInfo, L2AttributesWithParent, L2BlockInfo, L2PayloadAttributes, PipelineCheckpoint,
+        ResetError, RollupConfig, SingleBatch, StageError, StageResult, SystemConfig,
     },
 };
 use alloc::boxed::Box;
@@ -201,13 +204,48 @@ where
     async fn reset(
         &mut self,
         block_info: BlockInfo,
+        l2_safe_head: L2BlockInfo,
         system_config: &SystemConfig,
     ) -> StageResult<()> {
-        self.prev.reset(block_info, system_config).await?;
+        self.prev.reset(block_info, l2_safe_head, system_config).await?;
         info!("resetting attributes queue");
         self.batch = None;
         self.is_last_in_span = false;
-        Err(StageError::Eof)
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P, AB> FlushableStage for AttributesQueue<P, AB>
+where
+    P: AttributesProvider + PreviousStage + FlushableStage + Send + Debug,
+    AB: AttributesBuilder + Send + Debug,
+{
+    async fn flush_channel(&mut self) -> StageResult<()> {
+        self.prev.flush_channel().await?;
+        self.batch = None;
+        self.is_last_in_span = false;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P, AB> CheckpointableStage for AttributesQueue<P, AB>
+where
+    P: AttributesProvider + PreviousStage + CheckpointableStage + Send + Debug,
+    AB: AttributesBuilder + Send + Debug,
+{
+    fn checkpoint(&self, checkpoint: &mut PipelineCheckpoint) {
+        self.prev.checkpoint(checkpoint);
+        checkpoint.batch = self.batch.clone();
+        checkpoint.is_last_in_span = self.is_last_in_span;
+    }
+
+    async fn restore(&mut self, checkpoint: &PipelineCheckpoint) -> StageResult<()> {
+        self.prev.restore(checkpoint).await?;
+        self.batch = checkpoint.batch.clone();
+        self.is_last_in_span = checkpoint.is_last_in_span;
+        Ok(())
     }
 }
 
@@ -5,12 +5,12 @@ use crate::{
     params::SEQUENCER_FEE_VAULT_ADDRESS,
     traits::{ChainProvider, L2ChainProvider},
     types::{
-        BlockID, BuilderError, EcotoneTransactionBuilder, L1BlockInfoTx, L2BlockInfo,
-        L2PayloadAttributes, RawTransaction, RollupConfig,
+        BlockID, BuilderError, EcotoneTransactionBuilder, FjordTransactionBuilder, L1BlockInfoTx,
+        L2BlockInfo, L2PayloadAttributes, RawTransaction, RollupConfig,
     },
 };
 use alloc::{boxed::Box, fmt::Debug, sync::Arc, vec, vec::Vec};
-use alloy_rlp::Encodable;
+use alloy_eips::eip2718::Encodable2718;
 use async_trait::async_trait;
 
 /// The [AttributesBuilder] is responsible for preparing [L2PayloadAttributes]
@@ -120,10 +120,14 @@ where
         }
 
         let mut upgrade_transactions: Vec<RawTransaction> = vec![];
-        if self.rollup_cfg.is_ecotone_active(next_l2_time) {
+        if self.rollup_cfg.is_ecotone_activation_block(next_l2_time) {
             upgrade_transactions =
                 EcotoneTransactionBuilder::build_txs().map_err(BuilderError::Custom)?;
         }
+        if self.rollup_cfg.is_fjord_activation_block(next_l2_time) {
+            upgrade_transactions
+                .extend(FjordTransactionBuilder::build_txs().map_err(BuilderError::Custom)?);
+        }
 
         // Build and encode the L1 info transaction for the current payload.
         let (_, l1_info_tx_envelope) = L1BlockInfoTx::try_new_with_deposit_tx(
@@ -133,9 +137,11 @@ where
             &l1_header,
             next_l2_time,
         )?;
-        let mut encoded_l1_info_tx = Vec::with_capacity(l1_info_tx_envelope.length());
-        l1_info_tx_envelope.encode(&mut encoded_l1_info_tx);
+        let mut encoded_l1_info_tx = Vec::new();
+        l1_info_tx_envelope.encode_2718(&mut encoded_l1_info_tx);
 
+        // The upgrade transactions follow the L1 info transaction and the user deposits, which
+        // matches the ordering of the reference implementation.
         let mut txs =
             Vec::with_capacity(1 + deposit_transactions.len() + upgrade_transactions.len());
         txs.push(encoded_l1_info_tx.into());
@@ -153,6 +159,11 @@ where
             parent_beacon_root = Some(l1_header.parent_beacon_block_root.unwrap_or_default());
         }
 
+        let mut eip_1559_params = None;
+        if self.rollup_cfg.is_holocene_active(next_l2_time) {
+            eip_1559_params = Some(sys_config.eip_1559_params());
+        }
+
         Ok(L2PayloadAttributes {
             timestamp: next_l2_time,
             prev_randao: l1_header.mix_hash,
@@ -164,6 +175,7 @@ where
             )),
             withdrawals,
             parent_beacon_block_root: parent_beacon_root,
+            eip_1559_params,
         })
     }
 }
@@ -297,6 +309,7 @@ mod tests {
             )),
             withdrawals: None,
             parent_beacon_block_root: None,
+            eip_1559_params: None,
         };
         assert_eq!(payload, expected);
         assert_eq!(payload.transactions.len(), 1);
@@ -340,11 +353,47 @@ mod tests {
             )),
             withdrawals: Some(Vec::default()),
             parent_beacon_block_root: None,
+            eip_1559_params: None,
         };
         assert_eq!(payload, expected);
         assert_eq!(payload.transactions.len(), 1);
     }
 
+    #[tokio::test]
+    async fn test_prepare_payload_with_holocene_eip1559_params() {
+        let block_time = 10;
+        let timestamp = 100;
+        let cfg =
+            Arc::new(RollupConfig { block_time, holocene_time: Some(0), ..Default::default() });
+        let l2_number = 1;
+        let mut fetcher = MockSystemConfigL2Fetcher::default();
+        fetcher.insert(
+            l2_number,
+            SystemConfig { eip1559_denominator: 250, eip1559_elasticity: 6, ..Default::default() },
+        );
+        let mut provider = TestChainProvider::default();
+        let header = Header { timestamp, ..Default::default() };
+        let hash = header.hash_slow();
+        provider.insert_header(hash, header);
+        let mut builder = StatefulAttributesBuilder::new(cfg, fetcher, provider);
+        let epoch = BlockID { hash, number: l2_number };
+        let l2_parent = L2BlockInfo {
+            block_info: BlockInfo {
+                hash: B256::ZERO,
+                number: l2_number,
+                timestamp,
+                parent_hash: hash,
+            },
+            l1_origin: BlockID { hash, number: l2_number },
+            seq_num: 0,
+        };
+        let payload = builder.prepare_payload_attributes(l2_parent, epoch).await.unwrap();
+        assert_eq!(
+            payload.eip_1559_params,
+            Some(alloy_primitives::B64::from([0, 0, 0, 0xfa, 0, 0, 0, 6]))
+        );
+    }
+
     #[tokio::test]
     async fn test_prepare_payload_with_ecotone() {
         let block_time = 10;
@@ -385,8 +434,83 @@ mod tests {
             )),
             withdrawals: None,
             parent_beacon_block_root,
+            eip_1559_params: None,
         };
         assert_eq!(payload, expected);
+        assert_eq!(payload.transactions.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_prepare_payload_ecotone_activation_block() {
+        let block_time = 10;
+        let timestamp = 100;
+        let cfg = Arc::new(RollupConfig {
+            block_time,
+            ecotone_time: Some(timestamp + 1),
+            ..Default::default()
+        });
+        let l2_number = 1;
+        let mut fetcher = MockSystemConfigL2Fetcher::default();
+        fetcher.insert(l2_number, SystemConfig::default());
+        let mut provider = TestChainProvider::default();
+        let header = Header { timestamp, ..Default::default() };
+        let hash = header.hash_slow();
+        provider.insert_header(hash, header);
+        let mut builder = StatefulAttributesBuilder::new(cfg, fetcher, provider);
+        let epoch = BlockID { hash, number: l2_number };
+        let l2_parent = L2BlockInfo {
+            block_info: BlockInfo {
+                hash: B256::ZERO,
+                number: l2_number,
+                timestamp,
+                parent_hash: hash,
+            },
+            l1_origin: BlockID { hash, number: l2_number },
+            seq_num: 0,
+        };
+        let payload = builder.prepare_payload_attributes(l2_parent, epoch).await.unwrap();
+
+        // The L1 info transaction is followed by the Ecotone upgrade transactions.
         assert_eq!(payload.transactions.len(), 7);
+        let l1_info_tx = payload.transactions[0].as_ref();
+        let selector_offset = l1_info_tx.len() - (4 + 32 * 8);
+        assert_eq!(l1_info_tx[selector_offset..selector_offset + 4], [0x01, 0x5d, 0x8e, 0xb9]);
+    }
+
+    #[tokio::test]
+    async fn test_prepare_payload_fjord_activation_block() {
+        let block_time = 10;
+        let timestamp = 100;
+        let cfg = Arc::new(RollupConfig {
+            block_time,
+            ecotone_time: Some(0),
+            fjord_time: Some(timestamp + 1),
+            ..Default::default()
+        });
+        let l2_number = 1;
+        let mut fetcher = MockSystemConfigL2Fetcher::default();
+        fetcher.insert(l2_number, SystemConfig::default());
+        let mut provider = TestChainProvider::default();
+        let header = Header { timestamp, ..Default::default() };
+        let hash = header.hash_slow();
+        provider.insert_header(hash, header);
+        let mut builder = StatefulAttributesBuilder::new(cfg, fetcher, provider);
+        let epoch = BlockID { hash, number: l2_number };
+        let l2_parent = L2BlockInfo {
+            block_info: BlockInfo {
+                hash: B256::ZERO,
+                number: l2_number,
+                timestamp,
+                parent_hash: hash,
+            },
+            l1_origin: BlockID { hash, number: l2_number },
+            seq_num: 0,
+        };
+        let payload = builder.prepare_payload_attributes(l2_parent, epoch).await.unwrap();
+
+        // The L1 info transaction is followed by the Fjord upgrade transactions.
+        let fjord_txs = FjordTransactionBuilder::build_txs().unwrap();
+        assert_eq!(payload.transactions.len(), 1 + fjord_txs.len());
+        assert_eq!(payload.transactions[1..], fjord_txs);
     }
 }
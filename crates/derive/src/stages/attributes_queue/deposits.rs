@@ -1,40 +1,47 @@
 //! Contains a helper method to derive deposit transactions from L1 Receipts.
 
-use crate::types::{DepositError, RawTransaction};
+use crate::types::RawTransaction;
 use alloc::vec::Vec;
 use alloy_consensus::Receipt;
 use alloy_primitives::{Address, Log, B256};
-use kona_primitives::{decode_deposit, DEPOSIT_EVENT_ABI_HASH};
+use kona_primitives::{decode_deposit_raw, DEPOSIT_EVENT_ABI_HASH};
 
 /// Derive deposits for transaction receipts.
 ///
 /// Successful deposits must be emitted by the deposit contract and have the correct event
 /// signature. So the receipt address must equal the specified deposit contract and the first topic
-/// must be the [DEPOSIT_EVENT_ABI_HASH].
+/// must be the [DEPOSIT_EVENT_ABI_HASH]. Each deposit commits to the index of its log within all
+/// logs of the block. If any deposit log is malformed, no deposits are derived for the block.
 pub(crate) async fn derive_deposits(
     block_hash: B256,
     receipts: Vec<Receipt>,
     deposit_contract: Address,
 ) -> anyhow::Result<Vec<RawTransaction>> {
-    let receipts = receipts.into_iter().filter(|r| r.status).collect::<Vec<_>>();
-    // Flatten the list of receipts into a list of logs.
-    let addr = |l: &Log| l.address == deposit_contract;
-    let topics = |l: &Log| l.data.topics().first().map_or(false, |i| *i == DEPOSIT_EVENT_ABI_HASH);
-    let filter_logs =
-        |r: Receipt| r.logs.into_iter().filter(|l| addr(l) && topics(l)).collect::<Vec<Log>>();
-    let logs = receipts.into_iter().flat_map(filter_logs).collect::<Vec<Log>>();
-    // TODO(refcell): are logs **and** receipts guaranteed to be _in order_?
-    //                If not, we need to somehow get the index of each log in the block.
-    logs.iter()
-        .enumerate()
-        .map(|(i, l)| decode_deposit(block_hash, i, l))
-        .collect::<Result<Vec<_>, DepositError>>()
-        .map_err(|e| anyhow::anyhow!(e))
+    let is_deposit = |l: &Log| {
+        l.address == deposit_contract &&
+            l.data.topics().first().map_or(false, |i| *i == DEPOSIT_EVENT_ABI_HASH)
+    };
+
+    let mut deposits = Vec::new();
+    let mut log_index = 0;
+    for receipt in receipts.iter() {
+        for log in receipt.logs.iter() {
+            if receipt.status && is_deposit(log) {
+                deposits.push(
+                    decode_deposit_raw(block_hash, log_index, log)
+                        .map_err(|e| anyhow::anyhow!(e))?,
+                );
+            }
+            log_index += 1;
+        }
+    }
+    Ok(deposits)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::DepositError;
     use alloc::vec;
     use alloy_primitives::{address, Bytes, LogData, U256, U64};
 
@@ -135,4 +142,22 @@ mod tests {
         let result = derive_deposits(B256::default(), receipts, deposit_contract).await;
         assert_eq!(result.unwrap().len(), 4);
     }
+
+    #[tokio::test]
+    async fn test_derive_deposits_block_log_index() {
+        let deposit_contract = address!("1111111111111111111111111111111111111111");
+        let block_hash = B256::repeat_byte(0xAA);
+        let receipts = vec![generate_valid_receipt(), generate_valid_receipt()];
+        let result = derive_deposits(block_hash, receipts, deposit_contract).await.unwrap();
+
+        // The first two of the three logs in each receipt are deposits.
+        let receipt = generate_valid_receipt();
+        let expected = vec![
+            decode_deposit_raw(block_hash, 0, &receipt.logs[0]).unwrap(),
+            decode_deposit_raw(block_hash, 1, &receipt.logs[1]).unwrap(),
+            decode_deposit_raw(block_hash, 3, &receipt.logs[0]).unwrap(),
+            decode_deposit_raw(block_hash, 4, &receipt.logs[1]).unwrap(),
+        ];
+        assert_eq!(result, expected);
+    }
 }
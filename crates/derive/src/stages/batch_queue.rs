@@ -2,17 +2,20 @@
 
 use crate::{
     stages::attributes_queue::AttributesProvider,
-    traits::{L2ChainProvider, OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
+    traits::{
+        CheckpointableStage, FlushableStage, L2ChainProvider, OriginAdvancer, OriginProvider,
+        PreviousStage, ResettableStage,
+    },
     types::{
-        Batch, BatchValidity, BatchWithInclusionBlock, BlockInfo, L2BlockInfo, RollupConfig,
-        SingleBatch, StageError, StageResult, SystemConfig,
+        check_batch, Batch, BatchValidity, BatchWithInclusionBlock, BlockInfo, L2BlockInfo,
+        PipelineCheckpoint, RollupConfig, SingleBatch, StageError, StageResult, SystemConfig,
     },
 };
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use anyhow::anyhow;
 use async_trait::async_trait;
 use core::fmt::Debug;
-use tracing::{error, info, warn};
+use tracing::{debug, error, info, warn};
 
 /// Provides [Batch]es for the [BatchQueue] stage.
 #[async_trait]
@@ -144,14 +147,24 @@ where
         let mut remaining = Vec::new();
         for i in 0..self.batches.len() {
             let batch = &self.batches[i];
-            let validity =
-                batch.check_batch(&self.cfg, &self.l1_blocks, parent, &mut self.fetcher).await;
+            let validity = check_batch(
+                &self.cfg,
+                &self.l1_blocks,
+                parent,
+                &batch.batch,
+                &batch.inclusion_block,
+                &mut self.fetcher,
+            )
+            .await;
             match validity {
                 BatchValidity::Future => {
                     remaining.push(batch.clone());
                 }
-                BatchValidity::Drop => {
-                    warn!("Dropping batch: {:?}, parent: {}", batch.batch, parent.block_info);
+                BatchValidity::Drop(reason) => {
+                    warn!(
+                        "Dropping batch ({reason}): {:?}, parent: {}",
+                        batch.batch, parent.block_info
+                    );
                     continue;
                 }
                 BatchValidity::Accept => {
@@ -176,10 +189,13 @@ where
         }
 
         // If the current epoch is too old compared to the L1 block we are at,
-        // i.e. if the sequence window expired, we create empty batches for the current epoch
+        // i.e. if the sequence window expired, we create empty batches for the current epoch.
+        // The window is measured against the origin of this stage, as the safe head cannot
+        // progress past an epoch that has no batches.
+        let origin = self.origin.ok_or(StageError::MissingOrigin)?;
         let expiry_epoch = epoch.number + self.cfg.seq_window_size;
-        let force_empty_batches = (expiry_epoch == parent.l1_origin.number && empty) ||
-            expiry_epoch < parent.l1_origin.number;
+        let force_empty_batches =
+            (expiry_epoch == origin.number && empty) || expiry_epoch < origin.number;
         let first_of_epoch = epoch.number == parent.l1_origin.number + 1;
 
         // If the sequencer window did not expire,
@@ -190,8 +206,8 @@ where
         }
 
         info!(
-            "Generating empty batches for epoch: {} | parent: {}",
-            epoch.number, parent.l1_origin.number
+            "Generating empty batches for epoch: {} | parent: {} | origin: {}",
+            epoch.number, parent.l1_origin.number, origin.number
         );
 
         // The next L1 block is needed to proceed towards the next epoch.
@@ -204,6 +220,8 @@ where
         // Fill with empty L2 blocks of the same epoch until we meet the time of the next L1 origin,
         // to preserve that L2 time >= L1 time. If this is the first block of the epoch, always
         // generate a batch to ensure that we at least have one batch per epoch.
+        // Empty batches past the max sequencer drift are only valid while the origin cannot
+        // advance, which is exactly when the next L1 origin is still ahead of the L2 time.
         if next_timestamp < next_epoch.timestamp || first_of_epoch {
             info!("Generating empty batch for epoch: {}", epoch.number);
             return Ok(Batch::Single(SingleBatch {
@@ -232,12 +250,15 @@ where
             panic!("Cannot add batch without an origin");
         }
         let origin = self.origin.ok_or_else(|| anyhow!("cannot add batch with missing origin"))?;
-        let data = BatchWithInclusionBlock { inclusion_block: origin, batch };
-        // If we drop the batch, validation logs the drop reason with WARN level.
-        if data.check_batch(&self.cfg, &self.l1_blocks, parent, &mut self.fetcher).await.is_drop() {
+        let validity =
+            check_batch(&self.cfg, &self.l1_blocks, parent, &batch, &origin, &mut self.fetcher)
+                .await;
+        if let Some(reason) = validity.drop_reason() {
+            // Validation logs the details of the violated rule with WARN level.
+            debug!("Dropping batch on insertion ({reason}): {:?}", batch);
             return Ok(());
         }
-        self.batches.push(data);
+        self.batches.push(BatchWithInclusionBlock { inclusion_block: origin, batch });
         Ok(())
     }
 }
@@ -409,20 +430,64 @@ where
     P: BatchQueueProvider + PreviousStage + Send + Debug,
     BF: L2ChainProvider + Send + Debug,
 {
-    async fn reset(&mut self, base: BlockInfo, system_config: &SystemConfig) -> StageResult<()> {
-        self.prev.reset(base, system_config).await?;
+    async fn reset(
+        &mut self,
+        base: BlockInfo,
+        l2_safe_head: L2BlockInfo,
+        system_config: &SystemConfig,
+    ) -> StageResult<()> {
+        self.prev.reset(base, l2_safe_head, system_config).await?;
         // Copy over the Origin from the next stage.
         // It is set in the engine queue (two stages away)
         // such that the L2 Safe Head origin is the progress.
         self.origin = Some(base);
         self.batches.clear();
-        // Include the new origin as an origin to build on.
-        // This is only for the initialization case.
-        // During normal resets we will later throw out this block.
+        // Include the new origin as an origin to build on, unless it is behind the epoch of the
+        // L2 safe head. In that case, the epoch is pushed once the origin catches up with it.
         self.l1_blocks.clear();
-        self.l1_blocks.push(base);
+        if base.number >= l2_safe_head.l1_origin.number {
+            self.l1_blocks.push(base);
+        }
         self.next_spans.clear();
-        Err(StageError::Eof)
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P, BF> FlushableStage for BatchQueue<P, BF>
+where
+    P: BatchQueueProvider + PreviousStage + FlushableStage + Send + Debug,
+    BF: L2ChainProvider + Send + Debug,
+{
+    async fn flush_channel(&mut self) -> StageResult<()> {
+        self.prev.flush_channel().await?;
+        self.batches.clear();
+        self.next_spans.clear();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P, BF> CheckpointableStage for BatchQueue<P, BF>
+where
+    P: BatchQueueProvider + PreviousStage + CheckpointableStage + Send + Debug,
+    BF: L2ChainProvider + Send + Debug,
+{
+    fn checkpoint(&self, checkpoint: &mut PipelineCheckpoint) {
+        self.prev.checkpoint(checkpoint);
+        checkpoint.batch_origin = self.origin;
+        checkpoint.l1_blocks = self.l1_blocks.clone();
+        checkpoint.batches = self.batches.clone();
+        checkpoint.next_spans = self.next_spans.clone();
+    }
+
+    async fn restore(&mut self, checkpoint: &PipelineCheckpoint) -> StageResult<()> {
+        self.prev.restore(checkpoint).await?;
+        self.origin = checkpoint.batch_origin;
+        self.l1_blocks = checkpoint.l1_blocks.clone();
+        self.batches = checkpoint.batches.clone();
+        self.next_spans = checkpoint.next_spans.clone();
+        Ok(())
     }
 }
 
@@ -596,8 +661,8 @@ mod tests {
             block_info: BlockInfo {
                 number: 7,
                 timestamp: 1639845745,
-                parent_hash: parent_check,
-                hash: origin_check,
+                parent_hash: Default::default(),
+                hash: parent_check,
             },
             ..Default::default()
         };
@@ -647,6 +712,27 @@ mod tests {
         assert_eq!(res, StageError::NotEnoughData);
     }
 
+    #[tokio::test]
+    async fn test_reset_seeds_epoch_of_safe_head() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mock = MockBatchQueueProvider::new(vec![]);
+        let mut bq = BatchQueue::new(cfg, mock, TestL2ChainProvider::default());
+        let safe_head = L2BlockInfo {
+            l1_origin: BlockID { number: 10, hash: l1_block(10).hash },
+            ..Default::default()
+        };
+
+        // The origin of the safe head is the epoch to build on.
+        bq.reset(l1_block(10), safe_head, &SystemConfig::default()).await.unwrap();
+        assert_eq!(bq.origin, Some(l1_block(10)));
+        assert_eq!(bq.l1_blocks, vec![l1_block(10)]);
+
+        // An origin behind the safe head is not an epoch of the next batches.
+        bq.reset(l1_block(7), safe_head, &SystemConfig::default()).await.unwrap();
+        assert_eq!(bq.origin, Some(l1_block(7)));
+        assert!(bq.l1_blocks.is_empty());
+    }
+
     #[tokio::test]
     async fn test_batch_queue_empty_bytes() {
         let data = vec![Ok(Batch::Single(SingleBatch::default()))];
@@ -658,4 +744,133 @@ mod tests {
         let batch = bq.next_batch(parent).await.unwrap();
         assert_eq!(batch, SingleBatch::default());
     }
+
+    /// Returns the L1 block with the given number, 12 seconds apart from its neighbours.
+    fn l1_block(number: u64) -> BlockInfo {
+        BlockInfo {
+            number,
+            hash: B256::repeat_byte(number as u8),
+            parent_hash: B256::repeat_byte(number as u8 - 1),
+            timestamp: 120 + (number - 10) * 12,
+        }
+    }
+
+    /// Returns a [BatchQueue] without batches, that buffered the given L1 blocks and whose
+    /// origin is the last of them.
+    fn expired_batch_queue(
+        l1_blocks: core::ops::RangeInclusive<u64>,
+    ) -> BatchQueue<MockBatchQueueProvider, TestL2ChainProvider> {
+        let cfg = Arc::new(RollupConfig {
+            block_time: 2,
+            seq_window_size: 4,
+            max_sequencer_drift: 6,
+            ..Default::default()
+        });
+        let mut mock = MockBatchQueueProvider::new(vec![]);
+        mock.origin = Some(l1_block(*l1_blocks.end()));
+        let mut bq = BatchQueue::new(cfg, mock, TestL2ChainProvider::default());
+        bq.origin = bq.prev.origin;
+        bq.l1_blocks = l1_blocks.map(l1_block).collect();
+        bq
+    }
+
+    /// Returns the L2 block built from the given batch.
+    fn child(parent: L2BlockInfo, batch: &SingleBatch) -> L2BlockInfo {
+        L2BlockInfo {
+            block_info: BlockInfo {
+                number: parent.block_info.number + 1,
+                hash: B256::with_last_byte(batch.timestamp as u8),
+                parent_hash: parent.block_info.hash,
+                timestamp: batch.timestamp,
+            },
+            l1_origin: BlockID { number: batch.epoch_num, hash: batch.epoch_hash },
+            ..Default::default()
+        }
+    }
+
+    fn empty_batch(parent: L2BlockInfo, epoch: u64) -> SingleBatch {
+        SingleBatch {
+            parent_hash: parent.block_info.hash,
+            epoch_num: epoch,
+            epoch_hash: l1_block(epoch).hash,
+            timestamp: parent.block_info.timestamp + 2,
+            transactions: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_next_batch_window_expires_mid_epoch() {
+        // Two L2 blocks of epoch 10 are safe when its sequencing window expires at origin 14.
+        let mut bq = expired_batch_queue(10..=14);
+        let mut parent = L2BlockInfo {
+            block_info: BlockInfo {
+                number: 2,
+                hash: B256::with_last_byte(124),
+                timestamp: 124,
+                ..Default::default()
+            },
+            l1_origin: l1_block(10).id(),
+            ..Default::default()
+        };
+
+        // The rest of epoch 10 is filled up to the timestamp of epoch 11, past the sequencer
+        // drift, since epoch 11 cannot be adopted earlier.
+        for _ in 0..3 {
+            let batch = bq.next_batch(parent).await.unwrap();
+            assert_eq!(batch, empty_batch(parent, 10));
+            parent = child(parent, &batch);
+        }
+        assert_eq!(parent.block_info.timestamp, 130);
+
+        // Epoch 10 is complete, and the window of epoch 11 has not expired yet.
+        assert_eq!(bq.next_batch(parent).await, Err(StageError::Eof));
+        assert_eq!(bq.l1_blocks[0], l1_block(11));
+        assert_eq!(bq.next_batch(parent).await, Err(StageError::Eof));
+
+        // Once it does, the first block of epoch 11 is generated.
+        bq.prev.origin = Some(l1_block(15));
+        let batch = bq.next_batch(parent).await.unwrap();
+        assert_eq!(batch, empty_batch(parent, 11));
+        assert_eq!(batch.timestamp, l1_block(11).timestamp);
+    }
+
+    #[tokio::test]
+    async fn test_next_batch_consecutive_empty_epochs() {
+        // The windows of epochs 10 to 12 are expired at origin 16, and epoch 13 is still open.
+        let mut bq = expired_batch_queue(10..=16);
+        let genesis = L2BlockInfo {
+            block_info: BlockInfo {
+                hash: B256::with_last_byte(120),
+                timestamp: 120,
+                ..Default::default()
+            },
+            l1_origin: l1_block(10).id(),
+            ..Default::default()
+        };
+
+        let mut parent = genesis;
+        let mut batches = Vec::new();
+        for _ in 0..32 {
+            match bq.next_batch(parent).await {
+                Ok(batch) => {
+                    parent = child(parent, &batch);
+                    batches.push(batch);
+                }
+                Err(e) => assert_eq!(e, StageError::Eof),
+            }
+        }
+
+        let mut expected = Vec::new();
+        let mut parent = genesis;
+        for epoch in 10..=12 {
+            while parent.block_info.timestamp + 2 < l1_block(epoch + 1).timestamp {
+                let batch = empty_batch(parent, epoch);
+                parent = child(parent, &batch);
+                expected.push(batch);
+            }
+        }
+        assert_eq!(batches, expected);
+        assert_eq!(batches.len(), 17);
+        assert_eq!(bq.l1_blocks[0], l1_block(13));
+    }
 }
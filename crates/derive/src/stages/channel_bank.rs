@@ -1,10 +1,16 @@
 //! This module contains the `ChannelBank` struct.
 
 use crate::{
-    params::{ChannelID, MAX_CHANNEL_BANK_SIZE},
+    params::{ChannelID, MAX_CHANNEL_BANK_SIZE, MAX_CHANNEL_BANK_SIZE_FJORD},
     stages::ChannelReaderProvider,
-    traits::{OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
-    types::{BlockInfo, Channel, Frame, RollupConfig, StageError, StageResult, SystemConfig},
+    traits::{
+        CheckpointableStage, FlushableStage, OriginAdvancer, OriginProvider, PreviousStage,
+        ResettableStage,
+    },
+    types::{
+        BlockInfo, Channel, Frame, L2BlockInfo, PipelineCheckpoint, RollupConfig, StageError,
+        StageResult, SystemConfig,
+    },
 };
 use alloc::{boxed::Box, collections::VecDeque, sync::Arc};
 use alloy_primitives::Bytes;
@@ -63,13 +69,51 @@ where
         self.channels.iter().fold(0, |acc, (_, c)| acc + c.size())
     }
 
-    /// Prunes the Channel bank, until it is below [MAX_CHANNEL_BANK_SIZE].
-    /// Prunes from the high-priority channel since it failed to be read.
+    /// Returns the maximum size of the channel bank at the given L1 origin. The
+    /// [RollupConfig::max_channel_bank_size] override takes precedence over the protocol limit,
+    /// which is raised by the Fjord hardfork.
+    pub fn max_size(&self, origin: &BlockInfo) -> usize {
+        if let Some(max_size) = self.cfg.max_channel_bank_size {
+            return max_size as usize;
+        }
+        if self.cfg.is_fjord_active(origin.timestamp) {
+            MAX_CHANNEL_BANK_SIZE_FJORD
+        } else {
+            MAX_CHANNEL_BANK_SIZE
+        }
+    }
+
+    /// Returns whether the channel was opened more than [RollupConfig::channel_timeout] L1 blocks
+    /// before the given L1 origin.
+    fn is_timed_out(&self, channel: &Channel, origin: &BlockInfo) -> bool {
+        channel.open_block_number() + self.cfg.channel_timeout < origin.number
+    }
+
+    /// Prunes the Channel bank. Timed out channels at the front of the queue are dropped first,
+    /// then channels are evicted oldest first until the bank is no larger than
+    /// [ChannelBank::max_size].
     pub fn prune(&mut self) -> StageResult<()> {
+        let origin = *self.origin().ok_or(StageError::MissingOrigin)?;
+
+        while let Some(&id) = self.channel_queue.front() {
+            let channel = self.channels.get(&id).ok_or(StageError::ChannelNotFound)?;
+            if !self.is_timed_out(channel, &origin) {
+                break;
+            }
+            debug!("Dropping channel {:?}: timed out", id);
+            self.channels.remove(&id);
+            self.channel_queue.pop_front();
+        }
+
+        let max_size = self.max_size(&origin);
         let mut total_size = self.size();
-        while total_size > MAX_CHANNEL_BANK_SIZE {
+        while total_size > max_size {
             let id = self.channel_queue.pop_front().ok_or(StageError::NoChannelsAvailable)?;
             let channel = self.channels.remove(&id).ok_or(StageError::ChannelNotFound)?;
+            debug!(
+                "Dropping channel {:?}: channel bank size {} exceeds {}",
+                id, total_size, max_size
+            );
             total_size -= channel.size();
         }
         Ok(())
@@ -117,7 +161,7 @@ where
         let first = self.channel_queue[0];
         let channel = self.channels.get(&first).ok_or(StageError::ChannelNotFound)?;
         let origin = self.origin().ok_or(StageError::MissingOrigin)?;
-        if channel.open_block_number() + self.cfg.channel_timeout < origin.number {
+        if self.is_timed_out(channel, origin) {
             warn!("Channel {:?} timed out", first);
             self.channels.remove(&first);
             self.channel_queue.pop_front();
@@ -150,8 +194,7 @@ where
         let channel = self.channels.get(&channel_id).ok_or(StageError::ChannelNotFound)?;
         let origin = self.origin().ok_or(StageError::MissingOrigin)?;
 
-        let timed_out = channel.open_block_number() + self.cfg.channel_timeout < origin.number;
-        if timed_out || !channel.is_ready() {
+        if self.is_timed_out(channel, origin) || !channel.is_ready() {
             return Err(StageError::Eof);
         }
 
@@ -222,12 +265,45 @@ where
     async fn reset(
         &mut self,
         block_info: BlockInfo,
+        l2_safe_head: L2BlockInfo,
         system_config: &SystemConfig,
     ) -> StageResult<()> {
-        self.prev.reset(block_info, system_config).await?;
+        self.prev.reset(block_info, l2_safe_head, system_config).await?;
         self.channels.clear();
         self.channel_queue = VecDeque::with_capacity(10);
-        Err(StageError::Eof)
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P> FlushableStage for ChannelBank<P>
+where
+    P: ChannelBankProvider + PreviousStage + FlushableStage + Send + Debug,
+{
+    async fn flush_channel(&mut self) -> StageResult<()> {
+        self.prev.flush_channel().await?;
+        self.channels.clear();
+        self.channel_queue = VecDeque::with_capacity(10);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P> CheckpointableStage for ChannelBank<P>
+where
+    P: ChannelBankProvider + PreviousStage + CheckpointableStage + Send + Debug,
+{
+    fn checkpoint(&self, checkpoint: &mut PipelineCheckpoint) {
+        self.prev.checkpoint(checkpoint);
+        checkpoint.channels = self.channels.values().cloned().collect();
+        checkpoint.channel_queue = self.channel_queue.iter().copied().collect();
+    }
+
+    async fn restore(&mut self, checkpoint: &PipelineCheckpoint) -> StageResult<()> {
+        self.prev.restore(checkpoint).await?;
+        self.channels = checkpoint.channels.iter().map(|c| (c.id(), c.clone())).collect();
+        self.channel_queue = checkpoint.channel_queue.iter().copied().collect();
+        Ok(())
     }
 }
 
@@ -302,6 +378,71 @@ mod tests {
         assert_eq!(channel_bank.size(), current_size);
     }
 
+    fn new_channel_frame(id: u8) -> Frame {
+        Frame { id: [id; 16], number: 0, data: vec![0xDD; 50], is_last: false }
+    }
+
+    #[test]
+    fn test_prune_evicts_oldest_channels() {
+        let frame_size = new_channel_frame(0).size();
+        let mock = MockChannelBankProvider::new(vec![]);
+        let cfg = Arc::new(RollupConfig {
+            max_channel_bank_size: Some(3 * frame_size as u64),
+            ..Default::default()
+        });
+        let mut channel_bank = ChannelBank::new(cfg, mock);
+        for id in 1..=5 {
+            channel_bank.ingest_frame(new_channel_frame(id)).unwrap();
+            assert!(channel_bank.size() <= 3 * frame_size);
+        }
+        assert_eq!(channel_bank.channel_queue, vec![[3; 16], [4; 16], [5; 16]]);
+        assert_eq!(channel_bank.channels.len(), 3);
+        assert!(!channel_bank.channels.contains_key(&[1; 16]));
+        assert!(!channel_bank.channels.contains_key(&[2; 16]));
+    }
+
+    #[test]
+    fn test_prune_drops_timed_out_channels() {
+        let mock = MockChannelBankProvider::new(vec![]);
+        let cfg = Arc::new(RollupConfig { channel_timeout: 10, ..Default::default() });
+        let mut channel_bank = ChannelBank::new(cfg, mock);
+        channel_bank.ingest_frame(new_channel_frame(1)).unwrap();
+        channel_bank.prev.block_info = Some(BlockInfo { number: 5, ..Default::default() });
+        channel_bank.ingest_frame(new_channel_frame(2)).unwrap();
+        assert_eq!(channel_bank.channel_queue, vec![[1; 16], [2; 16]]);
+
+        // Channel 1 is timed out from block 11, channel 2 from block 16.
+        channel_bank.prev.block_info = Some(BlockInfo { number: 12, ..Default::default() });
+        channel_bank.ingest_frame(new_channel_frame(3)).unwrap();
+        assert_eq!(channel_bank.channel_queue, vec![[2; 16], [3; 16]]);
+        assert!(!channel_bank.channels.contains_key(&[1; 16]));
+    }
+
+    #[test]
+    fn test_max_size_fork_gated() {
+        let mock = || MockChannelBankProvider::new(vec![]);
+        let origin = BlockInfo { timestamp: 10, ..Default::default() };
+
+        let channel_bank = ChannelBank::new(Arc::new(RollupConfig::default()), mock());
+        assert_eq!(channel_bank.max_size(&origin), MAX_CHANNEL_BANK_SIZE);
+
+        let cfg = Arc::new(RollupConfig { fjord_time: Some(10), ..Default::default() });
+        let channel_bank = ChannelBank::new(cfg, mock());
+        assert_eq!(channel_bank.max_size(&origin), MAX_CHANNEL_BANK_SIZE_FJORD);
+        assert_eq!(
+            channel_bank.max_size(&BlockInfo { timestamp: 9, ..Default::default() }),
+            MAX_CHANNEL_BANK_SIZE
+        );
+
+        let cfg = Arc::new(RollupConfig {
+            fjord_time: Some(10),
+            max_channel_bank_size: Some(1_000),
+            ..Default::default()
+        });
+        let channel_bank = ChannelBank::new(cfg, mock());
+        assert_eq!(channel_bank.max_size(&origin), 1_000);
+    }
+
     #[tokio::test]
     async fn test_read_empty_channel_bank() {
         let frames = new_test_frames(1);
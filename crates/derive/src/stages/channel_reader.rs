@@ -1,17 +1,27 @@
 //! This module contains the `ChannelReader` struct.
 
 use crate::{
+    params::{
+        CHANNEL_VERSION_BROTLI, MAX_RLP_BYTES_PER_CHANNEL, MAX_RLP_BYTES_PER_CHANNEL_FJORD,
+        ZLIB_DEFLATE_COMPRESSION_METHOD, ZLIB_RESERVED_COMPRESSION_METHOD,
+    },
     stages::BatchQueueProvider,
-    traits::{OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
-    types::{Batch, BlockInfo, RollupConfig, StageError, StageResult, SystemConfig},
+    traits::{
+        CheckpointableStage, FlushableStage, OriginAdvancer, OriginProvider, PreviousStage,
+        ResettableStage,
+    },
+    types::{
+        decompress_brotli, Batch, BlockInfo, L2BlockInfo, PipelineCheckpoint, RollupConfig,
+        StageError, StageResult, SystemConfig,
+    },
 };
 
 use alloc::{boxed::Box, sync::Arc, vec::Vec};
 use alloy_primitives::Bytes;
 use async_trait::async_trait;
 use core::fmt::Debug;
-use miniz_oxide::inflate::decompress_to_vec_zlib;
-use tracing::error;
+use miniz_oxide::inflate::{decompress_to_vec_zlib_with_limit, TINFLStatus};
+use tracing::{error, warn};
 
 /// The [ChannelReader] provider trait.
 #[async_trait]
@@ -50,7 +60,9 @@ where
     async fn set_batch_reader(&mut self) -> StageResult<()> {
         if self.next_batch.is_none() {
             let channel = self.prev.next_data().await?.ok_or(StageError::NoChannel)?;
-            self.next_batch = Some(BatchReader::from(&channel[..]));
+            let origin = self.prev.origin().ok_or(StageError::MissingOrigin)?;
+            let fjord_active = self.cfg.is_fjord_active(origin.timestamp);
+            self.next_batch = Some(BatchReader::new(&channel[..], fjord_active));
         }
         Ok(())
     }
@@ -113,8 +125,13 @@ impl<P> ResettableStage for ChannelReader<P>
 where
     P: ChannelReaderProvider + PreviousStage + Debug + Send,
 {
-    async fn reset(&mut self, base: BlockInfo, cfg: &SystemConfig) -> StageResult<()> {
-        self.prev.reset(base, cfg).await?;
+    async fn reset(
+        &mut self,
+        base: BlockInfo,
+        l2_safe_head: L2BlockInfo,
+        cfg: &SystemConfig,
+    ) -> StageResult<()> {
+        self.prev.reset(base, l2_safe_head, cfg).await?;
         self.next_channel();
         Ok(())
     }
@@ -133,7 +150,8 @@ where
 /// The L1Inclusion block is also provided at creation time.
 /// Warning: the batch reader can read every batch-type.
 /// The caller of the batch-reader should filter the results.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub(crate) struct BatchReader {
     /// The raw data to decode.
     data: Option<Vec<u8>>,
@@ -141,15 +159,61 @@ pub(crate) struct BatchReader {
     decompressed: Vec<u8>,
     /// The current cursor in the `decompressed` data.
     cursor: usize,
+    /// Whether brotli compressed channels are accepted, i.e. the Fjord hardfork is active.
+    brotli_enabled: bool,
+    /// The maximum number of bytes that will be read from the decompressed channel.
+    max_rlp_bytes_per_channel: usize,
 }
 
 impl BatchReader {
+    /// Creates a new [BatchReader] from the given channel data, respecting the
+    /// compression formats and limits of the Fjord hardfork if it is active.
+    pub(crate) fn new<T: Into<Vec<u8>>>(data: T, fjord_active: bool) -> Self {
+        let max_rlp_bytes_per_channel =
+            if fjord_active { MAX_RLP_BYTES_PER_CHANNEL_FJORD } else { MAX_RLP_BYTES_PER_CHANNEL };
+        Self {
+            data: Some(data.into()),
+            decompressed: Vec::new(),
+            cursor: 0,
+            brotli_enabled: fjord_active,
+            max_rlp_bytes_per_channel: max_rlp_bytes_per_channel as usize,
+        }
+    }
+
+    /// Decompresses the raw channel data, dispatching on the compression type.
+    /// Returns [None] if the channel is invalid and should be dropped.
+    fn decompress(&self, data: &[u8]) -> Option<Vec<u8>> {
+        let compression_type = *data.first()?;
+        let method = compression_type & 0x0F;
+        if method == ZLIB_DEFLATE_COMPRESSION_METHOD || method == ZLIB_RESERVED_COMPRESSION_METHOD {
+            match decompress_to_vec_zlib_with_limit(data, self.max_rlp_bytes_per_channel) {
+                Ok(d) => Some(d),
+                // Data past the limit is dropped, the batches within the limit are still read.
+                Err(e) if e.status == TINFLStatus::HasMoreOutput => Some(e.output),
+                Err(e) => {
+                    warn!("Failed to decompress zlib channel: {:?}", e.status);
+                    None
+                }
+            }
+        } else if compression_type == CHANNEL_VERSION_BROTLI {
+            if !self.brotli_enabled {
+                warn!("Dropping brotli compressed channel before the Fjord hardfork");
+                return None;
+            }
+            decompress_brotli(&data[1..], self.max_rlp_bytes_per_channel)
+                .map_err(|e| warn!("Failed to decompress brotli channel: {e}"))
+                .ok()
+        } else {
+            warn!("Dropping channel with unknown compression type: {compression_type}");
+            None
+        }
+    }
+
     /// Pulls out the next batch from the reader.
     pub(crate) fn next_batch(&mut self, cfg: &RollupConfig) -> Option<Batch> {
         // If the data is not already decompressed, decompress it.
         if let Some(data) = self.data.take() {
-            let decompressed_data = decompress_to_vec_zlib(&data).ok()?;
-            self.decompressed = decompressed_data;
+            self.decompressed = self.decompress(&data)?;
         }
 
         // Decompress and RLP decode the batch data, before finally decoding the batch itself.
@@ -165,7 +229,36 @@ impl BatchReader {
 
 impl<T: Into<Vec<u8>>> From<T> for BatchReader {
     fn from(data: T) -> Self {
-        Self { data: Some(data.into()), decompressed: Vec::new(), cursor: 0 }
+        Self::new(data, false)
+    }
+}
+
+#[async_trait]
+impl<P> FlushableStage for ChannelReader<P>
+where
+    P: ChannelReaderProvider + PreviousStage + FlushableStage + Send + Debug,
+{
+    async fn flush_channel(&mut self) -> StageResult<()> {
+        self.prev.flush_channel().await?;
+        self.next_channel();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P> CheckpointableStage for ChannelReader<P>
+where
+    P: ChannelReaderProvider + PreviousStage + CheckpointableStage + Send + Debug,
+{
+    fn checkpoint(&self, checkpoint: &mut PipelineCheckpoint) {
+        self.prev.checkpoint(checkpoint);
+        checkpoint.batch_reader = self.next_batch.clone();
+    }
+
+    async fn restore(&mut self, checkpoint: &PipelineCheckpoint) -> StageResult<()> {
+        self.prev.restore(checkpoint).await?;
+        self.next_batch = checkpoint.batch_reader.clone();
+        Ok(())
     }
 }
 
@@ -176,11 +269,21 @@ mod test {
     use alloc::vec;
     use miniz_oxide::deflate::compress_to_vec_zlib;
 
-    fn new_compressed_batch_data() -> Bytes {
+    fn new_typed_batch_data() -> Vec<u8> {
         let raw_data = include_bytes!("../../testdata/raw_batch.hex");
         let mut typed_data = vec![BatchType::Span as u8];
         typed_data.extend_from_slice(raw_data.as_slice());
-        compress_to_vec_zlib(typed_data.as_slice(), 5).into()
+        typed_data
+    }
+
+    fn new_compressed_batch_data() -> Bytes {
+        compress_to_vec_zlib(new_typed_batch_data().as_slice(), 5).into()
+    }
+
+    /// Returns a Fjord channel holding the span batch of [new_typed_batch_data], compressed by the
+    /// reference brotli encoder as op-batcher does.
+    fn new_brotli_batch_data() -> Bytes {
+        Bytes::from_static(include_bytes!("../../testdata/brotli_channel.bin"))
     }
 
     #[tokio::test]
@@ -229,4 +332,72 @@ mod test {
 
         assert_eq!(reader.cursor, typed_data.len());
     }
+
+    #[tokio::test]
+    async fn test_next_batch_brotli_before_fjord_drops_channel() {
+        let raw = new_brotli_batch_data();
+        let mock = MockChannelReaderProvider::new(vec![Ok(Some(raw))]);
+        let cfg = RollupConfig { fjord_time: Some(1), ..Default::default() };
+        let mut reader = ChannelReader::new(mock, Arc::new(cfg));
+        assert_eq!(reader.next_batch().await, Err(StageError::NotEnoughData));
+        assert!(reader.next_batch.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_next_batch_brotli_after_fjord() {
+        let raw = new_brotli_batch_data();
+        let mock = MockChannelReaderProvider::new(vec![Ok(Some(raw))]);
+        let cfg = RollupConfig { fjord_time: Some(0), ..Default::default() };
+        let mut reader = ChannelReader::new(mock, Arc::new(cfg));
+        let res = reader.next_batch().await.unwrap();
+        assert!(matches!(res, Batch::Span(_)));
+        assert!(reader.next_batch.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_next_batch_zlib_after_fjord() {
+        let raw = new_compressed_batch_data();
+        let mock = MockChannelReaderProvider::new(vec![Ok(Some(raw))]);
+        let cfg = RollupConfig { fjord_time: Some(0), ..Default::default() };
+        let mut reader = ChannelReader::new(mock, Arc::new(cfg));
+        let res = reader.next_batch().await.unwrap();
+        assert!(matches!(res, Batch::Span(_)));
+    }
+
+    #[test]
+    fn test_batch_reader_unknown_compression_type() {
+        let mut data = vec![0x02];
+        data.extend_from_slice(&new_brotli_batch_data()[1..]);
+        let mut reader = BatchReader::new(data, true);
+        assert!(reader.next_batch(&RollupConfig::default()).is_none());
+    }
+
+    #[test]
+    fn test_batch_reader_zlib_exceeds_limit() {
+        let typed_data = new_typed_batch_data();
+        let doubled = [typed_data.as_slice(), typed_data.as_slice()].concat();
+        let compressed = compress_to_vec_zlib(doubled.as_slice(), 5);
+        let mut reader = BatchReader::from(compressed);
+        reader.max_rlp_bytes_per_channel = typed_data.len() + typed_data.len() / 2;
+        let cfg = RollupConfig::default();
+        // The first batch is within the limit, the second is cut off mid-stream.
+        assert!(reader.next_batch(&cfg).is_some());
+        assert!(reader.next_batch(&cfg).is_none());
+        assert_eq!(reader.decompressed.len(), reader.max_rlp_bytes_per_channel);
+    }
+
+    #[test]
+    fn test_batch_reader_brotli_exceeds_limit() {
+        // The channel holds copies of the span batch of `raw_batch.hex`, 100_014_869 bytes in
+        // total, compressed by the reference brotli encoder.
+        let typed_data = new_typed_batch_data();
+        let data = include_bytes!("../../testdata/brotli_channel_over_limit.bin");
+        let mut reader = BatchReader::new(data.as_slice(), true);
+        reader.max_rlp_bytes_per_channel = typed_data.len() + typed_data.len() / 2;
+        let cfg = RollupConfig::default();
+        // The first batch is within the limit, the second is cut off mid-stream.
+        assert!(reader.next_batch(&cfg).is_some());
+        assert!(reader.next_batch(&cfg).is_none());
+        assert_eq!(reader.decompressed.len(), reader.max_rlp_bytes_per_channel);
+    }
 }
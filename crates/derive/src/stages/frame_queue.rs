@@ -2,21 +2,26 @@
 
 use crate::{
     stages::ChannelBankProvider,
-    traits::{OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
-    types::{into_frames, BlockInfo, Frame, StageError, StageResult, SystemConfig},
+    traits::{
+        CheckpointableStage, FlushableStage, OriginAdvancer, OriginProvider, PreviousStage,
+        ResettableStage,
+    },
+    types::{
+        BlockInfo, DataSourceItem, Frame, L2BlockInfo, PipelineCheckpoint, StageError, StageResult,
+        SystemConfig,
+    },
 };
 use alloc::{boxed::Box, collections::VecDeque};
-use alloy_primitives::Bytes;
 use anyhow::anyhow;
 use async_trait::async_trait;
 use core::fmt::Debug;
-use tracing::{debug, error};
+use tracing::{debug, error, warn};
 
 /// Provides data frames for the [FrameQueue] stage.
 #[async_trait]
 pub trait FrameQueueProvider {
-    /// An item that can be converted into a byte array.
-    type Item: Into<Bytes>;
+    /// An item that can be converted into a [DataSourceItem].
+    type Item: Into<DataSourceItem>;
 
     /// Retrieves the next data item from the L1 retrieval stage.
     /// If there is data, it pushes it into the next stage.
@@ -69,13 +74,17 @@ where
     async fn next_frame(&mut self) -> StageResult<Frame> {
         if self.queue.is_empty() {
             match self.prev.next_data().await {
-                Ok(data) => {
-                    if let Ok(frames) = into_frames(Ok(data)) {
-                        self.queue.extend(frames);
-                    } else {
-                        // There may be more frames in the queue for the
-                        // pipeline to advance, so don't return an error here.
-                        error!("Failed to parse frames from data.");
+                Ok(item) => {
+                    // Frames are parsed all-or-nothing: if any frame of the transaction is
+                    // malformed, all of its frames are dropped. There may be more frames in the
+                    // queue for the pipeline to advance, so don't return an error here.
+                    let item: DataSourceItem = item.into();
+                    match Frame::parse_frames(&item.data) {
+                        Ok(frames) => self.queue.extend(frames),
+                        Err(e) => warn!(
+                            "Failed to parse frames from {} of tx {}: {}",
+                            item.kind, item.origin_tx, e
+                        ),
                     }
                 }
                 Err(e) => {
@@ -121,11 +130,41 @@ where
     async fn reset(
         &mut self,
         block_info: BlockInfo,
+        l2_safe_head: L2BlockInfo,
         system_config: &SystemConfig,
     ) -> StageResult<()> {
-        self.prev.reset(block_info, system_config).await?;
+        self.prev.reset(block_info, l2_safe_head, system_config).await?;
         self.queue = VecDeque::default();
-        Err(StageError::Eof)
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P> FlushableStage for FrameQueue<P>
+where
+    P: FrameQueueProvider + PreviousStage + FlushableStage + Send + Debug,
+{
+    async fn flush_channel(&mut self) -> StageResult<()> {
+        self.prev.flush_channel().await?;
+        self.queue = VecDeque::default();
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<P> CheckpointableStage for FrameQueue<P>
+where
+    P: FrameQueueProvider + PreviousStage + CheckpointableStage + Send + Debug,
+{
+    fn checkpoint(&self, checkpoint: &mut PipelineCheckpoint) {
+        self.prev.checkpoint(checkpoint);
+        checkpoint.frames = self.queue.iter().cloned().collect();
+    }
+
+    async fn restore(&mut self, checkpoint: &PipelineCheckpoint) -> StageResult<()> {
+        self.prev.restore(checkpoint).await?;
+        self.queue = checkpoint.frames.iter().cloned().collect();
+        Ok(())
     }
 }
 
@@ -134,6 +173,7 @@ pub(crate) mod tests {
     use super::*;
     use crate::{stages::test_utils::MockFrameQueueProvider, DERIVATION_VERSION_0};
     use alloc::{vec, vec::Vec};
+    use alloy_primitives::Bytes;
 
     pub(crate) fn new_test_frames(count: usize) -> Vec<Frame> {
         (0..count)
@@ -192,6 +232,19 @@ pub(crate) mod tests {
         assert_eq!(err, StageError::NotEnoughData);
     }
 
+    #[tokio::test]
+    async fn test_frame_queue_trailing_garbage() {
+        let mut data = new_encoded_test_frames(2).to_vec();
+        data.extend_from_slice(&[0xBE, 0xEF]);
+        let mock = MockFrameQueueProvider { data: vec![Ok(Bytes::from(data))] };
+        let mut frame_queue = FrameQueue::new(mock);
+        // The well-formed frames before the garbage are dropped along with it.
+        let err = frame_queue.next_frame().await.unwrap_err();
+        assert_eq!(err, StageError::NotEnoughData);
+        let err = frame_queue.next_frame().await.unwrap_err();
+        assert_eq!(err, StageError::Eof);
+    }
+
     #[tokio::test]
     async fn test_frame_queue_single_frame() {
         let data = new_encoded_test_frames(1);
@@ -3,10 +3,10 @@
 use crate::{
     stages::FrameQueueProvider,
     traits::{
-        AsyncIterator, DataAvailabilityProvider, OriginAdvancer, OriginProvider, PreviousStage,
-        ResettableStage,
+        AsyncIterator, CheckpointableStage, DataAvailabilityProvider, FlushableStage,
+        OriginAdvancer, OriginProvider, PreviousStage, ResettableStage,
     },
-    types::{BlockInfo, StageError, StageResult, SystemConfig},
+    types::{BlockInfo, L2BlockInfo, PipelineCheckpoint, StageError, StageResult, SystemConfig},
 };
 use alloc::boxed::Box;
 use alloy_primitives::Address;
@@ -48,6 +48,8 @@ where
     pub provider: DAP,
     /// The current data iterator.
     pub(crate) data: Option<DAP::DataIter>,
+    /// The number of items read from the current data iterator.
+    pub(crate) read: usize,
 }
 
 impl<DAP, P> L1Retrieval<DAP, P>
@@ -60,7 +62,7 @@ where
     ///
     /// [L1Traversal]: crate::stages::L1Traversal
     pub fn new(prev: P, provider: DAP) -> Self {
-        Self { prev, provider, data: None }
+        Self { prev, provider, data: None, read: 0 }
     }
 }
 
@@ -91,9 +93,13 @@ where
                 .await? // SAFETY: This question mark bubbles up the Eof error.
                 .ok_or_else(|| anyhow!("No block to retrieve data from"))?;
             self.data = Some(self.provider.open_data(&next, self.prev.batcher_addr()).await?);
+            self.read = 0;
         }
 
         let data = self.data.as_mut().expect("Cannot be None").next().await.ok_or(StageError::Eof);
+        if data.is_ok() {
+            self.read += 1;
+        }
         match data {
             Ok(Ok(data)) => Ok(data),
             Err(StageError::Eof) | Ok(Err(StageError::Eof)) => {
@@ -131,13 +137,60 @@ where
     DAP: DataAvailabilityProvider + Send,
     P: L1RetrievalProvider + PreviousStage + Send,
 {
-    async fn reset(&mut self, base: BlockInfo, cfg: &SystemConfig) -> StageResult<()> {
-        self.prev.reset(base, cfg).await?;
+    async fn reset(
+        &mut self,
+        base: BlockInfo,
+        l2_safe_head: L2BlockInfo,
+        cfg: &SystemConfig,
+    ) -> StageResult<()> {
+        self.prev.reset(base, l2_safe_head, cfg).await?;
         self.data = Some(self.provider.open_data(&base, cfg.batcher_addr).await?);
+        self.read = 0;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<DAP, P> CheckpointableStage for L1Retrieval<DAP, P>
+where
+    DAP: DataAvailabilityProvider + Send,
+    P: L1RetrievalProvider + PreviousStage + CheckpointableStage + Send,
+{
+    fn checkpoint(&self, checkpoint: &mut PipelineCheckpoint) {
+        self.prev.checkpoint(checkpoint);
+        checkpoint.data_read = self.data.as_ref().map(|_| self.read);
+    }
+
+    /// Restores the previous stages, then re-opens the data of the L1 origin if it was open in the
+    /// checkpointed stage, skipping the items that were already read from it.
+    async fn restore(&mut self, checkpoint: &PipelineCheckpoint) -> StageResult<()> {
+        self.prev.restore(checkpoint).await?;
+        self.data = None;
+        self.read = 0;
+        if let Some(read) = checkpoint.data_read {
+            let origin = *self.prev.origin().ok_or(StageError::MissingOrigin)?;
+            let mut data = self.provider.open_data(&origin, self.prev.batcher_addr()).await?;
+            for _ in 0..read {
+                data.next().await;
+            }
+            self.data = Some(data);
+            self.read = read;
+        }
         Ok(())
     }
 }
 
+#[async_trait]
+impl<DAP, P> FlushableStage for L1Retrieval<DAP, P>
+where
+    DAP: DataAvailabilityProvider + Send,
+    P: L1RetrievalProvider + PreviousStage + FlushableStage + Send,
+{
+    async fn flush_channel(&mut self) -> StageResult<()> {
+        self.prev.flush_channel().await
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -165,7 +218,7 @@ mod tests {
         let mut retrieval = L1Retrieval::new(traversal, dap);
         assert_eq!(retrieval.data, None);
         let data = retrieval.next_data().await.unwrap();
-        assert_eq!(data, Bytes::default());
+        assert_eq!(data.data, Bytes::default());
         assert!(retrieval.data.is_some());
         let retrieval_data = retrieval.data.as_ref().unwrap();
         assert_eq!(retrieval_data.open_data_calls.len(), 1);
@@ -177,6 +230,22 @@ mod tests {
         assert!(retrieval.data.is_none());
     }
 
+    #[tokio::test]
+    async fn test_l1_retrieval_reset_reopens_data() {
+        let traversal = new_populated_test_traversal();
+        let dap = TestDAP { results: vec![Ok(Bytes::default())] };
+        let mut retrieval = L1Retrieval::new(traversal, dap);
+        let base = BlockInfo { number: 10, ..Default::default() };
+        let cfg =
+            SystemConfig { batcher_addr: Address::left_padding_from(&[1]), ..Default::default() };
+        retrieval.reset(base, L2BlockInfo::default(), &cfg).await.unwrap();
+        assert_eq!(retrieval.origin(), Some(&base));
+        let retrieval_data = retrieval.data.as_ref().unwrap();
+        assert_eq!(retrieval_data.open_data_calls, vec![(base, cfg.batcher_addr)]);
+        let data = retrieval.next_data().await.unwrap();
+        assert_eq!(data.data, Bytes::default());
+    }
+
     #[tokio::test]
     async fn test_l1_retrieval_existing_data_is_respected() {
         let data = TestIter {
@@ -188,9 +257,10 @@ mod tests {
         // (traversal) is called in the retrieval stage.
         let traversal = new_test_traversal(vec![], vec![]);
         let dap = TestDAP { results: vec![] };
-        let mut retrieval = L1Retrieval { prev: traversal, provider: dap, data: Some(data) };
+        let mut retrieval =
+            L1Retrieval { prev: traversal, provider: dap, data: Some(data), read: 0 };
         let data = retrieval.next_data().await.unwrap();
-        assert_eq!(data, Bytes::default());
+        assert_eq!(data.data, Bytes::default());
         assert!(retrieval.data.is_some());
         let retrieval_data = retrieval.data.as_ref().unwrap();
         assert_eq!(retrieval_data.open_data_calls.len(), 1);
@@ -204,7 +274,8 @@ mod tests {
         };
         let traversal = new_populated_test_traversal();
         let dap = TestDAP { results: vec![] };
-        let mut retrieval = L1Retrieval { prev: traversal, provider: dap, data: Some(data) };
+        let mut retrieval =
+            L1Retrieval { prev: traversal, provider: dap, data: Some(data), read: 0 };
         let data = retrieval.next_data().await.unwrap_err();
         assert_eq!(data, StageError::Eof);
         assert!(retrieval.data.is_none());
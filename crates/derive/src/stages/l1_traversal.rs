@@ -2,14 +2,51 @@
 
 use crate::{
     stages::L1RetrievalProvider,
-    traits::{ChainProvider, OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
-    types::{BlockInfo, RollupConfig, StageError, StageResult, SystemConfig},
+    traits::{
+        ChainProvider, CheckpointableStage, FlushableStage, OriginAdvancer, OriginProvider,
+        PreviousStage, ResettableStage,
+    },
+    types::{
+        BlockInfo, DriverConfig, L2BlockInfo, PipelineCheckpoint, RollupConfig, StageError,
+        StageResult, SystemConfig,
+    },
 };
 use alloc::{boxed::Box, sync::Arc};
 use alloy_primitives::Address;
 use async_trait::async_trait;
+use spin::Mutex;
 use tracing::warn;
 
+/// The latest L1 head and finalized L1 block known to the driver, which bound the origin of the
+/// [L1Traversal] stage. Clones share the same blocks, so that the driver can keep updating them
+/// after the stage has been moved into the pipeline.
+#[derive(Debug, Clone, Default)]
+pub struct L1Tip {
+    inner: Arc<Mutex<(Option<BlockInfo>, Option<BlockInfo>)>>,
+}
+
+impl L1Tip {
+    /// Updates the L1 head.
+    pub fn update_l1_head(&self, block: BlockInfo) {
+        self.inner.lock().0 = Some(block);
+    }
+
+    /// Updates the finalized L1 block.
+    pub fn update_finalized(&self, block: BlockInfo) {
+        self.inner.lock().1 = Some(block);
+    }
+
+    /// Returns the latest known L1 head.
+    pub fn l1_head(&self) -> Option<BlockInfo> {
+        self.inner.lock().0
+    }
+
+    /// Returns the latest known finalized L1 block.
+    pub fn finalized(&self) -> Option<BlockInfo> {
+        self.inner.lock().1
+    }
+}
+
 /// The [L1Traversal] stage of the derivation pipeline.
 ///
 /// This stage sits at the bottom of the pipeline, holding a handle to the data source
@@ -17,6 +54,10 @@ use tracing::warn;
 /// which are used to traverse the L1 chain. When the [L1Traversal] stage is advanced,
 /// it fetches the next L1 [BlockInfo] from the data source and updates the [SystemConfig]
 /// with the receipts from the block.
+///
+/// With a [DriverConfig] that bounds the L1 origin, the stage does not advance past the
+/// confirmation depth below the L1 head, or past the finalized L1 block, as supplied by the
+/// driver through an [L1Tip].
 #[derive(Debug, Clone)]
 pub struct L1Traversal<Provider: ChainProvider> {
     /// The current block in the traversal stage.
@@ -29,6 +70,10 @@ pub struct L1Traversal<Provider: ChainProvider> {
     pub system_config: SystemConfig,
     /// A reference to the rollup config.
     pub rollup_config: Arc<RollupConfig>,
+    /// Bounds how far the stage follows the L1 chain.
+    driver_config: DriverConfig,
+    /// The L1 blocks bounding the origin.
+    tip: L1Tip,
 }
 
 #[async_trait]
@@ -56,13 +101,55 @@ impl<F: ChainProvider> L1Traversal<F> {
             done: false,
             system_config: SystemConfig::default(),
             rollup_config: cfg,
+            driver_config: DriverConfig::default(),
+            tip: L1Tip::default(),
         }
     }
 
+    /// Creates a new [L1Traversal] instance that bounds its origin with the given [DriverConfig],
+    /// using the blocks of the given [L1Tip].
+    pub fn with_driver_config(
+        data_source: F,
+        cfg: Arc<RollupConfig>,
+        driver_config: DriverConfig,
+        tip: L1Tip,
+    ) -> Self {
+        Self { driver_config, tip, ..Self::new(data_source, cfg) }
+    }
+
     /// Retrieves a reference to the inner data source of the [L1Traversal] stage.
     pub fn data_source(&self) -> &F {
         &self.data_source
     }
+
+    /// Returns the [L1Tip] bounding the origin of the stage.
+    pub fn tip(&self) -> &L1Tip {
+        &self.tip
+    }
+
+    /// Updates the L1 head, which bounds the origin when a confirmation depth is configured.
+    pub fn update_l1_head(&mut self, block: BlockInfo) {
+        self.tip.update_l1_head(block);
+    }
+
+    /// Updates the finalized L1 block, which bounds the origin when
+    /// [DriverConfig::use_finalized] is set.
+    pub fn update_finalized(&mut self, block: BlockInfo) {
+        self.tip.update_finalized(block);
+    }
+
+    /// Returns the highest L1 block number the stage may advance to, or [None] if the origin is
+    /// not bounded. Until the driver supplies the bounding block, the origin cannot advance.
+    pub fn max_origin_number(&self) -> Option<u64> {
+        if self.driver_config.use_finalized {
+            return Some(self.tip.finalized().map_or(0, |b| b.number));
+        }
+        if self.driver_config.l1_confirmation_depth > 0 {
+            let depth = self.driver_config.l1_confirmation_depth;
+            return Some(self.tip.l1_head().map_or(0, |b| b.number.saturating_sub(depth)));
+        }
+        None
+    }
 }
 
 #[async_trait]
@@ -80,6 +167,13 @@ impl<F: ChainProvider + Send> OriginAdvancer for L1Traversal<F> {
                 return Err(StageError::Eof);
             }
         };
+
+        // Stay behind the confirmation depth or the finalized block, if configured. Running
+        // out of confirmed blocks is the same as running out of L1 blocks.
+        if self.max_origin_number().is_some_and(|max| block.number + 1 > max) {
+            return Err(StageError::Eof);
+        }
+
         let next_l1_origin = match self.data_source.block_info_by_number(block.number + 1).await {
             Ok(block) => block,
             Err(e) => return Err(StageError::BlockInfoFetch(e)),
@@ -124,11 +218,40 @@ impl<F: ChainProvider + Send> PreviousStage for L1Traversal<F> {
 
 #[async_trait]
 impl<F: ChainProvider + Send> ResettableStage for L1Traversal<F> {
-    async fn reset(&mut self, base: BlockInfo, cfg: &SystemConfig) -> StageResult<()> {
+    async fn reset(
+        &mut self,
+        base: BlockInfo,
+        _l2_safe_head: L2BlockInfo,
+        cfg: &SystemConfig,
+    ) -> StageResult<()> {
         self.block = Some(base);
         self.done = false;
         self.system_config = *cfg;
-        Err(StageError::Eof)
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F: ChainProvider + Send> FlushableStage for L1Traversal<F> {
+    async fn flush_channel(&mut self) -> StageResult<()> {
+        // The traversal stage does not buffer any channel data.
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F: ChainProvider + Send> CheckpointableStage for L1Traversal<F> {
+    fn checkpoint(&self, checkpoint: &mut PipelineCheckpoint) {
+        checkpoint.origin = self.block;
+        checkpoint.origin_consumed = self.done;
+        checkpoint.system_config = self.system_config;
+    }
+
+    async fn restore(&mut self, checkpoint: &PipelineCheckpoint) -> StageResult<()> {
+        self.block = checkpoint.origin;
+        self.done = checkpoint.origin_consumed;
+        self.system_config = checkpoint.system_config;
+        Ok(())
     }
 }
 
@@ -139,7 +262,7 @@ pub(crate) mod tests {
         params::{CONFIG_UPDATE_EVENT_VERSION_0, CONFIG_UPDATE_TOPIC},
         traits::test_utils::TestChainProvider,
     };
-    use alloc::vec;
+    use alloc::{vec, vec::Vec};
     use alloy_consensus::Receipt;
     use alloy_primitives::{address, b256, hex, Bytes, Log, LogData, B256};
 
@@ -264,4 +387,115 @@ pub(crate) mod tests {
         let expected = address!("000000000000000000000000000000000000bEEF");
         assert_eq!(traversal.system_config.batcher_addr, expected);
     }
+
+    /// Returns an L1 block of the given fork, building on `parent`.
+    fn fork_block(number: u64, fork: u8, parent: &BlockInfo) -> BlockInfo {
+        let mut hash = B256::with_last_byte(number as u8);
+        hash[0] = fork;
+        BlockInfo { number, hash, parent_hash: parent.hash, timestamp: number * 12 }
+    }
+
+    /// Returns the L1 blocks `from..=to` of the given fork, building on `parent`.
+    fn fork(from: u64, to: u64, fork: u8, parent: &BlockInfo) -> Vec<BlockInfo> {
+        let mut parent = *parent;
+        (from..=to)
+            .map(|n| {
+                parent = fork_block(n, fork, &parent);
+                parent
+            })
+            .collect()
+    }
+
+    /// Replaces the blocks of the provider from the first given block onwards.
+    fn reorg(traversal: &mut L1Traversal<TestChainProvider>, blocks: &[BlockInfo]) {
+        let provider = &mut traversal.data_source;
+        provider.blocks.retain(|(n, _)| *n < blocks[0].number);
+        for block in blocks {
+            provider.insert_block(block.number, *block);
+            provider.insert_receipts(block.hash, vec![]);
+        }
+    }
+
+    /// Returns a traversal at genesis over the blocks `1..=5` of fork 0.
+    async fn bounded_traversal(
+        driver_config: DriverConfig,
+    ) -> (L1Traversal<TestChainProvider>, Vec<BlockInfo>) {
+        let genesis = BlockInfo::default();
+        let mut traversal = L1Traversal::with_driver_config(
+            TestChainProvider::default(),
+            Arc::new(RollupConfig::default()),
+            driver_config,
+            L1Tip::default(),
+        );
+        let blocks = fork(1, 5, 0, &genesis);
+        reorg(&mut traversal, &blocks);
+        traversal.reset(genesis, L2BlockInfo::default(), &SystemConfig::default()).await.unwrap();
+        (traversal, blocks)
+    }
+
+    /// Advances the traversal as far as it goes, returning the consumed L1 blocks.
+    async fn consume(traversal: &mut L1Traversal<TestChainProvider>) -> Vec<BlockInfo> {
+        let mut consumed = Vec::new();
+        while traversal.advance_origin().await.is_ok() {
+            consumed.push(traversal.next_l1_block().await.unwrap().unwrap());
+        }
+        consumed
+    }
+
+    #[tokio::test]
+    async fn test_l1_traversal_confirmation_depth() {
+        let dc = DriverConfig { l1_confirmation_depth: 2, use_finalized: false };
+        let (mut traversal, blocks) = bounded_traversal(dc).await;
+        let tip = traversal.tip().clone();
+
+        // The origin cannot advance until the L1 head is known.
+        assert_eq!(traversal.advance_origin().await, Err(StageError::Eof));
+
+        tip.update_l1_head(blocks[4]);
+        assert_eq!(traversal.max_origin_number(), Some(3));
+        let mut consumed = consume(&mut traversal).await;
+        assert_eq!(consumed, blocks[..3]);
+        assert_eq!(traversal.advance_origin().await, Err(StageError::Eof));
+
+        // Blocks 4 and 5 reorg within the confirmation depth, and were never consumed.
+        let reorged = fork(4, 6, 1, &blocks[2]);
+        reorg(&mut traversal, &reorged);
+        tip.update_l1_head(reorged[2]);
+        consumed.extend(consume(&mut traversal).await);
+
+        assert_eq!(consumed, [&blocks[..3], &reorged[..1]].concat());
+        assert!(!consumed.iter().any(|b| b.hash == blocks[3].hash || b.hash == blocks[4].hash));
+    }
+
+    #[tokio::test]
+    async fn test_l1_traversal_use_finalized() {
+        let dc = DriverConfig { l1_confirmation_depth: 2, use_finalized: true };
+        let (mut traversal, blocks) = bounded_traversal(dc).await;
+
+        // The L1 head does not bound the origin when following the finalized block.
+        traversal.update_l1_head(blocks[4]);
+        assert_eq!(traversal.advance_origin().await, Err(StageError::Eof));
+
+        traversal.update_finalized(blocks[1]);
+        assert_eq!(consume(&mut traversal).await, blocks[..2]);
+        traversal.update_finalized(blocks[3]);
+        assert_eq!(consume(&mut traversal).await, blocks[2..4]);
+    }
+
+    #[tokio::test]
+    async fn test_l1_traversal_reorg_below_confirmation_depth() {
+        let dc = DriverConfig { l1_confirmation_depth: 2, use_finalized: false };
+        let (mut traversal, blocks) = bounded_traversal(dc).await;
+        traversal.update_l1_head(blocks[4]);
+        assert_eq!(consume(&mut traversal).await, blocks[..3]);
+
+        // A reorg deeper than the confirmation depth still fails the parent hash check.
+        let reorged = fork(3, 6, 1, &blocks[1]);
+        reorg(&mut traversal, &reorged);
+        traversal.update_l1_head(reorged[3]);
+        assert_eq!(
+            traversal.advance_origin().await,
+            Err(StageError::ReorgDetected(blocks[2].hash, reorged[0].hash))
+        );
+    }
 }
@@ -14,7 +14,7 @@
 //! 8. (Omitted) Engine Queue
 
 mod l1_traversal;
-pub use l1_traversal::L1Traversal;
+pub use l1_traversal::{L1Tip, L1Traversal};
 
 mod l1_retrieval;
 pub use l1_retrieval::{L1Retrieval, L1RetrievalProvider};
@@ -26,6 +26,7 @@ mod channel_bank;
 pub use channel_bank::{ChannelBank, ChannelBankProvider};
 
 mod channel_reader;
+pub(crate) use channel_reader::BatchReader;
 pub use channel_reader::{ChannelReader, ChannelReaderProvider};
 
 mod batch_queue;
@@ -2,7 +2,7 @@
 
 use crate::{
     stages::attributes_queue::{AttributesBuilder, AttributesProvider},
-    traits::{OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
+    traits::{FlushableStage, OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
     types::{
         BlockID, BlockInfo, BuilderError, L2BlockInfo, L2PayloadAttributes, SingleBatch,
         StageError, StageResult, SystemConfig,
@@ -58,7 +58,19 @@ impl OriginAdvancer for MockAttributesProvider {
 
 #[async_trait]
 impl ResettableStage for MockAttributesProvider {
-    async fn reset(&mut self, _base: BlockInfo, _cfg: &SystemConfig) -> StageResult<()> {
+    async fn reset(
+        &mut self,
+        _base: BlockInfo,
+        _l2_safe_head: L2BlockInfo,
+        _cfg: &SystemConfig,
+    ) -> StageResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FlushableStage for MockAttributesProvider {
+    async fn flush_channel(&mut self) -> StageResult<()> {
         Ok(())
     }
 }
@@ -2,8 +2,8 @@
 
 use crate::{
     stages::batch_queue::BatchQueueProvider,
-    traits::{OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
-    types::{Batch, BlockInfo, StageError, StageResult, SystemConfig},
+    traits::{FlushableStage, OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
+    types::{Batch, BlockInfo, L2BlockInfo, StageError, StageResult, SystemConfig},
 };
 use alloc::{boxed::Box, vec::Vec};
 use async_trait::async_trait;
@@ -46,7 +46,19 @@ impl OriginAdvancer for MockBatchQueueProvider {
 
 #[async_trait]
 impl ResettableStage for MockBatchQueueProvider {
-    async fn reset(&mut self, _base: BlockInfo, _cfg: &SystemConfig) -> StageResult<()> {
+    async fn reset(
+        &mut self,
+        _base: BlockInfo,
+        _l2_safe_head: L2BlockInfo,
+        _cfg: &SystemConfig,
+    ) -> StageResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl FlushableStage for MockBatchQueueProvider {
+    async fn flush_channel(&mut self) -> StageResult<()> {
         Ok(())
     }
 }
@@ -3,7 +3,7 @@
 use crate::{
     stages::ChannelBankProvider,
     traits::{OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
-    types::{BlockInfo, Frame, StageError, StageResult, SystemConfig},
+    types::{BlockInfo, Frame, L2BlockInfo, StageError, StageResult, SystemConfig},
 };
 use alloc::{boxed::Box, vec::Vec};
 use async_trait::async_trait;
@@ -46,7 +46,12 @@ impl ChannelBankProvider for MockChannelBankProvider {
 
 #[async_trait]
 impl ResettableStage for MockChannelBankProvider {
-    async fn reset(&mut self, _base: BlockInfo, _cfg: &SystemConfig) -> StageResult<()> {
+    async fn reset(
+        &mut self,
+        _base: BlockInfo,
+        _l2_safe_head: L2BlockInfo,
+        _cfg: &SystemConfig,
+    ) -> StageResult<()> {
         Ok(())
     }
 }
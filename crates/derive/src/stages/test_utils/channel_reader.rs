@@ -3,7 +3,7 @@
 use crate::{
     stages::ChannelReaderProvider,
     traits::{OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
-    types::{BlockInfo, StageError, StageResult, SystemConfig},
+    types::{BlockInfo, L2BlockInfo, StageError, StageResult, SystemConfig},
 };
 use alloc::{boxed::Box, vec::Vec};
 use alloy_primitives::Bytes;
@@ -47,7 +47,12 @@ impl ChannelReaderProvider for MockChannelReaderProvider {
 
 #[async_trait]
 impl ResettableStage for MockChannelReaderProvider {
-    async fn reset(&mut self, _base: BlockInfo, _cfg: &SystemConfig) -> StageResult<()> {
+    async fn reset(
+        &mut self,
+        _base: BlockInfo,
+        _l2_safe_head: L2BlockInfo,
+        _cfg: &SystemConfig,
+    ) -> StageResult<()> {
         Ok(())
     }
 }
@@ -3,10 +3,12 @@
 use crate::{
     stages::FrameQueueProvider,
     traits::{OriginAdvancer, OriginProvider, PreviousStage, ResettableStage},
-    types::{BlockInfo, StageError, StageResult, SystemConfig},
+    types::{
+        BlockInfo, DataKind, DataSourceItem, L2BlockInfo, StageError, StageResult, SystemConfig,
+    },
 };
 use alloc::{boxed::Box, vec::Vec};
-use alloy_primitives::Bytes;
+use alloy_primitives::{Bytes, B256};
 use async_trait::async_trait;
 
 /// A mock [FrameQueueProvider] for testing the [FrameQueue] stage.
@@ -38,16 +40,22 @@ impl OriginAdvancer for MockFrameQueueProvider {
 
 #[async_trait]
 impl FrameQueueProvider for MockFrameQueueProvider {
-    type Item = Bytes;
+    type Item = DataSourceItem;
 
     async fn next_data(&mut self) -> StageResult<Self::Item> {
-        self.data.pop().unwrap_or(Err(StageError::Eof))
+        let data = self.data.pop().unwrap_or(Err(StageError::Eof))?;
+        Ok(DataSourceItem::new(data, B256::ZERO, DataKind::Calldata))
     }
 }
 
 #[async_trait]
 impl ResettableStage for MockFrameQueueProvider {
-    async fn reset(&mut self, _base: BlockInfo, _cfg: &SystemConfig) -> StageResult<()> {
+    async fn reset(
+        &mut self,
+        _base: BlockInfo,
+        _l2_safe_head: L2BlockInfo,
+        _cfg: &SystemConfig,
+    ) -> StageResult<()> {
         Ok(())
     }
 }
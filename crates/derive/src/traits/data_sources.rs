@@ -1,9 +1,11 @@
 //! Contains traits that describe the functionality of various data sources used in the derivation
 //! pipeline's stages.
 
-use crate::types::{Blob, BlobProviderError, BlockInfo, IndexedBlobHash, StageResult};
+use crate::types::{
+    Blob, BlobProviderError, BlockInfo, DataSourceItem, IndexedBlobHash, StageResult,
+};
 use alloc::{boxed::Box, fmt::Debug, vec::Vec};
-use alloy_primitives::{Address, Bytes};
+use alloy_primitives::Address;
 use anyhow::Result;
 use async_trait::async_trait;
 
@@ -22,8 +24,8 @@ pub trait BlobProvider {
 #[async_trait]
 pub trait DataAvailabilityProvider {
     /// The item type of the data iterator.
-    type Item: Send + Sync + Debug + Into<Bytes>;
-    /// An iterator over returned bytes data.
+    type Item: Send + Sync + Debug + Into<DataSourceItem>;
+    /// An iterator over returned data items.
     type DataIter: AsyncIterator<Item = Self::Item> + Send + Debug;
 
     /// Returns the data availability for the block with the given hash, or an error if the block
@@ -40,7 +42,7 @@ pub trait DataAvailabilityProvider {
 #[async_trait]
 pub trait AsyncIterator {
     /// The item type of the iterator.
-    type Item: Send + Sync + Debug + Into<Bytes>;
+    type Item: Send + Sync + Debug + Into<DataSourceItem>;
 
     /// Returns the next item in the iterator, or [crate::types::StageError::Eof] if the iterator is
     /// exhausted.
@@ -8,7 +8,13 @@ mod providers;
 pub use providers::{ChainProvider, L2ChainProvider};
 
 mod stages;
-pub use stages::{OriginAdvancer, OriginProvider, PreviousStage, ResettableStage};
+pub use stages::{
+    CheckpointableStage, FlushableStage, OriginAdvancer, OriginProvider, PreviousStage,
+    ResettableStage,
+};
+
+mod pipeline;
+pub use pipeline::Pipeline;
 
 mod ecrecover;
 pub use ecrecover::SignedRecoverable;
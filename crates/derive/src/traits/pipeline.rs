@@ -0,0 +1,22 @@
+//! Defines the interface for driving the derivation pipeline.
+
+use crate::{
+    traits::OriginProvider,
+    types::{L2AttributesWithParent, L2BlockInfo, Signal, StageResult, StepResult},
+};
+use alloc::boxed::Box;
+use async_trait::async_trait;
+
+/// The [Pipeline] trait is the single entrypoint for driving the derivation pipeline,
+/// used by both the client program and online rollup nodes.
+#[async_trait]
+pub trait Pipeline: OriginProvider {
+    /// Returns the next prepared [L2AttributesWithParent], if one is available.
+    fn next_attributes(&mut self) -> Option<L2AttributesWithParent>;
+
+    /// Attempts to progress the pipeline on top of the given L2 safe head `cursor`.
+    async fn step(&mut self, cursor: L2BlockInfo) -> StepResult;
+
+    /// Sends a [Signal] to the pipeline, which is propagated through every stage.
+    async fn signal(&mut self, signal: Signal) -> StageResult<()>;
+}
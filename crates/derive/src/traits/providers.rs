@@ -39,7 +39,9 @@ pub trait L2ChainProvider {
     /// Errors if the execution payload does not exist.
     async fn payload_by_number(&mut self, number: u64) -> Result<L2ExecutionPayloadEnvelope>;
 
-    /// Returns the [SystemConfig] by L2 number.
+    /// Returns the [SystemConfig] as of the L2 block with the given number, decoded from the L1
+    /// info deposit transaction of the block. The genesis block carries no deposit, and returns
+    /// the genesis [SystemConfig] of the [RollupConfig].
     async fn system_config_by_number(
         &mut self,
         number: u64,
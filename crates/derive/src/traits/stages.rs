@@ -1,14 +1,38 @@
 //! This module contains common traits for stages within the derivation pipeline.
 
-use crate::types::{BlockInfo, StageResult, SystemConfig};
+use crate::types::{BlockInfo, L2BlockInfo, PipelineCheckpoint, StageResult, SystemConfig};
 use alloc::boxed::Box;
 use async_trait::async_trait;
 
 /// Describes the functionality fo a resettable stage within the derivation pipeline.
 #[async_trait]
 pub trait ResettableStage {
-    /// Resets the derivation stage to its initial state.
-    async fn reset(&mut self, base: BlockInfo, cfg: &SystemConfig) -> StageResult<()>;
+    /// Resets the derivation stage to its initial state at the L1 `base` origin, to continue
+    /// derivation on top of the given L2 safe head.
+    async fn reset(
+        &mut self,
+        base: BlockInfo,
+        l2_safe_head: L2BlockInfo,
+        cfg: &SystemConfig,
+    ) -> StageResult<()>;
+}
+
+/// Describes the functionality of a stage that buffers channel data which can be flushed.
+#[async_trait]
+pub trait FlushableStage {
+    /// Flushes any buffered channel data in the stage and all of its previous stages.
+    async fn flush_channel(&mut self) -> StageResult<()>;
+}
+
+/// Describes a stage whose internal state can be captured in a [PipelineCheckpoint], and restored
+/// onto a freshly built stage.
+#[async_trait]
+pub trait CheckpointableStage {
+    /// Writes the state of the stage and all of its previous stages into the checkpoint.
+    fn checkpoint(&self, checkpoint: &mut PipelineCheckpoint);
+
+    /// Restores the state of the stage and all of its previous stages from the checkpoint.
+    async fn restore(&mut self, checkpoint: &PipelineCheckpoint) -> StageResult<()>;
 }
 
 /// Provides a method for accessing the pipeline's current L1 origin.
@@ -1,8 +1,12 @@
 //! Test Utilities for derive traits
 
 use crate::{
-    traits::{AsyncIterator, ChainProvider, DataAvailabilityProvider, L2ChainProvider},
-    types::{StageError, StageResult},
+    traits::{
+        AsyncIterator, BlobProvider, ChainProvider, DataAvailabilityProvider, L2ChainProvider,
+    },
+    types::{
+        Blob, BlobProviderError, DataKind, DataSourceItem, IndexedBlobHash, StageError, StageResult,
+    },
 };
 use alloc::{boxed::Box, sync::Arc, vec, vec::Vec};
 use alloy_consensus::{Header, Receipt, TxEnvelope};
@@ -26,10 +30,11 @@ pub struct TestIter {
 
 #[async_trait]
 impl AsyncIterator for TestIter {
-    type Item = Bytes;
+    type Item = DataSourceItem;
 
     async fn next(&mut self) -> Option<StageResult<Self::Item>> {
-        Some(self.results.pop().unwrap_or_else(|| Err(StageError::Eof)))
+        let result = self.results.pop().unwrap_or_else(|| Err(StageError::Eof));
+        Some(result.map(|data| DataSourceItem::new(data, B256::ZERO, DataKind::Calldata)))
     }
 }
 
@@ -42,7 +47,7 @@ pub struct TestDAP {
 
 #[async_trait]
 impl DataAvailabilityProvider for TestDAP {
-    type Item = Bytes;
+    type Item = DataSourceItem;
     type DataIter = TestIter;
 
     async fn open_data(
@@ -72,6 +77,8 @@ pub struct TestChainProvider {
     pub headers: Vec<(B256, Header)>,
     /// Maps block hashes to receipts using a tuple list.
     pub receipts: Vec<(B256, Vec<Receipt>)>,
+    /// Maps block hashes to transactions using a tuple list.
+    pub transactions: Vec<(B256, Vec<TxEnvelope>)>,
 }
 
 impl TestChainProvider {
@@ -85,6 +92,11 @@ impl TestChainProvider {
         self.receipts.push((hash, receipts));
     }
 
+    /// Insert transactions into the mock chain provider.
+    pub fn insert_transactions(&mut self, hash: B256, txs: Vec<TxEnvelope>) {
+        self.transactions.push((hash, txs));
+    }
+
     /// Insert a header into the mock chain provider.
     pub fn insert_header(&mut self, hash: B256, header: Header) {
         self.headers.push((hash, header));
@@ -105,11 +117,17 @@ impl TestChainProvider {
         self.receipts.clear();
     }
 
+    /// Clears transactions from the mock chain provider.
+    pub fn clear_transactions(&mut self) {
+        self.transactions.clear();
+    }
+
     /// Clears all blocks and receipts from the mock chain provider.
     pub fn clear(&mut self) {
         self.clear_blocks();
         self.clear_receipts();
         self.clear_headers();
+        self.clear_transactions();
     }
 }
 
@@ -149,7 +167,58 @@ impl ChainProvider for TestChainProvider {
             .find(|(_, b)| b.hash == hash)
             .map(|(_, b)| *b)
             .ok_or_else(|| anyhow::anyhow!("Block not found"))?;
-        Ok((block, Vec::new()))
+        let txs = self
+            .transactions
+            .iter()
+            .find(|(h, _)| *h == hash)
+            .map(|(_, txs)| txs.clone())
+            .unwrap_or_default();
+        Ok((block, txs))
+    }
+}
+
+/// A [BlobProvider] implementation for testing, which can fail to serve a blob once.
+#[derive(Debug, Clone, Default)]
+pub struct TestBlobProvider {
+    /// Maps blob hashes to blobs using a tuple list.
+    pub blobs: Vec<(B256, Blob)>,
+    /// The indices of blobs that fail to be served the first time they are requested.
+    pub fail_once: Arc<spin::Mutex<Vec<usize>>>,
+    /// The indices of the blobs requested by each call, in order.
+    pub requests: Arc<spin::Mutex<Vec<Vec<usize>>>>,
+}
+
+impl TestBlobProvider {
+    /// Insert a blob into the mock blob provider.
+    pub fn insert_blob(&mut self, hash: B256, blob: Blob) {
+        self.blobs.push((hash, blob));
+    }
+}
+
+#[async_trait]
+impl BlobProvider for TestBlobProvider {
+    async fn get_blobs(
+        &mut self,
+        _block_ref: &BlockInfo,
+        blob_hashes: &[IndexedBlobHash],
+    ) -> Result<Vec<Blob>, BlobProviderError> {
+        self.requests.lock().push(blob_hashes.iter().map(|hash| hash.index).collect());
+        let mut blobs = Vec::new();
+        for hash in blob_hashes {
+            let mut fail_once = self.fail_once.lock();
+            if let Some(i) = fail_once.iter().position(|i| *i == hash.index) {
+                fail_once.remove(i);
+                return Err(BlobProviderError::Custom(anyhow::anyhow!("Blob unavailable")));
+            }
+            let blob = self
+                .blobs
+                .iter()
+                .find(|(h, _)| *h == hash.hash)
+                .map(|(_, b)| *b)
+                .ok_or_else(|| BlobProviderError::Custom(anyhow::anyhow!("Blob not found")))?;
+            blobs.push(blob);
+        }
+        Ok(blobs)
     }
 }
 
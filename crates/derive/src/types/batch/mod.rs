@@ -6,13 +6,14 @@ use crate::{
     traits::L2ChainProvider,
     types::{BlockInfo, L2BlockInfo, RollupConfig},
 };
-use alloy_rlp::{Buf, Decodable};
+use alloc::vec::Vec;
+use alloy_rlp::{Buf, Decodable, Encodable};
 
 mod batch_type;
 pub use batch_type::BatchType;
 
 mod validity;
-pub use validity::BatchValidity;
+pub use validity::{BatchValidity, DropReason};
 
 mod span_batch;
 pub use span_batch::{
@@ -27,6 +28,7 @@ pub use single_batch::SingleBatch;
 
 /// A batch with its inclusion block.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct BatchWithInclusionBlock {
     /// The inclusion block
     pub inclusion_block: BlockInfo,
@@ -36,10 +38,7 @@ pub struct BatchWithInclusionBlock {
 
 impl BatchWithInclusionBlock {
     /// Validates the batch can be applied on top of the specified L2 safe head.
-    /// The first entry of the l1_blocks should match the origin of the l2_safe_head.
-    /// One or more consecutive l1_blocks should be provided.
-    /// In case of only a single L1 block, the decision whether a batch is valid may have to stay
-    /// undecided.
+    /// See [check_batch] for details.
     pub async fn check_batch<BF: L2ChainProvider>(
         &self,
         cfg: &RollupConfig,
@@ -47,21 +46,39 @@ impl BatchWithInclusionBlock {
         l2_safe_head: L2BlockInfo,
         fetcher: &mut BF,
     ) -> BatchValidity {
-        match &self.batch {
-            Batch::Single(single_batch) => {
-                single_batch.check_batch(cfg, l1_blocks, l2_safe_head, &self.inclusion_block)
-            }
-            Batch::Span(span_batch) => {
-                span_batch
-                    .check_batch(cfg, l1_blocks, l2_safe_head, &self.inclusion_block, fetcher)
-                    .await
-            }
+        check_batch(cfg, l1_blocks, l2_safe_head, &self.batch, &self.inclusion_block, fetcher).await
+    }
+}
+
+/// Validates the [Batch] included in the `inclusion_block` can be applied on top of the
+/// specified L2 safe head.
+///
+/// The first entry of the l1_blocks should match the origin of the l2_safe_head.
+/// One or more consecutive l1_blocks should be provided.
+/// In case of only a single L1 block, the decision whether a batch is valid may have to stay
+/// undecided. If the batch is dropped, the returned [BatchValidity::Drop] carries the
+/// [DropReason] of the violated validity rule.
+pub async fn check_batch<BF: L2ChainProvider>(
+    cfg: &RollupConfig,
+    l1_blocks: &[BlockInfo],
+    l2_safe_head: L2BlockInfo,
+    batch: &Batch,
+    inclusion_block: &BlockInfo,
+    fetcher: &mut BF,
+) -> BatchValidity {
+    match batch {
+        Batch::Single(single_batch) => {
+            single_batch.check_batch(cfg, l1_blocks, l2_safe_head, inclusion_block)
+        }
+        Batch::Span(span_batch) => {
+            span_batch.check_batch(cfg, l1_blocks, l2_safe_head, inclusion_block, fetcher).await
         }
     }
 }
 
 /// A Batch.
 #[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Batch {
     /// A single batch
     Single(SingleBatch),
@@ -103,4 +120,21 @@ impl Batch {
             }
         }
     }
+
+    /// Encodes the batch into a writer, prefixed with its [BatchType]. Span batches are encoded
+    /// for the genesis timestamp and chain ID of the given [RollupConfig]. This is the inverse of
+    /// [Batch::decode].
+    pub fn encode(&self, w: &mut Vec<u8>, cfg: &RollupConfig) -> Result<(), SpanBatchError> {
+        match self {
+            Self::Single(single_batch) => {
+                w.push(BatchType::Single as u8);
+                single_batch.encode(w);
+                Ok(())
+            }
+            Self::Span(span_batch) => {
+                w.push(BatchType::Span as u8);
+                span_batch.to_raw_span_batch(cfg.genesis.timestamp, cfg.l2_chain_id)?.encode(w)
+            }
+        }
+    }
 }
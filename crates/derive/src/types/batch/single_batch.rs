@@ -1,6 +1,6 @@
 //! This module contains the [SingleBatch] type.
 
-use super::validity::BatchValidity;
+use super::validity::{BatchValidity, DropReason};
 use crate::types::{BlockID, BlockInfo, L2BlockInfo, RawTransaction, RollupConfig};
 use alloc::vec::Vec;
 use alloy_primitives::BlockHash;
@@ -9,6 +9,7 @@ use tracing::{info, warn};
 
 /// Represents a single batch: a single encoded L2 block
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SingleBatch {
     /// Block hash of the previous L2 block. `B256::ZERO` if it has not been set by the Batch
     /// Queue.
@@ -56,7 +57,7 @@ impl SingleBatch {
         }
         if self.timestamp < next_timestamp {
             warn!("dropping batch with old timestamp, min_timestamp: {next_timestamp}");
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::TimestampTooOld);
         }
 
         // Dependent on the above timestamp check.
@@ -64,20 +65,20 @@ impl SingleBatch {
         if self.parent_hash != l2_safe_head.block_info.hash {
             let h = l2_safe_head.block_info.hash;
             warn!("ignoring batch with mismatching parent hash, current_safe_head: {h}");
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::ParentHashMismatch);
         }
 
         // Filter out batches that were included too late.
         if self.epoch_num + cfg.seq_window_size < inclusion_block.number {
             warn!("batch was included too late, sequence window expired");
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::SequenceWindowExpired);
         }
 
         // Check the L1 origin of the batch
         let mut batch_origin = epoch;
         if self.epoch_num < epoch.number {
             warn!("dropped batch, epoch is too old, minimum: {}", epoch.id());
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::EpochTooOld);
         } else if self.epoch_num == epoch.number {
             // Batch is sticking to the current epoch, continue.
         } else if self.epoch_num == epoch.number + 1 {
@@ -93,18 +94,18 @@ impl SingleBatch {
             batch_origin = l1_blocks[1];
         } else {
             warn!("dropped batch, epoch is too far ahead, maximum: {}", epoch.id());
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::EpochTooFarAhead);
         }
 
         // Validate the batch epoch hash
         if self.epoch_hash != batch_origin.hash {
             warn!("dropped batch, epoch hash does not match, expected: {}", batch_origin.id());
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::EpochHashMismatch);
         }
 
         if self.timestamp < batch_origin.timestamp {
             warn!("dropped batch, batch timestamp is less than L1 origin timestamp, l2_timestamp: {}, l1_timestamp: {}, origin: {}", self.timestamp, batch_origin.timestamp, batch_origin.id());
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::TimestampBeforeL1Origin);
         }
 
         // Check if we ran out of sequencer time drift
@@ -112,7 +113,7 @@ impl SingleBatch {
             max
         } else {
             warn!("dropped batch, timestamp exceeds configured max sequencer drift, origin timestamp: {}, max drift: {}", batch_origin.timestamp, cfg.max_sequencer_drift);
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::SequencerDriftOverflow);
         };
 
         let no_txs = self.transactions.is_empty();
@@ -121,7 +122,7 @@ impl SingleBatch {
             // empty batch instead, as the sequencer is not allowed to include anything
             // past this point without moving to the next epoch.
             warn!("batch exceeded sequencer time drift, sequencer must adopt new L1 origin to include transactions again, max time: {max}");
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::SequencerDriftExceeded);
         }
         if self.timestamp > max && no_txs {
             // If the sequencer is co-operating by producing an empty batch,
@@ -137,7 +138,7 @@ impl SingleBatch {
                 // Check if the next L1 Origin could have been adopted
                 if self.timestamp >= next_origin.timestamp {
                     info!("empty batch that exceeds the time drift without adopting next L1 origin, dropping");
-                    return BatchValidity::Drop;
+                    return BatchValidity::Drop(DropReason::SequencerDriftNotAdoptedNextOrigin);
                 } else {
                     info!("empty batch continuation, preserving L2 time invariant");
                 }
@@ -148,11 +149,11 @@ impl SingleBatch {
         for (i, tx) in self.transactions.iter().enumerate() {
             if tx.is_empty() {
                 warn!("transaction data must not be empty, but found empty tx at index {i}");
-                return BatchValidity::Drop;
+                return BatchValidity::Drop(DropReason::EmptyTransaction);
             }
             if tx.is_deposit() {
                 warn!("sequencers may not embed any deposits into batch data, but found tx that has one at index: {i}");
-                return BatchValidity::Drop;
+                return BatchValidity::Drop(DropReason::DepositTransaction);
             }
         }
 
@@ -183,8 +184,7 @@ impl Decodable for SingleBatch {
 
 #[cfg(test)]
 mod tests {
-    use super::SingleBatch;
-    use crate::types::RawTransaction;
+    use super::*;
     use alloc::vec;
     use alloy_primitives::{hex, B256};
     use alloy_rlp::{BytesMut, Decodable, Encodable};
@@ -218,4 +218,198 @@ mod tests {
 
         assert!(single_batch.has_invalid_transactions());
     }
+
+    struct CheckBatchCase {
+        name: &'static str,
+        cfg: RollupConfig,
+        l1_blocks: Vec<BlockInfo>,
+        l2_safe_head: L2BlockInfo,
+        inclusion_block: BlockInfo,
+        batch: SingleBatch,
+        expected: BatchValidity,
+    }
+
+    impl Default for CheckBatchCase {
+        fn default() -> Self {
+            let l1_a = BlockInfo {
+                number: 5,
+                hash: B256::left_padding_from(&[0xa]),
+                timestamp: 18,
+                ..Default::default()
+            };
+            let l1_b = BlockInfo {
+                number: 6,
+                hash: B256::left_padding_from(&[0xb]),
+                timestamp: 30,
+                ..Default::default()
+            };
+            let l2_safe_head = L2BlockInfo {
+                block_info: BlockInfo {
+                    number: 10,
+                    hash: B256::left_padding_from(&[0x10]),
+                    timestamp: 20,
+                    ..Default::default()
+                },
+                l1_origin: l1_a.id(),
+                seq_num: 1,
+            };
+            Self {
+                name: "valid",
+                cfg: RollupConfig {
+                    block_time: 2,
+                    seq_window_size: 10,
+                    max_sequencer_drift: 10,
+                    ..Default::default()
+                },
+                l1_blocks: vec![l1_a, l1_b],
+                l2_safe_head,
+                inclusion_block: BlockInfo { number: 6, ..Default::default() },
+                batch: SingleBatch {
+                    parent_hash: l2_safe_head.block_info.hash,
+                    epoch_num: l1_a.number,
+                    epoch_hash: l1_a.hash,
+                    timestamp: 22,
+                    transactions: vec![RawTransaction(hex!("01").into())],
+                },
+                expected: BatchValidity::Accept,
+            }
+        }
+    }
+
+    #[test]
+    fn test_check_batch_table() {
+        let base = CheckBatchCase::default();
+        let l1_b = base.l1_blocks[1];
+        let cases = vec![
+            CheckBatchCase::default(),
+            CheckBatchCase {
+                name: "missing L1 block input",
+                l1_blocks: vec![],
+                expected: BatchValidity::Undecided,
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "future timestamp",
+                batch: SingleBatch { timestamp: 24, ..base.batch.clone() },
+                expected: BatchValidity::Future,
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "old timestamp",
+                batch: SingleBatch { timestamp: 20, ..base.batch.clone() },
+                expected: BatchValidity::Drop(DropReason::TimestampTooOld),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "parent hash mismatch",
+                batch: SingleBatch { parent_hash: B256::ZERO, ..base.batch.clone() },
+                expected: BatchValidity::Drop(DropReason::ParentHashMismatch),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "sequence window expired",
+                inclusion_block: BlockInfo { number: 16, ..Default::default() },
+                expected: BatchValidity::Drop(DropReason::SequenceWindowExpired),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "epoch too old",
+                batch: SingleBatch { epoch_num: 4, ..base.batch.clone() },
+                expected: BatchValidity::Drop(DropReason::EpochTooOld),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "next epoch without the next L1 block",
+                l1_blocks: vec![base.l1_blocks[0]],
+                batch: SingleBatch { epoch_num: 6, ..base.batch.clone() },
+                expected: BatchValidity::Undecided,
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "epoch too far ahead",
+                batch: SingleBatch { epoch_num: 7, ..base.batch.clone() },
+                expected: BatchValidity::Drop(DropReason::EpochTooFarAhead),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "epoch hash mismatch",
+                batch: SingleBatch { epoch_hash: B256::ZERO, ..base.batch.clone() },
+                expected: BatchValidity::Drop(DropReason::EpochHashMismatch),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "timestamp before L1 origin",
+                batch: SingleBatch {
+                    epoch_num: l1_b.number,
+                    epoch_hash: l1_b.hash,
+                    ..base.batch.clone()
+                },
+                expected: BatchValidity::Drop(DropReason::TimestampBeforeL1Origin),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "sequencer drift overflow",
+                cfg: RollupConfig { max_sequencer_drift: u64::MAX, ..base.cfg },
+                expected: BatchValidity::Drop(DropReason::SequencerDriftOverflow),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "sequencer drift exceeded",
+                cfg: RollupConfig { max_sequencer_drift: 2, ..base.cfg },
+                expected: BatchValidity::Drop(DropReason::SequencerDriftExceeded),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "empty batch past drift without the next L1 block",
+                cfg: RollupConfig { max_sequencer_drift: 2, ..base.cfg },
+                l1_blocks: vec![base.l1_blocks[0]],
+                batch: SingleBatch { transactions: vec![], ..base.batch.clone() },
+                expected: BatchValidity::Undecided,
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "empty batch past drift preserving the L2 time invariant",
+                cfg: RollupConfig { max_sequencer_drift: 2, ..base.cfg },
+                batch: SingleBatch { transactions: vec![], ..base.batch.clone() },
+                expected: BatchValidity::Accept,
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "empty batch past drift not adopting the next origin",
+                cfg: RollupConfig { max_sequencer_drift: 2, ..base.cfg },
+                l1_blocks: vec![base.l1_blocks[0], BlockInfo { timestamp: 22, ..l1_b }],
+                batch: SingleBatch { transactions: vec![], ..base.batch.clone() },
+                expected: BatchValidity::Drop(DropReason::SequencerDriftNotAdoptedNextOrigin),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "empty transaction",
+                batch: SingleBatch {
+                    transactions: vec![RawTransaction::default()],
+                    ..base.batch.clone()
+                },
+                expected: BatchValidity::Drop(DropReason::EmptyTransaction),
+                ..Default::default()
+            },
+            CheckBatchCase {
+                name: "deposit transaction",
+                batch: SingleBatch {
+                    transactions: vec![RawTransaction(hex!("7E").into())],
+                    ..base.batch.clone()
+                },
+                expected: BatchValidity::Drop(DropReason::DepositTransaction),
+                ..Default::default()
+            },
+        ];
+
+        for case in cases {
+            let validity = case.batch.check_batch(
+                &case.cfg,
+                &case.l1_blocks,
+                case.l2_safe_head,
+                &case.inclusion_block,
+            );
+            assert_eq!(validity, case.expected, "case: {}", case.name);
+        }
+    }
 }
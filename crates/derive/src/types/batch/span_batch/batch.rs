@@ -4,8 +4,8 @@ use super::{SpanBatchError, SpanBatchTransactions};
 use crate::{
     traits::L2ChainProvider,
     types::{
-        BatchValidity, BlockInfo, L2BlockInfo, RollupConfig, SingleBatch, SpanBatchBits,
-        SpanBatchElement,
+        BatchValidity, BlockInfo, DropReason, L2BlockInfo, RawSpanBatch, RollupConfig, SingleBatch,
+        SpanBatchBits, SpanBatchElement, SpanBatchPayload, SpanBatchPrefix,
     },
 };
 use alloc::vec::Vec;
@@ -14,7 +14,11 @@ use op_alloy_consensus::OpTxType;
 use tracing::{info, warn};
 
 /// The span batch contains the input to build a span of L2 blocks in derived form.
+///
+/// With the `serde` feature, only the derived form is serialized: the cached encoding fields are
+/// only needed to append batches and encode the span batch, not to derive from it.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpanBatch {
     /// First 20 bytes of the first block's parent hash
     pub parent_check: FixedBytes<20>,
@@ -27,10 +31,13 @@ pub struct SpanBatch {
     /// List of block input in derived form
     pub batches: Vec<SpanBatchElement>,
     /// Caching - origin bits
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub origin_bits: SpanBatchBits,
     /// Caching - block tx counts
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub block_tx_counts: Vec<u64>,
     /// Caching - span batch txs
+    #[cfg_attr(feature = "serde", serde(skip))]
     pub txs: SpanBatchTransactions,
 }
 
@@ -90,7 +97,7 @@ impl SpanBatch {
                 batch_origin.id(),
                 batch_origin.timestamp
             );
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::SpanBatchPreDelta);
         }
 
         // Skip out of order batches.
@@ -106,7 +113,7 @@ impl SpanBatch {
         // SAFETY: The span batch is not empty so the last element exists.
         if self.batches.last().unwrap().timestamp < next_timestamp {
             warn!("span batch has no new blocks after safe head");
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::SpanBatchNoNewBlocks);
         }
 
         // Find the parent block of the span batch.
@@ -118,11 +125,11 @@ impl SpanBatch {
             if self.timestamp() > l2_safe_head.block_info.timestamp {
                 // Batch timestamp cannot be between safe head and next timestamp.
                 warn!("batch has misaligned timestamp, block time is too short");
-                return BatchValidity::Drop;
+                return BatchValidity::Drop(DropReason::SpanBatchMisalignedTimestamp);
             }
             if (l2_safe_head.block_info.timestamp - self.timestamp()) % cfg.block_time != 0 {
                 warn!("batch has misaligned timestamp, not overlapped exactly");
-                return BatchValidity::Drop;
+                return BatchValidity::Drop(DropReason::SpanBatchNotOverlappedExactly);
             }
             parent_num = l2_safe_head.block_info.number -
                 (l2_safe_head.block_info.timestamp - self.timestamp()) / cfg.block_time -
@@ -136,20 +143,20 @@ impl SpanBatch {
                 }
             };
         }
-        if !self.check_parent_hash(parent_block.block_info.parent_hash) {
+        if !self.check_parent_hash(parent_block.block_info.hash) {
             warn!(
                 "parent block number mismatch, expected: {parent_num}, received: {}, parent hash: {}, self hash: {}",
                 parent_block.block_info.number,
-                parent_block.block_info.parent_hash,
+                parent_block.block_info.hash,
                 self.parent_check,
             );
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::ParentHashMismatch);
         }
 
         // Filter out batches that were included too late.
         if starting_epoch_num + cfg.seq_window_size < inclusion_block.number {
             warn!("batch was included too late, sequence window expired");
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::SequenceWindowExpired);
         }
 
         // Check the L1 origin of the batch
@@ -158,7 +165,7 @@ impl SpanBatch {
                 "batch is for future epoch too far ahead, while it has the next timestamp, so it must be invalid, current_epoch: {}",
                 epoch.id()
             );
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::EpochTooFarAhead);
         }
 
         // Verify the l1 origin hash for each l1 block.
@@ -173,7 +180,7 @@ impl SpanBatch {
                         "batch is for different L1 chain, epoch hash does not match, expected: {}",
                         l1_block.hash
                     );
-                    return BatchValidity::Drop;
+                    return BatchValidity::Drop(DropReason::EpochHashMismatch);
                 }
                 origin_checked = true;
                 break;
@@ -187,7 +194,7 @@ impl SpanBatch {
         // Check if the batch is too old.
         if starting_epoch_num < parent_block.l1_origin.number {
             warn!("dropped batch, epoch is too old, minimum: {}", parent_block.block_info.id());
-            return BatchValidity::Drop;
+            return BatchValidity::Drop(DropReason::EpochTooOld);
         }
 
         let mut origin_index = 0;
@@ -218,7 +225,7 @@ impl SpanBatch {
                     l1_origin.timestamp,
                     l1_origin.id()
                 );
-                return BatchValidity::Drop;
+                return BatchValidity::Drop(DropReason::TimestampBeforeL1Origin);
             }
             // Check if we ran out of sequencer time drift
             if block_timestamp > l1_origin.timestamp + cfg.max_sequencer_drift {
@@ -236,7 +243,9 @@ impl SpanBatch {
                         if block_timestamp >= l1_blocks[origin_index + 1].timestamp {
                             // check if the next L1 origin could have been adopted
                             info!("batch exceeded sequencer time drift without adopting next origin, and next L1 origin would have been valid");
-                            return BatchValidity::Drop;
+                            return BatchValidity::Drop(
+                                DropReason::SequencerDriftNotAdoptedNextOrigin,
+                            );
                         } else {
                             info!("continuing with empty batch before late L1 block to preserve L2 time invariant");
                         }
@@ -249,7 +258,7 @@ impl SpanBatch {
                         "batch exceeded sequencer time drift, sequencer must adopt new L1 origin to include transactions again, max_time: {}",
                         l1_origin.timestamp + cfg.max_sequencer_drift
                     );
-                    return BatchValidity::Drop;
+                    return BatchValidity::Drop(DropReason::SequencerDriftExceeded);
                 }
             }
 
@@ -260,11 +269,11 @@ impl SpanBatch {
                         "transaction data must not be empty, but found empty tx, tx_index: {}",
                         tx_index
                     );
-                    return BatchValidity::Drop;
+                    return BatchValidity::Drop(DropReason::EmptyTransaction);
                 }
                 if tx_bytes.0[0] == OpTxType::Deposit as u8 {
                     warn!("sequencers may not embed any deposits into batch data, but found tx that has one, tx_index: {}", tx_index);
-                    return BatchValidity::Drop;
+                    return BatchValidity::Drop(DropReason::DepositTransaction);
                 }
             }
         }
@@ -293,19 +302,19 @@ impl SpanBatch {
                         safe_block_txs.len(),
                         batch_txs.len()
                     );
-                    return BatchValidity::Drop;
+                    return BatchValidity::Drop(DropReason::OverlappedTxCountMismatch);
                 }
                 for j in 0..batch_txs.len() {
                     if safe_block_txs[j + deposit_count] != batch_txs[j].0 {
                         warn!("overlapped block's transaction does not match");
-                        return BatchValidity::Drop;
+                        return BatchValidity::Drop(DropReason::OverlappedTxMismatch);
                     }
                 }
                 let safe_block_ref = match safe_block_payload.to_l2_block_ref(cfg) {
                     Ok(r) => r,
                     Err(e) => {
                         warn!("failed to extract L2BlockInfo from execution payload, hash: {}, err: {e}", safe_block_payload.execution_payload.block_hash);
-                        return BatchValidity::Drop;
+                        return BatchValidity::Drop(DropReason::L2BlockInfoExtractionFailed);
                     }
                 };
                 if safe_block_ref.l1_origin.number != self.batches[i as usize].epoch_num {
@@ -313,7 +322,7 @@ impl SpanBatch {
                         "overlapped block's L1 origin number does not match {}, {}",
                         safe_block_ref.l1_origin.number, self.batches[i as usize].epoch_num
                     );
-                    return BatchValidity::Drop;
+                    return BatchValidity::Drop(DropReason::OverlappedL1OriginMismatch);
                 }
             }
         }
@@ -335,20 +344,12 @@ impl SpanBatch {
             if batch.timestamp <= l2_safe_head.block_info.timestamp {
                 continue;
             }
-            tracing::info!(
-                "checking {} l1 origins with first timestamp: {}, batch timestamp: {}, {}",
-                l1_origins.len(),
-                l1_origins[0].timestamp,
-                batch.timestamp,
-                batch.epoch_num
-            );
-            let origin_epoch_hash = l1_origins[origin_index..l1_origins.len()]
+            let origin_epoch_hash = l1_origins[origin_index..]
                 .iter()
-                .enumerate()
-                .find(|(_, origin)| origin.timestamp == batch.timestamp)
-                .map(|(i, origin)| {
-                    origin_index = i;
-                    origin.hash
+                .position(|origin| origin.number == batch.epoch_num)
+                .map(|i| {
+                    origin_index += i;
+                    l1_origins[origin_index].hash
                 })
                 .ok_or(SpanBatchError::MissingL1Origin)?;
             let single_batch = SingleBatch {
@@ -363,6 +364,47 @@ impl SpanBatch {
         Ok(single_batches)
     }
 
+    /// Converts the [SpanBatch] into a [RawSpanBatch] for the chain with the given genesis
+    /// timestamp and chain ID, rebuilding the encoding fields from the derived
+    /// [SpanBatchElement]s. This is the inverse of [RawSpanBatch::derive].
+    ///
+    /// The origin bit of the first block cannot be recovered from the derived form, and is left
+    /// unset, as it is ignored when deriving.
+    pub fn to_raw_span_batch(
+        &self,
+        genesis_time: u64,
+        chain_id: u64,
+    ) -> Result<RawSpanBatch, SpanBatchError> {
+        let (Some(span_start), Some(span_end)) = (self.batches.first(), self.batches.last()) else {
+            return Err(SpanBatchError::EmptySpanBatch);
+        };
+
+        let mut origin_bits = SpanBatchBits::default();
+        let mut block_tx_counts = Vec::with_capacity(self.batches.len());
+        let mut txs = SpanBatchTransactions::default();
+        for (i, batch) in self.batches.iter().enumerate() {
+            let changed = i > 0 && self.batches[i - 1].epoch_num < batch.epoch_num;
+            origin_bits.set_bit(i, changed);
+            block_tx_counts.push(batch.transactions.len() as u64);
+            txs.add_txs(batch.transactions.clone(), chain_id)?;
+        }
+
+        Ok(RawSpanBatch {
+            prefix: SpanBatchPrefix {
+                rel_timestamp: span_start.timestamp - genesis_time,
+                l1_origin_num: span_end.epoch_num,
+                parent_check: self.parent_check,
+                l1_origin_check: self.l1_origin_check,
+            },
+            payload: SpanBatchPayload {
+                block_count: self.batches.len() as u64,
+                origin_bits,
+                block_tx_counts,
+                txs,
+            },
+        })
+    }
+
     /// Append a [SingleBatch] to the [SpanBatch]. Updates the L1 origin check if need be.
     pub fn append_singular_batch(
         &mut self,
@@ -463,6 +505,51 @@ mod tests {
         assert!(!batch.check_parent_hash(invalid));
     }
 
+    #[test]
+    fn test_get_singular_batches() {
+        let l1_origins: Vec<BlockInfo> = (10..14)
+            .map(|n| BlockInfo {
+                number: n,
+                hash: B256::repeat_byte(n as u8),
+                // L1 timestamps never line up with L2 timestamps.
+                timestamp: n * 12 + 1,
+                ..Default::default()
+            })
+            .collect();
+        let element = |epoch_num, timestamp| SpanBatchElement {
+            epoch_num,
+            timestamp,
+            transactions: vec![RawTransaction(Bytes::from(vec![epoch_num as u8]))],
+        };
+        let batch = SpanBatch {
+            batches: vec![element(10, 120), element(11, 122), element(11, 124), element(13, 126)],
+            ..Default::default()
+        };
+        let l2_safe_head = L2BlockInfo {
+            block_info: BlockInfo { timestamp: 120, ..Default::default() },
+            ..Default::default()
+        };
+
+        let single_batches = batch.get_singular_batches(&l1_origins, l2_safe_head).unwrap();
+        let expected: Vec<_> = batch.batches[1..]
+            .iter()
+            .map(|b| SingleBatch {
+                epoch_num: b.epoch_num,
+                epoch_hash: B256::repeat_byte(b.epoch_num as u8),
+                timestamp: b.timestamp,
+                transactions: b.transactions.clone(),
+                ..Default::default()
+            })
+            .collect();
+        assert_eq!(single_batches, expected);
+
+        // The origins must cover the epochs of the batches after the safe head.
+        assert_eq!(
+            batch.get_singular_batches(&l1_origins[..3], l2_safe_head),
+            Err(SpanBatchError::MissingL1Origin)
+        );
+    }
+
     #[tokio::test]
     async fn test_check_batch_missing_l1_block_input() {
         let trace_store: TraceStorage = Default::default();
@@ -548,7 +635,7 @@ mod tests {
         let batch = SpanBatch { batches: vec![first], ..Default::default() };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::SpanBatchPreDelta)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -607,7 +694,7 @@ mod tests {
         let batch = SpanBatch { batches: vec![first], ..Default::default() };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::SpanBatchNoNewBlocks)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -634,7 +721,7 @@ mod tests {
         let batch = SpanBatch { batches: vec![first, second], ..Default::default() };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::SpanBatchMisalignedTimestamp)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -661,7 +748,7 @@ mod tests {
         let batch = SpanBatch { batches: vec![first, second], ..Default::default() };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::SpanBatchNotOverlappedExactly)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -729,7 +816,7 @@ mod tests {
         // parent number = 41 - (10 - 10) / 10 - 1 = 40
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::ParentHashMismatch)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -747,12 +834,22 @@ mod tests {
         let l1_blocks = vec![block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             ..Default::default()
         };
         let inclusion_block = BlockInfo { number: 50, ..Default::default() };
         let l2_block = L2BlockInfo {
-            block_info: BlockInfo { number: 40, parent_hash, timestamp: 10, ..Default::default() },
+            block_info: BlockInfo {
+                number: 40,
+                hash: parent_hash,
+                timestamp: 10,
+                ..Default::default()
+            },
             ..Default::default()
         };
         let mut fetcher = TestL2ChainProvider { blocks: vec![l2_block], ..Default::default() };
@@ -766,7 +863,7 @@ mod tests {
         // parent number = 41 - (10 - 10) / 10 - 1 = 40
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::SequenceWindowExpired)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -789,13 +886,23 @@ mod tests {
         let l1_blocks = vec![block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 8, ..Default::default() },
             ..Default::default()
         };
         let inclusion_block = BlockInfo { number: 50, ..Default::default() };
         let l2_block = L2BlockInfo {
-            block_info: BlockInfo { number: 40, parent_hash, timestamp: 10, ..Default::default() },
+            block_info: BlockInfo {
+                number: 40,
+                hash: parent_hash,
+                timestamp: 10,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 8, ..Default::default() },
             ..Default::default()
         };
@@ -810,7 +917,7 @@ mod tests {
         // parent number = 41 - (10 - 10) / 10 - 1 = 40
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::EpochTooFarAhead)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -840,13 +947,23 @@ mod tests {
         let l1_blocks = vec![block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
         let inclusion_block = BlockInfo { number: 50, ..Default::default() };
         let l2_block = L2BlockInfo {
-            block_info: BlockInfo { number: 40, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 40,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -860,7 +977,7 @@ mod tests {
         };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::EpochHashMismatch)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -890,13 +1007,23 @@ mod tests {
         let l1_blocks = vec![block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
         let inclusion_block = BlockInfo { number: 50, ..Default::default() };
         let l2_block = L2BlockInfo {
-            block_info: BlockInfo { number: 40, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 40,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -937,13 +1064,23 @@ mod tests {
         let l1_blocks = vec![block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 13, ..Default::default() },
             ..Default::default()
         };
         let inclusion_block = BlockInfo { number: 50, ..Default::default() };
         let l2_block = L2BlockInfo {
-            block_info: BlockInfo { number: 40, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 40,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 14, ..Default::default() },
             ..Default::default()
         };
@@ -958,7 +1095,7 @@ mod tests {
         };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::EpochTooOld)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -995,7 +1132,12 @@ mod tests {
         let l1_blocks = vec![block, second_block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -1016,7 +1158,7 @@ mod tests {
         };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::SequencerDriftNotAdoptedNextOrigin)
         );
         let logs = trace_store.get_by_level(Level::INFO);
         assert_eq!(logs.len(), 1);
@@ -1045,7 +1187,12 @@ mod tests {
         let l1_blocks = vec![block, second_block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -1098,7 +1245,12 @@ mod tests {
         let l1_blocks = vec![block, second_block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -1132,7 +1284,7 @@ mod tests {
         };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::SequencerDriftExceeded)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -1161,7 +1313,12 @@ mod tests {
         let l1_blocks = vec![block, second_block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -1191,7 +1348,7 @@ mod tests {
         };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::EmptyTransaction)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -1220,7 +1377,12 @@ mod tests {
         let l1_blocks = vec![block, second_block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -1252,7 +1414,7 @@ mod tests {
         };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::DepositTransaction)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -1278,13 +1440,23 @@ mod tests {
         let l1_blocks = vec![block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
         let inclusion_block = BlockInfo { number: 50, ..Default::default() };
         let l2_block = L2BlockInfo {
-            block_info: BlockInfo { number: 40, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 40,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -1329,13 +1501,23 @@ mod tests {
         let l1_blocks = vec![block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
         let inclusion_block = BlockInfo { number: 50, ..Default::default() };
         let l2_block = L2BlockInfo {
-            block_info: BlockInfo { number: 40, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 40,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -1358,7 +1540,7 @@ mod tests {
         };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::L2BlockInfoExtractionFailed)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -1394,13 +1576,23 @@ mod tests {
         let l1_blocks = vec![block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
         let inclusion_block = BlockInfo { number: 50, ..Default::default() };
         let l2_block = L2BlockInfo {
-            block_info: BlockInfo { number: 40, parent_hash, timestamp: 10, ..Default::default() },
+            block_info: BlockInfo {
+                number: 40,
+                hash: parent_hash,
+                timestamp: 10,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -1427,7 +1619,7 @@ mod tests {
         };
         assert_eq!(
             batch.check_batch(&cfg, &l1_blocks, l2_safe_head, &inclusion_block, &mut fetcher).await,
-            BatchValidity::Drop
+            BatchValidity::Drop(DropReason::OverlappedL1OriginMismatch)
         );
         let logs = trace_store.get_by_level(Level::WARN);
         assert_eq!(logs.len(), 1);
@@ -1460,13 +1652,23 @@ mod tests {
         let l1_blocks = vec![block];
         let parent_hash = b256!("1111111111111111111111111111111111111111000000000000000000000000");
         let l2_safe_head = L2BlockInfo {
-            block_info: BlockInfo { number: 41, timestamp: 10, parent_hash, ..Default::default() },
+            block_info: BlockInfo {
+                number: 41,
+                timestamp: 10,
+                hash: parent_hash,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
         let inclusion_block = BlockInfo { number: 50, ..Default::default() };
         let l2_block = L2BlockInfo {
-            block_info: BlockInfo { number: 40, parent_hash, timestamp: 10, ..Default::default() },
+            block_info: BlockInfo {
+                number: 40,
+                hash: parent_hash,
+                timestamp: 10,
+                ..Default::default()
+            },
             l1_origin: BlockID { number: 9, ..Default::default() },
             ..Default::default()
         };
@@ -7,6 +7,7 @@ use alloc::vec::Vec;
 /// but does not contain the parent hash and epoch hash since spans
 /// do not contain this data for every block in the span.
 #[derive(Debug, Default, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SpanBatchElement {
     /// The epoch number of the L1 block
     pub epoch_num: u64,
@@ -72,6 +72,8 @@ pub enum SpanDecodingError {
     BlockTxCounts,
     /// Failed to decode transaction nonces
     TxNonces,
+    /// Failed to decode transaction gas limits
+    TxGases,
     /// Mismatch in length between the transaction type and signature arrays in a span batch
     /// transaction payload.
     TypeSignatureLenMismatch,
@@ -95,6 +97,7 @@ impl Display for SpanDecodingError {
             SpanDecodingError::BlockCount => write!(f, "Failed to decode block count"),
             SpanDecodingError::BlockTxCounts => write!(f, "Failed to decode block tx counts"),
             SpanDecodingError::TxNonces => write!(f, "Failed to decode transaction nonces"),
+            SpanDecodingError::TxGases => write!(f, "Failed to decode transaction gas limits"),
             SpanDecodingError::TypeSignatureLenMismatch => {
                 write!(f, "Mismatch in length between the transaction type and signature arrays in a span batch transaction payload")
             }
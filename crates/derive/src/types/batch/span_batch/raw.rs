@@ -1,6 +1,6 @@
 //! Raw Span Batch
 
-use alloc::vec::Vec;
+use alloc::{vec, vec::Vec};
 
 use crate::types::{
     BatchType, RawTransaction, SpanBatchElement, SpanBatchPayload, SpanBatchPrefix,
@@ -84,19 +84,27 @@ impl RawSpanBatch {
             return Err(SpanBatchError::EmptySpanBatch);
         }
 
-        let mut block_origin_nums = Vec::with_capacity(self.payload.block_count as usize);
+        let block_count = self.payload.block_count as usize;
+        if self.payload.block_tx_counts.len() != block_count {
+            return Err(SpanBatchError::Decoding(SpanDecodingError::BlockTxCounts));
+        }
+
+        // The L1 origin number is that of the last block, and walking backwards each set origin
+        // bit marks a block whose origin is one above the previous block's origin.
+        let mut block_origin_nums = vec![0; block_count];
         let mut l1_origin_number = self.prefix.l1_origin_num;
-        for i in (0..self.payload.block_count).rev() {
-            block_origin_nums.push(l1_origin_number);
-            if self
+        for i in (0..block_count).rev() {
+            block_origin_nums[i] = l1_origin_number;
+            let changed = self
                 .payload
                 .origin_bits
-                .get_bit(i as usize)
+                .get_bit(i)
                 .ok_or(SpanBatchError::Decoding(SpanDecodingError::L1OriginCheck))? ==
-                1 &&
-                i > 0
-            {
-                l1_origin_number -= 1;
+                1;
+            if changed && i > 0 {
+                l1_origin_number = l1_origin_number
+                    .checked_sub(1)
+                    .ok_or(SpanBatchError::Decoding(SpanDecodingError::L1OriginNumber))?;
             }
         }
 
@@ -104,23 +112,20 @@ impl RawSpanBatch {
         self.payload.txs.recover_v(chain_id)?;
 
         // Get all transactions in the batch.
-        let enveloped_txs = self.payload.txs.full_txs(chain_id)?;
-
-        let mut tx_idx = 0;
-        let batches = (0..self.payload.block_count).fold(Vec::new(), |mut acc, i| {
-            let transactions =
-                (0..self.payload.block_tx_counts[i as usize]).fold(Vec::new(), |mut acc, _| {
-                    acc.push(enveloped_txs[tx_idx].clone());
-                    tx_idx += 1;
-                    acc
-                });
-            acc.push(SpanBatchElement {
-                epoch_num: block_origin_nums[i as usize],
-                timestamp: genesis_time + self.prefix.rel_timestamp + block_time * i,
-                transactions: transactions.into_iter().map(|v| RawTransaction(v.into())).collect(),
+        let mut enveloped_txs = self.payload.txs.full_txs(chain_id)?.into_iter();
+
+        let mut batches = Vec::with_capacity(block_count);
+        for (i, epoch_num) in block_origin_nums.into_iter().enumerate() {
+            let transactions = (0..self.payload.block_tx_counts[i])
+                .map(|_| enveloped_txs.next().map(|tx| RawTransaction(tx.into())))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(SpanBatchError::Decoding(SpanDecodingError::BlockTxCounts))?;
+            batches.push(SpanBatchElement {
+                epoch_num,
+                timestamp: genesis_time + self.prefix.rel_timestamp + block_time * i as u64,
+                transactions,
             });
-            acc
-        });
+        }
 
         Ok(SpanBatch {
             parent_check: self.prefix.parent_check,
@@ -135,8 +140,23 @@ impl RawSpanBatch {
 mod test {
     extern crate std;
     use super::{RawSpanBatch, SpanBatch, SpanBatchElement};
+    use crate::types::{RawTransaction, SpanBatchTransactions};
     use alloc::{vec, vec::Vec};
-    use alloy_primitives::FixedBytes;
+    use alloy_primitives::{hex, FixedBytes};
+
+    /// A span batch for chain ID 10 with two blocks, built following the span batch spec. The
+    /// first block holds an unprotected and an EIP-155 protected legacy transaction, the second
+    /// an EIP-2930 transaction and an EIP-1559 contract creation. The second block advances the
+    /// L1 origin to block 21.
+    const SPAN_BATCH: &[u8] = &hex!("0415aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaabbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb02020202080a1af13b8a86806ae808ddc6d36680556d3be03ad8c378528b55bf1dc366342ea32af8efc32a47ff042b9202b95f10ae8e912219678ea1b17d5c662874a57fc169b1a06c567b9b7faa9bd07c461c60744bbabdf04899102392d523eb322cdccf756ca22ba09c976c018da372697e21ca3804537e6fe75e66e165a47e2dc7962e71c72d6b2e56ba0be305c9a705ee34ee563ebbd2e279667c43bdf422edc10e192825a00cb4c54462965925449cd17b24f045d4b1607ebecf1160e92d0bc3f0614fd6f0cb74a39616547ae155cf460737d9bc220db6ec5e949de998c533cf7558e364ae5bcac76917dcecb7be4c9936835509ec43ccc74efac9a419d2a068eeec5aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaabbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaac701843b9aca0080ca01843b9aca008301020301f84302843b9aca0082deadf838f794bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbe1a0000000000000000000000000000000000000000000000000000000000000000102d180830f424084773594008560006000f3c00001020388a401d08603e0d403a08d0602");
+
+    /// The EIP-2718 encoded transactions of [SPAN_BATCH], in order.
+    const SPAN_BATCH_TXS: [&[u8]; 4] = [
+        &hex!("f86380843b9aca0082520894aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa01801ba01af13b8a86806ae808ddc6d36680556d3be03ad8c378528b55bf1dc366342ea3a02af8efc32a47ff042b9202b95f10ae8e912219678ea1b17d5c662874a57fc169"),
+        &hex!("f86601843b9aca0082c35094bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb018301020338a0b1a06c567b9b7faa9bd07c461c60744bbabdf04899102392d523eb322cdccf75a06ca22ba09c976c018da372697e21ca3804537e6fe75e66e165a47e2dc7962e71"),
+        &hex!("01f8a00a02843b9aca0082ea6094aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa0282deadf838f794bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbe1a0000000000000000000000000000000000000000000000000000000000000000180a0c72d6b2e56ba0be305c9a705ee34ee563ebbd2e279667c43bdf422edc10e1928a025a00cb4c54462965925449cd17b24f045d4b1607ebecf1160e92d0bc3f0614f"),
+        &hex!("02f85b0a03830f42408477359400830186a080808560006000f3c001a0d6f0cb74a39616547ae155cf460737d9bc220db6ec5e949de998c533cf7558e3a064ae5bcac76917dcecb7be4c9936835509ec43ccc74efac9a419d2a068eeec5a"),
+    ];
 
     #[test]
     fn test_try_from_span_batch_empty_batches_errors() {
@@ -181,4 +201,70 @@ mod test {
         raw_span_batch.encode(&mut encoding_buf).unwrap();
         assert_eq!(encoding_buf, raw_span_batch_hex);
     }
+
+    #[test]
+    fn test_derive_span_batch_mixed_tx_types() {
+        let mut raw_span_batch = RawSpanBatch::decode(&mut &SPAN_BATCH[..]).unwrap();
+        let span_batch = raw_span_batch.derive(2, 1000, 10).unwrap();
+
+        assert_eq!(span_batch.parent_check, FixedBytes::from([0xaa; 20]));
+        assert_eq!(span_batch.l1_origin_check, FixedBytes::from([0xbb; 20]));
+        let txs: Vec<RawTransaction> =
+            SPAN_BATCH_TXS.iter().map(|tx| RawTransaction(tx.to_vec().into())).collect();
+        let expected = vec![
+            SpanBatchElement { epoch_num: 20, timestamp: 1004, transactions: txs[..2].to_vec() },
+            SpanBatchElement { epoch_num: 21, timestamp: 1006, transactions: txs[2..].to_vec() },
+        ];
+        assert_eq!(span_batch.batches, expected);
+    }
+
+    #[test]
+    fn test_encode_span_batch_mixed_tx_types() {
+        let mut raw_span_batch = RawSpanBatch::decode(&mut &SPAN_BATCH[..]).unwrap();
+        raw_span_batch.payload.txs.recover_v(10).unwrap();
+
+        let mut txs = SpanBatchTransactions::default();
+        let raw_txs = SPAN_BATCH_TXS.iter().map(|tx| RawTransaction(tx.to_vec().into())).collect();
+        txs.add_txs(raw_txs, 10).unwrap();
+        assert_eq!(txs, raw_span_batch.payload.txs);
+
+        let mut encoding_buf = Vec::new();
+        raw_span_batch.encode(&mut encoding_buf).unwrap();
+        assert_eq!(encoding_buf, SPAN_BATCH);
+    }
+
+    #[test]
+    fn test_derive_raw_span_batch_roundtrip() {
+        // Every transaction derived from the `op-node` span batch encodes back into the batch.
+        let raw_span_batch_hex = include_bytes!("../../../../testdata/raw_batch.hex");
+        let mut raw_span_batch = RawSpanBatch::decode(&mut raw_span_batch_hex.as_slice()).unwrap();
+        let span_batch = raw_span_batch.derive(2, 0, 981).unwrap();
+
+        let raw_txs = span_batch.batches.into_iter().flat_map(|b| b.transactions).collect();
+        let mut txs = SpanBatchTransactions::default();
+        txs.add_txs(raw_txs, 981).unwrap();
+        let (mut expected, mut encoded) = (Vec::new(), Vec::new());
+        raw_span_batch.payload.txs.encode(&mut expected).unwrap();
+        txs.encode(&mut encoded).unwrap();
+        assert_eq!(encoded, expected);
+    }
+
+    #[test]
+    fn test_to_raw_span_batch_roundtrip() {
+        let mut raw_span_batch = RawSpanBatch::decode(&mut &SPAN_BATCH[..]).unwrap();
+        let span_batch = raw_span_batch.derive(2, 1000, 10).unwrap();
+
+        let mut encoding_buf = Vec::new();
+        span_batch.to_raw_span_batch(1000, 10).unwrap().encode(&mut encoding_buf).unwrap();
+        let mut reencoded = RawSpanBatch::decode(&mut encoding_buf.as_slice()).unwrap();
+        assert_eq!(reencoded.derive(2, 1000, 10).unwrap(), span_batch);
+    }
+
+    #[test]
+    fn test_decode_truncated_span_batch() {
+        // Only the trailing protected bits are padded when missing.
+        for len in 0..SPAN_BATCH.len() - 1 {
+            assert!(RawSpanBatch::decode(&mut &SPAN_BATCH[..len]).is_err());
+        }
+    }
 }
@@ -11,6 +11,7 @@ use super::{
 };
 use crate::types::RawTransaction;
 use alloy_consensus::{Transaction, TxEnvelope, TxType};
+use alloy_eips::eip2718::{Decodable2718, Encodable2718};
 use alloy_primitives::TxKind;
 
 /// This struct contains the decoded information for transactions in a span batch.
@@ -152,6 +153,11 @@ impl SpanBatchTransactions {
     pub fn decode_tx_sigs_rs(&mut self, r: &mut &[u8]) -> Result<(), SpanBatchError> {
         let mut sigs = Vec::with_capacity(self.total_block_tx_count as usize);
         for _ in 0..self.total_block_tx_count {
+            if r.len() < 64 {
+                return Err(SpanBatchError::Decoding(
+                    SpanDecodingError::InvalidTransactionSignature,
+                ));
+            }
             let r_val = U256::from_be_slice(&r[..32]);
             let s_val = U256::from_be_slice(&r[32..64]);
             sigs.push(SpanBatchSignature { v: 0, r: r_val, s: s_val });
@@ -179,7 +185,7 @@ impl SpanBatchTransactions {
         let mut gases = Vec::with_capacity(self.total_block_tx_count as usize);
         for _ in 0..self.total_block_tx_count {
             let (gas, remaining) = unsigned_varint::decode::u64(r)
-                .map_err(|_| SpanBatchError::Decoding(SpanDecodingError::TxNonces))?;
+                .map_err(|_| SpanBatchError::Decoding(SpanDecodingError::TxGases))?;
             gases.push(gas);
             *r = remaining;
         }
@@ -191,7 +197,14 @@ impl SpanBatchTransactions {
     pub fn decode_tx_tos(&mut self, r: &mut &[u8]) -> Result<(), SpanBatchError> {
         let mut tos = Vec::with_capacity(self.total_block_tx_count as usize);
         let contract_creation_count = self.contract_creation_count();
-        for _ in 0..(self.total_block_tx_count - contract_creation_count) {
+        let to_count = self
+            .total_block_tx_count
+            .checked_sub(contract_creation_count)
+            .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
+        for _ in 0..to_count {
+            if r.len() < 20 {
+                return Err(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData));
+            }
             let to = Address::from_slice(&r[..20]);
             tos.push(to);
             r.advance(20);
@@ -290,7 +303,7 @@ impl SpanBatchTransactions {
                 .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
             let tx_envelope = tx.to_enveloped_tx(*nonce, *gas, to, chain_id, sig.try_into()?)?;
             let mut buf = Vec::new();
-            tx_envelope.encode(&mut buf);
+            tx_envelope.encode_2718(&mut buf);
             txs.push(buf);
         }
         Ok(txs)
@@ -306,7 +319,7 @@ impl SpanBatchTransactions {
         let offset = self.total_block_tx_count;
 
         for i in 0..total_block_tx_count {
-            let tx_enveloped = TxEnvelope::decode(&mut txs[i as usize].as_ref())
+            let tx_enveloped = TxEnvelope::decode_2718(&mut txs[i as usize].as_ref())
                 .map_err(|_| SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?;
             let span_batch_tx = SpanBatchTransactionData::try_from(&tx_enveloped)?;
 
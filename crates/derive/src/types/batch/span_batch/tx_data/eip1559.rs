@@ -1,7 +1,7 @@
 //! This module contains the eip1559 transaction data type for a span batch.
 
 use crate::types::{SpanBatchError, SpanDecodingError};
-use alloy_consensus::{SignableTransaction, Signed, TxEip1559, TxEnvelope};
+use alloy_consensus::{SignableTransaction, TxEip1559, TxEnvelope};
 use alloy_eips::eip2930::AccessList;
 use alloy_primitives::{Address, Signature, TxKind, U256};
 use alloy_rlp::{Bytes, RlpDecodable, RlpEncodable};
@@ -50,8 +50,7 @@ impl SpanBatchEip1559TransactionData {
             input: self.data.clone().into(),
             access_list: self.access_list.clone(),
         };
-        let signature_hash = eip1559_tx.signature_hash();
-        let signed_eip1559_tx = Signed::new_unchecked(eip1559_tx, signature, signature_hash);
+        let signed_eip1559_tx = eip1559_tx.into_signed(signature);
         Ok(TxEnvelope::Eip1559(signed_eip1559_tx))
     }
 }
@@ -1,7 +1,7 @@
 //! This module contains the eip2930 transaction data type for a span batch.
 
 use crate::types::{SpanBatchError, SpanDecodingError};
-use alloy_consensus::{SignableTransaction, Signed, TxEip2930, TxEnvelope};
+use alloy_consensus::{SignableTransaction, TxEip2930, TxEnvelope};
 use alloy_eips::eip2930::AccessList;
 use alloy_primitives::{Address, Signature, TxKind, U256};
 use alloy_rlp::{Bytes, RlpDecodable, RlpEncodable};
@@ -43,9 +43,7 @@ impl SpanBatchEip2930TransactionData {
             input: self.data.clone().into(),
             access_list: self.access_list.clone(),
         };
-        let signature_hash = access_list_tx.signature_hash();
-        let signed_access_list_tx =
-            Signed::new_unchecked(access_list_tx, signature, signature_hash);
+        let signed_access_list_tx = access_list_tx.into_signed(signature);
         Ok(TxEnvelope::Eip2930(signed_access_list_tx))
     }
 }
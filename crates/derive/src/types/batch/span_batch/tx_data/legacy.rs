@@ -1,7 +1,7 @@
 //! This module contains the legacy transaction data type for a span batch.
 
 use crate::types::{SpanBatchError, SpanDecodingError};
-use alloy_consensus::{SignableTransaction, Signed, TxEnvelope, TxLegacy};
+use alloy_consensus::{SignableTransaction, TxEnvelope, TxLegacy};
 use alloy_primitives::{Address, Signature, TxKind, U256};
 use alloy_rlp::{Bytes, RlpDecodable, RlpEncodable};
 
@@ -27,7 +27,8 @@ impl SpanBatchLegacyTransactionData {
         signature: Signature,
     ) -> Result<TxEnvelope, SpanBatchError> {
         let legacy_tx = TxLegacy {
-            chain_id: Some(chain_id),
+            // Only EIP-155 protected transactions are signed for a chain ID.
+            chain_id: signature.v().chain_id().is_some().then_some(chain_id),
             nonce,
             gas_price: u128::from_be_bytes(
                 self.gas_price.to_be_bytes::<32>()[16..].try_into().map_err(|_| {
@@ -39,8 +40,7 @@ impl SpanBatchLegacyTransactionData {
             value: self.value,
             input: self.data.clone().into(),
         };
-        let signature_hash = legacy_tx.signature_hash();
-        let signed_legacy_tx = Signed::new_unchecked(legacy_tx, signature, signature_hash);
+        let signed_legacy_tx = legacy_tx.into_signed(signature);
         Ok(TxEnvelope::Legacy(signed_legacy_tx))
     }
 }
@@ -26,7 +26,10 @@ pub(crate) fn read_tx_data(r: &mut &[u8]) -> Result<(Vec<u8>, TxType), SpanBatch
     let tx_payload = if rlp_header.list {
         // Grab the raw RLP for the transaction data from `r`. It was unaffected since we copied it.
         let payload_length_with_header = rlp_header.payload_length + rlp_header.length();
-        let payload = r[0..payload_length_with_header].to_vec();
+        let payload = r
+            .get(0..payload_length_with_header)
+            .ok_or(SpanBatchError::Decoding(SpanDecodingError::InvalidTransactionData))?
+            .to_vec();
         r.advance(payload_length_with_header);
         Ok(payload)
     } else {
@@ -48,7 +51,10 @@ pub(crate) fn convert_v_to_y_parity(v: u64, tx_type: TxType) -> Result<bool, Spa
         TxType::Legacy => {
             if v != 27 && v != 28 {
                 // EIP-155: v = 2 * chain_id + 35 + yParity
-                Ok((v - 35) & 1 == 1)
+                let v = v.checked_sub(35).ok_or(SpanBatchError::Decoding(
+                    SpanDecodingError::InvalidTransactionSignature,
+                ))?;
+                Ok(v & 1 == 1)
             } else {
                 // Unprotected legacy txs must have v = 27 or 28
                 Ok(v - 27 == 1)
@@ -1,14 +1,16 @@
 //! Contains the [BatchValidity] and its encodings.
 
+use core::fmt::Display;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// Batch Validity
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum BatchValidity {
     /// The batch is invalid now and in the future, unless we reorg
-    Drop,
+    Drop(DropReason),
     /// The batch is valid and should be processed
     Accept,
     /// We are lacking L1 information until we can proceed batch filtering
@@ -20,6 +22,102 @@ pub enum BatchValidity {
 impl BatchValidity {
     /// Returns if the batch is dropped.
     pub fn is_drop(&self) -> bool {
-        matches!(self, BatchValidity::Drop)
+        matches!(self, BatchValidity::Drop(_))
+    }
+
+    /// Returns the [DropReason] if the batch is dropped.
+    pub fn drop_reason(&self) -> Option<DropReason> {
+        match self {
+            BatchValidity::Drop(reason) => Some(*reason),
+            _ => None,
+        }
+    }
+}
+
+/// The reason a batch was dropped, one for each batch validity rule of the derivation spec.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropReason {
+    /// The batch timestamp is older than the next expected L2 timestamp.
+    TimestampTooOld,
+    /// The batch parent hash does not match the L2 safe head.
+    ParentHashMismatch,
+    /// The batch was included after the sequence window of its epoch expired.
+    SequenceWindowExpired,
+    /// The batch epoch is older than the current L1 origin.
+    EpochTooOld,
+    /// The batch epoch is more than one block ahead of the current L1 origin.
+    EpochTooFarAhead,
+    /// The batch epoch hash does not match the canonical L1 origin hash.
+    EpochHashMismatch,
+    /// A block timestamp is less than the timestamp of its L1 origin.
+    TimestampBeforeL1Origin,
+    /// The L1 origin timestamp plus the max sequencer drift overflows.
+    SequencerDriftOverflow,
+    /// A block with transactions exceeds the max sequencer drift.
+    SequencerDriftExceeded,
+    /// An empty block exceeds the max sequencer drift while the next L1 origin could have been
+    /// adopted.
+    SequencerDriftNotAdoptedNextOrigin,
+    /// The batch contains an empty transaction.
+    EmptyTransaction,
+    /// The batch contains a deposit transaction.
+    DepositTransaction,
+    /// A span batch was received with an L1 origin before the Delta hard fork.
+    SpanBatchPreDelta,
+    /// The span batch has no blocks after the L2 safe head.
+    SpanBatchNoNewBlocks,
+    /// The span batch timestamp falls between the L2 safe head and the next L2 timestamp.
+    SpanBatchMisalignedTimestamp,
+    /// The span batch does not overlap the safe chain on an exact block boundary.
+    SpanBatchNotOverlappedExactly,
+    /// An overlapped block has a different number of transactions than the safe block.
+    OverlappedTxCountMismatch,
+    /// An overlapped block has a transaction that differs from the safe block.
+    OverlappedTxMismatch,
+    /// The L2 block info of an overlapped safe block could not be extracted from its payload.
+    L2BlockInfoExtractionFailed,
+    /// An overlapped block has a different L1 origin than the safe block.
+    OverlappedL1OriginMismatch,
+}
+
+impl Display for DropReason {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DropReason::TimestampTooOld => write!(f, "Batch timestamp is too old"),
+            DropReason::ParentHashMismatch => write!(f, "Batch parent hash mismatch"),
+            DropReason::SequenceWindowExpired => write!(f, "Sequence window expired"),
+            DropReason::EpochTooOld => write!(f, "Batch epoch is too old"),
+            DropReason::EpochTooFarAhead => write!(f, "Batch epoch is too far ahead"),
+            DropReason::EpochHashMismatch => write!(f, "Batch epoch hash mismatch"),
+            DropReason::TimestampBeforeL1Origin => {
+                write!(f, "Block timestamp is less than the L1 origin timestamp")
+            }
+            DropReason::SequencerDriftOverflow => write!(f, "Max sequencer drift overflow"),
+            DropReason::SequencerDriftExceeded => write!(f, "Max sequencer drift exceeded"),
+            DropReason::SequencerDriftNotAdoptedNextOrigin => {
+                write!(f, "Max sequencer drift exceeded without adopting the next L1 origin")
+            }
+            DropReason::EmptyTransaction => write!(f, "Empty transaction in batch"),
+            DropReason::DepositTransaction => write!(f, "Deposit transaction in batch"),
+            DropReason::SpanBatchPreDelta => write!(f, "Span batch before the Delta hard fork"),
+            DropReason::SpanBatchNoNewBlocks => write!(f, "Span batch has no new blocks"),
+            DropReason::SpanBatchMisalignedTimestamp => {
+                write!(f, "Span batch has a misaligned timestamp")
+            }
+            DropReason::SpanBatchNotOverlappedExactly => {
+                write!(f, "Span batch is not overlapped exactly")
+            }
+            DropReason::OverlappedTxCountMismatch => {
+                write!(f, "Overlapped block transaction count mismatch")
+            }
+            DropReason::OverlappedTxMismatch => write!(f, "Overlapped block transaction mismatch"),
+            DropReason::L2BlockInfoExtractionFailed => {
+                write!(f, "Failed to extract the L2 block info of an overlapped block")
+            }
+            DropReason::OverlappedL1OriginMismatch => {
+                write!(f, "Overlapped block L1 origin mismatch")
+            }
+        }
     }
 }
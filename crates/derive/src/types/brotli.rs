@@ -0,0 +1,216 @@
+//! Contains a `no_std` brotli decompressor for Fjord channel data, and a stored encoder for tests.
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use alloc_no_stdlib::{Allocator, SliceWrapper, SliceWrapperMut};
+use brotli_decompressor::{BrotliDecompressStream, BrotliResult, BrotliState};
+use core::fmt::Display;
+
+/// The size of each chunk the output buffer is grown by while decompressing.
+const BROTLI_OUTPUT_CHUNK_SIZE: usize = 32 * 1024;
+
+/// An error returned by [decompress_brotli].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrotliDecompressionError {
+    /// The brotli stream ended before the final meta-block.
+    UnexpectedEof,
+    /// The brotli stream is malformed.
+    Failed,
+}
+
+impl Display for BrotliDecompressionError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnexpectedEof => write!(f, "Brotli stream ended unexpectedly"),
+            Self::Failed => write!(f, "Brotli decompression failed"),
+        }
+    }
+}
+
+/// Decompresses the given brotli stream, reading at most `max_len` bytes of output.
+///
+/// Similar to a limited reader, output beyond `max_len` is silently dropped rather than
+/// returned as an error, so that the batches within the limit can still be decoded.
+pub fn decompress_brotli(data: &[u8], max_len: usize) -> Result<Vec<u8>, BrotliDecompressionError> {
+    let mut state = BrotliState::new(HeapAllocator, HeapAllocator, HeapAllocator);
+
+    let mut output = vec![0u8; BROTLI_OUTPUT_CHUNK_SIZE.min(max_len)];
+    let mut available_in = data.len();
+    let mut input_offset = 0;
+    let mut available_out = output.len();
+    let mut output_offset = 0;
+    let mut written = 0;
+
+    loop {
+        match BrotliDecompressStream(
+            &mut available_in,
+            &mut input_offset,
+            data,
+            &mut available_out,
+            &mut output_offset,
+            &mut output,
+            &mut written,
+            &mut state,
+        ) {
+            BrotliResult::ResultSuccess => {
+                output.truncate(output_offset);
+                return Ok(output);
+            }
+            BrotliResult::NeedsMoreOutput => {
+                // The output limit has been reached, drop the remainder of the stream.
+                if output.len() >= max_len {
+                    output.truncate(max_len);
+                    return Ok(output);
+                }
+                let new_len = (output.len() + BROTLI_OUTPUT_CHUNK_SIZE).min(max_len);
+                available_out += new_len - output.len();
+                output.resize(new_len, 0);
+            }
+            BrotliResult::NeedsMoreInput => return Err(BrotliDecompressionError::UnexpectedEof),
+            BrotliResult::ResultFailure => return Err(BrotliDecompressionError::Failed),
+        }
+    }
+}
+
+/// Encodes the given data as a brotli stream made up of uncompressed meta-blocks.
+///
+/// The data is stored rather than compressed, so the stream is larger than its input. Every
+/// brotli decoder accepts it, which is enough to build Fjord channels in tests that round-trip
+/// through [decompress_brotli], but it does not produce the channels a batcher would. It is only
+/// compiled with the `test-utils` feature.
+#[cfg(any(test, feature = "test-utils"))]
+pub fn encode_stored_brotli(data: &[u8]) -> Vec<u8> {
+    // WBITS = 16, ISLAST = 1, ISLASTEMPTY = 1
+    if data.is_empty() {
+        return vec![0b110];
+    }
+
+    let mut out = Vec::new();
+    for (i, chunk) in data.chunks(1 << 16).enumerate() {
+        // ISLAST = 0, MNIBBLES = 4, MLEN - 1, ISUNCOMPRESSED = 1
+        let header = (((chunk.len() as u64) - 1) << 3) | (1 << 19);
+        // The first meta-block header follows the single zero bit encoding WBITS = 16.
+        // Subsequent headers start on the byte boundary following the stored data.
+        let header = if i == 0 { header << 1 } else { header };
+        out.extend_from_slice(&header.to_le_bytes()[..3]);
+        out.extend_from_slice(chunk);
+    }
+    // ISLAST = 1, ISLASTEMPTY = 1
+    out.push(0b11);
+    out
+}
+
+/// A heap [Allocator] for the brotli decoder state, backed by the global allocator.
+#[derive(Debug, Default, Clone, Copy)]
+struct HeapAllocator;
+
+/// A heap allocated cell handed out by the [HeapAllocator].
+#[derive(Debug, Default)]
+struct HeapCell<T>(Box<[T]>);
+
+impl<T> SliceWrapper<T> for HeapCell<T> {
+    fn slice(&self) -> &[T] {
+        &self.0
+    }
+}
+
+impl<T> SliceWrapperMut<T> for HeapCell<T> {
+    fn slice_mut(&mut self) -> &mut [T] {
+        &mut self.0
+    }
+}
+
+impl<T: Clone + Default> Allocator<T> for HeapAllocator {
+    type AllocatedMemory = HeapCell<T>;
+
+    fn alloc_cell(&mut self, len: usize) -> Self::AllocatedMemory {
+        HeapCell(vec![T::default(); len].into_boxed_slice())
+    }
+
+    fn free_cell(&mut self, _data: Self::AllocatedMemory) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{params::MAX_RLP_BYTES_PER_CHANNEL_FJORD, types::BatchType};
+
+    /// A Fjord channel holding the span batch of `raw_batch.hex`, compressed by the reference
+    /// brotli encoder at quality 10 with a 4 MiB window, as op-batcher does.
+    const BROTLI_CHANNEL: &[u8] = include_bytes!("../../testdata/brotli_channel.bin");
+
+    /// A Fjord channel holding 4187 copies of the span batch of `raw_batch.hex`, 100_014_869
+    /// bytes in total, compressed like [BROTLI_CHANNEL]. It exceeds the maximum RLP bytes per
+    /// channel after Fjord in the middle of the last batch.
+    const BROTLI_CHANNEL_OVER_LIMIT: &[u8] =
+        include_bytes!("../../testdata/brotli_channel_over_limit.bin");
+
+    fn typed_batch_data() -> Vec<u8> {
+        let mut typed_data = vec![BatchType::Span as u8];
+        typed_data.extend_from_slice(include_bytes!("../../testdata/raw_batch.hex"));
+        typed_data
+    }
+
+    #[test]
+    fn test_decompress_brotli_channel() {
+        let decoded = decompress_brotli(&BROTLI_CHANNEL[1..], usize::MAX).unwrap();
+        assert_eq!(decoded, typed_batch_data());
+    }
+
+    #[test]
+    fn test_decompress_brotli_channel_truncated() {
+        let data = &BROTLI_CHANNEL[1..BROTLI_CHANNEL.len() / 2];
+        let err = decompress_brotli(data, usize::MAX).unwrap_err();
+        assert_eq!(err, BrotliDecompressionError::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_decompress_brotli_channel_exceeds_limit() {
+        let max_len = MAX_RLP_BYTES_PER_CHANNEL_FJORD as usize;
+        let decoded = decompress_brotli(&BROTLI_CHANNEL_OVER_LIMIT[1..], max_len).unwrap();
+        assert_eq!(decoded.len(), max_len);
+
+        let typed_data = typed_batch_data();
+        for chunk in decoded.chunks(typed_data.len()) {
+            assert_eq!(chunk, &typed_data[..chunk.len()]);
+        }
+    }
+
+    #[test]
+    fn test_decompress_brotli_roundtrip() {
+        let data = (0..100_000u32).map(|i| i as u8).collect::<Vec<_>>();
+        let encoded = encode_stored_brotli(&data);
+        let decoded = decompress_brotli(&encoded, usize::MAX).unwrap();
+        assert_eq!(decoded, data);
+    }
+
+    #[test]
+    fn test_decompress_brotli_empty() {
+        let encoded = encode_stored_brotli(&[]);
+        assert_eq!(decompress_brotli(&encoded, usize::MAX).unwrap(), Vec::<u8>::new());
+    }
+
+    #[test]
+    fn test_decompress_brotli_exceeds_limit() {
+        let data = vec![0xFF; 100_000];
+        let encoded = encode_stored_brotli(&data);
+        let decoded = decompress_brotli(&encoded, 40_000).unwrap();
+        assert_eq!(decoded, data[..40_000]);
+    }
+
+    #[test]
+    fn test_decompress_brotli_truncated_stream() {
+        let data = vec![0xFF; 1_000];
+        let encoded = encode_stored_brotli(&data);
+        let err = decompress_brotli(&encoded[..500], usize::MAX).unwrap_err();
+        assert_eq!(err, BrotliDecompressionError::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_decompress_brotli_malformed() {
+        // The padding bits following an uncompressed meta-block header must be zero.
+        let mut encoded = encode_stored_brotli(&[0xFF; 10]);
+        encoded[2] |= 0x20;
+        let err = decompress_brotli(&encoded, usize::MAX).unwrap_err();
+        assert_eq!(err, BrotliDecompressionError::Failed);
+    }
+}
@@ -14,6 +14,7 @@ use hashbrown::HashMap;
 /// Each frame is ingested one by one. Once a frame with `closed` is added to the channel, the
 /// channel may mark itself as ready for reading once all intervening frames have been added
 #[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Channel {
     /// The unique identifier for this channel
     id: ChannelID,
@@ -98,6 +99,11 @@ impl Channel {
         Ok(())
     }
 
+    /// Returns the [ChannelID] of the channel.
+    pub fn id(&self) -> ChannelID {
+        self.id
+    }
+
     /// Returns the block number of the L1 block that contained the first [Frame] in this channel.
     pub fn open_block_number(&self) -> u64 {
         self.open_block.number
@@ -0,0 +1,326 @@
+//! This module contains the [ChannelOut] type, which encodes batches into a channel and splits it
+//! into frames, mirroring the channel building of the batcher. It is a test utility, only
+//! compiled with the `test-utils` feature, so client programs do not carry the compressors.
+
+use crate::{
+    params::{
+        ChannelID, CHANNEL_VERSION_BROTLI, MAX_FRAME_LEN, MAX_RLP_BYTES_PER_CHANNEL,
+        MAX_RLP_BYTES_PER_CHANNEL_FJORD,
+    },
+    types::{encode_stored_brotli, Batch, ChannelOutError, Frame, RollupConfig},
+};
+use alloc::{sync::Arc, vec::Vec};
+use miniz_oxide::deflate::compress_to_vec_zlib;
+
+/// The number of bytes an encoded frame adds to its data: the channel ID, the frame number, the
+/// frame data length and the `is_last` byte.
+const FRAME_V0_OVERHEAD: usize = 16 + 2 + 4 + 1;
+
+/// The zlib compression level used for channels, matching the best compression of the batcher.
+const ZLIB_COMPRESSION_LEVEL: u8 = 9;
+
+/// A [ChannelOut] is the writing counterpart of a [Channel]: batches are added to it one by one,
+/// and once the channel is closed its data is compressed and split into [Frame]s.
+///
+/// Channels are compressed with zlib before the Fjord hardfork, each with the respective maximum
+/// RLP bytes per channel. Once Fjord is active, channels are brotli channels, but their data is
+/// stored in uncompressed meta-blocks by [encode_stored_brotli] rather than compressed, as no
+/// brotli compressor is linked. Such channels are valid, and read back by the derivation
+/// pipeline, but they are larger than the ones of a batcher and do not exercise the brotli
+/// decoder beyond stored meta-blocks.
+///
+/// [Channel]: crate::types::Channel
+#[derive(Debug, Clone)]
+pub struct ChannelOut {
+    /// The unique identifier of the channel.
+    id: ChannelID,
+    /// The rollup config, used to encode span batches.
+    cfg: Arc<RollupConfig>,
+    /// Whether the Fjord hardfork is active, selecting the stored brotli encoding.
+    fjord_active: bool,
+    /// The encoded batches, before compression.
+    rlp: Vec<u8>,
+    /// True once the frames of the channel have been output.
+    closed: bool,
+}
+
+impl ChannelOut {
+    /// Creates a new, empty [ChannelOut] with the given [ChannelID], respecting the compression
+    /// format and limits of the Fjord hardfork if it is active.
+    pub fn new(id: ChannelID, cfg: Arc<RollupConfig>, fjord_active: bool) -> Self {
+        Self { id, cfg, fjord_active, rlp: Vec::new(), closed: false }
+    }
+
+    /// Returns the [ChannelID] of the channel.
+    pub fn id(&self) -> ChannelID {
+        self.id
+    }
+
+    /// Returns the number of bytes of encoded batches added to the channel, before compression.
+    pub fn input_bytes(&self) -> usize {
+        self.rlp.len()
+    }
+
+    /// Returns the maximum number of bytes of encoded batches the channel can hold.
+    fn max_rlp_bytes(&self) -> usize {
+        let max = if self.fjord_active {
+            MAX_RLP_BYTES_PER_CHANNEL_FJORD
+        } else {
+            MAX_RLP_BYTES_PER_CHANNEL
+        };
+        max as usize
+    }
+
+    /// Encodes the [Batch] and appends it to the channel.
+    ///
+    /// Returns an error if the channel is closed, or if the batch would grow the channel beyond
+    /// the maximum RLP bytes per channel, in which case the channel is left unchanged.
+    pub fn add_batch(&mut self, batch: Batch) -> Result<(), ChannelOutError> {
+        if self.closed {
+            return Err(ChannelOutError::ChannelClosed);
+        }
+
+        let mut encoded = Vec::new();
+        batch.encode(&mut encoded, &self.cfg).map_err(ChannelOutError::BatchEncoding)?;
+
+        let size = self.rlp.len() + encoded.len();
+        let max = self.max_rlp_bytes();
+        if size > max {
+            return Err(ChannelOutError::MaxRlpBytesExceeded { size, max });
+        }
+        self.rlp.extend_from_slice(&encoded);
+        Ok(())
+    }
+
+    /// Closes the channel, and returns its compressed data split into [Frame]s of at most
+    /// `max_frame_size` encoded bytes each.
+    ///
+    /// The frames are numbered from zero, and only the final frame is marked as the last. Once
+    /// the frames have been output, the channel is closed and no more frames are returned.
+    pub fn output_frames(&mut self, max_frame_size: usize) -> Result<Vec<Frame>, ChannelOutError> {
+        if max_frame_size <= FRAME_V0_OVERHEAD {
+            return Err(ChannelOutError::MaxFrameSizeTooSmall(max_frame_size));
+        }
+        if self.closed {
+            return Ok(Vec::new());
+        }
+
+        let data = self.compress();
+        let max_data_len = (max_frame_size - FRAME_V0_OVERHEAD).min(MAX_FRAME_LEN);
+        let frame_count = data.len().div_ceil(max_data_len);
+        if frame_count > u16::MAX as usize + 1 {
+            return Err(ChannelOutError::TooManyFrames(frame_count));
+        }
+
+        self.closed = true;
+        self.rlp = Vec::new();
+        Ok(data
+            .chunks(max_data_len)
+            .enumerate()
+            .map(|(i, chunk)| Frame {
+                id: self.id,
+                number: i as u16,
+                data: chunk.to_vec(),
+                is_last: i == frame_count - 1,
+            })
+            .collect())
+    }
+
+    /// Compresses the encoded batches of the channel with zlib, or stores them in a brotli stream
+    /// once Fjord is active.
+    fn compress(&self) -> Vec<u8> {
+        if self.fjord_active {
+            let mut data = Vec::with_capacity(self.rlp.len() + 1);
+            data.push(CHANNEL_VERSION_BROTLI);
+            data.extend(encode_stored_brotli(&self.rlp));
+            data
+        } else {
+            compress_to_vec_zlib(&self.rlp, ZLIB_COMPRESSION_LEVEL)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        stages::{test_utils::MockChannelBankProvider, BatchReader, ChannelBank},
+        types::{BatchType, RawTransaction, SingleBatch},
+    };
+    use alloc::vec;
+    use alloy_primitives::B256;
+    use proptest::{collection, prelude::*};
+
+    const ID: ChannelID = [0xAA; 16];
+
+    /// Sends the frames through a [ChannelBank] and a [BatchReader], returning the batches read
+    /// back from the channel.
+    fn read_batches(frames: Vec<Frame>, cfg: Arc<RollupConfig>, fjord_active: bool) -> Vec<Batch> {
+        let mut channel_bank = ChannelBank::new(cfg.clone(), MockChannelBankProvider::new(vec![]));
+        for frame in frames {
+            channel_bank.ingest_frame(frame).unwrap();
+        }
+        let data = channel_bank.read().unwrap().unwrap();
+
+        let mut reader = BatchReader::new(data, fjord_active);
+        core::iter::from_fn(|| reader.next_batch(&cfg)).collect()
+    }
+
+    fn new_single_batch(timestamp: u64, transactions: Vec<Vec<u8>>) -> Batch {
+        Batch::Single(SingleBatch {
+            parent_hash: B256::repeat_byte(0x01),
+            epoch_num: 7,
+            epoch_hash: B256::repeat_byte(0x02),
+            timestamp,
+            transactions: transactions.into_iter().map(RawTransaction::from).collect(),
+        })
+    }
+
+    fn new_span_batch(cfg: &RollupConfig) -> Batch {
+        let raw_data = include_bytes!("../../testdata/raw_batch.hex");
+        let mut typed_data = vec![BatchType::Span as u8];
+        typed_data.extend_from_slice(raw_data.as_slice());
+        Batch::decode(&mut typed_data.as_slice(), cfg).unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip_zlib() {
+        let cfg = Arc::new(RollupConfig::default());
+        let batches =
+            vec![new_single_batch(10, vec![vec![0xFF; 100]]), new_single_batch(12, vec![])];
+        let mut channel_out = ChannelOut::new(ID, cfg.clone(), false);
+        for batch in batches.clone() {
+            channel_out.add_batch(batch).unwrap();
+        }
+
+        let frames = channel_out.output_frames(1_000).unwrap();
+        assert_eq!(frames[0].data[0] & 0x0F, crate::params::ZLIB_DEFLATE_COMPRESSION_METHOD);
+        assert_eq!(read_batches(frames, cfg, false), batches);
+    }
+
+    #[test]
+    fn test_roundtrip_brotli() {
+        let cfg = Arc::new(RollupConfig::default());
+        let batches =
+            vec![new_single_batch(10, vec![vec![0xFF; 100]]), new_single_batch(12, vec![])];
+        let mut channel_out = ChannelOut::new(ID, cfg.clone(), true);
+        for batch in batches.clone() {
+            channel_out.add_batch(batch).unwrap();
+        }
+
+        let frames = channel_out.output_frames(1_000).unwrap();
+        assert_eq!(frames[0].data[0], CHANNEL_VERSION_BROTLI);
+        assert_eq!(read_batches(frames, cfg, true), batches);
+    }
+
+    #[test]
+    fn test_roundtrip_span_batch() {
+        let cfg = Arc::new(RollupConfig { block_time: 2, l2_chain_id: 981, ..Default::default() });
+        let batches = vec![new_span_batch(&cfg), new_single_batch(10, vec![vec![0x01]])];
+        let mut channel_out = ChannelOut::new(ID, cfg.clone(), true);
+        for batch in batches.clone() {
+            channel_out.add_batch(batch).unwrap();
+        }
+
+        let frames = channel_out.output_frames(1_000).unwrap();
+        assert_eq!(read_batches(frames, cfg, true), batches);
+    }
+
+    #[test]
+    fn test_output_frames_numbering() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mut channel_out = ChannelOut::new(ID, cfg, true);
+        channel_out.add_batch(new_single_batch(10, vec![vec![0xFF; 250]])).unwrap();
+
+        let frames = channel_out.output_frames(FRAME_V0_OVERHEAD + 100).unwrap();
+        assert_eq!(frames.len(), 3);
+        for (i, frame) in frames.iter().enumerate() {
+            assert_eq!(frame.id, ID);
+            assert_eq!(frame.number, i as u16);
+            assert_eq!(frame.is_last, i == 2);
+            assert!(frame.encode().len() <= FRAME_V0_OVERHEAD + 100);
+        }
+
+        // The channel is closed once its frames are output.
+        assert!(channel_out.output_frames(1_000).unwrap().is_empty());
+        assert_eq!(
+            channel_out.add_batch(new_single_batch(12, vec![])),
+            Err(ChannelOutError::ChannelClosed)
+        );
+    }
+
+    #[test]
+    fn test_output_frames_empty_channel() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mut channel_out = ChannelOut::new(ID, cfg.clone(), true);
+        let frames = channel_out.output_frames(1_000).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert!(frames[0].is_last);
+        assert!(read_batches(frames, cfg, true).is_empty());
+    }
+
+    #[test]
+    fn test_output_frames_max_frame_size_too_small() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mut channel_out = ChannelOut::new(ID, cfg, false);
+        assert_eq!(
+            channel_out.output_frames(FRAME_V0_OVERHEAD),
+            Err(ChannelOutError::MaxFrameSizeTooSmall(FRAME_V0_OVERHEAD))
+        );
+    }
+
+    #[test]
+    fn test_add_batch_exceeds_max_rlp_bytes() {
+        let cfg = Arc::new(RollupConfig::default());
+        let mut channel_out = ChannelOut::new(ID, cfg, false);
+        channel_out.add_batch(new_single_batch(10, vec![vec![0xFF; 100]])).unwrap();
+        let input_bytes = channel_out.input_bytes();
+
+        let large = vec![0xFF; MAX_RLP_BYTES_PER_CHANNEL as usize];
+        let err = channel_out.add_batch(new_single_batch(12, vec![large])).unwrap_err();
+        assert!(matches!(
+            err,
+            ChannelOutError::MaxRlpBytesExceeded { max, .. } if max == MAX_RLP_BYTES_PER_CHANNEL as usize
+        ));
+        assert_eq!(channel_out.input_bytes(), input_bytes);
+    }
+
+    fn arb_single_batch() -> impl Strategy<Value = Batch> {
+        (
+            any::<[u8; 32]>(),
+            any::<u64>(),
+            any::<[u8; 32]>(),
+            any::<u64>(),
+            collection::vec(collection::vec(any::<u8>(), 1..200), 0..8),
+        )
+            .prop_map(|(parent_hash, epoch_num, epoch_hash, timestamp, transactions)| {
+                Batch::Single(SingleBatch {
+                    parent_hash: parent_hash.into(),
+                    epoch_num,
+                    epoch_hash: epoch_hash.into(),
+                    timestamp,
+                    transactions: transactions.into_iter().map(RawTransaction::from).collect(),
+                })
+            })
+    }
+
+    proptest! {
+        #[test]
+        fn test_roundtrip_arbitrary_batches(
+            batches in collection::vec(arb_single_batch(), 0..16),
+            fjord_active in any::<bool>(),
+            max_frame_size in (FRAME_V0_OVERHEAD + 1)..2_000,
+        ) {
+            let cfg = Arc::new(RollupConfig::default());
+            let mut channel_out = ChannelOut::new(ID, cfg.clone(), fjord_active);
+            for batch in batches.clone() {
+                channel_out.add_batch(batch).unwrap();
+            }
+
+            let mut frames = channel_out.output_frames(max_frame_size).unwrap();
+            // Frames may arrive out of order within a channel.
+            frames.reverse();
+            prop_assert_eq!(read_batches(frames, cfg, fjord_active), batches);
+        }
+    }
+}
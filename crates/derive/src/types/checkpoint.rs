@@ -0,0 +1,91 @@
+//! Contains the [PipelineCheckpoint], a snapshot of the internal state of the derivation pipeline.
+
+use crate::{
+    params::ChannelID,
+    stages::BatchReader,
+    types::{
+        BatchWithInclusionBlock, BlockInfo, Channel, Frame, L2AttributesWithParent, SingleBatch,
+        SystemConfig,
+    },
+};
+use alloc::vec::Vec;
+
+/// A snapshot of the internal state of every stage of the [DerivationPipeline], taken with
+/// [DerivationPipeline::checkpoint].
+///
+/// Restoring a checkpoint with [DerivationPipeline::restore] resumes derivation exactly where the
+/// checkpointed pipeline left off, without re-deriving from the L2 safe head. The checkpoint only
+/// holds the data buffered by the stages: the providers of the stages are not part of it, and are
+/// supplied by the freshly built pipeline the checkpoint is restored onto. With the `serde`
+/// feature enabled, the checkpoint can be serialized.
+///
+/// [DerivationPipeline]: crate::DerivationPipeline
+/// [DerivationPipeline::checkpoint]: crate::DerivationPipeline::checkpoint
+/// [DerivationPipeline::restore]: crate::DerivationPipeline::restore
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PipelineCheckpoint {
+    /// The L1 origin of the [L1Traversal] stage.
+    ///
+    /// [L1Traversal]: crate::stages::L1Traversal
+    pub(crate) origin: Option<BlockInfo>,
+    /// Whether the L1 origin was already handed to the [L1Retrieval] stage.
+    ///
+    /// [L1Retrieval]: crate::stages::L1Retrieval
+    pub(crate) origin_consumed: bool,
+    /// The [SystemConfig] as of the L1 origin.
+    pub(crate) system_config: SystemConfig,
+    /// The number of items read from the data of the L1 block opened by the [L1Retrieval] stage,
+    /// or [None] if no data is open.
+    ///
+    /// [L1Retrieval]: crate::stages::L1Retrieval
+    pub(crate) data_read: Option<usize>,
+    /// The frames buffered in the [FrameQueue] stage.
+    ///
+    /// [FrameQueue]: crate::stages::FrameQueue
+    pub(crate) frames: Vec<Frame>,
+    /// The channels buffered in the [ChannelBank] stage.
+    ///
+    /// [ChannelBank]: crate::stages::ChannelBank
+    pub(crate) channels: Vec<Channel>,
+    /// The IDs of the buffered channels, in FIFO order.
+    pub(crate) channel_queue: Vec<ChannelID>,
+    /// The channel being read by the [ChannelReader] stage.
+    ///
+    /// [ChannelReader]: crate::stages::ChannelReader
+    pub(crate) batch_reader: Option<BatchReader>,
+    /// The L1 origin of the [BatchQueue] stage.
+    ///
+    /// [BatchQueue]: crate::stages::BatchQueue
+    pub(crate) batch_origin: Option<BlockInfo>,
+    /// The L1 blocks the [BatchQueue] stage builds on.
+    ///
+    /// [BatchQueue]: crate::stages::BatchQueue
+    pub(crate) l1_blocks: Vec<BlockInfo>,
+    /// The batches buffered in the [BatchQueue] stage.
+    ///
+    /// [BatchQueue]: crate::stages::BatchQueue
+    pub(crate) batches: Vec<BatchWithInclusionBlock>,
+    /// The [SingleBatch]es left over from the span batch being applied.
+    pub(crate) next_spans: Vec<SingleBatch>,
+    /// The batch the [AttributesQueue] stage is building attributes for.
+    ///
+    /// [AttributesQueue]: crate::stages::AttributesQueue
+    pub(crate) batch: Option<SingleBatch>,
+    /// Whether the batch is the last in its span.
+    pub(crate) is_last_in_span: bool,
+    /// The attributes prepared by the pipeline that were not consumed yet.
+    pub(crate) prepared: Vec<L2AttributesWithParent>,
+}
+
+impl PipelineCheckpoint {
+    /// Returns the L1 origin of the checkpointed pipeline.
+    pub fn origin(&self) -> Option<BlockInfo> {
+        self.origin
+    }
+
+    /// Returns the [SystemConfig] of the checkpointed pipeline.
+    pub fn system_config(&self) -> &SystemConfig {
+        &self.system_config
+    }
+}
@@ -0,0 +1,70 @@
+//! Contains the [DataSourceItem] type returned by data availability sources.
+
+use alloy_primitives::{Bytes, B256};
+use core::fmt::Display;
+
+/// The kind of data availability that a [DataSourceItem] was read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataKind {
+    /// The calldata of a batcher transaction.
+    Calldata,
+    /// A blob, along with its index in the L1 block.
+    Blob(usize),
+    /// An input fetched from a plasma DA provider.
+    Plasma,
+}
+
+impl Display for DataKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            DataKind::Calldata => write!(f, "calldata"),
+            DataKind::Blob(index) => write!(f, "blob {}", index),
+            DataKind::Plasma => write!(f, "plasma input"),
+        }
+    }
+}
+
+/// A piece of data returned by a data availability source, along with where it came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DataSourceItem {
+    /// The raw data.
+    pub data: Bytes,
+    /// The hash of the L1 transaction that carried the data.
+    /// This is zero if the source does not know the transaction.
+    pub origin_tx: B256,
+    /// The kind of data availability the data was read from.
+    pub kind: DataKind,
+}
+
+impl DataSourceItem {
+    /// Creates a new [DataSourceItem].
+    pub fn new(data: Bytes, origin_tx: B256, kind: DataKind) -> Self {
+        Self { data, origin_tx, kind }
+    }
+}
+
+impl From<DataSourceItem> for Bytes {
+    fn from(item: DataSourceItem) -> Self {
+        item.data
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+
+    #[test]
+    fn test_data_kind_display() {
+        assert_eq!(DataKind::Calldata.to_string(), "calldata");
+        assert_eq!(DataKind::Blob(3).to_string(), "blob 3");
+        assert_eq!(DataKind::Plasma.to_string(), "plasma input");
+    }
+
+    #[test]
+    fn test_data_source_item_into_bytes() {
+        let data = Bytes::from_static(&[0xBE, 0xEF]);
+        let item = DataSourceItem::new(data.clone(), B256::repeat_byte(0x01), DataKind::Blob(0));
+        assert_eq!(Bytes::from(item), data);
+    }
+}
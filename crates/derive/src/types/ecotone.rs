@@ -2,10 +2,10 @@
 //!
 //! [Transaction]: alloy_consensus::Transaction
 
-use crate::types::{RawTransaction, UpgradeDepositSource};
-use alloc::{string::String, vec, vec::Vec};
+use crate::types::{DepositSourceDomain, RawTransaction};
+use alloc::{vec, vec::Vec};
+use alloy_eips::eip2718::Encodable2718;
 use alloy_primitives::{address, bytes, Address, Bytes, TxKind, U256};
-use alloy_rlp::Encodable;
 use op_alloy_consensus::{OpTxEnvelope, TxDeposit};
 use spin::Lazy;
 
@@ -30,7 +30,7 @@ pub const NEW_L1_BLOCK_ADDRESS: Address = address!("07dbe8500fc591d1852b76fee44d
 pub const GAS_PRICE_ORACLE_ADDRESS: Address = address!("b528d11cc114e026f138fe568744c6d45ce6da7a");
 
 /// The Enable Ecotone Input Method 4Byte Signature
-pub const ENABLE_ECOTONE_INPUT: &[u8] = &[0x22, 0xb9, 0x08, 0xb3];
+pub const ENABLE_ECOTONE_INPUT: &[u8] = &[0x22, 0xb9, 0x0a, 0xb3];
 
 /// UpgradeTo Function 4Byte Signature
 pub const UPGRADE_TO_FUNC_BYTES_4: &[u8] = &[0x36, 0x59, 0xcf, 0xe6];
@@ -38,32 +38,39 @@ pub const UPGRADE_TO_FUNC_BYTES_4: &[u8] = &[0x36, 0x59, 0xcf, 0xe6];
 /// EIP-4788 From Address
 pub const EIP4788_FROM: Address = address!("0B799C86a49DEeb90402691F1041aa3AF2d3C875");
 
-static DEPLOY_L1_BLOCK_SOURCE: Lazy<UpgradeDepositSource> =
-    Lazy::new(|| UpgradeDepositSource { intent: String::from("Ecotone: L1 Block Deployment") });
+/// The L1 Block Proxy Address
+pub const L1_BLOCK_PROXY_ADDRESS: Address = address!("4200000000000000000000000000000000000015");
 
-static DEPLOY_GAS_PRICE_ORACLE_SOURCE: Lazy<UpgradeDepositSource> = Lazy::new(|| {
-    UpgradeDepositSource { intent: String::from("Ecotone: Gas Price Oracle Deployment") }
-});
+/// The Gas Price Oracle Proxy Address
+pub const GAS_PRICE_ORACLE_PROXY_ADDRESS: Address =
+    address!("420000000000000000000000000000000000000F");
 
-static UPDATE_L1_BLOCK_PROXY_SOURCE: Lazy<UpgradeDepositSource> =
-    Lazy::new(|| UpgradeDepositSource { intent: String::from("Ecotone: L1 Block Proxy Update") });
+/// The L1 Info Depositor Address
+pub const L1_INFO_DEPOSITOR_ADDRESS: Address = address!("deaddeaddeaddeaddeaddeaddeaddeaddead0001");
 
-static UPDATE_GAS_PRICE_ORACLE_SOURCE: Lazy<UpgradeDepositSource> = Lazy::new(|| {
-    UpgradeDepositSource { intent: String::from("Ecotone: Gas Price Oracle Proxy Update") }
-});
+static DEPLOY_L1_BLOCK_SOURCE: Lazy<DepositSourceDomain> =
+    Lazy::new(|| DepositSourceDomain::upgrade("Ecotone: L1 Block Deployment"));
 
-static ENABLE_ECOTONE_SOURCE: Lazy<UpgradeDepositSource> = Lazy::new(|| UpgradeDepositSource {
-    intent: String::from("Ecotone: Gas Price Oracle Set Ecotone"),
-});
+static DEPLOY_GAS_PRICE_ORACLE_SOURCE: Lazy<DepositSourceDomain> =
+    Lazy::new(|| DepositSourceDomain::upgrade("Ecotone: Gas Price Oracle Deployment"));
 
-static BEACON_ROOTS_SOURCE: Lazy<UpgradeDepositSource> = Lazy::new(|| UpgradeDepositSource {
-    intent: String::from("Ecotone: beacon block roots contract deployment"),
-});
+static UPDATE_L1_BLOCK_PROXY_SOURCE: Lazy<DepositSourceDomain> =
+    Lazy::new(|| DepositSourceDomain::upgrade("Ecotone: L1 Block Proxy Update"));
+
+static UPDATE_GAS_PRICE_ORACLE_SOURCE: Lazy<DepositSourceDomain> =
+    Lazy::new(|| DepositSourceDomain::upgrade("Ecotone: Gas Price Oracle Proxy Update"));
+
+static ENABLE_ECOTONE_SOURCE: Lazy<DepositSourceDomain> =
+    Lazy::new(|| DepositSourceDomain::upgrade("Ecotone: Gas Price Oracle Set Ecotone"));
+
+static BEACON_ROOTS_SOURCE: Lazy<DepositSourceDomain> =
+    Lazy::new(|| DepositSourceDomain::upgrade("Ecotone: beacon block roots contract deployment"));
 
 /// Turns the given address into calldata for the `upgradeTo` function.
+/// The address is ABI encoded, so it is left padded to 32 bytes.
 pub fn upgrade_to_calldata(addr: Address) -> Bytes {
     let mut v = UPGRADE_TO_FUNC_BYTES_4.to_vec();
-    v.extend_from_slice(addr.as_slice());
+    v.extend_from_slice(addr.into_word().as_slice());
     Bytes::from(v)
 }
 
@@ -95,7 +102,7 @@ impl EcotoneTransactionBuilder {
             is_system_transaction: false,
             input: l1_block_deployment_bytecode,
         })
-        .encode(&mut buffer);
+        .encode_2718(&mut buffer);
         txs.push(RawTransaction::from(buffer));
 
         // Deploy the Gas Price Oracle
@@ -110,7 +117,7 @@ impl EcotoneTransactionBuilder {
             is_system_transaction: false,
             input: gas_price_oracle_deployment_bytecode,
         })
-        .encode(&mut buffer);
+        .encode_2718(&mut buffer);
         txs.push(RawTransaction::from(buffer));
 
         // Update the l1 block proxy
@@ -118,14 +125,14 @@ impl EcotoneTransactionBuilder {
         OpTxEnvelope::Deposit(TxDeposit {
             source_hash: UPDATE_L1_BLOCK_PROXY_SOURCE.source_hash(),
             from: Address::default(),
-            to: TxKind::Call(L1_BLOCK_DEPLOYER_ADDRESS),
+            to: TxKind::Call(L1_BLOCK_PROXY_ADDRESS),
             mint: 0.into(),
             value: U256::ZERO,
             gas_limit: 50_000,
             is_system_transaction: false,
             input: upgrade_to_calldata(NEW_L1_BLOCK_ADDRESS),
         })
-        .encode(&mut buffer);
+        .encode_2718(&mut buffer);
         txs.push(RawTransaction::from(buffer));
 
         // Update gas price oracle proxy
@@ -133,29 +140,29 @@ impl EcotoneTransactionBuilder {
         OpTxEnvelope::Deposit(TxDeposit {
             source_hash: UPDATE_GAS_PRICE_ORACLE_SOURCE.source_hash(),
             from: Address::default(),
-            to: TxKind::Call(GAS_PRICE_ORACLE_DEPLOYER_ADDRESS),
+            to: TxKind::Call(GAS_PRICE_ORACLE_PROXY_ADDRESS),
             mint: 0.into(),
             value: U256::ZERO,
             gas_limit: 50_000,
             is_system_transaction: false,
             input: upgrade_to_calldata(GAS_PRICE_ORACLE_ADDRESS),
         })
-        .encode(&mut buffer);
+        .encode_2718(&mut buffer);
         txs.push(RawTransaction::from(buffer));
 
         // Enable ecotone
         buffer = Vec::new();
         OpTxEnvelope::Deposit(TxDeposit {
             source_hash: ENABLE_ECOTONE_SOURCE.source_hash(),
-            from: L1_BLOCK_DEPLOYER_ADDRESS,
-            to: TxKind::Call(GAS_PRICE_ORACLE_ADDRESS),
+            from: L1_INFO_DEPOSITOR_ADDRESS,
+            to: TxKind::Call(GAS_PRICE_ORACLE_PROXY_ADDRESS),
             mint: 0.into(),
             value: U256::ZERO,
             gas_limit: 80_000,
             is_system_transaction: false,
             input: ENABLE_ECOTONE_INPUT.into(),
         })
-        .encode(&mut buffer);
+        .encode_2718(&mut buffer);
         txs.push(RawTransaction::from(buffer));
 
         // Deploy EIP4788
@@ -170,9 +177,82 @@ impl EcotoneTransactionBuilder {
             is_system_transaction: false,
             input: eip4788_creation_data,
         })
-        .encode(&mut buffer);
+        .encode_2718(&mut buffer);
         txs.push(RawTransaction::from(buffer));
 
         Ok(txs)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloy_primitives::{b256, keccak256, B256};
+
+    #[test]
+    fn test_upgrade_to_calldata() {
+        let calldata = upgrade_to_calldata(NEW_L1_BLOCK_ADDRESS);
+        assert_eq!(calldata.len(), 4 + 32);
+        assert_eq!(&calldata[..4], UPGRADE_TO_FUNC_BYTES_4);
+        assert_eq!(&calldata[4..16], &[0u8; 12]);
+        assert_eq!(&calldata[16..], NEW_L1_BLOCK_ADDRESS.as_slice());
+    }
+
+    #[test]
+    fn test_function_selectors() {
+        assert_eq!(ENABLE_ECOTONE_INPUT, &keccak256("setEcotone()")[..4]);
+        assert_eq!(UPGRADE_TO_FUNC_BYTES_4, &keccak256("upgradeTo(address)")[..4]);
+    }
+
+    #[test]
+    fn test_ecotone_upgrade_source_hashes() {
+        let expected: [(B256, B256); 6] = [
+            (
+                DEPLOY_L1_BLOCK_SOURCE.source_hash(),
+                b256!("877a6077205782ea15a6dc8699fa5ebcec5e0f4389f09cb8eda09488231346f8"),
+            ),
+            (
+                DEPLOY_GAS_PRICE_ORACLE_SOURCE.source_hash(),
+                b256!("a312b4510adf943510f05fcc8f15f86995a5066bd83ce11384688ae20e6ecf42"),
+            ),
+            (
+                UPDATE_L1_BLOCK_PROXY_SOURCE.source_hash(),
+                b256!("18acb38c5ff1c238a7460ebc1b421fa49ec4874bdf1e0a530d234104e5e67dbc"),
+            ),
+            (
+                UPDATE_GAS_PRICE_ORACLE_SOURCE.source_hash(),
+                b256!("ee4f9385eceef498af0be7ec5862229f426dec41c8d42397c7257a5117d9230a"),
+            ),
+            (
+                ENABLE_ECOTONE_SOURCE.source_hash(),
+                b256!("0c1cb38e99dbc9cbfab3bb80863380b0905290b37eb3d6ab18dc01c1f3e75f93"),
+            ),
+            (
+                BEACON_ROOTS_SOURCE.source_hash(),
+                b256!("69b763c48478b9dc2f65ada09b3d92133ec592ea715ec65ad6e7f3dc519dc00c"),
+            ),
+        ];
+        for (source_hash, expected) in expected {
+            assert_eq!(source_hash, expected);
+        }
+    }
+
+    #[test]
+    fn test_ecotone_upgrade_tx_hashes() {
+        // The hashes of the Ecotone upgrade transactions included in the Ecotone activation block
+        // of OP Mainnet. Deposits do not commit to a chain ID, so they are the same on every chain.
+        let expected: [B256; 6] = [
+            b256!("50549bed9a83ba7238893970780b458ed3d543f299d6f69f7a5d0b32d7cd71a7"),
+            b256!("f8e8dd83c100c9eb137fa372cdda2cf854f79b6e8cfc604c5e54d970c05cb6dc"),
+            b256!("9f2b2d34dfa2cb55cceb9860cade0cb03cfbd7ff1dd07d48b4708b29a46b4a24"),
+            b256!("edbfdad1f44ea830b863eac45eca4408398351b30511826012882059625963d2"),
+            b256!("91f762393442ac507c6852211b62445d82c9b58bae34726ecef2f10b4156f027"),
+            b256!("5b7915f2a55ce67519e6d8f65248918f4e62f545706af2bf02549bb9782c03f3"),
+        ];
+        let txs = EcotoneTransactionBuilder::build_txs().unwrap();
+        assert_eq!(txs.len(), expected.len());
+        for (tx, expected) in txs.iter().zip(expected) {
+            assert_eq!(keccak256(tx), expected);
+        }
+    }
+}
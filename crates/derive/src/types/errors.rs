@@ -128,6 +128,8 @@ impl Display for StageError {
 pub enum BlobProviderError {
     /// The number of specified blob hashes did not match the number of returned sidecars.
     SidecarLengthMismatch(usize, usize),
+    /// No blob sidecars were found for the given slot.
+    NotFound(u64),
     /// Slot derivation error.
     Slot(anyhow::Error),
     /// A custom [anyhow::Error] occurred.
@@ -140,6 +142,7 @@ impl PartialEq for BlobProviderError {
             (Self::SidecarLengthMismatch(a, b), Self::SidecarLengthMismatch(c, d)) => {
                 a == c && b == d
             }
+            (Self::NotFound(a), Self::NotFound(b)) => a == b,
             (Self::Slot(_), Self::Slot(_)) | (Self::Custom(_), Self::Custom(_)) => true,
             _ => false,
         }
@@ -150,6 +153,7 @@ impl Display for BlobProviderError {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         match self {
             Self::SidecarLengthMismatch(a, b) => write!(f, "expected {} sidecars but got {}", a, b),
+            Self::NotFound(slot) => write!(f, "blob sidecars not found for slot {}", slot),
             Self::Slot(e) => {
                 write!(f, "Slot Derivation Error: {}", e)
             }
@@ -320,3 +324,123 @@ impl Display for BuilderError {
         }
     }
 }
+
+/// An error returned by a [crate::traits::ChainProvider].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChainProviderError {
+    /// The parent hash of a newly fetched block does not match the hash of the previously cached
+    /// block at `number`, meaning the L1 chain has reorged at or below `number`.
+    ReorgDetected {
+        /// The number of the block that was reorged.
+        number: u64,
+        /// The hash of the previously cached block at `number`.
+        old_hash: B256,
+        /// The hash of the new block at `number`, as referenced by its child.
+        new_hash: B256,
+    },
+}
+
+impl Display for ChainProviderError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ChainProviderError::ReorgDetected { number, old_hash, new_hash } => {
+                write!(f, "Reorg detected at block {}: {} -> {}", number, old_hash, new_hash)
+            }
+        }
+    }
+}
+
+/// An error returned when parsing [Frame]s from the data of an L1 transaction. Offsets are
+/// relative to the start of the data, including the derivation version byte.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameParseError {
+    /// There is no data to parse.
+    NoData,
+    /// The derivation version byte is not supported.
+    UnsupportedVersion(u8),
+    /// The frame starting at `offset` is truncated.
+    Truncated {
+        /// The offset of the start of the frame.
+        offset: usize,
+    },
+    /// The frame starting at `offset` has more data than [crate::params::MAX_FRAME_LEN].
+    FrameTooLarge {
+        /// The offset of the start of the frame.
+        offset: usize,
+        /// The length of the frame data.
+        len: usize,
+    },
+    /// The `is_last` byte of the frame starting at `offset` is neither `0` nor `1`.
+    InvalidIsLast {
+        /// The offset of the start of the frame.
+        offset: usize,
+        /// The invalid `is_last` byte.
+        byte: u8,
+    },
+}
+
+impl Display for FrameParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FrameParseError::NoData => write!(f, "No frames to parse"),
+            FrameParseError::UnsupportedVersion(version) => {
+                write!(f, "Unsupported derivation version {}", version)
+            }
+            FrameParseError::Truncated { offset } => {
+                write!(f, "Truncated frame at offset {}", offset)
+            }
+            FrameParseError::FrameTooLarge { offset, len } => {
+                write!(f, "Frame data too large at offset {}: {} bytes", offset, len)
+            }
+            FrameParseError::InvalidIsLast { offset, byte } => {
+                write!(f, "Invalid is_last byte {} in frame at offset {}", byte, offset)
+            }
+        }
+    }
+}
+
+/// An error returned when building a channel with a [ChannelOut].
+///
+/// [ChannelOut]: crate::types::ChannelOut
+#[cfg(any(test, feature = "test-utils"))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChannelOutError {
+    /// The frames of the channel were already output, and it no longer accepts batches.
+    ChannelClosed,
+    /// Adding the batch would grow the channel beyond the maximum RLP bytes per channel.
+    MaxRlpBytesExceeded {
+        /// The size of the channel with the batch added.
+        size: usize,
+        /// The maximum RLP bytes per channel.
+        max: usize,
+    },
+    /// The maximum frame size leaves no room for frame data.
+    MaxFrameSizeTooSmall(usize),
+    /// The channel data needs more frames than a frame number can address.
+    TooManyFrames(usize),
+    /// The batch could not be encoded.
+    BatchEncoding(SpanBatchError),
+}
+
+#[cfg(any(test, feature = "test-utils"))]
+impl Display for ChannelOutError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ChannelOutError::ChannelClosed => write!(f, "Channel is closed"),
+            ChannelOutError::MaxRlpBytesExceeded { size, max } => {
+                write!(f, "Channel size {} exceeds the maximum of {} RLP bytes", size, max)
+            }
+            ChannelOutError::MaxFrameSizeTooSmall(size) => {
+                write!(f, "Maximum frame size {} leaves no room for frame data", size)
+            }
+            ChannelOutError::TooManyFrames(count) => {
+                write!(
+                    f,
+                    "Channel data needs {} frames, more than a frame number can address",
+                    count
+                )
+            }
+            ChannelOutError::BatchEncoding(e) => write!(f, "Failed to encode batch: {}", e),
+        }
+    }
+}
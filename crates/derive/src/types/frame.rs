@@ -1,6 +1,9 @@
 //! This module contains the [Frame] type used within the derivation pipeline.
 
-use crate::params::{ChannelID, DERIVATION_VERSION_0, FRAME_OVERHEAD, MAX_FRAME_LEN};
+use crate::{
+    params::{ChannelID, DERIVATION_VERSION_0, FRAME_OVERHEAD, MAX_FRAME_LEN},
+    types::FrameParseError,
+};
 use alloc::vec::Vec;
 use anyhow::{anyhow, bail, Result};
 
@@ -14,6 +17,7 @@ use anyhow::{anyhow, bail, Result};
 /// * frame_data        = bytes
 /// * is_last           = bool
 #[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Frame {
     /// The unique idetifier for the frame.
     pub id: ChannelID,
@@ -39,25 +43,36 @@ impl Frame {
 
     /// Decode a frame from a byte vector.
     pub fn decode(encoded: &[u8]) -> Result<(usize, Self)> {
+        Self::decode_at(encoded, 0).map_err(|e| anyhow!(e))
+    }
+
+    /// Decodes the frame at the start of `encoded`, reporting errors at the given `offset`.
+    fn decode_at(encoded: &[u8], offset: usize) -> Result<(usize, Self), FrameParseError> {
         const BASE_FRAME_LEN: usize = 16 + 2 + 4 + 1;
 
         if encoded.len() < BASE_FRAME_LEN {
-            bail!("Frame too short to decode");
+            return Err(FrameParseError::Truncated { offset });
         }
 
-        let id = encoded[..16].try_into().map_err(|e| anyhow!("Error: {e}"))?;
-        let number =
-            u16::from_be_bytes(encoded[16..18].try_into().map_err(|e| anyhow!("Error: {e}"))?);
+        let mut id = ChannelID::default();
+        id.copy_from_slice(&encoded[..16]);
+        let number = u16::from_be_bytes([encoded[16], encoded[17]]);
         let data_len =
-            u32::from_be_bytes(encoded[18..22].try_into().map_err(|e| anyhow!("Error: {e}"))?)
-                as usize;
+            u32::from_be_bytes([encoded[18], encoded[19], encoded[20], encoded[21]]) as usize;
 
         if data_len > MAX_FRAME_LEN {
-            bail!("Frame data too large");
+            return Err(FrameParseError::FrameTooLarge { offset, len: data_len });
+        }
+        if encoded.len() < BASE_FRAME_LEN + data_len {
+            return Err(FrameParseError::Truncated { offset });
         }
 
         let data = encoded[22..22 + data_len].to_vec();
-        let is_last = encoded[22 + data_len] != 0;
+        let is_last = match encoded[22 + data_len] {
+            0 => false,
+            1 => true,
+            byte => return Err(FrameParseError::InvalidIsLast { offset, byte }),
+        };
         Ok((BASE_FRAME_LEN + data_len, Self { id, number, data, is_last }))
     }
 
@@ -70,30 +85,43 @@ impl Frame {
     /// * `data = DerivationVersion0 ++ Frame(s)`
     /// Where there is one or more frames concatenated together.
     pub fn parse_frames(encoded: &[u8]) -> Result<Vec<Self>> {
-        if encoded.is_empty() {
-            bail!("No frames to parse");
+        let (frames, err) = Self::parse_frames_lossy(encoded);
+        if let Some(err) = err {
+            bail!(err);
         }
-        if encoded[0] != DERIVATION_VERSION_0 {
-            bail!("Unsupported derivation version");
+        if frames.is_empty() {
+            bail!("No frames decoded");
         }
 
-        let data = &encoded[1..];
-        let mut frames = Vec::new();
-        let mut offset = 0;
-        while offset < data.len() {
-            let (frame_length, frame) = Self::decode(&data[offset..])?;
-            frames.push(frame);
-            offset += frame_length;
-        }
+        Ok(frames)
+    }
 
-        if offset != data.len() {
-            bail!("Frame data length mismatch");
+    /// Parses the on chain serialization of frame(s) in an L1 transaction, keeping every frame
+    /// that was parsed before the first malformed frame. The error that stopped parsing, if any,
+    /// is returned alongside the frames.
+    ///
+    /// This is meant for diagnostics only. Derivation drops every frame of a transaction that
+    /// fails to parse, see [Frame::parse_frames].
+    pub fn parse_frames_lossy(encoded: &[u8]) -> (Vec<Self>, Option<FrameParseError>) {
+        let Some(version) = encoded.first() else {
+            return (Vec::new(), Some(FrameParseError::NoData));
+        };
+        if *version != DERIVATION_VERSION_0 {
+            return (Vec::new(), Some(FrameParseError::UnsupportedVersion(*version)));
         }
-        if frames.is_empty() {
-            bail!("No frames decoded");
+
+        let mut frames = Vec::new();
+        let mut offset = 1;
+        while offset < encoded.len() {
+            let (frame_length, frame) = match Self::decode_at(&encoded[offset..], offset) {
+                Ok(decoded) => decoded,
+                Err(e) => return (frames, Some(e)),
+            };
+            offset += frame_length;
+            frames.push(frame);
         }
 
-        Ok(frames)
+        (frames, None)
     }
 
     /// Calculates the size of the frame + overhead for storing the frame. The sum of the frame size
@@ -135,4 +163,96 @@ mod test {
             assert_eq!(frames[i], frame);
         });
     }
+
+    #[test]
+    fn test_decode_truncated_length_field() {
+        let mut bytes = std::vec![DERIVATION_VERSION_0];
+        bytes.extend_from_slice(&[0xFF; 16]);
+        bytes.extend_from_slice(&[0x00, 0x01, 0x00, 0x00]);
+
+        let (frames, err) = Frame::parse_frames_lossy(&bytes);
+        assert!(frames.is_empty());
+        assert_eq!(err, Some(FrameParseError::Truncated { offset: 1 }));
+        assert!(Frame::parse_frames(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_decode_truncated_frame_data() {
+        let frame = Frame { id: [0xFF; 16], number: 0, data: std::vec![0xDD; 50], is_last: false };
+        let encoded = frame.encode();
+        let mut bytes = std::vec![DERIVATION_VERSION_0];
+        bytes.extend_from_slice(&encoded);
+        bytes.extend_from_slice(&encoded[..encoded.len() - 2]);
+
+        let (frames, err) = Frame::parse_frames_lossy(&bytes);
+        assert_eq!(frames, std::vec![frame]);
+        assert_eq!(err, Some(FrameParseError::Truncated { offset: 1 + encoded.len() }));
+        assert!(Frame::parse_frames(&bytes).is_err());
+    }
+
+    #[test]
+    fn test_decode_oversized_frame_data() {
+        let frame = Frame { id: [0xFF; 16], number: 0, data: std::vec![0xDD; 50], is_last: false };
+        let encoded = frame.encode();
+        let mut bytes = std::vec![DERIVATION_VERSION_0];
+        bytes.extend_from_slice(&encoded);
+        bytes.extend_from_slice(&[0xEE; 16]);
+        bytes.extend_from_slice(&[0x00, 0x00]);
+        bytes.extend_from_slice(&(MAX_FRAME_LEN as u32 + 1).to_be_bytes());
+        bytes.extend_from_slice(&[0xDD; 32]);
+
+        let (frames, err) = Frame::parse_frames_lossy(&bytes);
+        assert_eq!(frames, std::vec![frame]);
+        assert_eq!(
+            err,
+            Some(FrameParseError::FrameTooLarge {
+                offset: 1 + encoded.len(),
+                len: MAX_FRAME_LEN + 1
+            })
+        );
+    }
+
+    #[test]
+    fn test_decode_invalid_is_last() {
+        let frame = Frame { id: [0xFF; 16], number: 0, data: std::vec![0xDD; 4], is_last: true };
+        let mut bytes = std::vec![DERIVATION_VERSION_0];
+        bytes.extend_from_slice(&frame.encode());
+        *bytes.last_mut().unwrap() = 0x02;
+
+        let (frames, err) = Frame::parse_frames_lossy(&bytes);
+        assert!(frames.is_empty());
+        assert_eq!(err, Some(FrameParseError::InvalidIsLast { offset: 1, byte: 0x02 }));
+    }
+
+    #[test]
+    fn test_decode_unsupported_version() {
+        assert_eq!(Frame::parse_frames_lossy(&[]), (Vec::new(), Some(FrameParseError::NoData)));
+        assert_eq!(
+            Frame::parse_frames_lossy(&[0x01]),
+            (Vec::new(), Some(FrameParseError::UnsupportedVersion(0x01)))
+        );
+    }
+
+    #[test]
+    fn test_decode_keeps_frames_after_last() {
+        let frame = |id: u8, number: u16, is_last: bool| Frame {
+            id: [id; 16],
+            number,
+            data: std::vec![0xDD; 8],
+            is_last,
+        };
+        let all = [
+            frame(0xAA, 0, false),
+            frame(0xAA, 1, true),
+            frame(0xBB, 0, false),
+            frame(0xAA, 2, false),
+            frame(0xAA, 1, true),
+            frame(0xBB, 1, true),
+        ];
+        let mut bytes = std::vec![DERIVATION_VERSION_0];
+        all.iter().for_each(|f| bytes.extend_from_slice(&f.encode()));
+
+        // Frames of a closed channel are left for the channel bank to accept or reject.
+        assert_eq!(Frame::parse_frames(&bytes).unwrap(), all);
+    }
 }
@@ -5,16 +5,19 @@ pub use kona_primitives::*;
 
 pub mod batch;
 pub use batch::{
-    Batch, BatchType, BatchValidity, BatchWithInclusionBlock, RawSpanBatch, SingleBatch, SpanBatch,
-    SpanBatchBits, SpanBatchEip1559TransactionData, SpanBatchEip2930TransactionData,
-    SpanBatchElement, SpanBatchError, SpanBatchLegacyTransactionData, SpanBatchPayload,
-    SpanBatchPrefix, SpanBatchTransactionData, SpanBatchTransactions, SpanDecodingError,
-    MAX_SPAN_BATCH_SIZE,
+    check_batch, Batch, BatchType, BatchValidity, BatchWithInclusionBlock, DropReason,
+    RawSpanBatch, SingleBatch, SpanBatch, SpanBatchBits, SpanBatchEip1559TransactionData,
+    SpanBatchEip2930TransactionData, SpanBatchElement, SpanBatchError,
+    SpanBatchLegacyTransactionData, SpanBatchPayload, SpanBatchPrefix, SpanBatchTransactionData,
+    SpanBatchTransactions, SpanDecodingError, MAX_SPAN_BATCH_SIZE,
 };
 
 mod ecotone;
 pub use ecotone::*;
 
+mod fjord;
+pub use fjord::*;
+
 mod blob;
 pub use blob::{Blob, BlobData, BlobDecodingError, IndexedBlobHash};
 
@@ -25,11 +28,30 @@ pub use sidecar::{
     VersionInformation, KZG_COMMITMENT_SIZE, KZG_PROOF_SIZE,
 };
 
+mod brotli;
+#[cfg(any(test, feature = "test-utils"))]
+pub use brotli::encode_stored_brotli;
+pub use brotli::{decompress_brotli, BrotliDecompressionError};
+
 mod frame;
 pub use frame::Frame;
 
+mod data_source;
+pub use data_source::{DataKind, DataSourceItem};
+
 mod channel;
 pub use channel::Channel;
 
+#[cfg(any(test, feature = "test-utils"))]
+mod channel_out;
+#[cfg(any(test, feature = "test-utils"))]
+pub use channel_out::ChannelOut;
+
+mod pipeline;
+pub use pipeline::{DriverConfig, Signal, StepResult};
+
+mod checkpoint;
+pub use checkpoint::PipelineCheckpoint;
+
 mod errors;
 pub use errors::*;
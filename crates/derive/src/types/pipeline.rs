@@ -0,0 +1,47 @@
+//! Contains the types used to drive the [DerivationPipeline].
+//!
+//! [DerivationPipeline]: crate::DerivationPipeline
+
+use crate::types::{BlockInfo, L2BlockInfo, StageError};
+
+/// The result of a single [Pipeline::step] call.
+///
+/// [Pipeline::step]: crate::traits::Pipeline::step
+#[derive(Debug, PartialEq)]
+pub enum StepResult {
+    /// The pipeline prepared a new set of payload attributes.
+    PreparedAttributes,
+    /// The pipeline ran out of data for the current origin and advanced to the next L1 origin.
+    AdvancedOrigin,
+    /// The pipeline ran out of data for the current origin, but failed to advance the origin.
+    OriginAdvanceErr(StageError),
+    /// The step failed with an error that was not an [StageError::Eof].
+    StepFailed(StageError),
+}
+
+/// A signal sent to the pipeline by its driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Signal {
+    /// Resets every stage of the pipeline, re-seeding the L1 origin.
+    Reset {
+        /// The L2 safe head to continue derivation from.
+        l2_safe_head: L2BlockInfo,
+        /// The L1 origin to reset the pipeline to.
+        l1_origin: BlockInfo,
+    },
+    /// Drops all buffered channel data without changing the L1 origin.
+    FlushChannel,
+}
+
+/// Configures how far the pipeline may follow the L1 chain, used by online drivers to stay clear
+/// of shallow L1 reorgs.
+///
+/// The default configuration does not bound the L1 origin, which is what the client program uses
+/// as its L1 chain is fixed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DriverConfig {
+    /// The number of L1 blocks the L1 origin stays behind the L1 head.
+    pub l1_confirmation_depth: u64,
+    /// Whether the L1 origin is bounded by the finalized L1 block instead of the L1 head.
+    pub use_finalized: bool,
+}
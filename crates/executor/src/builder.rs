@@ -0,0 +1,71 @@
+//! Contains the [StatelessL2BlockExecutorBuilder], which configures the EVM of a
+//! [StatelessL2BlockExecutor].
+
+use crate::{ExecutorLimits, StatelessL2BlockExecutor};
+use alloc::sync::Arc;
+use kona_mpt::{TrieDB, TrieHinter, TrieProvider};
+use kona_primitives::RollupConfig;
+use revm::{db::State, handler::register::EvmHandler};
+
+/// A handler register of the EVM of a [StatelessL2BlockExecutor], run on the handler of each
+/// block before it is executed.
+///
+/// Registers can replace any handle of the EVM, such as the precompiles loaded in
+/// `pre_execution.load_precompiles`, to swap heavy precompiles for versions accelerated by the
+/// host of a fault proof VM.
+pub type KonaHandleRegister<P, H> =
+    for<'i> fn(&mut EvmHandler<'i, (), &mut State<&mut TrieDB<P, H>>>);
+
+/// A builder for a [StatelessL2BlockExecutor].
+#[derive(Debug)]
+pub struct StatelessL2BlockExecutorBuilder<P, H>
+where
+    P: TrieProvider,
+    H: TrieHinter,
+{
+    /// The rollup config of the L2 chain.
+    config: Arc<RollupConfig>,
+    /// The state of the parent block.
+    trie_db: TrieDB<P, H>,
+    /// The handler register of the EVM, if any.
+    handle_register: Option<KonaHandleRegister<P, H>>,
+    /// The resource limits of the executor.
+    limits: ExecutorLimits,
+}
+
+impl<P, H> StatelessL2BlockExecutorBuilder<P, H>
+where
+    P: TrieProvider,
+    H: TrieHinter,
+{
+    /// Creates a new [StatelessL2BlockExecutorBuilder] for an executor that executes payloads on
+    /// top of the state served by the `trie_db`, with the plain revm handler and precompiles and
+    /// no resource limits.
+    pub fn new(config: Arc<RollupConfig>, trie_db: TrieDB<P, H>) -> Self {
+        Self { config, trie_db, handle_register: None, limits: ExecutorLimits::default() }
+    }
+
+    /// Sets the `handle_register` run on the handler of the EVM before each block is executed,
+    /// after the handle registers of the OP Stack.
+    pub fn with_handle_register(mut self, handle_register: KonaHandleRegister<P, H>) -> Self {
+        self.handle_register = Some(handle_register);
+        self
+    }
+
+    /// Sets the resource `limits` of the executor, which refuses to execute the payloads
+    /// exceeding them.
+    pub fn with_limits(mut self, limits: ExecutorLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    /// Builds the [StatelessL2BlockExecutor].
+    pub fn build(self) -> StatelessL2BlockExecutor<P, H> {
+        StatelessL2BlockExecutor {
+            config: self.config,
+            trie_db: self.trie_db,
+            handle_register: self.handle_register,
+            limits: self.limits,
+        }
+    }
+}
@@ -0,0 +1,49 @@
+//! Contains the force deployment of the `create2Deployer` contract in the Canyon activation block.
+
+use crate::ExecutorError;
+use alloc::vec;
+use alloy_primitives::{address, b256, Address, B256};
+use kona_primitives::RollupConfig;
+use revm::{
+    db::State,
+    primitives::{AccountInfo, Bytecode},
+    Database,
+};
+
+/// The address of the `create2Deployer` contract.
+pub(crate) const CREATE_2_DEPLOYER_ADDRESS: Address =
+    address!("13b0D85CcB8bf860b6b79AF3029fCA081AE9beF2");
+
+/// The code hash of the `create2Deployer` contract.
+pub(crate) const CREATE_2_DEPLOYER_CODE_HASH: B256 =
+    b256!("b0550b5b431e30d38000efb7107aaa0ade03d48a7198a140edda9d27134468b2");
+
+/// Returns whether the block with the given `timestamp`, built on top of a parent block with the
+/// `parent_timestamp`, is the first block after Canyon activated.
+pub(crate) fn is_canyon_activation_block(
+    config: &RollupConfig,
+    parent_timestamp: u64,
+    timestamp: u64,
+) -> bool {
+    config.is_canyon_active(timestamp) && !config.is_canyon_active(parent_timestamp)
+}
+
+/// Deploys the `create2Deployer` `code` to its address in the Canyon activation block, keeping the
+/// nonce and balance of the account, before any transaction of the block is executed.
+pub(crate) fn ensure_create2_deployer<DB: Database>(
+    state: &mut State<DB>,
+    code: Bytecode,
+) -> Result<(), ExecutorError>
+where
+    ExecutorError: From<DB::Error>,
+{
+    let account = state.load_cache_account(CREATE_2_DEPLOYER_ADDRESS)?;
+    let info = AccountInfo {
+        code_hash: CREATE_2_DEPLOYER_CODE_HASH,
+        code: Some(code),
+        ..account.account_info().unwrap_or_default()
+    };
+    let transition = account.change(info, Default::default());
+    state.apply_transition(vec![(CREATE_2_DEPLOYER_ADDRESS, transition)]);
+    Ok(())
+}
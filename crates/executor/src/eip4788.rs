@@ -0,0 +1,71 @@
+//! Contains the [EIP-4788] system call, which stores the parent beacon block root in the beacon
+//! roots contract at the start of every block after Ecotone.
+//!
+//! [EIP-4788]: https://eips.ethereum.org/EIPS/eip-4788
+
+use crate::ExecutorError;
+use alloc::string::ToString;
+use alloy_primitives::{address, Address, Bytes, B256, U256};
+use core::fmt::Display;
+use revm::{
+    primitives::{OptimismFields, TransactTo, TxEnv},
+    Database, DatabaseCommit, Evm,
+};
+
+/// The address the system call is sent from.
+pub(crate) const SYSTEM_ADDRESS: Address = address!("fffffffffffffffffffffffffffffffffffffffe");
+
+/// The address of the beacon roots contract.
+pub(crate) const BEACON_ROOTS_ADDRESS: Address =
+    address!("000F3df6D732807Ef1319fB7B8bB8522d0Beac02");
+
+/// The gas limit of the system call, which is not charged to the block.
+const SYSTEM_CALL_GAS_LIMIT: u64 = 30_000_000;
+
+/// Stores the `parent_beacon_block_root` in the beacon roots contract with a system call, before
+/// the transactions of the block are executed.
+///
+/// The call does not pay for gas and does not count towards the gas used by the block, so the
+/// base fee is lifted for its duration. The system address and the coinbase are dropped from the
+/// changes it commits, as the call must not touch them. The environment of the `evm` is restored
+/// once the call is done.
+pub(crate) fn apply_beacon_root_contract_call<DB>(
+    evm: &mut Evm<'_, (), DB>,
+    parent_beacon_block_root: B256,
+) -> Result<(), ExecutorError>
+where
+    DB: Database + DatabaseCommit,
+    DB::Error: Display,
+{
+    let previous_env = evm.context.evm.env.clone();
+
+    let env = &mut evm.context.evm.env;
+    env.tx = TxEnv {
+        caller: SYSTEM_ADDRESS,
+        gas_limit: SYSTEM_CALL_GAS_LIMIT,
+        gas_price: U256::ZERO,
+        transact_to: TransactTo::Call(BEACON_ROOTS_ADDRESS),
+        value: U256::ZERO,
+        data: Bytes::copy_from_slice(parent_beacon_block_root.as_slice()),
+        nonce: None,
+        chain_id: None,
+        optimism: OptimismFields {
+            source_hash: None,
+            mint: None,
+            is_system_transaction: Some(false),
+            enveloped_tx: Some(Bytes::new()),
+        },
+        ..Default::default()
+    };
+    env.block.gas_limit = U256::from(SYSTEM_CALL_GAS_LIMIT);
+    env.block.basefee = U256::ZERO;
+
+    let result = evm.transact();
+    evm.context.evm.env = previous_env;
+    let mut state = result.map_err(|e| ExecutorError::Evm(None, e.to_string()))?.state;
+
+    state.remove(&SYSTEM_ADDRESS);
+    state.remove(&evm.context.evm.env.block.coinbase);
+    evm.context.evm.db.commit(state);
+    Ok(())
+}
@@ -0,0 +1,104 @@
+//! Contains the [ExecutorError], returned by the [StatelessL2BlockExecutor].
+//!
+//! [StatelessL2BlockExecutor]: crate::StatelessL2BlockExecutor
+
+use crate::LimitKind;
+use alloc::string::String;
+use alloy_primitives::{Bytes, B64};
+use core::fmt::Display;
+use kona_mpt::TrieDBError;
+
+/// An error returned by the [StatelessL2BlockExecutor].
+///
+/// [StatelessL2BlockExecutor]: crate::StatelessL2BlockExecutor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExecutorError {
+    /// The payload attributes do not set the gas limit of the block.
+    MissingGasLimit,
+    /// The payload attributes do not set the parent beacon block root, which the block commits
+    /// to after Ecotone.
+    MissingParentBeaconBlockRoot,
+    /// The payload attributes do not set the EIP-1559 parameters, which the block commits to
+    /// after Holocene.
+    MissingEip1559Params,
+    /// The EIP-1559 parameters of the payload attributes set only one of the denominator and the
+    /// elasticity to zero.
+    InvalidEip1559Params(B64),
+    /// The `extraData` of the parent Holocene block does not hold valid EIP-1559 parameters.
+    InvalidHoloceneExtraData(Bytes),
+    /// The transaction at the given index of the payload could not be decoded.
+    InvalidTransaction(usize, String),
+    /// The transaction at the given index of the payload is of a type that is not supported on
+    /// L2.
+    UnsupportedTransactionType(usize),
+    /// The gas limit of the transaction at the given index exceeds the gas left in the block.
+    BlockGasLimitExceeded(usize),
+    /// The EVM failed to execute the transaction at the given index, or the system calls of the
+    /// block if there is none.
+    Evm(Option<usize>, String),
+    /// The block is built under a fork whose rules revm does not implement, so it can't be
+    /// executed correctly.
+    UnsupportedFork(&'static str),
+    /// The payload exceeds a limit of the [ExecutorLimits] of the executor, which refused to
+    /// execute it.
+    ///
+    /// [ExecutorLimits]: crate::ExecutorLimits
+    LimitExceeded {
+        /// The kind of the exceeded limit.
+        kind: LimitKind,
+        /// The value of the limit.
+        limit: u64,
+        /// The value of the payload, which exceeds the limit.
+        actual: u64,
+    },
+    /// The [TrieDB] failed to serve or update the state.
+    ///
+    /// [TrieDB]: kona_mpt::TrieDB
+    TrieDB(TrieDBError),
+}
+
+impl Display for ExecutorError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ExecutorError::MissingGasLimit => write!(f, "Payload attributes miss the gas limit"),
+            ExecutorError::MissingParentBeaconBlockRoot => {
+                write!(f, "Payload attributes miss the parent beacon block root")
+            }
+            ExecutorError::MissingEip1559Params => {
+                write!(f, "Payload attributes miss the EIP-1559 parameters")
+            }
+            ExecutorError::InvalidEip1559Params(params) => {
+                write!(f, "Invalid EIP-1559 parameters in payload attributes: {params}")
+            }
+            ExecutorError::InvalidHoloceneExtraData(extra_data) => {
+                write!(f, "Invalid extraData in Holocene parent block: {extra_data}")
+            }
+            ExecutorError::InvalidTransaction(index, e) => {
+                write!(f, "Failed to decode transaction {index}: {e}")
+            }
+            ExecutorError::UnsupportedTransactionType(index) => {
+                write!(f, "Transaction {index} has an unsupported type")
+            }
+            ExecutorError::BlockGasLimitExceeded(index) => {
+                write!(f, "Gas limit of transaction {index} exceeds the gas left in the block")
+            }
+            ExecutorError::Evm(Some(index), e) => {
+                write!(f, "Failed to execute transaction {index}: {e}")
+            }
+            ExecutorError::Evm(None, e) => write!(f, "Failed to execute system call: {e}"),
+            ExecutorError::UnsupportedFork(fork) => {
+                write!(f, "Blocks after {fork} are not supported by the executor")
+            }
+            ExecutorError::LimitExceeded { kind, limit, actual } => {
+                write!(f, "Payload exceeds the {kind} limit of {limit}: {actual}")
+            }
+            ExecutorError::TrieDB(e) => write!(f, "State access failed: {e}"),
+        }
+    }
+}
+
+impl From<TrieDBError> for ExecutorError {
+    fn from(e: TrieDBError) -> Self {
+        ExecutorError::TrieDB(e)
+    }
+}
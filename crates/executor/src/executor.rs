@@ -0,0 +1,657 @@
+//! Contains the [StatelessL2BlockExecutor], which executes L2 payloads on top of the state served
+//! by a [TrieDB].
+
+use crate::{
+    canyon::{
+        ensure_create2_deployer, is_canyon_activation_block, CREATE_2_DEPLOYER_ADDRESS,
+        CREATE_2_DEPLOYER_CODE_HASH,
+    },
+    eip4788::apply_beacon_root_contract_call,
+    header::header_template,
+    receipts::logs_bloom,
+    ExecutorError, ExecutorLimits, KonaHandleRegister, OpReceipt, StatelessL2BlockExecutorBuilder,
+};
+use alloc::{string::ToString, sync::Arc, vec::Vec};
+use alloy_consensus::Header;
+use alloy_eips::{eip2718::Decodable2718, eip2930::AccessList};
+use alloy_primitives::{Address, Bytes, SignatureError, TxKind, U256};
+use kona_mpt::{
+    code_by_hash, compute_receipts_root, TrieDB, TrieDBError, TrieHinter, TrieProvider,
+};
+use kona_primitives::{L2PayloadAttributes, RollupConfig};
+use op_alloy_consensus::OpTxEnvelope;
+use revm::{
+    db::{states::bundle_state::BundleRetention, State},
+    primitives::{
+        BlobExcessGasAndPrice, BlockEnv, CfgEnv, CfgEnvWithHandlerCfg, EnvWithHandlerCfg,
+        OptimismFields, SpecId, TransactTo, TxEnv,
+    },
+    Evm,
+};
+
+/// The outcome of executing an L2 payload with the [StatelessL2BlockExecutor].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExecutionOutcome {
+    /// The header of the block, which commits to the post state, the receipts and the logs of
+    /// the payload.
+    pub header: Header,
+    /// The receipts of the transactions of the block, in order.
+    pub receipts: Vec<OpReceipt>,
+}
+
+/// A stateless executor of L2 payloads, which executes the transactions of
+/// [L2PayloadAttributes] on top of the state of the parent block served by a [TrieDB].
+///
+/// The executor applies the system calls and the irregular state transitions of the OP Stack
+/// hardforks around the transactions, and returns the header of the block. Once a payload is
+/// executed, the [TrieDB] is advanced onto the block and serves its post state.
+///
+/// The EVM runs with the plain revm precompiles, unless a [KonaHandleRegister] is installed with
+/// the [StatelessL2BlockExecutorBuilder].
+///
+/// revm 8 does not know Fjord, so blocks after Fjord are rejected with
+/// [ExecutorError::UnsupportedFork] rather than executed with the wrong rules.
+#[derive(Debug)]
+pub struct StatelessL2BlockExecutor<P, H>
+where
+    P: TrieProvider,
+    H: TrieHinter,
+{
+    /// The rollup config of the L2 chain.
+    pub(crate) config: Arc<RollupConfig>,
+    /// The state of the parent block.
+    pub(crate) trie_db: TrieDB<P, H>,
+    /// The handler register of the EVM, if any.
+    pub(crate) handle_register: Option<KonaHandleRegister<P, H>>,
+    /// The resource limits of the executor.
+    pub(crate) limits: ExecutorLimits,
+}
+
+impl<P, H> StatelessL2BlockExecutor<P, H>
+where
+    P: TrieProvider,
+    H: TrieHinter,
+{
+    /// Creates a new [StatelessL2BlockExecutor] that executes payloads on top of the state served
+    /// by the `trie_db`, with the plain revm handler and precompiles and no resource limits.
+    pub fn new(config: Arc<RollupConfig>, trie_db: TrieDB<P, H>) -> Self {
+        Self::builder(config, trie_db).build()
+    }
+
+    /// Returns a [StatelessL2BlockExecutorBuilder] for a [StatelessL2BlockExecutor] that executes
+    /// payloads on top of the state served by the `trie_db`.
+    pub fn builder(
+        config: Arc<RollupConfig>,
+        trie_db: TrieDB<P, H>,
+    ) -> StatelessL2BlockExecutorBuilder<P, H> {
+        StatelessL2BlockExecutorBuilder::new(config, trie_db)
+    }
+
+    /// Returns the [TrieDB] serving the state of the executor.
+    pub const fn trie_db(&self) -> &TrieDB<P, H> {
+        &self.trie_db
+    }
+
+    /// Executes the transactions of the payload `attributes` on top of the parent block of the
+    /// [TrieDB], and returns the [ExecutionOutcome] of the block.
+    ///
+    /// The optional fields of the header are set by the hardforks active at the timestamp of the
+    /// block, as in `header_template`. The [TrieDB] is advanced onto the header once its state
+    /// root is computed.
+    ///
+    /// Payloads exceeding the [ExecutorLimits] of the executor are rejected with
+    /// [ExecutorError::LimitExceeded]: the gas limit and the number of transactions before
+    /// anything is executed, and the gas used after each transaction.
+    ///
+    /// Each deposit transaction executed after Canyon records the nonce of its depositor before
+    /// the deposit in its receipt, along with the deposit receipt version. Both are committed to
+    /// by the receipts root.
+    pub fn execute_payload(
+        &mut self,
+        attributes: L2PayloadAttributes,
+    ) -> Result<ExecutionOutcome, ExecutorError> {
+        self.limits.check_payload(&attributes)?;
+        let spec_id = self.spec_id(attributes.timestamp)?;
+
+        let parent = self.trie_db.parent_header().clone();
+        let mut header =
+            header_template(&self.config, &parent, self.trie_db.parent_hash(), &attributes)?;
+        let timestamp = header.timestamp;
+        let gas_limit = header.gas_limit;
+        let is_canyon = self.config.is_canyon_active(timestamp);
+        let is_ecotone = self.config.is_ecotone_active(timestamp);
+
+        let transactions = attributes
+            .transactions
+            .iter()
+            .enumerate()
+            .map(|(index, raw)| {
+                OpTxEnvelope::decode_2718(&mut raw.0.as_ref())
+                    .map(|tx| (raw.0.clone(), tx))
+                    .map_err(|e| ExecutorError::InvalidTransaction(index, e.to_string()))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // The bytecode of the `create2Deployer` is fetched before the state borrows the TrieDB.
+        let create2_deployer =
+            is_canyon_activation_block(&self.config, parent.timestamp, timestamp)
+                .then(|| {
+                    code_by_hash(
+                        self.trie_db.provider(),
+                        self.trie_db.hinter(),
+                        CREATE_2_DEPLOYER_CODE_HASH,
+                        CREATE_2_DEPLOYER_ADDRESS,
+                    )
+                })
+                .transpose()
+                .map_err(TrieDBError::Code)?;
+
+        let block_env = BlockEnv {
+            number: U256::from(header.number),
+            coinbase: attributes.fee_recipient,
+            timestamp: U256::from(timestamp),
+            gas_limit: U256::from(gas_limit),
+            basefee: U256::from(header.base_fee_per_gas.unwrap_or_default()),
+            difficulty: U256::ZERO,
+            prevrandao: Some(attributes.prev_randao),
+            blob_excess_gas_and_price: is_ecotone.then(|| BlobExcessGasAndPrice::new(0)),
+        };
+        let mut cfg_env = CfgEnv::default();
+        cfg_env.chain_id = self.config.l2_chain_id;
+        let mut cfg = CfgEnvWithHandlerCfg::new_with_spec_id(cfg_env, spec_id);
+        cfg.enable_optimism();
+
+        let handle_register = self.handle_register;
+        let mut state =
+            State::builder().with_database(&mut self.trie_db).with_bundle_update().build();
+        if let Some(code) = create2_deployer {
+            ensure_create2_deployer(&mut state, code)?;
+        }
+
+        let mut builder = Evm::builder().with_db(&mut state).with_env_with_handler_cfg(
+            EnvWithHandlerCfg::new_with_cfg_env(cfg, block_env, TxEnv::default()),
+        );
+        if let Some(handle_register) = handle_register {
+            builder = builder.append_handler_register(handle_register);
+        }
+        let mut evm = builder.build();
+
+        if let Some(parent_beacon_block_root) = header.parent_beacon_block_root {
+            apply_beacon_root_contract_call(&mut evm, parent_beacon_block_root)?;
+        }
+
+        let mut cumulative_gas_used = 0u64;
+        let mut receipts = Vec::with_capacity(transactions.len());
+        for (index, (encoded, tx)) in transactions.into_iter().enumerate() {
+            let tx_env = Self::prepare_tx_env(index, &tx, encoded)?;
+            if tx_env.gas_limit > gas_limit - cumulative_gas_used {
+                return Err(ExecutorError::BlockGasLimitExceeded(index));
+            }
+
+            let depositor_nonce = match &tx {
+                OpTxEnvelope::Deposit(deposit) if is_canyon => Some(
+                    evm.context
+                        .evm
+                        .db
+                        .load_cache_account(deposit.from)?
+                        .account_info()
+                        .map(|info| info.nonce)
+                        .unwrap_or_default(),
+                ),
+                _ => None,
+            };
+
+            evm.context.evm.env.tx = tx_env;
+            let result = evm
+                .transact_commit()
+                .map_err(|e| ExecutorError::Evm(Some(index), e.to_string()))?;
+
+            cumulative_gas_used += result.gas_used();
+            self.limits.check_gas_used(cumulative_gas_used)?;
+            let receipt = OpReceipt::new(
+                tx.tx_type(),
+                result.is_success(),
+                cumulative_gas_used,
+                result.into_logs(),
+            );
+            receipts.push(match depositor_nonce {
+                Some(nonce) => receipt.with_deposit_nonce(nonce),
+                None => receipt,
+            });
+        }
+        drop(evm);
+
+        state.merge_transitions(BundleRetention::Reverts);
+        let bundle = state.take_bundle();
+        header.state_root = self.trie_db.state_root(&bundle)?;
+        header.receipts_root = compute_receipts_root(&receipts);
+        header.logs_bloom = logs_bloom(&receipts);
+        header.gas_used = cumulative_gas_used;
+        self.trie_db.advance(header.clone())?;
+
+        Ok(ExecutionOutcome { header, receipts })
+    }
+
+    /// Returns the [SpecId] of the block with the given `timestamp`, or
+    /// [ExecutorError::UnsupportedFork] if revm does not implement the rules of the block.
+    fn spec_id(&self, timestamp: u64) -> Result<SpecId, ExecutorError> {
+        if self.config.is_fjord_active(timestamp) {
+            Err(ExecutorError::UnsupportedFork("Fjord"))
+        } else if self.config.is_ecotone_active(timestamp) {
+            Ok(SpecId::ECOTONE)
+        } else if self.config.is_canyon_active(timestamp) {
+            Ok(SpecId::CANYON)
+        } else if self.config.is_regolith_active(timestamp) {
+            Ok(SpecId::REGOLITH)
+        } else {
+            Ok(SpecId::BEDROCK)
+        }
+    }
+
+    /// Prepares the [TxEnv] of the `tx` at the given `index` of the payload, given its
+    /// [EIP-2718] `encoded` bytes, which the L1 data fee is charged for.
+    ///
+    /// [EIP-2718]: https://eips.ethereum.org/EIPS/eip-2718
+    fn prepare_tx_env(
+        index: usize,
+        tx: &OpTxEnvelope,
+        encoded: Bytes,
+    ) -> Result<TxEnv, ExecutorError> {
+        let invalid = |e: SignatureError| ExecutorError::InvalidTransaction(index, e.to_string());
+        let optimism = OptimismFields {
+            source_hash: None,
+            mint: None,
+            is_system_transaction: Some(false),
+            enveloped_tx: Some(encoded),
+        };
+
+        match tx {
+            OpTxEnvelope::Legacy(signed) => {
+                let tx = signed.tx();
+                Ok(TxEnv {
+                    caller: signed.recover_signer().map_err(invalid)?,
+                    gas_limit: tx.gas_limit as u64,
+                    gas_price: U256::from(tx.gas_price),
+                    transact_to: transact_to(tx.to),
+                    value: tx.value,
+                    data: tx.input.clone(),
+                    nonce: Some(tx.nonce),
+                    chain_id: tx.chain_id,
+                    optimism,
+                    ..Default::default()
+                })
+            }
+            OpTxEnvelope::Eip2930(signed) => {
+                let tx = signed.tx();
+                Ok(TxEnv {
+                    caller: signed.recover_signer().map_err(invalid)?,
+                    gas_limit: tx.gas_limit as u64,
+                    gas_price: U256::from(tx.gas_price),
+                    transact_to: transact_to(tx.to),
+                    value: tx.value,
+                    data: tx.input.clone(),
+                    nonce: Some(tx.nonce),
+                    chain_id: Some(tx.chain_id),
+                    access_list: access_list(&tx.access_list),
+                    optimism,
+                    ..Default::default()
+                })
+            }
+            OpTxEnvelope::Eip1559(signed) => {
+                let tx = signed.tx();
+                Ok(TxEnv {
+                    caller: signed.recover_signer().map_err(invalid)?,
+                    gas_limit: tx.gas_limit as u64,
+                    gas_price: U256::from(tx.max_fee_per_gas),
+                    gas_priority_fee: Some(U256::from(tx.max_priority_fee_per_gas)),
+                    transact_to: transact_to(tx.to),
+                    value: tx.value,
+                    data: tx.input.clone(),
+                    nonce: Some(tx.nonce),
+                    chain_id: Some(tx.chain_id),
+                    access_list: access_list(&tx.access_list),
+                    optimism,
+                    ..Default::default()
+                })
+            }
+            OpTxEnvelope::Deposit(tx) => Ok(TxEnv {
+                caller: tx.from,
+                gas_limit: tx.gas_limit as u64,
+                gas_price: U256::ZERO,
+                transact_to: transact_to(tx.to),
+                value: tx.value,
+                data: tx.input.clone(),
+                nonce: None,
+                chain_id: None,
+                optimism: OptimismFields {
+                    source_hash: Some(tx.source_hash),
+                    mint: tx.mint,
+                    is_system_transaction: Some(tx.is_system_transaction),
+                    ..optimism
+                },
+                ..Default::default()
+            }),
+            _ => Err(ExecutorError::UnsupportedTransactionType(index)),
+        }
+    }
+}
+
+/// Converts the destination of a transaction into a [TransactTo].
+fn transact_to(to: TxKind) -> TransactTo {
+    match to {
+        TxKind::Call(to) => TransactTo::Call(to),
+        TxKind::Create => TransactTo::create(),
+    }
+}
+
+/// Converts an [EIP-2930] access list into the access list of a [TxEnv].
+///
+/// [EIP-2930]: https://eips.ethereum.org/EIPS/eip-2930
+fn access_list(list: &AccessList) -> Vec<(Address, Vec<U256>)> {
+    list.0
+        .iter()
+        .map(|item| {
+            let keys = item.storage_keys.iter().map(|key| U256::from_be_bytes(key.0)).collect();
+            (item.address, keys)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::LimitKind;
+    use alloc::vec;
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_primitives::{address, b256, Bloom, B256};
+    use core::sync::atomic::{self, AtomicUsize};
+    use kona_mpt::{MapTrieProvider, NoopTrieHinter, SecureTrie, TrieAccount};
+    use op_alloy_consensus::{OpTxType, TxDeposit};
+    use revm::{
+        handler::register::EvmHandler,
+        precompile::{
+            secp256k1::ec_recover_run, Precompile, PrecompileResult, PrecompileSpecId, Precompiles,
+        },
+        ContextPrecompile, ContextPrecompiles,
+    };
+
+    const DEPOSITOR: Address = address!("deaddeaddeaddeaddeaddeaddeaddeaddead0001");
+    const RECIPIENT: Address = address!("deaddeaddeaddeaddeaddeaddeaddeaddead0002");
+    const ONE_ETHER: u128 = 1_000_000_000_000_000_000;
+
+    /// Returns the state root of a state holding the given accounts.
+    fn state_root(accounts: &[(Address, TrieAccount)]) -> B256 {
+        let mut trie = SecureTrie::new(TrieAccount::EMPTY_ROOT, MapTrieProvider::new());
+        for (address, account) in accounts {
+            trie.insert(address, alloy_rlp::encode(account).into()).unwrap();
+        }
+        trie.root()
+    }
+
+    /// Returns a [TrieDB] serving a parent block whose state only holds the [DEPOSITOR], with a
+    /// nonce of 3 and no balance.
+    fn trie_db() -> TrieDB<MapTrieProvider, NoopTrieHinter> {
+        let mut provider = MapTrieProvider::new();
+        let depositor = TrieAccount { nonce: 3, ..Default::default() };
+        let mut state = SecureTrie::new(TrieAccount::EMPTY_ROOT, MapTrieProvider::new());
+        state.insert(DEPOSITOR, alloy_rlp::encode(depositor).into()).unwrap();
+        let state_root = provider.insert_trie(state.root_node());
+
+        let parent = Header {
+            number: 10,
+            timestamp: 100,
+            gas_limit: 30_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            state_root,
+            ..Default::default()
+        };
+        TrieDB::new(parent, provider, NoopTrieHinter)
+    }
+
+    /// Builds an executor on top of the parent block served by [trie_db].
+    fn executor(config: RollupConfig) -> StatelessL2BlockExecutor<MapTrieProvider, NoopTrieHinter> {
+        StatelessL2BlockExecutor::new(Arc::new(config), trie_db())
+    }
+
+    /// Returns payload attributes holding a single deposit from the [DEPOSITOR] to `to`, which
+    /// mints the `value` it sends.
+    fn attributes_with_deposit(to: Address, value: u128, input: Bytes) -> L2PayloadAttributes {
+        let deposit = OpTxEnvelope::Deposit(TxDeposit {
+            source_hash: B256::repeat_byte(0x01),
+            from: DEPOSITOR,
+            to: TxKind::Call(to),
+            mint: (value > 0).then_some(value),
+            value: U256::from(value),
+            gas_limit: 100_000,
+            is_system_transaction: false,
+            input,
+        });
+        let mut encoded = Vec::new();
+        deposit.encode_2718(&mut encoded);
+
+        L2PayloadAttributes {
+            timestamp: 102,
+            fee_recipient: address!("4200000000000000000000000000000000000011"),
+            transactions: vec![encoded.into()],
+            gas_limit: Some(30_000_000),
+            ..Default::default()
+        }
+    }
+
+    /// Returns payload attributes holding a single deposit, minting one ether to the
+    /// [DEPOSITOR] and sending it to the [RECIPIENT].
+    fn deposit_attributes() -> L2PayloadAttributes {
+        attributes_with_deposit(RECIPIENT, ONE_ETHER, Bytes::new())
+    }
+
+    /// Returns the state root after the deposit of [deposit_attributes] was executed.
+    fn post_state_root() -> B256 {
+        state_root(&[
+            (DEPOSITOR, TrieAccount { nonce: 4, ..Default::default() }),
+            (RECIPIENT, TrieAccount { balance: U256::from(ONE_ETHER), ..Default::default() }),
+        ])
+    }
+
+    #[test]
+    fn test_execute_deposit_regolith() {
+        let config = RollupConfig { l2_chain_id: 10, regolith_time: Some(0), ..Default::default() };
+        let mut executor = executor(config);
+        let outcome = executor.execute_payload(deposit_attributes()).unwrap();
+
+        // The deposit nonce is not recorded in receipts before Canyon.
+        assert_eq!(outcome.receipts, vec![OpReceipt::new(OpTxType::Deposit, true, 21_000, vec![])]);
+        assert_eq!(outcome.header.gas_used, 21_000);
+        assert_eq!(outcome.header.logs_bloom, Bloom::ZERO);
+        assert_eq!(
+            outcome.header.receipts_root,
+            b256!("d19338b387dee74026dee1dd0c1e74b9a6a167331616c37d44419f8a1081d833")
+        );
+        assert_eq!(outcome.header.state_root, post_state_root());
+    }
+
+    #[test]
+    fn test_execute_deposit_canyon() {
+        let config = RollupConfig {
+            l2_chain_id: 10,
+            regolith_time: Some(0),
+            canyon_time: Some(0),
+            ..Default::default()
+        };
+        let mut executor = executor(config);
+        let outcome = executor.execute_payload(deposit_attributes()).unwrap();
+
+        // The receipt records the nonce of the depositor before the deposit.
+        let receipt = &outcome.receipts[0];
+        assert_eq!(receipt.deposit_nonce, Some(3));
+        assert_eq!(receipt.deposit_receipt_version, Some(DEPOSIT_RECEIPT_VERSION));
+        assert_eq!(
+            outcome.header.receipts_root,
+            b256!("37b83535c92b0cad2977cbb840e70955225b3a9d875d799c4bc6c7de7daf2cc8")
+        );
+        assert_eq!(outcome.header.state_root, post_state_root());
+        assert_eq!(outcome.header.withdrawals_root, Some(TrieAccount::EMPTY_ROOT));
+
+        // The TrieDB is advanced onto the executed block.
+        assert_eq!(executor.trie_db().parent_header(), &outcome.header);
+        assert_eq!(executor.trie_db().parent_hash(), outcome.header.hash_slow());
+    }
+
+    /// The number of calls to [counting_ecrecover].
+    static ECRECOVER_CALLS: AtomicUsize = AtomicUsize::new(0);
+
+    /// An `ecrecover` precompile that counts its calls, and defers to the revm implementation.
+    fn counting_ecrecover(input: &Bytes, gas_limit: u64) -> PrecompileResult {
+        ECRECOVER_CALLS.fetch_add(1, atomic::Ordering::SeqCst);
+        ec_recover_run(input, gas_limit)
+    }
+
+    /// A [KonaHandleRegister] replacing the `ecrecover` precompile with [counting_ecrecover].
+    fn counting_ecrecover_register(
+        handler: &mut EvmHandler<'_, (), &mut State<&mut TrieDB<MapTrieProvider, NoopTrieHinter>>>,
+    ) {
+        let spec_id = handler.cfg.spec_id;
+        handler.pre_execution.load_precompiles = Arc::new(move || {
+            let mut precompiles: ContextPrecompiles<_> =
+                Precompiles::new(PrecompileSpecId::from_spec_id(spec_id)).clone().into();
+            precompiles.extend([(
+                Address::with_last_byte(1),
+                ContextPrecompile::Ordinary(Precompile::Standard(counting_ecrecover)),
+            )]);
+            precompiles
+        });
+    }
+
+    #[test]
+    fn test_execute_payload_handle_register() {
+        let config = Arc::new(RollupConfig {
+            l2_chain_id: 10,
+            regolith_time: Some(0),
+            ..Default::default()
+        });
+        let attributes =
+            attributes_with_deposit(Address::with_last_byte(1), 0, Bytes::from(vec![0u8; 128]));
+
+        // The plain revm precompiles are used by default.
+        let mut executor = StatelessL2BlockExecutor::new(config.clone(), trie_db());
+        let expected = executor.execute_payload(attributes.clone()).unwrap();
+        assert_eq!(ECRECOVER_CALLS.load(atomic::Ordering::SeqCst), 0);
+
+        let mut executor = StatelessL2BlockExecutor::builder(config, trie_db())
+            .with_handle_register(counting_ecrecover_register)
+            .build();
+        let outcome = executor.execute_payload(attributes).unwrap();
+        assert_eq!(ECRECOVER_CALLS.load(atomic::Ordering::SeqCst), 1);
+        assert_eq!(outcome, expected);
+        assert_eq!(
+            outcome.header.state_root,
+            state_root(&[(DEPOSITOR, TrieAccount { nonce: 4, ..Default::default() })])
+        );
+    }
+
+    #[test]
+    fn test_execute_payload_missing_gas_limit() {
+        let mut executor = executor(RollupConfig::default());
+        let attributes = L2PayloadAttributes { gas_limit: None, ..deposit_attributes() };
+        assert_eq!(executor.execute_payload(attributes), Err(ExecutorError::MissingGasLimit));
+    }
+
+    #[test]
+    fn test_execute_payload_fjord_unsupported() {
+        let config = RollupConfig {
+            l2_chain_id: 10,
+            regolith_time: Some(0),
+            canyon_time: Some(0),
+            ecotone_time: Some(0),
+            fjord_time: Some(102),
+            ..Default::default()
+        };
+        let mut executor = executor(config);
+        let attributes = L2PayloadAttributes {
+            parent_beacon_block_root: Some(B256::ZERO),
+            ..deposit_attributes()
+        };
+        assert_eq!(
+            executor.execute_payload(attributes),
+            Err(ExecutorError::UnsupportedFork("Fjord"))
+        );
+    }
+
+    #[test]
+    fn test_execute_payload_block_gas_limit() {
+        let config = RollupConfig { l2_chain_id: 10, regolith_time: Some(0), ..Default::default() };
+        let mut executor = executor(config);
+        let attributes = L2PayloadAttributes { gas_limit: Some(99_999), ..deposit_attributes() };
+        assert_eq!(
+            executor.execute_payload(attributes),
+            Err(ExecutorError::BlockGasLimitExceeded(0))
+        );
+    }
+
+    /// Builds an executor with the given `limits` on top of the parent block served by
+    /// [trie_db], with Regolith active.
+    fn limited_executor(
+        limits: ExecutorLimits,
+    ) -> StatelessL2BlockExecutor<MapTrieProvider, NoopTrieHinter> {
+        let config = RollupConfig { l2_chain_id: 10, regolith_time: Some(0), ..Default::default() };
+        StatelessL2BlockExecutor::builder(Arc::new(config), trie_db()).with_limits(limits).build()
+    }
+
+    #[test]
+    fn test_execute_payload_limits_not_exceeded() {
+        let limits = ExecutorLimits {
+            max_gas_limit: Some(30_000_000),
+            max_transactions: Some(1),
+            max_gas_used: Some(21_000),
+        };
+        let outcome = limited_executor(limits).execute_payload(deposit_attributes()).unwrap();
+        assert_eq!(outcome.header.state_root, post_state_root());
+    }
+
+    #[test]
+    fn test_execute_payload_max_gas_limit() {
+        let limits = ExecutorLimits { max_gas_limit: Some(29_999_999), ..Default::default() };
+        let mut executor = limited_executor(limits);
+        let parent_hash = executor.trie_db().parent_hash();
+        assert_eq!(
+            executor.execute_payload(deposit_attributes()),
+            Err(ExecutorError::LimitExceeded {
+                kind: LimitKind::GasLimit,
+                limit: 29_999_999,
+                actual: 30_000_000
+            })
+        );
+        assert_eq!(executor.trie_db().parent_hash(), parent_hash);
+    }
+
+    #[test]
+    fn test_execute_payload_max_transactions() {
+        let mut attributes = deposit_attributes();
+        attributes.transactions.push(attributes.transactions[0].clone());
+
+        let limits = ExecutorLimits { max_transactions: Some(1), ..Default::default() };
+        assert_eq!(
+            limited_executor(limits).execute_payload(attributes),
+            Err(ExecutorError::LimitExceeded {
+                kind: LimitKind::TransactionCount,
+                limit: 1,
+                actual: 2
+            })
+        );
+    }
+
+    #[test]
+    fn test_execute_payload_max_gas_used() {
+        let limits = ExecutorLimits { max_gas_used: Some(20_999), ..Default::default() };
+        let mut executor = limited_executor(limits);
+        let parent_hash = executor.trie_db().parent_hash();
+        assert_eq!(
+            executor.execute_payload(deposit_attributes()),
+            Err(ExecutorError::LimitExceeded {
+                kind: LimitKind::GasUsed,
+                limit: 20_999,
+                actual: 21_000
+            })
+        );
+        assert_eq!(executor.trie_db().parent_hash(), parent_hash);
+    }
+}
@@ -0,0 +1,351 @@
+//! Contains the construction of the header of an L2 block, whose optional fields are set by the
+//! hardforks active at the timestamp of the block.
+
+use crate::ExecutorError;
+use alloy_consensus::Header;
+use alloy_primitives::{b256, Bloom, Bytes, B256, B64, U256};
+use core::cmp::Ordering;
+use kona_mpt::{compute_withdrawals_root, ordered_trie_root_with_encoder};
+use kona_primitives::{L2PayloadAttributes, RollupConfig};
+
+/// The ommers hash of a block without ommers, `keccak256(rlp([]))`.
+const EMPTY_OMMERS_HASH: B256 =
+    b256!("1dcc4de8dec75d7aab85b567b6ccd41ad312451b948a7413f0a142fd40d49347");
+
+/// The elasticity multiplier of the EIP-1559 base fee on the OP Stack, before Holocene.
+const BASE_FEE_ELASTICITY: u64 = 6;
+
+/// The base fee max change denominator before Canyon.
+const BASE_FEE_DENOMINATOR: u64 = 50;
+
+/// The base fee max change denominator from Canyon on, before Holocene.
+const BASE_FEE_DENOMINATOR_CANYON: u64 = 250;
+
+/// The version of the `extraData` of Holocene blocks, which holds their EIP-1559 parameters.
+const HOLOCENE_EXTRA_DATA_VERSION: u8 = 0;
+
+/// Returns the header of the block built from the payload `attributes` on top of the `parent`
+/// block, with the given `parent_hash`, before its transactions are executed.
+///
+/// Each optional field of the header is set or left out by the hardforks active at the timestamp
+/// of the block:
+/// - Canyon sets the withdrawals root, which commits to the empty withdrawals of the OP Stack.
+/// - Ecotone sets the blob gas used and the excess blob gas, which are always zero on L2, and the
+///   parent beacon block root of the `attributes`, which must then be set.
+/// - Holocene sets the `extraData` to the EIP-1559 parameters of the `attributes`, which must then
+///   be set.
+///
+/// The state root, receipts root, logs bloom and gas used are left empty, and are set once the
+/// transactions of the block were executed.
+pub(crate) fn header_template(
+    config: &RollupConfig,
+    parent: &Header,
+    parent_hash: B256,
+    attributes: &L2PayloadAttributes,
+) -> Result<Header, ExecutorError> {
+    let timestamp = attributes.timestamp;
+    let is_canyon = config.is_canyon_active(timestamp);
+    let is_ecotone = config.is_ecotone_active(timestamp);
+
+    let parent_beacon_block_root = is_ecotone
+        .then(|| {
+            attributes.parent_beacon_block_root.ok_or(ExecutorError::MissingParentBeaconBlockRoot)
+        })
+        .transpose()?;
+    let extra_data = if config.is_holocene_active(timestamp) {
+        holocene_extra_data(attributes.eip_1559_params)?
+    } else {
+        Bytes::new()
+    };
+    let withdrawals_root = is_canyon
+        .then(|| compute_withdrawals_root(attributes.withdrawals.as_deref().unwrap_or_default()));
+
+    Ok(Header {
+        parent_hash,
+        ommers_hash: EMPTY_OMMERS_HASH,
+        beneficiary: attributes.fee_recipient,
+        state_root: B256::ZERO,
+        transactions_root: ordered_trie_root_with_encoder(&attributes.transactions, |tx, buf| {
+            buf.put_slice(&tx.0)
+        }),
+        receipts_root: B256::ZERO,
+        withdrawals_root,
+        logs_bloom: Bloom::ZERO,
+        difficulty: U256::ZERO,
+        number: parent.number + 1,
+        gas_limit: attributes.gas_limit.ok_or(ExecutorError::MissingGasLimit)?,
+        gas_used: 0,
+        timestamp,
+        extra_data,
+        mix_hash: attributes.prev_randao,
+        base_fee_per_gas: Some(next_block_base_fee(config, parent, timestamp)?),
+        blob_gas_used: is_ecotone.then_some(0),
+        excess_blob_gas: is_ecotone.then_some(0),
+        parent_beacon_block_root,
+        ..Default::default()
+    })
+}
+
+/// Returns the EIP-1559 base fee of the block with the given `timestamp`, built on top of the
+/// `parent` block.
+///
+/// Before Holocene, the base fee parameters are the constants of the OP Stack, with the
+/// denominator raised by Canyon. From the first block after a Holocene block on, they are read
+/// from the `extraData` of the parent block.
+pub(crate) fn next_block_base_fee(
+    config: &RollupConfig,
+    parent: &Header,
+    timestamp: u64,
+) -> Result<u64, ExecutorError> {
+    let (denominator, elasticity) = if config.is_holocene_active(parent.timestamp) {
+        decode_holocene_extra_data(&parent.extra_data)?
+    } else if config.is_canyon_active(timestamp) {
+        (BASE_FEE_DENOMINATOR_CANYON, BASE_FEE_ELASTICITY)
+    } else {
+        (BASE_FEE_DENOMINATOR, BASE_FEE_ELASTICITY)
+    };
+    Ok(base_fee(parent, elasticity, denominator))
+}
+
+/// Returns the EIP-1559 base fee of the child of the `parent` block, given the `elasticity`
+/// multiplier and the base fee max change `denominator`.
+fn base_fee(parent: &Header, elasticity: u64, denominator: u64) -> u64 {
+    let base_fee = parent.base_fee_per_gas.unwrap_or_default();
+    let gas_target = parent.gas_limit / elasticity;
+    if gas_target == 0 {
+        return base_fee;
+    }
+
+    let delta = |gas_delta: u64| {
+        (base_fee as u128 * gas_delta as u128 / gas_target as u128 / denominator as u128) as u64
+    };
+    match parent.gas_used.cmp(&gas_target) {
+        Ordering::Equal => base_fee,
+        Ordering::Greater => base_fee + delta(parent.gas_used - gas_target).max(1),
+        Ordering::Less => base_fee.saturating_sub(delta(gas_target - parent.gas_used)),
+    }
+}
+
+/// Returns the `extraData` of a Holocene block: the version byte, followed by the base fee max
+/// change denominator and the elasticity multiplier of the EIP-1559 `params` of the payload, as
+/// big-endian u32s. Zero `params` select the constants of Canyon.
+fn holocene_extra_data(params: Option<B64>) -> Result<Bytes, ExecutorError> {
+    let params = params.ok_or(ExecutorError::MissingEip1559Params)?;
+    let (denominator, elasticity) = match split_eip1559_params(&params.0) {
+        (0, 0) => (BASE_FEE_DENOMINATOR_CANYON as u32, BASE_FEE_ELASTICITY as u32),
+        (0, _) | (_, 0) => return Err(ExecutorError::InvalidEip1559Params(params)),
+        params => params,
+    };
+
+    let mut extra_data = [0u8; 9];
+    extra_data[0] = HOLOCENE_EXTRA_DATA_VERSION;
+    extra_data[1..5].copy_from_slice(&denominator.to_be_bytes());
+    extra_data[5..].copy_from_slice(&elasticity.to_be_bytes());
+    Ok(Bytes::copy_from_slice(&extra_data))
+}
+
+/// Decodes the base fee max change denominator and the elasticity multiplier from the
+/// `extraData` of a Holocene block.
+fn decode_holocene_extra_data(extra_data: &Bytes) -> Result<(u64, u64), ExecutorError> {
+    let invalid = || ExecutorError::InvalidHoloceneExtraData(extra_data.clone());
+    let (&version, params) = extra_data.split_first().ok_or_else(invalid)?;
+    let params = <&[u8; 8]>::try_from(params).map_err(|_| invalid())?;
+    match split_eip1559_params(params) {
+        (0, _) | (_, 0) => Err(invalid()),
+        _ if version != HOLOCENE_EXTRA_DATA_VERSION => Err(invalid()),
+        (denominator, elasticity) => Ok((denominator as u64, elasticity as u64)),
+    }
+}
+
+/// Splits encoded EIP-1559 parameters into the base fee max change denominator and the
+/// elasticity multiplier.
+fn split_eip1559_params(params: &[u8; 8]) -> (u32, u32) {
+    let denominator = u32::from_be_bytes([params[0], params[1], params[2], params[3]]);
+    let elasticity = u32::from_be_bytes([params[4], params[5], params[6], params[7]]);
+    (denominator, elasticity)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::address;
+    use kona_mpt::TrieAccount;
+
+    /// The state root the headers of the tests commit to.
+    const STATE_ROOT: B256 = B256::repeat_byte(0x44);
+
+    /// The receipts root the headers of the tests commit to.
+    const RECEIPTS_ROOT: B256 = B256::repeat_byte(0x55);
+
+    /// A rollup config activating Canyon at 10, Ecotone at 20 and Holocene at 30.
+    fn config() -> RollupConfig {
+        RollupConfig {
+            block_time: 2,
+            regolith_time: Some(0),
+            canyon_time: Some(10),
+            delta_time: Some(10),
+            ecotone_time: Some(20),
+            fjord_time: Some(20),
+            holocene_time: Some(30),
+            ..Default::default()
+        }
+    }
+
+    /// Returns a parent block at `timestamp - 2`, using half of its gas target, and payload
+    /// attributes for its child at `timestamp`.
+    fn parent_and_attributes(timestamp: u64) -> (Header, L2PayloadAttributes) {
+        let parent = Header {
+            number: 10,
+            timestamp: timestamp - 2,
+            gas_limit: 30_000_000,
+            gas_used: 5_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        let attributes = L2PayloadAttributes {
+            timestamp,
+            prev_randao: B256::repeat_byte(0x22),
+            fee_recipient: address!("4200000000000000000000000000000000000011"),
+            withdrawals: Some(Vec::new()),
+            parent_beacon_block_root: Some(B256::repeat_byte(0x33)),
+            gas_limit: Some(30_000_000),
+            eip_1559_params: Some(B64::ZERO),
+            ..Default::default()
+        };
+        (parent, attributes)
+    }
+
+    /// Builds the header of the block at `timestamp`, and sets the commitments of its execution.
+    fn header(timestamp: u64) -> Header {
+        let (parent, attributes) = parent_and_attributes(timestamp);
+        let mut header =
+            header_template(&config(), &parent, B256::repeat_byte(0x11), &attributes).unwrap();
+        header.state_root = STATE_ROOT;
+        header.receipts_root = RECEIPTS_ROOT;
+        header.gas_used = 21_000;
+        header
+    }
+
+    // The expected hashes below were computed independently, by RLP encoding the expected fields
+    // of each header in order and hashing them. They were not captured from op-geth.
+
+    #[test]
+    fn test_header_regolith() {
+        let header = header(8);
+        assert_eq!(header.withdrawals_root, None);
+        assert_eq!((header.blob_gas_used, header.excess_blob_gas), (None, None));
+        assert_eq!(header.parent_beacon_block_root, None);
+        assert!(header.extra_data.is_empty());
+        assert_eq!(
+            header.hash_slow(),
+            b256!("25fd5f7911cf7a7783357d8de1907ef87c9d8b4a2a94f833dccccbf45e19e030")
+        );
+    }
+
+    #[test]
+    fn test_header_canyon_activation() {
+        let header = header(10);
+        assert_eq!(header.withdrawals_root, Some(TrieAccount::EMPTY_ROOT));
+        assert_eq!((header.blob_gas_used, header.excess_blob_gas), (None, None));
+        assert_eq!(header.parent_beacon_block_root, None);
+        assert_eq!(
+            header.hash_slow(),
+            b256!("53ddc27770eaf6a1189dca5303bba18bc4f8d9f732e75c0557b51c3551d8a8e9")
+        );
+    }
+
+    #[test]
+    fn test_header_ecotone_activation() {
+        let header = header(20);
+        assert_eq!((header.blob_gas_used, header.excess_blob_gas), (Some(0), Some(0)));
+        assert_eq!(header.parent_beacon_block_root, Some(B256::repeat_byte(0x33)));
+        assert!(header.extra_data.is_empty());
+        assert_eq!(
+            header.hash_slow(),
+            b256!("d3d9bd070b6e6370fcb21c7c1ec0cf1e29ac850def0f18878ccec1a2ba7fc4b3")
+        );
+    }
+
+    #[test]
+    fn test_header_holocene_activation() {
+        let header = header(30);
+        // Zero EIP-1559 parameters select the constants of Canyon.
+        assert_eq!(header.extra_data, Bytes::from_static(&[0, 0, 0, 0, 250, 0, 0, 0, 6]));
+        assert_eq!(header.base_fee_per_gas, Some(1_000_000_000));
+        assert_eq!(
+            header.hash_slow(),
+            b256!("9a80bfc4d1adadeac7fd493532a6a296bd02faa7324f62b7090ce60f5dff81e3")
+        );
+    }
+
+    #[test]
+    fn test_header_missing_parent_beacon_block_root() {
+        let (parent, attributes) = parent_and_attributes(20);
+        let attributes = L2PayloadAttributes { parent_beacon_block_root: None, ..attributes };
+        assert_eq!(
+            header_template(&config(), &parent, B256::ZERO, &attributes),
+            Err(ExecutorError::MissingParentBeaconBlockRoot)
+        );
+    }
+
+    #[test]
+    fn test_header_holocene_eip1559_params() {
+        let (parent, attributes) = parent_and_attributes(30);
+        let missing = L2PayloadAttributes { eip_1559_params: None, ..attributes.clone() };
+        assert_eq!(
+            header_template(&config(), &parent, B256::ZERO, &missing),
+            Err(ExecutorError::MissingEip1559Params)
+        );
+
+        let params = B64::from([0, 0, 0, 0, 0, 0, 0, 2]);
+        let invalid = L2PayloadAttributes { eip_1559_params: Some(params), ..attributes.clone() };
+        assert_eq!(
+            header_template(&config(), &parent, B256::ZERO, &invalid),
+            Err(ExecutorError::InvalidEip1559Params(params))
+        );
+
+        let params = B64::from([0, 0, 0, 100, 0, 0, 0, 2]);
+        let custom = L2PayloadAttributes { eip_1559_params: Some(params), ..attributes };
+        let header = header_template(&config(), &parent, B256::ZERO, &custom).unwrap();
+        assert_eq!(header.extra_data, Bytes::from_static(&[0, 0, 0, 0, 100, 0, 0, 0, 2]));
+    }
+
+    #[test]
+    fn test_base_fee_after_holocene_parent() {
+        // The base fee of the child of a Holocene block uses the parameters of its `extraData`.
+        let parent = Header {
+            timestamp: 30,
+            gas_limit: 30_000_000,
+            gas_used: 0,
+            base_fee_per_gas: Some(1_000_000_000),
+            extra_data: Bytes::from_static(&[0, 0, 0, 0, 100, 0, 0, 0, 2]),
+            ..Default::default()
+        };
+        assert_eq!(next_block_base_fee(&config(), &parent, 32), Ok(990_000_000));
+
+        let invalid = Header { extra_data: Bytes::from_static(&[1, 0, 0, 0, 100]), ..parent };
+        assert_eq!(
+            next_block_base_fee(&config(), &invalid, 32),
+            Err(ExecutorError::InvalidHoloceneExtraData(invalid.extra_data.clone()))
+        );
+    }
+
+    #[test]
+    fn test_base_fee() {
+        let parent = Header {
+            gas_limit: 30_000_000,
+            gas_used: 5_000_000,
+            base_fee_per_gas: Some(1_000_000_000),
+            ..Default::default()
+        };
+        assert_eq!(base_fee(&parent, 6, 50), 1_000_000_000);
+
+        let empty = Header { gas_used: 0, ..parent.clone() };
+        assert_eq!(base_fee(&empty, 6, 50), 980_000_000);
+        assert_eq!(base_fee(&empty, 6, 250), 996_000_000);
+
+        let full = Header { gas_used: 30_000_000, ..parent };
+        assert_eq!(base_fee(&full, 6, 50), 1_100_000_000);
+        assert_eq!(base_fee(&full, 6, 250), 1_020_000_000);
+    }
+}
@@ -0,0 +1,28 @@
+#![doc = include_str!("../README.md")]
+#![warn(missing_debug_implementations, missing_docs, unreachable_pub, rustdoc::all)]
+#![deny(unused_must_use, rust_2018_idioms)]
+#![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
+#![no_std]
+
+extern crate alloc;
+
+mod errors;
+pub use errors::ExecutorError;
+
+mod receipts;
+pub use receipts::{OpReceipt, DEPOSIT_RECEIPT_VERSION};
+
+mod eip4788;
+
+mod canyon;
+
+mod header;
+
+mod executor;
+pub use executor::{ExecutionOutcome, StatelessL2BlockExecutor};
+
+mod limits;
+pub use limits::{ExecutorLimits, LimitKind};
+
+mod builder;
+pub use builder::{KonaHandleRegister, StatelessL2BlockExecutorBuilder};
@@ -0,0 +1,77 @@
+//! Contains the [ExecutorLimits], which bound the resources a [StatelessL2BlockExecutor] spends on
+//! a payload.
+//!
+//! [StatelessL2BlockExecutor]: crate::StatelessL2BlockExecutor
+
+use crate::ExecutorError;
+use core::fmt::Display;
+use kona_primitives::L2PayloadAttributes;
+
+/// The resource limits of a [StatelessL2BlockExecutor], which refuses to execute the payloads
+/// exceeding them. By default, no limit is set.
+///
+/// [StatelessL2BlockExecutor]: crate::StatelessL2BlockExecutor
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct ExecutorLimits {
+    /// The maximum gas limit of a block, checked before the payload is executed.
+    pub max_gas_limit: Option<u64>,
+    /// The maximum number of transactions of a payload, checked before the payload is executed.
+    pub max_transactions: Option<u64>,
+    /// The maximum gas used by the transactions of a block, checked after each transaction.
+    pub max_gas_used: Option<u64>,
+}
+
+impl ExecutorLimits {
+    /// Checks the gas limit and the number of transactions of the payload `attributes` against
+    /// the limits, before anything is decoded or executed.
+    ///
+    /// Payloads without a gas limit are left to the executor, which rejects them.
+    pub(crate) fn check_payload(
+        &self,
+        attributes: &L2PayloadAttributes,
+    ) -> Result<(), ExecutorError> {
+        if let Some(gas_limit) = attributes.gas_limit {
+            check(LimitKind::GasLimit, self.max_gas_limit, gas_limit)?;
+        }
+        check(
+            LimitKind::TransactionCount,
+            self.max_transactions,
+            attributes.transactions.len() as u64,
+        )
+    }
+
+    /// Checks the `gas_used` by the transactions of the block executed so far against the limit.
+    pub(crate) fn check_gas_used(&self, gas_used: u64) -> Result<(), ExecutorError> {
+        check(LimitKind::GasUsed, self.max_gas_used, gas_used)
+    }
+}
+
+/// The kind of an [ExecutorLimits] limit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LimitKind {
+    /// The gas limit of the block.
+    GasLimit,
+    /// The number of transactions of the payload.
+    TransactionCount,
+    /// The gas used by the transactions of the block.
+    GasUsed,
+}
+
+impl Display for LimitKind {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LimitKind::GasLimit => write!(f, "block gas limit"),
+            LimitKind::TransactionCount => write!(f, "transaction count"),
+            LimitKind::GasUsed => write!(f, "block gas used"),
+        }
+    }
+}
+
+/// Returns [ExecutorError::LimitExceeded] if the `actual` value exceeds the `limit` of the given
+/// `kind`, if any.
+fn check(kind: LimitKind, limit: Option<u64>, actual: u64) -> Result<(), ExecutorError> {
+    match limit {
+        Some(limit) if actual > limit => Err(ExecutorError::LimitExceeded { kind, limit, actual }),
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,175 @@
+//! Contains the [OpReceipt], the receipt of a transaction executed by the
+//! [StatelessL2BlockExecutor].
+//!
+//! [StatelessL2BlockExecutor]: crate::StatelessL2BlockExecutor
+
+use alloc::vec::Vec;
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{Bloom, Log};
+use alloy_rlp::{length_of_length, BufMut, Encodable, Header};
+use op_alloy_consensus::OpTxType;
+
+/// The version of the deposit receipts introduced in Canyon, which commit to the deposit nonce.
+pub const DEPOSIT_RECEIPT_VERSION: u64 = 1;
+
+/// The receipt of a transaction executed by the [StatelessL2BlockExecutor].
+///
+/// After Canyon, the receipts of deposit transactions carry the nonce of the depositor before
+/// the deposit was executed, and the [DEPOSIT_RECEIPT_VERSION]. Both are appended to the fields
+/// of the receipt in its encoding, and so are committed to by the receipts root. Before Canyon,
+/// they are left out.
+///
+/// [StatelessL2BlockExecutor]: crate::StatelessL2BlockExecutor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OpReceipt {
+    /// The type of the transaction.
+    pub tx_type: OpTxType,
+    /// Whether the transaction succeeded.
+    pub status: bool,
+    /// The gas used in the block up to and including the transaction.
+    pub cumulative_gas_used: u64,
+    /// The bloom filter of the logs of the transaction.
+    pub logs_bloom: Bloom,
+    /// The logs emitted by the transaction.
+    pub logs: Vec<Log>,
+    /// The nonce of the depositor before a deposit transaction, after Canyon.
+    pub deposit_nonce: Option<u64>,
+    /// The version of a deposit receipt, after Canyon.
+    pub deposit_receipt_version: Option<u64>,
+}
+
+impl OpReceipt {
+    /// Creates a new [OpReceipt], computing the bloom filter of the `logs`.
+    pub fn new(tx_type: OpTxType, status: bool, cumulative_gas_used: u64, logs: Vec<Log>) -> Self {
+        let mut logs_bloom = Bloom::ZERO;
+        for log in logs.iter() {
+            logs_bloom.accrue_log(log);
+        }
+        Self {
+            tx_type,
+            status,
+            cumulative_gas_used,
+            logs_bloom,
+            logs,
+            deposit_nonce: None,
+            deposit_receipt_version: None,
+        }
+    }
+
+    /// Sets the fields of a Canyon deposit receipt, given the nonce of the depositor before the
+    /// deposit was executed.
+    pub fn with_deposit_nonce(mut self, deposit_nonce: u64) -> Self {
+        self.deposit_nonce = Some(deposit_nonce);
+        self.deposit_receipt_version = Some(DEPOSIT_RECEIPT_VERSION);
+        self
+    }
+
+    /// Returns the length of the RLP encoded fields of the receipt.
+    fn fields_len(&self) -> usize {
+        self.status.length() +
+            self.cumulative_gas_used.length() +
+            self.logs_bloom.length() +
+            self.logs.length() +
+            self.deposit_nonce.map_or(0, |nonce| nonce.length()) +
+            self.deposit_receipt_version.map_or(0, |version| version.length())
+    }
+}
+
+/// Receipts are committed to in the receipts root as [EIP-2718] envelopes: the type of the
+/// transaction, followed by the RLP encoded fields of the receipt. Receipts of legacy
+/// transactions are not typed.
+///
+/// [EIP-2718]: https://eips.ethereum.org/EIPS/eip-2718
+impl Encodable2718 for OpReceipt {
+    fn type_flag(&self) -> Option<u8> {
+        match self.tx_type {
+            OpTxType::Legacy => None,
+            tx_type => Some(tx_type as u8),
+        }
+    }
+
+    fn encode_2718_len(&self) -> usize {
+        let payload_length = self.fields_len();
+        self.type_flag().is_some() as usize + length_of_length(payload_length) + payload_length
+    }
+
+    fn encode_2718(&self, out: &mut dyn BufMut) {
+        if let Some(flag) = self.type_flag() {
+            out.put_u8(flag);
+        }
+        Header { list: true, payload_length: self.fields_len() }.encode(out);
+        self.status.encode(out);
+        self.cumulative_gas_used.encode(out);
+        self.logs_bloom.encode(out);
+        self.logs.encode(out);
+        if let Some(deposit_nonce) = self.deposit_nonce {
+            deposit_nonce.encode(out);
+        }
+        if let Some(deposit_receipt_version) = self.deposit_receipt_version {
+            deposit_receipt_version.encode(out);
+        }
+    }
+}
+
+/// Returns the bloom filter of a block, accruing the bloom filters of its receipts.
+pub fn logs_bloom(receipts: &[OpReceipt]) -> Bloom {
+    let mut bloom = Bloom::ZERO;
+    for receipt in receipts {
+        bloom.accrue_bloom(&receipt.logs_bloom);
+    }
+    bloom
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::{address, b256, hex, Bytes, LogData};
+
+    #[test]
+    fn test_deposit_receipt_encoding() {
+        let receipt = OpReceipt::new(OpTxType::Deposit, true, 21_000, Vec::new());
+        let mut buf = Vec::new();
+        receipt.encode_2718(&mut buf);
+        assert_eq!(buf[0], OpTxType::Deposit as u8);
+        assert_eq!(buf[1..4], hex!("f90108"));
+        assert_eq!(buf.len(), 4 + 264);
+        assert_eq!(receipt.encode_2718_len(), buf.len());
+
+        // The Canyon fields are appended to the fields of the receipt.
+        let canyon = receipt.with_deposit_nonce(3);
+        let mut canyon_buf = Vec::new();
+        canyon.encode_2718(&mut canyon_buf);
+        assert_eq!(canyon_buf[1..4], hex!("f9010a"));
+        assert_eq!(canyon_buf[4..canyon_buf.len() - 2], buf[4..]);
+        assert_eq!(canyon_buf[canyon_buf.len() - 2..], [0x03, 0x01]);
+        assert_eq!(canyon.encode_2718_len(), canyon_buf.len());
+    }
+
+    #[test]
+    fn test_legacy_receipt_is_untyped() {
+        let receipt = OpReceipt::new(OpTxType::Legacy, false, 1, Vec::new());
+        let mut buf = Vec::new();
+        receipt.encode_2718(&mut buf);
+        assert_eq!(buf[..3], hex!("f90106"));
+        assert_eq!(buf[3..5], [0x80, 0x01]);
+    }
+
+    #[test]
+    fn test_receipts_bloom() {
+        let log = Log {
+            address: address!("4200000000000000000000000000000000000015"),
+            data: LogData::new_unchecked(
+                vec![b256!("0000000000000000000000000000000000000000000000000000000000000001")],
+                Bytes::new(),
+            ),
+        };
+        let receipts = [
+            OpReceipt::new(OpTxType::Deposit, true, 21_000, Vec::new()),
+            OpReceipt::new(OpTxType::Eip1559, true, 50_000, vec![log.clone()]),
+        ];
+        let mut expected = Bloom::ZERO;
+        expected.accrue_log(&log);
+        assert_eq!(receipts[1].logs_bloom, expected);
+        assert_eq!(logs_bloom(&receipts), expected);
+    }
+}
@@ -0,0 +1,74 @@
+//! Benchmarks of the serial and parallel state root computation of the [TrieDB], for a synthetic
+//! block that writes to the storage of many contracts.
+
+use alloy_consensus::Header;
+use alloy_primitives::{keccak256, Address, B256, U256};
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use kona_mpt::{MapTrieProvider, NoopTrieHinter, SecureTrie, TrieAccount, TrieDB};
+use revm::{
+    db::{AccountStatus, BundleAccount, BundleState},
+    primitives::{AccountInfo, StorageSlot},
+};
+
+/// The number of contracts the block touches.
+const CONTRACTS: u64 = 200;
+/// The number of storage slots each contract holds before the block.
+const SLOTS: u64 = 64;
+/// The number of storage slots of each contract the block writes to.
+const WRITES: u64 = 16;
+
+/// Builds the pre state of the block, and returns a provider serving it with the header
+/// committing to it and the bundle of the block.
+fn synthetic_block() -> (MapTrieProvider, Header, BundleState) {
+    let mut provider = MapTrieProvider::new();
+    let mut state = SecureTrie::new(TrieAccount::EMPTY_ROOT, MapTrieProvider::new());
+    let mut accounts = Vec::new();
+
+    for i in 0..CONTRACTS {
+        let address = Address::from_word(keccak256(i.to_be_bytes()));
+        let mut storage = SecureTrie::new(TrieAccount::EMPTY_ROOT, MapTrieProvider::new());
+        for slot in 0..SLOTS {
+            storage.set_storage(B256::from(U256::from(slot)), U256::from(slot + 1)).unwrap();
+        }
+        let storage_root = provider.insert_trie(storage.root_node());
+        let account = TrieAccount { nonce: 1, storage_root, ..Default::default() };
+        state.insert(address, alloy_rlp::encode(account).into()).unwrap();
+
+        // Overwrite half of the written slots, and fill the other half from scratch.
+        let writes = (SLOTS - WRITES / 2..SLOTS + WRITES / 2).map(|slot| {
+            let original = if slot < SLOTS { U256::from(slot + 1) } else { U256::ZERO };
+            (U256::from(slot), StorageSlot::new_changed(original, U256::from(i + slot)))
+        });
+        let info = AccountInfo { nonce: 2, ..Default::default() };
+        let account =
+            BundleAccount::new(None, Some(info), writes.collect(), AccountStatus::Changed);
+        accounts.push((address, account));
+    }
+
+    let state_root = provider.insert_trie(state.root_node());
+    let header = Header { state_root, ..Default::default() };
+    let bundle = BundleState { state: accounts.into_iter().collect(), ..Default::default() };
+    (provider, header, bundle)
+}
+
+fn bench_state_root(c: &mut Criterion) {
+    let (provider, header, bundle) = synthetic_block();
+    let new_db = || TrieDB::new(header.clone(), &provider, NoopTrieHinter);
+    assert_eq!(
+        new_db().state_root(&bundle).unwrap(),
+        new_db().par_state_root(&bundle).unwrap(),
+        "serial and parallel state roots differ"
+    );
+
+    let mut group = c.benchmark_group("state_root");
+    group.bench_function("serial", |b| {
+        b.iter_batched(new_db, |mut db| db.state_root(&bundle).unwrap(), BatchSize::SmallInput)
+    });
+    group.bench_function("parallel", |b| {
+        b.iter_batched(new_db, |mut db| db.par_state_root(&bundle).unwrap(), BatchSize::SmallInput)
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_state_root);
+criterion_main!(benches);
@@ -0,0 +1,135 @@
+//! This module contains the [TrieAccount] type, which represents an account in the state trie.
+
+use alloy_primitives::{b256, B256, U256};
+use alloy_rlp::{RlpDecodable, RlpEncodable};
+use revm_primitives::{AccountInfo, KECCAK_EMPTY};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An Ethereum account as it is stored in the state trie, encoded as
+/// `rlp([nonce, balance, storage_root, code_hash])`.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct TrieAccount {
+    /// The nonce of the account.
+    pub nonce: u64,
+    /// The balance of the account.
+    pub balance: U256,
+    /// The root of the account's storage trie.
+    pub storage_root: B256,
+    /// The hash of the account's code.
+    pub code_hash: B256,
+}
+
+impl TrieAccount {
+    /// The root hash of an empty trie, `keccak256(rlp(""))`.
+    pub const EMPTY_ROOT: B256 =
+        b256!("56e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421");
+
+    /// The code hash of an account without code, `keccak256("")`.
+    pub const EMPTY_CODE_HASH: B256 = KECCAK_EMPTY;
+
+    /// Returns whether the account is empty as defined by EIP-158: it has a zero nonce, a zero
+    /// balance and no code. Empty accounts must not exist in the state trie.
+    ///
+    /// The storage root is not part of the definition.
+    pub fn is_empty(&self) -> bool {
+        self.nonce == 0 && self.balance == U256::ZERO && self.code_hash == Self::EMPTY_CODE_HASH
+    }
+}
+
+impl Default for TrieAccount {
+    /// Returns an empty account, with no storage and no code.
+    fn default() -> Self {
+        Self {
+            nonce: 0,
+            balance: U256::ZERO,
+            storage_root: Self::EMPTY_ROOT,
+            code_hash: Self::EMPTY_CODE_HASH,
+        }
+    }
+}
+
+impl From<(AccountInfo, B256)> for TrieAccount {
+    /// Creates a [TrieAccount] from an [AccountInfo] and the root of the account's storage trie.
+    fn from((info, storage_root): (AccountInfo, B256)) -> Self {
+        Self { nonce: info.nonce, balance: info.balance, storage_root, code_hash: info.code_hash }
+    }
+}
+
+impl From<TrieAccount> for AccountInfo {
+    /// Creates an [AccountInfo] from a [TrieAccount]. The code is not part of the account and
+    /// has to be loaded separately by its hash.
+    fn from(account: TrieAccount) -> Self {
+        Self {
+            balance: account.balance,
+            nonce: account.nonce,
+            code_hash: account.code_hash,
+            code: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use alloy_primitives::{hex, keccak256};
+    use alloy_rlp::{Decodable, Encodable};
+
+    #[test]
+    fn test_empty_constants() {
+        assert_eq!(TrieAccount::EMPTY_ROOT, keccak256([alloy_rlp::EMPTY_STRING_CODE]));
+        assert_eq!(TrieAccount::EMPTY_CODE_HASH, keccak256(""));
+    }
+
+    #[test]
+    fn test_is_empty() {
+        let empty = TrieAccount::default();
+        assert!(empty.is_empty());
+        assert!(!TrieAccount { nonce: 1, ..empty }.is_empty());
+        assert!(!TrieAccount { balance: U256::from(1), ..empty }.is_empty());
+        assert!(!TrieAccount { code_hash: B256::repeat_byte(0xFF), ..empty }.is_empty());
+        assert!(TrieAccount { storage_root: B256::repeat_byte(0xFF), ..empty }.is_empty());
+    }
+
+    #[test]
+    fn test_rlp_roundtrip() {
+        let account = TrieAccount::default();
+        let mut buf = Vec::new();
+        account.encode(&mut buf);
+        let expected = hex!("f8448080a056e81f171bcc55a6ff8345e692c0f86e5b48e01b996cadc001622fb5e363b421a0c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470");
+        assert_eq!(buf, expected);
+        assert_eq!(TrieAccount::decode(&mut buf.as_slice()).unwrap(), account);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_roundtrip() {
+        let account = TrieAccount { nonce: 7, balance: U256::from(1_000), ..Default::default() };
+        let serialized = serde_json::to_string(&account).unwrap();
+        assert_eq!(serde_json::from_str::<TrieAccount>(&serialized).unwrap(), account);
+    }
+
+    #[test]
+    fn test_account_info_conversions() {
+        let info = AccountInfo {
+            balance: U256::from(100),
+            nonce: 7,
+            code_hash: B256::repeat_byte(0xAA),
+            code: None,
+        };
+        let storage_root = B256::repeat_byte(0xBB);
+        let account = TrieAccount::from((info.clone(), storage_root));
+        assert_eq!(
+            account,
+            TrieAccount {
+                nonce: 7,
+                balance: U256::from(100),
+                storage_root,
+                code_hash: info.code_hash
+            }
+        );
+        assert_eq!(AccountInfo::from(account), info);
+    }
+}
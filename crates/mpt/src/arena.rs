@@ -0,0 +1,618 @@
+//! This module contains the [ArenaTrie] type, an arena-backed trie with the same operations as
+//! [TrieNode].
+
+use crate::{
+    node::{common_prefix_length, fetch_node, MIN_BLINDED_LENGTH},
+    Nibbles, TrieNode, TrieNodeError, TrieProvider,
+};
+use alloc::{boxed::Box, vec, vec::Vec};
+use alloy_primitives::{Bytes, B256};
+use alloy_rlp::Encodable;
+
+/// The number of children of a branch node.
+const BRANCH_WIDTH: usize = 16;
+
+/// The index of the shared [ArenaNode::Empty] node, which every empty slot points to.
+const EMPTY: usize = 0;
+
+/// A node of an [ArenaTrie]. Children are indices into the arena rather than owned nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ArenaNode {
+    /// See [TrieNode::Empty].
+    Empty,
+    /// See [TrieNode::Blinded].
+    Blinded(B256),
+    /// See [TrieNode::Leaf].
+    Leaf { key: Nibbles, value: Bytes },
+    /// See [TrieNode::Extension].
+    Extension { prefix: Nibbles, child: usize },
+    /// See [TrieNode::Branch]. Values stored in branches are not supported.
+    Branch { children: [usize; BRANCH_WIDTH] },
+}
+
+/// An [ArenaTrie] stores its nodes in a single slab and links them by index, instead of boxing
+/// every child like [TrieNode] does. Opening, inserting and deleting paths reuses the slots of
+/// the nodes they replace, which keeps the number of heap allocations low in environments such as
+/// the FPVM, where the bump allocator never frees memory.
+///
+/// The operations mirror those of [TrieNode], and produce the same roots and proofs. Values
+/// stored in branch nodes are not supported.
+#[derive(Debug, Clone)]
+pub struct ArenaTrie {
+    /// The slab of nodes. The slot at [EMPTY] always holds [ArenaNode::Empty].
+    nodes: Vec<ArenaNode>,
+    /// The indices of the slots that are no longer referenced, and may be reused.
+    free: Vec<usize>,
+    /// The index of the root node.
+    root: usize,
+}
+
+impl Default for ArenaTrie {
+    /// Returns an empty [ArenaTrie].
+    fn default() -> Self {
+        Self { nodes: vec![ArenaNode::Empty], free: Vec::new(), root: EMPTY }
+    }
+}
+
+impl ArenaTrie {
+    /// Creates a new [ArenaTrie] with the given root commitment.
+    pub fn new(root: B256) -> Self {
+        let mut trie = Self::default();
+        trie.root = trie.alloc(ArenaNode::Blinded(root));
+        trie
+    }
+
+    /// Creates a new [ArenaTrie] from the given, possibly opened, [TrieNode].
+    ///
+    /// Returns [TrieNodeError::BranchValue] if a branch of the trie holds a value.
+    pub fn from_node(node: TrieNode) -> Result<Self, TrieNodeError> {
+        let mut trie = Self::default();
+        trie.root = trie.alloc_node(node)?;
+        Ok(trie)
+    }
+
+    /// Converts the [ArenaTrie] into a [TrieNode], keeping its opened nodes open.
+    pub fn to_node(&self) -> TrieNode {
+        self.node_at(self.root)
+    }
+
+    /// Returns the number of slots in the arena, including the free ones.
+    pub fn capacity(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Returns the root commitment of the trie. See [TrieNode::root].
+    pub fn root(&self) -> B256 {
+        self.shallow(self.root).root()
+    }
+
+    /// Returns the value stored at the given nibble `path`, without opening any nodes. See
+    /// [TrieNode::get].
+    pub fn get<P: TrieProvider>(
+        &self,
+        path: &Nibbles,
+        provider: &P,
+    ) -> Result<Option<Bytes>, TrieNodeError> {
+        let mut id = self.root;
+        let mut path = path.as_slice();
+        loop {
+            match &self.nodes[id] {
+                ArenaNode::Empty => return Ok(None),
+                ArenaNode::Blinded(commitment) => {
+                    let rest = Nibbles::from_nibbles_unchecked(path);
+                    return fetch_node(*commitment, provider)?.get(&rest, provider);
+                }
+                ArenaNode::Leaf { key, value } => {
+                    return Ok((key.as_slice() == path).then(|| value.clone()))
+                }
+                ArenaNode::Extension { prefix, child } => {
+                    let Some(rest) = path.strip_prefix(prefix.as_slice()) else {
+                        return Ok(None);
+                    };
+                    (id, path) = (*child, rest);
+                }
+                ArenaNode::Branch { children } => {
+                    let Some((&index, rest)) = path.split_first() else {
+                        return Ok(None);
+                    };
+                    (id, path) = (branch_child(children, index)?, rest);
+                }
+            }
+        }
+    }
+
+    /// Returns a mutable reference to the value stored at the given nibble `path`, opening the
+    /// nodes along it. See [TrieNode::open].
+    pub fn open<P: TrieProvider>(
+        &mut self,
+        path: &Nibbles,
+        provider: &P,
+    ) -> Result<Option<&mut Bytes>, TrieNodeError> {
+        let mut id = self.root;
+        let mut path = path.as_slice();
+        loop {
+            self.unblind(id, provider)?;
+            match &self.nodes[id] {
+                ArenaNode::Empty => return Ok(None),
+                ArenaNode::Blinded(commitment) => return Err(TrieNodeError::Blinded(*commitment)),
+                ArenaNode::Leaf { key, .. } => {
+                    if key.as_slice() != path {
+                        return Ok(None);
+                    }
+                    break;
+                }
+                ArenaNode::Extension { prefix, child } => {
+                    let Some(rest) = path.strip_prefix(prefix.as_slice()) else {
+                        return Ok(None);
+                    };
+                    (id, path) = (*child, rest);
+                }
+                ArenaNode::Branch { children } => {
+                    let Some((&index, rest)) = path.split_first() else {
+                        return Ok(None);
+                    };
+                    (id, path) = (branch_child(children, index)?, rest);
+                }
+            }
+        }
+
+        match &mut self.nodes[id] {
+            ArenaNode::Leaf { value, .. } => Ok(Some(value)),
+            _ => Ok(None),
+        }
+    }
+
+    /// Inserts the `value` at the given nibble `path`. See [TrieNode::insert].
+    pub fn insert<P: TrieProvider>(
+        &mut self,
+        path: &Nibbles,
+        value: Bytes,
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        self.root = self.insert_at(self.root, path.as_slice(), value, provider)?;
+        Ok(())
+    }
+
+    /// Deletes the value at the given nibble `path`. See [TrieNode::delete].
+    pub fn delete<P: TrieProvider>(
+        &mut self,
+        path: &Nibbles,
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        self.root = self.delete_at(self.root, path.as_slice(), provider)?;
+        Ok(())
+    }
+
+    /// Returns the RLP encoded nodes along the given nibble `path`, ordered from the root down.
+    /// See [TrieNode::proof].
+    pub fn proof<P: TrieProvider>(
+        &mut self,
+        path: &Nibbles,
+        provider: &P,
+    ) -> Result<Vec<Bytes>, TrieNodeError> {
+        let mut proof = Vec::new();
+        let mut id = self.root;
+        let mut path = path.as_slice();
+        loop {
+            self.unblind(id, provider)?;
+            if matches!(self.nodes[id], ArenaNode::Empty) {
+                return Ok(proof);
+            }
+
+            let node = self.shallow(id);
+            if id == self.root || node.length() >= MIN_BLINDED_LENGTH {
+                let mut rlp_buf = Vec::with_capacity(node.length());
+                node.encode(&mut rlp_buf);
+                proof.push(rlp_buf.into());
+            }
+
+            match &self.nodes[id] {
+                ArenaNode::Empty | ArenaNode::Leaf { .. } => return Ok(proof),
+                ArenaNode::Blinded(commitment) => return Err(TrieNodeError::Blinded(*commitment)),
+                ArenaNode::Extension { prefix, child } => {
+                    let Some(rest) = path.strip_prefix(prefix.as_slice()) else {
+                        return Ok(proof);
+                    };
+                    (id, path) = (*child, rest);
+                }
+                ArenaNode::Branch { children } => {
+                    let Some((&index, rest)) = path.split_first() else {
+                        return Ok(proof);
+                    };
+                    (id, path) = (branch_child(children, index)?, rest);
+                }
+            }
+        }
+    }
+
+    /// Recursive helper for [ArenaTrie::insert]. Returns the index of the node that replaces the
+    /// one at `id`.
+    fn insert_at<P: TrieProvider>(
+        &mut self,
+        id: usize,
+        path: &[u8],
+        value: Bytes,
+        provider: &P,
+    ) -> Result<usize, TrieNodeError> {
+        self.unblind(id, provider)?;
+        match self.nodes[id].clone() {
+            ArenaNode::Empty => {
+                Ok(self
+                    .put(id, ArenaNode::Leaf { key: Nibbles::from_nibbles_unchecked(path), value }))
+            }
+            ArenaNode::Blinded(commitment) => Err(TrieNodeError::Blinded(commitment)),
+            ArenaNode::Leaf { key, value: existing } => {
+                if key.as_slice() == path {
+                    return Ok(self.put(id, ArenaNode::Leaf { key, value }));
+                }
+
+                // Split the leaf into a branch that holds both the existing and the new value.
+                let shared = common_prefix_length(&key, path);
+                let (Some(&existing_index), Some(&new_index)) = (key.get(shared), path.get(shared))
+                else {
+                    return Err(TrieNodeError::BranchValue);
+                };
+                let mut children = [EMPTY; BRANCH_WIDTH];
+                children[existing_index as usize] = self.alloc(ArenaNode::Leaf {
+                    key: Nibbles::from_nibbles_unchecked(&key[shared + 1..]),
+                    value: existing,
+                });
+                children[new_index as usize] = self.alloc(ArenaNode::Leaf {
+                    key: Nibbles::from_nibbles_unchecked(&path[shared + 1..]),
+                    value,
+                });
+                Ok(self.put_with_shared_prefix(id, &path[..shared], children))
+            }
+            ArenaNode::Extension { prefix, child } => {
+                if let Some(rest) = path.strip_prefix(prefix.as_slice()) {
+                    let child = self.insert_at(child, rest, value, provider)?;
+                    return Ok(self.put(id, ArenaNode::Extension { prefix, child }));
+                }
+
+                // Split the extension at the first nibble where it diverges from `path`.
+                let shared = common_prefix_length(&prefix, path);
+                let Some(&new_index) = path.get(shared) else {
+                    return Err(TrieNodeError::BranchValue);
+                };
+                let mut children = [EMPTY; BRANCH_WIDTH];
+                children[prefix[shared] as usize] = if prefix.len() == shared + 1 {
+                    child
+                } else {
+                    self.alloc(ArenaNode::Extension {
+                        prefix: Nibbles::from_nibbles_unchecked(&prefix[shared + 1..]),
+                        child,
+                    })
+                };
+                children[new_index as usize] = self.alloc(ArenaNode::Leaf {
+                    key: Nibbles::from_nibbles_unchecked(&path[shared + 1..]),
+                    value,
+                });
+                Ok(self.put_with_shared_prefix(id, &path[..shared], children))
+            }
+            ArenaNode::Branch { mut children } => {
+                let Some((&index, rest)) = path.split_first() else {
+                    return Err(TrieNodeError::BranchValue);
+                };
+                let child = branch_child(&children, index)?;
+                children[index as usize] = self.insert_at(child, rest, value, provider)?;
+                Ok(self.put(id, ArenaNode::Branch { children }))
+            }
+        }
+    }
+
+    /// Recursive helper for [ArenaTrie::delete]. Returns the index of the node that replaces the
+    /// one at `id`.
+    fn delete_at<P: TrieProvider>(
+        &mut self,
+        id: usize,
+        path: &[u8],
+        provider: &P,
+    ) -> Result<usize, TrieNodeError> {
+        self.unblind(id, provider)?;
+        match self.nodes[id].clone() {
+            ArenaNode::Empty => Ok(id),
+            ArenaNode::Blinded(commitment) => Err(TrieNodeError::Blinded(commitment)),
+            ArenaNode::Leaf { key, .. } => {
+                if key.as_slice() != path {
+                    return Ok(id);
+                }
+                self.release(id);
+                Ok(EMPTY)
+            }
+            ArenaNode::Extension { prefix, child } => {
+                let Some(rest) = path.strip_prefix(prefix.as_slice()) else {
+                    return Ok(id);
+                };
+                let child = self.delete_at(child, rest, provider)?;
+
+                // The child may have collapsed into a leaf or extension, which absorbs the prefix.
+                if matches!(self.nodes[child], ArenaNode::Branch { .. }) {
+                    return Ok(self.put(id, ArenaNode::Extension { prefix, child }));
+                }
+                self.release(id);
+                self.prepend_path(&prefix, child)
+            }
+            ArenaNode::Branch { mut children } => {
+                let Some((&index, rest)) = path.split_first() else {
+                    return Ok(id);
+                };
+                let child = branch_child(&children, index)?;
+                children[index as usize] = self.delete_at(child, rest, provider)?;
+
+                // Collapse the branch if it is left with fewer than two children.
+                let mut remaining = children
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, &child)| !matches!(self.nodes[child], ArenaNode::Empty));
+                match (remaining.next(), remaining.next()) {
+                    (Some(_), Some(_)) => Ok(self.put(id, ArenaNode::Branch { children })),
+                    (None, _) => {
+                        self.release(id);
+                        Ok(EMPTY)
+                    }
+                    (Some((index, &child)), None) => {
+                        self.release(id);
+                        self.unblind(child, provider)?;
+                        self.prepend_path(&[index as u8], child)
+                    }
+                }
+            }
+        }
+    }
+
+    /// Prepends the `prefix` nibbles to the path of the opened node at `id`, merging it into the
+    /// extension or branch slot above it that is being collapsed. Returns the index of the merged
+    /// node.
+    fn prepend_path(&mut self, prefix: &[u8], id: usize) -> Result<usize, TrieNodeError> {
+        let join = |path: &[u8]| Nibbles::from_nibbles_unchecked([prefix, path].concat());
+        match self.nodes[id].clone() {
+            ArenaNode::Empty => Ok(EMPTY),
+            ArenaNode::Blinded(commitment) => Err(TrieNodeError::Blinded(commitment)),
+            ArenaNode::Leaf { key, value } => {
+                Ok(self.put(id, ArenaNode::Leaf { key: join(&key), value }))
+            }
+            ArenaNode::Extension { prefix: extension, child } => {
+                Ok(self.put(id, ArenaNode::Extension { prefix: join(&extension), child }))
+            }
+            ArenaNode::Branch { .. } if prefix.is_empty() => Ok(id),
+            ArenaNode::Branch { .. } => Ok(self.alloc(ArenaNode::Extension {
+                prefix: Nibbles::from_nibbles_unchecked(prefix),
+                child: id,
+            })),
+        }
+    }
+
+    /// Stores a branch with the given `children` at `id`, behind an extension over the `shared`
+    /// nibbles if there are any. Returns the index of the topmost node.
+    fn put_with_shared_prefix(
+        &mut self,
+        id: usize,
+        shared: &[u8],
+        children: [usize; BRANCH_WIDTH],
+    ) -> usize {
+        if shared.is_empty() {
+            return self.put(id, ArenaNode::Branch { children });
+        }
+        let child = self.alloc(ArenaNode::Branch { children });
+        self.put(
+            id,
+            ArenaNode::Extension { prefix: Nibbles::from_nibbles_unchecked(shared), child },
+        )
+    }
+
+    /// Replaces the node at `id` with the node decoded from the preimage of its commitment, if it
+    /// is blinded. The children of the decoded node are stored in the arena.
+    fn unblind<P: TrieProvider>(&mut self, id: usize, provider: &P) -> Result<(), TrieNodeError> {
+        if let ArenaNode::Blinded(commitment) = self.nodes[id] {
+            let node = fetch_node(commitment, provider)?;
+            self.nodes[id] = self.lower(node)?;
+        }
+        Ok(())
+    }
+
+    /// Converts a [TrieNode] into an [ArenaNode], storing its children in the arena.
+    fn lower(&mut self, node: TrieNode) -> Result<ArenaNode, TrieNodeError> {
+        Ok(match node {
+            TrieNode::Empty => ArenaNode::Empty,
+            TrieNode::Blinded { commitment } => ArenaNode::Blinded(commitment),
+            TrieNode::Leaf { key, value } => ArenaNode::Leaf { key, value },
+            TrieNode::Extension { prefix, node } => {
+                ArenaNode::Extension { prefix, child: self.alloc_node(*node)? }
+            }
+            TrieNode::Branch { stack } => {
+                let mut stack = stack.into_iter();
+                let mut children = [EMPTY; BRANCH_WIDTH];
+                for (slot, child) in children.iter_mut().zip(stack.by_ref()) {
+                    *slot = self.alloc_node(child)?;
+                }
+                if stack.any(|value| value != TrieNode::Empty) {
+                    return Err(TrieNodeError::BranchValue);
+                }
+                ArenaNode::Branch { children }
+            }
+        })
+    }
+
+    /// Stores a [TrieNode] and its children in the arena, and returns its index.
+    fn alloc_node(&mut self, node: TrieNode) -> Result<usize, TrieNodeError> {
+        let node = self.lower(node)?;
+        Ok(self.alloc(node))
+    }
+
+    /// Stores the `node` in a free slot of the arena, and returns its index. The shared empty node
+    /// is never duplicated.
+    fn alloc(&mut self, node: ArenaNode) -> usize {
+        if node == ArenaNode::Empty {
+            return EMPTY;
+        }
+        match self.free.pop() {
+            Some(id) => {
+                self.nodes[id] = node;
+                id
+            }
+            None => {
+                self.nodes.push(node);
+                self.nodes.len() - 1
+            }
+        }
+    }
+
+    /// Stores the `node` at `id`, unless `id` is the shared empty node, in which case a new slot is
+    /// allocated. Returns the index the node was stored at.
+    fn put(&mut self, id: usize, node: ArenaNode) -> usize {
+        if id == EMPTY {
+            return self.alloc(node);
+        }
+        self.nodes[id] = node;
+        id
+    }
+
+    /// Marks the slot at `id` as free. Its children are not released, since they may have been
+    /// moved into another node.
+    fn release(&mut self, id: usize) {
+        if id != EMPTY {
+            self.nodes[id] = ArenaNode::Empty;
+            self.free.push(id);
+        }
+    }
+
+    /// Converts the node at `id` and all of its stored descendants into a [TrieNode].
+    fn node_at(&self, id: usize) -> TrieNode {
+        match &self.nodes[id] {
+            ArenaNode::Empty => TrieNode::Empty,
+            ArenaNode::Blinded(commitment) => TrieNode::Blinded { commitment: *commitment },
+            ArenaNode::Leaf { key, value } => TrieNode::leaf(key.clone(), value.clone()),
+            ArenaNode::Extension { prefix, child } => {
+                TrieNode::extension(prefix.clone(), self.node_at(*child))
+            }
+            ArenaNode::Branch { children } => TrieNode::Branch {
+                stack: children
+                    .iter()
+                    .map(|&child| self.node_at(child))
+                    .chain([TrieNode::Empty])
+                    .collect(),
+            },
+        }
+    }
+
+    /// Converts the node at `id` into a [TrieNode] whose children are blinded if their encoding
+    /// is 32 bytes or longer, so that it can be encoded without materializing the whole subtrie.
+    fn shallow(&self, id: usize) -> TrieNode {
+        match &self.nodes[id] {
+            ArenaNode::Extension { prefix, child } => TrieNode::Extension {
+                prefix: prefix.clone(),
+                node: Box::new(self.shallow(*child).blind()),
+            },
+            ArenaNode::Branch { children } => TrieNode::Branch {
+                stack: children
+                    .iter()
+                    .map(|&child| self.shallow(child).blind())
+                    .chain([TrieNode::Empty])
+                    .collect(),
+            },
+            _ => self.node_at(id),
+        }
+    }
+}
+
+/// Returns the index of the child of a branch at the nibble `index`.
+fn branch_child(children: &[usize; BRANCH_WIDTH], index: u8) -> Result<usize, TrieNodeError> {
+    children.get(index as usize).copied().ok_or(TrieNodeError::InvalidBranchIndex(index))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{verify_proof, MapTrieProvider};
+    use alloc::collections::BTreeMap;
+    use alloy_primitives::keccak256;
+    use alloy_trie::{HashBuilder, EMPTY_ROOT_HASH};
+
+    fn keys() -> Vec<Nibbles> {
+        (0u32..64).map(|i| Nibbles::unpack(keccak256(i.to_be_bytes()))).collect()
+    }
+
+    #[test]
+    fn test_arena_matches_trie_node() {
+        let provider = MapTrieProvider::new();
+        let mut arena = ArenaTrie::new(EMPTY_ROOT_HASH);
+        let mut node = TrieNode::Empty;
+        let mut expected = BTreeMap::new();
+        for (i, key) in keys().into_iter().enumerate() {
+            let value = Bytes::from(vec![i as u8; 1 + i % 40]);
+            arena.insert(&key, value.clone(), &provider).unwrap();
+            node.insert(&key, value.clone(), &provider).unwrap();
+            expected.insert(key, value);
+        }
+
+        let mut hb = HashBuilder::default();
+        expected.iter().for_each(|(key, value)| hb.add_leaf(key.clone(), value));
+        assert_eq!(arena.root(), hb.root());
+        assert_eq!(arena.root(), node.root());
+        assert_eq!(arena.to_node(), node);
+
+        for (key, value) in &expected {
+            assert_eq!(arena.get(key, &provider).unwrap().as_ref(), Some(value));
+        }
+        let key = keys()[0].clone();
+        assert_eq!(arena.proof(&key, &provider).unwrap(), node.proof(&key, &provider).unwrap());
+    }
+
+    #[test]
+    fn test_arena_open_blinded_and_delete() {
+        let mut provider = MapTrieProvider::new();
+        let mut node = TrieNode::Empty;
+        for (i, key) in keys().into_iter().enumerate() {
+            node.insert(&key, Bytes::from(vec![i as u8; 40]), &provider).unwrap();
+        }
+        let root = provider.insert_trie(&node);
+
+        let mut arena = ArenaTrie::new(root);
+        let key = keys()[3].clone();
+        let proof = arena.proof(&key, &provider).unwrap();
+        verify_proof(root, &key, Some(&[3; 40][..]), &proof).unwrap();
+
+        *arena.open(&key, &provider).unwrap().unwrap() = Bytes::from_static(&[0xFF]);
+        node.insert(&key, Bytes::from_static(&[0xFF]), &provider).unwrap();
+        assert_eq!(arena.root(), node.root());
+
+        for key in keys() {
+            arena.delete(&key, &provider).unwrap();
+            node.delete(&key, &provider).unwrap();
+            assert_eq!(arena.root(), node.root());
+        }
+        assert_eq!(arena.root(), EMPTY_ROOT_HASH);
+        assert_eq!(arena.to_node(), TrieNode::Empty);
+    }
+
+    #[test]
+    fn test_arena_reuses_freed_slots() {
+        let provider = MapTrieProvider::new();
+        let mut node = TrieNode::Empty;
+        for (i, key) in keys().into_iter().enumerate() {
+            node.insert(&key, Bytes::from(vec![i as u8; 40]), &provider).unwrap();
+        }
+        let mut arena = ArenaTrie::from_node(node.clone()).unwrap();
+        assert_eq!(arena.to_node(), node);
+
+        let capacity = arena.capacity();
+        for key in keys().iter().take(32) {
+            arena.delete(key, &provider).unwrap();
+        }
+        for (i, key) in keys().into_iter().take(32).enumerate() {
+            arena.insert(&key, Bytes::from(vec![i as u8; 40]), &provider).unwrap();
+        }
+        assert_eq!(arena.root(), node.root());
+        assert_eq!(arena.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_arena_rejects_branch_values() {
+        let mut stack = vec![TrieNode::Empty; 17];
+        stack[16] = TrieNode::leaf(Nibbles::default(), Bytes::from_static(&[1]));
+        assert_eq!(
+            ArenaTrie::from_node(TrieNode::Branch { stack }).unwrap_err(),
+            TrieNodeError::BranchValue
+        );
+    }
+}
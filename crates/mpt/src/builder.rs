@@ -0,0 +1,149 @@
+//! This module contains [trie_from_sorted], which builds an opened trie from its leaves in key
+//! order.
+
+use crate::{Nibbles, TrieNode};
+use alloc::{boxed::Box, vec, vec::Vec};
+use alloy_primitives::Bytes;
+use anyhow::Result;
+
+/// The number of children of a branch node, excluding its value.
+const BRANCH_CHILDREN: usize = 16;
+
+/// Builds a fully opened trie from an iterator of `(key, value)` pairs sorted by key.
+///
+/// Each node is constructed exactly once, rather than being split and rebuilt as with repeated
+/// calls to [TrieNode::insert]. The keys must be strictly increasing, and no key may be a prefix
+/// of another, as branch values are not supported.
+pub fn trie_from_sorted<I>(entries: I) -> Result<TrieNode>
+where
+    I: IntoIterator<Item = (Nibbles, Bytes)>,
+{
+    let entries = entries.into_iter().collect::<Vec<_>>();
+    for pair in entries.windows(2) {
+        let (previous, key) = (&pair[0].0, &pair[1].0);
+        if previous >= key {
+            anyhow::bail!("Trie keys are not strictly increasing: {previous:?} >= {key:?}");
+        }
+        if key.starts_with(previous) {
+            anyhow::bail!("Branch values are not supported");
+        }
+    }
+    Ok(build_node(&entries, 0))
+}
+
+/// Builds the node holding the `entries`, all of which share their first `depth` nibbles.
+fn build_node(entries: &[(Nibbles, Bytes)], depth: usize) -> TrieNode {
+    let (first, last) = match entries {
+        [] => return TrieNode::Empty,
+        [(key, value)] => {
+            return TrieNode::leaf(Nibbles::from_nibbles_unchecked(&key[depth..]), value.clone())
+        }
+        [(first, _), .., (last, _)] => (first, last),
+    };
+
+    // As the keys are sorted, the prefix shared by the first and last key is shared by all.
+    let shared = first[depth..].iter().zip(&last[depth..]).take_while(|(a, b)| a == b).count();
+    if shared > 0 {
+        return TrieNode::Extension {
+            prefix: Nibbles::from_nibbles_unchecked(&first[depth..depth + shared]),
+            node: Box::new(build_node(entries, depth + shared)),
+        };
+    }
+
+    // Split the entries into contiguous runs by their nibble at `depth`.
+    let mut stack = vec![TrieNode::Empty; BRANCH_CHILDREN + 1];
+    let mut start = 0;
+    while start < entries.len() {
+        let nibble = entries[start].0[depth];
+        let len = entries[start..].iter().take_while(|(key, _)| key[depth] == nibble).count();
+        stack[nibble as usize] = build_node(&entries[start..start + len], depth + 1);
+        start += len;
+    }
+    TrieNode::Branch { stack }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{test_utils::ordered_trie_with_encoder, MapTrieProvider};
+    use alloc::collections::BTreeMap;
+    use alloy_primitives::keccak256;
+    use alloy_rlp::Encodable;
+    use alloy_trie::{HashBuilder, EMPTY_ROOT_HASH};
+
+    fn hashed_entries(len: u16) -> BTreeMap<Nibbles, Bytes> {
+        (0..len)
+            .map(|i| (Nibbles::unpack(keccak256(i.to_be_bytes())), Bytes::from(vec![i as u8; 40])))
+            .collect()
+    }
+
+    #[test]
+    fn test_trie_from_sorted_matches_hash_builder() {
+        for len in [1, 2, 17, 256, 1024] {
+            let entries = hashed_entries(len);
+            let mut hb = HashBuilder::default();
+            for (key, value) in &entries {
+                hb.add_leaf(key.clone(), value);
+            }
+
+            let trie = trie_from_sorted(entries.clone()).unwrap();
+            assert_eq!(trie.root(), hb.root());
+
+            // The opened trie can be read back without a provider.
+            for (key, value) in &entries {
+                assert_eq!(trie.get(key, &MapTrieProvider::new()).unwrap(), Some(value.clone()));
+            }
+        }
+    }
+
+    #[test]
+    fn test_trie_from_sorted_matches_insertion() {
+        let entries = hashed_entries(64);
+        let mut inserted = TrieNode::Empty;
+        for (key, value) in &entries {
+            inserted.insert(key, value.clone(), &MapTrieProvider::new()).unwrap();
+        }
+        assert_eq!(trie_from_sorted(entries).unwrap().root(), inserted.root());
+    }
+
+    #[test]
+    fn test_trie_from_sorted_list_indices() {
+        const VALUES: [&str; 5] = ["yeah", "dog", ", ", "laminated", "fooz"];
+        let mut hb = ordered_trie_with_encoder(&VALUES, |v, buf| v.encode(buf));
+
+        let entries = VALUES
+            .iter()
+            .enumerate()
+            .map(|(index, value)| {
+                let mut key = Vec::new();
+                index.encode(&mut key);
+                let mut rlp = Vec::new();
+                value.encode(&mut rlp);
+                (Nibbles::unpack(key), Bytes::from(rlp))
+            })
+            .collect::<BTreeMap<_, _>>();
+        assert_eq!(trie_from_sorted(entries).unwrap().root(), hb.root());
+    }
+
+    #[test]
+    fn test_trie_from_sorted_empty() {
+        let trie = trie_from_sorted(core::iter::empty()).unwrap();
+        assert_eq!(trie, TrieNode::Empty);
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn test_trie_from_sorted_rejects_invalid_keys() {
+        let value = Bytes::from_static(&[0x01]);
+        let a = Nibbles::from_nibbles_unchecked([0x01, 0x02]);
+        let b = Nibbles::from_nibbles_unchecked([0x01, 0x03]);
+
+        // Unsorted.
+        assert!(trie_from_sorted([(b.clone(), value.clone()), (a.clone(), value.clone())]).is_err());
+        // Duplicate.
+        assert!(trie_from_sorted([(a.clone(), value.clone()), (a.clone(), value.clone())]).is_err());
+        // Prefix of another key.
+        let prefix = Nibbles::from_nibbles_unchecked([0x01]);
+        assert!(trie_from_sorted([(prefix, value.clone()), (a, value)]).is_err());
+    }
+}
@@ -0,0 +1,179 @@
+//! Contains [code_by_hash], which fetches and verifies contract bytecode through a
+//! [TrieProvider].
+
+use crate::{TrieHinter, TrieProvider};
+use alloc::string::{String, ToString};
+use alloy_primitives::{keccak256, Address, B256};
+use core::fmt::Display;
+use revm_primitives::{Bytecode, KECCAK_EMPTY, MAX_CODE_SIZE};
+
+/// An error returned by [code_by_hash].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodeError {
+    /// The host could not be hinted about the bytecode.
+    Hint(String),
+    /// The [TrieProvider] failed to return the bytecode.
+    Fetch(String),
+    /// The bytecode is larger than the maximum code size of [EIP-170].
+    ///
+    /// [EIP-170]: https://eips.ethereum.org/EIPS/eip-170
+    TooLarge(usize),
+    /// The bytecode does not hash to the requested code hash.
+    HashMismatch {
+        /// The requested code hash.
+        expected: B256,
+        /// The hash of the returned bytecode.
+        actual: B256,
+    },
+}
+
+impl Display for CodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            CodeError::Hint(e) => write!(f, "Failed to hint bytecode: {e}"),
+            CodeError::Fetch(e) => write!(f, "Failed to fetch bytecode: {e}"),
+            CodeError::TooLarge(len) => {
+                write!(f, "Bytecode of {len} bytes exceeds the maximum of {MAX_CODE_SIZE} bytes")
+            }
+            CodeError::HashMismatch { expected, actual } => {
+                write!(f, "Bytecode hash mismatch, expected {expected}, got {actual}")
+            }
+        }
+    }
+}
+
+/// Fetches the bytecode with the given code hash, deployed at `address`, from the `provider`.
+///
+/// The host is hinted about the bytecode with [TrieHinter::hint_code] before it is fetched. The
+/// returned bytes are untrusted: they must not exceed the maximum code size of [EIP-170], which
+/// also bounds the initcode of [EIP-3860] once deployed, and must hash to `code_hash`. Accounts
+/// without code short-circuit to empty bytecode, without a hint or a fetch.
+///
+/// [EIP-170]: https://eips.ethereum.org/EIPS/eip-170
+/// [EIP-3860]: https://eips.ethereum.org/EIPS/eip-3860
+pub fn code_by_hash<P, H>(
+    provider: &P,
+    hinter: &H,
+    code_hash: B256,
+    address: Address,
+) -> Result<Bytecode, CodeError>
+where
+    P: TrieProvider,
+    H: TrieHinter,
+{
+    if code_hash == KECCAK_EMPTY {
+        return Ok(Bytecode::new());
+    }
+
+    hinter.hint_code(code_hash, address).map_err(|e| CodeError::Hint(e.to_string()))?;
+    let code = provider.bytecode_by_hash(code_hash).map_err(|e| CodeError::Fetch(e.to_string()))?;
+
+    if code.len() > MAX_CODE_SIZE {
+        return Err(CodeError::TooLarge(code.len()));
+    }
+    let actual = keccak256(&code);
+    if actual != code_hash {
+        return Err(CodeError::HashMismatch { expected: code_hash, actual });
+    }
+
+    Ok(Bytecode::new_raw(code))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::{vec, vec::Vec};
+    use alloy_primitives::{address, Bytes};
+    use anyhow::{anyhow, Result};
+    use core::cell::RefCell;
+
+    const ADDRESS: Address = address!("4200000000000000000000000000000000000015");
+
+    /// A [TrieProvider] that returns the same bytecode for every code hash, and records the
+    /// requests and hints it receives.
+    #[derive(Default)]
+    struct MockProvider {
+        code: Option<Bytes>,
+        fetches: RefCell<Vec<B256>>,
+        hints: RefCell<Vec<(B256, Address)>>,
+    }
+
+    impl TrieProvider for MockProvider {
+        fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+            Err(anyhow!("unknown trie node {key}"))
+        }
+
+        fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+            self.fetches.borrow_mut().push(code_hash);
+            self.code.clone().ok_or_else(|| anyhow!("unknown bytecode {code_hash}"))
+        }
+    }
+
+    impl TrieHinter for MockProvider {
+        fn hint_trie_node(&self, _: B256) -> Result<()> {
+            Ok(())
+        }
+
+        fn hint_code(&self, code_hash: B256, address: Address) -> Result<()> {
+            self.hints.borrow_mut().push((code_hash, address));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_code_by_hash() {
+        let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+        let code_hash = keccak256(&code);
+        let provider = MockProvider { code: Some(code.clone()), ..Default::default() };
+
+        let bytecode = code_by_hash(&provider, &provider, code_hash, ADDRESS).unwrap();
+        assert_eq!(bytecode, Bytecode::new_raw(code));
+        assert_eq!(*provider.hints.borrow(), vec![(code_hash, ADDRESS)]);
+        assert_eq!(*provider.fetches.borrow(), vec![code_hash]);
+    }
+
+    #[test]
+    fn test_code_by_hash_empty() {
+        let provider = MockProvider::default();
+        let bytecode = code_by_hash(&provider, &provider, KECCAK_EMPTY, ADDRESS).unwrap();
+        assert_eq!(bytecode, Bytecode::new());
+        assert!(provider.hints.borrow().is_empty());
+        assert!(provider.fetches.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_code_by_hash_wrong_hash() {
+        let code = Bytes::from_static(&[0x60, 0x00]);
+        let provider = MockProvider { code: Some(code.clone()), ..Default::default() };
+        let requested = B256::repeat_byte(0xAA);
+        assert_eq!(
+            code_by_hash(&provider, &provider, requested, ADDRESS),
+            Err(CodeError::HashMismatch { expected: requested, actual: keccak256(&code) })
+        );
+    }
+
+    #[test]
+    fn test_code_by_hash_too_large() {
+        let code = Bytes::from(vec![0x5b; MAX_CODE_SIZE + 1]);
+        let code_hash = keccak256(&code);
+        let provider = MockProvider { code: Some(code), ..Default::default() };
+        assert_eq!(
+            code_by_hash(&provider, &provider, code_hash, ADDRESS),
+            Err(CodeError::TooLarge(MAX_CODE_SIZE + 1))
+        );
+
+        // The maximum code size itself is allowed.
+        let code = Bytes::from(vec![0x5b; MAX_CODE_SIZE]);
+        let code_hash = keccak256(&code);
+        let provider = MockProvider { code: Some(code), ..Default::default() };
+        assert!(code_by_hash(&provider, &provider, code_hash, ADDRESS).is_ok());
+    }
+
+    #[test]
+    fn test_code_by_hash_fetch_error() {
+        let provider = MockProvider::default();
+        let code_hash = B256::repeat_byte(0xAA);
+        let err = code_by_hash(&provider, &provider, code_hash, ADDRESS).unwrap_err();
+        assert_eq!(err, CodeError::Fetch(alloc::format!("unknown bytecode {code_hash}")));
+    }
+}
@@ -0,0 +1,71 @@
+//! Contains the [AncestorCache], which memoizes the hashes of the ancestors of the block a
+//! [TrieDB] executes on top of.
+//!
+//! [TrieDB]: crate::TrieDB
+
+use alloc::collections::VecDeque;
+use alloy_primitives::B256;
+use revm::primitives::BLOCK_HASH_HISTORY;
+
+/// An [AncestorCache] is a ring buffer of the `(number, hash)` pairs of the most recent
+/// [BLOCK_HASH_HISTORY] ancestors, newest first. The ancestors are contiguous, so the cached
+/// entry `i` blocks below the newest one holds the hash of block `newest - i`.
+///
+/// The cache starts out empty and is filled as the parent chain is walked back, so only the
+/// headers that `BLOCKHASH` actually reaches are fetched.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(crate) struct AncestorCache {
+    /// The cached `(number, hash)` pairs, newest first.
+    entries: VecDeque<(u64, B256)>,
+    /// The parent hash of the oldest cached ancestor, where the walk continues.
+    next_parent: B256,
+}
+
+impl AncestorCache {
+    /// Returns whether the cache holds no ancestors.
+    pub(crate) fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the number of cached ancestors.
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns the oldest cached ancestor, and the hash of its parent.
+    pub(crate) fn oldest(&self) -> Option<((u64, B256), B256)> {
+        self.entries.back().map(|oldest| (*oldest, self.next_parent))
+    }
+
+    /// Returns the hash of the ancestor `depth` blocks below the newest one, if it is cached.
+    pub(crate) fn get(&self, depth: usize) -> Option<B256> {
+        self.entries.get(depth).map(|(_, hash)| *hash)
+    }
+
+    /// Appends the parent of the oldest cached ancestor, whose own parent hash is `parent_hash`.
+    /// The caller validates that `number` continues the chain. Appending to a full cache is a
+    /// no-op, as the ancestor is out of reach of `BLOCKHASH`.
+    pub(crate) fn push_oldest(&mut self, number: u64, hash: B256, parent_hash: B256) {
+        if self.entries.len() < BLOCK_HASH_HISTORY {
+            self.entries.push_back((number, hash));
+            self.next_parent = parent_hash;
+        }
+    }
+
+    /// Prepends the child of the newest cached ancestor, once the [TrieDB] advanced onto it. The
+    /// caller validates that `number` continues the chain. The oldest ancestor is evicted once it
+    /// falls out of reach of `BLOCKHASH`. An empty cache is left empty, as the walk starts from
+    /// the parent header.
+    ///
+    /// [TrieDB]: crate::TrieDB
+    pub(crate) fn push_newest(&mut self, number: u64, hash: B256) {
+        if self.entries.is_empty() {
+            return;
+        }
+        self.entries.push_front((number, hash));
+        if self.entries.len() > BLOCK_HASH_HISTORY {
+            let (_, evicted) = self.entries.pop_back().expect("Cannot be empty");
+            self.next_parent = evicted;
+        }
+    }
+}
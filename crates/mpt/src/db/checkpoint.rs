@@ -0,0 +1,39 @@
+//! Contains the [TrieDBCheckpoint], a snapshot of the state served by a [TrieDB].
+//!
+//! [TrieDB]: crate::TrieDB
+
+use super::{AncestorCache, StateDiff};
+use crate::{TrieDBHeader, TrieNode};
+use alloc::collections::BTreeMap;
+use alloy_primitives::{Address, B256};
+
+/// A [TrieDBCheckpoint] is a snapshot of the state served by a [TrieDB], taken with
+/// [TrieDB::checkpoint] and restored with [TrieDB::revert]. It holds the opened state and storage
+/// tries, the pending [StateDiff] and the block the [TrieDB] is anchored on, which are plain
+/// data, so taking it costs a clone of the paths opened so far and no fetches.
+///
+/// [TrieDB]: crate::TrieDB
+/// [TrieDB::checkpoint]: crate::TrieDB::checkpoint
+/// [TrieDB::revert]: crate::TrieDB::revert
+#[derive(Debug, Clone)]
+pub struct TrieDBCheckpoint<B: TrieDBHeader = alloy_consensus::Header> {
+    /// The root node of the state trie.
+    pub(crate) root_node: TrieNode,
+    /// The root nodes of the storage tries of the loaded accounts, keyed by address.
+    pub(crate) storage_roots: BTreeMap<Address, TrieNode>,
+    /// The changes applied to the state since the diff was last taken.
+    pub(crate) state_diff: StateDiff<B::Account>,
+    /// The header of the parent block.
+    pub(crate) parent_header: B,
+    /// The hash of the parent block.
+    pub(crate) parent_hash: B256,
+    /// The hashes of the ancestors walked so far.
+    pub(crate) ancestors: AncestorCache,
+}
+
+impl<B: TrieDBHeader> TrieDBCheckpoint<B> {
+    /// Returns the state root at the checkpoint.
+    pub fn state_root(&self) -> B256 {
+        self.root_node.root()
+    }
+}
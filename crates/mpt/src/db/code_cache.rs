@@ -0,0 +1,105 @@
+//! Contains the [CodeCache], the bytecode cache of the [TrieDB].
+//!
+//! [TrieDB]: crate::TrieDB
+
+use alloy_primitives::B256;
+use lru::LruCache;
+use revm::primitives::Bytecode;
+
+/// A [CodeCache] is an LRU cache of contract bytecode keyed by code hash, bounded by the total
+/// size of the bytecode it holds.
+#[derive(Debug, Clone)]
+pub(crate) struct CodeCache {
+    /// The cached bytecode, from the most to the least recently used.
+    entries: LruCache<B256, Bytecode>,
+    /// The total size of the cached bytecode, in bytes.
+    size: usize,
+    /// The maximum total size of the cached bytecode, in bytes.
+    max_size: usize,
+}
+
+impl CodeCache {
+    /// Creates a new, empty [CodeCache] holding up to `max_size` bytes of bytecode.
+    pub(crate) fn new(max_size: usize) -> Self {
+        Self { entries: LruCache::unbounded(), size: 0, max_size }
+    }
+
+    /// Returns the total size of the cached bytecode, in bytes.
+    pub(crate) const fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Returns the cached bytecode with the given code hash, marking it as the most recently
+    /// used.
+    pub(crate) fn get(&mut self, code_hash: &B256) -> Option<Bytecode> {
+        self.entries.get(code_hash).cloned()
+    }
+
+    /// Caches the `code` with the given code hash, evicting the least recently used bytecode
+    /// until the cache fits in its maximum size. Bytecode larger than the whole cache is not
+    /// cached.
+    pub(crate) fn insert(&mut self, code_hash: B256, code: Bytecode) {
+        if code.len() > self.max_size {
+            return;
+        }
+
+        self.size += code.len();
+        if let Some(replaced) = self.entries.put(code_hash, code) {
+            self.size -= replaced.len();
+        }
+        self.evict();
+    }
+
+    /// Changes the maximum size of the cache to `max_size` bytes, evicting the least recently
+    /// used bytecode if it no longer fits.
+    pub(crate) fn resize(&mut self, max_size: usize) {
+        self.max_size = max_size;
+        self.evict();
+    }
+
+    /// Evicts the least recently used bytecode until the cache fits in its maximum size.
+    fn evict(&mut self) {
+        while self.size > self.max_size {
+            let Some((_, evicted)) = self.entries.pop_lru() else { break };
+            self.size -= evicted.len();
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::Bytes;
+
+    fn code(byte: u8, len: usize) -> (B256, Bytecode) {
+        (B256::repeat_byte(byte), Bytecode::new_raw(Bytes::from(alloc::vec![byte; len])))
+    }
+
+    #[test]
+    fn test_code_cache_evicts_least_recently_used() {
+        let mut cache = CodeCache::new(100);
+        let (a, b, c) = (code(1, 40), code(2, 40), code(3, 40));
+        cache.insert(a.0, a.1.clone());
+        cache.insert(b.0, b.1);
+        assert_eq!(cache.get(&a.0), Some(a.1));
+
+        // `b` is the least recently used, and is evicted to make room for `c`.
+        cache.insert(c.0, c.1.clone());
+        assert_eq!(cache.size(), 80);
+        assert_eq!(cache.get(&b.0), None);
+        assert_eq!(cache.get(&c.0), Some(c.1));
+
+        cache.resize(40);
+        assert_eq!(cache.size(), 40);
+        assert_eq!(cache.get(&a.0), None);
+    }
+
+    #[test]
+    fn test_code_cache_skips_oversized_code() {
+        let mut cache = CodeCache::new(10);
+        let (code_hash, code) = code(1, 11);
+        cache.insert(code_hash, code);
+        assert_eq!(cache.size(), 0);
+        assert_eq!(cache.get(&code_hash), None);
+    }
+}
@@ -0,0 +1,1361 @@
+//! This module contains the [TrieDB], a [revm] [Database] that serves the state of a parent block
+//! from its state trie, unblinding the trie nodes it needs on the fly.
+
+use crate::{
+    code_by_hash, secure::hashed_path, CodeError, TrieAccount, TrieHinter, TrieNode, TrieNodeError,
+    TrieProvider,
+};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_rlp::Decodable;
+use core::fmt::Display;
+use revm::{
+    db::BundleState,
+    primitives::{AccountInfo, Bytecode, BLOCK_HASH_HISTORY},
+    Database, DatabaseRef,
+};
+use spin::Mutex;
+
+mod ancestors;
+use ancestors::AncestorCache;
+
+mod storage;
+use storage::{is_slot_changed, StorageUpdate};
+
+mod primitives;
+pub use primitives::{TrieDBAccount, TrieDBHeader};
+
+mod state_diff;
+pub use state_diff::{AccountDiff, StateDiff};
+
+mod checkpoint;
+pub use checkpoint::TrieDBCheckpoint;
+
+mod code_cache;
+use code_cache::CodeCache;
+
+mod node_cache;
+use node_cache::NodeCache;
+
+mod node_store;
+pub use node_store::SharedNodeStore;
+
+/// An error returned by the [TrieDB].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrieDBError {
+    /// A trie node could not be opened.
+    TrieNode(TrieNodeError),
+    /// An account or storage value in the trie is malformed.
+    Rlp(alloy_rlp::Error),
+    /// The bytecode of an account could not be loaded.
+    Code(CodeError),
+    /// The host could not be hinted about a preimage.
+    Hint(String),
+    /// The [TrieProvider] failed to return a block header.
+    Provider(String),
+    /// The bytecode was requested by a code hash that no loaded account holds, so the host
+    /// cannot be told where it is deployed.
+    UnknownCode(B256),
+    /// The preimage of a block header does not hash to the requested hash.
+    HeaderMismatch(B256),
+    /// The parent of a block header does not have the number right below it.
+    InvalidParentNumber {
+        /// The hash of the parent header.
+        hash: B256,
+        /// The number the parent header should have.
+        expected: u64,
+        /// The number of the parent header.
+        actual: u64,
+    },
+    /// The hash of a block more than [BLOCK_HASH_HISTORY] blocks below the executed block was
+    /// requested.
+    BlockNumberPastHorizon(u64),
+    /// The header the [TrieDB] was asked to advance onto is not a child of its parent block.
+    InvalidChildHeader(B256),
+    /// The state root of the header the [TrieDB] was asked to advance onto does not match the
+    /// state root of the applied changes.
+    StateRootMismatch {
+        /// The state root of the header.
+        expected: B256,
+        /// The state root of the state served by the [TrieDB].
+        actual: B256,
+    },
+}
+
+impl Display for TrieDBError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TrieDBError::TrieNode(e) => write!(f, "Failed to open trie node: {e}"),
+            TrieDBError::Rlp(e) => write!(f, "Failed to decode trie value: {e}"),
+            TrieDBError::Code(e) => write!(f, "Failed to load bytecode: {e}"),
+            TrieDBError::Hint(e) => write!(f, "Failed to send hint: {e}"),
+            TrieDBError::Provider(e) => write!(f, "Failed to fetch block header: {e}"),
+            TrieDBError::UnknownCode(code_hash) => {
+                write!(f, "Bytecode {code_hash} does not belong to a loaded account")
+            }
+            TrieDBError::HeaderMismatch(hash) => {
+                write!(f, "Block header preimage does not match hash {hash}")
+            }
+            TrieDBError::InvalidParentNumber { hash, expected, actual } => {
+                write!(f, "Parent header {hash} has number {actual}, expected {expected}")
+            }
+            TrieDBError::BlockNumberPastHorizon(number) => {
+                write!(f, "Block {number} is more than {BLOCK_HASH_HISTORY} blocks in the past")
+            }
+            TrieDBError::InvalidChildHeader(hash) => {
+                write!(f, "Header {hash} is not a child of the parent block")
+            }
+            TrieDBError::StateRootMismatch { expected, actual } => {
+                write!(f, "State root mismatch: expected {expected}, got {actual}")
+            }
+        }
+    }
+}
+
+impl From<TrieNodeError> for TrieDBError {
+    fn from(e: TrieNodeError) -> Self {
+        TrieDBError::TrieNode(e)
+    }
+}
+
+impl From<alloy_rlp::Error> for TrieDBError {
+    fn from(e: alloy_rlp::Error) -> Self {
+        TrieDBError::Rlp(e)
+    }
+}
+
+impl From<CodeError> for TrieDBError {
+    fn from(e: CodeError) -> Self {
+        TrieDBError::Code(e)
+    }
+}
+
+/// A [TrieDB] serves the state of the parent block of the block being executed to [revm], given
+/// only the trusted parent header. The accounts and storage slots are read from the state trie
+/// committed to by the header, and the trie nodes on their paths are fetched through the
+/// [TrieProvider] as they are first touched. Opened paths are kept in memory, so later reads that
+/// share them do not fetch them again.
+///
+/// The hashes of the ancestors of the parent block, needed by `BLOCKHASH`, are resolved by walking
+/// the parent hashes of the headers returned by [TrieProvider::header_by_hash]. Each header is
+/// verified against the hash it was requested by and must be numbered right below its child.
+/// The walked hashes are cached, and the walk never reaches further back than the
+/// [BLOCK_HASH_HISTORY] blocks `BLOCKHASH` has access to.
+///
+/// The [TrieDB] also implements [DatabaseRef], so that a single database can be shared across
+/// threads, such as to execute transactions in parallel. Reads through a shared reference do not
+/// open the paths they walk, and fetch their nodes through the trie node cache instead. The
+/// caches the reads fill are guarded by spin locks, so the [TrieDB] is [Sync] whenever its
+/// provider and hinter are.
+///
+/// The block header, and with it the account type of the state trie, is generic over
+/// [TrieDBHeader], and defaults to the alloy [Header] with the Ethereum [TrieAccount].
+///
+/// [Header]: alloy_consensus::Header
+#[derive(Debug)]
+pub struct TrieDB<P, H, B: TrieDBHeader = alloy_consensus::Header> {
+    /// The root node of the state trie.
+    root_node: TrieNode,
+    /// The root nodes of the storage tries of the loaded accounts, keyed by address.
+    storage_roots: BTreeMap<Address, TrieNode>,
+    /// The address of a loaded account holding each code hash, used to hint the host about the
+    /// bytecode.
+    code_addresses: Mutex<BTreeMap<B256, Address>>,
+    /// The bytecode loaded so far, and deployed by the applied bundles.
+    code_cache: Mutex<CodeCache>,
+    /// The changes applied to the state since the diff was last taken.
+    state_diff: StateDiff<B::Account>,
+    /// The header of the parent block.
+    parent_header: B,
+    /// The hash of the parent block.
+    parent_hash: B256,
+    /// The hashes of the ancestors walked so far.
+    ancestors: Mutex<AncestorCache>,
+    /// The provider used to fetch trie nodes, bytecode and headers, behind the trie node cache.
+    provider: NodeCache<P>,
+    /// The hinter used to prepare preimages on the host.
+    hinter: H,
+}
+
+impl<P, H, B> TrieDB<P, H, B>
+where
+    P: TrieProvider,
+    H: TrieHinter,
+    B: TrieDBHeader,
+{
+    /// The default maximum size of the bytecode cache, in bytes.
+    pub const DEFAULT_CODE_CACHE_SIZE: usize = 8 * 1024 * 1024;
+
+    /// The default maximum number of trie nodes held by the trie node cache.
+    pub const DEFAULT_NODE_CACHE_CAPACITY: usize = 4096;
+
+    /// Creates a new [TrieDB] that serves the state committed to by the `parent_header`.
+    pub fn new(parent_header: B, provider: P, hinter: H) -> Self {
+        Self {
+            root_node: TrieNode::Blinded { commitment: parent_header.state_root() },
+            storage_roots: BTreeMap::new(),
+            code_addresses: Mutex::new(BTreeMap::new()),
+            code_cache: Mutex::new(CodeCache::new(Self::DEFAULT_CODE_CACHE_SIZE)),
+            state_diff: StateDiff::default(),
+            parent_hash: parent_header.hash_slow(),
+            parent_header,
+            ancestors: Mutex::new(AncestorCache::default()),
+            provider: NodeCache::new(provider, Self::DEFAULT_NODE_CACHE_CAPACITY),
+            hinter,
+        }
+    }
+
+    /// Sets the maximum size of the bytecode cache to `max_size` bytes. Loaded bytecode is
+    /// cached by code hash, so that accounts sharing code, and later calls to
+    /// [Database::code_by_hash], do not fetch it again. The cache is kept when the [TrieDB]
+    /// applies a bundle, which also caches the bytecode the bundle deploys.
+    pub fn with_code_cache_size(mut self, max_size: usize) -> Self {
+        self.code_cache.get_mut().resize(max_size);
+        self
+    }
+
+    /// Sets the maximum number of trie nodes held by the trie node cache to `capacity`. The
+    /// preimages of the trie nodes fetched through the provider are cached by commitment, so
+    /// that nodes opened again, after the trie holding them was dropped or reverted, do not
+    /// round trip to the host. Zero disables the cache.
+    pub fn with_node_cache_capacity(mut self, capacity: usize) -> Self {
+        self.provider.resize(capacity);
+        self
+    }
+
+    /// Attaches the [TrieDB] to the shared node `store`. The trie nodes the [TrieDB] fetches are
+    /// held in the store until the [TrieDB] is dropped, and the nodes fetched by the other
+    /// instances attached to it are served without a round trip to the host. Consecutive blocks
+    /// executed with a [TrieDB] each then only fetch the subtrees the previous blocks did not.
+    pub fn with_node_store(mut self, store: SharedNodeStore) -> Self {
+        self.provider.set_store(store);
+        self
+    }
+
+    /// Returns the number of trie nodes held by the trie node cache.
+    pub fn cached_nodes(&self) -> usize {
+        self.provider.len()
+    }
+
+    /// Returns the total size of the cached bytecode, in bytes.
+    pub fn cached_code_size(&self) -> usize {
+        self.code_cache.lock().size()
+    }
+
+    /// Returns the header of the parent block.
+    pub const fn parent_header(&self) -> &B {
+        &self.parent_header
+    }
+
+    /// Returns the hash of the parent block.
+    pub const fn parent_hash(&self) -> B256 {
+        self.parent_hash
+    }
+
+    /// Returns the root node of the state trie, with the paths opened so far.
+    pub const fn root_node(&self) -> &TrieNode {
+        &self.root_node
+    }
+
+    /// Returns the root node of the storage trie of the loaded account at `address`, with the
+    /// paths opened so far, or `None` if the account has not been loaded. The storage trie of an
+    /// account starts out blinded when the account is loaded, and only the paths of the slots
+    /// that are read or written are opened.
+    pub fn storage_trie(&self, address: Address) -> Option<&TrieNode> {
+        self.storage_roots.get(&address)
+    }
+
+    /// Returns the provider used to fetch trie nodes, bytecode and headers.
+    pub const fn provider(&self) -> &P {
+        self.provider.inner()
+    }
+
+    /// Returns the hinter used to prepare preimages on the host.
+    pub const fn hinter(&self) -> &H {
+        &self.hinter
+    }
+
+    /// Returns the account at `address` in the state trie, or `None` if the account does not
+    /// exist. The path to the account is kept open.
+    pub fn get_trie_account(
+        &mut self,
+        address: Address,
+    ) -> Result<Option<B::Account>, TrieDBError> {
+        match self.root_node.open(&hashed_path(address), &self.provider)? {
+            Some(value) => Ok(Some(B::Account::decode(&mut value.as_ref())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Applies the account and storage changes of a [BundleState], such as the one merged by a
+    /// [State] after executing a block, to the state trie, and returns the new state root.
+    ///
+    /// Accounts that did not exist are created with an empty storage trie. Destroyed accounts,
+    /// by `SELFDESTRUCT` or by the removal of empty touched accounts of [EIP-161], have their
+    /// storage trie wiped, and are deleted from the state trie unless they were created again
+    /// later in the bundle.
+    ///
+    /// Only the paths of the modified accounts and slots are opened. The updated tries stay open,
+    /// so the [TrieDB] keeps serving the post state to later reads.
+    ///
+    /// [State]: revm::db::State
+    /// [EIP-161]: https://eips.ethereum.org/EIPS/eip-161
+    pub fn state_root(&mut self, bundle: &BundleState) -> Result<B256, TrieDBError> {
+        self.cache_contracts(bundle);
+        let updates = self.storage_updates(bundle)?;
+        let updated = updates
+            .into_iter()
+            .map(|update| update.apply(&self.provider))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.update_accounts(bundle, updated)
+    }
+
+    /// Computes the same state root as [TrieDB::state_root], updating the storage tries of the
+    /// accounts with changed storage in parallel. The account trie is still updated serially.
+    #[cfg(feature = "rayon")]
+    pub fn par_state_root(&mut self, bundle: &BundleState) -> Result<B256, TrieDBError>
+    where
+        P: Sync,
+    {
+        use rayon::prelude::*;
+
+        self.cache_contracts(bundle);
+        let updates = self.storage_updates(bundle)?;
+        let provider = &self.provider;
+        let updated = updates
+            .into_par_iter()
+            .map(|update| update.apply(provider))
+            .collect::<Result<Vec<_>, _>>()?;
+        self.update_accounts(bundle, updated)
+    }
+
+    /// Returns the [StateDiff] of the bundles applied by [TrieDB::state_root] since the diff was
+    /// last taken, and starts a new, empty one.
+    pub fn take_state_diff(&mut self) -> StateDiff<B::Account> {
+        core::mem::take(&mut self.state_diff)
+    }
+
+    /// Takes a [TrieDBCheckpoint] of the state served by the [TrieDB], such as before applying a
+    /// block speculatively with [TrieDB::state_root].
+    pub fn checkpoint(&self) -> TrieDBCheckpoint<B> {
+        TrieDBCheckpoint {
+            root_node: self.root_node.clone(),
+            storage_roots: self.storage_roots.clone(),
+            state_diff: self.state_diff.clone(),
+            parent_header: self.parent_header.clone(),
+            parent_hash: self.parent_hash,
+            ancestors: self.ancestors.lock().clone(),
+        }
+    }
+
+    /// Reverts the state served by the [TrieDB] to the `checkpoint`, such as when the state root
+    /// of a speculatively applied block does not match. The bytecode and trie node caches are
+    /// kept, as they are keyed by hash and stay valid.
+    pub fn revert(&mut self, checkpoint: TrieDBCheckpoint<B>) {
+        self.root_node = checkpoint.root_node;
+        self.storage_roots = checkpoint.storage_roots;
+        self.state_diff = checkpoint.state_diff;
+        self.parent_header = checkpoint.parent_header;
+        self.parent_hash = checkpoint.parent_hash;
+        *self.ancestors.get_mut() = checkpoint.ancestors;
+    }
+
+    /// Advances the [TrieDB] onto the block with the given `header`, once the changes of the
+    /// block were applied with [TrieDB::state_root], so that it serves the state of the next
+    /// block. The header must be a child of the parent block and commit to the state root of the
+    /// applied changes.
+    ///
+    /// The opened tries and the caches are kept, so a client program executing several blocks
+    /// does not rebuild the [TrieDB] or fetch the shared subtrees again. The pending [StateDiff]
+    /// is kept too, and is taken with [TrieDB::take_state_diff].
+    pub fn advance(&mut self, header: B) -> Result<(), TrieDBError> {
+        let hash = header.hash_slow();
+        if header.parent_hash() != self.parent_hash ||
+            header.number() != self.parent_header.number() + 1
+        {
+            return Err(TrieDBError::InvalidChildHeader(hash));
+        }
+        let actual = self.root_node.root();
+        if header.state_root() != actual {
+            return Err(TrieDBError::StateRootMismatch { expected: header.state_root(), actual });
+        }
+
+        self.ancestors.get_mut().push_newest(header.number(), hash);
+        self.parent_header = header;
+        self.parent_hash = hash;
+        Ok(())
+    }
+
+    /// Caches the bytecode deployed by the `bundle`, which the provider may not know yet, and
+    /// records it in the [StateDiff].
+    fn cache_contracts(&mut self, bundle: &BundleState) {
+        for (code_hash, code) in bundle.contracts.iter() {
+            self.code_cache.get_mut().insert(*code_hash, code.clone());
+            self.state_diff.contracts.insert(*code_hash, code.original_bytes());
+        }
+    }
+
+    /// Takes the storage tries of the accounts of the `bundle` with changed storage out of the
+    /// [TrieDB], paired with their changes. Wiped storage tries are replaced with an empty one.
+    fn storage_updates<'a>(
+        &mut self,
+        bundle: &'a BundleState,
+    ) -> Result<Vec<StorageUpdate<'a>>, TrieDBError> {
+        let mut updates = Vec::new();
+        for (address, account) in bundle.state() {
+            if account.status.is_not_modified() || account.info.is_none() {
+                continue;
+            }
+
+            let mut update = StorageUpdate {
+                address: *address,
+                trie: TrieNode::Empty,
+                storage: &account.storage,
+                wiped: account.was_destroyed(),
+            };
+            if !update.is_dirty() {
+                continue;
+            }
+            if !update.wiped && self.load_storage_root(*address)? {
+                update.trie = self.storage_roots.remove(address).expect("Loaded above");
+            }
+            updates.push(update);
+        }
+        Ok(updates)
+    }
+
+    /// Puts the `updated` storage tries back into the [TrieDB], and writes the modified accounts
+    /// of the `bundle` to the state trie. Returns the new state root.
+    fn update_accounts(
+        &mut self,
+        bundle: &BundleState,
+        updated: Vec<(Address, TrieNode, B256)>,
+    ) -> Result<B256, TrieDBError> {
+        let mut storage_roots = BTreeMap::new();
+        for (address, trie, root) in updated {
+            self.storage_roots.insert(address, trie);
+            storage_roots.insert(address, root);
+        }
+
+        for (address, account) in bundle.state() {
+            if account.status.is_not_modified() {
+                continue;
+            }
+
+            let path = hashed_path(address);
+            let Some(info) = account.account_info() else {
+                self.root_node.delete(&path, &self.provider)?;
+                self.storage_roots.remove(address);
+                self.state_diff.wipe_storage(*address);
+                self.state_diff.set_account(*address, None);
+                continue;
+            };
+
+            let wiped = account.was_destroyed();
+            if wiped {
+                self.state_diff.wipe_storage(*address);
+            }
+            for (slot, value) in account.storage.iter() {
+                if is_slot_changed(value, wiped) {
+                    self.state_diff.set_storage(*address, B256::from(*slot), value.present_value);
+                }
+            }
+
+            let storage_root = match storage_roots.get(address) {
+                Some(root) => *root,
+                None if self.load_storage_root(*address)? => self.storage_roots[address].root(),
+                None => {
+                    self.storage_roots.insert(*address, TrieNode::Empty);
+                    TrieAccount::EMPTY_ROOT
+                }
+            };
+            let trie_account = B::Account::from_info(info, storage_root);
+            self.root_node.insert(
+                &path,
+                alloy_rlp::encode(&trie_account).into(),
+                &self.provider,
+            )?;
+            self.state_diff.set_account(*address, Some(trie_account));
+        }
+        Ok(self.root_node.root())
+    }
+
+    /// Loads the storage root of the account at `address`, if it has not been loaded yet.
+    /// Returns whether the account exists.
+    fn load_storage_root(&mut self, address: Address) -> Result<bool, TrieDBError> {
+        if self.storage_roots.contains_key(&address) {
+            return Ok(true);
+        }
+        let Some(account) = self.get_trie_account(address)? else {
+            return Ok(false);
+        };
+        self.storage_roots
+            .insert(address, TrieNode::Blinded { commitment: account.storage_root() });
+        Ok(true)
+    }
+
+    /// Returns the account at `address` in the state trie, or `None` if the account does not
+    /// exist, without opening the path to the account.
+    fn trie_account_ref(&self, address: Address) -> Result<Option<B::Account>, TrieDBError> {
+        match self.root_node.get(&hashed_path(address), &self.provider)? {
+            Some(value) => Ok(Some(B::Account::decode(&mut value.as_ref())?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Fetches the header with the given hash, verifying the preimage against the hash.
+    fn fetch_header(&self, hash: B256) -> Result<B, TrieDBError> {
+        let preimage =
+            self.provider.header_by_hash(hash).map_err(|e| TrieDBError::Provider(e.to_string()))?;
+        if keccak256(&preimage) != hash {
+            return Err(TrieDBError::HeaderMismatch(hash));
+        }
+        Ok(B::decode(&mut preimage.as_ref())?)
+    }
+
+    /// Returns the hash of the ancestor `depth` blocks below the parent block, walking the parent
+    /// chain back from the oldest cached ancestor as far as needed. The ancestor cache stays
+    /// locked during the walk, so concurrent walks do not fetch the same headers.
+    fn ancestor_hash(&self, depth: usize) -> Result<B256, TrieDBError> {
+        let mut ancestors = self.ancestors.lock();
+        if ancestors.is_empty() {
+            ancestors.push_oldest(
+                self.parent_header.number(),
+                self.parent_hash,
+                self.parent_header.parent_hash(),
+            );
+        }
+
+        while ancestors.len() <= depth {
+            // The oldest ancestor is above the requested block, so it is not the genesis block.
+            let ((child_number, _), hash) = ancestors.oldest().expect("Cannot be empty");
+            let header = self.fetch_header(hash)?;
+            let expected = child_number - 1;
+            if header.number() != expected {
+                return Err(TrieDBError::InvalidParentNumber {
+                    hash,
+                    expected,
+                    actual: header.number(),
+                });
+            }
+            ancestors.push_oldest(header.number(), hash, header.parent_hash());
+        }
+
+        Ok(ancestors.get(depth).expect("Walked above"))
+    }
+}
+
+impl<P, H, B> Clone for TrieDB<P, H, B>
+where
+    P: Clone,
+    H: Clone,
+    B: TrieDBHeader,
+{
+    fn clone(&self) -> Self {
+        Self {
+            root_node: self.root_node.clone(),
+            storage_roots: self.storage_roots.clone(),
+            code_addresses: Mutex::new(self.code_addresses.lock().clone()),
+            code_cache: Mutex::new(self.code_cache.lock().clone()),
+            state_diff: self.state_diff.clone(),
+            parent_header: self.parent_header.clone(),
+            parent_hash: self.parent_hash,
+            ancestors: Mutex::new(self.ancestors.lock().clone()),
+            provider: self.provider.clone(),
+            hinter: self.hinter.clone(),
+        }
+    }
+}
+
+impl<P, H, B> Database for TrieDB<P, H, B>
+where
+    P: TrieProvider,
+    H: TrieHinter,
+    B: TrieDBHeader,
+{
+    type Error = TrieDBError;
+
+    fn basic(&mut self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let Some(account) = self.get_trie_account(address)? else {
+            return Ok(None);
+        };
+        self.storage_roots
+            .entry(address)
+            .or_insert(TrieNode::Blinded { commitment: account.storage_root() });
+        if account.code_hash() != TrieAccount::EMPTY_CODE_HASH {
+            self.code_addresses.get_mut().entry(account.code_hash()).or_insert(address);
+        }
+        Ok(Some(account.info()))
+    }
+
+    fn code_by_hash(&mut self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        self.code_by_hash_ref(code_hash)
+    }
+
+    fn storage(&mut self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let slot = B256::from(index);
+        self.hinter
+            .hint_storage_slots(address, &[slot])
+            .map_err(|e| TrieDBError::Hint(e.to_string()))?;
+
+        if !self.load_storage_root(address)? {
+            return Ok(U256::ZERO);
+        }
+        let storage_trie = self.storage_roots.get_mut(&address).expect("Loaded above");
+        match storage_trie.open(&hashed_path(slot), &self.provider)? {
+            Some(value) => Ok(U256::decode(&mut value.as_ref())?),
+            None => Ok(U256::ZERO),
+        }
+    }
+
+    /// Returns the hash of the block with the given `number`, as [DatabaseRef::block_hash_ref].
+    fn block_hash(&mut self, number: U256) -> Result<B256, Self::Error> {
+        self.block_hash_ref(number)
+    }
+}
+
+impl<P, H, B> DatabaseRef for TrieDB<P, H, B>
+where
+    P: TrieProvider,
+    H: TrieHinter,
+    B: TrieDBHeader,
+{
+    type Error = TrieDBError;
+
+    fn basic_ref(&self, address: Address) -> Result<Option<AccountInfo>, Self::Error> {
+        let Some(account) = self.trie_account_ref(address)? else {
+            return Ok(None);
+        };
+        if account.code_hash() != TrieAccount::EMPTY_CODE_HASH {
+            self.code_addresses.lock().entry(account.code_hash()).or_insert(address);
+        }
+        Ok(Some(account.info()))
+    }
+
+    fn code_by_hash_ref(&self, code_hash: B256) -> Result<Bytecode, Self::Error> {
+        if code_hash == TrieAccount::EMPTY_CODE_HASH {
+            return Ok(Bytecode::new());
+        }
+        if let Some(code) = self.code_cache.lock().get(&code_hash) {
+            return Ok(code);
+        }
+
+        let address = self
+            .code_addresses
+            .lock()
+            .get(&code_hash)
+            .copied()
+            .ok_or(TrieDBError::UnknownCode(code_hash))?;
+        let code = code_by_hash(&self.provider, &self.hinter, code_hash, address)?;
+        self.code_cache.lock().insert(code_hash, code.clone());
+        Ok(code)
+    }
+
+    fn storage_ref(&self, address: Address, index: U256) -> Result<U256, Self::Error> {
+        let slot = B256::from(index);
+        self.hinter
+            .hint_storage_slots(address, &[slot])
+            .map_err(|e| TrieDBError::Hint(e.to_string()))?;
+
+        let path = hashed_path(slot);
+        let value = match self.storage_roots.get(&address) {
+            Some(storage_trie) => storage_trie.get(&path, &self.provider)?,
+            None => match self.trie_account_ref(address)? {
+                Some(account) => TrieNode::Blinded { commitment: account.storage_root() }
+                    .get(&path, &self.provider)?,
+                None => None,
+            },
+        };
+        match value {
+            Some(value) => Ok(U256::decode(&mut value.as_ref())?),
+            None => Ok(U256::ZERO),
+        }
+    }
+
+    /// Returns the hash of the block with the given `number`. The parent block and its ancestors
+    /// up to [BLOCK_HASH_HISTORY] blocks below the executed block are resolved through the parent
+    /// chain. Blocks that do not have a hash yet, the executed block and the ones after it, hash
+    /// to zero, and older blocks are rejected with [TrieDBError::BlockNumberPastHorizon].
+    fn block_hash_ref(&self, number: U256) -> Result<B256, Self::Error> {
+        let parent_number = self.parent_header.number();
+        let number = match u64::try_from(number) {
+            Ok(number) if number <= parent_number => number,
+            _ => return Ok(B256::ZERO),
+        };
+
+        let depth = parent_number - number;
+        if depth >= BLOCK_HASH_HISTORY as u64 {
+            return Err(TrieDBError::BlockNumberPastHorizon(number));
+        }
+        self.ancestor_hash(depth as usize)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MapTrieProvider, MeteredTrieProvider, NoopTrieHinter, SecureTrie, TrieCounters};
+    use alloy_consensus::Header;
+    use alloy_primitives::{address, Bytes};
+    use anyhow::{anyhow, Result};
+    use core::cell::Cell;
+    use revm::db::{AccountStatus, BundleAccount};
+
+    const CONTRACT: Address = address!("4200000000000000000000000000000000000015");
+    const EOA: Address = address!("deaddeaddeaddeaddeaddeaddeaddeaddead0001");
+
+    /// A [TrieProvider] that serves block headers by hash, and counts the headers it returns.
+    #[derive(Default)]
+    struct MockChain {
+        headers: BTreeMap<B256, Bytes>,
+        fetches: Cell<usize>,
+    }
+
+    impl MockChain {
+        /// Builds a chain of `len` headers from genesis, and returns it with its tip.
+        fn new(len: u64) -> (Self, Header) {
+            let mut chain = Self::default();
+            let mut parent = Header::default();
+            chain.insert(&parent);
+            for number in 1..len {
+                parent = Header { number, parent_hash: parent.hash_slow(), ..Default::default() };
+                chain.insert(&parent);
+            }
+            (chain, parent)
+        }
+
+        fn insert(&mut self, header: &Header) -> B256 {
+            let hash = header.hash_slow();
+            self.headers.insert(hash, alloy_rlp::encode(header).into());
+            hash
+        }
+    }
+
+    impl TrieProvider for MockChain {
+        fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+            Err(anyhow!("unknown trie node {key}"))
+        }
+
+        fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+            Err(anyhow!("unknown bytecode {code_hash}"))
+        }
+
+        fn header_by_hash(&self, hash: B256) -> Result<Bytes> {
+            self.fetches.set(self.fetches.get() + 1);
+            self.headers.get(&hash).cloned().ok_or_else(|| anyhow!("unknown header {hash}"))
+        }
+    }
+
+    /// Builds a state holding a contract with code and a storage slot, and an account without
+    /// either, and returns a provider serving it with the header committing to it.
+    fn test_state() -> (MapTrieProvider, Header, Bytes) {
+        let mut provider = MapTrieProvider::new();
+        let code =
+            Bytes::from_static(&[0x60, 0x2a, 0x60, 0x00, 0x52, 0x60, 0x20, 0x60, 0x00, 0xf3]);
+        let code_hash = provider.insert_bytecode(code.clone());
+
+        let mut storage = SecureTrie::new(TrieAccount::EMPTY_ROOT, MapTrieProvider::new());
+        storage.set_storage(B256::with_last_byte(1), U256::from(0xbeef)).unwrap();
+        let storage_root = provider.insert_trie(storage.root_node());
+
+        let contract = TrieAccount { nonce: 1, balance: U256::from(10), storage_root, code_hash };
+        let eoa = TrieAccount { nonce: 7, balance: U256::from(5), ..Default::default() };
+        let mut state = SecureTrie::new(TrieAccount::EMPTY_ROOT, MapTrieProvider::new());
+        state.insert(CONTRACT, alloy_rlp::encode(contract).into()).unwrap();
+        state.insert(EOA, alloy_rlp::encode(eoa).into()).unwrap();
+        let state_root = provider.insert_trie(state.root_node());
+
+        (provider, Header { number: 10, state_root, ..Default::default() }, code)
+    }
+
+    #[test]
+    fn test_trie_db_reads_state() {
+        let (provider, header, code) = test_state();
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+
+        let info = db.basic(CONTRACT).unwrap().unwrap();
+        assert_eq!((info.nonce, info.balance), (1, U256::from(10)));
+        assert_eq!(db.code_by_hash(info.code_hash).unwrap(), Bytecode::new_raw(code));
+        assert_eq!(db.storage(CONTRACT, U256::from(1)).unwrap(), U256::from(0xbeef));
+        assert_eq!(db.storage(CONTRACT, U256::from(2)).unwrap(), U256::ZERO);
+
+        let info = db.basic(EOA).unwrap().unwrap();
+        assert_eq!((info.nonce, info.balance), (7, U256::from(5)));
+        assert_eq!(db.code_by_hash(info.code_hash).unwrap(), Bytecode::new());
+        assert_eq!(db.storage(EOA, U256::from(1)).unwrap(), U256::ZERO);
+
+        let missing = Address::repeat_byte(0x01);
+        assert_eq!(db.basic(missing).unwrap(), None);
+        assert_eq!(db.storage(missing, U256::from(1)).unwrap(), U256::ZERO);
+    }
+
+    /// A header extended with a field of another chain, wrapping the alloy [Header].
+    #[derive(Debug, Clone, alloy_rlp::RlpEncodable, alloy_rlp::RlpDecodable)]
+    struct ExtendedHeader {
+        inner: Header,
+        extra: u64,
+    }
+
+    impl TrieDBHeader for ExtendedHeader {
+        type Account = TrieAccount;
+
+        fn number(&self) -> u64 {
+            self.inner.number
+        }
+
+        fn parent_hash(&self) -> B256 {
+            self.inner.parent_hash
+        }
+
+        fn state_root(&self) -> B256 {
+            self.inner.state_root
+        }
+    }
+
+    #[test]
+    fn test_trie_db_extended_header() {
+        let (provider, header, _) = test_state();
+        let header = ExtendedHeader { inner: header, extra: 42 };
+        let hash = keccak256(alloy_rlp::encode(&header));
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+
+        assert_eq!(db.parent_hash(), hash);
+        assert_eq!(db.block_hash(U256::from(10)).unwrap(), hash);
+        assert_eq!(db.basic(EOA).unwrap().unwrap().nonce, 7);
+        assert_eq!(db.storage(CONTRACT, U256::from(1)).unwrap(), U256::from(0xbeef));
+    }
+
+    #[test]
+    fn test_trie_db_shared_reads() {
+        extern crate std;
+
+        let (provider, header, code) = test_state();
+        let db = TrieDB::new(header, provider, NoopTrieHinter);
+
+        std::thread::scope(|scope| {
+            for _ in 0..4 {
+                scope.spawn(|| {
+                    let info = db.basic_ref(CONTRACT).unwrap().unwrap();
+                    assert_eq!((info.nonce, info.balance), (1, U256::from(10)));
+                    let loaded = db.code_by_hash_ref(info.code_hash).unwrap();
+                    assert_eq!(loaded, Bytecode::new_raw(code.clone()));
+                    let value = db.storage_ref(CONTRACT, U256::from(1)).unwrap();
+                    assert_eq!(value, U256::from(0xbeef));
+                    assert_eq!(db.storage_ref(EOA, U256::from(1)).unwrap(), U256::ZERO);
+                    assert_eq!(db.basic_ref(Address::repeat_byte(0x01)).unwrap(), None);
+                    assert_eq!(db.block_hash_ref(U256::from(10)).unwrap(), db.parent_hash());
+                });
+            }
+        });
+
+        // Shared reads leave the state trie blinded, and fill the caches.
+        assert_eq!(db.root_node().opened_nodes(), 0);
+        assert_eq!(db.cached_code_size(), code.len());
+    }
+
+    #[test]
+    fn test_trie_db_storage_loads_account() {
+        let (provider, header, _) = test_state();
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+        assert_eq!(db.storage(CONTRACT, U256::from(1)).unwrap(), U256::from(0xbeef));
+    }
+
+    #[test]
+    fn test_trie_db_opens_storage_lazily() {
+        let mut provider = MapTrieProvider::new();
+        let mut storage = SecureTrie::new(TrieAccount::EMPTY_ROOT, MapTrieProvider::new());
+        for slot in 0..256u64 {
+            storage.set_storage(B256::from(U256::from(slot)), U256::from(slot + 1)).unwrap();
+        }
+        let storage_root = provider.insert_trie(storage.root_node());
+        let contract = TrieAccount { storage_root, ..Default::default() };
+        let mut state = SecureTrie::new(TrieAccount::EMPTY_ROOT, MapTrieProvider::new());
+        state.insert(CONTRACT, alloy_rlp::encode(contract).into()).unwrap();
+        let header =
+            Header { state_root: provider.insert_trie(state.root_node()), ..Default::default() };
+        let provider = MeteredTrieProvider::new(provider, TrieCounters::new());
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+
+        db.basic(CONTRACT).unwrap();
+        assert!(matches!(db.storage_trie(CONTRACT), Some(TrieNode::Blinded { .. })));
+
+        assert_eq!(db.storage(CONTRACT, U256::from(7)).unwrap(), U256::from(8));
+        let opened = db.storage_trie(CONTRACT).unwrap().opened_nodes();
+        assert!(opened < 8);
+        // Only the nodes on the path of the read slot are fetched.
+        let fetched = db.provider().metrics().stats().nodes_opened;
+        assert_eq!(fetched, db.root_node().opened_nodes() + opened);
+    }
+
+    #[test]
+    fn test_trie_db_caches_code() {
+        let (provider, header, code) = test_state();
+        let provider = MeteredTrieProvider::new(provider, TrieCounters::new());
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+
+        let code_hash = db.basic(CONTRACT).unwrap().unwrap().code_hash;
+        for _ in 0..3 {
+            assert_eq!(db.code_by_hash(code_hash).unwrap(), Bytecode::new_raw(code.clone()));
+        }
+        assert_eq!(db.provider().metrics().stats().bytecode_fetched, 1);
+        assert_eq!(db.cached_code_size(), code.len());
+    }
+
+    #[test]
+    fn test_trie_db_code_cache_size_bound() {
+        let (provider, header, code) = test_state();
+        let provider = MeteredTrieProvider::new(provider, TrieCounters::new());
+        let mut db =
+            TrieDB::new(header, provider, NoopTrieHinter).with_code_cache_size(code.len() - 1);
+
+        let code_hash = db.basic(CONTRACT).unwrap().unwrap().code_hash;
+        for _ in 0..3 {
+            assert_eq!(db.code_by_hash(code_hash).unwrap(), Bytecode::new_raw(code.clone()));
+        }
+        assert_eq!(db.provider().metrics().stats().bytecode_fetched, 3);
+        assert_eq!(db.cached_code_size(), 0);
+    }
+
+    #[test]
+    fn test_trie_db_caches_nodes() {
+        let (provider, header, _) = test_state();
+        let mut db = TrieDB::new(header.clone(), provider.clone(), NoopTrieHinter);
+        db.storage(CONTRACT, U256::from(1)).unwrap();
+        db.basic(EOA).unwrap();
+        // The state trie holds a branch with two leaves, and the storage trie a single leaf.
+        assert_eq!(db.cached_nodes(), 4);
+
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter).with_node_cache_capacity(0);
+        db.storage(CONTRACT, U256::from(1)).unwrap();
+        assert_eq!(db.cached_nodes(), 0);
+    }
+
+    #[test]
+    fn test_trie_db_shares_node_store() {
+        let (provider, header, _) = test_state();
+        let provider = MeteredTrieProvider::new(provider, TrieCounters::new());
+        let store = SharedNodeStore::new();
+
+        let mut db =
+            TrieDB::new(header.clone(), &provider, NoopTrieHinter).with_node_store(store.clone());
+        db.storage(CONTRACT, U256::from(1)).unwrap();
+        db.basic(EOA).unwrap();
+        drop(db);
+        let fetched = provider.metrics().stats().nodes_opened;
+        assert_eq!(store.len(), fetched);
+
+        // The next instance is served the released nodes it reads, and the rest are pruned.
+        let mut next =
+            TrieDB::new(header, &provider, NoopTrieHinter).with_node_store(store.clone());
+        assert_eq!(next.storage(CONTRACT, U256::from(1)).unwrap(), U256::from(0xbeef));
+        assert_eq!(provider.metrics().stats().nodes_opened, fetched);
+        assert_eq!(store.prune(), 1);
+        assert_eq!(store.len(), fetched - 1);
+    }
+
+    #[test]
+    fn test_trie_db_code_of_unloaded_account() {
+        let (provider, header, code) = test_state();
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+        let code_hash = keccak256(&code);
+        assert_eq!(db.code_by_hash(code_hash).unwrap_err(), TrieDBError::UnknownCode(code_hash));
+    }
+
+    /// Returns a [BundleAccount] that changes an existing account to `info`, with the given
+    /// `(slot, original, present)` storage changes.
+    fn changed_account(info: AccountInfo, storage: &[(u64, u64, u64)]) -> BundleAccount {
+        let storage = storage.iter().map(|(slot, original, present)| {
+            let slot_value = StorageSlot::new_changed(U256::from(*original), U256::from(*present));
+            (U256::from(*slot), slot_value)
+        });
+        BundleAccount::new(None, Some(info), storage.collect(), AccountStatus::Changed)
+    }
+
+    /// Computes the root of a storage trie holding the given `(slot, value)` pairs.
+    fn storage_root(storage: &[(u64, u64)]) -> B256 {
+        let mut trie = SecureTrie::new(TrieAccount::EMPTY_ROOT, MapTrieProvider::new());
+        for (slot, value) in storage {
+            trie.set_storage(B256::from(U256::from(*slot)), U256::from(*value)).unwrap();
+        }
+        trie.root()
+    }
+
+    /// Computes the root of a state trie holding the given accounts.
+    fn state_root(accounts: &[(Address, TrieAccount)]) -> B256 {
+        let mut trie = SecureTrie::new(TrieAccount::EMPTY_ROOT, MapTrieProvider::new());
+        for (address, account) in accounts {
+            trie.insert(address, alloy_rlp::encode(account).into()).unwrap();
+        }
+        trie.root()
+    }
+
+    #[test]
+    fn test_state_root_applies_bundle() {
+        let (provider, header, code) = test_state();
+        let code_hash = keccak256(&code);
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+
+        let storage_root = storage_root(&[(2, 7)]);
+        let contract = TrieAccount { nonce: 2, balance: U256::from(11), storage_root, code_hash };
+        let eoa = TrieAccount { nonce: 8, balance: U256::from(4), ..Default::default() };
+        let bundle = BundleState {
+            state: [
+                (
+                    CONTRACT,
+                    changed_account(contract.into(), &[(1, 0xbeef, 0), (2, 0, 7), (3, 5, 5)]),
+                ),
+                (EOA, changed_account(eoa.into(), &[])),
+            ]
+            .into_iter()
+            .collect(),
+            ..Default::default()
+        };
+
+        let expected = state_root(&[(CONTRACT, contract), (EOA, eoa)]);
+        assert_eq!(db.state_root(&bundle).unwrap(), expected);
+
+        // The post state is served to later reads.
+        assert_eq!(db.storage(CONTRACT, U256::from(1)).unwrap(), U256::ZERO);
+        assert_eq!(db.storage(CONTRACT, U256::from(2)).unwrap(), U256::from(7));
+        assert_eq!(db.basic(EOA).unwrap().unwrap().nonce, 8);
+    }
+
+    #[test]
+    fn test_state_root_skips_unmodified_accounts() {
+        let (provider, header, _) = test_state();
+        let state_root = header.state_root;
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+
+        let info = AccountInfo { nonce: 9, ..Default::default() };
+        let mut account = changed_account(info, &[(1, 0xbeef, 1)]);
+        account.status = AccountStatus::Loaded;
+        let bundle =
+            BundleState { state: [(EOA, account)].into_iter().collect(), ..Default::default() };
+        assert_eq!(db.state_root(&bundle).unwrap(), state_root);
+    }
+
+    #[test]
+    fn test_state_root_creates_and_destroys_accounts() {
+        let (provider, header, code) = test_state();
+        let code_hash = keccak256(&code);
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+
+        let created_address = Address::repeat_byte(0x01);
+        let created = TrieAccount {
+            nonce: 1,
+            balance: U256::from(3),
+            storage_root: storage_root(&[(1, 9)]),
+            ..Default::default()
+        };
+        let mut created_account = changed_account(created.into(), &[(1, 0, 9), (2, 0, 0)]);
+        created_account.status = AccountStatus::InMemoryChange;
+        let destroyed =
+            BundleAccount::new(None, None, Default::default(), AccountStatus::Destroyed);
+        let bundle = BundleState {
+            state: [(created_address, created_account), (EOA, destroyed)].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let contract = TrieAccount {
+            nonce: 1,
+            balance: U256::from(10),
+            storage_root: storage_root(&[(1, 0xbeef)]),
+            code_hash,
+        };
+        let expected = state_root(&[(CONTRACT, contract), (created_address, created)]);
+        assert_eq!(db.state_root(&bundle).unwrap(), expected);
+
+        assert_eq!(db.basic(EOA).unwrap(), None);
+        assert_eq!(db.storage(created_address, U256::from(1)).unwrap(), U256::from(9));
+    }
+
+    #[test]
+    fn test_state_root_caches_deployed_code() {
+        let (provider, header, _) = test_state();
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+
+        let code = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xfd]));
+        let code_hash = code.hash_slow();
+        let info = AccountInfo { nonce: 1, code_hash, ..Default::default() };
+        let mut deployed = changed_account(info, &[]);
+        deployed.status = AccountStatus::InMemoryChange;
+        let bundle = BundleState {
+            state: [(Address::repeat_byte(0x01), deployed)].into_iter().collect(),
+            contracts: [(code_hash, code.clone())].into_iter().collect(),
+            ..Default::default()
+        };
+        db.state_root(&bundle).unwrap();
+
+        // The provider does not know the deployed code, so it is served from the cache.
+        assert_eq!(db.code_by_hash(code_hash).unwrap(), code);
+    }
+
+    #[test]
+    fn test_state_root_recreated_account_wipes_storage() {
+        let (provider, header, code) = test_state();
+        let code_hash = keccak256(&code);
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+        assert_eq!(db.storage(CONTRACT, U256::from(1)).unwrap(), U256::from(0xbeef));
+
+        // The contract is destroyed and created again at the same address. Slot 1 is not
+        // written again, and the original value of slot 2 predates the wipe.
+        let recreated = TrieAccount {
+            nonce: 1,
+            balance: U256::ZERO,
+            storage_root: storage_root(&[(2, 3)]),
+            code_hash,
+        };
+        let mut account = changed_account(recreated.into(), &[(2, 3, 3), (3, 6, 0)]);
+        account.status = AccountStatus::DestroyedChanged;
+        let bundle = BundleState {
+            state: [(CONTRACT, account)].into_iter().collect(),
+            ..Default::default()
+        };
+
+        let eoa = TrieAccount { nonce: 7, balance: U256::from(5), ..Default::default() };
+        let expected = state_root(&[(CONTRACT, recreated), (EOA, eoa)]);
+        assert_eq!(db.state_root(&bundle).unwrap(), expected);
+        assert_eq!(db.storage(CONTRACT, U256::from(1)).unwrap(), U256::ZERO);
+        assert_eq!(db.storage(CONTRACT, U256::from(2)).unwrap(), U256::from(3));
+    }
+
+    #[test]
+    fn test_revert_to_checkpoint() {
+        let (provider, header, _) = test_state();
+        let state_root = header.state_root;
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+        assert_eq!(db.storage(CONTRACT, U256::from(1)).unwrap(), U256::from(0xbeef));
+
+        let checkpoint = db.checkpoint();
+        assert_eq!(checkpoint.state_root(), state_root);
+
+        let info = AccountInfo { nonce: 9, ..Default::default() };
+        let bundle = BundleState {
+            state: [(CONTRACT, changed_account(info, &[(1, 0xbeef, 1)]))].into_iter().collect(),
+            ..Default::default()
+        };
+        assert_ne!(db.state_root(&bundle).unwrap(), state_root);
+        assert_eq!(db.storage(CONTRACT, U256::from(1)).unwrap(), U256::from(1));
+
+        db.revert(checkpoint);
+        assert_eq!(db.root_node().root(), state_root);
+        assert_eq!(db.basic(CONTRACT).unwrap().unwrap().nonce, 1);
+        assert_eq!(db.storage(CONTRACT, U256::from(1)).unwrap(), U256::from(0xbeef));
+        assert!(db.take_state_diff().is_empty());
+    }
+
+    #[test]
+    fn test_advance_onto_child() {
+        let (provider, parent, _) = test_state();
+        let parent_hash = parent.hash_slow();
+        let mut db = TrieDB::new(parent.clone(), provider, NoopTrieHinter);
+        assert_eq!(db.block_hash(U256::from(10)).unwrap(), parent_hash);
+
+        let info = AccountInfo { nonce: 8, balance: U256::from(4), ..Default::default() };
+        let bundle = BundleState {
+            state: [(EOA, changed_account(info, &[]))].into_iter().collect(),
+            ..Default::default()
+        };
+        let state_root = db.state_root(&bundle).unwrap();
+        let child = Header { number: 11, parent_hash, state_root, ..Default::default() };
+        let child_hash = child.hash_slow();
+        db.advance(child.clone()).unwrap();
+
+        assert_eq!(db.parent_hash(), child_hash);
+        assert_eq!(db.parent_header(), &child);
+        assert_eq!(db.basic(EOA).unwrap().unwrap().nonce, 8);
+        assert_eq!(db.block_hash(U256::from(11)).unwrap(), child_hash);
+        assert_eq!(db.block_hash(U256::from(10)).unwrap(), parent_hash);
+        assert_eq!(db.block_hash(U256::from(12)).unwrap(), B256::ZERO);
+    }
+
+    #[test]
+    fn test_advance_rejects_invalid_child() {
+        let (provider, parent, _) = test_state();
+        let parent_hash = parent.hash_slow();
+        let state_root = parent.state_root;
+        let mut db = TrieDB::new(parent, provider, NoopTrieHinter);
+
+        let orphan = Header { number: 11, state_root, ..Default::default() };
+        assert_eq!(
+            db.advance(orphan.clone()).unwrap_err(),
+            TrieDBError::InvalidChildHeader(orphan.hash_slow())
+        );
+        let skipped = Header { number: 12, parent_hash, state_root, ..Default::default() };
+        assert_eq!(
+            db.advance(skipped.clone()).unwrap_err(),
+            TrieDBError::InvalidChildHeader(skipped.hash_slow())
+        );
+        let forged = B256::repeat_byte(0x01);
+        let mismatch = Header { number: 11, parent_hash, state_root: forged, ..Default::default() };
+        assert_eq!(
+            db.advance(mismatch).unwrap_err(),
+            TrieDBError::StateRootMismatch { expected: forged, actual: state_root }
+        );
+        assert_eq!(db.parent_hash(), parent_hash);
+    }
+
+    #[test]
+    fn test_state_root_records_state_diff() {
+        let (provider, header, code) = test_state();
+        let code_hash = keccak256(&code);
+        let mut db = TrieDB::new(header, provider, NoopTrieHinter);
+
+        let contract = TrieAccount {
+            nonce: 2,
+            balance: U256::from(10),
+            storage_root: storage_root(&[(2, 7)]),
+            code_hash,
+        };
+        let deployed = Bytecode::new_raw(Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xfd]));
+        let destroyed =
+            BundleAccount::new(None, None, Default::default(), AccountStatus::Destroyed);
+        let bundle = BundleState {
+            state: [
+                (
+                    CONTRACT,
+                    changed_account(contract.into(), &[(1, 0xbeef, 0), (2, 0, 7), (3, 5, 5)]),
+                ),
+                (EOA, destroyed),
+            ]
+            .into_iter()
+            .collect(),
+            contracts: [(deployed.hash_slow(), deployed.clone())].into_iter().collect(),
+            ..Default::default()
+        };
+        db.state_root(&bundle).unwrap();
+
+        let diff = db.take_state_diff();
+        let expected_storage =
+            [(B256::from(U256::from(1)), U256::ZERO), (B256::from(U256::from(2)), U256::from(7))];
+        assert_eq!(
+            diff.accounts[&CONTRACT],
+            AccountDiff {
+                account: Some(contract),
+                storage_wiped: false,
+                storage: expected_storage.into_iter().collect(),
+            }
+        );
+        assert_eq!(
+            diff.accounts[&EOA],
+            AccountDiff { account: None, storage_wiped: true, storage: BTreeMap::new() }
+        );
+        assert_eq!(diff.contracts[&deployed.hash_slow()], deployed.original_bytes());
+
+        // Taking the diff starts a new one.
+        assert!(db.take_state_diff().is_empty());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn test_par_state_root_matches_serial() {
+        let (provider, header, code) = test_state();
+        let info = AccountInfo { nonce: 3, code_hash: keccak256(&code), ..Default::default() };
+        let mut recreated = changed_account(info, &[(2, 3, 3)]);
+        recreated.status = AccountStatus::DestroyedChanged;
+        let created = changed_account(AccountInfo { nonce: 1, ..Default::default() }, &[(1, 0, 9)]);
+        let destroyed =
+            BundleAccount::new(None, None, Default::default(), AccountStatus::Destroyed);
+        let bundle = BundleState {
+            state: [(CONTRACT, recreated), (Address::repeat_byte(0x01), created), (EOA, destroyed)]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        };
+
+        let mut serial = TrieDB::new(header.clone(), provider.clone(), NoopTrieHinter);
+        let mut parallel = TrieDB::new(header, provider, NoopTrieHinter);
+        assert_eq!(parallel.par_state_root(&bundle).unwrap(), serial.state_root(&bundle).unwrap());
+    }
+
+    #[test]
+    fn test_block_hash_walks_parent_chain() {
+        let (chain, parent) = MockChain::new(300);
+        let hashes = chain.headers.values().map(|header| {
+            let header = Header::decode(&mut header.as_ref()).unwrap();
+            (header.number, header.hash_slow())
+        });
+        let hashes = hashes.collect::<BTreeMap<_, _>>();
+        let mut db = TrieDB::new(parent, chain, NoopTrieHinter);
+
+        for number in (299 - 255..=299).rev() {
+            assert_eq!(db.block_hash(U256::from(number)).unwrap(), hashes[&number]);
+        }
+        assert_eq!(db.provider().fetches.get(), 255);
+    }
+
+    #[test]
+    fn test_block_hash_caches_ancestors() {
+        let (chain, parent) = MockChain::new(64);
+        let parent_hash = parent.hash_slow();
+        let mut db = TrieDB::new(parent, chain, NoopTrieHinter);
+
+        assert_eq!(db.block_hash(U256::from(63)).unwrap(), parent_hash);
+        assert_eq!(db.provider().fetches.get(), 0);
+
+        let hash = db.block_hash(U256::from(53)).unwrap();
+        assert_eq!(db.provider().fetches.get(), 10);
+        assert_eq!(db.block_hash(U256::from(53)).unwrap(), hash);
+        db.block_hash(U256::from(58)).unwrap();
+        assert_eq!(db.provider().fetches.get(), 10);
+        db.block_hash(U256::from(51)).unwrap();
+        assert_eq!(db.provider().fetches.get(), 12);
+    }
+
+    #[test]
+    fn test_block_hash_out_of_range() {
+        let (chain, parent) = MockChain::new(300);
+        let mut db = TrieDB::new(parent, chain, NoopTrieHinter);
+
+        assert_eq!(db.block_hash(U256::from(300)).unwrap(), B256::ZERO);
+        assert_eq!(db.block_hash(U256::from(1000)).unwrap(), B256::ZERO);
+        assert_eq!(db.block_hash(U256::MAX).unwrap(), B256::ZERO);
+        assert_eq!(
+            db.block_hash(U256::from(299 - 256)).unwrap_err(),
+            TrieDBError::BlockNumberPastHorizon(299 - 256)
+        );
+        assert_eq!(db.block_hash(U256::ZERO).unwrap_err(), TrieDBError::BlockNumberPastHorizon(0));
+        assert_eq!(db.provider().fetches.get(), 0);
+    }
+
+    #[test]
+    fn test_block_hash_invalid_parent_number() {
+        let mut chain = MockChain::default();
+        let grandparent = Header { number: 5, ..Default::default() };
+        let grandparent_hash = chain.insert(&grandparent);
+        let child = Header { number: 7, parent_hash: grandparent_hash, ..Default::default() };
+        let child_hash = chain.insert(&child);
+        let parent = Header { number: 8, parent_hash: child_hash, ..Default::default() };
+        let mut db = TrieDB::new(parent, chain, NoopTrieHinter);
+
+        assert_eq!(db.block_hash(U256::from(7)).unwrap(), child_hash);
+        assert_eq!(
+            db.block_hash(U256::from(6)).unwrap_err(),
+            TrieDBError::InvalidParentNumber { hash: grandparent_hash, expected: 6, actual: 5 }
+        );
+    }
+
+    #[test]
+    fn test_block_hash_header_mismatch() {
+        let mut chain = MockChain::default();
+        let forged = Header { number: 7, ..Default::default() };
+        let claimed = B256::repeat_byte(0xff);
+        chain.headers.insert(claimed, alloy_rlp::encode(&forged).into());
+        let parent = Header { number: 8, parent_hash: claimed, ..Default::default() };
+        let mut db = TrieDB::new(parent, chain, NoopTrieHinter);
+
+        assert_eq!(db.block_hash(U256::from(7)).unwrap_err(), TrieDBError::HeaderMismatch(claimed));
+    }
+
+    #[test]
+    fn test_block_hash_missing_header() {
+        let parent =
+            Header { number: 8, parent_hash: B256::repeat_byte(0xff), ..Default::default() };
+        let mut db = TrieDB::new(parent, MockChain::default(), NoopTrieHinter);
+        assert!(matches!(db.block_hash(U256::from(7)).unwrap_err(), TrieDBError::Provider(_)));
+    }
+}
@@ -0,0 +1,282 @@
+//! Contains the [NodeCache], the trie node cache of the [TrieDB].
+//!
+//! [TrieDB]: crate::TrieDB
+
+use super::SharedNodeStore;
+use crate::TrieProvider;
+use alloc::collections::BTreeSet;
+use alloy_primitives::{keccak256, Bytes, B256};
+use anyhow::Result;
+use core::num::NonZeroUsize;
+use lru::LruCache;
+use spin::Mutex;
+
+/// A [NodeCache] wraps a [TrieProvider], and keeps the most recently fetched trie node preimages
+/// in an LRU cache keyed by their commitment. Nodes that are opened again after the trie holding
+/// them was blinded or dropped are then served without a round trip to the host.
+///
+/// Behind the LRU cache, the [NodeCache] may be attached to a [SharedNodeStore], which serves the
+/// nodes fetched by other caches attached to it. The nodes this cache fetched or was served are
+/// retained in the store, and released when the cache is dropped.
+///
+/// Only preimages that match their commitment are cached. Bytecode and headers are passed
+/// through to the inner provider.
+#[derive(Debug)]
+pub(crate) struct NodeCache<P> {
+    /// The inner provider.
+    inner: P,
+    /// The cached trie node preimages, from the most to the least recently used.
+    nodes: Mutex<LruCache<B256, Bytes>>,
+    /// The maximum number of cached trie nodes. Zero disables the cache.
+    capacity: usize,
+    /// The shared node store the cache is attached to, if any.
+    store: Option<SharedNodeStore>,
+    /// The commitments of the nodes retained in the shared node store.
+    retained: Mutex<BTreeSet<B256>>,
+}
+
+impl<P> NodeCache<P> {
+    /// Creates a new [NodeCache] over `inner`, holding up to `capacity` trie nodes.
+    pub(crate) fn new(inner: P, capacity: usize) -> Self {
+        Self {
+            inner,
+            nodes: Mutex::new(LruCache::new(lru_capacity(capacity))),
+            capacity,
+            store: None,
+            retained: Mutex::new(BTreeSet::new()),
+        }
+    }
+
+    /// Returns a reference to the inner provider.
+    pub(crate) const fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns the number of cached trie nodes.
+    pub(crate) fn len(&self) -> usize {
+        self.nodes.lock().len()
+    }
+
+    /// Changes the maximum number of cached trie nodes to `capacity`, evicting the least recently
+    /// used nodes if they no longer fit.
+    pub(crate) fn resize(&mut self, capacity: usize) {
+        let nodes = self.nodes.get_mut();
+        nodes.resize(lru_capacity(capacity));
+        if capacity == 0 {
+            nodes.clear();
+        }
+        self.capacity = capacity;
+    }
+
+    /// Attaches the cache to the shared node `store`, releasing the nodes retained in the store it
+    /// was attached to before.
+    pub(crate) fn set_store(&mut self, store: SharedNodeStore) {
+        self.release_all();
+        self.store = Some(store);
+    }
+
+    /// Caches the verified `preimage` of the node with the given commitment, unless the cache is
+    /// disabled.
+    fn cache(&self, key: B256, preimage: &Bytes) {
+        if self.capacity > 0 {
+            self.nodes.lock().put(key, preimage.clone());
+        }
+    }
+
+    /// Returns the preimage of the node with the given commitment from the shared node store,
+    /// retaining it if this cache did not already.
+    fn stored(&self, key: B256) -> Option<Bytes> {
+        let store = self.store.as_ref()?;
+        let mut retained = self.retained.lock();
+        if retained.contains(&key) {
+            return store.get(&key);
+        }
+        let preimage = store.retain(&key)?;
+        retained.insert(key);
+        Some(preimage)
+    }
+
+    /// Stores the verified `preimage` of the node with the given commitment in the shared node
+    /// store, retaining it.
+    fn insert_stored(&self, key: B256, preimage: &Bytes) {
+        let Some(store) = &self.store else { return };
+        if self.retained.lock().insert(key) {
+            store.insert(key, preimage.clone());
+        }
+    }
+
+    /// Releases the nodes retained in the shared node store.
+    fn release_all(&mut self) {
+        let retained = core::mem::take(self.retained.get_mut());
+        if let Some(store) = &self.store {
+            for key in retained.iter() {
+                store.release(key);
+            }
+        }
+    }
+}
+
+impl<P> Drop for NodeCache<P> {
+    fn drop(&mut self) {
+        self.release_all();
+    }
+}
+
+impl<P: Clone> Clone for NodeCache<P> {
+    fn clone(&self) -> Self {
+        let retained = self.retained.lock().clone();
+        if let Some(store) = &self.store {
+            // The clone holds the retained nodes too.
+            for key in retained.iter() {
+                store.retain(key);
+            }
+        }
+        Self {
+            inner: self.inner.clone(),
+            nodes: Mutex::new(self.nodes.lock().clone()),
+            capacity: self.capacity,
+            store: self.store.clone(),
+            retained: Mutex::new(retained),
+        }
+    }
+}
+
+impl<P: TrieProvider> TrieProvider for NodeCache<P> {
+    fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+        if let Some(preimage) = self.nodes.lock().get(&key) {
+            return Ok(preimage.clone());
+        }
+        if let Some(preimage) = self.stored(key) {
+            self.cache(key, &preimage);
+            return Ok(preimage);
+        }
+
+        let preimage = self.inner.trie_node_preimage(key)?;
+        if keccak256(&preimage) == key {
+            self.cache(key, &preimage);
+            self.insert_stored(key, &preimage);
+        }
+        Ok(preimage)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+        self.inner.bytecode_by_hash(code_hash)
+    }
+
+    fn header_by_hash(&self, hash: B256) -> Result<Bytes> {
+        self.inner.header_by_hash(hash)
+    }
+}
+
+/// Returns the capacity of the [LruCache] of a [NodeCache] holding up to `capacity` trie nodes.
+/// An empty cache still needs a capacity of one, but is never filled.
+fn lru_capacity(capacity: usize) -> NonZeroUsize {
+    NonZeroUsize::new(capacity).unwrap_or(NonZeroUsize::MIN)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MapTrieProvider, MeteredTrieProvider, Nibbles, TrieCounters, TrieNode};
+    use alloc::vec;
+
+    /// Builds a trie of 32 leaves, and returns a counting provider serving it with its root.
+    fn test_trie() -> (MeteredTrieProvider<MapTrieProvider, TrieCounters>, B256) {
+        let mut trie = TrieNode::Empty;
+        let scratch = MapTrieProvider::new();
+        for i in 0..32u8 {
+            let path = Nibbles::unpack(keccak256([i]));
+            trie.insert(&path, Bytes::from(vec![i; 40]), &scratch).unwrap();
+        }
+        let mut provider = MapTrieProvider::new();
+        let root = provider.insert_trie(&trie);
+        (MeteredTrieProvider::new(provider, TrieCounters::new()), root)
+    }
+
+    /// Opens the path of every leaf of the trie built by [test_trie] from its blinded root.
+    fn open_all<P: TrieProvider>(root: B256, provider: &P) {
+        let mut trie = TrieNode::Blinded { commitment: root };
+        for i in 0..32u8 {
+            let path = Nibbles::unpack(keccak256([i]));
+            assert_eq!(trie.open(&path, provider).unwrap().cloned(), Some(vec![i; 40].into()));
+        }
+    }
+
+    #[test]
+    fn test_node_cache_serves_reopened_nodes() {
+        let (provider, root) = test_trie();
+        let cache = NodeCache::new(provider, 1024);
+
+        open_all(root, &cache);
+        let opened = cache.inner().metrics().stats().nodes_opened;
+        assert_eq!(cache.len(), opened);
+
+        open_all(root, &cache);
+        assert_eq!(cache.inner().metrics().stats().nodes_opened, opened);
+    }
+
+    #[test]
+    fn test_node_cache_capacity() {
+        let (provider, root) = test_trie();
+        let mut cache = NodeCache::new(provider, 4);
+        open_all(root, &cache);
+        assert_eq!(cache.len(), 4);
+
+        cache.resize(0);
+        assert_eq!(cache.len(), 0);
+        let opened = cache.inner().metrics().reset().nodes_opened;
+        open_all(root, &cache);
+        assert_eq!(cache.inner().metrics().stats().nodes_opened, opened);
+        assert_eq!(cache.len(), 0);
+    }
+
+    #[test]
+    fn test_node_cache_shares_store() {
+        let (provider, root) = test_trie();
+        let store = SharedNodeStore::new();
+        let mut first = NodeCache::new(&provider, 0);
+        first.set_store(store.clone());
+        open_all(root, &first);
+        let opened = provider.metrics().stats().nodes_opened;
+        assert_eq!(store.len(), opened);
+        assert_eq!(store.refs(&root), 1);
+
+        // A second cache attached to the store is served the nodes the first one fetched.
+        let mut second = NodeCache::new(&provider, 0);
+        second.set_store(store.clone());
+        open_all(root, &second);
+        open_all(root, &second);
+        assert_eq!(provider.metrics().stats().nodes_opened, opened);
+        assert_eq!(store.refs(&root), 2);
+
+        // The nodes are evicted by pruning once no cache holds them anymore.
+        drop(first);
+        assert_eq!((store.prune(), store.refs(&root)), (0, 1));
+        let cloned = second.clone();
+        drop(second);
+        assert_eq!(store.refs(&root), 1);
+        drop(cloned);
+        assert_eq!((store.len(), store.refs(&root)), (opened, 0));
+        assert_eq!(store.prune(), opened);
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_node_cache_skips_mismatched_preimages() {
+        struct LyingProvider;
+
+        impl TrieProvider for LyingProvider {
+            fn trie_node_preimage(&self, _: B256) -> Result<Bytes> {
+                Ok(Bytes::from_static(&[0xc0]))
+            }
+
+            fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+                Err(anyhow::anyhow!("unknown bytecode {code_hash}"))
+            }
+        }
+
+        let cache = NodeCache::new(LyingProvider, 16);
+        cache.trie_node_preimage(B256::ZERO).unwrap();
+        assert_eq!(cache.len(), 0);
+    }
+}
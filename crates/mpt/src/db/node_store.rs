@@ -0,0 +1,92 @@
+//! Contains the [SharedNodeStore], a reference-counted store of trie node preimages shared by
+//! consecutive [TrieDB] instances.
+//!
+//! [TrieDB]: crate::TrieDB
+
+use alloc::{collections::BTreeMap, sync::Arc};
+use alloy_primitives::{Bytes, B256};
+use spin::Mutex;
+
+/// A [SharedNodeStore] holds the trie node preimages fetched by the [TrieDB] instances attached
+/// to it with [TrieDB::with_node_store], keyed by their commitment. When a client program
+/// executes several blocks with a [TrieDB] per block, the subtrees left unchanged by a block are
+/// then served to the next [TrieDB] from the store, instead of being fetched again.
+///
+/// Each stored node counts the attached [TrieDB] instances that fetched it, or were served it.
+/// A [TrieDB] releases its nodes when it is dropped, but released nodes stay in the store until
+/// it is pruned with [SharedNodeStore::prune], so the next [TrieDB] can still be served them.
+/// Pruning after each block keeps the store bounded by the nodes of the live instances.
+///
+/// The store is a handle, and its clones share the same nodes.
+///
+/// [TrieDB]: crate::TrieDB
+/// [TrieDB::with_node_store]: crate::TrieDB::with_node_store
+#[derive(Debug, Clone, Default)]
+pub struct SharedNodeStore {
+    /// The stored preimages, keyed by commitment, with the number of instances holding them.
+    nodes: Arc<Mutex<BTreeMap<B256, (Bytes, usize)>>>,
+}
+
+impl SharedNodeStore {
+    /// Creates a new, empty [SharedNodeStore].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of stored trie nodes.
+    pub fn len(&self) -> usize {
+        self.nodes.lock().len()
+    }
+
+    /// Returns whether the store holds no trie nodes.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.lock().is_empty()
+    }
+
+    /// Returns the number of [TrieDB] instances holding the trie node with the given
+    /// `commitment`, or zero if it is not stored or no longer held.
+    ///
+    /// [TrieDB]: crate::TrieDB
+    pub fn refs(&self, commitment: &B256) -> usize {
+        self.nodes.lock().get(commitment).map_or(0, |(_, refs)| *refs)
+    }
+
+    /// Returns the stored preimage of the trie node with the given `commitment`.
+    pub(crate) fn get(&self, commitment: &B256) -> Option<Bytes> {
+        self.nodes.lock().get(commitment).map(|(preimage, _)| preimage.clone())
+    }
+
+    /// Returns the stored preimage of the trie node with the given `commitment`, and counts one
+    /// more holder of it.
+    pub(crate) fn retain(&self, commitment: &B256) -> Option<Bytes> {
+        let mut nodes = self.nodes.lock();
+        let (preimage, refs) = nodes.get_mut(commitment)?;
+        *refs += 1;
+        Some(preimage.clone())
+    }
+
+    /// Stores the verified `preimage` of the trie node with the given `commitment` for a new
+    /// holder, which may already have been stored by another one.
+    pub(crate) fn insert(&self, commitment: B256, preimage: Bytes) {
+        self.nodes.lock().entry(commitment).or_insert((preimage, 0)).1 += 1;
+    }
+
+    /// Evicts the trie nodes that no [TrieDB] instance holds anymore, and returns the number of
+    /// evicted nodes.
+    ///
+    /// [TrieDB]: crate::TrieDB
+    pub fn prune(&self) -> usize {
+        let mut nodes = self.nodes.lock();
+        let len = nodes.len();
+        nodes.retain(|_, (_, refs)| *refs > 0);
+        len - nodes.len()
+    }
+
+    /// Counts one less holder of the trie node with the given `commitment`. The node is kept
+    /// until the store is pruned.
+    pub(crate) fn release(&self, commitment: &B256) {
+        if let Some((_, refs)) = self.nodes.lock().get_mut(commitment) {
+            *refs -= 1;
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Contains the [TrieDBHeader] and [TrieDBAccount] traits, which abstract the block header and
+//! account types a [TrieDB] is built on.
+//!
+//! [TrieDB]: crate::TrieDB
+
+use crate::TrieAccount;
+use alloy_consensus::Header;
+use alloy_primitives::{keccak256, B256};
+use alloy_rlp::{Decodable, Encodable};
+use core::fmt::Debug;
+use revm::primitives::AccountInfo;
+
+/// The [TrieDBHeader] trait defines the block header a [TrieDB] is anchored on, and the account
+/// type of the state trie it commits to. It lets chains with extended headers or accounts reuse
+/// the [TrieDB] with their own primitives. It is implemented for the alloy [Header], with the
+/// Ethereum [TrieAccount].
+///
+/// [TrieDB]: crate::TrieDB
+pub trait TrieDBHeader: Encodable + Decodable + Clone + Debug {
+    /// The account type stored in the state trie.
+    type Account: TrieDBAccount;
+
+    /// Returns the number of the block.
+    fn number(&self) -> u64;
+
+    /// Returns the hash of the parent block.
+    fn parent_hash(&self) -> B256;
+
+    /// Returns the root of the state trie after the block.
+    fn state_root(&self) -> B256;
+
+    /// Returns the hash of the block, the [keccak256] hash of the RLP encoded header.
+    fn hash_slow(&self) -> B256 {
+        keccak256(alloy_rlp::encode(self))
+    }
+}
+
+impl TrieDBHeader for Header {
+    type Account = TrieAccount;
+
+    fn number(&self) -> u64 {
+        self.number
+    }
+
+    fn parent_hash(&self) -> B256 {
+        self.parent_hash
+    }
+
+    fn state_root(&self) -> B256 {
+        self.state_root
+    }
+
+    fn hash_slow(&self) -> B256 {
+        Header::hash_slow(self)
+    }
+}
+
+/// The [TrieDBAccount] trait defines an account as it is RLP encoded in the state trie, and its
+/// conversions from and into the [AccountInfo] of [revm]. It is implemented for [TrieAccount].
+pub trait TrieDBAccount: Encodable + Decodable + Clone + Debug + PartialEq + Eq {
+    /// Creates an account from an [AccountInfo] and the root of the account's storage trie.
+    fn from_info(info: AccountInfo, storage_root: B256) -> Self;
+
+    /// Returns the [AccountInfo] of the account. The code is loaded separately by its hash.
+    fn info(&self) -> AccountInfo;
+
+    /// Returns the root of the account's storage trie.
+    fn storage_root(&self) -> B256;
+
+    /// Returns the hash of the account's code.
+    fn code_hash(&self) -> B256;
+}
+
+impl TrieDBAccount for TrieAccount {
+    fn from_info(info: AccountInfo, storage_root: B256) -> Self {
+        Self::from((info, storage_root))
+    }
+
+    fn info(&self) -> AccountInfo {
+        (*self).into()
+    }
+
+    fn storage_root(&self) -> B256 {
+        self.storage_root
+    }
+
+    fn code_hash(&self) -> B256 {
+        self.code_hash
+    }
+}
@@ -0,0 +1,78 @@
+//! Contains the [StateDiff], the changes applied to the state by a [TrieDB].
+//!
+//! [TrieDB]: crate::TrieDB
+
+use crate::TrieAccount;
+use alloc::collections::BTreeMap;
+use alloy_primitives::{Address, Bytes, B256, U256};
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A [StateDiff] holds the changes to the state applied by [TrieDB::state_root], accumulated
+/// across bundles until it is taken with [TrieDB::take_state_diff]. It lets hosts and debuggers
+/// inspect exactly what the executor changed, and with the `serde` feature it can be serialized
+/// as is. The accounts are of the account type of the [TrieDBHeader] of the [TrieDB].
+///
+/// [TrieDB]: crate::TrieDB
+/// [TrieDBHeader]: crate::TrieDBHeader
+/// [TrieDB::state_root]: crate::TrieDB::state_root
+/// [TrieDB::take_state_diff]: crate::TrieDB::take_state_diff
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StateDiff<A = TrieAccount> {
+    /// The changed accounts, keyed by address.
+    pub accounts: BTreeMap<Address, AccountDiff<A>>,
+    /// The deployed bytecode, keyed by code hash.
+    pub contracts: BTreeMap<B256, Bytes>,
+}
+
+/// An [AccountDiff] holds the changes to a single account in a [StateDiff].
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AccountDiff<A = TrieAccount> {
+    /// The account as it is stored in the state trie after the changes, including its new
+    /// storage root, or `None` if the account was deleted.
+    pub account: Option<A>,
+    /// Whether the storage of the account was wiped before the changed slots were written, such
+    /// as by a `SELFDESTRUCT`.
+    pub storage_wiped: bool,
+    /// The changed storage slots, with their new values. Slots set to zero were deleted.
+    pub storage: BTreeMap<B256, U256>,
+}
+
+impl<A> Default for StateDiff<A> {
+    fn default() -> Self {
+        Self { accounts: BTreeMap::new(), contracts: BTreeMap::new() }
+    }
+}
+
+impl<A> Default for AccountDiff<A> {
+    fn default() -> Self {
+        Self { account: None, storage_wiped: false, storage: BTreeMap::new() }
+    }
+}
+
+impl<A> StateDiff<A> {
+    /// Returns whether the diff holds no changes.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty() && self.contracts.is_empty()
+    }
+
+    /// Records the wipe of the storage of the account at `address`, dropping the slots recorded
+    /// for it so far.
+    pub(crate) fn wipe_storage(&mut self, address: Address) {
+        let diff = self.accounts.entry(address).or_default();
+        diff.storage_wiped = true;
+        diff.storage.clear();
+    }
+
+    /// Records the new state of the account at `address`, `None` if it was deleted.
+    pub(crate) fn set_account(&mut self, address: Address, account: Option<A>) {
+        self.accounts.entry(address).or_default().account = account;
+    }
+
+    /// Records the new `value` of a storage `slot` of the account at `address`.
+    pub(crate) fn set_storage(&mut self, address: Address, slot: B256, value: U256) {
+        self.accounts.entry(address).or_default().storage.insert(slot, value);
+    }
+}
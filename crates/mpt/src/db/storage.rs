@@ -0,0 +1,64 @@
+//! Contains the [StorageUpdate], a pending update of the storage trie of an account by the
+//! [TrieDB].
+//!
+//! [TrieDB]: crate::TrieDB
+
+use crate::{secure::hashed_path, TrieDBError, TrieNode, TrieProvider};
+use alloy_primitives::{Address, B256};
+use revm::{db::StorageWithOriginalValues, primitives::StorageSlot};
+
+/// A [StorageUpdate] holds the storage trie of an account, taken out of the [TrieDB], with the
+/// slots of a bundle to write to it. The storage tries of different accounts are independent, so
+/// the updates can be applied in any order, or in parallel.
+///
+/// [TrieDB]: crate::TrieDB
+#[derive(Debug)]
+pub(crate) struct StorageUpdate<'a> {
+    /// The address of the account.
+    pub(crate) address: Address,
+    /// The storage trie of the account.
+    pub(crate) trie: TrieNode,
+    /// The storage slots of the account in the bundle.
+    pub(crate) storage: &'a StorageWithOriginalValues,
+    /// Whether the storage of the account was wiped, in which case `trie` starts out empty.
+    pub(crate) wiped: bool,
+}
+
+impl StorageUpdate<'_> {
+    /// Returns whether the update changes the storage trie.
+    pub(crate) fn is_dirty(&self) -> bool {
+        self.wiped || self.storage.values().any(|value| is_slot_changed(value, self.wiped))
+    }
+
+    /// Writes the changed slots to the storage trie, deleting the slots set to zero, and returns
+    /// the address of the account with its updated storage trie and storage root.
+    pub(crate) fn apply<P: TrieProvider>(
+        mut self,
+        provider: &P,
+    ) -> Result<(Address, TrieNode, B256), TrieDBError> {
+        for (slot, value) in
+            self.storage.iter().filter(|(_, value)| is_slot_changed(value, self.wiped))
+        {
+            let path = hashed_path(B256::from(*slot));
+            if value.present_value.is_zero() {
+                self.trie.delete(&path, provider)?;
+            } else {
+                let value = alloy_rlp::encode(value.present_value).into();
+                self.trie.insert(&path, value, provider)?;
+            }
+        }
+        let root = self.trie.root();
+        Ok((self.address, self.trie, root))
+    }
+}
+
+/// Returns whether the `value` of a slot has to be written to the storage trie. Once the storage
+/// was `wiped`, the original values of the slots predate the wipe, so every slot that holds a
+/// value is written.
+pub(crate) fn is_slot_changed(value: &StorageSlot, wiped: bool) -> bool {
+    if wiped {
+        !value.present_value.is_zero()
+    } else {
+        value.is_changed()
+    }
+}
@@ -0,0 +1,279 @@
+//! This module contains [diff_tries], which reports the leaves that differ between two tries.
+
+use crate::{Nibbles, TrieIterator, TrieNode, TrieNodeError, TrieProvider};
+use alloc::vec::Vec;
+use alloy_primitives::{Bytes, B256};
+use core::cmp::Ordering;
+
+/// The number of children of a branch node, excluding its value.
+const BRANCH_CHILDREN: usize = 16;
+
+/// A [TrieDiff] is a single leaf that differs between two tries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrieDiff {
+    /// The leaf is only present in the new trie.
+    Added {
+        /// The full key of the leaf.
+        key: Nibbles,
+        /// The value of the leaf in the new trie.
+        value: Bytes,
+    },
+    /// The leaf is only present in the old trie.
+    Removed {
+        /// The full key of the leaf.
+        key: Nibbles,
+        /// The value of the leaf in the old trie.
+        value: Bytes,
+    },
+    /// The leaf is present in both tries, with different values.
+    Changed {
+        /// The full key of the leaf.
+        key: Nibbles,
+        /// The value of the leaf in the old trie.
+        old: Bytes,
+        /// The value of the leaf in the new trie.
+        new: Bytes,
+    },
+}
+
+impl TrieDiff {
+    /// Returns the full key of the leaf that differs.
+    pub fn key(&self) -> &Nibbles {
+        match self {
+            Self::Added { key, .. } | Self::Removed { key, .. } | Self::Changed { key, .. } => key,
+        }
+    }
+}
+
+/// Walks the tries rooted at `old_root` and `new_root`, and returns the leaves that were added,
+/// removed, or changed between them, in key order.
+///
+/// Subtries whose commitments match in both tries are skipped without being unblinded, so the
+/// cost of the walk is proportional to the size of the difference rather than the size of the
+/// tries.
+pub fn diff_tries<P: TrieProvider>(
+    old_root: B256,
+    new_root: B256,
+    provider: &P,
+) -> Result<Vec<TrieDiff>, TrieNodeError> {
+    let mut diffs = Vec::new();
+    diff_nodes(
+        Vec::new(),
+        TrieNode::Blinded { commitment: old_root },
+        TrieNode::Blinded { commitment: new_root },
+        provider,
+        &mut diffs,
+    )?;
+    Ok(diffs)
+}
+
+/// Recursive helper for [diff_tries], which appends the differences between the `old` and `new`
+/// nodes at `path` to `diffs`.
+fn diff_nodes<P: TrieProvider>(
+    path: Vec<u8>,
+    mut old: TrieNode,
+    mut new: TrieNode,
+    provider: &P,
+    diffs: &mut Vec<TrieDiff>,
+) -> Result<(), TrieNodeError> {
+    if let (TrieNode::Blinded { commitment: old }, TrieNode::Blinded { commitment: new }) =
+        (&old, &new)
+    {
+        if old == new {
+            return Ok(());
+        }
+    }
+
+    old.unblind(provider)?;
+    new.unblind(provider)?;
+    match (old, new) {
+        (TrieNode::Branch { stack: old_stack }, TrieNode::Branch { stack: new_stack }) => {
+            if [&old_stack, &new_stack]
+                .iter()
+                .any(|stack| stack.get(BRANCH_CHILDREN).is_some_and(|v| *v != TrieNode::Empty))
+            {
+                return Err(TrieNodeError::BranchValue);
+            }
+
+            for (index, (old_child, new_child)) in
+                old_stack.into_iter().zip(new_stack).take(BRANCH_CHILDREN).enumerate()
+            {
+                let child_path = [path.as_slice(), &[index as u8][..]].concat();
+                diff_nodes(child_path, old_child, new_child, provider, diffs)?;
+            }
+            Ok(())
+        }
+        (
+            TrieNode::Extension { prefix: old_prefix, node: old_node },
+            TrieNode::Extension { prefix: new_prefix, node: new_node },
+        ) if old_prefix == new_prefix => {
+            let child_path = [path.as_slice(), old_prefix.as_slice()].concat();
+            diff_nodes(child_path, *old_node, *new_node, provider, diffs)
+        }
+        (old, new) => diff_leaves(&path, old, new, provider, diffs),
+    }
+}
+
+/// Appends the differences between the leaves of the `old` and `new` subtries at `path` to
+/// `diffs`. Used once the shapes of the two subtries diverge.
+fn diff_leaves<P: TrieProvider>(
+    path: &[u8],
+    old: TrieNode,
+    new: TrieNode,
+    provider: &P,
+    diffs: &mut Vec<TrieDiff>,
+) -> Result<(), TrieNodeError> {
+    let full_key = |key: Nibbles| Nibbles::from_nibbles_unchecked([path, key.as_slice()].concat());
+    let old_leaves = TrieIterator::new(old, provider).collect::<Result<Vec<_>, _>>()?;
+    let new_leaves = TrieIterator::new(new, provider).collect::<Result<Vec<_>, _>>()?;
+
+    let mut old_leaves = old_leaves.into_iter().peekable();
+    let mut new_leaves = new_leaves.into_iter().peekable();
+    loop {
+        let ordering = match (old_leaves.peek(), new_leaves.peek()) {
+            (None, None) => return Ok(()),
+            (Some(_), None) => Ordering::Less,
+            (None, Some(_)) => Ordering::Greater,
+            (Some((old_key, _)), Some((new_key, _))) => old_key.cmp(new_key),
+        };
+
+        match ordering {
+            Ordering::Less => {
+                let (key, value) = old_leaves.next().expect("peeked");
+                diffs.push(TrieDiff::Removed { key: full_key(key), value });
+            }
+            Ordering::Greater => {
+                let (key, value) = new_leaves.next().expect("peeked");
+                diffs.push(TrieDiff::Added { key: full_key(key), value });
+            }
+            Ordering::Equal => {
+                let (key, old) = old_leaves.next().expect("peeked");
+                let (_, new) = new_leaves.next().expect("peeked");
+                if old != new {
+                    diffs.push(TrieDiff::Changed { key: full_key(key), old, new });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MapTrieProvider;
+    use alloc::{
+        collections::{BTreeMap, BTreeSet},
+        vec,
+    };
+    use alloy_primitives::keccak256;
+    use alloy_trie::EMPTY_ROOT_HASH;
+
+    fn key(i: u8) -> Nibbles {
+        Nibbles::unpack(keccak256([i]))
+    }
+
+    /// Builds a trie from `entries`, inserting the preimages of its nodes into `provider`, and
+    /// returns its root.
+    fn build_trie(entries: &BTreeMap<Nibbles, Bytes>, provider: &mut MapTrieProvider) -> B256 {
+        let mut trie = TrieNode::Empty;
+        for (key, value) in entries {
+            trie.insert(key, value.clone(), &MapTrieProvider::new()).unwrap();
+        }
+        provider.insert_trie(&trie)
+    }
+
+    /// Computes the expected differences between two sets of entries.
+    fn expected_diff(
+        old: &BTreeMap<Nibbles, Bytes>,
+        new: &BTreeMap<Nibbles, Bytes>,
+    ) -> Vec<TrieDiff> {
+        let keys = old.keys().chain(new.keys()).cloned().collect::<BTreeSet<_>>();
+        keys.into_iter()
+            .filter_map(|key| match (old.get(&key), new.get(&key)) {
+                (Some(value), None) => Some(TrieDiff::Removed { key, value: value.clone() }),
+                (None, Some(value)) => Some(TrieDiff::Added { key, value: value.clone() }),
+                (Some(old), Some(new)) if old != new => {
+                    Some(TrieDiff::Changed { key, old: old.clone(), new: new.clone() })
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_diff_tries() {
+        let old: BTreeMap<_, _> =
+            (0..64u8).map(|i| (key(i), Bytes::from(vec![i; 1 + i as usize]))).collect();
+        let mut new = old.clone();
+        new.remove(&key(3));
+        new.remove(&key(40));
+        new.insert(key(7), Bytes::from_static(b"changed"));
+        new.insert(key(100), Bytes::from_static(b"added"));
+        new.insert(key(101), Bytes::from_static(b"added too"));
+
+        let mut provider = MapTrieProvider::new();
+        let old_root = build_trie(&old, &mut provider);
+        let new_root = build_trie(&new, &mut provider);
+
+        let diffs = diff_tries(old_root, new_root, &provider).unwrap();
+        assert_eq!(diffs, expected_diff(&old, &new));
+        assert_eq!(diffs.len(), 5);
+        assert!(diffs.windows(2).all(|pair| pair[0].key() < pair[1].key()));
+
+        let reversed = diff_tries(new_root, old_root, &provider).unwrap();
+        assert_eq!(reversed, expected_diff(&new, &old));
+    }
+
+    #[test]
+    fn test_diff_identical_tries() {
+        let entries: BTreeMap<_, _> =
+            (0..16u8).map(|i| (key(i), Bytes::from(vec![i; 40]))).collect();
+        let mut provider = MapTrieProvider::new();
+        let root = build_trie(&entries, &mut provider);
+
+        // Identical roots are never unblinded, so no preimages are needed.
+        assert!(diff_tries(root, root, &MapTrieProvider::new()).unwrap().is_empty());
+        assert!(diff_tries(root, root, &provider).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_diff_against_empty_trie() {
+        let entries: BTreeMap<_, _> = (0..8u8).map(|i| (key(i), Bytes::from(vec![i; 8]))).collect();
+        let mut provider = MapTrieProvider::new();
+        let root = build_trie(&entries, &mut provider);
+
+        let empty = BTreeMap::new();
+        assert_eq!(
+            diff_tries(EMPTY_ROOT_HASH, root, &provider).unwrap(),
+            expected_diff(&empty, &entries)
+        );
+        assert_eq!(
+            diff_tries(root, EMPTY_ROOT_HASH, &provider).unwrap(),
+            expected_diff(&entries, &empty)
+        );
+    }
+
+    #[test]
+    fn test_diff_diverging_shapes() {
+        // A single leaf becomes a branch once a second key is inserted.
+        let old = BTreeMap::from([(key(0), Bytes::from_static(b"zero"))]);
+        let mut new = old.clone();
+        new.insert(key(1), Bytes::from_static(b"one"));
+
+        let mut provider = MapTrieProvider::new();
+        let old_root = build_trie(&old, &mut provider);
+        let new_root = build_trie(&new, &mut provider);
+
+        assert_eq!(
+            diff_tries(old_root, new_root, &provider).unwrap(),
+            vec![TrieDiff::Added { key: key(1), value: Bytes::from_static(b"one") }]
+        );
+    }
+
+    #[test]
+    fn test_diff_missing_preimage() {
+        let entries: BTreeMap<_, _> = (0..8u8).map(|i| (key(i), Bytes::from(vec![i; 8]))).collect();
+        let root = build_trie(&entries, &mut MapTrieProvider::new());
+        assert!(diff_tries(EMPTY_ROOT_HASH, root, &MapTrieProvider::new()).is_err());
+    }
+}
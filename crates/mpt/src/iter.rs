@@ -0,0 +1,164 @@
+//! This module contains the [TrieIterator] struct, which walks the leaves of a trie in key order.
+
+use crate::{Nibbles, TrieNode, TrieNodeError, TrieProvider};
+use alloc::{vec, vec::Vec};
+use alloy_primitives::Bytes;
+
+/// The number of children of a branch node, excluding its value.
+const BRANCH_CHILDREN: usize = 16;
+
+/// A [TrieIterator] walks all leaves of a trie in key order, yielding their full key and value.
+///
+/// Blinded nodes are unblinded through the [TrieProvider] as the walk reaches them, so the trie
+/// may be fully blinded, partially opened, or fully opened. Unlike the
+/// [OrderedListWalker](crate::OrderedListWalker), the keys of the trie are not assumed to be list
+/// indices.
+///
+/// The provider may be borrowed, as [TrieProvider] is implemented for references. If a node cannot
+/// be unblinded, the error is yielded and the iteration ends.
+#[derive(Debug)]
+pub struct TrieIterator<P> {
+    /// The provider used to unblind nodes.
+    provider: P,
+    /// The nodes that are yet to be visited, paired with the path leading to them. The next node
+    /// in key order is at the top of the stack.
+    stack: Vec<(Vec<u8>, TrieNode)>,
+}
+
+impl<P: TrieProvider> TrieIterator<P> {
+    /// Creates a new [TrieIterator] over the leaves of the trie rooted at `root`.
+    pub fn new(root: TrieNode, provider: P) -> Self {
+        Self { provider, stack: vec![(Vec::new(), root)] }
+    }
+
+    /// Visits the next node on the stack, returning its key and value if it is a leaf.
+    fn step(
+        &mut self,
+        path: Vec<u8>,
+        mut node: TrieNode,
+    ) -> Result<Option<(Nibbles, Bytes)>, TrieNodeError> {
+        node.unblind(&self.provider)?;
+        match node {
+            TrieNode::Empty => Ok(None),
+            TrieNode::Blinded { commitment } => Err(TrieNodeError::Blinded(commitment)),
+            TrieNode::Leaf { key, value } => Ok(Some((
+                Nibbles::from_nibbles_unchecked([path.as_slice(), key.as_slice()].concat()),
+                value,
+            ))),
+            TrieNode::Extension { prefix, node } => {
+                self.stack.push(([path.as_slice(), prefix.as_slice()].concat(), *node));
+                Ok(None)
+            }
+            TrieNode::Branch { mut stack } => {
+                if stack.get(BRANCH_CHILDREN).is_some_and(|value| *value != TrieNode::Empty) {
+                    return Err(TrieNodeError::BranchValue);
+                }
+                stack.truncate(BRANCH_CHILDREN);
+
+                // Push the children in reverse, so that the lowest nibble is visited first.
+                for (index, child) in stack.into_iter().enumerate().rev() {
+                    if child != TrieNode::Empty {
+                        self.stack.push(([path.as_slice(), &[index as u8][..]].concat(), child));
+                    }
+                }
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl<P: TrieProvider> Iterator for TrieIterator<P> {
+    type Item = Result<(Nibbles, Bytes), TrieNodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((path, node)) = self.stack.pop() {
+            match self.step(path, node) {
+                Ok(Some(leaf)) => return Some(Ok(leaf)),
+                Ok(None) => continue,
+                Err(e) => {
+                    self.stack.clear();
+                    return Some(Err(e));
+                }
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MapTrieProvider;
+    use alloc::collections::BTreeMap;
+    use alloy_primitives::{keccak256, B256};
+    use alloy_trie::{HashBuilder, EMPTY_ROOT_HASH};
+
+    /// Builds a trie keyed by hashed indices, returning its root, the preimages of its blinded
+    /// nodes, and its entries in key order.
+    fn build_trie(len: u8) -> (B256, MapTrieProvider, Vec<(Nibbles, Bytes)>) {
+        let entries = (0..len)
+            .map(|i| (Nibbles::unpack(keccak256([i])), Bytes::from(vec![i; 1 + i as usize])))
+            .collect::<BTreeMap<_, _>>();
+
+        let mut hb = HashBuilder::default().with_proof_retainer(entries.keys().cloned().collect());
+        for (key, value) in &entries {
+            hb.add_leaf(key.clone(), value);
+        }
+        let root = hb.root();
+        let mut provider = MapTrieProvider::new();
+        hb.take_proofs().into_values().for_each(|node| {
+            provider.insert_trie_node(node);
+        });
+
+        (root, provider, entries.into_iter().collect())
+    }
+
+    #[test]
+    fn test_iterate_blinded() {
+        let (root, provider, entries) = build_trie(64);
+        let leaves = TrieIterator::new(TrieNode::Blinded { commitment: root }, &provider)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(leaves, entries);
+    }
+
+    #[test]
+    fn test_iterate_partially_opened() {
+        let (root, provider, entries) = build_trie(32);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        for (key, _) in entries.iter().step_by(3) {
+            trie.open(key, &provider).unwrap();
+        }
+        let new_key = Nibbles::unpack(keccak256([0xFF]));
+        trie.insert(&new_key, Bytes::from_static(&[0xFF]), &provider).unwrap();
+
+        let mut expected = entries.into_iter().collect::<BTreeMap<_, _>>();
+        expected.insert(new_key, Bytes::from_static(&[0xFF]));
+        let leaves = TrieIterator::new(trie, &provider).collect::<Result<Vec<_>, _>>().unwrap();
+        assert_eq!(leaves, expected.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_iterate_empty() {
+        let provider = MapTrieProvider::new();
+        assert_eq!(TrieIterator::new(TrieNode::Empty, &provider).count(), 0);
+        assert_eq!(
+            TrieIterator::new(TrieNode::Blinded { commitment: EMPTY_ROOT_HASH }, &provider).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_iterate_missing_node() {
+        let (root, mut provider, entries) = build_trie(16);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        trie.open(&entries[0].0, &provider).unwrap();
+        provider.preimages.clear();
+
+        // The opened path yields its leaf, and the first blinded sibling ends the iteration.
+        let mut iter = TrieIterator::new(trie, &provider);
+        assert_eq!(iter.next().unwrap().unwrap(), entries[0]);
+        assert!(iter.next().unwrap().is_err());
+        assert!(iter.next().is_none());
+    }
+}
@@ -7,10 +7,71 @@
 extern crate alloc;
 
 mod node;
-pub use node::TrieNode;
+pub use node::{TrieNode, TrieNodeError};
+
+mod node_ref;
+pub use node_ref::{PathRef, TrieNodeRef};
+
+mod arena;
+pub use arena::ArenaTrie;
+
+mod account;
+pub use account::TrieAccount;
+
+pub use alloy_trie::Nibbles;
+
+mod traits;
+pub use traits::{
+    AsyncTrieAdapter, AsyncTrieHinter, AsyncTrieProvider, BlockOn, BlockingTrieProvider,
+    NoopTrieHinter, TrieHinter, TrieProvider,
+};
+
+mod provider;
+pub use provider::MapTrieProvider;
+
+mod witness;
+pub use witness::{RecordingTrieProvider, Witness};
+
+mod prefetch;
+pub use prefetch::PrefetchPlan;
+
+mod metrics;
+pub use metrics::{MeteredTrieProvider, TrieCounters, TrieMetrics, TrieStats};
+
+mod code;
+pub use code::{code_by_hash, CodeError};
 
 mod list_walker;
-pub use list_walker::OrderedListWalker;
+pub use list_walker::{OrderedListError, OrderedListStream, OrderedListWalker};
+
+mod secure;
+pub use secure::SecureTrie;
+
+mod db;
+pub use db::{
+    AccountDiff, SharedNodeStore, StateDiff, TrieDB, TrieDBAccount, TrieDBCheckpoint, TrieDBError,
+    TrieDBHeader,
+};
+
+mod iter;
+pub use iter::TrieIterator;
+
+mod builder;
+pub use builder::trie_from_sorted;
+
+mod root;
+pub use root::{
+    compute_receipts_root, compute_requests_hash, compute_requests_root, compute_transactions_root,
+    compute_withdrawals_root, ordered_trie_root_with_encoder, EMPTY_REQUESTS_HASH,
+};
+
+mod diff;
+pub use diff::{diff_tries, TrieDiff};
+
+mod proof;
+pub use proof::{
+    verify_proof, verify_range_proof, AccountProof, ProofError, StorageProof, VerifiedRange,
+};
 
-#[cfg(test)]
-mod test_util;
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
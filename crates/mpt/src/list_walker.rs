@@ -1,25 +1,72 @@
-//! This module contains the [OrderedListWalker] struct, which allows for traversing an MPT root of
-//! a derivable ordered list.
+//! This module contains the [OrderedListWalker] and [OrderedListStream] structs, which allow for
+//! traversing an MPT root of a derivable ordered list.
 
-use crate::TrieNode;
-use alloc::{collections::VecDeque, vec};
+use crate::{
+    node_ref::get_borrowed, ordered_trie_root_with_encoder, Nibbles, TrieIterator, TrieNode,
+    TrieProvider,
+};
+use alloc::{collections::VecDeque, vec, vec::Vec};
+use alloy_consensus::{ReceiptWithBloom, TxEnvelope};
 use alloy_primitives::{Bytes, B256};
-use alloy_rlp::{Decodable, EMPTY_STRING_CODE};
+use alloy_rlp::{BufMut, Decodable, Encodable, EMPTY_STRING_CODE};
 use anyhow::{anyhow, Result};
-use core::marker::PhantomData;
+use core::{fmt::Display, marker::PhantomData};
+
+/// The highest EIP-2718 transaction type. Any larger first byte of an encoded transaction or
+/// receipt is an RLP header, meaning the item is a legacy one.
+const MAX_TX_TYPE: u8 = 0x7f;
+
+/// An error returned when the hydrated elements of an [OrderedListWalker] do not match the list
+/// they were expected to hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OrderedListError {
+    /// The walker has not been hydrated.
+    NotHydrated,
+    /// The number of hydrated elements differs from the expected length of the list.
+    LengthMismatch {
+        /// The expected number of elements.
+        expected: usize,
+        /// The number of hydrated elements.
+        actual: usize,
+    },
+    /// Committing to the hydrated elements yields a different root than the one they were
+    /// hydrated from.
+    RootMismatch {
+        /// The root the walker was hydrated from.
+        expected: B256,
+        /// The root of the hydrated elements.
+        computed: B256,
+    },
+}
+
+impl Display for OrderedListError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NotHydrated => write!(f, "Ordered list has not been hydrated"),
+            Self::LengthMismatch { expected, actual } => {
+                write!(f, "Expected {expected} list elements, hydrated {actual}")
+            }
+            Self::RootMismatch { expected, computed } => {
+                write!(f, "List root mismatch: expected {expected}, computed {computed}")
+            }
+        }
+    }
+}
 
 /// A [OrderedListWalker] allows for traversing over a Merkle Patricia Trie containing a derivable
 /// ordered list.
 ///
 /// Once it has ben hydrated with [Self::hydrate], the elements in the derivable list can be
-/// iterated over using the [Iterator] implementation.
+/// iterated over using the [Iterator] implementation, in either direction. To avoid traversing the
+/// whole trie, [Self::seek] hydrates only the tail of the list, and [Self::get] fetches a single
+/// element.
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub struct OrderedListWalker<PreimageFetcher> {
     /// The Merkle Patricia Trie root.
     root: B256,
     /// The leaf nodes of the derived list, in order. [None] if the tree has yet to be fully
     /// traversed with [Self::hydrate].
-    inner: Option<VecDeque<(Bytes, Bytes)>>,
+    inner: Option<VecDeque<(Nibbles, Bytes)>>,
     /// Phantom data
     _phantom: PhantomData<PreimageFetcher>,
 }
@@ -74,17 +121,132 @@ where
         Ok(())
     }
 
+    /// Hydrates the [OrderedListWalker] with [Self::hydrate], and validates the hydrated list with
+    /// [Self::validate]. A list that is truncated or does not reproduce the root is rejected with
+    /// an [OrderedListError], rather than yielding the wrong elements.
+    pub fn hydrate_with_len(
+        &mut self,
+        expected_len: usize,
+        fetcher: PreimageFetcher,
+    ) -> Result<()> {
+        self.hydrate(fetcher)?;
+        self.validate(expected_len).map_err(|e| anyhow!(e))
+    }
+
+    /// Validates the hydrated elements of the [OrderedListWalker]: the list must hold
+    /// `expected_len` elements, such as the transaction count of a block, and committing to the
+    /// elements must reproduce the root they were hydrated from.
+    ///
+    /// This must be called after [Self::hydrate], before any element is consumed.
+    pub fn validate(&self, expected_len: usize) -> Result<(), OrderedListError> {
+        let inner = self.inner.as_ref().ok_or(OrderedListError::NotHydrated)?;
+        if inner.len() != expected_len {
+            return Err(OrderedListError::LengthMismatch {
+                expected: expected_len,
+                actual: inner.len(),
+            });
+        }
+
+        let values = inner.iter().map(|(_, value)| value.clone()).collect::<Vec<_>>();
+        let computed = ordered_trie_root_with_encoder(&values, |value, buf| buf.put_slice(value));
+        if computed != self.root {
+            return Err(OrderedListError::RootMismatch { expected: self.root, computed });
+        }
+        Ok(())
+    }
+
+    /// Hydrates the [OrderedListWalker]'s iterator with the elements of the derivable list starting
+    /// at `index`, replacing any elements that have not been consumed yet. Only the trie nodes on
+    /// the paths to these elements are fetched, so the head of the list is never traversed.
+    ///
+    /// Unlike [Self::hydrate], the keys of the hydrated elements are their full paths in the trie.
+    pub fn seek(&mut self, index: u64, fetcher: PreimageFetcher) -> Result<()> {
+        let provider = FetcherProvider(fetcher);
+        let mut trie = TrieNode::Blinded { commitment: self.root };
+
+        // Opening the elements in place keeps the nodes they share unblinded, so each node on the
+        // way to the tail is only fetched once.
+        let mut tail = VecDeque::new();
+        for index in index.. {
+            let key = index_key(index);
+            let Some(value) = trie.open(&key, &provider).map_err(|e| anyhow!(e))? else {
+                break;
+            };
+            tail.push_back((key, value.clone()));
+        }
+
+        self.inner = Some(tail);
+        Ok(())
+    }
+
+    /// Fetches the element at `index` in the derivable list, without hydrating the
+    /// [OrderedListWalker]. Returns [None] if the list is shorter than `index + 1` elements.
+    ///
+    /// The nodes on the path are decoded as [TrieNodeRef](crate::TrieNodeRef)s, which borrow from
+    /// their preimages instead of copying them.
+    pub fn get(&self, index: u64, fetcher: PreimageFetcher) -> Result<Option<Bytes>> {
+        get_borrowed(self.root, &index_key(index), &FetcherProvider(fetcher))
+            .map_err(|e| anyhow!(e))
+    }
+
+    /// Hydrates the [OrderedListWalker] with [Self::hydrate], and decodes each value of the
+    /// derivable list as an EIP-2718 encoded receipt. The inner list is consumed.
+    pub fn hydrate_receipts(&mut self, fetcher: PreimageFetcher) -> Result<Vec<ReceiptWithBloom>> {
+        self.hydrate(fetcher)?;
+        self.decode_inner(|value| {
+            // Typed receipts are prefixed with their transaction type, which is never a valid
+            // RLP list header. Legacy receipts are a bare RLP list.
+            let mut buf = match value.first() {
+                Some(ty) if *ty <= MAX_TX_TYPE => &value[1..],
+                _ => value,
+            };
+            ReceiptWithBloom::decode(&mut buf).map_err(|e| anyhow!(e))
+        })
+    }
+
+    /// Hydrates the [OrderedListWalker] with [Self::hydrate], and decodes each value of the
+    /// derivable list as an EIP-2718 encoded transaction. The inner list is consumed.
+    pub fn hydrate_transactions(&mut self, fetcher: PreimageFetcher) -> Result<Vec<TxEnvelope>> {
+        self.hydrate(fetcher)?;
+        self.decode_inner(|mut value| TxEnvelope::decode(&mut value).map_err(|e| anyhow!(e)))
+    }
+
     /// Takes the inner list of the [OrderedListWalker], returning it and setting the inner list to
     /// [None].
-    pub fn take_inner(&mut self) -> Option<VecDeque<(Bytes, Bytes)>> {
+    pub fn take_inner(&mut self) -> Option<VecDeque<(Nibbles, Bytes)>> {
         self.inner.take()
     }
 
+    /// Consumes the [OrderedListWalker], returning the values of the derivable list paired with
+    /// their index in the list. Returns an empty list if the walker has not been hydrated.
+    pub fn into_inner(self) -> Vec<(u64, Bytes)> {
+        self.inner
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .map(|(i, (_, value))| (i as u64, value))
+            .collect()
+    }
+
+    /// Takes the inner list of the [OrderedListWalker] and decodes each value with `decode`. Errors
+    /// report the index of the value that failed to decode.
+    fn decode_inner<T>(&mut self, decode: impl Fn(&[u8]) -> Result<T>) -> Result<Vec<T>> {
+        self.inner
+            .take()
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+            .map(|(i, (_, value))| {
+                decode(value.as_ref()).map_err(|e| anyhow!("Failed to decode index {i}: {e}"))
+            })
+            .collect()
+    }
+
     /// Traverses a [TrieNode], returning all values of child [TrieNode::Leaf] variants.
     fn fetch_leaves(
         trie_node: &TrieNode,
         fetcher: PreimageFetcher,
-    ) -> Result<VecDeque<(Bytes, Bytes)>> {
+    ) -> Result<VecDeque<(Nibbles, Bytes)>> {
         match trie_node {
             TrieNode::Branch { stack } => {
                 let mut leaf_values = VecDeque::with_capacity(stack.len());
@@ -134,7 +296,7 @@ where
 }
 
 impl<PreimageFetcher> Iterator for OrderedListWalker<PreimageFetcher> {
-    type Item = (Bytes, Bytes);
+    type Item = (Nibbles, Bytes);
 
     fn next(&mut self) -> Option<Self::Item> {
         match self.inner {
@@ -142,20 +304,152 @@ impl<PreimageFetcher> Iterator for OrderedListWalker<PreimageFetcher> {
             _ => None,
         }
     }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.inner.as_ref().map(|leaves| leaves.len()).unwrap_or_default();
+        (len, Some(len))
+    }
+}
+
+impl<PreimageFetcher> DoubleEndedIterator for OrderedListWalker<PreimageFetcher> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        match self.inner {
+            Some(ref mut leaves) => leaves.pop_back(),
+            _ => None,
+        }
+    }
+}
+
+impl<PreimageFetcher> ExactSizeIterator for OrderedListWalker<PreimageFetcher> {}
+
+/// An [OrderedListStream] lazily walks a Merkle Patricia Trie containing a derivable ordered list,
+/// yielding its elements in order along with their index.
+///
+/// Unlike the [OrderedListWalker], the list is never hydrated up front: trie nodes are unblinded
+/// as the walk reaches them and released once their subtrie has been yielded, so memory use is
+/// bounded by the depth of the trie rather than the length of the list.
+///
+/// If a node cannot be fetched or the trie does not hold a contiguous list, the error is yielded
+/// and the iteration ends.
+#[derive(Debug)]
+pub struct OrderedListStream<PreimageFetcher> {
+    /// The Merkle Patricia Trie root.
+    root: B256,
+    /// The preimage fetcher.
+    fetcher: PreimageFetcher,
+    /// The walk over the leaves of the trie in key order. [None] until the first element has
+    /// been yielded.
+    leaves: Option<TrieIterator<FetcherProvider<PreimageFetcher>>>,
+    /// The index of the next element to yield.
+    next_index: u64,
+    /// Whether the stream has been exhausted or has failed.
+    done: bool,
+}
+
+impl<PreimageFetcher> OrderedListStream<PreimageFetcher>
+where
+    PreimageFetcher: Fn(B256) -> Result<Bytes> + Copy,
+{
+    /// Creates a new [OrderedListStream] over the list committed to by `root`.
+    pub fn new(root: B256, fetcher: PreimageFetcher) -> Self {
+        Self { root, fetcher, leaves: None, next_index: 0, done: false }
+    }
+
+    /// Fetches the value of the next element, returning [None] once the list is exhausted.
+    fn next_value(&mut self) -> Result<Option<Bytes>> {
+        let first_key = index_key(0);
+        let Some(leaves) = self.leaves.as_mut() else {
+            // The first element is keyed by the RLP empty string, which sorts after the keys of
+            // indices 1 through 127, so it is looked up on its own before the walk begins.
+            let provider = FetcherProvider(self.fetcher);
+            let root = TrieNode::Blinded { commitment: self.root };
+            let first = root.get(&first_key, &provider).map_err(|e| anyhow!(e))?;
+            self.leaves = Some(TrieIterator::new(root, provider));
+            return Ok(first);
+        };
+
+        for leaf in leaves.by_ref() {
+            let (key, value) = leaf.map_err(|e| anyhow!(e))?;
+            if key == first_key {
+                continue;
+            }
+            if key != index_key(self.next_index) {
+                anyhow::bail!("Unexpected key {key:?} at index {}", self.next_index);
+            }
+            return Ok(Some(value));
+        }
+        Ok(None)
+    }
+}
+
+impl<PreimageFetcher> Iterator for OrderedListStream<PreimageFetcher>
+where
+    PreimageFetcher: Fn(B256) -> Result<Bytes> + Copy,
+{
+    type Item = Result<(u64, Bytes)>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        match self.next_value() {
+            Ok(Some(value)) => {
+                let index = self.next_index;
+                self.next_index += 1;
+                Some(Ok((index, value)))
+            }
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Returns the path of the element at `index` in a derivable list trie, which is the RLP encoding
+/// of the index.
+fn index_key(index: u64) -> Nibbles {
+    let mut key = Vec::with_capacity(index.length());
+    index.encode(&mut key);
+    Nibbles::unpack(key)
+}
+
+/// Adapts the preimage fetcher of an [OrderedListWalker] into a [TrieProvider], so that elements
+/// can be looked up through [TrieNode].
+#[derive(Debug)]
+struct FetcherProvider<PreimageFetcher>(PreimageFetcher);
+
+impl<PreimageFetcher> TrieProvider for FetcherProvider<PreimageFetcher>
+where
+    PreimageFetcher: Fn(B256) -> Result<Bytes>,
+{
+    fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+        (self.0)(key)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+        anyhow::bail!("Bytecode {code_hash} is not part of a derivable list")
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
-    use crate::test_util::{
+    use crate::test_utils::{
         get_live_derivable_receipts_list, get_live_derivable_transactions_list,
         ordered_trie_with_encoder,
     };
     use alloc::{collections::BTreeMap, string::String, vec::Vec};
-    use alloy_consensus::{ReceiptEnvelope, TxEnvelope};
+    use alloy_consensus::{Receipt, ReceiptEnvelope};
     use alloy_primitives::keccak256;
-    use alloy_provider::network::eip2718::Decodable2718;
+    use alloy_provider::network::eip2718::{Decodable2718, Encodable2718};
     use alloy_rlp::Encodable;
+    use core::cell::Cell;
 
     #[tokio::test]
     async fn test_list_walker_online_receipts() {
@@ -170,6 +464,17 @@ mod test {
                 .collect::<Vec<_>>(),
             envelopes
         );
+
+        let receipts = OrderedListWalker::new(root)
+            .hydrate_receipts(|f| Ok(preimages.get(&f).unwrap().clone()))
+            .unwrap();
+        assert_eq!(
+            receipts,
+            envelopes
+                .iter()
+                .map(|e| e.as_receipt_with_bloom().unwrap().clone())
+                .collect::<Vec<_>>()
+        );
     }
 
     #[tokio::test]
@@ -185,6 +490,11 @@ mod test {
                 .collect::<Vec<_>>(),
             envelopes
         );
+
+        let transactions = OrderedListWalker::new(root)
+            .hydrate_transactions(|f| Ok(preimages.get(&f).unwrap().clone()))
+            .unwrap();
+        assert_eq!(transactions, envelopes);
     }
 
     #[test]
@@ -213,4 +523,200 @@ mod test {
             VALUES
         );
     }
+
+    #[test]
+    fn test_list_walker_validate() {
+        let values = (0..200u64).collect::<Vec<_>>();
+        let mut trie = ordered_trie_with_encoder(&values, |v, buf| v.encode(buf));
+        let root = trie.root();
+        let mut preimages =
+            trie.take_proofs().into_iter().fold(BTreeMap::default(), |mut acc, (_, value)| {
+                acc.insert(keccak256(value.as_ref()), value);
+                acc
+            });
+        let fetcher = |f: B256| preimages.get(&f).cloned().ok_or(anyhow!("Missing preimage"));
+
+        let mut list = OrderedListWalker::new(root);
+        assert_eq!(list.validate(200), Err(OrderedListError::NotHydrated));
+        list.hydrate_with_len(200, fetcher).unwrap();
+        assert_eq!(list.len(), 200);
+
+        let mut list = OrderedListWalker::new(root);
+        let err = list.hydrate_with_len(199, fetcher).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<OrderedListError>(),
+            Some(&OrderedListError::LengthMismatch { expected: 199, actual: 200 })
+        );
+
+        // Serve the nodes of a different list under the root, as a corrupted host would.
+        let mut other = ordered_trie_with_encoder(&values[..100], |v, buf| v.encode(buf));
+        let other_root = other.root();
+        for (_, value) in other.take_proofs() {
+            preimages.insert(keccak256(value.as_ref()), value);
+        }
+        let other_preimage = preimages[&other_root].clone();
+        preimages.insert(root, other_preimage);
+        let fetcher = |f: B256| preimages.get(&f).cloned().ok_or(anyhow!("Missing preimage"));
+
+        let mut list = OrderedListWalker::new(root);
+        let err = list.hydrate_with_len(100, fetcher).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<OrderedListError>(),
+            Some(&OrderedListError::RootMismatch { expected: root, computed: other_root })
+        );
+    }
+
+    #[test]
+    fn test_list_walker_hydrate_receipts() {
+        let receipt = |status: bool, cumulative_gas_used: u64| {
+            ReceiptWithBloom::new(
+                Receipt { status, cumulative_gas_used, logs: vec![] },
+                Default::default(),
+            )
+        };
+        let envelopes = [
+            ReceiptEnvelope::Legacy(receipt(true, 21_000)),
+            ReceiptEnvelope::Eip1559(receipt(false, 42_000)),
+            ReceiptEnvelope::Eip2930(receipt(true, 63_000)),
+            ReceiptEnvelope::Legacy(receipt(true, 84_000)),
+        ];
+
+        let mut trie = ordered_trie_with_encoder(&envelopes, |e, buf| e.encode_2718(buf));
+        let root = trie.root();
+        let preimages =
+            trie.take_proofs().into_iter().fold(BTreeMap::default(), |mut acc, (_, value)| {
+                acc.insert(keccak256(value.as_ref()), value);
+                acc
+            });
+        let fetcher = |f: B256| -> Result<Bytes> { Ok(preimages.get(&f).unwrap().clone()) };
+
+        let receipts = OrderedListWalker::new(root).hydrate_receipts(fetcher).unwrap();
+        assert_eq!(
+            receipts,
+            envelopes
+                .iter()
+                .map(|e| e.as_receipt_with_bloom().unwrap().clone())
+                .collect::<Vec<_>>()
+        );
+
+        let inner = OrderedListWalker::try_new_hydrated(root, fetcher).unwrap().into_inner();
+        assert_eq!(inner.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        inner.iter().zip(envelopes.iter()).for_each(|((_, value), envelope)| {
+            let mut expected = Vec::new();
+            envelope.encode_2718(&mut expected);
+            assert_eq!(value.as_ref(), expected.as_slice());
+        });
+    }
+
+    #[test]
+    fn test_list_walker_reverse_and_seek() {
+        let values = (0..200u64).collect::<Vec<_>>();
+
+        let mut trie = ordered_trie_with_encoder(&values, |v, buf| v.encode(buf));
+        let root = trie.root();
+        let preimages =
+            trie.take_proofs().into_iter().fold(BTreeMap::default(), |mut acc, (_, value)| {
+                acc.insert(keccak256(value.as_ref()), value);
+                acc
+            });
+        let fetches = Cell::new(0);
+        let fetcher = |f: B256| -> Result<Bytes> {
+            fetches.set(fetches.get() + 1);
+            Ok(preimages.get(&f).unwrap().clone())
+        };
+        let decode = |(_, v): (Nibbles, Bytes)| u64::decode(&mut v.as_ref()).unwrap();
+
+        // Reverse iteration over the hydrated list.
+        let list = OrderedListWalker::try_new_hydrated(root, fetcher).unwrap();
+        assert_eq!(list.len(), values.len());
+        assert_eq!(
+            list.rev().map(decode).collect::<Vec<_>>(),
+            values.iter().rev().copied().collect::<Vec<_>>()
+        );
+
+        // Seeking hydrates only the tail, which spans the 0x80 boundary of the keys.
+        for index in [0, 1, 127, 128, 150, 199, 200] {
+            let mut list = OrderedListWalker::new(root);
+            list.seek(index, fetcher).unwrap();
+            assert_eq!(list.map(decode).collect::<Vec<_>>(), values[index as usize..]);
+        }
+
+        // Single elements are fetched along their path only.
+        let list = OrderedListWalker::new(root);
+        fetches.set(0);
+        assert_eq!(list.get(199, fetcher).unwrap(), Some(Bytes::from(vec![0x81, 199])));
+        assert!(fetches.get() < preimages.len());
+        assert_eq!(list.get(0, fetcher).unwrap(), Some(Bytes::from(vec![0x80])));
+        assert_eq!(list.get(200, fetcher).unwrap(), None);
+    }
+
+    #[test]
+    fn test_list_stream() {
+        for len in [0, 1, 2, 127, 128, 129, 300u64] {
+            let values = (0..len).map(|i| i * 3).collect::<Vec<_>>();
+            let mut trie = ordered_trie_with_encoder(&values, |v, buf| v.encode(buf));
+            let root = trie.root();
+            let preimages =
+                trie.take_proofs().into_iter().fold(BTreeMap::default(), |mut acc, (_, value)| {
+                    acc.insert(keccak256(value.as_ref()), value);
+                    acc
+                });
+            let fetcher = |f: B256| -> Result<Bytes> {
+                preimages.get(&f).cloned().ok_or_else(|| anyhow!("Missing preimage {f}"))
+            };
+
+            let streamed = OrderedListStream::new(root, fetcher)
+                .map(|element| {
+                    let (index, value) = element.unwrap();
+                    (index, u64::decode(&mut value.as_ref()).unwrap())
+                })
+                .collect::<Vec<_>>();
+            assert_eq!(
+                streamed,
+                values.into_iter().enumerate().map(|(i, v)| (i as u64, v)).collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn test_list_stream_stops_on_error() {
+        let values = (0..64u64).collect::<Vec<_>>();
+        let mut trie = ordered_trie_with_encoder(&values, |v, buf| v.encode(buf));
+        let root = trie.root();
+        let preimages =
+            trie.take_proofs().into_iter().fold(BTreeMap::default(), |mut acc, (_, value)| {
+                acc.insert(keccak256(value.as_ref()), value);
+                acc
+            });
+
+        // Only the root preimage is available.
+        let fetcher = |f: B256| -> Result<Bytes> {
+            if f == root {
+                Ok(preimages.get(&f).unwrap().clone())
+            } else {
+                anyhow::bail!("Missing preimage {f}")
+            }
+        };
+        let mut stream = OrderedListStream::new(root, fetcher);
+        assert!(stream.any(|element| element.is_err()));
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn test_list_walker_hydrate_malformed_entry() {
+        const VALUES: [&str; 3] = ["test one", "test two", "test three"];
+
+        let mut trie = ordered_trie_with_encoder(&VALUES, |v, buf| v.encode(buf));
+        let root = trie.root();
+        let preimages =
+            trie.take_proofs().into_iter().fold(BTreeMap::default(), |mut acc, (_, value)| {
+                acc.insert(keccak256(value.as_ref()), value);
+                acc
+            });
+
+        let err = OrderedListWalker::new(root)
+            .hydrate_receipts(|f| Ok(preimages.get(&f).unwrap().clone()))
+            .unwrap_err();
+        assert!(err.to_string().contains("index 0"));
+    }
 }
@@ -0,0 +1,213 @@
+//! This module contains the [TrieMetrics] trait and the [MeteredTrieProvider], which reports the
+//! preimages fetched through a [TrieProvider] to it.
+
+use crate::{TrieHinter, TrieProvider};
+use alloy_primitives::{Address, Bytes, B256};
+use anyhow::Result;
+use core::cell::Cell;
+
+/// Instrumentation hooks invoked by a [MeteredTrieProvider]. All hooks default to doing nothing,
+/// so implementors only override the events they are interested in.
+///
+/// Every opened trie node is decoded from a fetched preimage, so the sizes reported here bound
+/// the heap used by the opened parts of a trie.
+pub trait TrieMetrics {
+    /// Called when the preimage of the trie node committed to by `commitment` is fetched to open
+    /// it. `len` is the length of the preimage in bytes.
+    fn on_node_open(&self, _commitment: B256, _len: usize) {}
+
+    /// Called when the bytecode with the given `code_hash` is fetched. `len` is the length of the
+    /// bytecode in bytes.
+    fn on_bytecode_fetch(&self, _code_hash: B256, _len: usize) {}
+
+    /// Called when fetching the preimage of `key` fails.
+    fn on_fetch_error(&self, _key: B256) {}
+}
+
+impl<M: TrieMetrics + ?Sized> TrieMetrics for &M {
+    fn on_node_open(&self, commitment: B256, len: usize) {
+        (**self).on_node_open(commitment, len)
+    }
+
+    fn on_bytecode_fetch(&self, code_hash: B256, len: usize) {
+        (**self).on_bytecode_fetch(code_hash, len)
+    }
+
+    fn on_fetch_error(&self, key: B256) {
+        (**self).on_fetch_error(key)
+    }
+}
+
+/// The totals collected by [TrieCounters].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TrieStats {
+    /// The number of trie nodes opened.
+    pub nodes_opened: usize,
+    /// The total size of the opened trie node preimages, in bytes.
+    pub node_bytes: usize,
+    /// The number of bytecode preimages fetched.
+    pub bytecode_fetched: usize,
+    /// The total size of the fetched bytecode, in bytes.
+    pub bytecode_bytes: usize,
+    /// The number of failed fetches.
+    pub errors: usize,
+}
+
+/// A [TrieMetrics] implementation that sums up the reported events into [TrieStats].
+#[derive(Debug, Default)]
+pub struct TrieCounters {
+    /// The totals collected so far.
+    stats: Cell<TrieStats>,
+}
+
+impl TrieCounters {
+    /// Creates a new [TrieCounters] with all totals at zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the totals collected so far.
+    pub fn stats(&self) -> TrieStats {
+        self.stats.get()
+    }
+
+    /// Resets all totals to zero, returning the totals collected until now.
+    pub fn reset(&self) -> TrieStats {
+        self.stats.take()
+    }
+
+    /// Applies `update` to the totals.
+    fn update(&self, update: impl FnOnce(&mut TrieStats)) {
+        let mut stats = self.stats.get();
+        update(&mut stats);
+        self.stats.set(stats);
+    }
+}
+
+impl TrieMetrics for TrieCounters {
+    fn on_node_open(&self, _: B256, len: usize) {
+        self.update(|stats| {
+            stats.nodes_opened += 1;
+            stats.node_bytes += len;
+        });
+    }
+
+    fn on_bytecode_fetch(&self, _: B256, len: usize) {
+        self.update(|stats| {
+            stats.bytecode_fetched += 1;
+            stats.bytecode_bytes += len;
+        });
+    }
+
+    fn on_fetch_error(&self, _: B256) {
+        self.update(|stats| stats.errors += 1);
+    }
+}
+
+/// A [MeteredTrieProvider] wraps a [TrieProvider], and reports every trie node and bytecode
+/// preimage fetched through it to a [TrieMetrics] implementation. Hints are forwarded to the
+/// inner provider as is.
+#[derive(Debug)]
+pub struct MeteredTrieProvider<P, M> {
+    /// The inner provider.
+    inner: P,
+    /// The metrics the fetches are reported to.
+    metrics: M,
+}
+
+impl<P, M> MeteredTrieProvider<P, M> {
+    /// Creates a new [MeteredTrieProvider] that reports the fetches through `inner` to `metrics`.
+    pub const fn new(inner: P, metrics: M) -> Self {
+        Self { inner, metrics }
+    }
+
+    /// Returns a reference to the inner provider.
+    pub const fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns a reference to the metrics.
+    pub const fn metrics(&self) -> &M {
+        &self.metrics
+    }
+
+    /// Consumes the [MeteredTrieProvider], returning the inner provider and the metrics.
+    pub fn into_parts(self) -> (P, M) {
+        (self.inner, self.metrics)
+    }
+}
+
+impl<P: TrieProvider, M: TrieMetrics> TrieProvider for MeteredTrieProvider<P, M> {
+    fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+        let preimage = self.inner.trie_node_preimage(key).map_err(|e| {
+            self.metrics.on_fetch_error(key);
+            e
+        })?;
+        self.metrics.on_node_open(key, preimage.len());
+        Ok(preimage)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+        let code = self.inner.bytecode_by_hash(code_hash).map_err(|e| {
+            self.metrics.on_fetch_error(code_hash);
+            e
+        })?;
+        self.metrics.on_bytecode_fetch(code_hash, code.len());
+        Ok(code)
+    }
+    fn header_by_hash(&self, hash: B256) -> Result<Bytes> {
+        self.inner.header_by_hash(hash)
+    }
+}
+
+impl<P: TrieHinter, M> TrieHinter for MeteredTrieProvider<P, M> {
+    fn hint_trie_node(&self, hash: B256) -> Result<()> {
+        self.inner.hint_trie_node(hash)
+    }
+
+    fn hint_code(&self, code_hash: B256, address: Address) -> Result<()> {
+        self.inner.hint_code(code_hash, address)
+    }
+
+    fn hint_storage_slots(&self, address: Address, slots: &[B256]) -> Result<()> {
+        self.inner.hint_storage_slots(address, slots)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{MapTrieProvider, Nibbles, TrieNode};
+    use alloc::vec;
+    use alloy_primitives::keccak256;
+
+    #[test]
+    fn test_metered_provider_counts_fetches() {
+        let mut trie = TrieNode::Empty;
+        for i in 0..64u8 {
+            let key = Nibbles::unpack(keccak256([i]));
+            trie.insert(&key, Bytes::from(vec![i; 40]), &MapTrieProvider::new()).unwrap();
+        }
+        let mut provider = MapTrieProvider::new();
+        let root = provider.insert_trie(&trie);
+        let code_hash = provider.insert_bytecode(Bytes::from_static(&[0x60, 0x00]));
+
+        let counters = TrieCounters::new();
+        let metered = MeteredTrieProvider::new(&provider, &counters);
+        let mut opened = TrieNode::Blinded { commitment: root };
+        opened.open(&Nibbles::unpack(keccak256([7])), &metered).unwrap();
+
+        let stats = counters.stats();
+        assert!(stats.nodes_opened >= 2);
+        assert!(stats.node_bytes >= provider.trie_node_preimage(root).unwrap().len());
+
+        metered.bytecode_by_hash(code_hash).unwrap();
+        assert!(metered.bytecode_by_hash(B256::ZERO).is_err());
+        assert!(metered.trie_node_preimage(B256::ZERO).is_err());
+        assert_eq!(
+            counters.reset(),
+            TrieStats { bytecode_fetched: 1, bytecode_bytes: 2, errors: 2, ..stats }
+        );
+        assert_eq!(counters.stats(), TrieStats::default());
+    }
+}
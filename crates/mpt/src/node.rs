@@ -1,10 +1,19 @@
 //! This module contains the [TrieNode] type, which represents a node within a standard Merkle
 //! Patricia Trie.
 
-use alloc::{boxed::Box, vec, vec::Vec};
+use crate::TrieProvider;
+use alloc::{
+    boxed::Box,
+    string::{String, ToString},
+    vec,
+    vec::Vec,
+};
 use alloy_primitives::{keccak256, Bytes, B256};
 use alloy_rlp::{Buf, BufMut, Decodable, Encodable, Header, EMPTY_STRING_CODE};
-use anyhow::{anyhow, Result};
+use alloy_trie::{Nibbles, EMPTY_ROOT_HASH};
+use core::fmt::Display;
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
 
 /// The length of the branch list when RLP encoded
 const BRANCH_LIST_LENGTH: usize = 17;
@@ -12,6 +21,10 @@ const BRANCH_LIST_LENGTH: usize = 17;
 /// The length of a leaf or extension node's RLP encoded list
 const LEAF_OR_EXTENSION_LIST_LENGTH: usize = 2;
 
+/// Nodes whose RLP encoding is at least this many bytes long are referenced by their [keccak256]
+/// commitment in their parent, rather than being embedded in it.
+pub(crate) const MIN_BLINDED_LENGTH: usize = 32;
+
 /// Prefix for even-nibbled extension node paths.
 const PREFIX_EXTENSION_EVEN: u8 = 0;
 
@@ -24,6 +37,72 @@ const PREFIX_LEAF_EVEN: u8 = 2;
 /// Prefix for odd-nibbled leaf node paths.
 const PREFIX_LEAF_ODD: u8 = 3;
 
+/// An error returned by the operations on a [TrieNode].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrieNodeError {
+    /// The RLP encoding of a trie node is malformed.
+    Rlp(alloy_rlp::Error),
+    /// The path of a leaf or extension node is empty.
+    EmptyPath,
+    /// The hex-prefix byte of a leaf or extension path is invalid: its high-order nibble is not a
+    /// leaf or extension flag, or the padding nibble of an even-length path is not zero.
+    InvalidPathPrefix(u8),
+    /// The [TrieProvider] failed to return the preimage of a trie node.
+    Provider(String),
+    /// The preimage of a trie node does not hash to its commitment.
+    PreimageMismatch(B256),
+    /// The preimage of a trie node does not decode to exactly one opened trie node.
+    InvalidPreimage(B256),
+    /// A trie node that should have been unblinded is still blinded.
+    Blinded(B256),
+    /// A path ends at a branch node. Values stored in branch nodes are not supported.
+    BranchValue,
+    /// A branch child index is not a nibble.
+    InvalidBranchIndex(u8),
+    /// A trie node is not in the canonical form of a Merkle Patricia Trie.
+    Malformed {
+        /// The path of the malformed node from the root of the validated trie.
+        path: Nibbles,
+        /// The reason the node is malformed.
+        reason: &'static str,
+    },
+}
+
+impl Display for TrieNodeError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            TrieNodeError::Rlp(e) => write!(f, "Failed to decode trie node: {e}"),
+            TrieNodeError::EmptyPath => write!(f, "Empty leaf or extension path"),
+            TrieNodeError::InvalidPathPrefix(prefix) => {
+                write!(f, "Invalid hex-prefix byte {prefix:#04x} of leaf or extension path")
+            }
+            TrieNodeError::Provider(e) => write!(f, "Failed to fetch trie node: {e}"),
+            TrieNodeError::PreimageMismatch(commitment) => {
+                write!(f, "Trie node preimage does not match commitment {commitment}")
+            }
+            TrieNodeError::InvalidPreimage(commitment) => {
+                write!(f, "Trie node preimage of {commitment} is not a trie node")
+            }
+            TrieNodeError::Blinded(commitment) => {
+                write!(f, "Trie node {commitment} is still blinded")
+            }
+            TrieNodeError::BranchValue => write!(f, "Branch values are not supported"),
+            TrieNodeError::InvalidBranchIndex(index) => {
+                write!(f, "Invalid branch child index {index}")
+            }
+            TrieNodeError::Malformed { path, reason } => {
+                write!(f, "Malformed trie node at {path:?}: {reason}")
+            }
+        }
+    }
+}
+
+impl From<alloy_rlp::Error> for TrieNodeError {
+    fn from(e: alloy_rlp::Error) -> Self {
+        TrieNodeError::Rlp(e)
+    }
+}
+
 /// A [TrieNode] is a node within a standard Ethereum Merkle Patricia Trie.
 ///
 /// The [TrieNode] has several variants:
@@ -35,14 +114,18 @@ const PREFIX_LEAF_ODD: u8 = 3;
 /// - [TrieNode::Branch] represents a node that refers to up to 16 child nodes with the encoding
 ///   `rlp([ v0, ..., v15, value ])`.
 ///
-/// In the Ethereum Merkle Patricia Trie, nodes longer than an encoded 32 byte string (33 total
-/// bytes) are blinded with [keccak256] hashes. When a node is "opened", it is replaced with the
+/// In the Ethereum Merkle Patricia Trie, nodes whose RLP encoding is 32 bytes or longer are
+/// blinded with [keccak256] hashes. When a node is "opened", it is replaced with the
 /// [TrieNode] that is decoded from to the preimage of the hash.
 ///
 /// The [alloy_rlp::Encodable] and [alloy_rlp::Decodable] traits are implemented for [TrieNode],
 /// allowing for RLP encoding and decoding of the types for storage and retrieval. The
-/// implementation of these traits will implicitly blind nodes that are longer than 32 bytes in
+/// implementation of these traits will implicitly blind nodes that are 32 bytes or longer in
 /// length when encoding. When decoding, the implementation will leave blinded nodes in place.
+///
+/// With the `serde` feature, a [TrieNode] can be serialized as is, so a partially opened trie can
+/// be persisted and reloaded without fetching its opened nodes again.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, Eq, PartialEq)]
 pub enum TrieNode {
     /// An empty [TrieNode] is represented as an [EMPTY_STRING_CODE] (0x80).
@@ -54,15 +137,15 @@ pub enum TrieNode {
     },
     /// A leaf node is a 2-item node with the encoding `rlp([encoded_path, value])`
     Leaf {
-        /// The key of the leaf node
-        key: Bytes,
+        /// The remaining key of the leaf node, without its hex-prefix flag
+        key: Nibbles,
         /// The value of the leaf node
         value: Bytes,
     },
     /// An extension node is a 2-item pointer node with the encoding `rlp([encoded_path, key])`
     Extension {
-        /// The path prefix of the extension
-        prefix: Bytes,
+        /// The path prefix of the extension, without its hex-prefix flag
+        prefix: Nibbles,
         /// The pointer to the child node
         node: Box<TrieNode>,
     },
@@ -75,38 +158,57 @@ pub enum TrieNode {
 }
 
 impl TrieNode {
+    /// Creates a new [TrieNode::Leaf] with the given `path` and `value`.
+    pub fn leaf(path: Nibbles, value: Bytes) -> Self {
+        TrieNode::Leaf { key: path, value }
+    }
+
+    /// Creates a new [TrieNode::Extension] with the given `path` pointing to the `child` node.
+    pub fn extension(path: Nibbles, child: TrieNode) -> Self {
+        TrieNode::Extension { prefix: path, node: Box::new(child) }
+    }
+
+    /// Returns the path of a [TrieNode::Leaf] or [TrieNode::Extension]. Returns `None` for all
+    /// other node types.
+    pub fn key_nibbles(&self) -> Option<&Nibbles> {
+        match self {
+            Self::Leaf { key: path, .. } | Self::Extension { prefix: path, .. } => Some(path),
+            _ => None,
+        }
+    }
+
     /// Attempts to convert a `path` and `value` into a [TrieNode], if they correspond to a
     /// [TrieNode::Leaf] or [TrieNode::Extension].
     ///
     /// **Note:** This function assumes that the passed reader has already consumed the RLP header
     /// of the [TrieNode::Leaf] or [TrieNode::Extension] node.
-    pub fn try_decode_leaf_or_extension_payload(buf: &mut &[u8]) -> Result<Self> {
+    pub fn try_decode_leaf_or_extension_payload(buf: &mut &[u8]) -> Result<Self, TrieNodeError> {
         // Decode the path and value of the leaf or extension node.
-        let path = Bytes::decode(buf).map_err(|e| anyhow!("Failed to decode: {e}"))?;
+        let path = Bytes::decode(buf)?;
+        let Some(first) = path.first() else {
+            return Err(TrieNodeError::EmptyPath);
+        };
+        let nibbles = decode_path(&path).ok_or(TrieNodeError::InvalidPathPrefix(*first))?;
 
         // Check the high-order nibble of the path to determine the type of node.
-        match path[0] >> 4 {
+        match first >> 4 {
             PREFIX_EXTENSION_EVEN | PREFIX_EXTENSION_ODD => {
                 // extension node
-                let extension_node_value =
-                    TrieNode::decode(buf).map_err(|e| anyhow!("Failed to decode: {e}"))?;
-                Ok(TrieNode::Extension { prefix: path, node: Box::new(extension_node_value) })
+                let extension_node_value = decode_child(buf)?;
+                Ok(TrieNode::Extension { prefix: nibbles, node: Box::new(extension_node_value) })
             }
             PREFIX_LEAF_EVEN | PREFIX_LEAF_ODD => {
                 // leaf node
-                let value = Bytes::decode(buf).map_err(|e| anyhow!("Failed to decode: {e}"))?;
-                Ok(TrieNode::Leaf { key: path, value })
-            }
-            _ => {
-                anyhow::bail!("Unexpected path identifier in high-order nibble")
+                let value = Bytes::decode(buf)?;
+                Ok(TrieNode::Leaf { key: nibbles, value })
             }
+            _ => Err(TrieNodeError::InvalidPathPrefix(*first)),
         }
     }
 
-    /// Blinds the [TrieNode] if it is longer than an encoded [B256] string in length, and returns
-    /// the mutated node.
+    /// Blinds the [TrieNode] if its encoding is 32 bytes or longer, and returns the mutated node.
     pub fn blind(self) -> Self {
-        if self.length() > B256::ZERO.length() {
+        if self.length() >= MIN_BLINDED_LENGTH {
             let mut rlp_buf = Vec::with_capacity(self.length());
             self.encode(&mut rlp_buf);
             TrieNode::Blinded { commitment: keccak256(rlp_buf) }
@@ -114,6 +216,536 @@ impl TrieNode {
             self
         }
     }
+
+    /// Returns the [keccak256] commitment of the trie rooted at this node. Opened children that are
+    /// 32 bytes or longer are blinded when the node is encoded, so the root
+    /// reflects any values inserted into or deleted from the opened trie.
+    ///
+    /// Unlike [TrieNode::blind], the root is always hashed, even if the node is short enough to be
+    /// embedded in a parent.
+    pub fn root(&self) -> B256 {
+        match self {
+            Self::Blinded { commitment } => *commitment,
+            _ => {
+                let mut rlp_buf = Vec::with_capacity(self.length());
+                self.encode(&mut rlp_buf);
+                keccak256(rlp_buf)
+            }
+        }
+    }
+
+    /// Returns the number of opened nodes in the trie rooted at this node. Blinded subtrees and
+    /// empty nodes are not counted, so this measures how much of a sparse trie has been unblinded.
+    pub fn opened_nodes(&self) -> usize {
+        match self {
+            Self::Empty | Self::Blinded { .. } => 0,
+            Self::Leaf { .. } => 1,
+            Self::Extension { node, .. } => 1 + node.opened_nodes(),
+            Self::Branch { stack } => 1 + stack.iter().map(Self::opened_nodes).sum::<usize>(),
+        }
+    }
+
+    /// Replaces a [TrieNode::Blinded] node with the [TrieNode] decoded from the preimage of its
+    /// commitment, fetched through the [TrieProvider]. All other nodes are left untouched.
+    ///
+    /// The preimage is verified against the commitment before it is decoded.
+    pub fn unblind<P: TrieProvider>(&mut self, provider: &P) -> Result<(), TrieNodeError> {
+        if let Self::Blinded { commitment } = self {
+            *self = fetch_node(*commitment, provider)?;
+        }
+        Ok(())
+    }
+
+    /// Walks the trie rooted at this node down the given nibble `path`, and returns a mutable
+    /// reference to the value stored at the end of it. Returns `None` if the trie does not contain
+    /// the path.
+    ///
+    /// Blinded nodes along the path are unblinded through the [TrieProvider] and kept open in
+    /// place, so later lookups that share a prefix with `path` do not fetch them again.
+    ///
+    /// **Note:** Values stored in branch nodes are not supported, since the tries this crate
+    /// walks are keyed by fixed-length paths. A path that ends at a branch yields `None`.
+    pub fn open<'a, P: TrieProvider>(
+        &'a mut self,
+        path: &Nibbles,
+        provider: &P,
+    ) -> Result<Option<&'a mut Bytes>, TrieNodeError> {
+        self.open_inner(path.as_slice(), provider)
+    }
+
+    /// Walks the trie rooted at this node down the given nibble `path`, and returns the value
+    /// stored at the end of it. Returns `None` if the trie does not contain the path.
+    ///
+    /// Unlike [TrieNode::open], blinded nodes along the path are fetched through the
+    /// [TrieProvider] and discarded after the lookup, leaving this node untouched.
+    pub fn get<P: TrieProvider>(
+        &self,
+        path: &Nibbles,
+        provider: &P,
+    ) -> Result<Option<Bytes>, TrieNodeError> {
+        self.get_inner(path.as_slice(), provider)
+    }
+
+    /// Inserts the `value` at the given nibble `path` into the trie rooted at this node, replacing
+    /// the value if the path is already present.
+    ///
+    /// Blinded nodes along the path are unblinded through the [TrieProvider], and the nodes around
+    /// the insertion point are restructured as needed: leaves and extensions that share a partial
+    /// prefix with `path` are split into a branch behind a shorter extension.
+    ///
+    /// Only the nodes on `path` are unblinded. Subtrees off the path stay blinded, and contribute
+    /// their commitments to [TrieNode::root] as is, so mutating a sparse trie never fetches more
+    /// than the nodes of the modified path.
+    ///
+    /// **Note:** Values stored in branch nodes are not supported, so inserting a path that is a
+    /// strict prefix of an existing key, or vice versa, returns an error.
+    pub fn insert<P: TrieProvider>(
+        &mut self,
+        path: &Nibbles,
+        value: Bytes,
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        self.insert_inner(path.as_slice(), value, provider)
+    }
+
+    /// Inserts a batch of `entries` into the trie rooted at this node. This has the same effect
+    /// as inserting the entries one by one with [TrieNode::insert], with later entries
+    /// overwriting earlier ones at the same path.
+    ///
+    /// The entries are sorted by path first, so that each subtree is built or updated with all of
+    /// its entries at once: every node on the touched paths is unblinded and restructured a
+    /// single time, rather than once per entry. This makes batch insertion considerably cheaper
+    /// than independent insertions when many entries share their upper nodes, such as the
+    /// touched storage slots of a block.
+    pub fn insert_batch<P: TrieProvider>(
+        &mut self,
+        entries: impl IntoIterator<Item = (Nibbles, Bytes)>,
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        // Sort the entries from last to first, so that deduplicating keeps the latest value.
+        let mut entries = entries.into_iter().collect::<Vec<_>>();
+        entries.reverse();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+        entries.dedup_by(|(a, _), (b, _)| a == b);
+        self.insert_batch_inner(&entries, 0, provider)
+    }
+
+    /// Recursive helper for [TrieNode::insert_batch]. The `entries` are sorted and unique, and
+    /// their paths are relative to this node from the nibble at `depth` on.
+    fn insert_batch_inner<P: TrieProvider>(
+        &mut self,
+        entries: &[(Nibbles, Bytes)],
+        depth: usize,
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        let (first, last) = match entries {
+            [] => return Ok(()),
+            [(path, value)] => return self.insert_inner(&path[depth..], value.clone(), provider),
+            [(first, _), .., (last, _)] => (&first[depth..], &last[depth..]),
+        };
+
+        self.unblind(provider)?;
+        match self {
+            Self::Empty => {
+                // Lay out the branch at the first nibble where the entries diverge, and fill it in
+                // below. As the entries are sorted, the first and last ones diverge first.
+                let shared = common_prefix_length(first, last);
+                if shared == first.len() {
+                    return Err(TrieNodeError::BranchValue);
+                }
+                let stack = vec![Self::Empty; BRANCH_LIST_LENGTH];
+                *self = with_shared_prefix(&first[..shared], Self::Branch { stack });
+                self.insert_batch_inner(entries, depth, provider)
+            }
+            Self::Blinded { commitment } => Err(TrieNodeError::Blinded(*commitment)),
+            Self::Leaf { key, value } => {
+                // Rebuild the subtree from the existing leaf along with the entries, unless one of
+                // them overwrites it.
+                let path =
+                    Nibbles::from_nibbles_unchecked([&entries[0].0[..depth], &key[..]].concat());
+                let Err(position) = entries.binary_search_by(|(key, _)| key.cmp(&path)) else {
+                    *self = Self::Empty;
+                    return self.insert_batch_inner(entries, depth, provider);
+                };
+                let mut merged = Vec::with_capacity(entries.len() + 1);
+                merged.extend_from_slice(&entries[..position]);
+                merged.push((path, core::mem::take(value)));
+                merged.extend_from_slice(&entries[position..]);
+                *self = Self::Empty;
+                self.insert_batch_inner(&merged, depth, provider)
+            }
+            Self::Extension { prefix, node } => {
+                // Split the extension where the first entry diverging from it does, then descend
+                // into the restructured node.
+                let shared =
+                    common_prefix_length(prefix, first).min(common_prefix_length(prefix, last));
+                if shared == prefix.len() {
+                    return node.insert_batch_inner(entries, depth + shared, provider);
+                }
+                let child = core::mem::replace(node.as_mut(), Self::Empty);
+                let mut stack = vec![Self::Empty; BRANCH_LIST_LENGTH];
+                stack[prefix[shared] as usize] = if prefix.len() == shared + 1 {
+                    child
+                } else {
+                    Self::extension(Nibbles::from_nibbles_unchecked(&prefix[shared + 1..]), child)
+                };
+                *self = with_shared_prefix(&prefix[..shared], Self::Branch { stack });
+                self.insert_batch_inner(entries, depth, provider)
+            }
+            Self::Branch { stack } => {
+                // The entries of each child are contiguous, as they are sorted.
+                let mut rest = entries;
+                while let Some((path, _)) = rest.first() {
+                    let Some(&index) = path.get(depth) else {
+                        return Err(TrieNodeError::BranchValue);
+                    };
+                    let len =
+                        rest.iter().take_while(|(path, _)| path.get(depth) == Some(&index)).count();
+                    let (group, tail) = rest.split_at(len);
+                    branch_child_mut(stack, index)?.insert_batch_inner(
+                        group,
+                        depth + 1,
+                        provider,
+                    )?;
+                    rest = tail;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Returns the RLP encoded nodes along the given nibble `path`, ordered from this node down.
+    /// They form a Merkle proof of the value at `path`, or of its absence, against
+    /// [TrieNode::root], in the format of the `accountProof` and `storageProof` fields of
+    /// EIP-1186.
+    ///
+    /// Blinded nodes along the path are unblinded through the [TrieProvider] and kept open in
+    /// place. Nodes that are embedded in their parent are covered by the parent's encoding, and
+    /// are not listed separately. The proof of an empty trie is empty.
+    pub fn proof<P: TrieProvider>(
+        &mut self,
+        path: &Nibbles,
+        provider: &P,
+    ) -> Result<Vec<Bytes>, TrieNodeError> {
+        let mut proof = Vec::new();
+        self.proof_inner(path.as_slice(), provider, true, &mut proof)?;
+        Ok(proof)
+    }
+
+    /// Recursive helper for [TrieNode::proof].
+    fn proof_inner<P: TrieProvider>(
+        &mut self,
+        path: &[u8],
+        provider: &P,
+        is_root: bool,
+        proof: &mut Vec<Bytes>,
+    ) -> Result<(), TrieNodeError> {
+        self.unblind(provider)?;
+        if matches!(self, Self::Empty) {
+            return Ok(());
+        }
+        if is_root || self.length() >= MIN_BLINDED_LENGTH {
+            let mut rlp_buf = Vec::with_capacity(self.length());
+            self.encode(&mut rlp_buf);
+            proof.push(rlp_buf.into());
+        }
+
+        match self {
+            Self::Empty | Self::Leaf { .. } => Ok(()),
+            Self::Blinded { commitment } => Err(TrieNodeError::Blinded(*commitment)),
+            Self::Extension { prefix, node } => match path.strip_prefix(prefix.as_slice()) {
+                Some(rest) => node.proof_inner(rest, provider, false, proof),
+                None => Ok(()),
+            },
+            Self::Branch { stack } => match path.split_first() {
+                Some((&index, rest)) => {
+                    branch_child_mut(stack, index)?.proof_inner(rest, provider, false, proof)
+                }
+                None => Ok(()),
+            },
+        }
+    }
+
+    /// Recursive helper for [TrieNode::insert].
+    fn insert_inner<P: TrieProvider>(
+        &mut self,
+        path: &[u8],
+        value: Bytes,
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        self.unblind(provider)?;
+        match self {
+            Self::Empty => {
+                *self = Self::leaf(Nibbles::from_nibbles_unchecked(path), value);
+                Ok(())
+            }
+            Self::Blinded { commitment } => Err(TrieNodeError::Blinded(*commitment)),
+            Self::Leaf { key, value: leaf_value } => {
+                if key.as_slice() == path {
+                    *leaf_value = value;
+                    return Ok(());
+                }
+
+                // Split the leaf into a branch holding both the existing and the new leaf.
+                let shared = common_prefix_length(&key, path);
+                let (Some(&old_index), Some(&new_index)) = (key.get(shared), path.get(shared))
+                else {
+                    return Err(TrieNodeError::BranchValue);
+                };
+                let mut stack = vec![Self::Empty; BRANCH_LIST_LENGTH];
+                stack[old_index as usize] = Self::leaf(
+                    Nibbles::from_nibbles_unchecked(&key[shared + 1..]),
+                    core::mem::take(leaf_value),
+                );
+                stack[new_index as usize] =
+                    Self::leaf(Nibbles::from_nibbles_unchecked(&path[shared + 1..]), value);
+                *self = with_shared_prefix(&path[..shared], Self::Branch { stack });
+                Ok(())
+            }
+            Self::Extension { prefix, node } => {
+                if let Some(rest) = path.strip_prefix(prefix.as_slice()) {
+                    return node.insert_inner(rest, value, provider);
+                }
+
+                // Split the extension at the first diverging nibble. The remainder of the prefix
+                // points at the original child, or is dropped if it would be empty.
+                let shared = common_prefix_length(&prefix, path);
+                let Some(&new_index) = path.get(shared) else {
+                    return Err(TrieNodeError::BranchValue);
+                };
+                let old_index = prefix[shared];
+                let child = core::mem::replace(node.as_mut(), Self::Empty);
+                let mut stack = vec![Self::Empty; BRANCH_LIST_LENGTH];
+                stack[old_index as usize] = if prefix.len() == shared + 1 {
+                    child
+                } else {
+                    Self::extension(Nibbles::from_nibbles_unchecked(&prefix[shared + 1..]), child)
+                };
+                stack[new_index as usize] =
+                    Self::leaf(Nibbles::from_nibbles_unchecked(&path[shared + 1..]), value);
+                *self = with_shared_prefix(&path[..shared], Self::Branch { stack });
+                Ok(())
+            }
+            Self::Branch { stack } => {
+                let Some((&index, rest)) = path.split_first() else {
+                    return Err(TrieNodeError::BranchValue);
+                };
+                branch_child_mut(stack, index)?.insert_inner(rest, value, provider)
+            }
+        }
+    }
+
+    /// Deletes the value at the given nibble `path` from the trie rooted at this node. Deleting a
+    /// path that is not present in the trie leaves it unchanged.
+    ///
+    /// Blinded nodes along the path are unblinded through the [TrieProvider]. Branches that are
+    /// left with a single child are collapsed into it, and extensions are merged with the leaf or
+    /// extension below them, so the trie stays in its canonical form.
+    ///
+    /// Like [TrieNode::insert], subtrees off the path stay blinded. The only exception is the last
+    /// remaining child of a collapsed branch, which has to be unblinded to merge its path.
+    pub fn delete<P: TrieProvider>(
+        &mut self,
+        path: &Nibbles,
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        self.delete_inner(path.as_slice(), provider)
+    }
+
+    /// Validates the trie rooted at this node, for diagnosing state root mismatches. Every blinded
+    /// node is fetched through the [TrieProvider], and its preimage is checked against its
+    /// commitment and validated in turn, so the whole trie must be available.
+    ///
+    /// Besides the commitments, the trie must be in canonical form: paths only hold nibbles,
+    /// extensions have a non-empty prefix and point at a branch, branches have at least two
+    /// children and no value, and leaves hold a non-empty value. Violations are reported as
+    /// [TrieNodeError::Malformed], along with the path of the offending node.
+    pub fn validate<P: TrieProvider>(&self, provider: &P) -> Result<(), TrieNodeError> {
+        self.validate_inner(&mut Vec::new(), provider)
+    }
+
+    /// Recursive helper for [TrieNode::validate]. `path` holds the nibbles leading to this node.
+    fn validate_inner<P: TrieProvider>(
+        &self,
+        path: &mut Vec<u8>,
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        let malformed = |path: &[u8], reason| TrieNodeError::Malformed {
+            path: Nibbles::from_nibbles_unchecked(path),
+            reason,
+        };
+        match self {
+            Self::Empty => Ok(()),
+            Self::Blinded { commitment } => {
+                fetch_node(*commitment, provider)?.validate_inner(path, provider)
+            }
+            Self::Leaf { key, value } => {
+                if key.iter().any(|nibble| *nibble > 0x0F) {
+                    return Err(malformed(path, "leaf path holds a value larger than a nibble"));
+                }
+                if value.is_empty() {
+                    return Err(malformed(path, "leaf holds an empty value"));
+                }
+                Ok(())
+            }
+            Self::Extension { prefix, node } => {
+                if prefix.is_empty() {
+                    return Err(malformed(path, "extension has an empty prefix"));
+                }
+                if prefix.iter().any(|nibble| *nibble > 0x0F) {
+                    return Err(malformed(
+                        path,
+                        "extension prefix holds a value larger than a nibble",
+                    ));
+                }
+
+                let fetched;
+                let child = match node.as_ref() {
+                    Self::Blinded { commitment } => {
+                        fetched = fetch_node(*commitment, provider)?;
+                        &fetched
+                    }
+                    child => child,
+                };
+                if !matches!(child, Self::Branch { .. }) {
+                    return Err(malformed(path, "extension does not point at a branch"));
+                }
+
+                let len = path.len();
+                path.extend_from_slice(prefix);
+                child.validate_inner(path, provider)?;
+                path.truncate(len);
+                Ok(())
+            }
+            Self::Branch { stack } => {
+                if stack.len() != BRANCH_LIST_LENGTH {
+                    return Err(malformed(path, "branch does not have 17 elements"));
+                }
+                if stack[BRANCH_LIST_LENGTH - 1] != Self::Empty {
+                    return Err(TrieNodeError::BranchValue);
+                }
+                if stack.iter().filter(|child| !matches!(child, Self::Empty)).count() < 2 {
+                    return Err(malformed(path, "branch has fewer than two children"));
+                }
+
+                for (index, child) in stack[..BRANCH_LIST_LENGTH - 1].iter().enumerate() {
+                    path.push(index as u8);
+                    child.validate_inner(path, provider)?;
+                    path.pop();
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Recursive helper for [TrieNode::delete].
+    fn delete_inner<P: TrieProvider>(
+        &mut self,
+        path: &[u8],
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        self.unblind(provider)?;
+        match self {
+            Self::Empty => Ok(()),
+            Self::Blinded { commitment } => Err(TrieNodeError::Blinded(*commitment)),
+            Self::Leaf { key, .. } => {
+                if key.as_slice() == path {
+                    *self = Self::Empty;
+                }
+                Ok(())
+            }
+            Self::Extension { prefix, node } => {
+                let Some(rest) = path.strip_prefix(prefix.as_slice()) else {
+                    return Ok(());
+                };
+                node.delete_inner(rest, provider)?;
+
+                // The child may have collapsed into a leaf or extension, which absorbs the prefix.
+                let child = core::mem::replace(node.as_mut(), Self::Empty);
+                *self = prepend_path(&prefix, child)?;
+                Ok(())
+            }
+            Self::Branch { stack } => {
+                let Some((&index, rest)) = path.split_first() else {
+                    return Ok(());
+                };
+                branch_child_mut(stack, index)?.delete_inner(rest, provider)?;
+
+                // Collapse the branch if it is left with fewer than two children.
+                let mut children = stack
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, child)| !matches!(child, Self::Empty))
+                    .map(|(index, _)| index);
+                match (children.next(), children.next()) {
+                    (Some(_), Some(_)) => {}
+                    (None, _) => *self = Self::Empty,
+                    (Some(index), None) => {
+                        if index >= BRANCH_LIST_LENGTH - 1 {
+                            return Err(TrieNodeError::BranchValue);
+                        }
+                        let mut child = core::mem::replace(&mut stack[index], Self::Empty);
+                        child.unblind(provider)?;
+                        *self = prepend_path(&[index as u8], child)?;
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Recursive helper for [TrieNode::open].
+    fn open_inner<'a, P: TrieProvider>(
+        &'a mut self,
+        path: &[u8],
+        provider: &P,
+    ) -> Result<Option<&'a mut Bytes>, TrieNodeError> {
+        self.unblind(provider)?;
+        match self {
+            Self::Empty => Ok(None),
+            Self::Blinded { commitment } => Err(TrieNodeError::Blinded(*commitment)),
+            Self::Leaf { key, value } => Ok((key.as_slice() == path).then_some(value)),
+            Self::Extension { prefix, node } => match path.strip_prefix(prefix.as_slice()) {
+                Some(rest) => node.open_inner(rest, provider),
+                None => Ok(None),
+            },
+            Self::Branch { stack } => {
+                let Some((&index, rest)) = path.split_first() else {
+                    return Ok(None);
+                };
+                branch_child_mut(stack, index)?.open_inner(rest, provider)
+            }
+        }
+    }
+
+    /// Recursive helper for [TrieNode::get].
+    fn get_inner<P: TrieProvider>(
+        &self,
+        path: &[u8],
+        provider: &P,
+    ) -> Result<Option<Bytes>, TrieNodeError> {
+        match self {
+            Self::Empty => Ok(None),
+            Self::Blinded { commitment } => {
+                fetch_node(*commitment, provider)?.get_inner(path, provider)
+            }
+            Self::Leaf { key, value } => Ok((key.as_slice() == path).then(|| value.clone())),
+            Self::Extension { prefix, node } => match path.strip_prefix(prefix.as_slice()) {
+                Some(rest) => node.get_inner(rest, provider),
+                None => Ok(None),
+            },
+            Self::Branch { stack } => {
+                let Some((&index, rest)) = path.split_first() else {
+                    return Ok(None);
+                };
+                stack
+                    .get(..BRANCH_LIST_LENGTH - 1)
+                    .and_then(|children| children.get(index as usize))
+                    .ok_or(TrieNodeError::InvalidBranchIndex(index))?
+                    .get_inner(rest, provider)
+            }
+        }
+    }
 }
 
 impl Encodable for TrieNode {
@@ -123,22 +755,26 @@ impl Encodable for TrieNode {
             Self::Blinded { commitment } => commitment.encode(out),
             Self::Leaf { key, value } => {
                 // Encode the leaf node's header and key-value pair.
-                let leaf_list = vec![key, value];
-                leaf_list.encode(out);
+                let key = encode_path(key, true);
+                Header { list: true, payload_length: key.length() + value.length() }.encode(out);
+                key.encode(out);
+                value.encode(out);
             }
             Self::Extension { prefix, node } => {
                 // Encode the extension node's header, prefix, and pointer node.
-                Header { list: true, payload_length: prefix.length() + node.length() }.encode(out);
+                let prefix = encode_path(prefix, false);
+                Header {
+                    list: true,
+                    payload_length: prefix.length() + blinded_length(node.as_ref()),
+                }
+                .encode(out);
                 prefix.encode(out);
                 encode_blinded(node.as_ref(), out);
             }
             Self::Branch { stack } => {
-                // In branch nodes, if an element is longer than 32 bytes in length, it is blinded.
-                // Assuming we have an open trie node, we must re-hash the elements
-                // that are longer than 32 bytes in length.
-                let blinded_nodes =
-                    stack.iter().cloned().map(|node| node.blind()).collect::<Vec<TrieNode>>();
-                blinded_nodes.encode(out);
+                // In branch nodes, if an element is 32 bytes or longer, it is blinded. Assuming we
+                // have an open trie node, we must re-hash the elements that are 32 bytes or longer.
+                blind_children(stack).encode(out);
             }
         }
     }
@@ -148,21 +784,19 @@ impl Encodable for TrieNode {
             Self::Empty => 1,
             Self::Blinded { commitment } => commitment.length(),
             Self::Leaf { key, value } => {
-                let leaf_list = vec![key, value];
-                leaf_list.length()
+                let payload_length = encode_path(key, true).length() + value.length();
+                Header { list: true, payload_length }.length() + payload_length
             }
             Self::Extension { prefix, node } => {
-                let prefix_length = prefix.length();
+                let prefix_length = encode_path(prefix, false).length();
                 let node_length = blinded_length(node.as_ref());
                 Header { list: true, payload_length: prefix_length + node_length }.length() +
                     prefix_length +
                     node_length
             }
             Self::Branch { stack } => {
-                // In branch nodes, if an element is longer than an encoded 32 byte string, it is
-                // blinded. Assuming we have an open trie node, we must re-hash the
-                // elements that are longer than an encoded 32 byte string
-                // in length.
+                // In branch nodes, if an element is 32 bytes or longer, it is blinded. Assuming we
+                // have an open trie node, we must re-hash the elements that are 32 bytes or longer.
                 let inner_length = stack.iter().fold(0, |mut acc, node| {
                     acc += blinded_length(node);
                     acc
@@ -176,28 +810,42 @@ impl Encodable for TrieNode {
 
 impl Decodable for TrieNode {
     /// Attempts to decode the [TrieNode].
+    ///
+    /// Trie node preimages are untrusted, so any malformed input is rejected with an error: list
+    /// elements must not overrun the list, the list must be consumed exactly, and nodes embedded
+    /// in a parent must be shorter than 32 bytes.
     fn decode(buf: &mut &[u8]) -> alloy_rlp::Result<Self> {
         // Peek at the header to determine the type of Trie node we're currently decoding.
-        let header = Header::decode(&mut (**buf).as_ref())?;
+        let mut payload = *buf;
+        let header = Header::decode(&mut payload)?;
 
         if header.list {
-            // Peek at the RLP stream to determine the number of elements in the list.
+            // Peek at the RLP stream to determine the number of elements in the list. This also
+            // checks that the list and its elements fit in the buffer.
             let list_length = rlp_list_element_length(&mut (**buf).as_ref())?;
+            let rest = &payload[header.payload_length..];
+            payload = &payload[..header.payload_length];
 
-            match list_length {
+            let node = match list_length {
                 BRANCH_LIST_LENGTH => {
-                    let list = Vec::<TrieNode>::decode(buf)?;
-                    Ok(Self::Branch { stack: list })
+                    let stack = (0..BRANCH_LIST_LENGTH)
+                        .map(|_| decode_child(&mut payload))
+                        .collect::<alloy_rlp::Result<Vec<_>>>()?;
+                    Self::Branch { stack }
                 }
                 LEAF_OR_EXTENSION_LIST_LENGTH => {
-                    // Advance the buffer to the start of the list payload.
-                    buf.advance(header.length());
                     // Decode the leaf or extension node's raw payload.
-                    Self::try_decode_leaf_or_extension_payload(buf)
-                        .map_err(|_| alloy_rlp::Error::UnexpectedList)
+                    Self::try_decode_leaf_or_extension_payload(&mut payload)
+                        .map_err(leaf_or_extension_rlp_error)?
                 }
-                _ => Err(alloy_rlp::Error::UnexpectedLength),
+                _ => return Err(alloy_rlp::Error::UnexpectedLength),
+            };
+            if !payload.is_empty() {
+                return Err(alloy_rlp::Error::UnexpectedLength);
             }
+
+            *buf = rest;
+            Ok(node)
         } else {
             match header.payload_length {
                 0 => {
@@ -217,20 +865,63 @@ impl Decodable for TrieNode {
     }
 }
 
-/// Returns the encoded length of an [Encodable] value, blinding it if it is longer than an encoded
-/// [B256] string in length.
+/// Hex-prefix encodes the given [Nibbles] path. The high nibble of the first byte carries the
+/// leaf flag and the parity of the path, and odd-length paths store their first nibble in the low
+/// nibble of the first byte.
+fn encode_path(path: &Nibbles, is_leaf: bool) -> Bytes {
+    let odd = path.len() % 2 == 1;
+    let flag = match (is_leaf, odd) {
+        (false, false) => PREFIX_EXTENSION_EVEN,
+        (false, true) => PREFIX_EXTENSION_ODD,
+        (true, false) => PREFIX_LEAF_EVEN,
+        (true, true) => PREFIX_LEAF_ODD,
+    };
+
+    let mut encoded = Vec::with_capacity(path.len() / 2 + 1);
+    let mut nibbles = path.iter();
+    if odd {
+        // SAFETY: The path is odd in length, so it has at least one nibble.
+        encoded.push((flag << 4) | nibbles.next().unwrap());
+    } else {
+        encoded.push(flag << 4);
+    }
+    while let (Some(hi), Some(lo)) = (nibbles.next(), nibbles.next()) {
+        encoded.push((hi << 4) | lo);
+    }
+    encoded.into()
+}
+
+/// Decodes a hex-prefix encoded path into [Nibbles], stripping the flag. Returns `None` if the
+/// path is empty, the flag is invalid, or the padding nibble of an even-length path is not zero,
+/// so that only the canonical encoding of a path decodes.
+fn decode_path(path: &[u8]) -> Option<Nibbles> {
+    let (first, rest) = path.split_first()?;
+    let mut nibbles = Vec::with_capacity(rest.len() * 2 + 1);
+    match first >> 4 {
+        PREFIX_EXTENSION_EVEN | PREFIX_LEAF_EVEN if first & 0x0F == 0 => {}
+        PREFIX_EXTENSION_ODD | PREFIX_LEAF_ODD => nibbles.push(first & 0x0F),
+        _ => return None,
+    }
+    for byte in rest {
+        nibbles.push(byte >> 4);
+        nibbles.push(byte & 0x0F);
+    }
+    Some(Nibbles::from_nibbles_unchecked(nibbles))
+}
+
+/// Returns the encoded length of an [Encodable] value, blinding it if it is 32 bytes or longer.
 fn blinded_length<T: Encodable>(value: T) -> usize {
-    if value.length() > B256::ZERO.length() {
+    if value.length() >= MIN_BLINDED_LENGTH {
         B256::ZERO.length()
     } else {
         value.length()
     }
 }
 
-/// Encodes a value into an RLP stream, blidning it with a [keccak256] commitment if it is longer
-/// than an encoded [B256] string in length.
+/// Encodes a value into an RLP stream, blinding it with a [keccak256] commitment if it is 32 bytes
+/// or longer.
 fn encode_blinded<T: Encodable>(value: T, out: &mut dyn BufMut) {
-    if value.length() > B256::ZERO.length() {
+    if value.length() >= MIN_BLINDED_LENGTH {
         let mut rlp_buf = Vec::with_capacity(value.length());
         value.encode(&mut rlp_buf);
         TrieNode::Blinded { commitment: keccak256(rlp_buf) }.encode(out);
@@ -239,19 +930,136 @@ fn encode_blinded<T: Encodable>(value: T, out: &mut dyn BufMut) {
     }
 }
 
+/// Blinds the children of a branch node. With the `rayon` feature, the children are hashed in
+/// parallel.
+fn blind_children(stack: &[TrieNode]) -> Vec<TrieNode> {
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+        stack.par_iter().cloned().map(TrieNode::blind).collect()
+    }
+    #[cfg(not(feature = "rayon"))]
+    {
+        stack.iter().cloned().map(TrieNode::blind).collect()
+    }
+}
+
+/// Decodes the child of a branch or extension node. Children that are not blinded are embedded in
+/// their parent, which only happens for nodes that are shorter than 32 bytes. Rejecting longer
+/// embedded nodes also bounds the nesting depth of untrusted input.
+fn decode_child(buf: &mut &[u8]) -> alloy_rlp::Result<TrieNode> {
+    let header = Header::decode(&mut (**buf).as_ref())?;
+    if header.list && header.length().saturating_add(header.payload_length) >= MIN_BLINDED_LENGTH {
+        return Err(alloy_rlp::Error::Custom("embedded trie node is too long"));
+    }
+    TrieNode::decode(buf)
+}
+
+/// Converts an error decoding the payload of a leaf or extension node into the RLP error that
+/// describes it, so that [TrieNode::decode] keeps the reason decoding failed.
+fn leaf_or_extension_rlp_error(e: TrieNodeError) -> alloy_rlp::Error {
+    match e {
+        TrieNodeError::Rlp(e) => e,
+        TrieNodeError::EmptyPath => alloy_rlp::Error::Custom("empty leaf or extension path"),
+        TrieNodeError::InvalidPathPrefix(_) => alloy_rlp::Error::Custom("invalid hex-prefix path"),
+        _ => alloy_rlp::Error::Custom("invalid leaf or extension node"),
+    }
+}
+
+/// Fetches the preimage of a blinded [TrieNode] through the [TrieProvider], verifies it against the
+/// `commitment`, and decodes it. The empty trie root is resolved without fetching its preimage.
+pub(crate) fn fetch_node<P: TrieProvider>(
+    commitment: B256,
+    provider: &P,
+) -> Result<TrieNode, TrieNodeError> {
+    if commitment == EMPTY_ROOT_HASH {
+        return Ok(TrieNode::Empty);
+    }
+
+    let preimage = fetch_preimage(commitment, provider)?;
+    let mut buf = preimage.as_ref();
+    let node = TrieNode::decode(&mut buf)?;
+    if !buf.is_empty() || matches!(node, TrieNode::Blinded { .. }) {
+        return Err(TrieNodeError::InvalidPreimage(commitment));
+    }
+    Ok(node)
+}
+
+/// Fetches the preimage of a `commitment` through the [TrieProvider], and verifies it against the
+/// `commitment`.
+pub(crate) fn fetch_preimage<P: TrieProvider>(
+    commitment: B256,
+    provider: &P,
+) -> Result<Bytes, TrieNodeError> {
+    let preimage = provider
+        .trie_node_preimage(commitment)
+        .map_err(|e| TrieNodeError::Provider(e.to_string()))?;
+    if keccak256(&preimage) != commitment {
+        return Err(TrieNodeError::PreimageMismatch(commitment));
+    }
+    Ok(preimage)
+}
+
+/// Returns a mutable reference to the child of a branch node's `stack` at the nibble `index`.
+fn branch_child_mut(stack: &mut [TrieNode], index: u8) -> Result<&mut TrieNode, TrieNodeError> {
+    stack
+        .get_mut(..BRANCH_LIST_LENGTH - 1)
+        .and_then(|children| children.get_mut(index as usize))
+        .ok_or(TrieNodeError::InvalidBranchIndex(index))
+}
+
+/// Returns the number of leading nibbles that `a` and `b` have in common.
+pub(crate) fn common_prefix_length(a: &[u8], b: &[u8]) -> usize {
+    a.iter().zip(b).take_while(|(a, b)| a == b).count()
+}
+
+/// Places the `branch` behind an extension over the `shared` nibbles, if there are any.
+fn with_shared_prefix(shared: &[u8], branch: TrieNode) -> TrieNode {
+    if shared.is_empty() {
+        branch
+    } else {
+        TrieNode::extension(Nibbles::from_nibbles_unchecked(shared), branch)
+    }
+}
+
+/// Prepends the `prefix` nibbles to the path of an opened `node`, merging it into the extension or
+/// branch slot above it that is being collapsed.
+fn prepend_path(prefix: &[u8], node: TrieNode) -> Result<TrieNode, TrieNodeError> {
+    let join = |path: &[u8]| Nibbles::from_nibbles_unchecked([prefix, path].concat());
+    match node {
+        TrieNode::Empty => Ok(TrieNode::Empty),
+        TrieNode::Blinded { commitment } => Err(TrieNodeError::Blinded(commitment)),
+        TrieNode::Leaf { key, value } => Ok(TrieNode::leaf(join(&key), value)),
+        TrieNode::Extension { prefix: extension, node } => {
+            Ok(TrieNode::extension(join(&extension), *node))
+        }
+        TrieNode::Branch { .. } => Ok(with_shared_prefix(prefix, node)),
+    }
+}
+
 /// Walks through a RLP list's elements and returns the total number of elements in the list.
-/// Returns [alloy_rlp::Error::UnexpectedString] if the RLP stream is not a list.
+/// Returns [alloy_rlp::Error::UnexpectedString] if the RLP stream is not a list, and
+/// [alloy_rlp::Error::InputTooShort] if the list overruns the buffer or an element overruns the
+/// list.
 fn rlp_list_element_length(buf: &mut &[u8]) -> alloy_rlp::Result<usize> {
     let header = Header::decode(buf)?;
     if !header.list {
         return Err(alloy_rlp::Error::UnexpectedString);
     }
-    let len_after_consume = buf.len() - header.payload_length;
+    if header.payload_length > buf.len() {
+        return Err(alloy_rlp::Error::InputTooShort);
+    }
+    let mut payload = &buf[..header.payload_length];
+    buf.advance(header.payload_length);
 
     let mut list_element_length = 0;
-    while buf.len() > len_after_consume {
-        let header = Header::decode(buf)?;
-        buf.advance(header.payload_length);
+    while !payload.is_empty() {
+        // Every element consumes at least one byte, so the walk terminates.
+        let header = Header::decode(&mut payload)?;
+        if header.payload_length > payload.len() {
+            return Err(alloy_rlp::Error::InputTooShort);
+        }
+        payload.advance(header.payload_length);
         list_element_length += 1;
     }
     Ok(list_element_length)
@@ -260,8 +1068,19 @@ fn rlp_list_element_length(buf: &mut &[u8]) -> alloy_rlp::Result<usize> {
 #[cfg(test)]
 mod test {
     use super::*;
-    use alloc::vec;
+    use crate::{
+        test_utils::{arb_entries, reference_root, trie_from_entries},
+        verify_proof, ProofError,
+    };
+    use alloc::{collections::BTreeMap, vec};
     use alloy_primitives::{b256, bytes, hex};
+    use alloy_trie::HashBuilder;
+    use anyhow::{anyhow, Result};
+    use core::cell::RefCell;
+    use proptest::{
+        collection::vec,
+        prelude::{any, prop_assert_eq, proptest},
+    };
 
     #[test]
     fn test_decode_branch() {
@@ -280,7 +1099,10 @@ mod test {
                 TrieNode::Empty,
                 TrieNode::Empty,
                 TrieNode::Empty,
-                TrieNode::Leaf { key: bytes!("30"), value: bytes!("8a74657374207468726565") },
+                TrieNode::Leaf {
+                    key: Nibbles::from_nibbles_unchecked([0x0]),
+                    value: bytes!("8a74657374207468726565"),
+                },
                 TrieNode::Empty,
                 TrieNode::Empty,
                 TrieNode::Empty,
@@ -305,8 +1127,12 @@ mod test {
     fn test_encode_decode_extension_open_short() {
         const EXTENSION_RLP: [u8; 19] = hex!("d28300646fcd308b8a74657374207468726565");
 
-        let opened = TrieNode::Leaf { key: bytes!("30"), value: bytes!("8a74657374207468726565") };
-        let expected = TrieNode::Extension { prefix: bytes!("00646f"), node: Box::new(opened) };
+        let opened = TrieNode::Leaf {
+            key: Nibbles::from_nibbles_unchecked([0x0]),
+            value: bytes!("8a74657374207468726565"),
+        };
+        let expected =
+            TrieNode::Extension { prefix: Nibbles::unpack(hex!("646f")), node: Box::new(opened) };
 
         let mut rlp_buf = Vec::with_capacity(expected.length());
         expected.encode(&mut rlp_buf);
@@ -320,23 +1146,747 @@ mod test {
             hex!("e58300646fa0f3fe8b3c5b21d3e52860f1e4a5825a6100bb341069c1e88f4ebf6bd98de0c190");
         let mut rlp_buf = Vec::new();
 
-        let opened = TrieNode::Leaf { key: bytes!("30"), value: bytes!("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF") };
+        let opened = TrieNode::Leaf { key: Nibbles::from_nibbles_unchecked([0x0]), value: bytes!("FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF") };
         opened.encode(&mut rlp_buf);
         let blinded = TrieNode::Blinded { commitment: keccak256(&rlp_buf) };
 
         rlp_buf.clear();
         let opened_extension =
-            TrieNode::Extension { prefix: bytes!("00646f"), node: Box::new(opened) };
+            TrieNode::Extension { prefix: Nibbles::unpack(hex!("646f")), node: Box::new(opened) };
         opened_extension.encode(&mut rlp_buf);
 
-        let expected = TrieNode::Extension { prefix: bytes!("00646f"), node: Box::new(blinded) };
+        let expected =
+            TrieNode::Extension { prefix: Nibbles::unpack(hex!("646f")), node: Box::new(blinded) };
         assert_eq!(expected, TrieNode::decode(&mut EXTENSION_RLP.as_slice()).unwrap());
     }
 
     #[test]
     fn test_decode_leaf() {
         const LEAF_RLP: [u8; 11] = hex!("ca8320646f8576657262FF");
-        let expected = TrieNode::Leaf { key: bytes!("20646f"), value: bytes!("76657262FF") };
+        let expected =
+            TrieNode::Leaf { key: Nibbles::unpack(hex!("646f")), value: bytes!("76657262FF") };
         assert_eq!(expected, TrieNode::decode(&mut LEAF_RLP.as_slice()).unwrap());
     }
+
+    #[test]
+    fn test_leaf_and_extension_constructors() {
+        const EXTENSION_RLP: [u8; 19] = hex!("d28300646fcd308b8a74657374207468726565");
+
+        let leaf = TrieNode::leaf(
+            Nibbles::from_nibbles_unchecked([0x0]),
+            bytes!("8a74657374207468726565"),
+        );
+        let extension = TrieNode::extension(Nibbles::unpack(hex!("646f")), leaf.clone());
+
+        let mut rlp_buf = Vec::with_capacity(extension.length());
+        extension.encode(&mut rlp_buf);
+        assert_eq!(rlp_buf.as_slice(), EXTENSION_RLP.as_slice());
+
+        let decoded = TrieNode::decode(&mut EXTENSION_RLP.as_slice()).unwrap();
+        assert_eq!(decoded, extension);
+        assert_eq!(decoded.key_nibbles().unwrap(), &Nibbles::unpack(hex!("646f")));
+        assert_eq!(leaf.key_nibbles().unwrap(), &Nibbles::from_nibbles_unchecked([0x0]));
+        assert_eq!(
+            TrieNode::leaf(Nibbles::default(), bytes!("01")).key_nibbles().unwrap().len(),
+            0
+        );
+    }
+
+    #[test]
+    fn test_decode_odd_leaf() {
+        // rlp([0x3f1c, 0x01]): a leaf with the odd path [0xf, 0x1, 0xc].
+        let leaf = TrieNode::decode(&mut hex!("c4823f1c01").as_slice()).unwrap();
+        assert_eq!(leaf.key_nibbles().unwrap(), &Nibbles::from_nibbles_unchecked([0xf, 0x1, 0xc]));
+        assert_eq!(TrieNode::Empty.key_nibbles(), None);
+
+        // rlp([0x40, 0x01]): the path has an invalid hex-prefix flag.
+        assert!(TrieNode::decode(&mut hex!("c24001").as_slice()).is_err());
+    }
+
+    #[test]
+    fn test_blind_children() {
+        let long = TrieNode::leaf(Nibbles::unpack(hex!("0102")), Bytes::from(vec![0xFF; 40]));
+        let short = TrieNode::leaf(Nibbles::unpack(hex!("0102")), bytes!("FF"));
+        let stack = (0..BRANCH_LIST_LENGTH)
+            .map(|i| if i % 2 == 0 { long.clone() } else { short.clone() })
+            .collect::<Vec<_>>();
+
+        let blinded = blind_children(&stack);
+        assert_eq!(blinded.len(), BRANCH_LIST_LENGTH);
+        for (i, node) in blinded.into_iter().enumerate() {
+            if i % 2 == 0 {
+                assert_eq!(node, TrieNode::Blinded { commitment: long.root() });
+            } else {
+                assert_eq!(node, short);
+            }
+        }
+    }
+
+    #[test]
+    fn test_decode_malformed() {
+        // Inputs that used to panic or decode past the end of the node.
+        const MALFORMED: [&[u8]; 7] = [
+            // A leaf with an empty path.
+            &hex!("c28001"),
+            // A list header that is longer than the input.
+            &hex!("c580"),
+            // A list element that is longer than the list.
+            &hex!("c28500"),
+            // A leaf whose value overruns the list into the following data.
+            &hex!("c320820102"),
+            // A branch with 18 elements behind a correct list header.
+            &hex!("d2808080808080808080808080808080808080"),
+            // A blinded commitment that is one byte short.
+            &hex!("9f00000000000000000000000000000000000000000000000000000000000000"),
+            // An extension without a child.
+            &hex!("c100"),
+        ];
+        for input in MALFORMED {
+            assert!(TrieNode::decode(&mut &input[..]).is_err(), "decoded {}", hex::encode(input));
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde_roundtrip_partially_opened() {
+        let (root, provider, entries) = build_trie(64);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        trie.open(&entries[0].0, &provider).unwrap();
+
+        let serialized = serde_json::to_string(&trie).unwrap();
+        let deserialized: TrieNode = serde_json::from_str(&serialized).unwrap();
+        assert_eq!(deserialized, trie);
+
+        // The opened path is still available without the provider.
+        assert_eq!(
+            deserialized.get(&entries[0].0, &MockProvider::default()).unwrap(),
+            Some(entries[0].1.clone())
+        );
+        assert_eq!(deserialized.root(), root);
+    }
+
+    #[test]
+    fn test_decode_leaf_or_extension_payload_errors() {
+        // An empty path.
+        assert_eq!(
+            TrieNode::try_decode_leaf_or_extension_payload(&mut hex!("8001").as_slice()),
+            Err(TrieNodeError::EmptyPath)
+        );
+        // A path whose high-order nibble is not a leaf or extension flag.
+        assert_eq!(
+            TrieNode::try_decode_leaf_or_extension_payload(&mut hex!("4001").as_slice()),
+            Err(TrieNodeError::InvalidPathPrefix(0x40))
+        );
+        // Even-length paths whose padding nibble is not zero, which are not canonical.
+        assert_eq!(
+            TrieNode::try_decode_leaf_or_extension_payload(&mut hex!("82011280").as_slice()),
+            Err(TrieNodeError::InvalidPathPrefix(0x01))
+        );
+        assert_eq!(
+            TrieNode::try_decode_leaf_or_extension_payload(&mut hex!("822f1201").as_slice()),
+            Err(TrieNodeError::InvalidPathPrefix(0x2f))
+        );
+        // A leaf without a value.
+        assert_eq!(
+            TrieNode::try_decode_leaf_or_extension_payload(&mut hex!("20").as_slice()),
+            Err(TrieNodeError::Rlp(alloy_rlp::Error::InputTooShort))
+        );
+    }
+
+    #[test]
+    fn test_decode_leaf_or_extension_errors() {
+        // The reason a leaf or extension node fails to decode is kept in the RLP error.
+        assert_eq!(
+            TrieNode::decode(&mut hex!("c28001").as_slice()),
+            Err(alloy_rlp::Error::Custom("empty leaf or extension path"))
+        );
+        assert_eq!(
+            TrieNode::decode(&mut hex!("c24001").as_slice()),
+            Err(alloy_rlp::Error::Custom("invalid hex-prefix path"))
+        );
+        assert_eq!(
+            TrieNode::decode(&mut hex!("c3822f12c0").as_slice()),
+            Err(alloy_rlp::Error::Custom("invalid hex-prefix path"))
+        );
+        assert_eq!(
+            TrieNode::decode(&mut hex!("c220c0").as_slice()),
+            Err(alloy_rlp::Error::UnexpectedList)
+        );
+    }
+
+    #[test]
+    fn test_decode_long_embedded_node() {
+        // Nodes that are 32 bytes or longer are always blinded in their parent.
+        let leaf = TrieNode::leaf(Nibbles::default(), Bytes::from(vec![0xFF; 40]));
+        let mut rlp_buf = Vec::new();
+        Header { list: true, payload_length: 1 + leaf.length() }.encode(&mut rlp_buf);
+        bytes!("00").encode(&mut rlp_buf);
+        leaf.encode(&mut rlp_buf);
+        assert_eq!(
+            TrieNode::decode(&mut rlp_buf.as_slice()),
+            Err(alloy_rlp::Error::Custom("embedded trie node is too long"))
+        );
+    }
+
+    #[test]
+    fn test_decode_deeply_nested_extensions() {
+        // Deeply nested embedded nodes are rejected before recursing into them.
+        let mut rlp_buf = hex!("c20080").to_vec();
+        for _ in 0..2_000 {
+            let mut wrapped = Vec::with_capacity(rlp_buf.len() + 10);
+            Header { list: true, payload_length: 1 + rlp_buf.len() }.encode(&mut wrapped);
+            wrapped.push(0x00);
+            wrapped.extend_from_slice(&rlp_buf);
+            rlp_buf = wrapped;
+        }
+        assert!(TrieNode::decode(&mut rlp_buf.as_slice()).is_err());
+    }
+
+    #[derive(Default)]
+    struct MockProvider {
+        preimages: BTreeMap<B256, Bytes>,
+        fetches: RefCell<Vec<B256>>,
+    }
+
+    impl TrieProvider for MockProvider {
+        fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+            self.fetches.borrow_mut().push(key);
+            self.preimages.get(&key).cloned().ok_or_else(|| anyhow!("unknown trie node {key}"))
+        }
+
+        fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+            Err(anyhow!("unknown bytecode {code_hash}"))
+        }
+    }
+
+    /// Builds a trie keyed by hashed indices, returning its root, the preimages of its blinded
+    /// nodes, and its entries.
+    fn build_trie(len: u8) -> (B256, MockProvider, Vec<(Nibbles, Bytes)>) {
+        let mut entries = (0..len)
+            .map(|i| (Nibbles::unpack(keccak256([i])), Bytes::from(vec![i; 1 + i as usize * 4])))
+            .collect::<Vec<_>>();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut hb = HashBuilder::default()
+            .with_proof_retainer(entries.iter().map(|(key, _)| key.clone()).collect());
+        for (key, value) in &entries {
+            hb.add_leaf(key.clone(), value);
+        }
+        let root = hb.root();
+        let preimages =
+            hb.take_proofs().into_iter().fold(BTreeMap::default(), |mut acc, (_, value)| {
+                acc.insert(keccak256(value.as_ref()), value);
+                acc
+            });
+
+        (root, MockProvider { preimages, ..Default::default() }, entries)
+    }
+
+    #[test]
+    fn test_open_and_get() {
+        let (root, provider, entries) = build_trie(32);
+        let mut trie = TrieNode::Blinded { commitment: root };
+
+        for (key, value) in &entries {
+            assert_eq!(trie.get(key, &provider).unwrap().as_ref(), Some(value));
+        }
+        assert_eq!(trie, TrieNode::Blinded { commitment: root });
+
+        for (key, value) in &entries {
+            assert_eq!(trie.open(key, &provider).unwrap().map(|v| v.clone()).as_ref(), Some(value));
+        }
+        assert!(matches!(trie, TrieNode::Branch { .. }));
+
+        // Opened nodes are kept in place, so a second walk does not fetch anything.
+        provider.fetches.borrow_mut().clear();
+        for (key, value) in &entries {
+            assert_eq!(trie.open(key, &provider).unwrap().map(|v| v.clone()).as_ref(), Some(value));
+        }
+        assert!(provider.fetches.borrow().is_empty());
+
+        // Opening the trie does not change its commitment.
+        assert_eq!(trie.root(), root);
+    }
+
+    #[test]
+    fn test_open_missing_key() {
+        let (root, provider, entries) = build_trie(16);
+        let mut trie = TrieNode::Blinded { commitment: root };
+
+        let missing = Nibbles::unpack(keccak256([0xFF]));
+        assert!(entries.iter().all(|(key, _)| key != &missing));
+        assert_eq!(trie.get(&missing, &provider).unwrap(), None);
+        assert_eq!(trie.open(&missing, &provider).unwrap(), None);
+
+        // A prefix of an existing key ends at an inner node.
+        let prefix = Nibbles::from_nibbles_unchecked(&entries[0].0[..1]);
+        assert_eq!(trie.open(&prefix, &provider).unwrap(), None);
+    }
+
+    #[test]
+    fn test_open_single_leaf() {
+        let (root, provider, entries) = build_trie(1);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        assert_eq!(
+            trie.open(&entries[0].0, &provider).unwrap().map(|v| v.clone()),
+            Some(entries[0].1.clone())
+        );
+        assert!(matches!(trie, TrieNode::Leaf { .. }));
+    }
+
+    #[test]
+    fn test_open_empty_root() {
+        let provider = MockProvider::default();
+        let mut trie = TrieNode::Blinded { commitment: EMPTY_ROOT_HASH };
+        let path = Nibbles::unpack(keccak256([0]));
+
+        assert_eq!(trie.get(&path, &provider).unwrap(), None);
+        assert_eq!(trie.open(&path, &provider).unwrap(), None);
+        assert_eq!(trie, TrieNode::Empty);
+        assert!(provider.fetches.borrow().is_empty());
+    }
+
+    #[test]
+    fn test_open_mismatched_preimage() {
+        let (root, mut provider, entries) = build_trie(8);
+        let preimage = provider.preimages.remove(&root).unwrap();
+        let mut tampered = preimage.to_vec();
+        *tampered.last_mut().unwrap() ^= 0xFF;
+        provider.preimages.insert(root, tampered.into());
+
+        let mut trie = TrieNode::Blinded { commitment: root };
+        let err = trie.open(&entries[0].0, &provider).unwrap_err();
+        assert!(matches!(err, TrieNodeError::PreimageMismatch(_)));
+        assert!(trie.get(&entries[0].0, &provider).is_err());
+        assert_eq!(trie, TrieNode::Blinded { commitment: root });
+    }
+
+    #[test]
+    fn test_open_missing_preimage() {
+        let (root, _, entries) = build_trie(8);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        assert!(matches!(
+            trie.open(&entries[0].0, &MockProvider::default()),
+            Err(TrieNodeError::Provider(_))
+        ));
+    }
+
+    /// Computes the root of a trie from its entries with the [HashBuilder].
+    fn trie_root(entries: &BTreeMap<Nibbles, Bytes>) -> B256 {
+        let mut hb = HashBuilder::default();
+        entries.iter().for_each(|(key, value)| hb.add_leaf(key.clone(), value));
+        hb.root()
+    }
+
+    #[test]
+    fn test_insert_into_empty() {
+        let provider = MockProvider::default();
+        let mut trie = TrieNode::Empty;
+        let mut expected = BTreeMap::new();
+
+        for i in 0..64u8 {
+            let key = Nibbles::unpack(keccak256([i]));
+            let value = Bytes::from(vec![i; 1 + i as usize]);
+            trie.insert(&key, value.clone(), &provider).unwrap();
+            expected.insert(key, value);
+            assert_eq!(trie.root(), trie_root(&expected));
+        }
+        for (key, value) in &expected {
+            assert_eq!(trie.get(key, &provider).unwrap().as_ref(), Some(value));
+        }
+    }
+
+    #[test]
+    fn test_insert_into_blinded() {
+        let (root, provider, entries) = build_trie(16);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        let mut expected = entries.into_iter().collect::<BTreeMap<_, _>>();
+
+        // Update existing values and add new keys that split the blinded nodes.
+        for i in 8..24u8 {
+            let key = Nibbles::unpack(keccak256([i]));
+            let value = Bytes::from(vec![0xFF - i; 40]);
+            trie.insert(&key, value.clone(), &provider).unwrap();
+            expected.insert(key, value);
+        }
+        assert_eq!(trie.root(), trie_root(&expected));
+    }
+
+    #[test]
+    fn test_sparse_mutations_only_open_their_path() {
+        let (root, provider, entries) = build_trie(255);
+        let entries = entries.into_iter().collect::<BTreeMap<_, _>>();
+
+        // Inserting a new key fetches exactly the nodes of its absence proof, from the root down.
+        let key = Nibbles::unpack(keccak256([0xFF]));
+        let proof = TrieNode::Blinded { commitment: root }.proof(&key, &provider).unwrap();
+        provider.fetches.borrow_mut().clear();
+        let mut trie = TrieNode::Blinded { commitment: root };
+        trie.insert(&key, Bytes::from(vec![0xFF; 40]), &provider).unwrap();
+        assert_eq!(*provider.fetches.borrow(), proof.iter().map(keccak256).collect::<Vec<_>>());
+
+        let mut expected = entries.clone();
+        expected.insert(key, Bytes::from(vec![0xFF; 40]));
+        assert_eq!(trie.root(), trie_root(&expected));
+
+        // Deleting a key fetches the nodes of its proof, then at most the sibling of the leaf in
+        // the collapsed branch, which is referenced by a node of the proof.
+        let key = entries.keys().nth(100).unwrap().clone();
+        let proof = TrieNode::Blinded { commitment: root }.proof(&key, &provider).unwrap();
+        provider.fetches.borrow_mut().clear();
+        let mut trie = TrieNode::Blinded { commitment: root };
+        trie.delete(&key, &provider).unwrap();
+        let fetches = provider.fetches.borrow().clone();
+        let (path, siblings) = fetches.split_at(proof.len().min(fetches.len()));
+        assert_eq!(path, proof.iter().map(keccak256).collect::<Vec<_>>());
+        assert!(siblings.len() <= 1);
+        for sibling in siblings {
+            assert!(proof.iter().any(|node| node.windows(32).any(|w| w == sibling.as_slice())));
+        }
+
+        let mut expected = entries;
+        expected.remove(&key);
+        assert_eq!(trie.root(), trie_root(&expected));
+    }
+
+    #[test]
+    fn test_insert_batch() {
+        let (root, provider, entries) = build_trie(128);
+        let mut batch = (100..160u8)
+            .map(|i| (Nibbles::unpack(keccak256([i])), Bytes::from(vec![i; 48])))
+            .collect::<Vec<_>>();
+        // Later entries at the same path overwrite earlier ones.
+        batch.push((entries[7].0.clone(), bytes!("deadbeef")));
+        batch.push((entries[7].0.clone(), bytes!("c0ffee")));
+
+        let mut expected = TrieNode::Blinded { commitment: root };
+        for (path, value) in batch.iter().cloned() {
+            expected.insert(&path, value, &provider).unwrap();
+        }
+        let mut trie = TrieNode::Blinded { commitment: root };
+        trie.insert_batch(batch.iter().cloned(), &provider).unwrap();
+        assert_eq!(trie.root(), expected.root());
+        assert_eq!(trie.get(&entries[7].0, &provider).unwrap(), Some(bytes!("c0ffee")));
+
+        let mut fresh = TrieNode::Empty;
+        fresh.insert_batch(batch.iter().cloned(), &provider).unwrap();
+        let mut expected = TrieNode::Empty;
+        for (path, value) in batch {
+            expected.insert(&path, value, &provider).unwrap();
+        }
+        assert_eq!(fresh.root(), expected.root());
+
+        // A path that is a strict prefix of another requires a value in a branch.
+        let prefixed = [
+            (Nibbles::unpack([0x12, 0x34]), bytes!("01")),
+            (Nibbles::unpack([0x12]), bytes!("02")),
+        ];
+        assert_eq!(
+            TrieNode::Empty.insert_batch(prefixed, &provider),
+            Err(TrieNodeError::BranchValue)
+        );
+    }
+
+    #[test]
+    fn test_validate() {
+        let (root, provider, _) = build_trie(128);
+        TrieNode::Blinded { commitment: root }.validate(&provider).unwrap();
+        TrieNode::Empty.validate(&provider).unwrap();
+
+        // A preimage that does not hash to its commitment is reported.
+        let mut tampered =
+            MockProvider { preimages: provider.preimages.clone(), ..Default::default() };
+        let (&commitment, preimage) = tampered.preimages.iter_mut().nth(3).unwrap();
+        *preimage = Bytes::from([preimage.as_ref(), &[0x00]].concat());
+        assert_eq!(
+            TrieNode::Blinded { commitment: root }.validate(&tampered),
+            Err(TrieNodeError::PreimageMismatch(commitment))
+        );
+
+        let leaf = |value: &'static [u8]| {
+            TrieNode::leaf(Nibbles::unpack([0x12]), Bytes::from_static(value))
+        };
+        let mut stack = vec![TrieNode::Empty; BRANCH_LIST_LENGTH];
+        stack[3] = leaf(&[1]);
+        let single = TrieNode::Branch { stack: stack.clone() };
+        stack[5] = leaf(&[2]);
+        let branch = TrieNode::Branch { stack };
+
+        let cases = [
+            (
+                TrieNode::extension(Nibbles::default(), branch.clone()),
+                &[][..],
+                "extension has an empty prefix",
+            ),
+            (
+                TrieNode::extension(Nibbles::unpack([0xAB]), leaf(&[1])),
+                &[][..],
+                "extension does not point at a branch",
+            ),
+            (
+                TrieNode::extension(Nibbles::unpack([0xAB]), single),
+                &[0xA, 0xB][..],
+                "branch has fewer than two children",
+            ),
+            (
+                TrieNode::leaf(Nibbles::from_nibbles_unchecked([0x10]), bytes!("01")),
+                &[][..],
+                "leaf path holds a value larger than a nibble",
+            ),
+        ];
+        for (trie, path, reason) in cases {
+            assert_eq!(
+                trie.validate(&provider),
+                Err(TrieNodeError::Malformed {
+                    path: Nibbles::from_nibbles_unchecked(path),
+                    reason
+                })
+            );
+        }
+
+        let mut stack = vec![TrieNode::Empty; BRANCH_LIST_LENGTH];
+        stack[0] = leaf(&[1]);
+        stack[1] = leaf(&[]);
+        assert_eq!(
+            TrieNode::Branch { stack }.validate(&provider),
+            Err(TrieNodeError::Malformed {
+                path: Nibbles::from_nibbles_unchecked([1]),
+                reason: "leaf holds an empty value"
+            })
+        );
+        branch.validate(&provider).unwrap();
+    }
+
+    #[test]
+    fn test_insert_splits_extension() {
+        let provider = MockProvider::default();
+        let mut trie = TrieNode::Empty;
+        let mut expected = BTreeMap::new();
+
+        // The first two keys share a three nibble extension. The later keys split it after its
+        // first nibble, and then split the remaining extensions at each of their nibbles.
+        let keys = [
+            hex!("12340000"),
+            hex!("12350000"),
+            hex!("20000000"),
+            hex!("12400000"),
+            hex!("13000000"),
+        ];
+        for (i, key) in keys.into_iter().enumerate() {
+            let key = Nibbles::unpack(key);
+            let value = Bytes::from(vec![i as u8; 1 + i * 16]);
+            trie.insert(&key, value.clone(), &provider).unwrap();
+            expected.insert(key, value);
+            assert_eq!(trie.root(), trie_root(&expected));
+        }
+        for (key, value) in &expected {
+            assert_eq!(trie.open(key, &provider).unwrap().map(|v| v.clone()).as_ref(), Some(value));
+        }
+    }
+
+    #[test]
+    fn test_insert_branch_value() {
+        let provider = MockProvider::default();
+        let mut trie = TrieNode::Empty;
+        trie.insert(&Nibbles::unpack(hex!("1234")), bytes!("01"), &provider).unwrap();
+        trie.insert(&Nibbles::unpack(hex!("1334")), bytes!("02"), &provider).unwrap();
+
+        for path in [hex!("12").as_slice(), hex!("123456").as_slice(), &[]] {
+            let err = trie.insert(&Nibbles::unpack(path), bytes!("03"), &provider).unwrap_err();
+            assert_eq!(err, TrieNodeError::BranchValue);
+        }
+    }
+
+    #[test]
+    fn test_root() {
+        let provider = MockProvider::default();
+        assert_eq!(TrieNode::Empty.root(), EMPTY_ROOT_HASH);
+        assert_eq!(TrieNode::Blinded { commitment: B256::ZERO }.root(), B256::ZERO);
+
+        // Short roots are hashed even though they would be embedded in a parent.
+        let mut trie = TrieNode::Empty;
+        let key = Nibbles::unpack(hex!("01"));
+        trie.insert(&key, bytes!("02"), &provider).unwrap();
+        assert!(trie.length() < MIN_BLINDED_LENGTH);
+        assert_eq!(trie.root(), trie_root(&BTreeMap::from([(key, bytes!("02"))])));
+
+        // Mutating an opened trie is reflected in its root.
+        let (root, provider, entries) = build_trie(16);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        trie.open(&entries[0].0, &provider).unwrap();
+        assert_eq!(trie.root(), root);
+        *trie.open(&entries[0].0, &provider).unwrap().unwrap() = bytes!("FF");
+        let mut expected = entries.iter().cloned().collect::<BTreeMap<_, _>>();
+        expected.insert(entries[0].0.clone(), bytes!("FF"));
+        assert_eq!(trie.root(), trie_root(&expected));
+    }
+
+    #[test]
+    fn test_proof() {
+        let (root, provider, entries) = build_trie(32);
+        let mut trie = TrieNode::Blinded { commitment: root };
+
+        for (key, value) in &entries {
+            let proof = trie.proof(key, &provider).unwrap();
+            assert_eq!(keccak256(&proof[0]), root);
+            verify_proof(root, key, Some(&value[..]), &proof).unwrap();
+        }
+        assert_eq!(trie.root(), root);
+
+        // A proof of absence walks down to the node where the path diverges.
+        let missing = Nibbles::unpack(keccak256([0xFF]));
+        let proof = trie.proof(&missing, &provider).unwrap();
+        assert!(!proof.is_empty());
+        verify_proof(root, &missing, None, &proof).unwrap();
+
+        // The proof is not enough to look up other keys.
+        let proof = trie.proof(&entries[0].0, &provider).unwrap();
+        assert!(matches!(
+            verify_proof(root, &entries[1].0, Some(&entries[1].1[..]), &proof),
+            Err(ProofError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_proof_embedded_nodes() {
+        let provider = MockProvider::default();
+        let mut trie = TrieNode::Empty;
+        let keys = [hex!("12340000"), hex!("12350000"), hex!("20000000")];
+        for key in keys {
+            trie.insert(&Nibbles::unpack(key), bytes!("01"), &provider).unwrap();
+        }
+
+        // The short leaves are embedded in their parents, and are not listed in the proof.
+        let root = trie.root();
+        let key = Nibbles::unpack(keys[0]);
+        let proof = trie.proof(&key, &provider).unwrap();
+        assert_eq!(proof.len(), 2);
+        assert!(proof.iter().skip(1).all(|node| node.len() >= MIN_BLINDED_LENGTH));
+        verify_proof(root, &key, Some(&[0x01][..]), &proof).unwrap();
+    }
+
+    #[test]
+    fn test_proof_empty_trie() {
+        let provider = MockProvider::default();
+        let mut trie = TrieNode::Blinded { commitment: EMPTY_ROOT_HASH };
+        assert!(trie.proof(&Nibbles::unpack(hex!("01")), &provider).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_delete_from_blinded() {
+        let (root, provider, entries) = build_trie(32);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        let mut expected = entries.into_iter().collect::<BTreeMap<_, _>>();
+
+        let keys = expected.keys().cloned().collect::<Vec<_>>();
+        for key in keys.iter().step_by(2) {
+            trie.delete(key, &provider).unwrap();
+            expected.remove(key);
+            assert_eq!(trie.root(), trie_root(&expected));
+        }
+        for key in keys.iter().skip(1).step_by(2) {
+            trie.delete(key, &provider).unwrap();
+            expected.remove(key);
+            assert_eq!(trie.root(), trie_root(&expected));
+        }
+        assert_eq!(trie, TrieNode::Empty);
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn test_delete_missing_key() {
+        let (root, provider, entries) = build_trie(16);
+        let mut trie = TrieNode::Blinded { commitment: root };
+
+        trie.delete(&Nibbles::unpack(keccak256([0xFF])), &provider).unwrap();
+        let mut sibling = entries[0].0.to_vec();
+        *sibling.last_mut().unwrap() ^= 0x1;
+        trie.delete(&Nibbles::from_nibbles_unchecked(sibling), &provider).unwrap();
+        assert_eq!(trie.root(), root);
+    }
+
+    #[test]
+    fn test_delete_collapses_branches() {
+        let provider = MockProvider::default();
+        let mut trie = TrieNode::Empty;
+        let keys = [hex!("12340000"), hex!("12350000"), hex!("20000000")];
+        for (i, key) in keys.into_iter().enumerate() {
+            trie.insert(&Nibbles::unpack(key), Bytes::from(vec![i as u8; 8]), &provider).unwrap();
+        }
+
+        // The root branch collapses into its remaining child, which absorbs the branch nibble.
+        trie.delete(&Nibbles::unpack(keys[2]), &provider).unwrap();
+        let TrieNode::Extension { node, .. } = &trie else {
+            panic!("Expected an extension, got {trie:?}");
+        };
+        assert_eq!(trie.key_nibbles().unwrap(), &Nibbles::from_nibbles_unchecked([0x1, 0x2, 0x3]));
+        assert!(matches!(node.as_ref(), TrieNode::Branch { .. }));
+
+        // The inner branch collapses into a leaf, which absorbs the extension above it.
+        trie.delete(&Nibbles::unpack(keys[1]), &provider).unwrap();
+        assert_eq!(trie, TrieNode::leaf(Nibbles::unpack(keys[0]), Bytes::from(vec![0; 8])));
+    }
+
+    proptest! {
+        #[test]
+        fn test_insert_batch_matches_reference(
+            entries in arb_entries(64),
+            batch in arb_entries(64),
+        ) {
+            let mut trie = trie_from_entries(&entries);
+            trie.insert_batch(batch.clone(), &MockProvider::default()).unwrap();
+
+            let mut expected = entries;
+            expected.extend(batch);
+            prop_assert_eq!(trie.root(), reference_root(&expected));
+        }
+
+        #[test]
+        fn test_decode_arbitrary_bytes(input in vec(any::<u8>(), 0..=256)) {
+            let _ = TrieNode::decode(&mut input.as_slice());
+        }
+
+        #[test]
+        fn test_decode_arbitrary_list(payload in vec(any::<u8>(), 0..=256)) {
+            // Arbitrary elements behind a correct list header.
+            let mut rlp_buf = Vec::with_capacity(payload.len() + 3);
+            Header { list: true, payload_length: payload.len() }.encode(&mut rlp_buf);
+            rlp_buf.extend_from_slice(&payload);
+            let _ = TrieNode::decode(&mut rlp_buf.as_slice());
+        }
+
+        #[test]
+        fn test_hex_prefix_roundtrip(
+            path in vec(0u8..16, 0..=64),
+            is_leaf in any::<bool>(),
+            value in vec(any::<u8>(), 1..=32),
+        ) {
+            let path = Nibbles::from_nibbles_unchecked(path);
+            let node = if is_leaf {
+                TrieNode::leaf(path.clone(), value.into())
+            } else {
+                TrieNode::extension(path.clone(), TrieNode::Empty)
+            };
+
+            let encoded_path = encode_path(&path, is_leaf);
+            prop_assert_eq!(encoded_path.len(), path.len() / 2 + 1);
+            prop_assert_eq!(encoded_path[0] >> 4, ((is_leaf as u8) << 1) | (path.len() % 2) as u8);
+            prop_assert_eq!(decode_path(&encoded_path), Some(path.clone()));
+            prop_assert_eq!(node.key_nibbles().unwrap(), &path);
+
+            let mut rlp_buf = Vec::with_capacity(node.length());
+            node.encode(&mut rlp_buf);
+            let decoded = TrieNode::decode(&mut rlp_buf.as_slice()).unwrap();
+            prop_assert_eq!(decoded, node);
+        }
+
+        #[test]
+        fn test_decode_path_canonical(path in vec(any::<u8>(), 1..=33)) {
+            // Only canonical paths decode, so they encode back to the same bytes.
+            if let Some(nibbles) = decode_path(&path) {
+                let is_leaf = path[0] >> 4 >= PREFIX_LEAF_EVEN;
+                prop_assert_eq!(&encode_path(&nibbles, is_leaf)[..], &path[..]);
+            }
+        }
+    }
 }
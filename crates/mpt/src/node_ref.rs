@@ -0,0 +1,401 @@
+//! This module contains the [TrieNodeRef] type, a borrowed view of an RLP encoded [TrieNode].
+
+use crate::{
+    node::{fetch_preimage, MIN_BLINDED_LENGTH},
+    Nibbles, TrieNode, TrieNodeError, TrieProvider,
+};
+use alloc::vec::Vec;
+use alloy_primitives::{Bytes, B256};
+use alloy_rlp::{Decodable, Header};
+use alloy_trie::EMPTY_ROOT_HASH;
+
+/// The number of elements in the RLP list of a branch node.
+const BRANCH_LIST_LENGTH: usize = 17;
+
+/// The highest valid hex-prefix flag. Bit 0 of the flag is set for odd paths, and bit 1 for leaves.
+const MAX_PATH_FLAG: u8 = 3;
+
+/// A hex-prefix encoded path of a [TrieNodeRef::Leaf] or [TrieNodeRef::Extension], borrowed from
+/// the RLP encoding of the node. The nibbles are read from the encoding on demand.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PathRef<'a>(&'a [u8]);
+
+impl<'a> PathRef<'a> {
+    /// Wraps a hex-prefix encoded path. Returns `None` if the path is empty or its flag is
+    /// invalid.
+    fn new(encoded: &'a [u8]) -> Option<Self> {
+        let first = encoded.first()?;
+        (first >> 4 <= MAX_PATH_FLAG).then_some(Self(encoded))
+    }
+
+    /// Returns the hex-prefix encoded path, including its flag.
+    pub const fn encoded(&self) -> &'a [u8] {
+        self.0
+    }
+
+    /// Returns whether the path belongs to a leaf, rather than an extension.
+    pub fn is_leaf(&self) -> bool {
+        self.0[0] & 0x20 != 0
+    }
+
+    /// Returns whether the path has an odd number of nibbles.
+    fn is_odd(&self) -> bool {
+        self.0[0] & 0x10 != 0
+    }
+
+    /// Returns the number of nibbles in the path.
+    pub fn len(&self) -> usize {
+        (self.0.len() - 1) * 2 + self.is_odd() as usize
+    }
+
+    /// Returns whether the path has no nibbles.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the nibble at `index`, or `None` if it is out of bounds.
+    pub fn get(&self, index: usize) -> Option<u8> {
+        if index >= self.len() {
+            return None;
+        }
+        // Odd paths store their first nibble next to the flag, even paths pad it with a zero.
+        let position = index + if self.is_odd() { 1 } else { 2 };
+        let byte = self.0[position / 2];
+        Some(if position % 2 == 0 { byte >> 4 } else { byte & 0x0F })
+    }
+
+    /// Returns an iterator over the nibbles of the path.
+    pub fn iter(&self) -> impl Iterator<Item = u8> + 'a {
+        let path = *self;
+        (0..path.len()).filter_map(move |index| path.get(index))
+    }
+
+    /// Strips the path from the front of the nibble `path`, returning the rest of it. Returns
+    /// `None` if `path` does not start with this path.
+    pub fn strip_from<'p>(&self, path: &'p [u8]) -> Option<&'p [u8]> {
+        let rest = path.get(self.len()..)?;
+        self.iter().eq(path.iter().copied().take(self.len())).then_some(rest)
+    }
+
+    /// Copies the path into [Nibbles].
+    pub fn to_nibbles(&self) -> Nibbles {
+        Nibbles::from_nibbles_unchecked(self.iter().collect::<Vec<_>>())
+    }
+}
+
+/// A [TrieNodeRef] is a [TrieNode] that borrows its paths, values and children from the RLP buffer
+/// it was decoded from, instead of copying them. It suits hot paths where nodes are decoded, read
+/// once and discarded, such as walking a single path of a trie.
+///
+/// Children are kept in their raw RLP encoding, and are only validated once they are decoded
+/// themselves with [TrieNodeRef::decode].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrieNodeRef<'a> {
+    /// See [TrieNode::Empty].
+    Empty,
+    /// See [TrieNode::Blinded].
+    Blinded {
+        /// The commitment that blinds the node.
+        commitment: B256,
+    },
+    /// See [TrieNode::Leaf].
+    Leaf {
+        /// The remaining key of the leaf node.
+        key: PathRef<'a>,
+        /// The value of the leaf node.
+        value: &'a [u8],
+    },
+    /// See [TrieNode::Extension].
+    Extension {
+        /// The path prefix of the extension.
+        prefix: PathRef<'a>,
+        /// The RLP encoding of the child node.
+        node: &'a [u8],
+    },
+    /// See [TrieNode::Branch].
+    Branch {
+        /// The RLP encodings of the 16 child nodes and the value of the branch.
+        stack: [&'a [u8]; BRANCH_LIST_LENGTH],
+    },
+}
+
+impl<'a> TrieNodeRef<'a> {
+    /// Decodes a [TrieNodeRef] from the front of `buf`, borrowing from it.
+    ///
+    /// The same rules as for [TrieNode] apply: list elements must not overrun the list, the list
+    /// must be consumed exactly, and children embedded in the node must be shorter than 32 bytes.
+    pub fn decode(buf: &mut &'a [u8]) -> alloy_rlp::Result<Self> {
+        let mut payload = *buf;
+        let header = Header::decode(&mut payload)?;
+        if payload.len() < header.payload_length {
+            return Err(alloy_rlp::Error::InputTooShort);
+        }
+        let (mut payload, rest) = payload.split_at(header.payload_length);
+
+        let node = if header.list {
+            let mut stack = [&[][..]; BRANCH_LIST_LENGTH];
+            let mut length = 0;
+            while !payload.is_empty() {
+                let slot = stack.get_mut(length).ok_or(alloy_rlp::Error::UnexpectedLength)?;
+                *slot = split_item(&mut payload)?;
+                length += 1;
+            }
+
+            match length {
+                BRANCH_LIST_LENGTH => {
+                    stack.into_iter().try_for_each(check_child)?;
+                    Self::Branch { stack }
+                }
+                2 => {
+                    let path = PathRef::new(decode_string(stack[0])?)
+                        .ok_or(alloy_rlp::Error::Custom("invalid hex-prefix path"))?;
+                    if path.is_leaf() {
+                        Self::Leaf { key: path, value: decode_string(stack[1])? }
+                    } else {
+                        check_child(stack[1])?;
+                        Self::Extension { prefix: path, node: stack[1] }
+                    }
+                }
+                _ => return Err(alloy_rlp::Error::UnexpectedLength),
+            }
+        } else {
+            match header.payload_length {
+                0 => Self::Empty,
+                32 => Self::Blinded { commitment: B256::from_slice(payload) },
+                _ => return Err(alloy_rlp::Error::UnexpectedLength),
+            }
+        };
+
+        *buf = rest;
+        Ok(node)
+    }
+
+    /// Copies the node into a [TrieNode], decoding its children.
+    pub fn into_node(self) -> alloy_rlp::Result<TrieNode> {
+        Ok(match self {
+            Self::Empty => TrieNode::Empty,
+            Self::Blinded { commitment } => TrieNode::Blinded { commitment },
+            Self::Leaf { key, value } => {
+                TrieNode::leaf(key.to_nibbles(), Bytes::copy_from_slice(value))
+            }
+            Self::Extension { prefix, mut node } => {
+                TrieNode::extension(prefix.to_nibbles(), TrieNode::decode(&mut node)?)
+            }
+            Self::Branch { stack } => TrieNode::Branch {
+                stack: stack
+                    .into_iter()
+                    .map(|mut child| TrieNode::decode(&mut child))
+                    .collect::<alloy_rlp::Result<Vec<_>>>()?,
+            },
+        })
+    }
+}
+
+/// Walks the trie committed to by `root` down the nibble `path` and returns the value stored at
+/// the end of it, decoding every node on the way as a [TrieNodeRef]. The returned value shares
+/// the buffer of the preimage it was found in, so nothing is copied.
+pub(crate) fn get_borrowed<P: TrieProvider>(
+    root: B256,
+    mut path: &[u8],
+    provider: &P,
+) -> Result<Option<Bytes>, TrieNodeError> {
+    let mut commitment = root;
+    loop {
+        if commitment == EMPTY_ROOT_HASH {
+            return Ok(None);
+        }
+        let preimage = fetch_preimage(commitment, provider)?;
+        let mut buf = preimage.as_ref();
+        let mut node = TrieNodeRef::decode(&mut buf)?;
+        if !buf.is_empty() || matches!(node, TrieNodeRef::Blinded { .. }) {
+            return Err(TrieNodeError::InvalidPreimage(commitment));
+        }
+
+        // Descend through the nodes embedded in the preimage, until the path ends or leaves it.
+        commitment = loop {
+            match node {
+                TrieNodeRef::Empty => return Ok(None),
+                TrieNodeRef::Blinded { commitment } => break commitment,
+                TrieNodeRef::Leaf { key, value } => {
+                    let found = key.strip_from(path).is_some_and(|rest| rest.is_empty());
+                    return Ok(found.then(|| Bytes(preimage.0.slice_ref(value))));
+                }
+                TrieNodeRef::Extension { prefix, node: mut child } => {
+                    let Some(rest) = prefix.strip_from(path) else {
+                        return Ok(None);
+                    };
+                    path = rest;
+                    node = TrieNodeRef::decode(&mut child)?;
+                }
+                TrieNodeRef::Branch { stack } => {
+                    let Some((&index, rest)) = path.split_first() else {
+                        return Ok(None);
+                    };
+                    let mut child = *stack
+                        .get(..BRANCH_LIST_LENGTH - 1)
+                        .and_then(|children| children.get(index as usize))
+                        .ok_or(TrieNodeError::InvalidBranchIndex(index))?;
+                    path = rest;
+                    node = TrieNodeRef::decode(&mut child)?;
+                }
+            }
+        };
+    }
+}
+
+/// Splits the next RLP item, including its header, off the front of `buf`.
+fn split_item<'a>(buf: &mut &'a [u8]) -> alloy_rlp::Result<&'a [u8]> {
+    let mut payload = *buf;
+    let header = Header::decode(&mut payload)?;
+    let length = buf.len() - payload.len() + header.payload_length;
+    if buf.len() < length {
+        return Err(alloy_rlp::Error::InputTooShort);
+    }
+    let (item, rest) = buf.split_at(length);
+    *buf = rest;
+    Ok(item)
+}
+
+/// Returns the payload of an RLP encoded string.
+fn decode_string(mut item: &[u8]) -> alloy_rlp::Result<&[u8]> {
+    let header = Header::decode(&mut item)?;
+    if header.list {
+        return Err(alloy_rlp::Error::UnexpectedList);
+    }
+    item.get(..header.payload_length).ok_or(alloy_rlp::Error::InputTooShort)
+}
+
+/// Checks that the RLP encoded child of a branch or extension is empty, a commitment, or a node
+/// that is short enough to be embedded in its parent.
+fn check_child(mut item: &[u8]) -> alloy_rlp::Result<()> {
+    let header = Header::decode(&mut item)?;
+    match (header.list, header.payload_length) {
+        (true, _) if header.length() + header.payload_length >= MIN_BLINDED_LENGTH => {
+            Err(alloy_rlp::Error::Custom("embedded trie node is too long"))
+        }
+        (true, _) | (false, 0) => Ok(()),
+        (false, length) if length == B256::len_bytes() => Ok(()),
+        _ => Err(alloy_rlp::Error::UnexpectedLength),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MapTrieProvider;
+    use alloc::vec;
+    use alloy_primitives::{hex, keccak256};
+    use alloy_rlp::Encodable;
+    use proptest::{
+        collection::vec,
+        prelude::{any, prop_assert_eq, proptest},
+    };
+
+    #[test]
+    fn test_decode_matches_trie_node() {
+        const NODES: [&[u8]; 5] = [
+            // A branch with a blinded child and an embedded leaf.
+            &hex!("f83ea0eb08a66a94882454bec899d3e82952dcc918ba4b35a09a84acd98019aef4345080808080808080cd308b8a746573742074687265658080808080808080"),
+            // An extension with an embedded leaf.
+            &hex!("d28300646fcd308b8a74657374207468726565"),
+            // An extension with a blinded child.
+            &hex!("e58300646fa0f3fe8b3c5b21d3e52860f1e4a5825a6100bb341069c1e88f4ebf6bd98de0c190"),
+            // An even leaf.
+            &hex!("ca8320646f8576657262FF"),
+            // An odd leaf.
+            &hex!("c4823f1c01"),
+        ];
+        for rlp in NODES {
+            let mut buf = rlp;
+            let node = TrieNodeRef::decode(&mut buf).unwrap();
+            assert!(buf.is_empty());
+            assert_eq!(node.into_node().unwrap(), TrieNode::decode(&mut &rlp[..]).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_decode_borrows() {
+        let rlp = hex!("ca8320646f8576657262FF");
+        let TrieNodeRef::Leaf { key, value } = TrieNodeRef::decode(&mut &rlp[..]).unwrap() else {
+            panic!("Expected a leaf");
+        };
+        assert_eq!(value.as_ptr(), rlp[6..].as_ptr());
+        assert_eq!(key.encoded(), &rlp[2..5]);
+        assert!(key.is_leaf());
+        assert_eq!(key.to_nibbles(), Nibbles::unpack(hex!("646f")));
+        assert_eq!(key.strip_from(&[6, 4, 6, 15, 1]), Some(&[1][..]));
+        assert_eq!(key.strip_from(&[6, 4, 6]), None);
+        assert_eq!(key.get(4), None);
+    }
+
+    #[test]
+    fn test_decode_malformed() {
+        const MALFORMED: [&[u8]; 8] = [
+            &hex!("c28001"),
+            &hex!("c580"),
+            &hex!("c28500"),
+            &hex!("c320820102"),
+            &hex!("d2808080808080808080808080808080808080"),
+            &hex!("9f00000000000000000000000000000000000000000000000000000000000000"),
+            &hex!("c100"),
+            // rlp([0x40, 0x01]): the path has an invalid hex-prefix flag.
+            &hex!("c24001"),
+        ];
+        for input in MALFORMED {
+            assert!(
+                TrieNodeRef::decode(&mut &input[..]).is_err(),
+                "decoded {}",
+                hex::encode(input)
+            );
+        }
+    }
+
+    #[test]
+    fn test_get_borrowed() {
+        let mut provider = MapTrieProvider::new();
+        let mut trie = TrieNode::Empty;
+        let keys = (0u32..64).map(|i| keccak256(i.to_be_bytes())).collect::<Vec<_>>();
+        for (i, key) in keys.iter().enumerate() {
+            // Short values keep some of the nodes embedded in their parents.
+            let value = Bytes::from(vec![i as u8; 1 + i % 40]);
+            trie.insert(&Nibbles::unpack(key), value, &provider).unwrap();
+        }
+        let root = provider.insert_trie(&trie);
+
+        for key in &keys {
+            let path = Nibbles::unpack(key);
+            assert_eq!(
+                get_borrowed(root, &path, &provider).unwrap(),
+                trie.get(&path, &provider).unwrap()
+            );
+        }
+        let missing = Nibbles::unpack(keccak256([0xFF]));
+        assert_eq!(get_borrowed(root, &missing, &provider).unwrap(), None);
+        assert_eq!(get_borrowed(EMPTY_ROOT_HASH, &missing, &provider).unwrap(), None);
+    }
+
+    proptest! {
+        #[test]
+        fn test_decode_arbitrary_bytes(input in vec(any::<u8>(), 0..=256)) {
+            let _ = TrieNodeRef::decode(&mut input.as_slice());
+        }
+
+        #[test]
+        fn test_path_roundtrip(
+            path in vec(0u8..16, 0..=64),
+            value in vec(any::<u8>(), 1..=32),
+        ) {
+            let node = TrieNode::leaf(Nibbles::from_nibbles_unchecked(path.clone()), value.into());
+            let mut rlp_buf = Vec::with_capacity(node.length());
+            node.encode(&mut rlp_buf);
+
+            let decoded = TrieNodeRef::decode(&mut rlp_buf.as_slice()).unwrap();
+            let TrieNodeRef::Leaf { key, .. } = decoded else {
+                panic!("Expected a leaf");
+            };
+            prop_assert_eq!(key.len(), path.len());
+            prop_assert_eq!(key.iter().collect::<Vec<_>>(), path);
+            prop_assert_eq!(decoded.into_node().unwrap(), node);
+        }
+    }
+}
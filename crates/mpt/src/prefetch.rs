@@ -0,0 +1,215 @@
+//! This module contains the [PrefetchPlan], which collects the accounts and storage slots a block
+//! is about to touch, so that their trie paths can be hinted and opened before execution begins.
+
+use crate::{Nibbles, TrieHinter, TrieNode, TrieNodeError, TrieProvider};
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec::Vec,
+};
+use alloy_eips::eip2930::AccessList;
+use alloy_primitives::{keccak256, Address, B256};
+use anyhow::Result;
+
+/// A [PrefetchPlan] holds the deduplicated set of accounts and storage slots to prefetch, such as
+/// the entries of the access lists of a block's transactions.
+///
+/// The plan yields the paths of the accounts in the state trie and of the slots in each storage
+/// trie, sends a single batched storage hint per account, and can open the planned paths of a
+/// trie in one pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PrefetchPlan {
+    /// The planned accounts, with the storage slots to prefetch for each of them.
+    accounts: BTreeMap<Address, BTreeSet<B256>>,
+}
+
+impl PrefetchPlan {
+    /// Creates a new, empty [PrefetchPlan].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the account at `address` to the plan.
+    pub fn add_account(&mut self, address: Address) {
+        self.accounts.entry(address).or_default();
+    }
+
+    /// Adds the storage `slot` of the account at `address` to the plan, along with the account.
+    pub fn add_storage_slot(&mut self, address: Address, slot: B256) {
+        self.accounts.entry(address).or_default().insert(slot);
+    }
+
+    /// Adds every account and storage key of an [EIP-2930] access list to the plan.
+    ///
+    /// [EIP-2930]: https://eips.ethereum.org/EIPS/eip-2930
+    pub fn add_access_list(&mut self, access_list: &AccessList) {
+        for item in access_list.0.iter() {
+            self.accounts.entry(item.address).or_default().extend(item.storage_keys.iter());
+        }
+    }
+
+    /// Returns whether the plan holds no accounts.
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Returns the planned accounts, in ascending order.
+    pub fn accounts(&self) -> impl Iterator<Item = &Address> {
+        self.accounts.keys()
+    }
+
+    /// Returns the planned storage slots of the account at `address`, in ascending order.
+    pub fn storage_slots(&self, address: &Address) -> impl Iterator<Item = &B256> {
+        self.accounts.get(address).into_iter().flatten()
+    }
+
+    /// Returns the paths of the planned accounts in the state trie, in trie order.
+    pub fn account_paths(&self) -> Vec<Nibbles> {
+        sorted_paths(self.accounts.keys())
+    }
+
+    /// Returns the paths of the planned storage slots of the account at `address` in its storage
+    /// trie, in trie order.
+    pub fn storage_paths(&self, address: &Address) -> Vec<Nibbles> {
+        sorted_paths(self.storage_slots(address))
+    }
+
+    /// Sends one batched storage hint per planned account that has storage slots, so the host can
+    /// prepare the account and its slots in a single request. Accounts without slots are left to
+    /// be hinted node by node when they are opened.
+    pub fn hint<H: TrieHinter>(&self, hinter: &H) -> Result<()> {
+        for (address, slots) in self.accounts.iter().filter(|(_, slots)| !slots.is_empty()) {
+            let slots = slots.iter().copied().collect::<Vec<_>>();
+            hinter.hint_storage_slots(*address, &slots)?;
+        }
+        Ok(())
+    }
+
+    /// Opens the paths of the planned accounts in the state `trie`, in trie order, so that the
+    /// nodes they share are only fetched once.
+    pub fn open_accounts<P: TrieProvider>(
+        &self,
+        trie: &mut TrieNode,
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        open_paths(trie, self.account_paths(), provider)
+    }
+
+    /// Opens the paths of the planned storage slots of the account at `address` in its storage
+    /// `trie`, in trie order.
+    pub fn open_storage<P: TrieProvider>(
+        &self,
+        address: &Address,
+        trie: &mut TrieNode,
+        provider: &P,
+    ) -> Result<(), TrieNodeError> {
+        open_paths(trie, self.storage_paths(address), provider)
+    }
+}
+
+/// Returns the sorted paths of the keys in a secure trie, the nibbles of their [keccak256] hashes.
+fn sorted_paths<'a, T: AsRef<[u8]> + 'a>(keys: impl Iterator<Item = &'a T>) -> Vec<Nibbles> {
+    let mut paths = keys.map(|key| Nibbles::unpack(keccak256(key))).collect::<Vec<_>>();
+    paths.sort_unstable();
+    paths
+}
+
+/// Opens each of the `paths` in the `trie`, keeping the unblinded nodes in place.
+fn open_paths<P: TrieProvider>(
+    trie: &mut TrieNode,
+    paths: Vec<Nibbles>,
+    provider: &P,
+) -> Result<(), TrieNodeError> {
+    paths.iter().try_for_each(|path| trie.open(path, provider).map(|_| ()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MapTrieProvider;
+    use alloc::vec;
+    use alloy_eips::eip2930::AccessListItem;
+    use alloy_primitives::{address, Bytes};
+    use core::cell::RefCell;
+
+    #[derive(Default)]
+    struct MockHinter {
+        hints: RefCell<Vec<(Address, Vec<B256>)>>,
+    }
+
+    impl TrieHinter for MockHinter {
+        fn hint_trie_node(&self, _: B256) -> Result<()> {
+            Ok(())
+        }
+
+        fn hint_code(&self, _: B256, _: Address) -> Result<()> {
+            Ok(())
+        }
+
+        fn hint_storage_slots(&self, address: Address, slots: &[B256]) -> Result<()> {
+            self.hints.borrow_mut().push((address, slots.to_vec()));
+            Ok(())
+        }
+    }
+
+    const ALICE: Address = address!("4200000000000000000000000000000000000015");
+    const BOB: Address = address!("4200000000000000000000000000000000000016");
+
+    #[test]
+    fn test_plan_deduplicates_and_batches_hints() {
+        let access_list = AccessList(vec![
+            AccessListItem {
+                address: ALICE,
+                storage_keys: vec![B256::with_last_byte(2), B256::with_last_byte(1)],
+            },
+            AccessListItem { address: BOB, storage_keys: vec![] },
+            AccessListItem { address: ALICE, storage_keys: vec![B256::with_last_byte(1)] },
+        ]);
+        let mut plan = PrefetchPlan::new();
+        assert!(plan.is_empty());
+        plan.add_access_list(&access_list);
+        plan.add_storage_slot(ALICE, B256::with_last_byte(2));
+        plan.add_account(BOB);
+
+        assert_eq!(plan.accounts().copied().collect::<Vec<_>>(), vec![ALICE, BOB]);
+        assert_eq!(plan.account_paths().len(), 2);
+        assert_eq!(plan.storage_paths(&ALICE).len(), 2);
+        assert!(plan.storage_paths(&BOB).is_empty());
+        assert!(plan.storage_paths(&Address::ZERO).is_empty());
+
+        let hinter = MockHinter::default();
+        plan.hint(&hinter).unwrap();
+        assert_eq!(
+            hinter.hints.into_inner(),
+            vec![(ALICE, vec![B256::with_last_byte(1), B256::with_last_byte(2)])]
+        );
+    }
+
+    #[test]
+    fn test_open_accounts() {
+        let mut trie = TrieNode::Empty;
+        for i in 0..64u8 {
+            let path = Nibbles::unpack(keccak256(Address::with_last_byte(i)));
+            trie.insert(&path, Bytes::from(vec![i; 40]), &MapTrieProvider::new()).unwrap();
+        }
+        let mut provider = MapTrieProvider::new();
+        let root = provider.insert_trie(&trie);
+
+        let mut plan = PrefetchPlan::new();
+        plan.add_account(Address::with_last_byte(3));
+        plan.add_account(Address::with_last_byte(9));
+        plan.add_account(Address::with_last_byte(0xFF));
+
+        let mut state = TrieNode::Blinded { commitment: root };
+        plan.open_accounts(&mut state, &provider).unwrap();
+        assert_eq!(state.root(), root);
+
+        // The planned paths are open, so they are served without the provider.
+        let empty = MapTrieProvider::new();
+        for i in [3, 9] {
+            let path = Nibbles::unpack(keccak256(Address::with_last_byte(i)));
+            assert_eq!(state.open(&path, &empty).unwrap().cloned(), Some(Bytes::from(vec![i; 40])));
+        }
+        let path = Nibbles::unpack(keccak256(Address::with_last_byte(0xFF)));
+        assert_eq!(state.open(&path, &empty).unwrap(), None);
+    }
+}
@@ -0,0 +1,564 @@
+//! Contains [verify_proof] and [verify_range_proof], which check Merkle proofs against a trie root
+//! without a [TrieProvider], and the [AccountProof] type of `eth_getProof` responses.
+
+use crate::{Nibbles, TrieAccount, TrieNode, TrieNodeError, TrieProvider};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use alloy_primitives::{keccak256, Address, Bytes, B256, U256};
+use alloy_rlp::Encodable;
+use anyhow::{anyhow, Result};
+use core::fmt::Display;
+
+/// The number of children of a branch node, excluding its value.
+const BRANCH_CHILDREN: usize = 16;
+
+/// An error returned by [verify_proof] and [verify_range_proof].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProofError {
+    /// A node on the path of the key is missing from the proof, or is malformed.
+    Invalid(String),
+    /// The keys and values of a range proof are malformed.
+    InvalidRange(String),
+    /// The trie reconstructed from a range proof does not match the expected root.
+    RootMismatch {
+        /// The expected root.
+        expected: B256,
+        /// The root of the reconstructed trie.
+        actual: B256,
+    },
+    /// The proof resolves the key to a different value than the expected one.
+    ValueMismatch {
+        /// The expected value of the key.
+        expected: Option<Bytes>,
+        /// The value of the key in the trie.
+        actual: Option<Bytes>,
+    },
+}
+
+impl Display for ProofError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ProofError::Invalid(e) => write!(f, "Invalid proof: {e}"),
+            ProofError::InvalidRange(e) => write!(f, "Invalid range: {e}"),
+            ProofError::RootMismatch { expected, actual } => {
+                write!(f, "Range proof root mismatch, expected {expected}, got {actual}")
+            }
+            ProofError::ValueMismatch { expected, actual } => {
+                write!(f, "Proof value mismatch, expected {expected:?}, got {actual:?}")
+            }
+        }
+    }
+}
+
+/// Verifies that the trie with the given `root` maps `key` to `value`, using only the RLP encoded
+/// nodes of the `proof`. A `value` of `None` verifies that the key is absent from the trie.
+///
+/// The proof is in the format of the `accountProof` and `storageProof` fields of an
+/// `eth_getProof` response ([EIP-1186]), and of [TrieNode::proof]. For account and storage proofs,
+/// the `key` is the [keccak256] hash of the address or storage slot, and the `value` is the RLP
+/// encoded account or slot value.
+///
+/// [EIP-1186]: https://eips.ethereum.org/EIPS/eip-1186
+pub fn verify_proof(
+    root: B256,
+    key: &Nibbles,
+    value: Option<&[u8]>,
+    proof: &[Bytes],
+) -> Result<(), ProofError> {
+    let nodes = ProofNodes::new(proof);
+    let actual = TrieNode::Blinded { commitment: root }
+        .get(key, &nodes)
+        .map_err(|e| ProofError::Invalid(e.to_string()))?;
+
+    if actual.as_deref() != value {
+        return Err(ProofError::ValueMismatch {
+            expected: value.map(Bytes::copy_from_slice),
+            actual,
+        });
+    }
+    Ok(())
+}
+
+/// The proof of a storage slot within an [AccountProof], as in the `storageProof` field of an
+/// `eth_getProof` response ([EIP-1186]).
+///
+/// [EIP-1186]: https://eips.ethereum.org/EIPS/eip-1186
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StorageProof {
+    /// The storage slot, before it is hashed into the path of the storage trie.
+    pub key: B256,
+    /// The value of the slot. Zero if the slot is absent from the storage trie.
+    pub value: U256,
+    /// The RLP encoded nodes on the path of the slot, from the storage root down.
+    pub proof: Vec<Bytes>,
+}
+
+/// The proof of an account and a set of its storage slots against a state root, as returned by
+/// `eth_getProof` ([EIP-1186]).
+///
+/// [EIP-1186]: https://eips.ethereum.org/EIPS/eip-1186
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AccountProof {
+    /// The address of the account.
+    pub address: Address,
+    /// The account. An account that is absent from the state trie is reported as the default,
+    /// empty [TrieAccount].
+    pub account: TrieAccount,
+    /// The RLP encoded nodes on the path of the account, from the state root down.
+    pub account_proof: Vec<Bytes>,
+    /// The proofs of the requested storage slots, against the storage root of the account.
+    pub storage_proofs: Vec<StorageProof>,
+}
+
+impl AccountProof {
+    /// Verifies the account against the `state_root`, and each storage slot against the storage
+    /// root of the account. See [verify_proof].
+    pub fn verify(&self, state_root: B256) -> Result<(), ProofError> {
+        let account = (self.account != TrieAccount::default()).then(|| {
+            let mut rlp_buf = Vec::with_capacity(self.account.length());
+            self.account.encode(&mut rlp_buf);
+            rlp_buf
+        });
+        verify_proof(
+            state_root,
+            &Nibbles::unpack(keccak256(self.address)),
+            account.as_deref(),
+            &self.account_proof,
+        )?;
+
+        for storage_proof in &self.storage_proofs {
+            let value = (storage_proof.value != U256::ZERO).then(|| {
+                let mut rlp_buf = Vec::with_capacity(storage_proof.value.length());
+                storage_proof.value.encode(&mut rlp_buf);
+                rlp_buf
+            });
+            verify_proof(
+                self.account.storage_root,
+                &Nibbles::unpack(keccak256(storage_proof.key)),
+                value.as_deref(),
+                &storage_proof.proof,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// A sub-trie reconstructed by [verify_range_proof].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifiedRange {
+    /// The trie, with the leaves of the range opened and the rest of the trie blinded. Its
+    /// [TrieNode::root] is the root the range was verified against.
+    pub trie: TrieNode,
+    /// Whether the trie holds more leaves to the right of the range.
+    pub has_more: bool,
+}
+
+/// Verifies that the `keys` and `values` are exactly the leaves of the trie with the given `root`
+/// whose keys lie between `first_key` and the last of the `keys`, and reconstructs the sub-trie
+/// holding them. This is the range proof of the snap protocol, as verified by geth's
+/// `VerifyRangeProof`.
+///
+/// The `proof` holds the nodes of the [TrieNode::proof] of `first_key` and of the last key, which
+/// bound the range. The nodes between the two boundary paths are rebuilt from the leaves, and the
+/// resulting root must match `root`. If the proof is empty, the leaves must make up the whole
+/// trie. If the `keys` are empty, the proof must show that the trie holds no leaves at or after
+/// `first_key`.
+///
+/// The `keys` must be strictly increasing, no smaller than `first_key`, and as long as it. Values
+/// must not be empty.
+pub fn verify_range_proof(
+    root: B256,
+    first_key: &Nibbles,
+    keys: &[Nibbles],
+    values: &[Bytes],
+    proof: &[Bytes],
+) -> Result<VerifiedRange, ProofError> {
+    if keys.len() != values.len() {
+        return Err(ProofError::InvalidRange(alloc::format!(
+            "{} keys with {} values",
+            keys.len(),
+            values.len()
+        )));
+    }
+    if values.iter().any(|value| value.is_empty()) {
+        return Err(ProofError::InvalidRange("Empty value".to_string()));
+    }
+    if keys.iter().any(|key| key.len() != first_key.len()) {
+        return Err(ProofError::InvalidRange("Keys differ in length".to_string()));
+    }
+    if keys.first().is_some_and(|key| key < first_key) ||
+        keys.windows(2).any(|pair| pair[0] >= pair[1])
+    {
+        return Err(ProofError::InvalidRange("Keys are not strictly increasing".to_string()));
+    }
+
+    let nodes = ProofNodes::new(proof);
+    let mut trie =
+        if proof.is_empty() { TrieNode::Empty } else { TrieNode::Blinded { commitment: root } };
+
+    // Drop the leaves within the range from the trie described by the proof. Subtrees that lie
+    // entirely within it are dropped without being opened, the others are opened from the proof.
+    let mut has_more = false;
+    if !proof.is_empty() {
+        let last_key = keys.last().unwrap_or(first_key);
+        has_more = clear_range(&mut trie, &mut Vec::new(), first_key, last_key, &nodes)
+            .map_err(|e| ProofError::Invalid(e.to_string()))?;
+        if keys.is_empty() && has_more {
+            return Err(ProofError::InvalidRange(
+                "The trie holds leaves after the first key".to_string(),
+            ));
+        }
+    }
+
+    // Rebuild the range from the leaves.
+    for (key, value) in keys.iter().zip(values) {
+        trie.insert(key, value.clone(), &nodes).map_err(|e| ProofError::Invalid(e.to_string()))?;
+    }
+
+    let actual = trie.root();
+    if actual != root {
+        return Err(ProofError::RootMismatch { expected: root, actual });
+    }
+    Ok(VerifiedRange { trie, has_more })
+}
+
+/// Drops the leaves of the trie rooted at `node`, reached through `path`, whose keys lie within
+/// `[left, right]`. Subtrees that lie entirely within the range are dropped without being
+/// unblinded, and subtrees that straddle a bound are unblinded from the proof `nodes`. Returns
+/// whether the trie holds leaves to the right of the range.
+fn clear_range(
+    node: &mut TrieNode,
+    path: &mut Vec<u8>,
+    left: &[u8],
+    right: &[u8],
+    nodes: &ProofNodes,
+) -> Result<bool, TrieNodeError> {
+    if *node == TrieNode::Empty {
+        return Ok(false);
+    }
+
+    // The smallest and largest keys that can be stored below the path.
+    let padded = |fill: u8| {
+        let mut key = path.clone();
+        key.resize(left.len(), fill);
+        key
+    };
+    let (lowest, highest) = (padded(0x0), padded(0xF));
+    if highest.as_slice() < left {
+        return Ok(false);
+    }
+    if lowest.as_slice() > right {
+        return Ok(true);
+    }
+    if lowest.as_slice() >= left && highest.as_slice() <= right {
+        *node = TrieNode::Empty;
+        return Ok(false);
+    }
+
+    node.unblind(nodes)?;
+    let has_more = match node {
+        TrieNode::Empty => false,
+        TrieNode::Blinded { commitment } => return Err(TrieNodeError::Blinded(*commitment)),
+        TrieNode::Leaf { key, .. } => {
+            let key = [path.as_slice(), key.as_slice()].concat();
+            if key.as_slice() >= left && key.as_slice() <= right {
+                *node = TrieNode::Empty;
+            }
+            key.as_slice() > right
+        }
+        TrieNode::Extension { prefix, node: child } => {
+            let len = path.len();
+            path.extend_from_slice(prefix);
+            let has_more = clear_range(child, path, left, right, nodes)?;
+            path.truncate(len);
+            has_more
+        }
+        TrieNode::Branch { stack } => {
+            let mut has_more = false;
+            for (index, child) in stack.iter_mut().take(BRANCH_CHILDREN).enumerate() {
+                path.push(index as u8);
+                has_more |= clear_range(child, path, left, right, nodes)?;
+                path.pop();
+            }
+            has_more
+        }
+    };
+    Ok(has_more)
+}
+
+/// A [TrieProvider] over the nodes of a proof, keyed by their [keccak256] commitment.
+#[derive(Debug)]
+struct ProofNodes(BTreeMap<B256, Bytes>);
+
+impl ProofNodes {
+    /// Creates a new [ProofNodes] from the RLP encoded nodes of a proof.
+    fn new(proof: &[Bytes]) -> Self {
+        Self(proof.iter().map(|node| (keccak256(node), node.clone())).collect())
+    }
+}
+
+impl TrieProvider for ProofNodes {
+    fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+        self.0.get(&key).cloned().ok_or_else(|| anyhow!("Missing trie node {key}"))
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+        Err(anyhow!("Proofs do not contain bytecode {code_hash}"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec::Vec;
+    use alloy_primitives::bytes;
+    use alloy_trie::{HashBuilder, EMPTY_ROOT_HASH};
+
+    /// Builds a trie keyed by hashed indices, returning its root, its entries, and the proofs of
+    /// all of its entries retained by the [HashBuilder].
+    fn build_trie(len: u8) -> (B256, Vec<(Nibbles, Bytes)>, Vec<Bytes>) {
+        let mut entries = (0..len)
+            .map(|i| (Nibbles::unpack(keccak256([i])), Bytes::from(alloc::vec![i; 1 + i as usize])))
+            .collect::<Vec<_>>();
+        entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut hb = HashBuilder::default()
+            .with_proof_retainer(entries.iter().map(|(key, _)| key.clone()).collect());
+        for (key, value) in &entries {
+            hb.add_leaf(key.clone(), value);
+        }
+        let root = hb.root();
+        (root, entries, hb.take_proofs().into_values().collect())
+    }
+
+    #[test]
+    fn test_verify_proof() {
+        let (root, entries, nodes) = build_trie(16);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        let provider = ProofNodes::new(&nodes);
+
+        for (key, value) in &entries {
+            verify_proof(root, key, Some(&value[..]), &nodes).unwrap();
+
+            let proof = trie.proof(key, &provider).unwrap();
+            verify_proof(root, key, Some(&value[..]), &proof).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_verify_proof_absence() {
+        let (root, _, nodes) = build_trie(16);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        let provider = ProofNodes::new(&nodes);
+
+        let missing = Nibbles::unpack(keccak256([0xFF]));
+        let proof = trie.proof(&missing, &provider).unwrap();
+        verify_proof(root, &missing, None, &proof).unwrap();
+        assert_eq!(
+            verify_proof(root, &missing, Some(&[0x01][..]), &proof),
+            Err(ProofError::ValueMismatch { expected: Some(bytes!("01")), actual: None })
+        );
+
+        verify_proof(EMPTY_ROOT_HASH, &missing, None, &[]).unwrap();
+    }
+
+    #[test]
+    fn test_verify_proof_wrong_value() {
+        let (root, entries, nodes) = build_trie(16);
+        let (key, value) = &entries[0];
+        assert_eq!(
+            verify_proof(root, key, Some(&[0xFF][..]), &nodes),
+            Err(ProofError::ValueMismatch {
+                expected: Some(bytes!("FF")),
+                actual: Some(value.clone())
+            })
+        );
+        assert!(matches!(
+            verify_proof(root, key, None, &nodes),
+            Err(ProofError::ValueMismatch { expected: None, .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_proof_invalid() {
+        let (root, entries, nodes) = build_trie(16);
+        let (key, value) = &entries[0];
+
+        // A proof without the root node.
+        let proof =
+            nodes.iter().filter(|node| keccak256(node) != root).cloned().collect::<Vec<_>>();
+        assert!(matches!(
+            verify_proof(root, key, Some(&value[..]), &proof),
+            Err(ProofError::Invalid(_))
+        ));
+
+        // A proof against a different root.
+        assert!(matches!(
+            verify_proof(B256::ZERO, key, Some(&value[..]), &nodes),
+            Err(ProofError::Invalid(_))
+        ));
+
+        // A proof with a node that is not a trie node.
+        let garbage = bytes!("c0");
+        assert!(matches!(
+            verify_proof(keccak256(&garbage), key, Some(&value[..]), &[garbage]),
+            Err(ProofError::Invalid(_))
+        ));
+    }
+
+    /// Returns the proofs of the two keys bounding a range, in a single list.
+    fn range_proof(
+        root: B256,
+        nodes: &[Bytes],
+        first_key: &Nibbles,
+        last_key: &Nibbles,
+    ) -> Vec<Bytes> {
+        let provider = ProofNodes::new(nodes);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        let mut proof = trie.proof(first_key, &provider).unwrap();
+        proof.extend(trie.proof(last_key, &provider).unwrap());
+        proof
+    }
+
+    #[test]
+    fn test_verify_range_proof() {
+        let (root, entries, nodes) = build_trie(64);
+        let (keys, values): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+
+        for (start, end) in [(0, 1), (0, 10), (5, 6), (10, 40), (32, 64), (63, 64), (0, 64)] {
+            let proof = range_proof(root, &nodes, &keys[start], &keys[end - 1]);
+            let range = verify_range_proof(
+                root,
+                &keys[start],
+                &keys[start..end],
+                &values[start..end],
+                &proof,
+            )
+            .unwrap();
+            assert_eq!(range.has_more, end < keys.len());
+            assert_eq!(range.trie.root(), root);
+
+            // The leaves of the range are opened in the reconstructed trie.
+            let empty = ProofNodes::new(&[]);
+            for (key, value) in keys[start..end].iter().zip(&values[start..end]) {
+                assert_eq!(range.trie.get(key, &empty).unwrap().as_ref(), Some(value));
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_range_proof_absent_first_key() {
+        let (root, entries, nodes) = build_trie(64);
+        let (keys, values): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+
+        // A first key that falls between two leaves.
+        let mut first_key = keys[10].to_vec();
+        first_key[60..].fill(0);
+        let first_key = Nibbles::from_nibbles_unchecked(first_key);
+        assert!(keys[9] < first_key && first_key < keys[10]);
+
+        let proof = range_proof(root, &nodes, &first_key, &keys[19]);
+        let range =
+            verify_range_proof(root, &first_key, &keys[10..20], &values[10..20], &proof).unwrap();
+        assert!(range.has_more);
+
+        // The range must include every leaf after the first key.
+        let proof = range_proof(root, &nodes, &first_key, &keys[19]);
+        assert!(matches!(
+            verify_range_proof(root, &first_key, &keys[11..20], &values[11..20], &proof),
+            Err(ProofError::RootMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn test_verify_range_proof_tampered() {
+        let (root, entries, nodes) = build_trie(64);
+        let (keys, mut values): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        let proof = range_proof(root, &nodes, &keys[10], &keys[19]);
+
+        // A missing leaf in the middle of the range.
+        let (mut range_keys, mut range_values) = (keys[10..20].to_vec(), values[10..20].to_vec());
+        range_keys.remove(5);
+        range_values.remove(5);
+        assert!(matches!(
+            verify_range_proof(root, &keys[10], &range_keys, &range_values, &proof),
+            Err(ProofError::RootMismatch { .. })
+        ));
+
+        // A modified value.
+        values[15] = Bytes::from_static(&[0xFF]);
+        assert!(matches!(
+            verify_range_proof(root, &keys[10], &keys[10..20], &values[10..20], &proof),
+            Err(ProofError::RootMismatch { .. })
+        ));
+
+        // A proof that is missing a boundary path.
+        let mut trie = TrieNode::Blinded { commitment: root };
+        let proof = trie.proof(&keys[10], &ProofNodes::new(&nodes)).unwrap();
+        assert!(matches!(
+            verify_range_proof(root, &keys[10], &keys[10..30], &values[10..30], &proof),
+            Err(ProofError::Invalid(_))
+        ));
+    }
+
+    #[test]
+    fn test_verify_range_proof_malformed() {
+        let (root, entries, nodes) = build_trie(16);
+        let (keys, values): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+        let proof = range_proof(root, &nodes, &keys[0], &keys[3]);
+
+        let mut unsorted = keys[..4].to_vec();
+        unsorted.swap(1, 2);
+        let mut empty_value = values[..4].to_vec();
+        empty_value[1] = Bytes::new();
+        for (first_key, keys, values) in [
+            (&keys[0], &unsorted[..], &values[..4]),
+            (&keys[0], &keys[..4], &empty_value[..]),
+            (&keys[0], &keys[..4], &values[..3]),
+            (&keys[1], &keys[..4], &values[..4]),
+        ] {
+            assert!(matches!(
+                verify_range_proof(root, first_key, keys, values, &proof),
+                Err(ProofError::InvalidRange(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_verify_range_proof_whole_trie() {
+        let (root, entries, _) = build_trie(16);
+        let (keys, values): (Vec<_>, Vec<_>) = entries.into_iter().unzip();
+
+        let range = verify_range_proof(root, &keys[0], &keys, &values, &[]).unwrap();
+        assert!(!range.has_more);
+        assert!(matches!(
+            verify_range_proof(root, &keys[0], &keys[1..], &values[1..], &[]),
+            Err(ProofError::RootMismatch { .. })
+        ));
+        verify_range_proof(EMPTY_ROOT_HASH, &keys[0], &[], &[], &[]).unwrap();
+    }
+
+    #[test]
+    fn test_verify_range_proof_empty_range() {
+        let (root, entries, nodes) = build_trie(16);
+        let keys = entries.into_iter().map(|(key, _)| key).collect::<Vec<_>>();
+
+        // No leaves after the last key.
+        let after_last = Nibbles::from_nibbles_unchecked([0xF; 64]);
+        assert!(keys[15] < after_last);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        let proof = trie.proof(&after_last, &ProofNodes::new(&nodes)).unwrap();
+        let range = verify_range_proof(root, &after_last, &[], &[], &proof).unwrap();
+        assert!(!range.has_more);
+
+        // Leaves after the first key must be part of the range.
+        let proof = trie.proof(&keys[8], &ProofNodes::new(&nodes)).unwrap();
+        assert!(matches!(
+            verify_range_proof(root, &keys[8], &[], &[], &proof),
+            Err(ProofError::InvalidRange(_))
+        ));
+    }
+}
@@ -0,0 +1,219 @@
+//! This module contains the [MapTrieProvider] type, an in-memory [TrieProvider] for tests and
+//! tools that do not have access to the preimage oracle.
+
+use crate::{node::MIN_BLINDED_LENGTH, AccountProof, ProofError, TrieNode, TrieProvider};
+use alloc::{collections::BTreeMap, vec::Vec};
+use alloy_primitives::{keccak256, Bytes, B256};
+use alloy_rlp::Encodable;
+use anyhow::{anyhow, Result};
+
+/// A [MapTrieProvider] serves trie node preimages and bytecode from in-memory maps, keyed by
+/// their [keccak256] hash.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MapTrieProvider {
+    /// The RLP encoded trie nodes, keyed by their commitment.
+    trie_nodes: BTreeMap<B256, Bytes>,
+    /// The contract bytecode, keyed by its code hash.
+    bytecode: BTreeMap<B256, Bytes>,
+    /// The RLP encoded block headers, keyed by their hash.
+    headers: BTreeMap<B256, Bytes>,
+}
+
+impl MapTrieProvider {
+    /// Creates a new, empty [MapTrieProvider].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Inserts the RLP encoded trie `node`, returning its commitment.
+    pub fn insert_trie_node(&mut self, node: Bytes) -> B256 {
+        let commitment = keccak256(&node);
+        self.trie_nodes.insert(commitment, node);
+        commitment
+    }
+
+    /// Inserts the contract `code`, returning its code hash.
+    pub fn insert_bytecode(&mut self, code: Bytes) -> B256 {
+        let code_hash = keccak256(&code);
+        self.bytecode.insert(code_hash, code);
+        code_hash
+    }
+
+    /// Inserts the RLP encoded block `header`, returning its hash.
+    pub fn insert_header(&mut self, header: Bytes) -> B256 {
+        let hash = keccak256(&header);
+        self.headers.insert(hash, header);
+        hash
+    }
+
+    /// Inserts the preimages of the blinded nodes of the opened trie rooted at `root`, and returns
+    /// its root commitment. Nodes that are embedded in their parent are covered by the parent's
+    /// preimage, and blinded nodes within the trie are assumed to be known already.
+    pub fn insert_trie(&mut self, root: &TrieNode) -> B256 {
+        self.insert_opened_node(root, true);
+        root.root()
+    }
+
+    /// Verifies the `proof` of an account and its storage slots against the `state_root` with
+    /// [AccountProof::verify], and inserts all of its nodes. Nothing is inserted if the proof is
+    /// invalid.
+    ///
+    /// Seeding the provider with the `eth_getProof` responses of the accounts and slots an
+    /// execution touches lets it run without a preimage oracle.
+    pub fn insert_account_proof(
+        &mut self,
+        state_root: B256,
+        proof: &AccountProof,
+    ) -> Result<(), ProofError> {
+        proof.verify(state_root)?;
+
+        let storage_nodes = proof.storage_proofs.iter().flat_map(|slot| slot.proof.iter());
+        for node in proof.account_proof.iter().chain(storage_nodes) {
+            self.insert_trie_node(node.clone());
+        }
+        Ok(())
+    }
+
+    /// Recursive helper for [MapTrieProvider::insert_trie].
+    fn insert_opened_node(&mut self, node: &TrieNode, is_root: bool) {
+        match node {
+            TrieNode::Empty | TrieNode::Blinded { .. } => return,
+            TrieNode::Leaf { .. } => {}
+            TrieNode::Extension { node, .. } => self.insert_opened_node(node, false),
+            TrieNode::Branch { stack } => {
+                stack.iter().for_each(|child| self.insert_opened_node(child, false))
+            }
+        }
+
+        if is_root || node.length() >= MIN_BLINDED_LENGTH {
+            let mut rlp_buf = Vec::with_capacity(node.length());
+            node.encode(&mut rlp_buf);
+            self.insert_trie_node(rlp_buf.into());
+        }
+    }
+}
+
+impl TrieProvider for MapTrieProvider {
+    fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+        self.trie_nodes.get(&key).cloned().ok_or_else(|| anyhow!("Unknown trie node {key}"))
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+        self.bytecode
+            .get(&code_hash)
+            .cloned()
+            .ok_or_else(|| anyhow!("Unknown bytecode {code_hash}"))
+    }
+    fn header_by_hash(&self, hash: B256) -> Result<Bytes> {
+        self.headers.get(&hash).cloned().ok_or_else(|| anyhow!("Unknown block header {hash}"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{code_by_hash, Nibbles, NoopTrieHinter, SecureTrie, StorageProof, TrieAccount};
+    use alloc::vec;
+    use alloy_primitives::{Address, U256};
+    use alloy_trie::EMPTY_ROOT_HASH;
+
+    #[test]
+    fn test_insert_trie() {
+        let mut trie = TrieNode::Empty;
+        let scratch = MapTrieProvider::new();
+        for i in 0..32u8 {
+            let key = Nibbles::unpack(keccak256([i]));
+            trie.insert(&key, Bytes::from(vec![i; 1 + i as usize]), &scratch).unwrap();
+        }
+
+        let mut provider = MapTrieProvider::new();
+        let root = provider.insert_trie(&trie);
+        assert_eq!(root, trie.root());
+
+        // The blinded trie can be walked with the inserted preimages alone.
+        let blinded = TrieNode::Blinded { commitment: root };
+        for i in 0..32u8 {
+            let key = Nibbles::unpack(keccak256([i]));
+            assert_eq!(
+                blinded.get(&key, &provider).unwrap(),
+                Some(Bytes::from(vec![i; 1 + i as usize]))
+            );
+        }
+
+        assert_eq!(MapTrieProvider::new().insert_trie(&TrieNode::Empty), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn test_insert_account_proof() {
+        let address = Address::with_last_byte(0x42);
+        let slots =
+            [(B256::with_last_byte(1), U256::from(7)), (B256::with_last_byte(2), U256::MAX)];
+
+        // Build a storage trie for the account, and a state trie holding it among other accounts.
+        let mut storage = SecureTrie::new(EMPTY_ROOT_HASH, MapTrieProvider::new());
+        for (slot, value) in slots {
+            storage.insert(slot, alloy_rlp::encode(value).into()).unwrap();
+        }
+        let account = TrieAccount { nonce: 1, storage_root: storage.root(), ..Default::default() };
+        let mut state = SecureTrie::new(EMPTY_ROOT_HASH, MapTrieProvider::new());
+        for i in 0..32u8 {
+            let other = TrieAccount { nonce: i as u64, ..Default::default() };
+            state.insert(Address::with_last_byte(i), alloy_rlp::encode(other).into()).unwrap();
+        }
+        state.insert(address, alloy_rlp::encode(account).into()).unwrap();
+        let state_root = state.root();
+        let absent = Address::with_last_byte(0xFF);
+        let absent_proof = AccountProof {
+            address: absent,
+            account_proof: state.proof(absent).unwrap(),
+            ..Default::default()
+        };
+
+        let mut proof = AccountProof {
+            address,
+            account,
+            account_proof: state.proof(address).unwrap(),
+            storage_proofs: slots
+                .into_iter()
+                .chain([(B256::with_last_byte(3), U256::ZERO)])
+                .map(|(key, value)| StorageProof { key, value, proof: storage.proof(key).unwrap() })
+                .collect(),
+        };
+
+        // The seeded provider serves the account and its slots.
+        let mut provider = MapTrieProvider::new();
+        provider.insert_account_proof(state_root, &proof).unwrap();
+        let state = SecureTrie::new(state_root, &provider);
+        assert_eq!(state.get(address).unwrap(), Some(alloy_rlp::encode(account).into()));
+        let storage = SecureTrie::new(account.storage_root, &provider);
+        for (slot, value) in slots {
+            assert_eq!(storage.get(slot).unwrap(), Some(alloy_rlp::encode(value).into()));
+        }
+        assert_eq!(storage.get(B256::with_last_byte(3)).unwrap(), None);
+
+        // Tampered proofs are rejected without seeding the provider.
+        proof.storage_proofs[0].value = U256::from(8);
+        let mut provider = MapTrieProvider::new();
+        assert!(matches!(
+            provider.insert_account_proof(state_root, &proof),
+            Err(ProofError::ValueMismatch { .. })
+        ));
+        assert_eq!(provider, MapTrieProvider::new());
+
+        // An absent account is proven by the default account.
+        assert!(provider.insert_account_proof(state_root, &absent_proof).is_ok());
+        assert!(provider.trie_node_preimage(state_root).is_ok());
+    }
+
+    #[test]
+    fn test_insert_bytecode() {
+        let mut provider = MapTrieProvider::new();
+        let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+        let code_hash = provider.insert_bytecode(code.clone());
+
+        let bytecode = code_by_hash(&provider, &NoopTrieHinter, code_hash, Address::ZERO).unwrap();
+        assert_eq!(bytecode.original_bytes(), code);
+        assert!(provider.bytecode_by_hash(B256::ZERO).is_err());
+        assert!(provider.trie_node_preimage(code_hash).is_err());
+    }
+}
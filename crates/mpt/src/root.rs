@@ -0,0 +1,197 @@
+//! This module contains helpers for computing the roots of the ordered lists committed to in a
+//! block header: transactions, receipts, withdrawals, and execution layer requests.
+
+use crate::{trie_from_sorted, Nibbles};
+use alloc::{collections::BTreeMap, vec::Vec};
+use alloy_eips::eip2718::Encodable2718;
+use alloy_primitives::{b256, Bytes, B256};
+use alloy_rlp::{BufMut, Encodable};
+use alloy_trie::EMPTY_ROOT_HASH;
+use sha2::{Digest, Sha256};
+
+/// The requests hash of a block without any execution layer requests, `sha256("")`.
+pub const EMPTY_REQUESTS_HASH: B256 =
+    b256!("e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+
+/// Computes the root of an ordered list trie, keyed by the RLP encoded index of each item, with
+/// the item encoded by `encode`.
+pub fn ordered_trie_root_with_encoder<T, F>(items: &[T], mut encode: F) -> B256
+where
+    F: FnMut(&T, &mut dyn BufMut),
+{
+    if items.is_empty() {
+        return EMPTY_ROOT_HASH;
+    }
+
+    let mut index_buffer = Vec::new();
+    let entries = items
+        .iter()
+        .enumerate()
+        .map(|(index, item)| {
+            index_buffer.clear();
+            index.encode(&mut index_buffer);
+
+            let mut value_buffer = Vec::new();
+            encode(item, &mut value_buffer);
+            (Nibbles::unpack(&index_buffer), Bytes::from(value_buffer))
+        })
+        .collect::<BTreeMap<_, _>>();
+
+    // The RLP encoding of an index determines its length, so no key is a prefix of another.
+    trie_from_sorted(entries).expect("RLP encoded indices are prefix-free").root()
+}
+
+/// Computes the transactions root of a block, encoding each transaction as an [EIP-2718]
+/// envelope.
+///
+/// [EIP-2718]: https://eips.ethereum.org/EIPS/eip-2718
+pub fn compute_transactions_root<T: Encodable2718>(transactions: &[T]) -> B256 {
+    ordered_trie_root_with_encoder(transactions, |tx, buf| tx.encode_2718(buf))
+}
+
+/// Computes the receipts root of a block, encoding each receipt as an [EIP-2718] envelope.
+///
+/// [EIP-2718]: https://eips.ethereum.org/EIPS/eip-2718
+pub fn compute_receipts_root<T: Encodable2718>(receipts: &[T]) -> B256 {
+    ordered_trie_root_with_encoder(receipts, |receipt, buf| receipt.encode_2718(buf))
+}
+
+/// Computes the withdrawals root of a block, as introduced in [EIP-4895]. Each withdrawal is RLP
+/// encoded as a list of its index, validator index, address, and amount.
+///
+/// [EIP-4895]: https://eips.ethereum.org/EIPS/eip-4895
+pub fn compute_withdrawals_root<T: Encodable>(withdrawals: &[T]) -> B256 {
+    ordered_trie_root_with_encoder(withdrawals, |withdrawal, buf| withdrawal.encode(buf))
+}
+
+/// Computes the requests root of a block, as in the original draft of [EIP-7685]. Each request is
+/// given in its typed encoding, `request_type ++ request_data`, and committed to like a
+/// transaction in an ordered list trie.
+///
+/// [EIP-7685]: https://eips.ethereum.org/EIPS/eip-7685
+pub fn compute_requests_root(requests: &[Bytes]) -> B256 {
+    ordered_trie_root_with_encoder(requests, |request, buf| buf.put_slice(request))
+}
+
+/// Computes the requests hash of a block, as activated in Prague by [EIP-7685]. Each entry is the
+/// typed encoding of the requests of one type, `request_type ++ request_data`, in ascending order
+/// of type. Entries without any request data are left out of the commitment.
+///
+/// The hash is `sha256(sha256(requests_0) ++ sha256(requests_1) ++ ...)`.
+///
+/// [EIP-7685]: https://eips.ethereum.org/EIPS/eip-7685
+pub fn compute_requests_hash(requests: &[Bytes]) -> B256 {
+    let mut hasher = Sha256::new();
+    for request in requests.iter().filter(|request| request.len() > 1) {
+        hasher.update(Sha256::digest(request));
+    }
+    B256::from_slice(&hasher.finalize())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::test_utils::ordered_trie_with_encoder;
+    use alloc::vec;
+    use alloy_consensus::{Receipt, ReceiptEnvelope, ReceiptWithBloom, TxEnvelope};
+    use alloy_primitives::{Address, Bloom, Log, LogData};
+    use alloy_rlp::RlpEncodable;
+
+    #[derive(RlpEncodable)]
+    struct Withdrawal {
+        index: u64,
+        validator_index: u64,
+        address: Address,
+        amount: u64,
+    }
+
+    #[test]
+    fn test_compute_withdrawals_root() {
+        let withdrawals = (0..200u64)
+            .map(|i| Withdrawal {
+                index: i,
+                validator_index: i * 7,
+                address: Address::with_last_byte(i as u8),
+                amount: 32_000_000_000 + i,
+            })
+            .collect::<Vec<_>>();
+
+        for len in [1, 2, 127, 128, 129, 200] {
+            let withdrawals = &withdrawals[..len];
+            let mut hb = ordered_trie_with_encoder(withdrawals, |w, buf| w.encode(buf));
+            assert_eq!(compute_withdrawals_root(withdrawals), hb.root());
+        }
+    }
+
+    #[test]
+    fn test_compute_receipts_root() {
+        let receipts = (0..4u64)
+            .map(|i| {
+                let receipt = ReceiptWithBloom::new(
+                    Receipt {
+                        status: i % 2 == 0,
+                        cumulative_gas_used: 21_000 * (i + 1),
+                        logs: vec![Log {
+                            address: Address::with_last_byte(i as u8),
+                            data: LogData::new_unchecked(vec![], Bytes::from(vec![i as u8; 4])),
+                        }],
+                    },
+                    Bloom::default(),
+                );
+                if i % 2 == 0 {
+                    ReceiptEnvelope::Legacy(receipt)
+                } else {
+                    ReceiptEnvelope::Eip1559(receipt)
+                }
+            })
+            .collect::<Vec<_>>();
+
+        let mut hb = ordered_trie_with_encoder(&receipts, |r, buf| r.encode_2718(buf));
+        assert_eq!(compute_receipts_root(&receipts), hb.root());
+    }
+
+    #[test]
+    fn test_compute_empty_roots() {
+        assert_eq!(compute_transactions_root::<TxEnvelope>(&[]), EMPTY_ROOT_HASH);
+        assert_eq!(compute_receipts_root::<ReceiptEnvelope>(&[]), EMPTY_ROOT_HASH);
+        assert_eq!(compute_withdrawals_root::<Withdrawal>(&[]), EMPTY_ROOT_HASH);
+        assert_eq!(compute_requests_root(&[]), EMPTY_ROOT_HASH);
+        assert_eq!(compute_requests_hash(&[]), EMPTY_REQUESTS_HASH);
+    }
+
+    #[test]
+    fn test_compute_requests_root() {
+        let requests = (0..130u8)
+            .map(|i| Bytes::from([&[i % 3][..], &[i; 48][..]].concat()))
+            .collect::<Vec<_>>();
+
+        let mut hb = ordered_trie_with_encoder(&requests, |r, buf| buf.put_slice(r));
+        assert_eq!(compute_requests_root(&requests), hb.root());
+    }
+
+    #[test]
+    fn test_compute_requests_hash() {
+        let requests = [
+            Bytes::from([&[0x00][..], &[0xAA; 48][..]].concat()),
+            // A request type without any requests is skipped.
+            Bytes::from_static(&[0x01]),
+            Bytes::from([&[0x02][..], &[0xBB; 20][..]].concat()),
+        ];
+        assert_eq!(
+            compute_requests_hash(&requests),
+            b256!("7b5d3ae24b28277fa39115713e4592a4ad058f13ecbc6a4d4d4cbd4fa25ab84f")
+        );
+        assert_eq!(compute_requests_hash(&requests[1..2]), EMPTY_REQUESTS_HASH);
+    }
+
+    #[tokio::test]
+    async fn test_compute_live_roots() {
+        let (receipts_root, _, receipts) =
+            crate::test_utils::get_live_derivable_receipts_list().await.unwrap();
+        assert_eq!(compute_receipts_root(&receipts), receipts_root);
+
+        let (transactions_root, _, transactions) =
+            crate::test_utils::get_live_derivable_transactions_list().await.unwrap();
+        assert_eq!(compute_transactions_root(&transactions), transactions_root);
+    }
+}
@@ -0,0 +1,191 @@
+//! This module contains the [SecureTrie] type, which hashes the keys of a trie with [keccak256]
+//! like the state and storage tries of Ethereum.
+
+use crate::{Nibbles, TrieNode, TrieNodeError, TrieProvider};
+use alloc::vec::Vec;
+use alloy_primitives::{keccak256, Bytes, B256, U256};
+use alloy_rlp::Decodable;
+
+/// A [SecureTrie] wraps a [TrieNode] whose paths are the [keccak256] hashes of the keys passed to
+/// it, as in the account trie, keyed by address, and the storage tries, keyed by slot. Blinded
+/// nodes are unblinded through the [TrieProvider] it holds.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SecureTrie<P> {
+    /// The root node of the trie.
+    root: TrieNode,
+    /// The provider used to unblind nodes of the trie.
+    provider: P,
+}
+
+impl<P: TrieProvider> SecureTrie<P> {
+    /// Creates a new [SecureTrie] with the given root commitment, unblinded through `provider`.
+    pub const fn new(root: B256, provider: P) -> Self {
+        Self { root: TrieNode::Blinded { commitment: root }, provider }
+    }
+
+    /// Creates a new [SecureTrie] rooted at the given, possibly opened, [TrieNode].
+    pub const fn from_node(root: TrieNode, provider: P) -> Self {
+        Self { root, provider }
+    }
+
+    /// Returns the value stored at the given key. See [TrieNode::get].
+    pub fn get(&self, key: impl AsRef<[u8]>) -> Result<Option<Bytes>, TrieNodeError> {
+        self.root.get(&hashed_path(key), &self.provider)
+    }
+
+    /// Returns a mutable reference to the value stored at the given key, opening the nodes along
+    /// its path. See [TrieNode::open].
+    pub fn open(&mut self, key: impl AsRef<[u8]>) -> Result<Option<&mut Bytes>, TrieNodeError> {
+        self.root.open(&hashed_path(key), &self.provider)
+    }
+
+    /// Inserts the `value` at the given key. See [TrieNode::insert].
+    pub fn insert(&mut self, key: impl AsRef<[u8]>, value: Bytes) -> Result<(), TrieNodeError> {
+        self.root.insert(&hashed_path(key), value, &self.provider)
+    }
+
+    /// Deletes the value at the given key. See [TrieNode::delete].
+    pub fn delete(&mut self, key: impl AsRef<[u8]>) -> Result<(), TrieNodeError> {
+        self.root.delete(&hashed_path(key), &self.provider)
+    }
+
+    /// Returns the Merkle proof of the given key. See [TrieNode::proof].
+    pub fn proof(&mut self, key: impl AsRef<[u8]>) -> Result<Vec<Bytes>, TrieNodeError> {
+        self.root.proof(&hashed_path(key), &self.provider)
+    }
+
+    /// Returns the value of the storage `slot`, reading the trie as the storage trie of an account.
+    /// Slots that are not present in the trie hold zero.
+    pub fn storage(&self, slot: B256) -> Result<U256, TrieNodeError> {
+        match self.get(slot)? {
+            Some(value) => Ok(U256::decode(&mut value.as_ref())?),
+            None => Ok(U256::ZERO),
+        }
+    }
+
+    /// Writes the `value` to the storage `slot`, reading the trie as the storage trie of an
+    /// account. Values are stored RLP encoded. Writing zero deletes the slot from the trie
+    /// rather than storing a zero leaf, since storage tries never hold zero values.
+    pub fn set_storage(&mut self, slot: B256, value: U256) -> Result<(), TrieNodeError> {
+        if value.is_zero() {
+            return self.delete(slot);
+        }
+        self.insert(slot, alloy_rlp::encode(value).into())
+    }
+
+    /// Returns the root commitment of the trie. See [TrieNode::root].
+    pub fn root(&self) -> B256 {
+        self.root.root()
+    }
+
+    /// Returns the root node of the trie.
+    pub const fn root_node(&self) -> &TrieNode {
+        &self.root
+    }
+
+    /// Returns the provider used to unblind nodes of the trie.
+    pub const fn provider(&self) -> &P {
+        &self.provider
+    }
+
+    /// Consumes the [SecureTrie], returning its root node and provider.
+    pub fn into_parts(self) -> (TrieNode, P) {
+        (self.root, self.provider)
+    }
+}
+
+/// Returns the path of a key in a [SecureTrie], the nibbles of its [keccak256] hash.
+pub(crate) fn hashed_path(key: impl AsRef<[u8]>) -> Nibbles {
+    Nibbles::unpack(keccak256(key))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{verify_proof, MapTrieProvider};
+    use alloc::{collections::BTreeMap, vec};
+    use alloy_primitives::{address, Address};
+    use alloy_trie::{HashBuilder, EMPTY_ROOT_HASH};
+
+    const ADDRESSES: [Address; 4] = [
+        address!("4200000000000000000000000000000000000015"),
+        address!("4200000000000000000000000000000000000016"),
+        address!("deaddeaddeaddeaddeaddeaddeaddeaddead0001"),
+        address!("0000000000000000000000000000000000000000"),
+    ];
+
+    #[test]
+    fn test_secure_trie_hashes_keys() {
+        let mut trie = SecureTrie::new(EMPTY_ROOT_HASH, MapTrieProvider::new());
+        let mut expected = BTreeMap::new();
+        for (i, address) in ADDRESSES.iter().enumerate() {
+            let value = Bytes::from(vec![i as u8 + 1; 40]);
+            trie.insert(address, value.clone()).unwrap();
+            expected.insert(Nibbles::unpack(keccak256(address)), value);
+        }
+
+        let mut hb = HashBuilder::default();
+        expected.iter().for_each(|(key, value)| hb.add_leaf(key.clone(), value));
+        assert_eq!(trie.root(), hb.root());
+
+        for (i, address) in ADDRESSES.iter().enumerate() {
+            assert_eq!(trie.get(address).unwrap(), Some(Bytes::from(vec![i as u8 + 1; 40])));
+        }
+        assert_eq!(trie.get(Address::repeat_byte(0x01)).unwrap(), None);
+    }
+
+    #[test]
+    fn test_secure_trie_storage_zero_deletes() {
+        let mut trie = SecureTrie::new(EMPTY_ROOT_HASH, MapTrieProvider::new());
+        let mut expected = BTreeMap::new();
+        for i in 1..=16u8 {
+            let slot = B256::with_last_byte(i);
+            let value = U256::from(i) << (i as usize * 8);
+            trie.set_storage(slot, value).unwrap();
+            expected.insert(Nibbles::unpack(keccak256(slot)), alloy_rlp::encode(value));
+        }
+
+        // Zeroing a slot removes its leaf, so the root matches a trie without it.
+        for i in (1..=16u8).step_by(3) {
+            let slot = B256::with_last_byte(i);
+            trie.set_storage(slot, U256::ZERO).unwrap();
+            expected.remove(&Nibbles::unpack(keccak256(slot)));
+            assert_eq!(trie.storage(slot).unwrap(), U256::ZERO);
+        }
+        let mut hb = HashBuilder::default();
+        expected.iter().for_each(|(key, value)| hb.add_leaf(key.clone(), value));
+        assert_eq!(trie.root(), hb.root());
+        assert_eq!(trie.storage(B256::with_last_byte(2)).unwrap(), U256::from(2) << 16usize);
+
+        // Zeroing an absent slot leaves the trie untouched.
+        trie.set_storage(B256::with_last_byte(0xFF), U256::ZERO).unwrap();
+        assert_eq!(trie.root(), hb.root());
+
+        for i in 1..=16u8 {
+            trie.set_storage(B256::with_last_byte(i), U256::ZERO).unwrap();
+        }
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+    }
+
+    #[test]
+    fn test_secure_trie_proof_and_delete() {
+        let mut trie = SecureTrie::new(EMPTY_ROOT_HASH, MapTrieProvider::new());
+        for address in ADDRESSES {
+            trie.insert(address, Bytes::from(vec![0xFF; 40])).unwrap();
+        }
+
+        let root = trie.root();
+        let proof = trie.proof(ADDRESSES[0]).unwrap();
+        let path = Nibbles::unpack(keccak256(ADDRESSES[0]));
+        verify_proof(root, &path, Some(&[0xFF; 40][..]), &proof).unwrap();
+
+        *trie.open(ADDRESSES[0]).unwrap().unwrap() = Bytes::from_static(&[0x01]);
+        assert_eq!(trie.get(ADDRESSES[0]).unwrap(), Some(Bytes::from_static(&[0x01])));
+
+        for address in ADDRESSES {
+            trie.delete(address).unwrap();
+        }
+        assert_eq!(trie.root(), EMPTY_ROOT_HASH);
+        assert_eq!(trie.into_parts().0, TrieNode::Empty);
+    }
+}
@@ -0,0 +1,270 @@
+//! Trie-building utilities and [proptest] strategies for testing `kona-mpt`.
+//!
+//! With the `test-utils` feature, downstream crates and external fuzzers can use them to
+//! differential-test the insertions, deletions and roots of a [TrieNode] against the reference
+//! [HashBuilder] of `alloy-trie`.
+
+use crate::TrieNode;
+use alloc::{collections::BTreeMap, vec::Vec};
+use alloy_primitives::{Bytes, B256};
+use alloy_rlp::{BufMut, Encodable};
+use alloy_trie::{HashBuilder, Nibbles};
+use proptest::{
+    collection::{btree_map, vec},
+    prelude::{any, Just, Strategy},
+    sample::subsequence,
+};
+
+#[cfg(test)]
+pub(crate) use live::{get_live_derivable_receipts_list, get_live_derivable_transactions_list};
+
+/// The number of nibbles in the keys generated by [arb_key], the length of a [keccak256] hash.
+///
+/// [keccak256]: alloy_primitives::keccak256
+pub const KEY_NIBBLES: usize = 64;
+
+/// Builds the derivable list trie of the `items` with the reference [HashBuilder], retaining the
+/// proofs of every item. Each item is RLP encoded with `encode`.
+pub fn ordered_trie_with_encoder<T, F>(items: &[T], mut encode: F) -> HashBuilder
+where
+    F: FnMut(&T, &mut dyn BufMut),
+{
+    let mut index_buffer = Vec::new();
+    let mut value_buffer = Vec::new();
+    let items_len = items.len();
+
+    // Store preimages for all intermediates
+    let path_nibbles = (0..items_len)
+        .map(|i| {
+            let i = adjust_index_for_rlp(i, items_len);
+            index_buffer.clear();
+            i.encode(&mut index_buffer);
+            Nibbles::unpack(&index_buffer)
+        })
+        .collect::<Vec<_>>();
+
+    let mut hb = HashBuilder::default().with_proof_retainer(path_nibbles);
+    for i in 0..items_len {
+        let index = adjust_index_for_rlp(i, items_len);
+
+        index_buffer.clear();
+        index.encode(&mut index_buffer);
+
+        value_buffer.clear();
+        encode(&items[index], &mut value_buffer);
+
+        hb.add_leaf(Nibbles::unpack(&index_buffer), &value_buffer);
+    }
+
+    hb
+}
+
+/// Returns the index of the item that is inserted `i`-th into a derivable list trie of `len`
+/// items, matching the insertion order of the [HashBuilder], which requires sorted keys.
+pub const fn adjust_index_for_rlp(i: usize, len: usize) -> usize {
+    if i > 0x7f {
+        i
+    } else if i == 0x7f || i + 1 == len {
+        0
+    } else {
+        i + 1
+    }
+}
+
+/// Computes the root of a trie holding the `entries` with the reference [HashBuilder].
+pub fn reference_root(entries: &BTreeMap<Nibbles, Bytes>) -> B256 {
+    let mut hb = HashBuilder::default();
+    entries.iter().for_each(|(key, value)| hb.add_leaf(key.clone(), value));
+    hb.root()
+}
+
+/// Returns a [Strategy] for trie keys of [KEY_NIBBLES] nibbles, like the hashed keys of the state
+/// and storage tries. Keys of equal length are never prefixes of each other.
+pub fn arb_key() -> impl Strategy<Value = Nibbles> {
+    vec(0u8..16, KEY_NIBBLES).prop_map(Nibbles::from_nibbles_unchecked)
+}
+
+/// Returns a [Strategy] for non-empty trie values of up to 64 bytes. Values shorter than 32 bytes
+/// keep their leaves embedded in their parents.
+pub fn arb_value() -> impl Strategy<Value = Bytes> {
+    vec(any::<u8>(), 1..=64).prop_map(Bytes::from)
+}
+
+/// Returns a [Strategy] for sets of up to `max_len` random key/value pairs.
+pub fn arb_entries(max_len: usize) -> impl Strategy<Value = BTreeMap<Nibbles, Bytes>> {
+    btree_map(arb_key(), arb_value(), 0..=max_len)
+}
+
+/// Returns a [Strategy] for sets of up to `max_len` random key/value pairs, together with a random
+/// subset of their keys in a random order, to delete from a trie holding them.
+pub fn arb_entries_with_deletions(
+    max_len: usize,
+) -> impl Strategy<Value = (BTreeMap<Nibbles, Bytes>, Vec<Nibbles>)> {
+    arb_entries(max_len).prop_flat_map(|entries| {
+        let keys = entries.keys().cloned().collect::<Vec<_>>();
+        let len = keys.len();
+        (Just(entries), subsequence(keys, 0..=len).prop_shuffle())
+    })
+}
+
+/// Builds an opened [TrieNode] holding the `entries`, by inserting them into an empty trie in the
+/// order they are given.
+pub fn trie_from_entries<'a>(
+    entries: impl IntoIterator<Item = (&'a Nibbles, &'a Bytes)>,
+) -> TrieNode {
+    let provider = crate::MapTrieProvider::new();
+    let mut trie = TrieNode::Empty;
+    for (key, value) in entries {
+        // Inserting into an opened trie never fetches from the provider, and keys of equal length
+        // never require a value in a branch.
+        trie.insert(key, value.clone(), &provider).expect("Failed to insert entry");
+    }
+    trie
+}
+
+/// Helpers that fetch live blocks over RPC, for tests only.
+#[cfg(test)]
+mod live {
+    extern crate std;
+
+    use super::ordered_trie_with_encoder;
+    use alloc::{collections::BTreeMap, vec::Vec};
+    use alloy_consensus::{Receipt, ReceiptEnvelope, ReceiptWithBloom, TxEnvelope, TxType};
+    use alloy_primitives::{keccak256, Bytes, Log, B256};
+    use alloy_provider::{network::eip2718::Encodable2718, Provider, ProviderBuilder};
+    use alloy_rpc_types::BlockTransactions;
+    use anyhow::{anyhow, Result};
+    use reqwest::Url;
+
+    const RPC_URL: &str = "https://docs-demo.quiknode.pro/";
+
+    /// Grabs a live merkleized receipts list within a block header.
+    pub(crate) async fn get_live_derivable_receipts_list(
+    ) -> Result<(B256, BTreeMap<B256, Bytes>, Vec<ReceiptEnvelope>)> {
+        // Initialize the provider.
+        let provider = ProviderBuilder::new()
+            .on_http(Url::parse(RPC_URL).expect("invalid rpc url"))
+            .map_err(|e| anyhow!(e))?;
+
+        let block_number = 19005266;
+        let block = provider
+            .get_block(block_number.into(), true)
+            .await
+            .map_err(|e| anyhow!(e))?
+            .ok_or(anyhow!("Missing block"))?;
+        let receipts = provider
+            .get_block_receipts(block_number.into())
+            .await
+            .map_err(|e| anyhow!(e))?
+            .ok_or(anyhow!("Missing receipts"))?;
+
+        let consensus_receipts = receipts
+            .into_iter()
+            .map(|r| {
+                let rpc_receipt = r.inner.as_receipt_with_bloom().expect("Infalliable");
+                let consensus_receipt = ReceiptWithBloom::new(
+                    Receipt {
+                        status: rpc_receipt.receipt.status,
+                        cumulative_gas_used: rpc_receipt.receipt.cumulative_gas_used,
+                        logs: rpc_receipt
+                            .receipt
+                            .logs
+                            .iter()
+                            .map(|l| Log { address: l.address(), data: l.data().clone() })
+                            .collect(),
+                    },
+                    rpc_receipt.logs_bloom,
+                );
+
+                match r.transaction_type() {
+                    TxType::Legacy => ReceiptEnvelope::Legacy(consensus_receipt),
+                    TxType::Eip2930 => ReceiptEnvelope::Eip2930(consensus_receipt),
+                    TxType::Eip1559 => ReceiptEnvelope::Eip1559(consensus_receipt),
+                    TxType::Eip4844 => ReceiptEnvelope::Eip4844(consensus_receipt),
+                }
+            })
+            .collect::<Vec<_>>();
+
+        // Compute the derivable list
+        let mut list =
+            ordered_trie_with_encoder(consensus_receipts.as_ref(), |rlp, buf| rlp.encode_2718(buf));
+        let root = list.root();
+
+        // Sanity check receipts root is correct
+        assert_eq!(block.header.receipts_root, root);
+
+        // Construct the mapping of hashed intermediates -> raw intermediates
+        let preimages =
+            list.take_proofs().into_iter().fold(BTreeMap::default(), |mut acc, (_, value)| {
+                acc.insert(keccak256(value.as_ref()), value);
+                acc
+            });
+
+        Ok((root, preimages, consensus_receipts))
+    }
+
+    /// Grabs a live merkleized transactions list within a block header.
+    pub(crate) async fn get_live_derivable_transactions_list(
+    ) -> Result<(B256, BTreeMap<B256, Bytes>, Vec<TxEnvelope>)> {
+        // Initialize the provider.
+        let provider = ProviderBuilder::new()
+            .on_http(Url::parse(RPC_URL).expect("invalid rpc url"))
+            .map_err(|e| anyhow!(e))?;
+
+        let block_number = 19005266;
+        let block = provider
+            .get_block(block_number.into(), true)
+            .await
+            .map_err(|e| anyhow!(e))?
+            .ok_or(anyhow!("Missing block"))?;
+
+        let BlockTransactions::Full(txs) = block.transactions else {
+            anyhow::bail!("Did not fetch full block");
+        };
+        let consensus_txs = txs
+            .into_iter()
+            .map(|tx| TxEnvelope::try_from(tx).map_err(|e| anyhow!(e)))
+            .collect::<Result<Vec<_>>>()?;
+
+        // Compute the derivable list
+        let mut list =
+            ordered_trie_with_encoder(consensus_txs.as_ref(), |rlp, buf| rlp.encode_2718(buf));
+        let root = list.root();
+
+        // Sanity check transaction root is correct
+        assert_eq!(block.header.transactions_root, root);
+
+        // Construct the mapping of hashed intermediates -> raw intermediates
+        let preimages =
+            list.take_proofs().into_iter().fold(BTreeMap::default(), |mut acc, (_, value)| {
+                acc.insert(keccak256(value.as_ref()), value);
+                acc
+            });
+
+        Ok((root, preimages, consensus_txs))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use proptest::prelude::{prop_assert_eq, proptest};
+
+    proptest! {
+        #[test]
+        fn test_insert_matches_reference(entries in arb_entries(64)) {
+            prop_assert_eq!(trie_from_entries(entries.iter().rev()).root(), reference_root(&entries));
+        }
+
+        #[test]
+        fn test_delete_matches_reference((mut entries, deletions) in arb_entries_with_deletions(64)) {
+            let provider = crate::MapTrieProvider::new();
+            let mut trie = trie_from_entries(&entries);
+            for key in deletions {
+                trie.delete(&key, &provider).unwrap();
+                entries.remove(&key);
+                prop_assert_eq!(trie.root(), reference_root(&entries));
+            }
+        }
+    }
+}
@@ -0,0 +1,353 @@
+//! Contains the [TrieProvider] and [TrieHinter] traits, which define how the trie fetches the
+//! preimages it needs and how it tells the host about them in advance, along with their
+//! asynchronous counterparts and the adapters between the two.
+
+use alloc::boxed::Box;
+use alloy_primitives::{Address, Bytes, B256};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use core::future::Future;
+
+/// The [TrieProvider] trait defines the interface for fetching the preimages of trie nodes and
+/// contract bytecode.
+///
+/// The returned data is untrusted, callers must verify it against the requested hash.
+pub trait TrieProvider {
+    /// Returns the preimage of the trie node with the given [keccak256] commitment.
+    ///
+    /// [keccak256]: alloy_primitives::keccak256
+    fn trie_node_preimage(&self, key: B256) -> Result<Bytes>;
+
+    /// Returns the bytecode with the given code hash.
+    fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes>;
+
+    /// Returns the RLP encoded block header with the given hash, which is its [keccak256]
+    /// preimage.
+    ///
+    /// The default implementation returns an error, for providers that only serve the state.
+    ///
+    /// [keccak256]: alloy_primitives::keccak256
+    fn header_by_hash(&self, hash: B256) -> Result<Bytes> {
+        Err(anyhow!("Block header {hash} is not available"))
+    }
+}
+
+impl<P: TrieProvider + ?Sized> TrieProvider for &P {
+    fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+        (**self).trie_node_preimage(key)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+        (**self).bytecode_by_hash(code_hash)
+    }
+
+    fn header_by_hash(&self, hash: B256) -> Result<Bytes> {
+        (**self).header_by_hash(hash)
+    }
+}
+
+/// The [TrieHinter] trait defines the interface for hinting the host about the preimages the
+/// trie is about to fetch, so that it can prepare them ahead of the request.
+pub trait TrieHinter {
+    /// Hints the host to prepare the preimage of the trie node with the given commitment.
+    fn hint_trie_node(&self, hash: B256) -> Result<()>;
+
+    /// Hints the host to prepare the bytecode with the given code hash, deployed at the given
+    /// address. The address allows the host to fetch the code in a single `eth_getCode` call.
+    fn hint_code(&self, code_hash: B256, address: Address) -> Result<()>;
+
+    /// Hints the host to prepare the storage proofs of all `slots` of the account at `address` at
+    /// once, such as the storage keys of a transaction's access list. This lets the host fetch
+    /// them in a single `eth_getProof` call.
+    ///
+    /// The default implementation sends no hint, leaving the slots to be fetched on demand.
+    fn hint_storage_slots(&self, _: Address, _: &[B256]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A [TrieHinter] that sends no hints, for providers that already hold every preimage.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopTrieHinter;
+
+impl TrieHinter for NoopTrieHinter {
+    fn hint_trie_node(&self, _: B256) -> Result<()> {
+        Ok(())
+    }
+
+    fn hint_code(&self, _: B256, _: Address) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The [AsyncTrieProvider] trait is the asynchronous counterpart of [TrieProvider], for providers
+/// that fetch preimages over the network, such as an RPC backed host.
+///
+/// The returned data is untrusted, callers must verify it against the requested hash.
+#[async_trait]
+pub trait AsyncTrieProvider {
+    /// Returns the preimage of the trie node with the given [keccak256] commitment.
+    ///
+    /// [keccak256]: alloy_primitives::keccak256
+    async fn trie_node_preimage(&self, key: B256) -> Result<Bytes>;
+
+    /// Returns the bytecode with the given code hash.
+    async fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes>;
+
+    /// Returns the RLP encoded block header with the given hash.
+    ///
+    /// The default implementation returns an error, for providers that only serve the state.
+    async fn header_by_hash(&self, hash: B256) -> Result<Bytes> {
+        Err(anyhow!("Block header {hash} is not available"))
+    }
+}
+
+/// The [AsyncTrieHinter] trait is the asynchronous counterpart of [TrieHinter].
+#[async_trait]
+pub trait AsyncTrieHinter {
+    /// Hints the host to prepare the preimage of the trie node with the given commitment.
+    async fn hint_trie_node(&self, hash: B256) -> Result<()>;
+
+    /// Hints the host to prepare the bytecode with the given code hash, deployed at the given
+    /// address.
+    async fn hint_code(&self, code_hash: B256, address: Address) -> Result<()>;
+
+    /// Hints the host to prepare the storage proofs of all `slots` of the account at `address` at
+    /// once.
+    ///
+    /// The default implementation sends no hint, leaving the slots to be fetched on demand.
+    async fn hint_storage_slots(&self, _: Address, _: &[B256]) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// The [BlockOn] trait drives a future to completion from synchronous code, such as with the
+/// `block_on` function of an async runtime.
+pub trait BlockOn {
+    /// Blocks until the `future` resolves, and returns its output.
+    fn block_on<F: Future>(&self, future: F) -> F::Output;
+}
+
+/// A [BlockingTrieProvider] adapts an [AsyncTrieProvider] and [AsyncTrieHinter] into a
+/// [TrieProvider] and [TrieHinter], by blocking on each request with the [BlockOn] executor.
+#[derive(Debug, Clone)]
+pub struct BlockingTrieProvider<P, E> {
+    /// The asynchronous provider.
+    inner: P,
+    /// The executor that drives the requests of the provider.
+    executor: E,
+}
+
+impl<P, E> BlockingTrieProvider<P, E> {
+    /// Creates a new [BlockingTrieProvider] that drives the requests of `inner` with `executor`.
+    pub const fn new(inner: P, executor: E) -> Self {
+        Self { inner, executor }
+    }
+
+    /// Consumes the [BlockingTrieProvider], returning the asynchronous provider.
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+}
+
+impl<P: AsyncTrieProvider, E: BlockOn> TrieProvider for BlockingTrieProvider<P, E> {
+    fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+        self.executor.block_on(self.inner.trie_node_preimage(key))
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+        self.executor.block_on(self.inner.bytecode_by_hash(code_hash))
+    }
+
+    fn header_by_hash(&self, hash: B256) -> Result<Bytes> {
+        self.executor.block_on(self.inner.header_by_hash(hash))
+    }
+}
+
+impl<P: AsyncTrieHinter, E: BlockOn> TrieHinter for BlockingTrieProvider<P, E> {
+    fn hint_trie_node(&self, hash: B256) -> Result<()> {
+        self.executor.block_on(self.inner.hint_trie_node(hash))
+    }
+
+    fn hint_code(&self, code_hash: B256, address: Address) -> Result<()> {
+        self.executor.block_on(self.inner.hint_code(code_hash, address))
+    }
+
+    fn hint_storage_slots(&self, address: Address, slots: &[B256]) -> Result<()> {
+        self.executor.block_on(self.inner.hint_storage_slots(address, slots))
+    }
+}
+
+/// An [AsyncTrieAdapter] adapts a [TrieProvider] and [TrieHinter] into an [AsyncTrieProvider] and
+/// [AsyncTrieHinter], whose requests resolve immediately.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct AsyncTrieAdapter<P>(pub P);
+
+#[async_trait]
+impl<P: TrieProvider + Sync> AsyncTrieProvider for AsyncTrieAdapter<P> {
+    async fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+        self.0.trie_node_preimage(key)
+    }
+
+    async fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+        self.0.bytecode_by_hash(code_hash)
+    }
+
+    async fn header_by_hash(&self, hash: B256) -> Result<Bytes> {
+        self.0.header_by_hash(hash)
+    }
+}
+
+#[async_trait]
+impl<P: TrieHinter + Sync> AsyncTrieHinter for AsyncTrieAdapter<P> {
+    async fn hint_trie_node(&self, hash: B256) -> Result<()> {
+        self.0.hint_trie_node(hash)
+    }
+
+    async fn hint_code(&self, code_hash: B256, address: Address) -> Result<()> {
+        self.0.hint_code(code_hash, address)
+    }
+
+    async fn hint_storage_slots(&self, address: Address, slots: &[B256]) -> Result<()> {
+        self.0.hint_storage_slots(address, slots)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{Nibbles, TrieNode};
+    use alloc::{collections::BTreeMap, vec, vec::Vec};
+    use alloy_primitives::{address, keccak256};
+    use alloy_rlp::Encodable;
+    use anyhow::anyhow;
+    use std::sync::Mutex;
+
+    extern crate std;
+
+    /// An [AsyncTrieProvider] over a map of preimages, recording the hints it receives.
+    #[derive(Default)]
+    struct MockAsyncProvider {
+        preimages: BTreeMap<B256, Bytes>,
+        hints: Mutex<Vec<B256>>,
+    }
+
+    #[async_trait]
+    impl AsyncTrieProvider for MockAsyncProvider {
+        async fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+            tokio::task::yield_now().await;
+            self.preimages.get(&key).cloned().ok_or_else(|| anyhow!("unknown trie node {key}"))
+        }
+
+        async fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+            self.preimages
+                .get(&code_hash)
+                .cloned()
+                .ok_or_else(|| anyhow!("unknown code {code_hash}"))
+        }
+    }
+
+    #[async_trait]
+    impl AsyncTrieHinter for MockAsyncProvider {
+        async fn hint_trie_node(&self, hash: B256) -> Result<()> {
+            self.hints.lock().unwrap().push(hash);
+            Ok(())
+        }
+
+        async fn hint_code(&self, code_hash: B256, _: Address) -> Result<()> {
+            self.hints.lock().unwrap().push(code_hash);
+            Ok(())
+        }
+
+        async fn hint_storage_slots(&self, _: Address, slots: &[B256]) -> Result<()> {
+            self.hints.lock().unwrap().extend_from_slice(slots);
+            Ok(())
+        }
+    }
+
+    /// A [BlockOn] executor backed by a current-thread tokio runtime.
+    struct TokioBlockOn(tokio::runtime::Runtime);
+
+    impl BlockOn for TokioBlockOn {
+        fn block_on<F: Future>(&self, future: F) -> F::Output {
+            self.0.block_on(future)
+        }
+    }
+
+    #[test]
+    fn test_blocking_trie_provider() {
+        let leaf = TrieNode::leaf(Nibbles::unpack(keccak256([0])), Bytes::from(vec![0xFF; 32]));
+        let mut rlp_buf = Vec::new();
+        leaf.encode(&mut rlp_buf);
+        let root = leaf.root();
+
+        let provider = MockAsyncProvider {
+            preimages: BTreeMap::from([(root, rlp_buf.into())]),
+            ..Default::default()
+        };
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let provider = BlockingTrieProvider::new(provider, TokioBlockOn(runtime));
+
+        let mut trie = TrieNode::Blinded { commitment: root };
+        let value = trie.open(&Nibbles::unpack(keccak256([0])), &provider).unwrap().cloned();
+        assert_eq!(value, Some(Bytes::from(vec![0xFF; 32])));
+        assert!(provider.trie_node_preimage(B256::ZERO).is_err());
+
+        provider.hint_trie_node(root).unwrap();
+        provider.hint_code(B256::ZERO, Address::ZERO).unwrap();
+        provider.hint_storage_slots(Address::ZERO, &[B256::with_last_byte(1), root]).unwrap();
+        assert_eq!(
+            *provider.into_inner().hints.lock().unwrap(),
+            vec![root, B256::ZERO, B256::with_last_byte(1), root]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_async_trie_adapter() {
+        #[derive(Default)]
+        struct MockProvider {
+            hints: Mutex<Vec<(B256, Address)>>,
+        }
+
+        impl TrieProvider for MockProvider {
+            fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+                Ok(key.to_vec().into())
+            }
+
+            fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+                Err(anyhow!("unknown bytecode {code_hash}"))
+            }
+        }
+
+        impl TrieHinter for MockProvider {
+            fn hint_trie_node(&self, hash: B256) -> Result<()> {
+                self.hints.lock().unwrap().push((hash, Address::ZERO));
+                Ok(())
+            }
+
+            fn hint_code(&self, code_hash: B256, address: Address) -> Result<()> {
+                self.hints.lock().unwrap().push((code_hash, address));
+                Ok(())
+            }
+
+            fn hint_storage_slots(&self, address: Address, slots: &[B256]) -> Result<()> {
+                self.hints.lock().unwrap().extend(slots.iter().map(|slot| (*slot, address)));
+                Ok(())
+            }
+        }
+
+        let address = address!("4200000000000000000000000000000000000015");
+        let adapter = AsyncTrieAdapter(MockProvider::default());
+        assert_eq!(adapter.trie_node_preimage(B256::ZERO).await.unwrap(), Bytes::from(vec![0; 32]));
+        assert!(adapter.bytecode_by_hash(B256::ZERO).await.is_err());
+        adapter.hint_code(B256::ZERO, address).await.unwrap();
+        adapter.hint_storage_slots(address, &[B256::with_last_byte(1)]).await.unwrap();
+        assert_eq!(
+            *adapter.0.hints.lock().unwrap(),
+            vec![(B256::ZERO, address), (B256::with_last_byte(1), address)]
+        );
+
+        // Hinters that do not batch storage slots ignore them.
+        assert!(NoopTrieHinter.hint_storage_slots(address, &[B256::ZERO]).is_ok());
+    }
+}
@@ -0,0 +1,193 @@
+//! This module contains the [RecordingTrieProvider], which records the preimages fetched through
+//! a [TrieProvider] into a [Witness].
+
+use crate::{MapTrieProvider, TrieHinter, TrieProvider};
+use alloc::{collections::BTreeSet, vec::Vec};
+use alloy_primitives::{keccak256, Address, Bytes, B256};
+use alloy_rlp::{RlpDecodable, RlpEncodable};
+use anyhow::Result;
+use core::cell::RefCell;
+
+/// The preimages fetched through a [RecordingTrieProvider], in the order they were first fetched.
+///
+/// The witness is RLP encoded as `rlp([trie_nodes, bytecode])`, so it can be shipped as a single
+/// blob to a prover that replays the execution with [Witness::to_provider].
+#[derive(Debug, Clone, Default, PartialEq, Eq, RlpEncodable, RlpDecodable)]
+pub struct Witness {
+    /// The RLP encoded trie nodes.
+    pub trie_nodes: Vec<Bytes>,
+    /// The contract bytecode.
+    pub bytecode: Vec<Bytes>,
+}
+
+impl Witness {
+    /// Returns a [MapTrieProvider] that serves the preimages of the witness.
+    pub fn to_provider(&self) -> MapTrieProvider {
+        let mut provider = MapTrieProvider::new();
+        self.trie_nodes.iter().cloned().for_each(|node| {
+            provider.insert_trie_node(node);
+        });
+        self.bytecode.iter().cloned().for_each(|code| {
+            provider.insert_bytecode(code);
+        });
+        provider
+    }
+}
+
+/// A [RecordingTrieProvider] wraps a [TrieProvider], and records every trie node and bytecode
+/// preimage fetched through it into a [Witness]. Hints are forwarded to the inner provider as is.
+///
+/// Each preimage is recorded once, the first time it is fetched. Preimages that do not hash to
+/// the requested key are not recorded, as callers reject them.
+#[derive(Debug)]
+pub struct RecordingTrieProvider<P> {
+    /// The inner provider.
+    inner: P,
+    /// The preimages recorded so far.
+    recorded: RefCell<Recorded>,
+}
+
+/// The state of a [RecordingTrieProvider].
+#[derive(Debug, Default)]
+struct Recorded {
+    /// The commitments of the recorded trie nodes.
+    trie_nodes: BTreeSet<B256>,
+    /// The code hashes of the recorded bytecode.
+    bytecode: BTreeSet<B256>,
+    /// The recorded preimages, in fetch order.
+    witness: Witness,
+}
+
+impl<P> RecordingTrieProvider<P> {
+    /// Creates a new [RecordingTrieProvider] that records the preimages fetched through `inner`.
+    pub fn new(inner: P) -> Self {
+        Self { inner, recorded: RefCell::default() }
+    }
+
+    /// Returns a reference to the inner provider.
+    pub fn inner(&self) -> &P {
+        &self.inner
+    }
+
+    /// Returns the [Witness] recorded so far.
+    pub fn witness(&self) -> Witness {
+        self.recorded.borrow().witness.clone()
+    }
+
+    /// Consumes the [RecordingTrieProvider], returning the inner provider and the recorded
+    /// [Witness].
+    pub fn into_parts(self) -> (P, Witness) {
+        (self.inner, self.recorded.into_inner().witness)
+    }
+}
+
+impl<P: TrieProvider> TrieProvider for RecordingTrieProvider<P> {
+    fn trie_node_preimage(&self, key: B256) -> Result<Bytes> {
+        let preimage = self.inner.trie_node_preimage(key)?;
+        let mut recorded = self.recorded.borrow_mut();
+        if keccak256(&preimage) == key && recorded.trie_nodes.insert(key) {
+            recorded.witness.trie_nodes.push(preimage.clone());
+        }
+        Ok(preimage)
+    }
+
+    fn bytecode_by_hash(&self, code_hash: B256) -> Result<Bytes> {
+        let code = self.inner.bytecode_by_hash(code_hash)?;
+        let mut recorded = self.recorded.borrow_mut();
+        if keccak256(&code) == code_hash && recorded.bytecode.insert(code_hash) {
+            recorded.witness.bytecode.push(code.clone());
+        }
+        Ok(code)
+    }
+
+    fn header_by_hash(&self, hash: B256) -> Result<Bytes> {
+        self.inner.header_by_hash(hash)
+    }
+}
+
+impl<P: TrieHinter> TrieHinter for RecordingTrieProvider<P> {
+    fn hint_trie_node(&self, hash: B256) -> Result<()> {
+        self.inner.hint_trie_node(hash)
+    }
+
+    fn hint_code(&self, code_hash: B256, address: Address) -> Result<()> {
+        self.inner.hint_code(code_hash, address)
+    }
+
+    fn hint_storage_slots(&self, address: Address, slots: &[B256]) -> Result<()> {
+        self.inner.hint_storage_slots(address, slots)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{code_by_hash, Nibbles, NoopTrieHinter, TrieNode};
+    use alloc::vec;
+    use alloy_rlp::{Decodable, Encodable};
+
+    fn key(i: u8) -> Nibbles {
+        Nibbles::unpack(keccak256([i]))
+    }
+
+    #[test]
+    fn test_record_and_replay() {
+        let mut trie = TrieNode::Empty;
+        for i in 0..64u8 {
+            trie.insert(&key(i), Bytes::from(vec![i; 40]), &MapTrieProvider::new()).unwrap();
+        }
+        let mut provider = MapTrieProvider::new();
+        let root = provider.insert_trie(&trie);
+        let code = Bytes::from_static(&[0x60, 0x00, 0x60, 0x00, 0xf3]);
+        let code_hash = provider.insert_bytecode(code.clone());
+
+        let recorder = RecordingTrieProvider::new(provider);
+        let mut trie = TrieNode::Blinded { commitment: root };
+        trie.open(&key(1), &recorder).unwrap();
+        TrieNode::Blinded { commitment: root }.get(&key(2), &recorder).unwrap();
+        code_by_hash(&recorder, &NoopTrieHinter, code_hash, Address::ZERO).unwrap();
+        code_by_hash(&recorder, &NoopTrieHinter, code_hash, Address::ZERO).unwrap();
+
+        let witness = recorder.witness();
+        assert_eq!(witness.bytecode, vec![code]);
+        // The root is fetched first, and recorded once even though both lookups fetched it.
+        assert_eq!(keccak256(&witness.trie_nodes[0]), root);
+        let unique = witness.trie_nodes.iter().map(keccak256).collect::<BTreeSet<_>>();
+        assert_eq!(unique.len(), witness.trie_nodes.len());
+
+        // The witness alone is enough to replay the lookups.
+        let replay = witness.to_provider();
+        for i in [1, 2] {
+            assert_eq!(
+                TrieNode::Blinded { commitment: root }.get(&key(i), &replay).unwrap(),
+                Some(Bytes::from(vec![i; 40]))
+            );
+        }
+        assert!(TrieNode::Blinded { commitment: root }.get(&key(3), &replay).is_err());
+
+        let mut blob = Vec::new();
+        witness.encode(&mut blob);
+        assert_eq!(Witness::decode(&mut blob.as_slice()).unwrap(), witness);
+        assert_eq!(recorder.into_parts().1, witness);
+    }
+
+    #[test]
+    fn test_mismatched_preimages_are_not_recorded() {
+        struct LyingProvider;
+
+        impl TrieProvider for LyingProvider {
+            fn trie_node_preimage(&self, _: B256) -> Result<Bytes> {
+                Ok(Bytes::from_static(&[0xc0]))
+            }
+
+            fn bytecode_by_hash(&self, _: B256) -> Result<Bytes> {
+                Ok(Bytes::from_static(&[0x00]))
+            }
+        }
+
+        let recorder = RecordingTrieProvider::new(LyingProvider);
+        assert!(recorder.trie_node_preimage(B256::ZERO).is_ok());
+        assert!(recorder.bytecode_by_hash(B256::ZERO).is_ok());
+        assert_eq!(recorder.witness(), Witness::default());
+    }
+}
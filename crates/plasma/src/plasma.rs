@@ -7,7 +7,10 @@ use alloc::{boxed::Box, fmt::Debug};
 use alloy_primitives::{Address, Bytes};
 use anyhow::Result;
 use async_trait::async_trait;
-use kona_derive::traits::{ChainProvider, DataAvailabilityProvider};
+use kona_derive::{
+    traits::{ChainProvider, DataAvailabilityProvider},
+    types::DataSourceItem,
+};
 use kona_primitives::BlockInfo;
 
 /// The plasma data source implements the [DataAvailabilityProvider] trait for the Plasma source.
@@ -45,7 +48,7 @@ where
     F: PlasmaInputFetcher<C> + Clone + Debug + Send + Sync,
     I: Iterator<Item = Bytes> + Send + Clone + Debug + Sync,
 {
-    type Item = Bytes;
+    type Item = DataSourceItem;
     type DataIter = PlasmaSource<C, F, I>;
 
     async fn open_data(&self, block_ref: &BlockInfo, _: Address) -> Result<Self::DataIter> {
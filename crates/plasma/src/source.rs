@@ -7,12 +7,12 @@ use crate::{
     },
 };
 use alloc::boxed::Box;
-use alloy_primitives::Bytes;
+use alloy_primitives::{Bytes, B256};
 use anyhow::anyhow;
 use async_trait::async_trait;
 use kona_derive::{
     traits::{AsyncIterator, ChainProvider},
-    types::{ResetError, StageError, StageResult},
+    types::{DataKind, DataSourceItem, ResetError, StageError, StageResult},
 };
 use kona_primitives::block::BlockID;
 
@@ -55,7 +55,7 @@ where
     F: PlasmaInputFetcher<C> + Send,
     I: Iterator<Item = Bytes> + Send,
 {
-    type Item = Bytes;
+    type Item = DataSourceItem;
 
     async fn next(&mut self) -> Option<StageResult<Self::Item>> {
         // Process origin syncs the challenge contract events and updates the local challenge states
@@ -103,7 +103,7 @@ where
             // steps validate and potentially parse it as L1 DA inputs.
             if data[0] != TX_DATA_VERSION_1 {
                 tracing::info!("non-plasma tx data, forwarding downstream");
-                return Some(Ok(data));
+                return Some(Ok(DataSourceItem::new(data, B256::ZERO, DataKind::Calldata)));
             }
 
             // Validate that the batcher inbox data is a commitment.
@@ -180,7 +180,7 @@ where
         // Reset the commitment so we can fetch the next one from the source at the next iteration.
         self.commitment = None;
 
-        return Some(Ok(data));
+        return Some(Ok(DataSourceItem::new(data, B256::ZERO, DataKind::Plasma)));
     }
 }
 
@@ -277,7 +277,7 @@ mod tests {
         let mut plasma_source = PlasmaSource::new(chain_provider, input_fetcher, source, id);
 
         let data = plasma_source.next().await.unwrap().unwrap();
-        assert_eq!(data, first);
+        assert_eq!(data, DataSourceItem::new(first, B256::ZERO, DataKind::Calldata));
 
         let logs = trace_store.get_by_level(Level::INFO);
         assert_eq!(logs.len(), 1);
@@ -0,0 +1,54 @@
+//! Benchmarks of preimage oracle round trips over the file descriptor pipes and over the shared
+//! memory pipes.
+
+use alloy_primitives::keccak256;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use kona_common::FileDescriptor;
+use kona_preimage::{
+    OracleReader, OracleServer, Pipe, PipeHandle, PreimageKey, PreimageKeyType,
+    PreimageOracleClient, PreimageOracleServer, ShmPipe,
+};
+use std::{
+    os::{fd::AsRawFd, unix::net::UnixStream},
+    thread,
+};
+
+/// The sizes of the preimages requested, from a trie node reference to a large trie node.
+const SIZES: [usize; 3] = [32, 532, 4096];
+
+/// Serves the `preimage` for every request on `server` from a background thread, until the client
+/// end of the pipe goes away.
+fn spawn_host<P: Pipe + Send + 'static>(server: OracleServer<P>, preimage: Vec<u8>) {
+    thread::spawn(move || while server.next_preimage_request(|_| Ok(&preimage)).is_ok() {});
+}
+
+fn bench_pipes(c: &mut Criterion) {
+    let mut group = c.benchmark_group("oracle_round_trip");
+    for size in SIZES {
+        let preimage = vec![0xAA; size];
+        let key = PreimageKey::new(*keccak256(&preimage), PreimageKeyType::Keccak256);
+        group.throughput(Throughput::Bytes(size as u64));
+
+        // The sockets are kept open for the whole benchmark, and leaked afterwards, so the host
+        // thread never reads from a closed descriptor.
+        let (client, host) = UnixStream::pair().unwrap();
+        let fd = |stream: &UnixStream| FileDescriptor::Wildcard(stream.as_raw_fd() as usize);
+        spawn_host(OracleServer::new(PipeHandle::new(fd(&host), fd(&host))), preimage.clone());
+        let reader = OracleReader::new(PipeHandle::new(fd(&client), fd(&client)));
+        group.bench_with_input(BenchmarkId::new("fd", size), &key, |b, key| {
+            b.iter(|| reader.get(*key).unwrap())
+        });
+        std::mem::forget((client, host));
+
+        let (client, host) = ShmPipe::pair(1 << 16).unwrap();
+        spawn_host(OracleServer::new(host), preimage);
+        let reader = OracleReader::new(client);
+        group.bench_with_input(BenchmarkId::new("shm", size), &key, |b, key| {
+            b.iter(|| reader.get(*key).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_pipes);
+criterion_main!(benches);
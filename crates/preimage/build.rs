@@ -0,0 +1,26 @@
+//! Generates the gRPC client and server of the `grpc` transport, whose messages are defined by hand
+//! in `src/grpc.rs`.
+
+fn main() {
+    #[cfg(feature = "grpc")]
+    {
+        use tonic_build::manual::{Builder, Method, Service};
+
+        let method = |name: &str, route_name: &str, input: &str, output: &str| {
+            Method::builder()
+                .name(name)
+                .route_name(route_name)
+                .input_type(format!("crate::grpc::{input}"))
+                .output_type(format!("crate::grpc::{output}"))
+                .codec_path("tonic::codec::ProstCodec")
+                .build()
+        };
+        let service = Service::builder()
+            .name("PreimageService")
+            .package("kona.preimage.v1")
+            .method(method("get_preimage", "GetPreimage", "PreimageRequest", "PreimageResponse"))
+            .method(method("hint", "Hint", "HintRequest", "HintResponse"))
+            .build();
+        Builder::new().compile(&[service]);
+    }
+}
@@ -0,0 +1,377 @@
+//! This module contains the [CachingOracle], a [PreimageOracleClient] wrapper that memoizes the
+//! preimages fetched from the host in a size-bounded LRU cache.
+
+use crate::{HintWriterClient, PreimageKey, PreimageOracleClient, PreimageOracleError};
+use alloc::vec::Vec;
+use anyhow::{anyhow, Result};
+use lru::LruCache;
+use spin::Mutex;
+
+#[cfg(feature = "tokio")]
+use crate::{AsyncHintWriterClient, AsyncPreimageOracleClient, Hint};
+#[cfg(feature = "tokio")]
+use alloc::{boxed::Box, sync::Arc};
+#[cfg(feature = "tokio")]
+use async_trait::async_trait;
+#[cfg(feature = "tokio")]
+use std::collections::HashMap;
+#[cfg(feature = "tokio")]
+use tokio::sync::OnceCell;
+
+/// A [CachingOracle] wraps a [PreimageOracleClient], or with the `tokio` feature an
+/// [AsyncPreimageOracleClient], and caches the preimages it fetches in an LRU cache bounded by the
+/// total size of the preimages.
+///
+/// Asynchronous requests for a key that is already being fetched wait on the in-flight request,
+/// rather than fetching the same preimage again. Hints are passed through to the inner client.
+#[derive(Debug)]
+pub struct CachingOracle<C> {
+    /// The wrapped client.
+    inner: C,
+    /// The cached preimages.
+    cache: Mutex<PreimageCache>,
+    /// The requests being fetched by the inner client, shared by concurrent requests of the same
+    /// key.
+    #[cfg(feature = "tokio")]
+    in_flight: Mutex<HashMap<PreimageKey, Arc<OnceCell<Vec<u8>>>>>,
+}
+
+impl<C> CachingOracle<C> {
+    /// Creates a new [CachingOracle] over `inner`, caching up to `max_size` bytes of preimages.
+    pub fn new(inner: C, max_size: usize) -> Self {
+        Self {
+            inner,
+            cache: Mutex::new(PreimageCache { entries: LruCache::unbounded(), size: 0, max_size }),
+            #[cfg(feature = "tokio")]
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Returns a reference to the wrapped client.
+    pub fn inner(&self) -> &C {
+        &self.inner
+    }
+
+    /// Consumes the [CachingOracle], returning the wrapped client.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+
+    /// Returns the total size of the cached preimages, in bytes.
+    pub fn cached_size(&self) -> usize {
+        self.cache.lock().size
+    }
+
+    /// Returns a copy of the cached preimage of `key`, marking it as the most recently used.
+    fn cached(&self, key: PreimageKey) -> Option<Vec<u8>> {
+        let cached = self.cache.lock().entries.get(&key).cloned();
+        #[cfg(feature = "metrics")]
+        match cached {
+            Some(_) => metrics::counter!("kona_preimage_cache_hits_total").increment(1),
+            None => metrics::counter!("kona_preimage_cache_misses_total").increment(1),
+        }
+        cached
+    }
+
+    /// Returns the length of the cached preimage of `key`, if any.
+    fn cached_len(&self, key: PreimageKey) -> Option<usize> {
+        self.cache.lock().entries.peek(&key).map(Vec::len)
+    }
+
+    /// Caches the preimage of `key`.
+    fn insert(&self, key: PreimageKey, data: Vec<u8>) {
+        self.cache.lock().insert(key, data)
+    }
+}
+
+/// An LRU cache of preimages, bounded by their total size.
+#[derive(Debug)]
+struct PreimageCache {
+    /// The cached preimages, from the most to the least recently used.
+    entries: LruCache<PreimageKey, Vec<u8>>,
+    /// The total size of the cached preimages, in bytes.
+    size: usize,
+    /// The maximum total size of the cached preimages, in bytes.
+    max_size: usize,
+}
+
+impl PreimageCache {
+    /// Caches the preimage of `key`, evicting the least recently used preimages until the cache
+    /// fits in its maximum size. Preimages larger than the whole cache are not cached.
+    fn insert(&mut self, key: PreimageKey, data: Vec<u8>) {
+        if data.len() > self.max_size {
+            return;
+        }
+
+        self.size += data.len();
+        if let Some(replaced) = self.entries.put(key, data) {
+            self.size -= replaced.len();
+        }
+        while self.size > self.max_size {
+            let Some((_, evicted)) = self.entries.pop_lru() else { break };
+            self.size -= evicted.len();
+        }
+    }
+}
+
+/// Copies the `data` into `buf`, erroring if their lengths differ.
+fn copy_exact(data: &[u8], buf: &mut [u8]) -> Result<()> {
+    if buf.len() != data.len() {
+        return Err(anyhow!(PreimageOracleError::LengthMismatch {
+            expected: buf.len(),
+            actual: data.len()
+        }));
+    }
+    buf.copy_from_slice(data);
+    Ok(())
+}
+
+impl<C: PreimageOracleClient> PreimageOracleClient for CachingOracle<C> {
+    fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        if let Some(data) = self.cached(key) {
+            return Ok(data);
+        }
+
+        let data = self.inner.get(key)?;
+        self.insert(key, data.clone());
+        Ok(data)
+    }
+
+    fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        copy_exact(&PreimageOracleClient::get(self, key)?, buf)
+    }
+
+    fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        match self.cached_len(key) {
+            Some(length) => Ok(length),
+            None => self.inner.preimage_len(key),
+        }
+    }
+
+    fn get_many(&self, keys: &[PreimageKey]) -> Result<Vec<Vec<u8>>> {
+        // Only the preimages missing from the cache are requested from the inner client, in a
+        // single batch.
+        let mut preimages = keys.iter().map(|key| self.cached(*key)).collect::<Vec<_>>();
+        let missing = keys
+            .iter()
+            .zip(&preimages)
+            .filter_map(|(key, cached)| cached.is_none().then_some(*key))
+            .collect::<Vec<_>>();
+        let mut fetched = self.inner.get_many(&missing)?.into_iter();
+
+        for (key, preimage) in keys.iter().zip(preimages.iter_mut()) {
+            if preimage.is_none() {
+                let data = fetched.next().ok_or(anyhow!("Missing preimage for key {key}"))?;
+                self.insert(*key, data.clone());
+                *preimage = Some(data);
+            }
+        }
+        Ok(preimages.into_iter().flatten().collect())
+    }
+
+    fn try_get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+        if let Some(data) = self.cached(key) {
+            return Ok(Some(data));
+        }
+
+        let data = self.inner.try_get(key)?;
+        if let Some(data) = &data {
+            self.insert(key, data.clone());
+        }
+        Ok(data)
+    }
+}
+
+impl<C: HintWriterClient> HintWriterClient for CachingOracle<C> {
+    fn write(&self, hint: &str) -> Result<()> {
+        self.inner.write(hint)
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[async_trait]
+impl<C> AsyncPreimageOracleClient for CachingOracle<C>
+where
+    C: AsyncPreimageOracleClient + Send + Sync,
+{
+    async fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        if let Some(data) = self.cached(key) {
+            return Ok(data);
+        }
+
+        // Join the in-flight request for the key, or start a new one.
+        let request = self.in_flight.lock().entry(key).or_default().clone();
+        let result = request.get_or_try_init(|| self.inner.get(key)).await.cloned();
+        if let Ok(data) = &result {
+            self.insert(key, data.clone());
+        }
+
+        // The request is no longer in flight once its preimage is cached. A failed request is
+        // dropped as well, so that the next request for the key retries it.
+        let mut in_flight = self.in_flight.lock();
+        if matches!(in_flight.get(&key), Some(current) if Arc::ptr_eq(current, &request)) {
+            in_flight.remove(&key);
+        }
+        result
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        copy_exact(&AsyncPreimageOracleClient::get(self, key).await?, buf)
+    }
+
+    async fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        match self.cached_len(key) {
+            Some(length) => Ok(length),
+            None => self.inner.preimage_len(key).await,
+        }
+    }
+}
+
+#[cfg(feature = "tokio")]
+#[async_trait]
+impl<C> AsyncHintWriterClient for CachingOracle<C>
+where
+    C: AsyncHintWriterClient + Send + Sync,
+{
+    async fn write(&self, hint: &str) -> Result<()> {
+        self.inner.write(hint).await
+    }
+
+    async fn write_hint(&self, hint: &Hint) -> Result<()> {
+        self.inner.write_hint(hint).await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::CachingOracle;
+    use crate::{PreimageKey, PreimageKeyType, PreimageOracleClient, PreimageOracleError};
+    use alloc::{vec, vec::Vec};
+    use anyhow::Result;
+    use core::cell::RefCell;
+
+    /// A client serving `[key[31]; key[31]]` for each key, recording the keys it fetches.
+    #[derive(Default)]
+    struct MockClient {
+        fetches: RefCell<Vec<PreimageKey>>,
+    }
+
+    impl PreimageOracleClient for MockClient {
+        fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+            self.fetches.borrow_mut().push(key);
+            let byte = <[u8; 32]>::from(key)[31];
+            Ok(vec![byte; byte as usize])
+        }
+
+        fn get_exact(&self, _: PreimageKey, _: &mut [u8]) -> Result<()> {
+            unimplemented!()
+        }
+
+        fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+            Ok(<[u8; 32]>::from(key)[31] as usize)
+        }
+    }
+
+    fn key(byte: u8) -> PreimageKey {
+        let mut key = [0u8; 32];
+        key[31] = byte;
+        PreimageKey::new(key, PreimageKeyType::Keccak256)
+    }
+
+    #[test]
+    fn test_caching_oracle_memoizes() {
+        let oracle = CachingOracle::new(MockClient::default(), 64);
+        assert_eq!(oracle.get(key(8)).unwrap(), vec![8; 8]);
+        assert_eq!(oracle.get(key(8)).unwrap(), vec![8; 8]);
+        let mut buf = [0u8; 8];
+        oracle.get_exact(key(8), &mut buf).unwrap();
+        assert_eq!(buf, [8; 8]);
+        assert_eq!(
+            oracle
+                .get_exact(key(8), &mut [0u8; 4])
+                .unwrap_err()
+                .downcast_ref::<PreimageOracleError>(),
+            Some(&PreimageOracleError::LengthMismatch { expected: 4, actual: 8 })
+        );
+        assert_eq!(oracle.preimage_len(key(8)).unwrap(), 8);
+        assert_eq!(oracle.cached_size(), 8);
+
+        // Only the missing preimages are fetched by a batch.
+        let batch = oracle.get_many(&[key(1), key(8), key(2)]).unwrap();
+        assert_eq!(batch, vec![vec![1], vec![8; 8], vec![2; 2]]);
+        assert_eq!(*oracle.inner().fetches.borrow(), vec![key(8), key(1), key(2)]);
+    }
+
+    #[test]
+    fn test_caching_oracle_evicts_least_recently_used() {
+        let oracle = CachingOracle::new(MockClient::default(), 64);
+        oracle.get(key(30)).unwrap();
+        oracle.get(key(20)).unwrap();
+        oracle.get(key(30)).unwrap();
+
+        // Caching the third preimage evicts the least recently used one.
+        oracle.get(key(25)).unwrap();
+        assert_eq!(oracle.cached_size(), 55);
+        oracle.get(key(30)).unwrap();
+        oracle.get(key(20)).unwrap();
+        assert_eq!(oracle.cached_size(), 50);
+
+        // Preimages larger than the cache are never cached.
+        oracle.get(key(65)).unwrap();
+        oracle.get(key(65)).unwrap();
+
+        let fetches = oracle.into_inner().fetches.into_inner();
+        assert_eq!(fetches, vec![key(30), key(20), key(25), key(20), key(65), key(65)]);
+    }
+
+    #[cfg(feature = "tokio")]
+    #[tokio::test]
+    async fn test_caching_oracle_deduplicates_in_flight_requests() {
+        use crate::AsyncPreimageOracleClient;
+        use alloc::boxed::Box;
+        use anyhow::anyhow;
+        use async_trait::async_trait;
+        use core::sync::atomic::{AtomicUsize, Ordering};
+
+        /// A slow client counting the requests it serves, failing the first one.
+        #[derive(Default)]
+        struct SlowClient {
+            fetches: AtomicUsize,
+        }
+
+        #[async_trait]
+        impl AsyncPreimageOracleClient for SlowClient {
+            async fn get(&self, _: PreimageKey) -> Result<Vec<u8>> {
+                tokio::task::yield_now().await;
+                if self.fetches.fetch_add(1, Ordering::SeqCst) == 0 {
+                    return Err(anyhow!("Host unavailable"));
+                }
+                Ok(vec![0xAA; 4])
+            }
+
+            async fn get_exact(&self, _: PreimageKey, _: &mut [u8]) -> Result<()> {
+                unimplemented!()
+            }
+
+            async fn preimage_len(&self, _: PreimageKey) -> Result<usize> {
+                unimplemented!()
+            }
+        }
+
+        let oracle = CachingOracle::new(SlowClient::default(), 64);
+        assert!(AsyncPreimageOracleClient::get(&oracle, key(1)).await.is_err());
+
+        let (a, b, c) = tokio::join!(
+            AsyncPreimageOracleClient::get(&oracle, key(1)),
+            AsyncPreimageOracleClient::get(&oracle, key(1)),
+            AsyncPreimageOracleClient::get(&oracle, key(1)),
+        );
+        assert_eq!(
+            (a.unwrap(), b.unwrap(), c.unwrap()),
+            (vec![0xAA; 4], vec![0xAA; 4], vec![0xAA; 4])
+        );
+        assert_eq!(AsyncPreimageOracleClient::get(&oracle, key(1)).await.unwrap(), vec![0xAA; 4]);
+        assert_eq!(oracle.inner().fetches.load(Ordering::SeqCst), 2);
+        assert!(oracle.in_flight.lock().is_empty());
+    }
+}
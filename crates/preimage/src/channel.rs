@@ -0,0 +1,426 @@
+//! This module contains in-memory transports for the preimage and hint pipes, backed by a tokio
+//! [DuplexStream]. They speak the same wire format as the file descriptor pipes, so the host can
+//! run the client program natively in-process for tests and fast iteration.
+
+use crate::{
+    oracle::PendingPreimage, AsyncHintWriterClient, AsyncPreimageOracleClient, Hint, PreimageKey,
+    PreimageOracleError,
+};
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use anyhow::{anyhow, bail, Result};
+use async_trait::async_trait;
+use tokio::{
+    io::{duplex, split, AsyncReadExt, AsyncWriteExt, DuplexStream},
+    sync::Mutex,
+};
+use tracing::{debug, error};
+
+/// Creates a connected pair of a [ChannelOracleClient] and a [ChannelOracleServer]. Up to
+/// `max_buf_size` bytes can be in flight in each direction before writes wait on the other end.
+pub fn preimage_channel(max_buf_size: usize) -> (ChannelOracleClient, ChannelOracleServer) {
+    let (client, server) = duplex(max_buf_size);
+    (
+        ChannelOracleClient {
+            inner: Mutex::new(ClientState { stream: client, pending: PendingPreimage::default() }),
+        },
+        ChannelOracleServer { stream: Mutex::new(server) },
+    )
+}
+
+/// Creates a connected pair of a [ChannelHintWriter] and a [ChannelHintReader]. Up to
+/// `max_buf_size` bytes can be in flight in each direction before writes wait on the other end.
+pub fn hint_channel(max_buf_size: usize) -> (ChannelHintWriter, ChannelHintReader) {
+    let (client, server) = duplex(max_buf_size);
+    (
+        ChannelHintWriter { stream: Mutex::new(client) },
+        ChannelHintReader { stream: Mutex::new(server) },
+    )
+}
+
+/// A [ChannelOracleClient] is the client end of an in-memory preimage channel, the counterpart of
+/// the [crate::OracleReader].
+#[derive(Debug)]
+pub struct ChannelOracleClient {
+    /// The client end of the channel. Holding the lock for a whole request keeps concurrent
+    /// requests from interleaving on the channel.
+    inner: Mutex<ClientState>,
+}
+
+/// The state of a [ChannelOracleClient].
+#[derive(Debug)]
+struct ClientState {
+    /// The client end of the channel.
+    stream: DuplexStream,
+    /// The preimage whose length prefix has been read from the host, but whose data is still
+    /// waiting in the channel.
+    pending: PendingPreimage,
+}
+
+impl ClientState {
+    /// Sends the key to the host and reads back the length of its preimage, unless the length of
+    /// the same key is already pending. The data of any other pending preimage is discarded.
+    async fn write_key(&mut self, key: PreimageKey) -> Result<usize> {
+        let (reused, stale) = self.pending.take(Some(key));
+        self.discard(stale).await?;
+        if let Some(length) = reused {
+            return Ok(length);
+        }
+
+        let key_bytes: [u8; 32] = key.into();
+        self.stream.write_all(&key_bytes).await.map_err(|e| anyhow!(e))?;
+        let length = self.stream.read_u64().await.map_err(|e| anyhow!(e))?;
+        Ok(length as usize)
+    }
+
+    /// Reads and discards `length` bytes of preimage data from the channel.
+    async fn discard(&mut self, mut length: usize) -> Result<()> {
+        let mut scratch = [0u8; 256];
+        while length > 0 {
+            let chunk = length.min(scratch.len());
+            self.stream.read_exact(&mut scratch[..chunk]).await.map_err(|e| anyhow!(e))?;
+            length -= chunk;
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl AsyncPreimageOracleClient for ChannelOracleClient {
+    async fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        debug!(target: "oracle_client", "Requesting data from preimage channel. Key {key}");
+
+        let mut state = self.inner.lock().await;
+        let length = state.write_key(key).await?;
+        let mut data_buffer = vec![0; length];
+        state.stream.read_exact(&mut data_buffer).await.map_err(|e| anyhow!(e))?;
+        Ok(data_buffer)
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        debug!(target: "oracle_client", "Requesting data from preimage channel. Key {key}");
+
+        let mut state = self.inner.lock().await;
+        let length = state.write_key(key).await?;
+
+        // The preimage data stays in the channel, so that it can still be read with a correctly
+        // sized buffer.
+        if buf.len() != length {
+            state.pending.set(key, length);
+            return Err(anyhow!(PreimageOracleError::LengthMismatch {
+                expected: buf.len(),
+                actual: length
+            }));
+        }
+
+        state.stream.read_exact(buf).await.map_err(|e| anyhow!(e))?;
+        Ok(())
+    }
+
+    async fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        let mut state = self.inner.lock().await;
+        let length = state.write_key(key).await?;
+        state.pending.set(key, length);
+        Ok(length)
+    }
+
+    async fn get_many(&self, keys: &[PreimageKey]) -> Result<Vec<Vec<u8>>> {
+        debug!(target: "oracle_client", "Requesting {} preimages from channel", keys.len());
+
+        let mut state = self.inner.lock().await;
+
+        // The length of a pending preimage is reused if it is the first key requested, otherwise
+        // its data is discarded.
+        let (mut reused, stale) = state.pending.take(keys.first().copied());
+        state.discard(stale).await?;
+        let requests = keys[usize::from(reused.is_some())..]
+            .iter()
+            .flat_map(|key| <[u8; 32]>::from(*key))
+            .collect::<Vec<_>>();
+
+        // The keys are written while the responses are read, so that neither end waits on a full
+        // channel.
+        let (mut reader, mut writer) = split(&mut state.stream);
+        let write = async { writer.write_all(&requests).await.map_err(|e| anyhow!(e)) };
+        let read = async {
+            let mut preimages = Vec::with_capacity(keys.len());
+            for _ in keys {
+                let length = match reused.take() {
+                    Some(length) => length,
+                    None => reader.read_u64().await.map_err(|e| anyhow!(e))? as usize,
+                };
+                let mut data_buffer = vec![0; length];
+                reader.read_exact(&mut data_buffer).await.map_err(|e| anyhow!(e))?;
+                preimages.push(data_buffer);
+            }
+            Ok::<_, anyhow::Error>(preimages)
+        };
+        let (written, preimages) = tokio::join!(write, read);
+        written?;
+        preimages
+    }
+}
+
+/// A [ChannelOracleServer] is the host end of an in-memory preimage channel, the counterpart of the
+/// [crate::OracleServer].
+#[derive(Debug)]
+pub struct ChannelOracleServer {
+    /// The host end of the channel.
+    stream: Mutex<DuplexStream>,
+}
+
+impl ChannelOracleServer {
+    /// Get the next preimage request and return the response to the client, like
+    /// [crate::PreimageOracleServer::next_preimage_request].
+    ///
+    /// # Returns
+    /// - `Ok(())` if the data was successfully written into the channel.
+    /// - `Err(_)` if the data could not be written to the client, or the client end was dropped.
+    pub async fn next_preimage_request<'a>(
+        &self,
+        mut get_preimage: impl FnMut(PreimageKey) -> Result<&'a Vec<u8>>,
+    ) -> Result<()> {
+        let mut stream = self.stream.lock().await;
+
+        let mut buf = [0u8; 32];
+        stream.read_exact(&mut buf).await.map_err(|e| anyhow!(e))?;
+        let preimage_key = PreimageKey::try_from(buf)?;
+
+        debug!(target: "oracle_server", "Fetching preimage for key {preimage_key}");
+
+        let value = get_preimage(preimage_key)?;
+        stream.write_u64(value.len() as u64).await.map_err(|e| anyhow!(e))?;
+        stream.write_all(value).await.map_err(|e| anyhow!(e))?;
+        Ok(())
+    }
+}
+
+/// A [ChannelHintWriter] is the client end of an in-memory hint channel, the counterpart of the
+/// [crate::HintWriter].
+#[derive(Debug)]
+pub struct ChannelHintWriter {
+    /// The client end of the channel.
+    stream: Mutex<DuplexStream>,
+}
+
+#[async_trait]
+impl AsyncHintWriterClient for ChannelHintWriter {
+    async fn write(&self, hint: &str) -> Result<()> {
+        debug!(target: "hint_writer", "Writing hint \"{hint}\"");
+
+        // The hint is framed with a 4-byte big-endian length prefix, and acknowledged by the host
+        // with a single byte.
+        let mut stream = self.stream.lock().await;
+        stream.write_u32(hint.len() as u32).await.map_err(|e| anyhow!(e))?;
+        stream.write_all(hint.as_bytes()).await.map_err(|e| anyhow!(e))?;
+        stream.read_u8().await.map_err(|e| anyhow!(e))?;
+        Ok(())
+    }
+}
+
+/// A [ChannelHintReader] is the host end of an in-memory hint channel, the counterpart of the
+/// [crate::HintReader].
+#[derive(Debug)]
+pub struct ChannelHintReader {
+    /// The host end of the channel.
+    stream: Mutex<DuplexStream>,
+}
+
+impl ChannelHintReader {
+    /// Get the next hint request and return the acknowledgement to the client, like
+    /// [crate::HintReaderServer::next_hint].
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was received and the client was notified of the host's
+    ///   acknowledgement.
+    /// - `Err(_)` if the hint was not received correctly, or the client end was dropped.
+    pub async fn next_hint(&self, mut route_hint: impl FnMut(String) -> Result<()>) -> Result<()> {
+        let mut stream = self.stream.lock().await;
+
+        let len = stream.read_u32().await.map_err(|e| anyhow!(e))?;
+        let mut raw_payload = vec![0u8; len as usize];
+        stream.read_exact(&mut raw_payload).await.map_err(|e| anyhow!(e))?;
+        let payload = String::from_utf8(raw_payload)
+            .map_err(|e| anyhow!("Failed to decode hint payload: {e}"))?;
+
+        debug!(target: "hint_reader", "Successfully read hint: \"{payload}\"");
+
+        // Acknowledge the hint even if routing it fails, to prevent blocking the client.
+        let routed = route_hint(payload);
+        stream.write_u8(0x00).await.map_err(|e| anyhow!(e))?;
+        if let Err(e) = routed {
+            error!("Failed to route hint: {e}");
+            bail!("Failed to route hint: {e}");
+        }
+        Ok(())
+    }
+
+    /// Get the next hint request, parsed into a typed [Hint], and return the acknowledgement to the
+    /// client, like [crate::HintReaderServer::next_typed_hint].
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was received and routed, and the client was notified of the host's
+    ///   acknowledgement.
+    /// - `Err(_)` if the hint was not received correctly, is not a valid [Hint], or the client end
+    ///   was dropped. A hint that fails to parse is a [crate::HintParsingError].
+    pub async fn next_typed_hint(
+        &self,
+        mut route_hint: impl FnMut(Hint) -> Result<()>,
+    ) -> Result<()> {
+        let mut parse_error = None;
+        self.next_hint(|hint| match hint.parse::<Hint>() {
+            Ok(hint) => route_hint(hint),
+            Err(e) => {
+                parse_error = Some(e);
+                Ok(())
+            }
+        })
+        .await?;
+        parse_error.map_or(Ok(()), |e| Err(anyhow!(e)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{HintParsingError, HintType, PreimageKeyType, PreimageOracleClient};
+    use alloy_primitives::keccak256;
+    use std::{collections::HashMap, sync::Arc};
+
+    #[tokio::test]
+    async fn test_preimage_channel() {
+        const MOCK_DATA_A: &[u8] = b"1234567890";
+        const MOCK_DATA_B: &[u8] = b"FACADE";
+        let key_a = PreimageKey::new(*keccak256(MOCK_DATA_A), PreimageKeyType::Keccak256);
+        let key_b = PreimageKey::new(*keccak256(MOCK_DATA_B), PreimageKeyType::Keccak256);
+        let preimages =
+            HashMap::from([(key_a, MOCK_DATA_A.to_vec()), (key_b, MOCK_DATA_B.to_vec())]);
+
+        let (client, server) = preimage_channel(64);
+        let host = tokio::spawn(async move {
+            let get_preimage = |key| preimages.get(&key).ok_or(anyhow!("Preimage not available"));
+            while server.next_preimage_request(get_preimage).await.is_ok() {}
+        });
+
+        assert_eq!(client.get(key_a).await.unwrap(), MOCK_DATA_A);
+        assert_eq!(client.preimage_len(key_b).await.unwrap(), MOCK_DATA_B.len());
+
+        // A wrongly sized buffer leaves the preimage pending for the next read.
+        let mut buf = [0u8; 4];
+        let err = client.get_exact(key_b, &mut buf).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PreimageOracleError>(),
+            Some(&PreimageOracleError::LengthMismatch { expected: 4, actual: 6 })
+        );
+        let mut buf = [0u8; 6];
+        client.get_exact(key_b, &mut buf).await.unwrap();
+        assert_eq!(buf, MOCK_DATA_B);
+
+        // Dropping the client closes the channel, stopping the host loop.
+        drop(client);
+        host.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_preimage_channel_get_many() {
+        let preimages = (0..16u8).map(|i| vec![i; 48]).collect::<Vec<_>>();
+        let keys = preimages.iter().map(|p| PreimageKey::new_keccak256(p)).collect::<Vec<_>>();
+        let store = keys.iter().copied().zip(preimages.clone()).collect::<HashMap<_, _>>();
+
+        // The responses of the pipelined keys do not fit in the channel at once.
+        let (client, server) = preimage_channel(64);
+        let host = tokio::spawn(async move {
+            let get_preimage = |key| store.get(&key).ok_or(anyhow!("Preimage not available"));
+            while server.next_preimage_request(get_preimage).await.is_ok() {}
+        });
+
+        assert_eq!(client.get_many(&keys).await.unwrap(), preimages);
+        assert!(client.get_many(&[]).await.unwrap().is_empty());
+
+        // The pending length of the first key is reused, and of any other key is discarded.
+        client.preimage_len(keys[3]).await.unwrap();
+        assert_eq!(client.get_many(&keys[3..5]).await.unwrap(), preimages[3..5]);
+        client.preimage_len(keys[0]).await.unwrap();
+        assert_eq!(client.get_many(&keys[5..7]).await.unwrap(), preimages[5..7]);
+
+        drop(client);
+        host.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_hint_channel() {
+        let (writer, reader) = hint_channel(64);
+        let hints = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let host = tokio::spawn({
+            let hints = hints.clone();
+            async move {
+                let route_hint = |hint: String| {
+                    if hint.starts_with("bad") {
+                        bail!("Unknown hint");
+                    }
+                    hints.lock().unwrap().push(hint);
+                    Ok(())
+                };
+                while reader.next_hint(route_hint).await.is_ok() {}
+            }
+        });
+
+        writer.write("l1-block-header 0x00").await.unwrap();
+        writer.write("l2-code 0xff").await.unwrap();
+        drop(writer);
+        host.await.unwrap();
+        assert_eq!(*hints.lock().unwrap(), vec!["l1-block-header 0x00", "l2-code 0xff"]);
+    }
+
+    #[tokio::test]
+    async fn test_typed_hint_channel() {
+        let (writer, reader) = hint_channel(64);
+        let host = tokio::spawn(async move {
+            let mut hints = Vec::new();
+            let mut route_hint = |hint: Hint| -> Result<()> {
+                hints.push(hint);
+                Ok(())
+            };
+            let valid = reader.next_typed_hint(&mut route_hint).await;
+            let malformed = reader.next_typed_hint(&mut route_hint).await;
+            (
+                valid.is_ok(),
+                malformed.unwrap_err().downcast_ref::<HintParsingError>().cloned(),
+                hints,
+            )
+        });
+
+        let hint = Hint::new(HintType::L2Code, [0xFF; 32]).unwrap();
+        writer.write_hint(&hint).await.unwrap();
+        // A malformed hint is still acknowledged, so that the client is not blocked.
+        writer.write("l2-code 0xff").await.unwrap();
+        assert_eq!(
+            host.await.unwrap(),
+            (true, Some(HintParsingError::InvalidDataLength(HintType::L2Code, 1)), vec![hint])
+        );
+    }
+
+    #[test]
+    fn test_blocking_client_over_channel() {
+        struct TokioBlockOn(tokio::runtime::Handle);
+
+        impl crate::BlockOn for TokioBlockOn {
+            fn block_on<F: core::future::Future>(&self, future: F) -> F::Output {
+                self.0.block_on(future)
+            }
+        }
+
+        let runtime = tokio::runtime::Runtime::new().unwrap();
+        let (client, server) = preimage_channel(64);
+        let preimage = vec![0xAA; 256];
+        let key = PreimageKey::new(*keccak256(&preimage), PreimageKeyType::Keccak256);
+        runtime.spawn(async move {
+            while server.next_preimage_request(|_| Ok(&preimage)).await.is_ok() {}
+        });
+
+        // The client program runs natively on its own thread, with the blocking interface.
+        let client =
+            crate::BlockingOracleClient::new(client, TokioBlockOn(runtime.handle().clone()));
+        let data = std::thread::spawn(move || client.get(key).unwrap()).join().unwrap();
+        assert_eq!(data, vec![0xAA; 256]);
+    }
+}
@@ -0,0 +1,130 @@
+//! Error types for the preimage oracle.
+
+use crate::{HintType, PreimageKey};
+use alloc::string::String;
+use alloy_primitives::Address;
+use core::{fmt::Display, time::Duration};
+
+/// An error returned by a [crate::PreimageOracleClient] or a [crate::PreimageOracleServer].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PreimageOracleError {
+    /// The size of the caller's buffer does not match the length of the preimage sent by the
+    /// host.
+    LengthMismatch {
+        /// The length expected by the caller.
+        expected: usize,
+        /// The length of the preimage sent by the host.
+        actual: usize,
+    },
+    /// The digest of the preimage does not match its global [PreimageKey].
+    InvalidPreimage(PreimageKey),
+    /// The host did not answer the request before its deadline.
+    Timeout(Duration),
+    /// The [crate::PreimageStore] of the host, or the [crate::test_utils::TestOracle], does not
+    /// hold the preimage of the [PreimageKey].
+    MissingPreimage(PreimageKey),
+}
+
+impl Display for PreimageOracleError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PreimageOracleError::LengthMismatch { expected, actual } => {
+                write!(f, "Buffer size {} does not match preimage size {}", expected, actual)
+            }
+            PreimageOracleError::InvalidPreimage(key) => {
+                write!(f, "Preimage does not match its key {}", key)
+            }
+            PreimageOracleError::Timeout(timeout) => {
+                write!(f, "Host did not answer the request within {:?}", timeout)
+            }
+            PreimageOracleError::MissingPreimage(key) => {
+                write!(f, "No preimage stored for key {}", key)
+            }
+        }
+    }
+}
+
+/// An error returned when parsing a [crate::Hint] or [crate::HintType] from its string
+/// representation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum HintParsingError {
+    /// The hint type is not known.
+    UnknownHintType(String),
+    /// The hint has no data, i.e. it is not of the form `<hint type> <data>`.
+    MissingData,
+    /// The data of the hint is not valid hex.
+    InvalidHex,
+    /// The data of the hint has an invalid length for its type.
+    InvalidDataLength(HintType, usize),
+}
+
+impl Display for HintParsingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HintParsingError::UnknownHintType(ty) => write!(f, "Unknown hint type: {}", ty),
+            HintParsingError::MissingData => write!(f, "Hint is missing data"),
+            HintParsingError::InvalidHex => write!(f, "Hint data is not valid hex"),
+            HintParsingError::InvalidDataLength(ty, len) => {
+                write!(f, "Invalid data length {} for hint type {}", len, ty)
+            }
+        }
+    }
+}
+
+/// An error returned by a [crate::HintRouter] when routing a [crate::Hint].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum HintRoutingError {
+    /// No handler is registered for the type of the hint.
+    UnhandledHintType(HintType),
+}
+
+impl Display for HintRoutingError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            HintRoutingError::UnhandledHintType(ty) => {
+                write!(f, "No handler registered for hint type {}", ty)
+            }
+        }
+    }
+}
+
+/// An error returned when decoding or validating the result of an accelerated precompile call,
+/// as sent by the host.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrecompileResultError {
+    /// The result is empty, and is missing its status byte.
+    EmptyResult,
+    /// The status byte of the result is neither `0` nor `1`.
+    InvalidStatus(u8),
+    /// The output has an invalid length for the precompile.
+    InvalidOutputLength {
+        /// The address of the precompile.
+        address: Address,
+        /// The length of the output.
+        len: usize,
+    },
+    /// The output does not match the output computed locally for the precompile.
+    InvalidOutput(Address),
+    /// The call succeeded, but the input is known to make the precompile fail.
+    UnexpectedSuccess(Address),
+}
+
+impl Display for PrecompileResultError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            PrecompileResultError::EmptyResult => write!(f, "Precompile result is empty"),
+            PrecompileResultError::InvalidStatus(status) => {
+                write!(f, "Invalid precompile result status {}", status)
+            }
+            PrecompileResultError::InvalidOutputLength { address, len } => {
+                write!(f, "Invalid output length {} for precompile {}", len, address)
+            }
+            PrecompileResultError::InvalidOutput(address) => {
+                write!(f, "Invalid output for precompile {}", address)
+            }
+            PrecompileResultError::UnexpectedSuccess(address) => {
+                write!(f, "Precompile {} succeeded on an input it must reject", address)
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+//! This module contains a gRPC transport for the preimage oracle protocol, so that preimages can
+//! be served by a remote service shared by many proving workers.
+//!
+//! The [GrpcOracleService] serves preimages and accepts hints on behalf of a backend, and the
+//! [GrpcOracleClient] is the matching [AsyncPreimageOracleClient] and [AsyncHintWriterClient].
+//! A worker keeps running its pipe servers locally, and routes their requests to the client.
+
+use crate::{AsyncHintWriterClient, AsyncPreimageOracleClient, PreimageKey, PreimageOracleError};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use anyhow::{anyhow, Result};
+use async_trait::async_trait;
+use tonic::{transport::Channel, Request, Response, Status};
+
+#[allow(missing_docs, missing_debug_implementations, unreachable_pub, rustdoc::all)]
+mod generated {
+    include!(concat!(env!("OUT_DIR"), "/kona.preimage.v1.PreimageService.rs"));
+}
+pub use generated::{
+    preimage_service_client::PreimageServiceClient,
+    preimage_service_server::{PreimageService, PreimageServiceServer},
+};
+
+/// A request for the preimage of a [PreimageKey].
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct PreimageRequest {
+    /// The 32 byte [PreimageKey].
+    #[prost(bytes = "vec", tag = "1")]
+    pub key: Vec<u8>,
+}
+
+/// The preimage answering a [PreimageRequest].
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct PreimageResponse {
+    /// The preimage data.
+    #[prost(bytes = "vec", tag = "1")]
+    pub data: Vec<u8>,
+}
+
+/// A hint for the preimage service, in the format written to the hint pipe.
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct HintRequest {
+    /// The hint string.
+    #[prost(string, tag = "1")]
+    pub hint: String,
+}
+
+/// The acknowledgement of a [HintRequest].
+#[derive(Clone, PartialEq, Eq, prost::Message)]
+pub struct HintResponse {}
+
+/// A [GrpcOracleService] serves the preimages and accepts the hints of a backend over gRPC, such as
+/// a host's fetcher, or an [crate::AsyncOracleClient] over the pipes of a host.
+#[derive(Debug)]
+pub struct GrpcOracleService<B> {
+    /// The backend answering the requests.
+    backend: B,
+}
+
+impl<B> GrpcOracleService<B> {
+    /// Creates a new [GrpcOracleService] answering the requests with `backend`.
+    pub const fn new(backend: B) -> Self {
+        Self { backend }
+    }
+
+    /// Wraps the [GrpcOracleService] into a [PreimageServiceServer], to be added to a
+    /// [tonic::transport::Server].
+    pub fn into_server(self) -> PreimageServiceServer<Self>
+    where
+        Self: PreimageService,
+    {
+        PreimageServiceServer::new(self)
+    }
+}
+
+#[async_trait]
+impl<B> PreimageService for GrpcOracleService<B>
+where
+    B: AsyncPreimageOracleClient + AsyncHintWriterClient + Send + Sync + 'static,
+{
+    async fn get_preimage(
+        &self,
+        request: Request<PreimageRequest>,
+    ) -> Result<Response<PreimageResponse>, Status> {
+        let key = <[u8; 32]>::try_from(request.into_inner().key.as_slice())
+            .map_err(|_| Status::invalid_argument("Preimage key must be 32 bytes"))?;
+        let key =
+            PreimageKey::try_from(key).map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let data = self.backend.get(key).await.map_err(|e| Status::not_found(e.to_string()))?;
+        Ok(Response::new(PreimageResponse { data }))
+    }
+
+    async fn hint(&self, request: Request<HintRequest>) -> Result<Response<HintResponse>, Status> {
+        let hint = request.into_inner().hint;
+        self.backend.write(&hint).await.map_err(|e| Status::internal(e.to_string()))?;
+        Ok(Response::new(HintResponse {}))
+    }
+}
+
+/// A [GrpcOracleClient] requests preimages from, and sends hints to, a remote
+/// [GrpcOracleService].
+///
+/// The service keeps no state between requests, so [AsyncPreimageOracleClient::preimage_len] and
+/// a mismatched [AsyncPreimageOracleClient::get_exact] transfer the whole preimage, and a
+/// following read of the same key requests it again.
+#[derive(Debug, Clone)]
+pub struct GrpcOracleClient {
+    /// The generated gRPC client.
+    inner: PreimageServiceClient<Channel>,
+}
+
+impl GrpcOracleClient {
+    /// Creates a new [GrpcOracleClient] over an established [Channel].
+    pub fn new(channel: Channel) -> Self {
+        Self { inner: PreimageServiceClient::new(channel) }
+    }
+
+    /// Connects to the [GrpcOracleService] at the given `endpoint`, such as
+    /// `http://127.0.0.1:50051`.
+    pub async fn connect(endpoint: String) -> Result<Self> {
+        let inner = PreimageServiceClient::connect(endpoint).await.map_err(|e| anyhow!(e))?;
+        Ok(Self { inner })
+    }
+}
+
+#[async_trait]
+impl AsyncPreimageOracleClient for GrpcOracleClient {
+    async fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        let key: [u8; 32] = key.into();
+        let request = PreimageRequest { key: key.to_vec() };
+        let response = self.inner.clone().get_preimage(request).await.map_err(|e| anyhow!(e))?;
+        Ok(response.into_inner().data)
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        let data = self.get(key).await?;
+        if buf.len() != data.len() {
+            return Err(anyhow!(PreimageOracleError::LengthMismatch {
+                expected: buf.len(),
+                actual: data.len()
+            }));
+        }
+        buf.copy_from_slice(&data);
+        Ok(())
+    }
+
+    async fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        Ok(self.get(key).await?.len())
+    }
+}
+
+#[async_trait]
+impl AsyncHintWriterClient for GrpcOracleClient {
+    async fn write(&self, hint: &str) -> Result<()> {
+        let request = HintRequest { hint: hint.into() };
+        self.inner.clone().hint(request).await.map_err(|e| anyhow!(e))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{test_utils::MockBackend, PreimageKeyType};
+    use alloc::vec;
+    use alloy_primitives::keccak256;
+    use std::collections::HashMap;
+    use tokio_stream::wrappers::TcpListenerStream;
+
+    #[tokio::test]
+    async fn test_grpc_client_and_service() {
+        const MOCK_DATA: &[u8] = b"FACADE";
+        let key = PreimageKey::new(*keccak256(MOCK_DATA), PreimageKeyType::Keccak256);
+        let backend = MockBackend { preimages: HashMap::from([(key, MOCK_DATA.to_vec())]) };
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let endpoint = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(GrpcOracleService::new(backend).into_server())
+                .serve_with_incoming(TcpListenerStream::new(listener)),
+        );
+
+        let client = GrpcOracleClient::connect(endpoint).await.unwrap();
+        assert_eq!(client.get(key).await.unwrap(), MOCK_DATA);
+        assert_eq!(client.preimage_len(key).await.unwrap(), MOCK_DATA.len());
+        let mut buf = [0u8; 6];
+        client.get_exact(key, &mut buf).await.unwrap();
+        assert_eq!(buf, MOCK_DATA);
+        let err = client.get_exact(key, &mut [0u8; 4]).await.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PreimageOracleError>(),
+            Some(&PreimageOracleError::LengthMismatch { expected: 4, actual: 6 })
+        );
+
+        let unknown = PreimageKey::new([0xFF; 32], PreimageKeyType::Keccak256);
+        assert!(client.get(unknown).await.is_err());
+        let status = client
+            .inner
+            .clone()
+            .get_preimage(PreimageRequest { key: vec![0x02; 31] })
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::InvalidArgument);
+
+        client.write("l1-block-header 0x00").await.unwrap();
+        assert!(client.write("").await.is_err());
+    }
+}
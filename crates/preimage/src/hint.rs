@@ -1,4 +1,4 @@
-use crate::{traits::HintWriterClient, HintReaderServer, PipeHandle};
+use crate::{traits::HintWriterClient, HintReaderServer, Pipe, PipeHandle};
 use alloc::{string::String, vec};
 use anyhow::Result;
 use tracing::{debug, error};
@@ -6,18 +6,18 @@ use tracing::{debug, error};
 /// A [HintWriter] is a high-level interface to the hint pipe. It provides a way to write hints to
 /// the host.
 #[derive(Debug, Clone, Copy)]
-pub struct HintWriter {
-    pipe_handle: PipeHandle,
+pub struct HintWriter<P = PipeHandle> {
+    pipe_handle: P,
 }
 
-impl HintWriter {
-    /// Create a new [HintWriter] from a [PipeHandle].
-    pub fn new(pipe_handle: PipeHandle) -> Self {
+impl<P: Pipe> HintWriter<P> {
+    /// Create a new [HintWriter] from a [Pipe], such as a [PipeHandle].
+    pub fn new(pipe_handle: P) -> Self {
         Self { pipe_handle }
     }
 }
 
-impl HintWriterClient for HintWriter {
+impl<P: Pipe> HintWriterClient for HintWriter<P> {
     /// Write a hint to the host. This will overwrite any existing hint in the pipe, and block until
     /// all data has been written.
     fn write(&self, hint: &str) -> Result<()> {
@@ -47,18 +47,18 @@ impl HintWriterClient for HintWriter {
 /// A [HintReader] is a router for hints sent by the [HintWriter] from the client program. It
 /// provides a way for the host to prepare preimages for reading.
 #[derive(Debug, Clone, Copy)]
-pub struct HintReader {
-    pipe_handle: PipeHandle,
+pub struct HintReader<P = PipeHandle> {
+    pipe_handle: P,
 }
 
-impl HintReader {
-    /// Create a new [HintReader] from a [PipeHandle].
-    pub fn new(pipe_handle: PipeHandle) -> Self {
+impl<P: Pipe> HintReader<P> {
+    /// Create a new [HintReader] from a [Pipe], such as a [PipeHandle].
+    pub fn new(pipe_handle: P) -> Self {
         Self { pipe_handle }
     }
 }
 
-impl HintReaderServer for HintReader {
+impl<P: Pipe> HintReaderServer for HintReader<P> {
     fn next_hint(&self, mut route_hint: impl FnMut(String) -> Result<()>) -> Result<()> {
         // Read the length of the raw hint payload.
         let mut len_buf = [0u8; 4];
@@ -92,8 +92,6 @@ impl HintReaderServer for HintReader {
 }
 #[cfg(test)]
 mod test {
-    extern crate std;
-
     use super::*;
     use alloc::vec::Vec;
     use kona_common::FileDescriptor;
@@ -0,0 +1,285 @@
+//! Contains the [HintType] and [Hint] types, which define the wire format of the hints sent from
+//! the client program to the host.
+
+use crate::HintParsingError;
+use alloc::string::ToString;
+use alloy_primitives::{hex, Address, Bytes};
+use core::{fmt::Display, str::FromStr};
+
+/// The type of a [Hint], which determines the data the host prepares for the client.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HintType {
+    /// The L1 block header with the given hash.
+    L1BlockHeader,
+    /// The transactions of the L1 block with the given hash.
+    L1Transactions,
+    /// The receipts of the L1 block with the given hash.
+    L1Receipts,
+    /// The blob with the given versioned hash, in the L1 block with the given timestamp.
+    L1Blob,
+    /// The result of an L1 precompile call, given the precompile address and its input.
+    L1Precompile,
+    /// The L2 block header with the given hash.
+    L2BlockHeader,
+    /// The transactions of the L2 block with the given hash.
+    L2Transactions,
+    /// The L2 contract code with the given hash.
+    L2Code,
+    /// The L2 state trie node with the given hash.
+    L2StateNode,
+    /// The L2 output root with the given hash.
+    L2Output,
+    /// The proof of an L2 account, given a block number and the account address.
+    L2AccountProof,
+    /// The proof of an L2 storage slot, given a block number, the account address and the slot.
+    L2AccountStorageProof,
+    /// The proofs of several L2 storage slots of one account, given a block number, the account
+    /// address and the slots.
+    L2AccountStorageProofs,
+}
+
+impl HintType {
+    /// All [HintType]s.
+    pub const ALL: [HintType; 13] = [
+        HintType::L1BlockHeader,
+        HintType::L1Transactions,
+        HintType::L1Receipts,
+        HintType::L1Blob,
+        HintType::L1Precompile,
+        HintType::L2BlockHeader,
+        HintType::L2Transactions,
+        HintType::L2Code,
+        HintType::L2StateNode,
+        HintType::L2Output,
+        HintType::L2AccountProof,
+        HintType::L2AccountStorageProof,
+        HintType::L2AccountStorageProofs,
+    ];
+
+    /// Returns the name of the [HintType] used on the wire.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HintType::L1BlockHeader => "l1-block-header",
+            HintType::L1Transactions => "l1-transactions",
+            HintType::L1Receipts => "l1-receipts",
+            HintType::L1Blob => "l1-blob",
+            HintType::L1Precompile => "l1-precompile",
+            HintType::L2BlockHeader => "l2-block-header",
+            HintType::L2Transactions => "l2-transactions",
+            HintType::L2Code => "l2-code",
+            HintType::L2StateNode => "l2-state-node",
+            HintType::L2Output => "l2-output",
+            HintType::L2AccountProof => "l2-account-proof",
+            HintType::L2AccountStorageProof => "l2-account-storage-proof",
+            HintType::L2AccountStorageProofs => "l2-account-storage-proofs",
+        }
+    }
+
+    /// Returns the length of the data of the [HintType], or [None] if the length is variable.
+    ///
+    /// Variable length data still has a minimum length, see [HintType::min_data_len].
+    pub fn data_len(&self) -> Option<usize> {
+        match self {
+            // Block hash / code hash / node hash / output root.
+            HintType::L1BlockHeader |
+            HintType::L1Transactions |
+            HintType::L1Receipts |
+            HintType::L2BlockHeader |
+            HintType::L2Transactions |
+            HintType::L2Code |
+            HintType::L2StateNode |
+            HintType::L2Output => Some(32),
+            // Versioned hash ++ block timestamp.
+            HintType::L1Blob => Some(32 + 8),
+            // Block number ++ address.
+            HintType::L2AccountProof => Some(8 + 20),
+            // Block number ++ address ++ slot.
+            HintType::L2AccountStorageProof => Some(8 + 20 + 32),
+            // Address ++ input.
+            HintType::L1Precompile => None,
+            // Block number ++ address ++ slots.
+            HintType::L2AccountStorageProofs => None,
+        }
+    }
+
+    /// Returns the minimum length of the data of the [HintType].
+    pub fn min_data_len(&self) -> usize {
+        match self {
+            HintType::L1Precompile => 20,
+            HintType::L2AccountStorageProofs => 8 + 20 + 32,
+            _ => self.data_len().unwrap_or_default(),
+        }
+    }
+
+    /// Returns whether `len` is a valid length for the data of the [HintType].
+    pub fn is_valid_data_len(&self, len: usize) -> bool {
+        match self {
+            // The slots are 32 bytes each.
+            HintType::L2AccountStorageProofs => {
+                len >= self.min_data_len() && (len - (8 + 20)).is_multiple_of(32)
+            }
+            _ => match self.data_len() {
+                Some(data_len) => len == data_len,
+                None => len >= self.min_data_len(),
+            },
+        }
+    }
+}
+
+impl Display for HintType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for HintType {
+    type Err = HintParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HintType::ALL
+            .into_iter()
+            .find(|ty| ty.as_str() == s)
+            .ok_or_else(|| HintParsingError::UnknownHintType(s.to_string()))
+    }
+}
+
+/// A hint sent from the client program to the host, with the wire format
+/// `<hint type> 0x<hex encoded data>`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hint {
+    /// The type of the hint.
+    pub ty: HintType,
+    /// The data of the hint.
+    pub data: Bytes,
+}
+
+impl Hint {
+    /// Creates a new [Hint], validating the length of the data for the [HintType].
+    pub fn new(ty: HintType, data: impl Into<Bytes>) -> Result<Self, HintParsingError> {
+        let data = data.into();
+        if !ty.is_valid_data_len(data.len()) {
+            return Err(HintParsingError::InvalidDataLength(ty, data.len()));
+        }
+        Ok(Self { ty, data })
+    }
+
+    /// Creates a new [HintType::L1Precompile] hint for calling the precompile at `address` with
+    /// `input`.
+    pub fn precompile(address: Address, input: &[u8]) -> Self {
+        let data = [address.as_slice(), input].concat();
+        Self { ty: HintType::L1Precompile, data: data.into() }
+    }
+
+    /// Returns the precompile address and input of a [HintType::L1Precompile] hint, or [None] for
+    /// other hint types, or if the data is too short to hold an address.
+    pub fn precompile_call(&self) -> Option<(Address, &[u8])> {
+        if self.ty != HintType::L1Precompile || self.data.len() < 20 {
+            return None;
+        }
+        let (address, input) = self.data.split_at(20);
+        Some((Address::from_slice(address), input))
+    }
+}
+
+impl Display for Hint {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "{} {}", self.ty, self.data)
+    }
+}
+
+impl FromStr for Hint {
+    type Err = HintParsingError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (ty, data) = s.split_once(' ').ok_or(HintParsingError::MissingData)?;
+        let data = hex::decode(data).map_err(|_| HintParsingError::InvalidHex)?;
+        Hint::new(ty.parse()?, data)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::{format, string::String, vec};
+
+    #[test]
+    fn test_hint_type_roundtrip() {
+        for ty in HintType::ALL {
+            assert_eq!(ty.to_string().parse::<HintType>(), Ok(ty));
+        }
+    }
+
+    #[test]
+    fn test_hint_roundtrip() {
+        for ty in HintType::ALL {
+            for len in [ty.min_data_len(), ty.min_data_len() + 4, ty.min_data_len() + 32] {
+                if !ty.is_valid_data_len(len) {
+                    continue;
+                }
+                let data: Bytes = (0..len).map(|i| i as u8).collect();
+                let hint = Hint::new(ty, data.clone()).unwrap();
+                let wire = hint.to_string();
+                assert_eq!(wire, format!("{} 0x{}", ty.as_str(), hex::encode(&data)));
+                assert_eq!(wire.parse::<Hint>(), Ok(hint));
+            }
+        }
+    }
+
+    #[test]
+    fn test_hint_wire_format() {
+        let hint = Hint::new(HintType::L1BlockHeader, [0xAB; 32]).unwrap();
+        let expected = format!("l1-block-header 0x{}", "ab".repeat(32));
+        assert_eq!(hint.to_string(), expected);
+    }
+
+    #[test]
+    fn test_hint_unknown_type() {
+        let hint = format!("l1-block-headers 0x{}", "00".repeat(32));
+        assert_eq!(
+            hint.parse::<Hint>(),
+            Err(HintParsingError::UnknownHintType(String::from("l1-block-headers")))
+        );
+        assert!("".parse::<HintType>().is_err());
+    }
+
+    #[test]
+    fn test_hint_invalid_length() {
+        for ty in HintType::ALL {
+            let short = format!("{ty} 0x{}", "00".repeat(ty.min_data_len() - 1));
+            assert_eq!(
+                short.parse::<Hint>(),
+                Err(HintParsingError::InvalidDataLength(ty, ty.min_data_len() - 1))
+            );
+            if let Some(len) = ty.data_len() {
+                let long = format!("{ty} 0x{}", "00".repeat(len + 1));
+                assert_eq!(
+                    long.parse::<Hint>(),
+                    Err(HintParsingError::InvalidDataLength(ty, len + 1))
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_hint_storage_proofs_length() {
+        let ty = HintType::L2AccountStorageProofs;
+        assert!(ty.is_valid_data_len(8 + 20 + 32));
+        assert!(ty.is_valid_data_len(8 + 20 + 3 * 32));
+        assert!(!ty.is_valid_data_len(8 + 20));
+        assert!(!ty.is_valid_data_len(8 + 20 + 33));
+        assert_eq!(
+            Hint::new(ty, vec![0u8; 8 + 20 + 40]),
+            Err(HintParsingError::InvalidDataLength(ty, 8 + 20 + 40))
+        );
+    }
+
+    #[test]
+    fn test_hint_malformed() {
+        assert_eq!("l1-block-header".parse::<Hint>(), Err(HintParsingError::MissingData));
+        assert_eq!("l1-block-header 0xzz".parse::<Hint>(), Err(HintParsingError::InvalidHex));
+        assert_eq!(
+            Hint::new(HintType::L2Code, vec![0u8; 31]),
+            Err(HintParsingError::InvalidDataLength(HintType::L2Code, 31))
+        );
+    }
+}
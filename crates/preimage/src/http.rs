@@ -0,0 +1,236 @@
+//! This module contains the [HttpOracleBridge], a lightweight HTTP/JSON server that serves the
+//! preimages and accepts the hints of a backend, to inspect and serve preimages from scripts and
+//! external tooling while debugging.
+//!
+//! The bridge answers two routes, each over its own connection:
+//! - `GET /preimage/{key}` responds with `{"key": "0x..", "data": "0x.."}`, where `key` is the hex
+//!   encoded 32 byte [PreimageKey].
+//! - `POST /hint` accepts a `{"hint": ".."}` body, in the format written to the hint pipe, and
+//!   responds with `{}` once the backend has accepted it.
+//!
+//! Errors respond with `{"error": ".."}`.
+
+use crate::{AsyncHintWriterClient, AsyncPreimageOracleClient, PreimageKey};
+use alloc::{string::String, sync::Arc, vec, vec::Vec};
+use alloy_primitives::{hex, B256};
+use anyhow::{anyhow, bail, Result};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+use tracing::{debug, warn};
+
+/// The maximum size of a request body, in bytes.
+const MAX_BODY_SIZE: usize = 1 << 20;
+
+/// An [HttpOracleBridge] serves the preimages and accepts the hints of a backend over HTTP, such as
+/// a host's fetcher, or an [crate::AsyncOracleClient] over the pipes of a host.
+#[derive(Debug)]
+pub struct HttpOracleBridge<B> {
+    /// The backend answering the requests.
+    backend: B,
+}
+
+/// The body of a `POST /hint` request.
+#[derive(Debug, Deserialize)]
+struct HintBody {
+    /// The hint string.
+    hint: String,
+}
+
+impl<B> HttpOracleBridge<B>
+where
+    B: AsyncPreimageOracleClient + AsyncHintWriterClient + Send + Sync + 'static,
+{
+    /// Creates a new [HttpOracleBridge] answering the requests with `backend`.
+    pub const fn new(backend: B) -> Self {
+        Self { backend }
+    }
+
+    /// Serves the requests of the connections accepted by the `listener`, each on its own task.
+    /// Only returns if accepting a connection fails.
+    pub async fn serve(self, listener: TcpListener) -> Result<()> {
+        let bridge = Arc::new(self);
+        loop {
+            let (stream, peer) = listener.accept().await.map_err(|e| anyhow!(e))?;
+            let bridge = bridge.clone();
+            tokio::spawn(async move {
+                if let Err(e) = bridge.handle_connection(stream).await {
+                    warn!(target: "http_bridge", "Failed to serve request from {peer}: {e}");
+                }
+            });
+        }
+    }
+
+    /// Reads a single request from the `stream`, and writes back the response.
+    async fn handle_connection(&self, stream: TcpStream) -> Result<()> {
+        let mut stream = BufReader::new(stream);
+        let (status, body) = match read_request(&mut stream).await {
+            Ok((method, path, body)) => {
+                debug!(target: "http_bridge", "Serving {method} {path}");
+                self.route(&method, &path, &body).await
+            }
+            Err(e) => (400, json!({ "error": e.to_string() })),
+        };
+
+        let body = body.to_string();
+        let response = format!(
+            "HTTP/1.1 {status} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\
+             Connection: close\r\n\r\n{body}",
+            reason_phrase(status),
+            body.len()
+        );
+        stream.write_all(response.as_bytes()).await.map_err(|e| anyhow!(e))?;
+        stream.shutdown().await.map_err(|e| anyhow!(e))
+    }
+
+    /// Answers a request, returning the status code and the JSON body of the response.
+    async fn route(&self, method: &str, path: &str, body: &[u8]) -> (u16, Value) {
+        let error = |status, message: String| (status, json!({ "error": message }));
+        match (method, path) {
+            ("GET", path) if path.starts_with("/preimage/") => {
+                let key = match parse_key(&path["/preimage/".len()..]) {
+                    Ok(key) => key,
+                    Err(e) => return error(400, e.to_string()),
+                };
+                match self.backend.get(key).await {
+                    Ok(data) => {
+                        let key = hex::encode_prefixed(<[u8; 32]>::from(key));
+                        (200, json!({ "key": key, "data": hex::encode_prefixed(data) }))
+                    }
+                    Err(e) => error(404, e.to_string()),
+                }
+            }
+            ("POST", "/hint") => {
+                let hint = match serde_json::from_slice::<HintBody>(body) {
+                    Ok(body) => body.hint,
+                    Err(e) => return error(400, format!("Invalid hint body: {e}")),
+                };
+                match self.backend.write(&hint).await {
+                    Ok(()) => (200, json!({})),
+                    Err(e) => error(500, e.to_string()),
+                }
+            }
+            (_, "/hint") => error(405, format!("Method {method} not allowed")),
+            (_, path) if path.starts_with("/preimage/") => {
+                error(405, format!("Method {method} not allowed"))
+            }
+            _ => error(404, format!("Unknown route {path}")),
+        }
+    }
+}
+
+/// Reads the method, path and body of an HTTP/1.1 request.
+async fn read_request(stream: &mut BufReader<TcpStream>) -> Result<(String, String, Vec<u8>)> {
+    let mut request_line = String::new();
+    stream.read_line(&mut request_line).await.map_err(|e| anyhow!(e))?;
+    let mut parts = request_line.split_whitespace();
+    let (Some(method), Some(path)) = (parts.next(), parts.next()) else {
+        bail!("Malformed request line");
+    };
+    let (method, path) = (method.to_string(), path.to_string());
+
+    // Read the headers up to the empty line, keeping the length of the body.
+    let mut content_length = 0;
+    loop {
+        let mut header = String::new();
+        if stream.read_line(&mut header).await.map_err(|e| anyhow!(e))? == 0 {
+            bail!("Connection closed before the end of the headers");
+        }
+        let header = header.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("content-length") {
+                content_length =
+                    value.trim().parse().map_err(|_| anyhow!("Invalid Content-Length header"))?;
+            }
+        }
+    }
+    if content_length > MAX_BODY_SIZE {
+        bail!("Request body exceeds {MAX_BODY_SIZE} bytes");
+    }
+
+    let mut body = vec![0u8; content_length];
+    stream.read_exact(&mut body).await.map_err(|e| anyhow!(e))?;
+    Ok((method, path, body))
+}
+
+/// Parses a hex encoded [PreimageKey], with or without a `0x` prefix.
+fn parse_key(key: &str) -> Result<PreimageKey> {
+    let key = key.parse::<B256>().map_err(|e| anyhow!("Invalid preimage key: {e}"))?;
+    PreimageKey::try_from(key.0)
+}
+
+/// Returns the reason phrase of the status codes used by the bridge.
+const fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        400 => "Bad Request",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{test_utils::MockBackend, PreimageKeyType};
+    use alloy_primitives::keccak256;
+    use std::collections::HashMap;
+
+    /// Sends a raw HTTP request to the bridge, returning the status code and the JSON body of the
+    /// response.
+    async fn request(
+        addr: std::net::SocketAddr,
+        method: &str,
+        path: &str,
+        body: &str,
+    ) -> (u16, Value) {
+        let mut stream = TcpStream::connect(addr).await.unwrap();
+        let request = format!(
+            "{method} {path} HTTP/1.1\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{body}",
+            body.len()
+        );
+        stream.write_all(request.as_bytes()).await.unwrap();
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).await.unwrap();
+        let (head, body) = response.split_once("\r\n\r\n").unwrap();
+        let status = head.split_whitespace().nth(1).unwrap().parse().unwrap();
+        (status, serde_json::from_str(body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_http_bridge() {
+        const MOCK_DATA: &[u8] = b"FACADE";
+        let key = PreimageKey::new(*keccak256(MOCK_DATA), PreimageKeyType::Keccak256);
+        let backend = MockBackend { preimages: HashMap::from([(key, MOCK_DATA.to_vec())]) };
+
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(HttpOracleBridge::new(backend).serve(listener));
+
+        let encoded_key = hex::encode_prefixed(<[u8; 32]>::from(key));
+        assert_eq!(
+            request(addr, "GET", &format!("/preimage/{encoded_key}"), "").await,
+            (200, json!({ "key": encoded_key, "data": hex::encode_prefixed(MOCK_DATA) }))
+        );
+        let unknown =
+            hex::encode(<[u8; 32]>::from(PreimageKey::new([0xFF; 32], PreimageKeyType::Keccak256)));
+        assert_eq!(request(addr, "GET", &format!("/preimage/{unknown}"), "").await.0, 404);
+        assert_eq!(request(addr, "GET", "/preimage/0x1234", "").await.0, 400);
+        assert_eq!(request(addr, "DELETE", &format!("/preimage/{encoded_key}"), "").await.0, 405);
+
+        let hint = r#"{"hint":"l1-block-header 0x00"}"#;
+        assert_eq!(request(addr, "POST", "/hint", hint).await, (200, json!({})));
+        assert_eq!(request(addr, "POST", "/hint", r#"{"hint":""}"#).await.0, 500);
+        assert_eq!(request(addr, "POST", "/hint", "not json").await.0, 400);
+        assert_eq!(request(addr, "GET", "/hint", "").await.0, 405);
+        assert_eq!(request(addr, "GET", "/", "").await.0, 404);
+    }
+}
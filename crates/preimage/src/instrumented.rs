@@ -0,0 +1,336 @@
+//! This module contains the [InstrumentedOracle], a decorator for the preimage oracle and hint
+//! clients and servers that records their traffic with the [metrics] facade, so that a host can
+//! export it with any [metrics] exporter, such as Prometheus.
+//!
+//! The metrics are labeled with the `side` of the wrapped oracle, `client` or `server`, and the
+//! preimage metrics with the `key_type` of the requested [PreimageKey]:
+//! - `kona_preimage_requests_total`: the number of preimage requests.
+//! - `kona_preimage_request_errors_total`: the number of failed preimage requests.
+//! - `kona_preimage_bytes_total`: the number of preimage bytes transferred.
+//! - `kona_preimage_request_duration_seconds`: a histogram of the latency of preimage requests.
+//! - `kona_preimage_hints_total`: the number of hints.
+//! - `kona_preimage_hint_errors_total`: the number of failed hints.
+//!
+//! The [crate::CachingOracle] additionally records `kona_preimage_cache_hits_total` and
+//! `kona_preimage_cache_misses_total`.
+
+use crate::{
+    AsyncHintWriterClient, AsyncPreimageOracleClient, Hint, HintReaderServer, HintWriterClient,
+    PreimageKey, PreimageKeyType, PreimageOracleClient, PreimageOracleServer,
+};
+use alloc::{boxed::Box, string::String, vec::Vec};
+use anyhow::Result;
+use async_trait::async_trait;
+use metrics::{counter, histogram};
+use std::time::Instant;
+
+/// The side of the preimage channel an [InstrumentedOracle] wraps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Side {
+    /// A client, requesting preimages and sending hints.
+    Client,
+    /// A server, serving preimages and receiving hints.
+    Server,
+}
+
+impl Side {
+    /// Returns the value of the `side` label.
+    const fn as_str(&self) -> &'static str {
+        match self {
+            Side::Client => "client",
+            Side::Server => "server",
+        }
+    }
+}
+
+/// Returns the value of the `key_type` label of a [PreimageKeyType].
+const fn key_type_label(key_type: PreimageKeyType) -> &'static str {
+    match key_type {
+        PreimageKeyType::Local => "local",
+        PreimageKeyType::Keccak256 => "keccak256",
+        PreimageKeyType::GlobalGeneric => "global_generic",
+        PreimageKeyType::Sha256 => "sha256",
+        PreimageKeyType::Blob => "blob",
+        PreimageKeyType::Precompile => "precompile",
+    }
+}
+
+/// An [InstrumentedOracle] wraps a preimage oracle or hint client or server, and records the
+/// requests it makes or serves. It implements the same traits as the wrapped value.
+#[derive(Debug, Clone)]
+pub struct InstrumentedOracle<T> {
+    /// The wrapped client or server.
+    inner: T,
+}
+
+impl<T> InstrumentedOracle<T> {
+    /// Creates a new [InstrumentedOracle] over `inner`.
+    pub const fn new(inner: T) -> Self {
+        Self { inner }
+    }
+
+    /// Returns a reference to the wrapped client or server.
+    pub const fn inner(&self) -> &T {
+        &self.inner
+    }
+
+    /// Consumes the [InstrumentedOracle], returning the wrapped client or server.
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+}
+
+/// Records a preimage request for `key` that started at `start`, transferring `len` bytes if it
+/// succeeded.
+fn record_request(side: Side, key: PreimageKey, start: Instant, len: Result<usize, ()>) {
+    let labels = [("side", side.as_str()), ("key_type", key_type_label(key.key_type()))];
+    counter!("kona_preimage_requests_total", &labels[..]).increment(1);
+    histogram!("kona_preimage_request_duration_seconds", &labels[..])
+        .record(start.elapsed().as_secs_f64());
+    match len {
+        Ok(len) => counter!("kona_preimage_bytes_total", &labels[..]).increment(len as u64),
+        Err(()) => counter!("kona_preimage_request_errors_total", &labels[..]).increment(1),
+    }
+}
+
+/// Records a hint, failed or not.
+fn record_hint<T>(side: Side, result: &Result<T>) {
+    counter!("kona_preimage_hints_total", "side" => side.as_str()).increment(1);
+    if result.is_err() {
+        counter!("kona_preimage_hint_errors_total", "side" => side.as_str()).increment(1);
+    }
+}
+
+impl<C: PreimageOracleClient> PreimageOracleClient for InstrumentedOracle<C> {
+    fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        let start = Instant::now();
+        let result = self.inner.get(key);
+        record_request(Side::Client, key, start, result.as_ref().map(Vec::len).map_err(|_| ()));
+        result
+    }
+
+    fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        let start = Instant::now();
+        let result = self.inner.get_exact(key, buf);
+        record_request(
+            Side::Client,
+            key,
+            start,
+            result.as_ref().map(|_| buf.len()).map_err(|_| ()),
+        );
+        result
+    }
+
+    fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        self.inner.preimage_len(key)
+    }
+
+    fn get_many(&self, keys: &[PreimageKey]) -> Result<Vec<Vec<u8>>> {
+        // The latency of a batch is attributed to each of its requests.
+        let start = Instant::now();
+        let result = self.inner.get_many(keys);
+        for (i, key) in keys.iter().enumerate() {
+            let len = result.as_ref().map(|preimages| preimages[i].len()).map_err(|_| ());
+            record_request(Side::Client, *key, start, len);
+        }
+        result
+    }
+
+    fn try_get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+        // Only the poll that receives the preimage is recorded, with the latency of that poll.
+        let start = Instant::now();
+        let result = self.inner.try_get(key);
+        match &result {
+            Ok(Some(data)) => record_request(Side::Client, key, start, Ok(data.len())),
+            Ok(None) => {}
+            Err(_) => record_request(Side::Client, key, start, Err(())),
+        }
+        result
+    }
+}
+
+impl<C: HintWriterClient> HintWriterClient for InstrumentedOracle<C> {
+    fn write(&self, hint: &str) -> Result<()> {
+        let result = self.inner.write(hint);
+        record_hint(Side::Client, &result);
+        result
+    }
+}
+
+#[async_trait]
+impl<C> AsyncPreimageOracleClient for InstrumentedOracle<C>
+where
+    C: AsyncPreimageOracleClient + Send + Sync,
+{
+    async fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        let start = Instant::now();
+        let result = self.inner.get(key).await;
+        record_request(Side::Client, key, start, result.as_ref().map(Vec::len).map_err(|_| ()));
+        result
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        let start = Instant::now();
+        let result = self.inner.get_exact(key, buf).await;
+        record_request(
+            Side::Client,
+            key,
+            start,
+            result.as_ref().map(|_| buf.len()).map_err(|_| ()),
+        );
+        result
+    }
+
+    async fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        self.inner.preimage_len(key).await
+    }
+
+    async fn get_many(&self, keys: &[PreimageKey]) -> Result<Vec<Vec<u8>>> {
+        let start = Instant::now();
+        let result = self.inner.get_many(keys).await;
+        for (i, key) in keys.iter().enumerate() {
+            let len = result.as_ref().map(|preimages| preimages[i].len()).map_err(|_| ());
+            record_request(Side::Client, *key, start, len);
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl<C> AsyncHintWriterClient for InstrumentedOracle<C>
+where
+    C: AsyncHintWriterClient + Send + Sync,
+{
+    async fn write(&self, hint: &str) -> Result<()> {
+        let result = self.inner.write(hint).await;
+        record_hint(Side::Client, &result);
+        result
+    }
+
+    async fn write_hint(&self, hint: &Hint) -> Result<()> {
+        let result = self.inner.write_hint(hint).await;
+        record_hint(Side::Client, &result);
+        result
+    }
+}
+
+impl<S: PreimageOracleServer> PreimageOracleServer for InstrumentedOracle<S> {
+    fn next_preimage_request<'a>(
+        &self,
+        mut get_preimage: impl FnMut(PreimageKey) -> Result<&'a Vec<u8>>,
+    ) -> Result<()> {
+        // The latency is measured from the moment the request is read, rather than from when the
+        // server starts waiting for it.
+        let mut request = None;
+        let result = self.inner.next_preimage_request(|key| {
+            let start = Instant::now();
+            let preimage = get_preimage(key);
+            let len = preimage.as_ref().ok().map(|preimage| preimage.len());
+            request = Some((key, start, len));
+            preimage
+        });
+        if let Some((key, start, len)) = request {
+            let len = match (&result, len) {
+                (Ok(()), Some(len)) => Ok(len),
+                _ => Err(()),
+            };
+            record_request(Side::Server, key, start, len);
+        }
+        result
+    }
+}
+
+impl<S: HintReaderServer> HintReaderServer for InstrumentedOracle<S> {
+    fn next_hint(&self, route_hint: impl FnMut(String) -> Result<()>) -> Result<()> {
+        let result = self.inner.next_hint(route_hint);
+        record_hint(Side::Server, &result);
+        result
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+    use anyhow::anyhow;
+    use metrics_util::{
+        debugging::{DebugValue, DebuggingRecorder},
+        CompositeKey, MetricKind,
+    };
+
+    /// A client serving a 4 byte preimage for keccak256 keys, and failing for other keys.
+    struct MockClient;
+
+    impl PreimageOracleClient for MockClient {
+        fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+            match key.key_type() {
+                PreimageKeyType::Keccak256 => Ok(vec![0xAA; 4]),
+                _ => Err(anyhow!("Preimage not available")),
+            }
+        }
+
+        fn get_exact(&self, _: PreimageKey, _: &mut [u8]) -> Result<()> {
+            unimplemented!()
+        }
+
+        fn preimage_len(&self, _: PreimageKey) -> Result<usize> {
+            unimplemented!()
+        }
+    }
+
+    impl HintWriterClient for MockClient {
+        fn write(&self, _: &str) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Returns the value of the counter `name` in the `snapshot`, with the `key_type` label if
+    /// given.
+    fn counter(snapshot: &[(CompositeKey, DebugValue)], name: &str, key_type: Option<&str>) -> u64 {
+        snapshot
+            .iter()
+            .find_map(|(key, value)| {
+                let matches = key.kind() == MetricKind::Counter &&
+                    key.key().name() == name &&
+                    key_type.is_none_or(|key_type| {
+                        key.key().labels().any(|l| l.key() == "key_type" && l.value() == key_type)
+                    });
+                match value {
+                    DebugValue::Counter(count) if matches => Some(*count),
+                    _ => None,
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    #[test]
+    fn test_instrumented_client() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+        let oracle = InstrumentedOracle::new(MockClient);
+        let keccak_key = PreimageKey::new([0xFF; 32], PreimageKeyType::Keccak256);
+        let local_key = PreimageKey::new_local(1);
+
+        metrics::with_local_recorder(&recorder, || {
+            oracle.get(keccak_key).unwrap();
+            oracle.get_many(&[keccak_key, keccak_key]).unwrap();
+            assert!(oracle.get(local_key).is_err());
+            oracle.write("l2-code 0xff").unwrap();
+        });
+
+        let snapshot = snapshotter
+            .snapshot()
+            .into_vec()
+            .into_iter()
+            .map(|(key, _, _, value)| (key, value))
+            .collect::<Vec<_>>();
+        let keccak256 = Some("keccak256");
+        assert_eq!(counter(&snapshot, "kona_preimage_requests_total", keccak256), 3);
+        assert_eq!(counter(&snapshot, "kona_preimage_bytes_total", keccak256), 12);
+        assert_eq!(counter(&snapshot, "kona_preimage_request_errors_total", keccak256), 0);
+        assert_eq!(counter(&snapshot, "kona_preimage_requests_total", Some("local")), 1);
+        assert_eq!(counter(&snapshot, "kona_preimage_request_errors_total", Some("local")), 1);
+        assert_eq!(counter(&snapshot, "kona_preimage_hints_total", None), 1);
+        assert_eq!(counter(&snapshot, "kona_preimage_hint_errors_total", None), 0);
+    }
+}
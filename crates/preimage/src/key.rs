@@ -1,7 +1,9 @@
 //! Contains the [PreimageKey] type, which is used to identify preimages that may be fetched from
 //! the preimage oracle.
 
-use alloy_primitives::B256;
+use crate::PreimageOracleError;
+use alloy_primitives::{keccak256, B256};
+use sha2::{Digest, Sha256};
 
 /// <https://specs.optimism.io/experimental/fault-proof/index.html#pre-image-key-types>
 #[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Hash)]
@@ -23,6 +25,10 @@ pub enum PreimageKeyType {
     /// `keccak256(commitment ++ z)`, and then the high-order byte of the digest is set to the
     /// type byte.
     Blob = 5,
+    /// Precompile key types are global and context independent. Precompile keys are constructed
+    /// as `keccak256(precompile_addr ++ input)`, and then the high-order byte of the digest is
+    /// set to the type byte. The preimage is the result of the precompile call.
+    Precompile = 6,
 }
 
 impl TryFrom<u8> for PreimageKeyType {
@@ -35,6 +41,7 @@ impl TryFrom<u8> for PreimageKeyType {
             3 => PreimageKeyType::GlobalGeneric,
             4 => PreimageKeyType::Sha256,
             5 => PreimageKeyType::Blob,
+            6 => PreimageKeyType::Precompile,
             _ => anyhow::bail!("Invalid preimage key type"),
         })
     }
@@ -71,10 +78,35 @@ impl PreimageKey {
         Self { data, key_type: PreimageKeyType::Local }
     }
 
+    /// Creates a new [PreimageKeyType::Keccak256] [PreimageKey] for the given preimage.
+    pub fn new_keccak256(preimage: &[u8]) -> Self {
+        Self::new(*keccak256(preimage), PreimageKeyType::Keccak256)
+    }
+
+    /// Creates a new [PreimageKeyType::Sha256] [PreimageKey] for the given preimage.
+    pub fn new_sha256(preimage: &[u8]) -> Self {
+        Self::new(Sha256::digest(preimage).into(), PreimageKeyType::Sha256)
+    }
+
     /// Returns the [PreimageKeyType] for the [PreimageKey].
     pub fn key_type(&self) -> PreimageKeyType {
         self.key_type
     }
+
+    /// Validates that the `preimage` hashes to the [PreimageKey]. Only [PreimageKeyType::Keccak256]
+    /// and [PreimageKeyType::Sha256] keys are derived from the preimage alone, so the preimages of
+    /// other key types are always accepted.
+    pub fn validate_preimage(&self, preimage: &[u8]) -> Result<(), PreimageOracleError> {
+        let expected = match self.key_type {
+            PreimageKeyType::Keccak256 => Self::new_keccak256(preimage),
+            PreimageKeyType::Sha256 => Self::new_sha256(preimage),
+            _ => return Ok(()),
+        };
+        if expected != *self {
+            return Err(PreimageOracleError::InvalidPreimage(*self));
+        }
+        Ok(())
+    }
 }
 
 impl From<PreimageKey> for [u8; 32] {
@@ -108,7 +140,8 @@ mod test {
 
     #[test]
     fn test_preimage_keys() {
-        let types = [PreimageKeyType::Local, PreimageKeyType::Keccak256];
+        let types =
+            [PreimageKeyType::Local, PreimageKeyType::Keccak256, PreimageKeyType::Precompile];
 
         for key_type in types {
             let key = PreimageKey::new([0xFFu8; 32], key_type);
@@ -120,4 +153,38 @@ mod test {
             assert_eq!(actual, rendered_key);
         }
     }
+
+    #[test]
+    fn test_global_keys() {
+        const PREIMAGE: &[u8] = b"kona";
+
+        let key = PreimageKey::new_sha256(PREIMAGE);
+        assert_eq!(key.key_type(), PreimageKeyType::Sha256);
+        let mut expected: [u8; 32] = Sha256::digest(PREIMAGE).into();
+        expected[0] = PreimageKeyType::Sha256 as u8;
+        assert_eq!(<[u8; 32]>::from(key), expected);
+        assert_eq!(PreimageKey::try_from(expected).unwrap(), key);
+
+        let key = PreimageKey::new_keccak256(PREIMAGE);
+        assert_eq!(key, PreimageKey::new(*keccak256(PREIMAGE), PreimageKeyType::Keccak256));
+        assert_ne!(<[u8; 32]>::from(key), expected);
+    }
+
+    #[test]
+    fn test_validate_preimage() {
+        const PREIMAGE: &[u8] = b"kona";
+
+        for key in [PreimageKey::new_sha256(PREIMAGE), PreimageKey::new_keccak256(PREIMAGE)] {
+            assert_eq!(key.validate_preimage(PREIMAGE), Ok(()));
+            assert_eq!(
+                key.validate_preimage(b"anon"),
+                Err(PreimageOracleError::InvalidPreimage(key))
+            );
+        }
+
+        // A key of another type does not commit to the digest of its preimage.
+        let key = PreimageKey::new(*keccak256(PREIMAGE), PreimageKeyType::Sha256);
+        assert!(key.validate_preimage(PREIMAGE).is_err());
+        assert_eq!(PreimageKey::new_local(4).validate_preimage(PREIMAGE), Ok(()));
+    }
 }
@@ -2,21 +2,100 @@
 #![warn(missing_debug_implementations, missing_docs, unreachable_pub, rustdoc::all)]
 #![deny(unused_must_use, rust_2018_idioms)]
 #![cfg_attr(docsrs, feature(doc_cfg, doc_auto_cfg))]
-#![no_std]
+#![cfg_attr(not(feature = "std"), no_std)]
 
 extern crate alloc;
 
+// Without the `std` feature, the tests still need `std`.
+#[cfg(all(test, not(feature = "std")))]
+extern crate std;
+
+mod errors;
+pub use errors::{HintParsingError, HintRoutingError, PrecompileResultError, PreimageOracleError};
+
 mod key;
 pub use key::{PreimageKey, PreimageKeyType};
 
+mod precompile;
+pub use precompile::{
+    decode_precompile_result, encode_precompile_result, precompile_key, precompile_preimage,
+    validate_precompile_result, BN256_PAIRING_ADDRESS, ECRECOVER_ADDRESS,
+    KZG_POINT_EVALUATION_ADDRESS, SHA256_ADDRESS,
+};
+
 mod oracle;
 pub use oracle::{OracleReader, OracleServer};
 
+mod hint_type;
+pub use hint_type::{Hint, HintType};
+
 mod hint;
 pub use hint::{HintReader, HintWriter};
 
+mod router;
+pub use router::{HintHandler, HintMetrics, HintRouter};
+
 mod pipe;
-pub use pipe::PipeHandle;
+pub use pipe::{Pipe, PipeHandle};
+
+mod cache;
+pub use cache::CachingOracle;
 
 mod traits;
-pub use traits::{HintReaderServer, HintWriterClient, PreimageOracleClient, PreimageOracleServer};
+#[cfg(feature = "std")]
+pub use traits::{
+    AsyncHintWriterClient, AsyncOracleClient, AsyncPreimageOracleClient, BlockOn,
+    BlockingOracleClient,
+};
+pub use traits::{
+    HintReaderServer, HintWriterClient, PreimageOracleClient, PreimageOracleServer, PreimageStore,
+};
+
+#[cfg(feature = "std")]
+mod memory;
+#[cfg(feature = "std")]
+pub use memory::BidirectionalPipe;
+
+#[cfg(feature = "std")]
+mod store;
+#[cfg(feature = "std")]
+pub use store::DiskPreimageStore;
+
+#[cfg(feature = "std")]
+mod timeout;
+#[cfg(feature = "std")]
+pub use timeout::TimeoutOracleClient;
+
+#[cfg(feature = "tokio")]
+mod channel;
+#[cfg(feature = "tokio")]
+pub use channel::{
+    hint_channel, preimage_channel, ChannelHintReader, ChannelHintWriter, ChannelOracleClient,
+    ChannelOracleServer,
+};
+
+#[cfg(feature = "grpc")]
+mod grpc;
+#[cfg(feature = "grpc")]
+pub use grpc::{
+    GrpcOracleClient, GrpcOracleService, HintRequest, HintResponse, PreimageRequest,
+    PreimageResponse, PreimageService, PreimageServiceClient, PreimageServiceServer,
+};
+
+#[cfg(feature = "http")]
+mod http;
+#[cfg(feature = "http")]
+pub use http::HttpOracleBridge;
+
+#[cfg(feature = "shm")]
+mod shm;
+#[cfg(feature = "shm")]
+pub use shm::ShmPipe;
+
+#[cfg(feature = "metrics")]
+mod instrumented;
+#[cfg(feature = "metrics")]
+pub use instrumented::InstrumentedOracle;
+
+#[cfg(any(test, feature = "test-utils"))]
+pub mod test_utils;
@@ -0,0 +1,174 @@
+//! This module contains the [BidirectionalPipe], a [Pipe] over in-memory buffers, so that native
+//! tests can run a client program against a host in the same process, without any file
+//! descriptors.
+
+use crate::Pipe;
+use alloc::{collections::VecDeque, sync::Arc};
+use anyhow::{anyhow, bail, Result};
+use std::sync::{Condvar, Mutex, MutexGuard};
+
+/// A [BidirectionalPipe] is one end of an in-memory, bidirectional pipe, created with
+/// [BidirectionalPipe::pair]. Writes never block, as the buffers grow as needed, and reads block
+/// until enough data is written by the other end. Once an end is dropped, the reads of the other
+/// end fail after draining the buffered data, and its writes fail.
+#[derive(Debug)]
+pub struct BidirectionalPipe {
+    /// The stream this end reads from.
+    read: Arc<Stream>,
+    /// The stream this end writes to.
+    write: Arc<Stream>,
+}
+
+/// A one-way stream of bytes between the ends of a [BidirectionalPipe].
+#[derive(Debug, Default)]
+struct Stream {
+    /// The buffered data, and whether either end of the stream is closed.
+    state: Mutex<StreamState>,
+    /// Notified when data is written or the stream is closed.
+    updated: Condvar,
+}
+
+/// The state of a [Stream].
+#[derive(Debug, Default)]
+struct StreamState {
+    /// The data written but not yet read.
+    data: VecDeque<u8>,
+    /// Whether either end of the stream is closed.
+    closed: bool,
+}
+
+impl BidirectionalPipe {
+    /// Creates both ends of a new pipe. Data written to either end is read from the other.
+    pub fn pair() -> (Self, Self) {
+        let (a, b) = (Arc::new(Stream::default()), Arc::new(Stream::default()));
+        (Self { read: a.clone(), write: b.clone() }, Self { read: b, write: a })
+    }
+}
+
+impl Stream {
+    /// Locks the state of the stream.
+    fn lock(&self) -> Result<MutexGuard<'_, StreamState>> {
+        self.state.lock().map_err(|_| anyhow!("Pipe lock poisoned"))
+    }
+
+    /// Closes the stream, waking any blocked reader.
+    fn close(&self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.closed = true;
+        }
+        self.updated.notify_all();
+    }
+}
+
+impl Pipe for BidirectionalPipe {
+    fn read_exact(&self, buf: &mut [u8]) -> Result<usize> {
+        let n = buf.len();
+        let mut state = self.read.lock()?;
+        while state.data.len() < n {
+            if state.closed {
+                bail!("Pipe closed by the other end");
+            }
+            state = self.read.updated.wait(state).map_err(|_| anyhow!("Pipe lock poisoned"))?;
+        }
+        for (byte, data) in buf.iter_mut().zip(state.data.drain(..n)) {
+            *byte = data;
+        }
+        Ok(n)
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize> {
+        let mut state = self.write.lock()?;
+        if state.closed {
+            bail!("Pipe closed by the other end");
+        }
+        state.data.extend(buf);
+        self.write.updated.notify_all();
+        Ok(buf.len())
+    }
+
+    fn poll_readable(&self) -> Result<bool> {
+        let state = self.read.lock()?;
+        if state.data.is_empty() && state.closed {
+            bail!("Pipe closed by the other end");
+        }
+        Ok(!state.data.is_empty())
+    }
+
+    fn try_read(&self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() || !self.poll_readable()? {
+            return Ok(0);
+        }
+        let mut state = self.read.lock()?;
+        let n = buf.len().min(state.data.len());
+        for (byte, data) in buf.iter_mut().zip(state.data.drain(..n)) {
+            *byte = data;
+        }
+        Ok(n)
+    }
+}
+
+impl Drop for BidirectionalPipe {
+    fn drop(&mut self) {
+        self.read.close();
+        self.write.close();
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        HintReader, HintReaderServer, HintWriter, HintWriterClient, OracleReader, OracleServer,
+        PreimageKey, PreimageOracleClient, PreimageOracleServer,
+    };
+    use alloc::{string::String, vec, vec::Vec};
+    use std::thread;
+
+    #[test]
+    fn test_oracle_and_hints_over_memory() {
+        const MOCK_DATA: &[u8] = b"1234567890";
+        let key = PreimageKey::new_keccak256(MOCK_DATA);
+        let (preimage_client, preimage_host) = BidirectionalPipe::pair();
+        let (hint_client, hint_host) = BidirectionalPipe::pair();
+
+        let host = thread::spawn(move || {
+            let (server, hint_reader) =
+                (OracleServer::new(preimage_host), HintReader::new(hint_host));
+            let mut hints = Vec::new();
+            hint_reader
+                .next_hint(|hint: String| {
+                    hints.push(hint);
+                    Ok(())
+                })
+                .unwrap();
+            let preimage = MOCK_DATA.to_vec();
+            while server.next_preimage_request(|_| Ok(&preimage)).is_ok() {}
+            hints
+        });
+
+        let reader = OracleReader::new(preimage_client);
+        let hint_writer = HintWriter::new(hint_client);
+        hint_writer.write("l1-block-header 0x00").unwrap();
+        assert_eq!(reader.get(key).unwrap(), MOCK_DATA);
+        assert_eq!(reader.get_many(&[key, key]).unwrap(), vec![MOCK_DATA, MOCK_DATA]);
+        drop(reader);
+        assert_eq!(host.join().unwrap(), vec!["l1-block-header 0x00"]);
+    }
+
+    #[test]
+    fn test_closed_pipe() {
+        let (a, b) = BidirectionalPipe::pair();
+        let mut buf = [0u8; 4];
+        assert!(!b.poll_readable().unwrap());
+        assert_eq!(b.try_read(&mut buf).unwrap(), 0);
+
+        // Data written before the other end is dropped is still readable.
+        a.write(&[1, 2, 3]).unwrap();
+        drop(a);
+        assert!(b.read_exact(&mut buf).is_err());
+        assert_eq!(b.try_read(&mut buf).unwrap(), 3);
+        assert_eq!(buf[..3], [1, 2, 3]);
+        assert!(b.poll_readable().is_err());
+        assert!(b.write(&buf).is_err());
+    }
+}
@@ -1,24 +1,114 @@
-use crate::{PipeHandle, PreimageKey, PreimageOracleClient, PreimageOracleServer};
-use alloc::vec::Vec;
-use anyhow::{bail, Result};
+use crate::{
+    Pipe, PipeHandle, PreimageKey, PreimageOracleClient, PreimageOracleError, PreimageOracleServer,
+};
+use alloc::{sync::Arc, vec::Vec};
+use anyhow::{anyhow, Result};
+use spin::Mutex;
 use tracing::debug;
 
-/// An [OracleReader] is a high-level interface to the preimage oracle.
-#[derive(Debug, Clone, Copy)]
-pub struct OracleReader {
-    pipe_handle: PipeHandle,
+/// The maximum number of keys [OracleReader::get_many] sends to the host before reading their
+/// responses. The keys of a batch take 2KiB, which fits in the buffer of an OS pipe, so that the
+/// client never blocks on sending keys while the host blocks on sending responses.
+const MAX_PIPELINED_KEYS: usize = 64;
+
+/// An [OracleReader] is a high-level interface to the preimage oracle, over a [Pipe] to the host.
+///
+/// The clones of an [OracleReader] share its pipe, and the state of the requests on it, so that a
+/// preimage left pending by one clone is read correctly by the others.
+#[derive(Debug, Clone)]
+pub struct OracleReader<P = PipeHandle> {
+    pipe_handle: P,
+    /// The state of the requests on the pipe. Holding the lock for a whole request keeps the
+    /// requests of the clones from interleaving on the pipe.
+    state: Arc<Mutex<ReaderState>>,
 }
 
-impl OracleReader {
-    /// Create a new [OracleReader] from a [PipeHandle].
-    pub fn new(pipe_handle: PipeHandle) -> Self {
-        Self { pipe_handle }
+/// The state of the requests of an [OracleReader].
+#[derive(Debug, Default)]
+struct ReaderState {
+    /// The preimage whose length prefix has been read from the host, but whose data is still
+    /// waiting in the pipe.
+    pending: PendingPreimage,
+    /// The request sent by [PreimageOracleClient::try_get] whose response is still being
+    /// received.
+    in_flight: Option<InFlightRequest>,
+}
+
+/// A preimage whose length prefix has been read from the host, but whose data is still waiting in
+/// the pipe, such as after [PreimageOracleClient::preimage_len]. A following read of the same key
+/// reuses its length instead of requesting it again, and any other request first discards its
+/// data.
+#[derive(Debug, Default)]
+pub(crate) struct PendingPreimage(Option<(PreimageKey, usize)>);
+
+impl PendingPreimage {
+    /// Records the preimage of `key`, of `length` bytes, as pending.
+    pub(crate) fn set(&mut self, key: PreimageKey, length: usize) {
+        self.0 = Some((key, length));
+    }
+
+    /// Takes the pending preimage before a request whose first key is `next`. Returns the length
+    /// of the pending preimage if it is the preimage of `next`, and the number of bytes of pending
+    /// data the caller must discard from the pipe before sending the request.
+    pub(crate) fn take(&mut self, next: Option<PreimageKey>) -> (Option<usize>, usize) {
+        match self.0.take() {
+            Some((key, length)) if Some(key) == next => (Some(length), 0),
+            Some((_, length)) => (None, length),
+            None => (None, 0),
+        }
+    }
+}
+
+/// A preimage request sent to the host without waiting for its response.
+#[derive(Debug, Clone)]
+struct InFlightRequest {
+    /// The requested key.
+    key: PreimageKey,
+    /// The bytes of the response received so far, starting with the length prefix.
+    received: Vec<u8>,
+}
+
+impl InFlightRequest {
+    /// Returns the length of the full response, or of its length prefix while it is incomplete.
+    fn response_len(&self) -> usize {
+        match self.received.get(..8) {
+            Some(prefix) => 8 + u64::from_be_bytes(prefix.try_into().expect("8 bytes")) as usize,
+            None => 8,
+        }
+    }
+}
+
+impl<P: Pipe> OracleReader<P> {
+    /// Create a new [OracleReader] from a [Pipe], such as a [PipeHandle].
+    pub fn new(pipe_handle: P) -> Self {
+        Self { pipe_handle, state: Arc::new(Mutex::new(ReaderState::default())) }
+    }
+
+    /// Returns whether the response to the request sent by [PreimageOracleClient::try_get] can be
+    /// read without blocking, so that the caller can wait for the pipe before polling the
+    /// preimage again. Returns `true` if no request is in flight.
+    pub fn is_ready(&self) -> Result<bool> {
+        if self.state.lock().in_flight.is_none() {
+            return Ok(true);
+        }
+        self.pipe_handle.poll_readable()
     }
 
     /// Set the preimage key for the global oracle reader. This will overwrite any existing key, and
     /// block until the host has prepared the preimage and responded with the length of the
     /// preimage.
-    fn write_key(&self, key: PreimageKey) -> Result<usize> {
+    ///
+    /// If the length of the preimage for the same key was already read by
+    /// [PreimageOracleClient::preimage_len], the key is not sent to the host again. The data of
+    /// any other pending preimage is discarded.
+    fn write_key(&self, state: &mut ReaderState, key: PreimageKey) -> Result<usize> {
+        self.settle(state)?;
+        let (reused, stale) = state.pending.take(Some(key));
+        self.discard(stale)?;
+        if let Some(length) = reused {
+            return Ok(length);
+        }
+
         // Write the key to the host so that it can prepare the preimage.
         let key_bytes: [u8; 32] = key.into();
         self.pipe_handle.write(&key_bytes)?;
@@ -28,15 +118,39 @@ impl OracleReader {
         self.pipe_handle.read_exact(&mut length_buffer)?;
         Ok(u64::from_be_bytes(length_buffer) as usize)
     }
+
+    /// Blocks until the response to the request sent by [PreimageOracleClient::try_get] is
+    /// received, and discards it, so that the pipe is ready for a new request.
+    fn settle(&self, state: &mut ReaderState) -> Result<()> {
+        let Some(mut request) = state.in_flight.take() else { return Ok(()) };
+        if request.received.len() < 8 {
+            let read = request.received.len();
+            request.received.resize(8, 0);
+            self.pipe_handle.read_exact(&mut request.received[read..])?;
+        }
+        self.discard(request.response_len() - request.received.len())
+    }
+
+    /// Reads and discards `length` bytes of preimage data from the pipe.
+    fn discard(&self, mut length: usize) -> Result<()> {
+        let mut scratch = [0u8; 256];
+        while length > 0 {
+            let chunk = length.min(scratch.len());
+            self.pipe_handle.read_exact(&mut scratch[..chunk])?;
+            length -= chunk;
+        }
+        Ok(())
+    }
 }
 
-impl PreimageOracleClient for OracleReader {
+impl<P: Pipe> PreimageOracleClient for OracleReader<P> {
     /// Get the data corresponding to the currently set key from the host. Return the data in a new
     /// heap allocated `Vec<u8>`
     fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
         debug!(target: "oracle_client", "Requesting data from preimage oracle. Key {key}");
 
-        let length = self.write_key(key)?;
+        let mut state = self.state.lock();
+        let length = self.write_key(&mut state, key)?;
         let mut data_buffer = alloc::vec![0; length];
 
         debug!(target: "oracle_client", "Reading data from preimage oracle. Key {key}");
@@ -55,13 +169,19 @@ impl PreimageOracleClient for OracleReader {
         debug!(target: "oracle_client", "Requesting data from preimage oracle. Key {key}");
 
         // Write the key to the host and read the length of the preimage.
-        let length = self.write_key(key)?;
+        let mut state = self.state.lock();
+        let length = self.write_key(&mut state, key)?;
 
         debug!(target: "oracle_client", "Reading data from preimage oracle. Key {key}");
 
-        // Ensure the buffer is the correct size.
+        // Ensure the buffer is the correct size. The preimage data stays in the pipe, so that it
+        // can still be read with a correctly sized buffer.
         if buf.len() != length {
-            bail!("Buffer size {} does not match preimage size {}", buf.len(), length);
+            state.pending.set(key, length);
+            return Err(anyhow!(PreimageOracleError::LengthMismatch {
+                expected: buf.len(),
+                actual: length
+            }));
         }
 
         self.pipe_handle.read_exact(buf)?;
@@ -70,22 +190,122 @@ impl PreimageOracleClient for OracleReader {
 
         Ok(())
     }
+
+    /// Get the length of the data corresponding to the key from the host. The data itself is left
+    /// in the pipe for a following read of the same key.
+    fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        debug!(target: "oracle_client", "Requesting preimage length. Key {key}");
+
+        let mut state = self.state.lock();
+        let length = self.write_key(&mut state, key)?;
+        state.pending.set(key, length);
+        Ok(length)
+    }
+
+    /// Get the data corresponding to each of the keys from the host. The keys are sent in batches
+    /// of up to [MAX_PIPELINED_KEYS] before reading their responses, so the [Pipe] must be able to
+    /// buffer a batch of keys.
+    fn get_many(&self, keys: &[PreimageKey]) -> Result<Vec<Vec<u8>>> {
+        debug!(target: "oracle_client", "Requesting {} preimages from preimage oracle", keys.len());
+        let mut state = self.state.lock();
+        self.settle(&mut state)?;
+
+        // The length of a pending preimage is reused if it is the first key requested, otherwise
+        // its data is discarded.
+        let (mut reused, stale) = state.pending.take(keys.first().copied());
+        self.discard(stale)?;
+
+        let mut preimages = Vec::with_capacity(keys.len());
+        for batch in keys.chunks(MAX_PIPELINED_KEYS) {
+            let requests = batch[usize::from(reused.is_some())..]
+                .iter()
+                .flat_map(|key| <[u8; 32]>::from(*key))
+                .collect::<Vec<_>>();
+            if !requests.is_empty() {
+                self.pipe_handle.write(&requests)?;
+            }
+
+            for _ in batch {
+                let length = match reused.take() {
+                    Some(length) => length,
+                    None => {
+                        let mut length_buffer = [0u8; 8];
+                        self.pipe_handle.read_exact(&mut length_buffer)?;
+                        u64::from_be_bytes(length_buffer) as usize
+                    }
+                };
+                let mut data_buffer = alloc::vec![0; length];
+                self.pipe_handle.read_exact(&mut data_buffer)?;
+                preimages.push(data_buffer);
+            }
+        }
+
+        debug!(target: "oracle_client", "Successfully read {} preimages", preimages.len());
+
+        Ok(preimages)
+    }
+
+    /// Get the data corresponding to the key from the host, reading only the part of the response
+    /// that is already in the [Pipe]. Only one request is in flight at a time: polling another key,
+    /// or making any other request, first waits for the response of the request in flight and
+    /// discards it. Over a [Pipe] that cannot poll its peer, such as a [PipeHandle], this blocks
+    /// until the preimage is received.
+    fn try_get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+        let mut state = self.state.lock();
+        let mut request = match state.in_flight.take() {
+            Some(request) if request.key == key => request,
+            request => {
+                state.in_flight = request;
+                self.settle(&mut state)?;
+
+                // The length of a pending preimage for the same key is reused.
+                let (reused, stale) = state.pending.take(Some(key));
+                self.discard(stale)?;
+                let received = match reused {
+                    Some(length) => (length as u64).to_be_bytes().to_vec(),
+                    None => {
+                        debug!(target: "oracle_client", "Polling data. Key {key}");
+                        self.pipe_handle.write(&<[u8; 32]>::from(key))?;
+                        Vec::new()
+                    }
+                };
+                InFlightRequest { key, received }
+            }
+        };
+
+        loop {
+            let response_len = request.response_len();
+            if request.received.len() == response_len {
+                debug!(target: "oracle_client", "Successfully polled data. Key: {key}");
+                return Ok(Some(request.received.split_off(8)));
+            }
+            if !self.pipe_handle.poll_readable()? {
+                state.in_flight = Some(request);
+                return Ok(None);
+            }
+
+            let read = request.received.len();
+            request.received.resize(response_len, 0);
+            let n = self.pipe_handle.try_read(&mut request.received[read..])?;
+            request.received.truncate(read + n);
+        }
+    }
 }
 
 /// An [OracleServer] is a router for the host to serve data back to the client [OracleReader].
 #[derive(Debug, Clone, Copy)]
-pub struct OracleServer {
-    pipe_handle: PipeHandle,
+pub struct OracleServer<P = PipeHandle> {
+    pipe_handle: P,
 }
 
-impl OracleServer {
-    /// Create a new [OracleServer] from a [PipeHandle].
-    pub fn new(pipe_handle: PipeHandle) -> Self {
+impl<P: Pipe> OracleServer<P> {
+    /// Create a new [OracleServer] from a [Pipe], such as a [PipeHandle].
+    pub fn new(pipe_handle: P) -> Self {
         Self { pipe_handle }
     }
 }
 
-impl PreimageOracleServer for OracleServer {
+impl<P: Pipe> PreimageOracleServer for OracleServer<P> {
     fn next_preimage_request<'a>(
         &self,
         mut get_preimage: impl FnMut(PreimageKey) -> Result<&'a Vec<u8>>,
@@ -116,8 +336,6 @@ impl PreimageOracleServer for OracleServer {
 
 #[cfg(test)]
 mod test {
-    extern crate std;
-
     use super::*;
     use crate::PreimageKeyType;
     use alloy_primitives::keccak256;
@@ -172,7 +390,7 @@ mod test {
         preimages.insert(key_b, MOCK_DATA_B.to_vec());
 
         let sys = client_and_host();
-        let (oracle_reader, oracle_server) = (sys.oracle_reader, sys.oracle_server);
+        let (oracle_reader, oracle_server) = (sys.oracle_reader.clone(), sys.oracle_server);
 
         let client = tokio::task::spawn(async move {
             let contents_a = oracle_reader.get(key_a).unwrap();
@@ -200,4 +418,70 @@ mod test {
         assert_eq!(contents_a, MOCK_DATA_A);
         assert_eq!(contents_b, MOCK_DATA_B);
     }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_oracle_preimage_len_and_get_exact() {
+        const MOCK_DATA_A: &[u8] = b"1234567890";
+        const MOCK_DATA_B: &[u8] = b"FACADE";
+        let key_a: PreimageKey =
+            PreimageKey::new(*keccak256(MOCK_DATA_A), PreimageKeyType::Keccak256);
+        let key_b: PreimageKey =
+            PreimageKey::new(*keccak256(MOCK_DATA_B), PreimageKeyType::Keccak256);
+
+        let mut preimages = HashMap::new();
+        preimages.insert(key_a, MOCK_DATA_A.to_vec());
+        preimages.insert(key_b, MOCK_DATA_B.to_vec());
+
+        let sys = client_and_host();
+        let (oracle_reader, oracle_server) = (sys.oracle_reader.clone(), sys.oracle_server);
+
+        let client = tokio::task::spawn(async move {
+            // The length is known before the data is read, and the data is read with a
+            // preallocated buffer without requesting the preimage again.
+            let len_a = oracle_reader.preimage_len(key_a).unwrap();
+            let mut contents_a = [0u8; MOCK_DATA_A.len()];
+            oracle_reader.get_exact(key_a, &mut contents_a).unwrap();
+
+            // A mismatched buffer errors with the preimage still readable afterwards.
+            let mut too_small = [0u8; 1];
+            let err = oracle_reader.get_exact(key_b, &mut too_small).unwrap_err();
+            let err = *err.downcast_ref::<PreimageOracleError>().unwrap();
+            let contents_b = oracle_reader.get(key_b).unwrap();
+
+            // A pending preimage of another key is discarded before the next request.
+            oracle_reader.preimage_len(key_b).unwrap();
+            let contents_a_again = oracle_reader.get(key_a).unwrap();
+
+            // The clones of the reader share the pending preimage.
+            oracle_reader.clone().preimage_len(key_b).unwrap();
+            let contents_b_again = oracle_reader.get(key_b).unwrap();
+
+            drop(sys);
+
+            (len_a, contents_a, err, contents_b, contents_a_again, contents_b_again)
+        });
+        let host = tokio::task::spawn(async move {
+            let get_preimage =
+                |key| preimages.get(&key).ok_or(anyhow::anyhow!("Preimage not available"));
+
+            loop {
+                if oracle_server.next_preimage_request(get_preimage).is_err() {
+                    break;
+                }
+            }
+        });
+
+        let (client, _) = tokio::join!(client, host);
+        let (len_a, contents_a, err, contents_b, contents_a_again, contents_b_again) =
+            client.unwrap();
+        assert_eq!(len_a, MOCK_DATA_A.len());
+        assert_eq!(contents_a, MOCK_DATA_A);
+        assert_eq!(
+            err,
+            PreimageOracleError::LengthMismatch { expected: 1, actual: MOCK_DATA_B.len() }
+        );
+        assert_eq!(contents_b, MOCK_DATA_B);
+        assert_eq!(contents_a_again, MOCK_DATA_A);
+        assert_eq!(contents_b_again, MOCK_DATA_B);
+    }
 }
@@ -1,9 +1,32 @@
-//! This module contains a rudamentary pipe between two file descriptors, using [kona_common::io]
-//! for reading and writing from the file descriptors.
+//! This module contains the [Pipe] trait, and a rudamentary pipe between two file descriptors,
+//! using [kona_common::io] for reading and writing from the file descriptors.
 
 use anyhow::{bail, Result};
 use kona_common::{io, FileDescriptor};
 
+/// The [Pipe] trait is one end of a bidirectional, in-order byte channel between the client and
+/// the host, over which the preimage oracle and hint protocols are spoken.
+pub trait Pipe {
+    /// Reads exactly `buf.len()` bytes into `buf`, blocking until all bytes are read.
+    fn read_exact(&self, buf: &mut [u8]) -> Result<usize>;
+
+    /// Writes the whole buffer to the pipe, returning the number of bytes written.
+    fn write(&self, buf: &[u8]) -> Result<usize>;
+
+    /// Returns whether data can be read from the pipe without blocking. Pipes that cannot poll
+    /// their peer, such as [PipeHandle], always report data as readable.
+    fn poll_readable(&self) -> Result<bool> {
+        Ok(true)
+    }
+
+    /// Reads up to `buf.len()` bytes into `buf` without blocking, returning the number of bytes
+    /// read, which is zero if no data is readable. Pipes that cannot poll their peer, such as
+    /// [PipeHandle], block until `buf` is filled.
+    fn try_read(&self, buf: &mut [u8]) -> Result<usize> {
+        self.read_exact(buf)
+    }
+}
+
 /// [PipeHandle] is a handle for one end of a bidirectional pipe.
 #[derive(Debug, Clone, Copy)]
 pub struct PipeHandle {
@@ -50,3 +73,13 @@ impl PipeHandle {
         Ok(written)
     }
 }
+
+impl Pipe for PipeHandle {
+    fn read_exact(&self, buf: &mut [u8]) -> Result<usize> {
+        PipeHandle::read_exact(self, buf)
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize> {
+        PipeHandle::write(self, buf)
+    }
+}
@@ -0,0 +1,380 @@
+//! Contains the helpers for the results of accelerated precompiles, which the client fetches from
+//! the host with [PreimageKeyType::Precompile] keys.
+//!
+//! The preimage of a precompile key is the result of the call: a status byte, `1` if the call
+//! succeeded and `0` if it failed, followed by the output of the precompile.
+
+use crate::{PrecompileResultError, PreimageKey, PreimageKeyType};
+use alloc::vec::Vec;
+use alloy_primitives::{address, b256, keccak256, Address, B256, U256};
+use sha2::{Digest, Sha256};
+
+/// The address of the `ecrecover` precompile.
+pub const ECRECOVER_ADDRESS: Address = address!("0000000000000000000000000000000000000001");
+
+/// The address of the `sha256` precompile.
+pub const SHA256_ADDRESS: Address = address!("0000000000000000000000000000000000000002");
+
+/// The address of the `bn256Pairing` precompile.
+pub const BN256_PAIRING_ADDRESS: Address = address!("0000000000000000000000000000000000000008");
+
+/// The address of the KZG point evaluation precompile, introduced in [EIP-4844].
+///
+/// [EIP-4844]: https://eips.ethereum.org/EIPS/eip-4844
+pub const KZG_POINT_EVALUATION_ADDRESS: Address =
+    address!("000000000000000000000000000000000000000a");
+
+/// The length of a single `bn256Pairing` input pair.
+const BN256_PAIR_LEN: usize = 192;
+
+/// The length of a KZG point evaluation input:
+/// `versioned_hash ++ z ++ y ++ commitment ++ proof`.
+const KZG_POINT_EVALUATION_INPUT_LEN: usize = 192;
+
+/// The version byte of KZG versioned hashes.
+const VERSIONED_HASH_VERSION_KZG: u8 = 0x01;
+
+/// The number of field elements in a blob, returned by the KZG point evaluation precompile.
+const FIELD_ELEMENTS_PER_BLOB: u64 = 4096;
+
+/// The BLS12-381 scalar field modulus, returned by the KZG point evaluation precompile.
+const BLS_MODULUS: B256 = b256!("73eda753299d7d483339d80809a1d80553bda402fffe5bfeffffffff00000001");
+
+/// Returns the [PreimageKey] of the result of calling the precompile at `address` with `input`,
+/// i.e. `keccak256(address ++ input)` with the [PreimageKeyType::Precompile] type byte.
+pub fn precompile_key(address: Address, input: &[u8]) -> PreimageKey {
+    let mut data = Vec::with_capacity(20 + input.len());
+    data.extend_from_slice(address.as_slice());
+    data.extend_from_slice(input);
+    PreimageKey::new(*keccak256(data), PreimageKeyType::Precompile)
+}
+
+/// Encodes the result of a precompile call into the preimage format served by the host.
+pub fn encode_precompile_result(success: bool, output: &[u8]) -> Vec<u8> {
+    let mut result = Vec::with_capacity(output.len() + 1);
+    result.push(success as u8);
+    result.extend_from_slice(output);
+    result
+}
+
+/// Returns the [PreimageKey] and the preimage of the result of calling the precompile at `address`
+/// with `input`, for the host to serve after executing the call.
+pub fn precompile_preimage(
+    address: Address,
+    input: &[u8],
+    success: bool,
+    output: &[u8],
+) -> (PreimageKey, Vec<u8>) {
+    (precompile_key(address, input), encode_precompile_result(success, output))
+}
+
+/// Decodes a precompile result preimage into the success flag and the output of the call.
+pub fn decode_precompile_result(result: &[u8]) -> Result<(bool, &[u8]), PrecompileResultError> {
+    let (status, output) = result.split_first().ok_or(PrecompileResultError::EmptyResult)?;
+    match status {
+        0 => Ok((false, output)),
+        1 => Ok((true, output)),
+        status => Err(PrecompileResultError::InvalidStatus(*status)),
+    }
+}
+
+/// Checks the result of calling the precompile at `address` with `input`, as claimed by the host,
+/// as far as it can be checked without executing the precompile.
+///
+/// A failed call must have no output. For a successful call, the output length is checked for
+/// every accelerated precompile, the output of `sha256` is recomputed, and the versioned hash of
+/// a KZG point evaluation is checked against its commitment. The results of other precompiles
+/// are not checked.
+pub fn validate_precompile_result(
+    address: Address,
+    input: &[u8],
+    success: bool,
+    output: &[u8],
+) -> Result<(), PrecompileResultError> {
+    if !success {
+        if !output.is_empty() {
+            return Err(PrecompileResultError::InvalidOutputLength { address, len: output.len() });
+        }
+        return Ok(());
+    }
+
+    let invalid_length = PrecompileResultError::InvalidOutputLength { address, len: output.len() };
+    match address {
+        ECRECOVER_ADDRESS => {
+            // An unrecoverable signature produces no output, otherwise the output is the
+            // recovered address, left padded to 32 bytes.
+            match output.len() {
+                0 => Ok(()),
+                32 if output[..12].iter().all(|b| *b == 0) => Ok(()),
+                32 => Err(PrecompileResultError::InvalidOutput(address)),
+                _ => Err(invalid_length),
+            }
+        }
+        SHA256_ADDRESS => {
+            if output.len() != 32 {
+                return Err(invalid_length);
+            }
+            if output != Sha256::digest(input).as_slice() {
+                return Err(PrecompileResultError::InvalidOutput(address));
+            }
+            Ok(())
+        }
+        BN256_PAIRING_ADDRESS => {
+            if !input.len().is_multiple_of(BN256_PAIR_LEN) {
+                return Err(PrecompileResultError::UnexpectedSuccess(address));
+            }
+            if output.len() != 32 {
+                return Err(invalid_length);
+            }
+            // The output is a boolean, left padded to 32 bytes.
+            if output[..31].iter().any(|b| *b != 0) || output[31] > 1 {
+                return Err(PrecompileResultError::InvalidOutput(address));
+            }
+            Ok(())
+        }
+        KZG_POINT_EVALUATION_ADDRESS => {
+            if input.len() != KZG_POINT_EVALUATION_INPUT_LEN {
+                return Err(PrecompileResultError::UnexpectedSuccess(address));
+            }
+            let mut versioned_hash = Sha256::digest(&input[96..144]);
+            versioned_hash[0] = VERSIONED_HASH_VERSION_KZG;
+            if input[..32] != versioned_hash[..] {
+                return Err(PrecompileResultError::UnexpectedSuccess(address));
+            }
+            if output.len() != 64 {
+                return Err(invalid_length);
+            }
+            if output[..32] != U256::from(FIELD_ELEMENTS_PER_BLOB).to_be_bytes::<32>() ||
+                output[32..] != BLS_MODULUS[..]
+            {
+                return Err(PrecompileResultError::InvalidOutput(address));
+            }
+            Ok(())
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+
+    /// Returns a KZG point evaluation input whose versioned hash matches its commitment.
+    fn kzg_input() -> Vec<u8> {
+        let commitment = [0xC0; 48];
+        let mut versioned_hash = Sha256::digest(commitment);
+        versioned_hash[0] = VERSIONED_HASH_VERSION_KZG;
+
+        let mut input = versioned_hash.to_vec();
+        input.extend_from_slice(&[0x11; 64]);
+        input.extend_from_slice(&commitment);
+        input.extend_from_slice(&[0x22; 48]);
+        input
+    }
+
+    fn kzg_output() -> Vec<u8> {
+        let mut output = U256::from(FIELD_ELEMENTS_PER_BLOB).to_be_bytes::<32>().to_vec();
+        output.extend_from_slice(BLS_MODULUS.as_slice());
+        output
+    }
+
+    /// Sends the result through the preimage format and validates it.
+    fn roundtrip(
+        address: Address,
+        input: &[u8],
+        success: bool,
+        output: &[u8],
+    ) -> Result<(), PrecompileResultError> {
+        let result = encode_precompile_result(success, output);
+        let (decoded_success, decoded_output) = decode_precompile_result(&result)?;
+        assert_eq!((decoded_success, decoded_output), (success, output));
+        validate_precompile_result(address, input, decoded_success, decoded_output)
+    }
+
+    #[test]
+    fn test_precompile_key() {
+        let input = [0xAB; 128];
+        let key = precompile_key(ECRECOVER_ADDRESS, &input);
+        assert_eq!(key.key_type(), PreimageKeyType::Precompile);
+
+        let mut expected = *keccak256([ECRECOVER_ADDRESS.as_slice(), &input[..]].concat());
+        expected[0] = PreimageKeyType::Precompile as u8;
+        assert_eq!(<[u8; 32]>::from(key), expected);
+        assert_ne!(key, precompile_key(SHA256_ADDRESS, &input));
+    }
+
+    #[test]
+    fn test_decode_malformed_result() {
+        assert_eq!(decode_precompile_result(&[]), Err(PrecompileResultError::EmptyResult));
+        assert_eq!(decode_precompile_result(&[0x02]), Err(PrecompileResultError::InvalidStatus(2)));
+    }
+
+    #[test]
+    fn test_failed_call_with_output() {
+        assert_eq!(
+            roundtrip(SHA256_ADDRESS, &[], false, &[0x00]),
+            Err(PrecompileResultError::InvalidOutputLength { address: SHA256_ADDRESS, len: 1 })
+        );
+        assert!(roundtrip(KZG_POINT_EVALUATION_ADDRESS, &kzg_input(), false, &[]).is_ok());
+    }
+
+    #[test]
+    fn test_ecrecover_result() {
+        let mut output = [0u8; 32];
+        output[12..].copy_from_slice(&[0xEE; 20]);
+        assert!(roundtrip(ECRECOVER_ADDRESS, &[0x01; 128], true, &output).is_ok());
+        assert!(roundtrip(ECRECOVER_ADDRESS, &[0x01; 128], true, &[]).is_ok());
+
+        assert_eq!(
+            roundtrip(ECRECOVER_ADDRESS, &[0x01; 128], true, &[0u8; 64]),
+            Err(PrecompileResultError::InvalidOutputLength { address: ECRECOVER_ADDRESS, len: 64 })
+        );
+        assert_eq!(
+            roundtrip(ECRECOVER_ADDRESS, &[0x01; 128], true, &[0xEE; 32]),
+            Err(PrecompileResultError::InvalidOutput(ECRECOVER_ADDRESS))
+        );
+    }
+
+    #[test]
+    fn test_sha256_result() {
+        let input = b"kona";
+        let output = Sha256::digest(input);
+        assert!(roundtrip(SHA256_ADDRESS, input, true, &output).is_ok());
+
+        assert_eq!(
+            roundtrip(SHA256_ADDRESS, input, true, &output[..31]),
+            Err(PrecompileResultError::InvalidOutputLength { address: SHA256_ADDRESS, len: 31 })
+        );
+        assert_eq!(
+            roundtrip(SHA256_ADDRESS, input, true, &[0u8; 32]),
+            Err(PrecompileResultError::InvalidOutput(SHA256_ADDRESS))
+        );
+    }
+
+    #[test]
+    fn test_bn256_pairing_result() {
+        let input = vec![0x01; 2 * BN256_PAIR_LEN];
+        let mut output = [0u8; 32];
+        assert!(roundtrip(BN256_PAIRING_ADDRESS, &input, true, &output).is_ok());
+        output[31] = 1;
+        assert!(roundtrip(BN256_PAIRING_ADDRESS, &input, true, &output).is_ok());
+
+        output[31] = 2;
+        assert_eq!(
+            roundtrip(BN256_PAIRING_ADDRESS, &input, true, &output),
+            Err(PrecompileResultError::InvalidOutput(BN256_PAIRING_ADDRESS))
+        );
+        assert_eq!(
+            roundtrip(BN256_PAIRING_ADDRESS, &input, true, &[0u8; 31]),
+            Err(PrecompileResultError::InvalidOutputLength {
+                address: BN256_PAIRING_ADDRESS,
+                len: 31
+            })
+        );
+        assert_eq!(
+            roundtrip(BN256_PAIRING_ADDRESS, &input[1..], true, &[0u8; 32]),
+            Err(PrecompileResultError::UnexpectedSuccess(BN256_PAIRING_ADDRESS))
+        );
+    }
+
+    #[test]
+    fn test_kzg_point_evaluation_result() {
+        let input = kzg_input();
+        assert!(roundtrip(KZG_POINT_EVALUATION_ADDRESS, &input, true, &kzg_output()).is_ok());
+
+        // The versioned hash does not match the commitment, the call must fail.
+        let mut wrong_hash = input.clone();
+        wrong_hash[31] ^= 0xFF;
+        assert_eq!(
+            roundtrip(KZG_POINT_EVALUATION_ADDRESS, &wrong_hash, true, &kzg_output()),
+            Err(PrecompileResultError::UnexpectedSuccess(KZG_POINT_EVALUATION_ADDRESS))
+        );
+        assert!(roundtrip(KZG_POINT_EVALUATION_ADDRESS, &wrong_hash, false, &[]).is_ok());
+
+        // The input has an invalid length, the call must fail.
+        assert_eq!(
+            roundtrip(KZG_POINT_EVALUATION_ADDRESS, &input[..191], true, &kzg_output()),
+            Err(PrecompileResultError::UnexpectedSuccess(KZG_POINT_EVALUATION_ADDRESS))
+        );
+
+        assert_eq!(
+            roundtrip(KZG_POINT_EVALUATION_ADDRESS, &input, true, &kzg_output()[..32]),
+            Err(PrecompileResultError::InvalidOutputLength {
+                address: KZG_POINT_EVALUATION_ADDRESS,
+                len: 32
+            })
+        );
+        assert_eq!(
+            roundtrip(KZG_POINT_EVALUATION_ADDRESS, &input, true, &[0u8; 64]),
+            Err(PrecompileResultError::InvalidOutput(KZG_POINT_EVALUATION_ADDRESS))
+        );
+    }
+
+    #[test]
+    fn test_precompile_result_through_oracle() {
+        use crate::{Hint, HintType, HintWriterClient, PreimageOracleClient};
+        use alloc::string::ToString;
+        use anyhow::anyhow;
+        use core::cell::RefCell;
+
+        /// A host that executes the hinted `sha256` calls, and serves their results.
+        #[derive(Default)]
+        struct MockHost {
+            preimages: RefCell<Vec<(PreimageKey, Vec<u8>)>>,
+            corrupt: bool,
+        }
+
+        impl HintWriterClient for MockHost {
+            fn write(&self, hint: &str) -> anyhow::Result<()> {
+                let hint = hint.parse::<Hint>().map_err(|e| anyhow!(e))?;
+                let (address, input) = hint.precompile_call().ok_or(anyhow!("Unexpected hint"))?;
+                let output =
+                    if self.corrupt { [0u8; 32].into() } else { Sha256::digest(input).to_vec() };
+                self.preimages
+                    .borrow_mut()
+                    .push(precompile_preimage(address, input, true, &output));
+                Ok(())
+            }
+        }
+
+        impl PreimageOracleClient for MockHost {
+            fn get(&self, key: PreimageKey) -> anyhow::Result<Vec<u8>> {
+                let preimages = self.preimages.borrow();
+                let (_, value) =
+                    preimages.iter().find(|(k, _)| *k == key).ok_or(anyhow!("Missing preimage"))?;
+                Ok(value.clone())
+            }
+
+            fn get_exact(&self, _: PreimageKey, _: &mut [u8]) -> anyhow::Result<()> {
+                unimplemented!()
+            }
+
+            fn preimage_len(&self, _: PreimageKey) -> anyhow::Result<usize> {
+                unimplemented!()
+            }
+        }
+
+        let hint = Hint::precompile(SHA256_ADDRESS, b"kona");
+        assert_eq!(hint.ty, HintType::L1Precompile);
+        assert_eq!(hint.precompile_call(), Some((SHA256_ADDRESS, &b"kona"[..])));
+        assert_eq!(hint.to_string().parse::<Hint>().unwrap().precompile_call().unwrap().1, b"kona");
+
+        let host = MockHost::default();
+        assert!(host.precompile_result(SHA256_ADDRESS, b"kona").is_err());
+        host.hint_precompile(SHA256_ADDRESS, b"kona").unwrap();
+        assert_eq!(
+            host.precompile_result(SHA256_ADDRESS, b"kona").unwrap(),
+            (true, Sha256::digest(b"kona").to_vec())
+        );
+
+        // A result that does not match the input is rejected by the client.
+        let host = MockHost { corrupt: true, ..Default::default() };
+        host.hint_precompile(SHA256_ADDRESS, b"kona").unwrap();
+        let err = host.precompile_result(SHA256_ADDRESS, b"kona").unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PrecompileResultError>(),
+            Some(&PrecompileResultError::InvalidOutput(SHA256_ADDRESS))
+        );
+    }
+}
@@ -0,0 +1,176 @@
+//! This module contains the [HintRouter], which dispatches the typed [Hint]s received by the host
+//! to the [HintHandler] registered for their [HintType].
+
+use crate::{Hint, HintRoutingError, HintType};
+use alloc::{boxed::Box, vec::Vec};
+use anyhow::{anyhow, Result};
+
+/// A [HintHandler] prepares the preimages requested by the hints of the [HintType]s it is
+/// registered for in a [HintRouter].
+///
+/// [HintHandler] is implemented for closures taking a `&Hint`. As the closures are not passed as
+/// an [FnMut] bound, their argument and return types must be annotated.
+pub trait HintHandler {
+    /// Handles the `hint`.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was handled, and its preimages are ready to be served.
+    /// - `Err(_)` if the hint could not be handled.
+    fn handle(&mut self, hint: &Hint) -> Result<()>;
+}
+
+impl<F: FnMut(&Hint) -> Result<()>> HintHandler for F {
+    fn handle(&mut self, hint: &Hint) -> Result<()> {
+        self(hint)
+    }
+}
+
+/// The number of hints routed to the handler of a [HintType].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HintMetrics {
+    /// The number of hints the handler handled successfully.
+    pub handled: u64,
+    /// The number of hints the handler failed to handle.
+    pub failed: u64,
+}
+
+/// A handler registered in a [HintRouter], with its metrics.
+struct Route<'a> {
+    /// The type of the hints routed to the handler.
+    ty: HintType,
+    /// The handler.
+    handler: Box<dyn HintHandler + Send + 'a>,
+    /// The metrics of the handler.
+    metrics: HintMetrics,
+}
+
+/// A [HintRouter] dispatches each [Hint] to the [HintHandler] registered for its [HintType], so
+/// that the host can handle each family of hints in its own module. It is used as the routing
+/// function of [crate::HintReaderServer::next_typed_hint]:
+///
+/// ```ignore
+/// let mut router = HintRouter::new()
+///     .with_handler(HintType::L1BlockHeader, |hint: &Hint| fetch_l1_header(hint))
+///     .with_handler(HintType::L2StateNode, l2_fetcher);
+/// hint_reader.next_typed_hint(|hint| router.route(&hint))?;
+/// ```
+#[derive(Default)]
+pub struct HintRouter<'a> {
+    /// The registered handlers, with at most one handler per [HintType].
+    routes: Vec<Route<'a>>,
+}
+
+impl<'a> HintRouter<'a> {
+    /// Creates a new [HintRouter] without any handlers.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the `handler` for the hints of type `ty`, replacing any handler previously
+    /// registered for it along with its metrics.
+    pub fn register(&mut self, ty: HintType, handler: impl HintHandler + Send + 'a) -> &mut Self {
+        let route = Route { ty, handler: Box::new(handler), metrics: HintMetrics::default() };
+        match self.routes.iter_mut().find(|route| route.ty == ty) {
+            Some(existing) => *existing = route,
+            None => self.routes.push(route),
+        }
+        self
+    }
+
+    /// Registers the `handler` for the hints of type `ty`, like [HintRouter::register].
+    pub fn with_handler(mut self, ty: HintType, handler: impl HintHandler + Send + 'a) -> Self {
+        self.register(ty, handler);
+        self
+    }
+
+    /// Returns whether a handler is registered for the hints of type `ty`.
+    pub fn handles(&self, ty: HintType) -> bool {
+        self.routes.iter().any(|route| route.ty == ty)
+    }
+
+    /// Returns the [HintMetrics] of the handler registered for the hints of type `ty`, or [None]
+    /// if no handler is registered for it.
+    pub fn metrics(&self, ty: HintType) -> Option<HintMetrics> {
+        self.routes.iter().find(|route| route.ty == ty).map(|route| route.metrics)
+    }
+
+    /// Routes the `hint` to the handler registered for its type.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was handled.
+    /// - `Err(_)` if the handler failed to handle the hint, or if no handler is registered for its
+    ///   type, in which case the error is a [HintRoutingError::UnhandledHintType].
+    pub fn route(&mut self, hint: &Hint) -> Result<()> {
+        let route = self
+            .routes
+            .iter_mut()
+            .find(|route| route.ty == hint.ty)
+            .ok_or_else(|| anyhow!(HintRoutingError::UnhandledHintType(hint.ty)))?;
+
+        let result = route.handler.handle(hint);
+        match result {
+            Ok(()) => route.metrics.handled += 1,
+            Err(_) => route.metrics.failed += 1,
+        }
+        result
+    }
+}
+
+impl core::fmt::Debug for HintRouter<'_> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.debug_map().entries(self.routes.iter().map(|route| (route.ty, route.metrics))).finish()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::vec;
+
+    /// A handler rejecting every hint.
+    struct Rejecting;
+
+    impl HintHandler for Rejecting {
+        fn handle(&mut self, _: &Hint) -> Result<()> {
+            Err(anyhow!("Host unavailable"))
+        }
+    }
+
+    #[test]
+    fn test_hint_router() {
+        let mut headers = Vec::new();
+        let mut router = HintRouter::new()
+            .with_handler(HintType::L1BlockHeader, |hint: &Hint| -> Result<()> {
+                headers.push(hint.data.to_vec());
+                Ok(())
+            })
+            .with_handler(HintType::L2Code, Rejecting);
+        assert!(router.handles(HintType::L2Code));
+        assert!(!router.handles(HintType::L2StateNode));
+
+        router.route(&Hint::new(HintType::L1BlockHeader, [0x11; 32]).unwrap()).unwrap();
+        router.route(&Hint::new(HintType::L1BlockHeader, [0x22; 32]).unwrap()).unwrap();
+        assert!(router.route(&Hint::new(HintType::L2Code, [0x33; 32]).unwrap()).is_err());
+        let err = router.route(&Hint::new(HintType::L2StateNode, [0x44; 32]).unwrap()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<HintRoutingError>(),
+            Some(&HintRoutingError::UnhandledHintType(HintType::L2StateNode))
+        );
+
+        assert_eq!(
+            router.metrics(HintType::L1BlockHeader),
+            Some(HintMetrics { handled: 2, failed: 0 })
+        );
+        assert_eq!(router.metrics(HintType::L2Code), Some(HintMetrics { handled: 0, failed: 1 }));
+        assert_eq!(router.metrics(HintType::L2StateNode), None);
+
+        // Registering a handler again replaces it, and resets its metrics.
+        router.register(HintType::L2Code, |_: &Hint| -> Result<()> { Ok(()) });
+        assert_eq!(router.metrics(HintType::L2Code), Some(HintMetrics::default()));
+        router.route(&Hint::new(HintType::L2Code, [0x33; 32]).unwrap()).unwrap();
+        assert_eq!(router.metrics(HintType::L2Code), Some(HintMetrics { handled: 1, failed: 0 }));
+
+        drop(router);
+        assert_eq!(headers, vec![vec![0x11; 32], vec![0x22; 32]]);
+    }
+}
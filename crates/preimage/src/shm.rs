@@ -0,0 +1,571 @@
+//! This module contains the [ShmPipe], a [Pipe] over a pair of ring buffers in shared memory. It
+//! lets a natively executed client talk to the host without a syscall per read or write, which
+//! dominates the cost of the file descriptor pipes for the many small messages of the preimage
+//! oracle protocol.
+//!
+//! The shared memory holds a header with the capacity of the rings, followed by one ring per
+//! direction. Each ring is a single-producer single-consumer queue of bytes: the producer advances
+//! the `head` position after copying data in, and the consumer advances the `tail` position after
+//! copying data out. Both positions only ever grow, so the fill level of a ring is `head - tail`.
+
+use crate::Pipe;
+use alloc::sync::Arc;
+use anyhow::{anyhow, bail, Result};
+use core::{
+    hint::spin_loop,
+    ptr,
+    sync::atomic::{AtomicU64, Ordering},
+};
+use memmap2::{MmapMut, MmapRaw};
+use std::{fs::OpenOptions, path::Path, sync::Mutex, thread};
+
+/// The magic number at the start of the shared memory, `"konashm1"`.
+const MAGIC: u64 = u64::from_be_bytes(*b"konashm1");
+
+/// The size of the header at the start of the shared memory, holding the magic number and the
+/// capacity of the rings.
+const HEADER_SIZE: usize = 64;
+
+/// The size of the header of each ring. The fields written by the producer and by the consumer
+/// live on separate cache lines, so that the two ends do not contend for them.
+const RING_HEADER_SIZE: usize = 128;
+
+/// The number of times to poll a ring before yielding the thread while waiting on the other end.
+const SPIN_LIMIT: usize = 1 << 10;
+
+/// A [ShmPipe] is one end of a bidirectional pipe over shared memory.
+///
+/// A pipe is either set up in-process for both ends with [ShmPipe::pair], or across processes by
+/// the host with [ShmPipe::create] and by the client with [ShmPipe::open]. Clones of a [ShmPipe]
+/// are handles to the same end, and the end is closed once all of them are dropped, which fails
+/// the pending and following operations of the other end.
+#[derive(Debug, Clone)]
+pub struct ShmPipe {
+    /// The end of the pipe, shared by all clones.
+    end: Arc<ShmEnd>,
+}
+
+/// The state of one end of a [ShmPipe].
+#[derive(Debug)]
+struct ShmEnd {
+    /// The mapping of the shared memory.
+    map: Arc<MmapRaw>,
+    /// The capacity of each ring, in bytes.
+    capacity: usize,
+    /// The offset of the ring this end reads from.
+    read_ring: usize,
+    /// The offset of the ring this end writes to.
+    write_ring: usize,
+    /// Serializes the reads of the clones of this end, as the rings only support one consumer.
+    read_lock: Mutex<()>,
+    /// Serializes the writes of the clones of this end, as the rings only support one producer.
+    write_lock: Mutex<()>,
+}
+
+/// A view of the header and data of one ring in the shared memory.
+struct Ring {
+    /// The position up to which the producer has written data.
+    head: *const AtomicU64,
+    /// Set by the producer when its end of the pipe is closed.
+    closed: *const AtomicU64,
+    /// The position up to which the consumer has read data.
+    tail: *const AtomicU64,
+    /// The start of the data of the ring.
+    data: *mut u8,
+}
+
+impl ShmPipe {
+    /// Creates a connected pair of [ShmPipe]s over anonymous shared memory, whose rings hold up to
+    /// `capacity` bytes each.
+    pub fn pair(capacity: usize) -> Result<(Self, Self)> {
+        let map = MmapMut::map_anon(region_size(capacity)?).map_err(|e| anyhow!(e))?;
+        let map = Arc::new(MmapRaw::from(map));
+        init_header(&map, capacity);
+
+        let first = ShmEnd::new(map.clone(), capacity, false);
+        let second = ShmEnd::new(map, capacity, true);
+        Ok((Self { end: Arc::new(first) }, Self { end: Arc::new(second) }))
+    }
+
+    /// Creates the shared memory of a new pipe at `path`, with rings holding up to `capacity`
+    /// bytes each, and returns its first end. The second end is opened from the same path with
+    /// [ShmPipe::open], usually by another process. An existing file at `path` is truncated.
+    pub fn create(path: impl AsRef<Path>, capacity: usize) -> Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .open(path)
+            .map_err(|e| anyhow!(e))?;
+        file.set_len(region_size(capacity)? as u64).map_err(|e| anyhow!(e))?;
+        let map = MmapRaw::map_raw(&file).map_err(|e| anyhow!(e))?;
+        init_header(&map, capacity);
+        Ok(Self { end: Arc::new(ShmEnd::new(Arc::new(map), capacity, false)) })
+    }
+
+    /// Opens the second end of the pipe whose shared memory was created at `path` with
+    /// [ShmPipe::create].
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path).map_err(|e| anyhow!(e))?;
+        let map = MmapRaw::map_raw(&file).map_err(|e| anyhow!(e))?;
+        if map.len() < HEADER_SIZE {
+            bail!("Shared memory is too small to hold a pipe");
+        }
+
+        // SAFETY: The mapping holds at least the header, which is aligned to the page size.
+        let (magic, capacity) = unsafe {
+            let header = map.as_ptr() as *const AtomicU64;
+            ((*header).load(Ordering::Acquire), (*header.add(1)).load(Ordering::Acquire))
+        };
+        if magic != MAGIC {
+            bail!("Shared memory does not hold a pipe");
+        }
+        let capacity = usize::try_from(capacity).map_err(|e| anyhow!(e))?;
+        if map.len() != region_size(capacity)? {
+            bail!("Shared memory size does not match the capacity of the pipe");
+        }
+
+        let end = ShmEnd::new(Arc::new(map), capacity, true);
+        end.positions(&end.ring(end.read_ring))?;
+        end.positions(&end.ring(end.write_ring))?;
+        Ok(Self { end: Arc::new(end) })
+    }
+
+    /// Returns the capacity of each ring of the pipe, in bytes.
+    pub fn capacity(&self) -> usize {
+        self.end.capacity
+    }
+}
+
+impl Pipe for ShmPipe {
+    fn read_exact(&self, buf: &mut [u8]) -> Result<usize> {
+        let end = &self.end;
+        let _guard = end.read_lock.lock().map_err(|_| anyhow!("Pipe read lock poisoned"))?;
+        let ring = end.ring(end.read_ring);
+
+        let mut read = 0;
+        while read < buf.len() {
+            let (tail, head) = end.wait(|| {
+                let (tail, head) = end.positions(&ring)?;
+                Ok((head != tail).then_some((tail, head)))
+            })?;
+            read += end.consume(&ring, tail, head, &mut buf[read..]);
+        }
+        Ok(read)
+    }
+
+    fn poll_readable(&self) -> Result<bool> {
+        let end = &self.end;
+        let ring = end.ring(end.read_ring);
+        // The tail is only advanced by this end, so data that is readable stays readable until
+        // this end reads it.
+        let readable = || end.positions(&ring).map(|(tail, head)| head != tail);
+        if readable()? {
+            return Ok(true);
+        }
+        if end.peer_closed() && !readable()? {
+            bail!("Pipe closed by the other end");
+        }
+        Ok(false)
+    }
+
+    fn try_read(&self, buf: &mut [u8]) -> Result<usize> {
+        if buf.is_empty() || !self.poll_readable()? {
+            return Ok(0);
+        }
+
+        let end = &self.end;
+        let _guard = end.read_lock.lock().map_err(|_| anyhow!("Pipe read lock poisoned"))?;
+        let ring = end.ring(end.read_ring);
+        let (tail, head) = end.positions(&ring)?;
+        Ok(end.consume(&ring, tail, head, buf))
+    }
+
+    fn write(&self, buf: &[u8]) -> Result<usize> {
+        let end = &self.end;
+        let _guard = end.write_lock.lock().map_err(|_| anyhow!("Pipe write lock poisoned"))?;
+        let ring = end.ring(end.write_ring);
+        let capacity = end.capacity as u64;
+        if end.peer_closed() {
+            bail!("Pipe closed by the other end");
+        }
+
+        let mut written = 0;
+        while written < buf.len() {
+            let (tail, head) = end.wait(|| {
+                let (tail, head) = end.positions(&ring)?;
+                Ok((head - tail < capacity).then_some((tail, head)))
+            })?;
+
+            let n = (capacity - (head - tail)) as usize;
+            let n = n.min(buf.len() - written);
+            let start = (head % capacity) as usize;
+            let first = n.min(end.capacity - start);
+            // SAFETY: The consumer does not read the `n` free bytes from `head` until the head is
+            // advanced past them, and both ranges are within the data of the ring.
+            unsafe {
+                ptr::copy_nonoverlapping(buf[written..].as_ptr(), ring.data.add(start), first);
+                ptr::copy_nonoverlapping(buf[written + first..].as_ptr(), ring.data, n - first);
+                (*ring.head).store(head + n as u64, Ordering::Release);
+            }
+            written += n;
+        }
+        Ok(written)
+    }
+}
+
+impl ShmEnd {
+    /// Creates an end over the `map` of the shared memory. The first end writes to the first ring
+    /// and reads from the second one, and the second end the other way around.
+    fn new(map: Arc<MmapRaw>, capacity: usize, second: bool) -> Self {
+        let rings = [HEADER_SIZE, HEADER_SIZE + ring_size(capacity)];
+        let (write_ring, read_ring) =
+            if second { (rings[1], rings[0]) } else { (rings[0], rings[1]) };
+        Self {
+            map,
+            capacity,
+            read_ring,
+            write_ring,
+            read_lock: Mutex::new(()),
+            write_lock: Mutex::new(()),
+        }
+    }
+
+    /// Returns a view of the ring at `offset`.
+    fn ring(&self, offset: usize) -> Ring {
+        // SAFETY: The offsets of both rings and their data are within the mapping, and the ring
+        // headers are aligned to the cache line size.
+        unsafe {
+            let base = self.map.as_mut_ptr().add(offset);
+            Ring {
+                head: base as *const AtomicU64,
+                closed: base.add(8) as *const AtomicU64,
+                tail: base.add(64) as *const AtomicU64,
+                data: base.add(RING_HEADER_SIZE),
+            }
+        }
+    }
+
+    /// Returns the `tail` and `head` positions of the `ring`. The shared memory may be written by
+    /// another process, so the positions are checked to describe at most `capacity` bytes of
+    /// data, and to leave room to advance the head by another `capacity` bytes, before they are
+    /// used to address the ring.
+    fn positions(&self, ring: &Ring) -> Result<(u64, u64)> {
+        let capacity = self.capacity as u64;
+        // SAFETY: The mapping outlives `ring`.
+        let (tail, head) =
+            unsafe { ((*ring.tail).load(Ordering::Acquire), (*ring.head).load(Ordering::Acquire)) };
+        match head.checked_sub(tail) {
+            Some(len) if len <= capacity && head <= u64::MAX - capacity => Ok((tail, head)),
+            _ => bail!("Shared memory of the pipe is corrupted: tail {tail}, head {head}"),
+        }
+    }
+
+    /// Copies the data of the `ring` between `tail` and `head` into `buf`, up to its length, and
+    /// advances the tail past the copied data. Returns the number of bytes copied. The positions
+    /// must have been checked by [ShmEnd::positions].
+    fn consume(&self, ring: &Ring, tail: u64, head: u64, buf: &mut [u8]) -> usize {
+        let n = ((head - tail) as usize).min(buf.len());
+        let start = (tail % self.capacity as u64) as usize;
+        let first = n.min(self.capacity - start);
+        // SAFETY: The producer does not write to the `n` bytes from `tail` until the tail is
+        // advanced past them, and both ranges are within the data of the ring.
+        unsafe {
+            ptr::copy_nonoverlapping(ring.data.add(start), buf.as_mut_ptr(), first);
+            ptr::copy_nonoverlapping(ring.data, buf[first..].as_mut_ptr(), n - first);
+            (*ring.tail).store(tail + n as u64, Ordering::Release);
+        }
+        n
+    }
+
+    /// Returns whether the other end of the pipe is closed. The other end closes the ring it
+    /// writes to, which is the one this end reads from.
+    fn peer_closed(&self) -> bool {
+        let ring = self.ring(self.read_ring);
+        // SAFETY: The mapping outlives `ring`.
+        unsafe { (*ring.closed).load(Ordering::Acquire) != 0 }
+    }
+
+    /// Polls `ready` until it returns a value, spinning at first and then yielding the thread.
+    /// Fails if `ready` fails, or once the other end of the pipe is closed, after a last poll.
+    fn wait<T>(&self, mut ready: impl FnMut() -> Result<Option<T>>) -> Result<T> {
+        let mut spins = 0;
+        loop {
+            if let Some(value) = ready()? {
+                return Ok(value);
+            }
+            if self.peer_closed() {
+                return ready()?.ok_or_else(|| anyhow!("Pipe closed by the other end"));
+            }
+            if spins < SPIN_LIMIT {
+                spins += 1;
+                spin_loop();
+            } else {
+                thread::yield_now();
+            }
+        }
+    }
+}
+
+impl Drop for ShmEnd {
+    fn drop(&mut self) {
+        let ring = self.ring(self.write_ring);
+        // SAFETY: The mapping is still alive, it is dropped after this.
+        unsafe { (*ring.closed).store(1, Ordering::Release) };
+    }
+}
+
+/// Returns the size of the shared memory of a pipe whose rings hold `capacity` bytes each.
+fn region_size(capacity: usize) -> Result<usize> {
+    if capacity == 0 {
+        bail!("Pipe capacity must not be zero");
+    }
+    capacity
+        .checked_next_multiple_of(64)
+        .and_then(|data| data.checked_add(RING_HEADER_SIZE))
+        .and_then(|ring| ring.checked_mul(2))
+        .and_then(|rings| rings.checked_add(HEADER_SIZE))
+        .ok_or_else(|| anyhow!("Pipe capacity is too large"))
+}
+
+/// Returns the size of a ring holding `capacity` bytes, including its header. The data is padded
+/// to the cache line size, to keep the header of the following ring aligned.
+const fn ring_size(capacity: usize) -> usize {
+    RING_HEADER_SIZE + capacity.div_ceil(64) * 64
+}
+
+/// Writes the header of a new pipe with rings of `capacity` bytes to the `map`.
+fn init_header(map: &MmapRaw, capacity: usize) {
+    // SAFETY: The mapping holds at least the header, which is aligned to the page size, and the
+    // rest of it is zeroed.
+    unsafe {
+        let header = map.as_mut_ptr() as *const AtomicU64;
+        (*header.add(1)).store(capacity as u64, Ordering::Relaxed);
+        (*header).store(MAGIC, Ordering::Release);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        HintReader, HintReaderServer, HintWriter, HintWriterClient, OracleReader, OracleServer,
+        PreimageKey, PreimageKeyType, PreimageOracleClient, PreimageOracleServer,
+    };
+    use alloc::{string::String, vec::Vec};
+    use alloy_primitives::keccak256;
+
+    #[test]
+    fn test_pipe_wraps_around_the_ring() {
+        let (a, b) = ShmPipe::pair(100).unwrap();
+        assert_eq!(a.capacity(), 100);
+
+        // Messages larger than the ring are streamed through it, wrapping around its end.
+        let data = (0..=255u8).cycle().take(1000).collect::<Vec<_>>();
+        let writer = thread::spawn({
+            let data = data.clone();
+            move || {
+                a.write(&data).unwrap();
+                a.write(&data[..37]).unwrap();
+            }
+        });
+        let mut buf = vec![0u8; 1000];
+        assert_eq!(b.read_exact(&mut buf).unwrap(), 1000);
+        assert_eq!(buf, data);
+        let mut buf = [0u8; 37];
+        b.read_exact(&mut buf).unwrap();
+        assert_eq!(buf, data[..37]);
+
+        // Once the writer is dropped, reads fail instead of waiting forever.
+        writer.join().unwrap();
+        assert!(b.read_exact(&mut buf).is_err());
+        assert!(b.write(&buf).is_err());
+    }
+
+    #[test]
+    fn test_oracle_and_hints_over_shared_memory() {
+        const MOCK_DATA: &[u8] = b"1234567890";
+        let key = PreimageKey::new(*keccak256(MOCK_DATA), PreimageKeyType::Keccak256);
+
+        let dir = tempfile::tempdir().unwrap();
+        let (preimage_path, hint_path) = (dir.path().join("preimage"), dir.path().join("hint"));
+        let preimage_host = ShmPipe::create(&preimage_path, 64).unwrap();
+        let hint_host = ShmPipe::create(&hint_path, 64).unwrap();
+
+        let host = thread::spawn(move || {
+            let (server, hint_reader) =
+                (OracleServer::new(preimage_host), HintReader::new(hint_host));
+            let mut hints = Vec::new();
+            hint_reader
+                .next_hint(|hint: String| {
+                    hints.push(hint);
+                    Ok(())
+                })
+                .unwrap();
+            let preimage = MOCK_DATA.to_vec();
+            while server.next_preimage_request(|_| Ok(&preimage)).is_ok() {}
+            hints
+        });
+
+        let reader = OracleReader::new(ShmPipe::open(&preimage_path).unwrap());
+        let hint_writer = HintWriter::new(ShmPipe::open(&hint_path).unwrap());
+        hint_writer.write("l1-block-header 0x00").unwrap();
+        assert_eq!(reader.get(key).unwrap(), MOCK_DATA);
+        assert_eq!(reader.preimage_len(key).unwrap(), MOCK_DATA.len());
+        drop(reader);
+        assert_eq!(host.join().unwrap(), vec!["l1-block-header 0x00"]);
+    }
+
+    #[test]
+    fn test_get_many_over_shared_memory() {
+        let preimages = (0..100u8).map(|i| vec![i; 100]).collect::<Vec<_>>();
+        let keys = preimages.iter().map(|p| PreimageKey::new_keccak256(p)).collect::<Vec<_>>();
+
+        // The pipe buffers a batch of keys, but not all of their responses.
+        let (client, host) = ShmPipe::pair(4096).unwrap();
+        let host = thread::spawn({
+            let preimages = preimages.clone();
+            move || {
+                let get_preimage = |key: PreimageKey| {
+                    preimages
+                        .iter()
+                        .find(|p| PreimageKey::new_keccak256(p) == key)
+                        .ok_or(anyhow::anyhow!("Preimage not available"))
+                };
+                OracleServer::new(host).serve_preimage_requests(get_preimage)
+            }
+        });
+
+        let reader = OracleReader::new(client);
+        assert_eq!(reader.get_many(&keys).unwrap(), preimages);
+
+        // The pending length of the first key is reused, and of any other key is discarded.
+        reader.preimage_len(keys[70]).unwrap();
+        assert_eq!(reader.get_many(&keys[70..72]).unwrap(), preimages[70..72]);
+        reader.preimage_len(keys[0]).unwrap();
+        assert_eq!(reader.get_many(&keys[72..74]).unwrap(), preimages[72..74]);
+        assert_eq!(reader.get(keys[1]).unwrap(), preimages[1]);
+
+        drop(reader);
+        assert!(host.join().unwrap().is_err());
+    }
+
+    #[test]
+    fn test_try_read_does_not_block() {
+        let (a, b) = ShmPipe::pair(64).unwrap();
+        let mut buf = [0u8; 8];
+        assert!(!b.poll_readable().unwrap());
+        assert_eq!(b.try_read(&mut buf).unwrap(), 0);
+
+        a.write(&[1, 2, 3]).unwrap();
+        assert!(b.poll_readable().unwrap());
+        assert_eq!(b.try_read(&mut buf).unwrap(), 3);
+        assert_eq!(buf[..3], [1, 2, 3]);
+        assert!(!b.poll_readable().unwrap());
+
+        // Data written before the other end is closed is still readable.
+        a.write(&[4]).unwrap();
+        drop(a);
+        assert_eq!(b.try_read(&mut buf).unwrap(), 1);
+        assert!(b.poll_readable().is_err());
+        assert!(b.try_read(&mut buf).is_err());
+    }
+
+    #[test]
+    fn test_try_get_over_shared_memory() {
+        let preimages = [vec![0xAA; 100], vec![0xBB; 10], vec![]];
+        let keys = preimages.iter().map(|p| PreimageKey::new_keccak256(p)).collect::<Vec<_>>();
+        let (client, host) = ShmPipe::pair(64).unwrap();
+        let (serve_tx, serve_rx) = std::sync::mpsc::channel::<()>();
+        let host = thread::spawn({
+            let preimages = preimages.clone();
+            move || {
+                let server = OracleServer::new(host);
+                let get_preimage = |key: PreimageKey| {
+                    preimages
+                        .iter()
+                        .find(|p| PreimageKey::new_keccak256(p) == key)
+                        .ok_or(anyhow::anyhow!("Preimage not available"))
+                };
+                // Each request is served once the client allows it.
+                while serve_rx.recv().is_ok() {
+                    server.next_preimage_request(get_preimage).unwrap();
+                }
+            }
+        });
+
+        // The request is sent, but not served yet.
+        let reader = OracleReader::new(client);
+        assert_eq!(reader.try_get(keys[0]).unwrap(), None);
+        assert!(!reader.is_ready().unwrap());
+        serve_tx.send(()).unwrap();
+        // The response is larger than the ring, so it is received over several polls.
+        let preimage = loop {
+            if let Some(preimage) = reader.try_get(keys[0]).unwrap() {
+                break preimage;
+            }
+            thread::yield_now();
+        };
+        assert_eq!(preimage, preimages[0]);
+        assert!(reader.is_ready().unwrap());
+
+        // A blocking request discards the response of the request in flight.
+        assert_eq!(reader.try_get(keys[1]).unwrap(), None);
+        serve_tx.send(()).unwrap();
+        serve_tx.send(()).unwrap();
+        assert_eq!(reader.get(keys[2]).unwrap(), preimages[2]);
+
+        // The length read by `preimage_len` is reused.
+        serve_tx.send(()).unwrap();
+        assert_eq!(reader.preimage_len(keys[1]).unwrap(), 10);
+        let preimage = loop {
+            if let Some(preimage) = reader.try_get(keys[1]).unwrap() {
+                break preimage;
+            }
+        };
+        assert_eq!(preimage, preimages[1]);
+
+        drop(serve_tx);
+        host.join().unwrap();
+    }
+
+    #[test]
+    fn test_open_rejects_invalid_memory() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        file.as_file().set_len(4096).unwrap();
+        assert!(ShmPipe::open(file.path()).is_err());
+        assert!(ShmPipe::pair(0).is_err());
+        assert!(region_size(usize::MAX).is_err());
+    }
+
+    #[test]
+    fn test_corrupted_positions() {
+        use std::{
+            fs::OpenOptions,
+            io::{Seek, SeekFrom, Write},
+        };
+
+        const CAPACITY: usize = 64;
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let first = ShmPipe::create(file.path(), CAPACITY).unwrap();
+        let second = ShmPipe::open(file.path()).unwrap();
+
+        // Move the head of both rings past their capacity, as another process could.
+        let mut shm = OpenOptions::new().write(true).open(file.path()).unwrap();
+        for ring in [HEADER_SIZE, HEADER_SIZE + ring_size(CAPACITY)] {
+            shm.seek(SeekFrom::Start(ring as u64)).unwrap();
+            shm.write_all(&(4 * CAPACITY as u64).to_ne_bytes()).unwrap();
+        }
+        shm.flush().unwrap();
+
+        assert!(ShmPipe::open(file.path()).is_err());
+        let mut buf = [0u8; 8];
+        for end in [&first, &second] {
+            assert!(end.read_exact(&mut buf).is_err());
+            assert!(end.try_read(&mut buf).is_err());
+            assert!(end.poll_readable().is_err());
+            assert!(end.write(&buf).is_err());
+        }
+    }
+}
@@ -0,0 +1,138 @@
+//! This module contains the [DiskPreimageStore], a [PreimageStore] persisting the preimages of
+//! the host in a directory, so that they survive restarts of the host and can be shared between
+//! runs of the program.
+
+use crate::{PreimageKey, PreimageStore};
+use alloc::{format, vec::Vec};
+use anyhow::{anyhow, Result};
+use core::sync::atomic::{AtomicU64, Ordering};
+use std::{
+    fs,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+};
+
+/// A [DiskPreimageStore] stores each preimage in its own file, named after its [PreimageKey], in a
+/// directory.
+///
+/// Preimages are written to a temporary file before being renamed into place, so that a reader
+/// never observes a partially written preimage, even across processes sharing the directory.
+#[derive(Debug)]
+pub struct DiskPreimageStore {
+    /// The directory holding the preimages.
+    dir: PathBuf,
+    /// The number of preimages written, used to name their temporary files.
+    writes: AtomicU64,
+}
+
+impl DiskPreimageStore {
+    /// Opens the store in `dir`, creating the directory if it does not exist. The preimages
+    /// already stored in the directory are kept.
+    pub fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        fs::create_dir_all(&dir).map_err(|e| anyhow!("Failed to create {}: {e}", dir.display()))?;
+        Ok(Self { dir, writes: AtomicU64::new(0) })
+    }
+
+    /// Returns the directory holding the preimages.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Returns the path of the file holding the preimage of `key`.
+    fn path(&self, key: PreimageKey) -> PathBuf {
+        self.dir.join(format!("{key}"))
+    }
+}
+
+impl PreimageStore for DiskPreimageStore {
+    fn get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+        match fs::read(self.path(key)) {
+            Ok(value) => Ok(Some(value)),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(anyhow!("Failed to read preimage for key {key}: {e}")),
+        }
+    }
+
+    fn put(&self, key: PreimageKey, value: Vec<u8>) -> Result<()> {
+        key.validate_preimage(&value).map_err(|e| anyhow!(e))?;
+
+        // The temporary file is unique to this write, so that concurrent writers of the same key
+        // do not interleave their data.
+        let write = self.writes.fetch_add(1, Ordering::Relaxed);
+        let tmp = self.dir.join(format!("{key}.{}.{write}.tmp", std::process::id()));
+        fs::write(&tmp, value).and_then(|_| fs::rename(&tmp, self.path(key))).map_err(|e| {
+            let _ = fs::remove_file(&tmp);
+            anyhow!("Failed to write preimage for key {key}: {e}")
+        })
+    }
+
+    fn contains(&self, key: PreimageKey) -> Result<bool> {
+        self.path(key)
+            .try_exists()
+            .map_err(|e| anyhow!("Failed to check preimage for key {key}: {e}"))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PreimageOracleError;
+
+    #[test]
+    fn test_disk_preimage_store() {
+        let dir = tempfile::tempdir().unwrap();
+        let key = PreimageKey::new_keccak256(b"1234567890");
+        let store = DiskPreimageStore::open(dir.path().join("preimages")).unwrap();
+        assert_eq!(store.get(key).unwrap(), None);
+        assert!(!store.contains(key).unwrap());
+
+        store.put(key, b"1234567890".to_vec()).unwrap();
+        assert_eq!(store.get(key).unwrap(), Some(b"1234567890".to_vec()));
+        assert!(store.contains(key).unwrap());
+
+        // A preimage that does not match its key is rejected, and the stored one is kept.
+        let err = store.put(key, b"0987654321".to_vec()).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PreimageOracleError>(),
+            Some(&PreimageOracleError::InvalidPreimage(key))
+        );
+        assert_eq!(store.get(key).unwrap(), Some(b"1234567890".to_vec()));
+        assert!(store.contains(key).unwrap());
+
+        // The preimages persist across openings of the store.
+        drop(store);
+        let store = DiskPreimageStore::open(dir.path().join("preimages")).unwrap();
+        assert_eq!(store.get(key).unwrap(), Some(b"1234567890".to_vec()));
+        assert_eq!(fs::read_dir(store.dir()).unwrap().count(), 1);
+    }
+
+    #[cfg(feature = "shm")]
+    #[test]
+    fn test_serve_preimages_from_store() {
+        use crate::{
+            OracleReader, OracleServer, PreimageOracleClient, PreimageOracleServer, ShmPipe,
+        };
+        use alloc::vec;
+        use std::thread;
+
+        let dir = tempfile::tempdir().unwrap();
+        let store = DiskPreimageStore::open(dir.path()).unwrap();
+        let (stored, missing) =
+            (PreimageKey::new_keccak256(&[0xAA; 100]), PreimageKey::new_keccak256(&[0xBB]));
+        store.put(stored, vec![0xAA; 100]).unwrap();
+
+        let (client, host) = ShmPipe::pair(4096).unwrap();
+        let host =
+            thread::spawn(move || OracleServer::new(host).serve_preimage_requests_from(&store));
+
+        let reader = OracleReader::new(client);
+        assert_eq!(reader.get(stored).unwrap(), vec![0xAA; 100]);
+        assert!(reader.get(missing).is_err());
+        let err = host.join().unwrap().unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PreimageOracleError>(),
+            Some(&PreimageOracleError::MissingPreimage(missing))
+        );
+    }
+}
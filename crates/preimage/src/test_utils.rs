@@ -0,0 +1,273 @@
+//! In-memory preimage oracle fixtures for testing programs built on `kona-preimage`.
+//!
+//! With the `test-utils` feature, downstream crates can serve the preimages and handle the hints
+//! of their tests with a [TestOracle], instead of hand-rolling mock oracles.
+
+use crate::{
+    Hint, HintHandler, HintRouter, HintType, HintWriterClient, PreimageKey, PreimageOracleClient,
+    PreimageOracleError,
+};
+use alloc::{
+    collections::BTreeMap,
+    string::{String, ToString},
+    vec::Vec,
+};
+use anyhow::{anyhow, Result};
+use spin::Mutex;
+
+#[cfg(feature = "std")]
+use crate::{AsyncHintWriterClient, AsyncPreimageOracleClient};
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use async_trait::async_trait;
+
+/// A [TestOracle] serves a fixed set of preimages, and routes the hints it receives to the
+/// [HintHandler]s registered for their [HintType]. It implements the oracle and hint client
+/// traits, both blocking and async.
+///
+/// ```ignore
+/// let oracle = TestOracle::builder()
+///     .with_preimage(PreimageKey::new_keccak256(&header_rlp), header_rlp)
+///     .with_hint_handler(HintType::L1BlockHeader, |hint: &Hint| -> Result<()> { Ok(()) })
+///     .build();
+/// ```
+#[derive(Debug)]
+pub struct TestOracle {
+    /// The preimages served by the oracle, keyed by their raw [PreimageKey].
+    preimages: BTreeMap<[u8; 32], Vec<u8>>,
+    /// The router of the hints with a registered handler.
+    router: Mutex<HintRouter<'static>>,
+    /// The hints received by the oracle, in order.
+    hints: Mutex<Vec<String>>,
+}
+
+/// A builder for a [TestOracle].
+#[derive(Debug, Default)]
+pub struct TestOracleBuilder {
+    /// The preimages served by the oracle, keyed by their raw [PreimageKey].
+    preimages: BTreeMap<[u8; 32], Vec<u8>>,
+    /// The router of the hints with a registered handler.
+    router: HintRouter<'static>,
+}
+
+impl TestOracle {
+    /// Returns a [TestOracleBuilder] for a [TestOracle] without any preimages or hint handlers.
+    pub fn builder() -> TestOracleBuilder {
+        TestOracleBuilder::default()
+    }
+
+    /// Returns the hints received by the oracle, in order, including the hints without a
+    /// registered handler.
+    pub fn hints(&self) -> Vec<String> {
+        self.hints.lock().clone()
+    }
+
+    /// Returns the preimage of `key`, or a [PreimageOracleError::MissingPreimage] if the oracle
+    /// does not hold it.
+    fn preimage(&self, key: PreimageKey) -> Result<&Vec<u8>> {
+        self.preimages
+            .get(&<[u8; 32]>::from(key))
+            .ok_or_else(|| anyhow!(PreimageOracleError::MissingPreimage(key)))
+    }
+
+    /// Records the `hint`, and routes it to the handler registered for its type, if any.
+    fn hint(&self, hint: &Hint) -> Result<()> {
+        self.hints.lock().push(hint.to_string());
+        let mut router = self.router.lock();
+        if router.handles(hint.ty) {
+            router.route(hint)?;
+        }
+        Ok(())
+    }
+}
+
+impl TestOracleBuilder {
+    /// Adds the `data` as the preimage of `key`, replacing any preimage already added for it. The
+    /// preimage is not checked against its key.
+    pub fn with_preimage(mut self, key: PreimageKey, data: impl Into<Vec<u8>>) -> Self {
+        self.preimages.insert(key.into(), data.into());
+        self
+    }
+
+    /// Registers the `handler` for the hints of type `ty`, like [HintRouter::register]. The hints
+    /// of the types without a handler are only recorded.
+    pub fn with_hint_handler(
+        mut self,
+        ty: HintType,
+        handler: impl HintHandler + Send + 'static,
+    ) -> Self {
+        self.router.register(ty, handler);
+        self
+    }
+
+    /// Builds the [TestOracle].
+    pub fn build(self) -> TestOracle {
+        TestOracle {
+            preimages: self.preimages,
+            router: Mutex::new(self.router),
+            hints: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl PreimageOracleClient for TestOracle {
+    fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        self.preimage(key).cloned()
+    }
+
+    fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        let data = self.preimage(key)?;
+        if buf.len() != data.len() {
+            return Err(anyhow!(PreimageOracleError::LengthMismatch {
+                expected: buf.len(),
+                actual: data.len()
+            }));
+        }
+        buf.copy_from_slice(data);
+        Ok(())
+    }
+
+    fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        self.preimage(key).map(Vec::len)
+    }
+}
+
+impl HintWriterClient for TestOracle {
+    fn write(&self, hint: &str) -> Result<()> {
+        self.hint(&hint.parse::<Hint>().map_err(|e| anyhow!(e))?)
+    }
+
+    fn write_hint(&self, hint: &Hint) -> Result<()> {
+        self.hint(hint)
+    }
+}
+
+#[cfg(feature = "std")]
+#[async_trait]
+impl AsyncPreimageOracleClient for TestOracle {
+    async fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        PreimageOracleClient::get(self, key)
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        PreimageOracleClient::get_exact(self, key, buf)
+    }
+
+    async fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        PreimageOracleClient::preimage_len(self, key)
+    }
+}
+
+#[cfg(feature = "std")]
+#[async_trait]
+impl AsyncHintWriterClient for TestOracle {
+    async fn write(&self, hint: &str) -> Result<()> {
+        HintWriterClient::write(self, hint)
+    }
+
+    async fn write_hint(&self, hint: &Hint) -> Result<()> {
+        HintWriterClient::write_hint(self, hint)
+    }
+}
+
+/// A backend serving a map of preimages and rejecting empty hints, shared by the tests of the
+/// host servers that wrap an async oracle.
+#[cfg(all(test, any(feature = "grpc", feature = "http")))]
+#[derive(Debug, Default)]
+pub(crate) struct MockBackend {
+    /// The preimages served by the backend.
+    pub(crate) preimages: std::collections::HashMap<PreimageKey, Vec<u8>>,
+}
+
+#[cfg(all(test, any(feature = "grpc", feature = "http")))]
+impl MockBackend {
+    /// Returns the preimage of `key`, or an error if the backend does not hold it.
+    fn preimage(&self, key: PreimageKey) -> Result<&Vec<u8>> {
+        self.preimages.get(&key).ok_or_else(|| anyhow!("Preimage not available"))
+    }
+}
+
+#[cfg(all(test, any(feature = "grpc", feature = "http")))]
+#[async_trait]
+impl AsyncPreimageOracleClient for MockBackend {
+    async fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        self.preimage(key).cloned()
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        let data = self.preimage(key)?;
+        if buf.len() != data.len() {
+            return Err(anyhow!(PreimageOracleError::LengthMismatch {
+                expected: buf.len(),
+                actual: data.len()
+            }));
+        }
+        buf.copy_from_slice(data);
+        Ok(())
+    }
+
+    async fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        self.preimage(key).map(Vec::len)
+    }
+}
+
+#[cfg(all(test, any(feature = "grpc", feature = "http")))]
+#[async_trait]
+impl AsyncHintWriterClient for MockBackend {
+    async fn write(&self, hint: &str) -> Result<()> {
+        if hint.is_empty() {
+            return Err(anyhow!("Empty hint"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::HintParsingError;
+    use alloc::{sync::Arc, vec};
+
+    #[test]
+    fn test_test_oracle() {
+        let key = PreimageKey::new_keccak256(b"1234567890");
+        let handled = Arc::new(Mutex::new(Vec::new()));
+        let oracle = TestOracle::builder()
+            .with_preimage(key, b"1234567890".to_vec())
+            .with_hint_handler(HintType::L1BlockHeader, {
+                let handled = handled.clone();
+                move |hint: &Hint| -> Result<()> {
+                    handled.lock().push(hint.data.to_vec());
+                    Ok(())
+                }
+            })
+            .build();
+
+        assert_eq!(PreimageOracleClient::get(&oracle, key).unwrap(), b"1234567890");
+        assert_eq!(PreimageOracleClient::preimage_len(&oracle, key).unwrap(), 10);
+        let mut buf = [0u8; 4];
+        let err = PreimageOracleClient::get_exact(&oracle, key, &mut buf).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PreimageOracleError>(),
+            Some(&PreimageOracleError::LengthMismatch { expected: 4, actual: 10 })
+        );
+        let missing = PreimageKey::new_local(1);
+        let err = PreimageOracleClient::get(&oracle, missing).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PreimageOracleError>(),
+            Some(&PreimageOracleError::MissingPreimage(missing))
+        );
+
+        let header_hint = Hint::new(HintType::L1BlockHeader, [0x11; 32]).unwrap();
+        HintWriterClient::write_hint(&oracle, &header_hint).unwrap();
+        let code_hint = Hint::new(HintType::L2Code, [0x22; 32]).unwrap().to_string();
+        HintWriterClient::write(&oracle, &code_hint).unwrap();
+        let err = HintWriterClient::write(&oracle, "unknown-hint 0x00").unwrap_err();
+        assert!(err.downcast_ref::<HintParsingError>().is_some());
+
+        assert_eq!(*handled.lock(), vec![vec![0x11; 32]]);
+        assert_eq!(oracle.hints().len(), 2);
+        assert_eq!(oracle.hints()[0], header_hint.to_string());
+    }
+}
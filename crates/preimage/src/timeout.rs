@@ -0,0 +1,216 @@
+//! This module contains the [TimeoutOracleClient], which bounds the time spent waiting on the
+//! requests of a blocking oracle and hint client, so that a dead host fails the client with a
+//! [PreimageOracleError::Timeout] instead of blocking it forever.
+
+use crate::{HintWriterClient, PreimageKey, PreimageOracleClient, PreimageOracleError};
+use alloc::{boxed::Box, string::String, vec, vec::Vec};
+use anyhow::{anyhow, Result};
+use core::time::Duration;
+use std::{
+    sync::mpsc::{channel, RecvTimeoutError, Sender},
+    thread,
+};
+
+/// A request run by the worker thread of a [TimeoutOracleClient].
+type Job<C> = Box<dyn FnOnce(&C) + Send>;
+
+/// A [TimeoutOracleClient] runs the requests of a blocking [PreimageOracleClient] and
+/// [HintWriterClient], such as an [crate::OracleReader] and [crate::HintWriter], on a worker
+/// thread, and fails the requests that do not complete before their deadline with a
+/// [PreimageOracleError::Timeout].
+///
+/// The requests run in order, to completion, so a request that timed out still holds up the
+/// following requests until the host answers it. Its response is discarded, leaving the pipe
+/// ready for the next request.
+#[derive(Debug)]
+pub struct TimeoutOracleClient<C> {
+    /// The sender of the requests to the worker thread, which owns the wrapped client.
+    jobs: Sender<Job<C>>,
+    /// The deadline of the requests made through the client traits.
+    timeout: Duration,
+}
+
+impl<C: Send + 'static> TimeoutOracleClient<C> {
+    /// Creates a new [TimeoutOracleClient] over `inner`, failing the requests made through the
+    /// client traits after `timeout`. The client is moved to a new worker thread, which stops
+    /// once the [TimeoutOracleClient] is dropped and its pending requests complete.
+    pub fn new(inner: C, timeout: Duration) -> Self {
+        let (jobs, pending) = channel::<Job<C>>();
+        thread::spawn(move || {
+            while let Ok(job) = pending.recv() {
+                job(&inner);
+            }
+        });
+        Self { jobs, timeout }
+    }
+
+    /// Returns the deadline of the requests made through the client traits.
+    pub const fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    /// Sets the deadline of the requests made through the client traits.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Runs `request` on the worker thread, waiting up to `timeout` for its result.
+    fn run<T: Send + 'static>(
+        &self,
+        timeout: Duration,
+        request: impl FnOnce(&C) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let (result_tx, result_rx) = channel();
+        let job: Job<C> = Box::new(move |client| {
+            // The receiver is gone if the request timed out.
+            let _ = result_tx.send(request(client));
+        });
+        self.jobs.send(job).map_err(|_| anyhow!("Oracle client worker stopped"))?;
+
+        match result_rx.recv_timeout(timeout) {
+            Ok(result) => result,
+            Err(RecvTimeoutError::Timeout) => Err(anyhow!(PreimageOracleError::Timeout(timeout))),
+            Err(RecvTimeoutError::Disconnected) => Err(anyhow!("Oracle client worker stopped")),
+        }
+    }
+}
+
+impl<C: PreimageOracleClient + Send + 'static> TimeoutOracleClient<C> {
+    /// Like [PreimageOracleClient::get], failing with a [PreimageOracleError::Timeout] if the
+    /// preimage is not received within `timeout`.
+    pub fn get_timeout(&self, key: PreimageKey, timeout: Duration) -> Result<Vec<u8>> {
+        self.run(timeout, move |client| client.get(key))
+    }
+
+    /// Like [PreimageOracleClient::get_exact], failing with a [PreimageOracleError::Timeout] if
+    /// the preimage is not received within `timeout`.
+    pub fn get_exact_timeout(
+        &self,
+        key: PreimageKey,
+        buf: &mut [u8],
+        timeout: Duration,
+    ) -> Result<()> {
+        let len = buf.len();
+        let data = self.run(timeout, move |client| {
+            let mut data = vec![0; len];
+            client.get_exact(key, &mut data)?;
+            Ok(data)
+        })?;
+        buf.copy_from_slice(&data);
+        Ok(())
+    }
+
+    /// Like [PreimageOracleClient::preimage_len], failing with a [PreimageOracleError::Timeout]
+    /// if the length is not received within `timeout`.
+    pub fn preimage_len_timeout(&self, key: PreimageKey, timeout: Duration) -> Result<usize> {
+        self.run(timeout, move |client| client.preimage_len(key))
+    }
+}
+
+impl<C: HintWriterClient + Send + 'static> TimeoutOracleClient<C> {
+    /// Like [HintWriterClient::write], failing with a [PreimageOracleError::Timeout] if the hint
+    /// is not acknowledged within `timeout`.
+    pub fn write_timeout(&self, hint: &str, timeout: Duration) -> Result<()> {
+        let hint = String::from(hint);
+        self.run(timeout, move |client| client.write(&hint))
+    }
+}
+
+impl<C: PreimageOracleClient + Send + 'static> PreimageOracleClient for TimeoutOracleClient<C> {
+    fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        self.get_timeout(key, self.timeout)
+    }
+
+    fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        self.get_exact_timeout(key, buf, self.timeout)
+    }
+
+    fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        self.preimage_len_timeout(key, self.timeout)
+    }
+
+    fn get_many(&self, keys: &[PreimageKey]) -> Result<Vec<Vec<u8>>> {
+        let keys = keys.to_vec();
+        self.run(self.timeout, move |client| client.get_many(&keys))
+    }
+}
+
+impl<C: HintWriterClient + Send + 'static> HintWriterClient for TimeoutOracleClient<C> {
+    fn write(&self, hint: &str) -> Result<()> {
+        self.write_timeout(hint, self.timeout)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::PreimageKeyType;
+
+    /// A client serving `[key[31]; 4]`, sleeping for `key[31]` milliseconds first.
+    struct SlowClient;
+
+    impl PreimageOracleClient for SlowClient {
+        fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+            let byte = <[u8; 32]>::from(key)[31];
+            thread::sleep(Duration::from_millis(byte as u64));
+            Ok(vec![byte; 4])
+        }
+
+        fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+            let data = self.get(key)?;
+            if buf.len() != data.len() {
+                return Err(anyhow!(PreimageOracleError::LengthMismatch {
+                    expected: buf.len(),
+                    actual: data.len()
+                }));
+            }
+            buf.copy_from_slice(&data);
+            Ok(())
+        }
+
+        fn preimage_len(&self, _: PreimageKey) -> Result<usize> {
+            Ok(4)
+        }
+    }
+
+    impl HintWriterClient for SlowClient {
+        fn write(&self, hint: &str) -> Result<()> {
+            thread::sleep(Duration::from_millis(hint.len() as u64));
+            Ok(())
+        }
+    }
+
+    fn key(byte: u8) -> PreimageKey {
+        let mut key = [0u8; 32];
+        key[31] = byte;
+        PreimageKey::new(key, PreimageKeyType::Keccak256)
+    }
+
+    #[test]
+    fn test_timeout_oracle_client() {
+        let timeout = Duration::from_millis(100);
+        let client = TimeoutOracleClient::new(SlowClient, timeout);
+
+        assert_eq!(client.get(key(1)).unwrap(), vec![1; 4]);
+        let mut buf = [0u8; 4];
+        client.get_exact(key(2), &mut buf).unwrap();
+        assert_eq!(buf, [2; 4]);
+        assert_eq!(client.preimage_len(key(3)).unwrap(), 4);
+        client.write("l2-code 0xff").unwrap();
+
+        let err = client.get(key(250)).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PreimageOracleError>(),
+            Some(&PreimageOracleError::Timeout(timeout))
+        );
+        let err = client.write_timeout("l2-code 0xff", Duration::ZERO).unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<PreimageOracleError>(),
+            Some(&PreimageOracleError::Timeout(Duration::ZERO))
+        );
+
+        // The late response is discarded, and the following requests get their own responses.
+        assert_eq!(client.get_timeout(key(4), Duration::from_secs(5)).unwrap(), vec![4; 4]);
+        assert_eq!(client.get_many(&[key(5), key(6)]).unwrap(), vec![vec![5; 4], vec![6; 4]]);
+    }
+}
@@ -1,6 +1,32 @@
-use crate::PreimageKey;
-use alloc::{string::String, vec::Vec};
-use anyhow::Result;
+use crate::{
+    decode_precompile_result, precompile_key, validate_precompile_result, Hint, PreimageKey,
+    PreimageOracleError,
+};
+use alloc::{
+    string::{String, ToString},
+    vec::Vec,
+};
+use alloy_primitives::Address;
+use anyhow::{anyhow, Result};
+use core::cell::OnceCell;
+
+#[cfg(feature = "std")]
+use alloc::boxed::Box;
+#[cfg(feature = "std")]
+use alloc::{sync::Arc, vec};
+#[cfg(feature = "std")]
+use async_trait::async_trait;
+#[cfg(feature = "std")]
+use core::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+#[cfg(feature = "std")]
+use std::{
+    sync::{mpsc, Mutex},
+    thread::{self, JoinHandle},
+};
 
 /// A [PreimageOracleClient] is a high-level interface to read data from the host, keyed by a
 /// [PreimageKey].
@@ -18,8 +44,59 @@ pub trait PreimageOracleClient {
     ///
     /// # Returns
     /// - `Ok(())` if the data was successfully written into the buffer.
-    /// - `Err(_)` if the data could not be written into the buffer.
+    /// - `Err(_)` if the data could not be written into the buffer. If the buffer size does not
+    ///   match the length of the preimage, the error is a
+    ///   [crate::PreimageOracleError::LengthMismatch].
     fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()>;
+
+    /// Get the length of the data corresponding to the key from the host, without reading the
+    /// data. A following [PreimageOracleClient::get] or [PreimageOracleClient::get_exact] for the
+    /// same key does not request the preimage from the host again.
+    ///
+    /// # Returns
+    /// - `Ok(usize)` if the length of the preimage was successfully fetched from the host.
+    /// - `Err(_)` if the length could not be fetched from the host.
+    fn preimage_len(&self, key: PreimageKey) -> Result<usize>;
+
+    /// Get the data corresponding to each of the `keys` from the host, in order. Clients over a
+    /// pipe send several keys before waiting for the responses, rather than making a round trip
+    /// per key.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Vec<u8>>)` with the preimage of each key, if all of them were successfully fetched
+    ///   from the host.
+    /// - `Err(_)` if any of the preimages could not be fetched from the host.
+    fn get_many(&self, keys: &[PreimageKey]) -> Result<Vec<Vec<u8>>> {
+        keys.iter().map(|key| self.get(*key)).collect()
+    }
+
+    /// Get the data corresponding to the key from the host without blocking on the host. The key
+    /// is requested on the first call, and the following calls for the same key return the
+    /// preimage once the host has sent it, so that the caller can poll the client. Clients that
+    /// cannot poll the host block until the preimage is received, like [PreimageOracleClient::get].
+    ///
+    /// # Returns
+    /// - `Ok(Some(Vec<u8>))` if the preimage was received from the host.
+    /// - `Ok(None)` if the preimage is not yet available.
+    /// - `Err(_)` if the data could not be fetched from the host.
+    fn try_get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>> {
+        self.get(key).map(Some)
+    }
+
+    /// Get the result of calling the precompile at `address` with `input`, as computed by the
+    /// host, and check it with [validate_precompile_result]. The host should be hinted about the
+    /// call first, with [HintWriterClient::hint_precompile].
+    ///
+    /// # Returns
+    /// - `Ok((success, output))` with the success flag and the output of the call.
+    /// - `Err(_)` if the result could not be fetched from the host, or is invalid. An invalid
+    ///   result is a [crate::PrecompileResultError].
+    fn precompile_result(&self, address: Address, input: &[u8]) -> Result<(bool, Vec<u8>)> {
+        let result = self.get(precompile_key(address, input))?;
+        let (success, output) = decode_precompile_result(&result).map_err(|e| anyhow!(e))?;
+        validate_precompile_result(address, input, success, output).map_err(|e| anyhow!(e))?;
+        Ok((success, output.to_vec()))
+    }
 }
 
 /// A [HintWriterClient] is a high-level interface to the hint pipe. It provides a way to write
@@ -32,12 +109,35 @@ pub trait HintWriterClient {
     /// - `Ok(())` if the hint was successfully written to the host.
     /// - `Err(_)` if the hint could not be written to the host.
     fn write(&self, hint: &str) -> Result<()>;
+
+    /// Write a typed [Hint] to the host, in its canonical string representation.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was successfully written to the host.
+    /// - `Err(_)` if the hint could not be written to the host.
+    fn write_hint(&self, hint: &Hint) -> Result<()> {
+        self.write(&hint.to_string())
+    }
+
+    /// Hint the host to execute the precompile at `address` with `input`, so that its result can
+    /// be read with [PreimageOracleClient::precompile_result].
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was successfully written to the host.
+    /// - `Err(_)` if the hint could not be written to the host.
+    fn hint_precompile(&self, address: Address, input: &[u8]) -> Result<()> {
+        self.write_hint(&Hint::precompile(address, input))
+    }
 }
 
 /// A [PreimageOracleServer] is a high-level interface to accept read requests from the client and
 /// write the preimage data to the client pipe.
 pub trait PreimageOracleServer {
-    /// Get the next preimage request and return the response to the client.
+    /// Get the next preimage request and return the response to the client. The preimage returned
+    /// by `get_preimage` is served as is, so it must be checked against its [PreimageKey] with
+    /// [PreimageKey::validate_preimage] before it is made available, such as when it is put in a
+    /// [PreimageStore]. Failing the request after the key is read would leave the client waiting
+    /// for a response that never comes.
     ///
     /// # Returns
     /// - `Ok(())` if the data was successfully written into the client pipe.
@@ -46,6 +146,78 @@ pub trait PreimageOracleServer {
         &self,
         get_preimage: impl FnMut(PreimageKey) -> Result<&'a Vec<u8>>,
     ) -> Result<()>;
+
+    /// Serves preimage requests in a loop, answering each of the keys sent by the client in the
+    /// order they were sent, including the pipelined keys of [PreimageOracleClient::get_many].
+    /// Only returns if a request fails, such as when the client closes the pipe.
+    fn serve_preimage_requests<'a>(
+        &self,
+        mut get_preimage: impl FnMut(PreimageKey) -> Result<&'a Vec<u8>>,
+    ) -> Result<()> {
+        loop {
+            self.next_preimage_request(&mut get_preimage)?;
+        }
+    }
+
+    /// Get the next preimage request and answer it with the preimage held by the `store`.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the data was successfully written into the client pipe.
+    /// - `Err(_)` if the data could not be written to the client, or if the `store` does not hold
+    ///   the preimage, in which case the error is a [crate::PreimageOracleError::MissingPreimage].
+    fn next_preimage_request_from(&self, store: &impl PreimageStore) -> Result<()> {
+        // The preimage is read from the store by value, and outlives the request in this cell.
+        let preimage = OnceCell::new();
+        self.next_preimage_request(|key| {
+            let value = store
+                .get(key)?
+                .ok_or_else(|| anyhow!(PreimageOracleError::MissingPreimage(key)))?;
+            Ok(preimage.get_or_init(|| value))
+        })
+    }
+
+    /// Serves preimage requests in a loop with the preimages held by the `store`, like
+    /// [PreimageOracleServer::serve_preimage_requests].
+    fn serve_preimage_requests_from(&self, store: &impl PreimageStore) -> Result<()> {
+        loop {
+            self.next_preimage_request_from(store)?;
+        }
+    }
+}
+
+/// A [PreimageStore] holds the preimages served by the host, keyed by their [PreimageKey]. It is
+/// shared by the hint handlers, which put the preimages they fetch, and the
+/// [PreimageOracleServer], which serves them with
+/// [PreimageOracleServer::next_preimage_request_from].
+pub trait PreimageStore {
+    /// Get the preimage of the `key`.
+    ///
+    /// # Returns
+    /// - `Ok(Some(Vec<u8>))` if the store holds the preimage.
+    /// - `Ok(None)` if the store does not hold the preimage.
+    /// - `Err(_)` if the store could not be read.
+    fn get(&self, key: PreimageKey) -> Result<Option<Vec<u8>>>;
+
+    /// Store the `value` as the preimage of the `key`, replacing any preimage it already holds.
+    /// Implementations must reject a preimage that does not match its key, per
+    /// [PreimageKey::validate_preimage], as the [PreimageOracleServer] serves the stored
+    /// preimages as is.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the preimage was stored.
+    /// - `Err(_)` if the preimage does not match its key, in which case the error is a
+    ///   [crate::PreimageOracleError::InvalidPreimage], or if it could not be stored.
+    fn put(&self, key: PreimageKey, value: Vec<u8>) -> Result<()>;
+
+    /// Returns whether the store holds the preimage of the `key`. Stores that can check for a key
+    /// without reading its preimage should override this.
+    ///
+    /// # Returns
+    /// - `Ok(bool)` if the store could be read.
+    /// - `Err(_)` if the store could not be read.
+    fn contains(&self, key: PreimageKey) -> Result<bool> {
+        Ok(self.get(key)?.is_some())
+    }
 }
 
 /// A [HintReaderServer] is a high-level interface to read preimage hints from the
@@ -58,4 +230,509 @@ pub trait HintReaderServer {
     ///   acknowledgement.
     /// - `Err(_)` if the hint was not received correctly.
     fn next_hint(&self, route_hint: impl FnMut(String) -> Result<()>) -> Result<()>;
+
+    /// Get the next hint request, parsed into a typed [Hint], and return the acknowledgement to
+    /// the client. The client is acknowledged even if the hint fails to parse.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was received and routed, and the client was notified of the host's
+    ///   acknowledgement.
+    /// - `Err(_)` if the hint was not received correctly, or is not a valid [Hint]. A hint that
+    ///   fails to parse is a [crate::HintParsingError].
+    fn next_typed_hint(&self, mut route_hint: impl FnMut(Hint) -> Result<()>) -> Result<()> {
+        let mut parse_error = None;
+        self.next_hint(|hint| match hint.parse::<Hint>() {
+            Ok(hint) => route_hint(hint),
+            Err(e) => {
+                parse_error = Some(e);
+                Ok(())
+            }
+        })?;
+        parse_error.map_or(Ok(()), |e| Err(anyhow!(e)))
+    }
+}
+
+/// The [AsyncPreimageOracleClient] trait is the asynchronous counterpart of
+/// [PreimageOracleClient], for host-side and native-mode consumers that read preimages from within
+/// an async runtime.
+#[cfg(feature = "std")]
+#[async_trait]
+pub trait AsyncPreimageOracleClient {
+    /// Get the data corresponding to the key from the host. Return the data in a new heap
+    /// allocated `Vec<u8>`
+    ///
+    /// # Returns
+    /// - `Ok(Vec<u8>)` if the data was successfully fetched from the host.
+    /// - `Err(_)` if the data could not be fetched from the host.
+    async fn get(&self, key: PreimageKey) -> Result<Vec<u8>>;
+
+    /// Get the data corresponding to the key from the host. Writes the data into the provided
+    /// buffer.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the data was successfully written into the buffer.
+    /// - `Err(_)` if the data could not be written into the buffer. If the buffer size does not
+    ///   match the length of the preimage, the error is a
+    ///   [crate::PreimageOracleError::LengthMismatch].
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()>;
+
+    /// Get the length of the data corresponding to the key from the host, without reading the
+    /// data.
+    ///
+    /// # Returns
+    /// - `Ok(usize)` if the length of the preimage was successfully fetched from the host.
+    /// - `Err(_)` if the length could not be fetched from the host.
+    async fn preimage_len(&self, key: PreimageKey) -> Result<usize>;
+
+    /// Get the data corresponding to each of the `keys` from the host, in order.
+    ///
+    /// # Returns
+    /// - `Ok(Vec<Vec<u8>>)` with the preimage of each key, if all of them were successfully fetched
+    ///   from the host.
+    /// - `Err(_)` if any of the preimages could not be fetched from the host.
+    async fn get_many(&self, keys: &[PreimageKey]) -> Result<Vec<Vec<u8>>> {
+        let mut preimages = Vec::with_capacity(keys.len());
+        for key in keys {
+            preimages.push(self.get(*key).await?);
+        }
+        Ok(preimages)
+    }
+
+    /// Get the result of calling the precompile at `address` with `input`, as computed by the
+    /// host, and check it with [validate_precompile_result].
+    ///
+    /// # Returns
+    /// - `Ok((success, output))` with the success flag and the output of the call.
+    /// - `Err(_)` if the result could not be fetched from the host, or is invalid.
+    async fn precompile_result(&self, address: Address, input: &[u8]) -> Result<(bool, Vec<u8>)> {
+        let result = self.get(precompile_key(address, input)).await?;
+        let (success, output) = decode_precompile_result(&result).map_err(|e| anyhow!(e))?;
+        validate_precompile_result(address, input, success, output).map_err(|e| anyhow!(e))?;
+        Ok((success, output.to_vec()))
+    }
+}
+
+/// The [AsyncHintWriterClient] trait is the asynchronous counterpart of [HintWriterClient].
+#[cfg(feature = "std")]
+#[async_trait]
+pub trait AsyncHintWriterClient {
+    /// Write a hint to the host, resolving once all data has been written and the host has
+    /// acknowledged the hint.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was successfully written to the host.
+    /// - `Err(_)` if the hint could not be written to the host.
+    async fn write(&self, hint: &str) -> Result<()>;
+
+    /// Write a typed [Hint] to the host, in its canonical string representation.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was successfully written to the host.
+    /// - `Err(_)` if the hint could not be written to the host.
+    async fn write_hint(&self, hint: &Hint) -> Result<()> {
+        self.write(&hint.to_string()).await
+    }
+
+    /// Hint the host to execute the precompile at `address` with `input`.
+    ///
+    /// # Returns
+    /// - `Ok(())` if the hint was successfully written to the host.
+    /// - `Err(_)` if the hint could not be written to the host.
+    async fn hint_precompile(&self, address: Address, input: &[u8]) -> Result<()> {
+        self.write_hint(&Hint::precompile(address, input)).await
+    }
+}
+
+/// The [BlockOn] trait drives a future to completion from synchronous code, such as with the
+/// `block_on` function of an async runtime.
+#[cfg(feature = "std")]
+pub trait BlockOn {
+    /// Blocks until the `future` resolves, and returns its output.
+    fn block_on<F: Future>(&self, future: F) -> F::Output;
+}
+
+/// A [BlockingOracleClient] adapts an [AsyncPreimageOracleClient] and [AsyncHintWriterClient] into
+/// a [PreimageOracleClient] and [HintWriterClient], by blocking on each request with the
+/// [BlockOn] executor.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone)]
+pub struct BlockingOracleClient<C, E> {
+    /// The asynchronous client.
+    inner: C,
+    /// The executor that drives the requests of the client.
+    executor: E,
+}
+
+#[cfg(feature = "std")]
+impl<C, E> BlockingOracleClient<C, E> {
+    /// Creates a new [BlockingOracleClient] that drives the requests of `inner` with `executor`.
+    pub const fn new(inner: C, executor: E) -> Self {
+        Self { inner, executor }
+    }
+
+    /// Consumes the [BlockingOracleClient], returning the asynchronous client.
+    pub fn into_inner(self) -> C {
+        self.inner
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C, E> PreimageOracleClient for BlockingOracleClient<C, E>
+where
+    C: AsyncPreimageOracleClient + Sync,
+    E: BlockOn,
+{
+    fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        self.executor.block_on(self.inner.get(key))
+    }
+
+    fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        self.executor.block_on(self.inner.get_exact(key, buf))
+    }
+
+    fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        self.executor.block_on(self.inner.preimage_len(key))
+    }
+
+    fn get_many(&self, keys: &[PreimageKey]) -> Result<Vec<Vec<u8>>> {
+        self.executor.block_on(self.inner.get_many(keys))
+    }
+}
+
+#[cfg(feature = "std")]
+impl<C, E> HintWriterClient for BlockingOracleClient<C, E>
+where
+    C: AsyncHintWriterClient + Sync,
+    E: BlockOn,
+{
+    fn write(&self, hint: &str) -> Result<()> {
+        self.executor.block_on(self.inner.write(hint))
+    }
+
+    fn write_hint(&self, hint: &Hint) -> Result<()> {
+        self.executor.block_on(self.inner.write_hint(hint))
+    }
+}
+
+/// An [AsyncOracleClient] adapts a blocking [PreimageOracleClient] and [HintWriterClient], such
+/// as an [crate::OracleReader] and [crate::HintWriter], into an [AsyncPreimageOracleClient] and
+/// [AsyncHintWriterClient].
+///
+/// The blocking client is moved to a dedicated I/O thread, which runs the requests one at a time,
+/// so that concurrent requests cannot interleave their reads and writes on the same pipe, and so
+/// that the blocking pipe I/O never stalls the executor. This lets a host and its client share a
+/// single-threaded runtime. A request runs to completion on the I/O thread even if its future is
+/// dropped, so that the pipe is never left with a partially read response.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct AsyncOracleClient<C> {
+    /// The sender of the requests to the I/O thread.
+    requests: mpsc::Sender<Request<C>>,
+    /// The I/O thread, which owns the blocking client and returns it once the requests end.
+    io_thread: JoinHandle<C>,
+}
+
+/// A request run by the I/O thread of an [AsyncOracleClient] with its blocking client.
+#[cfg(feature = "std")]
+type Request<C> = Box<dyn FnOnce(&C) + Send>;
+
+#[cfg(feature = "std")]
+impl<C: Send + 'static> AsyncOracleClient<C> {
+    /// Creates a new [AsyncOracleClient] over the blocking client `inner`, and spawns its I/O
+    /// thread.
+    pub fn new(inner: C) -> Result<Self> {
+        let (requests, pending) = mpsc::channel::<Request<C>>();
+        let io_thread = thread::Builder::new()
+            .name("oracle-client-io".to_string())
+            .spawn(move || {
+                for request in pending {
+                    request(&inner);
+                }
+                inner
+            })
+            .map_err(|e| anyhow!(e))?;
+        Ok(Self { requests, io_thread })
+    }
+
+    /// Consumes the [AsyncOracleClient], returning the blocking client once the pending requests
+    /// have run. Fails if a request panicked.
+    pub fn into_inner(self) -> Result<C> {
+        drop(self.requests);
+        self.io_thread.join().map_err(|_| anyhow!("Oracle client I/O thread panicked"))
+    }
+
+    /// Runs `request` on the I/O thread with exclusive access to the blocking client. Fails if a
+    /// previous request panicked, as it may have left a partially read response in the pipe.
+    async fn request<T: Send + 'static>(
+        &self,
+        request: impl FnOnce(&C) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let slot = Arc::new(Mutex::new(ResponseSlot { response: None, waker: None }));
+        let responder = Responder { slot: slot.clone(), response: None };
+        self.requests
+            .send(Box::new(move |client: &C| responder.respond(request(client))))
+            .map_err(|_| anyhow!("Oracle client I/O thread panicked"))?;
+        Response { slot }.await
+    }
+}
+
+/// The response of a request to the I/O thread of an [AsyncOracleClient], shared between the
+/// [Responder] and the [Response] future.
+#[cfg(feature = "std")]
+struct ResponseSlot<T> {
+    /// The response, once the request has run.
+    response: Option<Result<T>>,
+    /// The waker of the task awaiting the response.
+    waker: Option<Waker>,
+}
+
+/// Sends the response of a request from the I/O thread of an [AsyncOracleClient]. If the request
+/// panics, the [Responder] is dropped without a response, and fails the request instead.
+#[cfg(feature = "std")]
+struct Responder<T> {
+    /// The slot of the response.
+    slot: Arc<Mutex<ResponseSlot<T>>>,
+    /// The response to send when dropped.
+    response: Option<Result<T>>,
+}
+
+#[cfg(feature = "std")]
+impl<T> Responder<T> {
+    /// Sends the `response` of the request.
+    fn respond(mut self, response: Result<T>) {
+        self.response = Some(response);
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T> Drop for Responder<T> {
+    fn drop(&mut self) {
+        let response =
+            self.response.take().unwrap_or_else(|| Err(anyhow!("Oracle client request panicked")));
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        slot.response = Some(response);
+        if let Some(waker) = slot.waker.take() {
+            waker.wake();
+        }
+    }
+}
+
+/// A future resolving to the response of a request to the I/O thread of an [AsyncOracleClient].
+#[cfg(feature = "std")]
+struct Response<T> {
+    /// The slot of the response.
+    slot: Arc<Mutex<ResponseSlot<T>>>,
+}
+
+#[cfg(feature = "std")]
+impl<T> Future for Response<T> {
+    type Output = Result<T>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut slot = self.slot.lock().unwrap_or_else(|e| e.into_inner());
+        match slot.response.take() {
+            Some(response) => Poll::Ready(response),
+            None => {
+                slot.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+#[async_trait]
+impl<C: PreimageOracleClient + Send + 'static> AsyncPreimageOracleClient for AsyncOracleClient<C> {
+    async fn get(&self, key: PreimageKey) -> Result<Vec<u8>> {
+        self.request(move |client| client.get(key)).await
+    }
+
+    async fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+        let len = buf.len();
+        let data = self
+            .request(move |client| {
+                let mut data = vec![0u8; len];
+                client.get_exact(key, &mut data).map(|_| data)
+            })
+            .await?;
+        buf.copy_from_slice(&data);
+        Ok(())
+    }
+
+    async fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+        self.request(move |client| client.preimage_len(key)).await
+    }
+
+    async fn get_many(&self, keys: &[PreimageKey]) -> Result<Vec<Vec<u8>>> {
+        let keys = keys.to_vec();
+        self.request(move |client| client.get_many(&keys)).await
+    }
+}
+
+#[cfg(feature = "std")]
+#[async_trait]
+impl<C: HintWriterClient + Send + 'static> AsyncHintWriterClient for AsyncOracleClient<C> {
+    async fn write(&self, hint: &str) -> Result<()> {
+        let hint = hint.to_string();
+        self.request(move |client| client.write(&hint)).await
+    }
+
+    async fn write_hint(&self, hint: &Hint) -> Result<()> {
+        let hint = hint.clone();
+        self.request(move |client| client.write_hint(&hint)).await
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod test {
+    use super::*;
+    use crate::PreimageKeyType;
+    use alloc::vec;
+    use core::cell::RefCell;
+
+    /// A blocking client over a single preimage, recording the hints it receives. It is not
+    /// [Sync], like the pipe clients.
+    #[derive(Default)]
+    struct MockClient {
+        preimage: Vec<u8>,
+        hints: RefCell<Vec<String>>,
+    }
+
+    impl PreimageOracleClient for MockClient {
+        fn get(&self, _: PreimageKey) -> Result<Vec<u8>> {
+            Ok(self.preimage.clone())
+        }
+
+        fn get_exact(&self, _: PreimageKey, buf: &mut [u8]) -> Result<()> {
+            if buf.len() != self.preimage.len() {
+                return Err(anyhow!("length mismatch"));
+            }
+            buf.copy_from_slice(&self.preimage);
+            Ok(())
+        }
+
+        fn preimage_len(&self, _: PreimageKey) -> Result<usize> {
+            Ok(self.preimage.len())
+        }
+    }
+
+    impl HintWriterClient for MockClient {
+        fn write(&self, hint: &str) -> Result<()> {
+            self.hints.borrow_mut().push(hint.to_string());
+            Ok(())
+        }
+    }
+
+    /// A [BlockOn] executor backed by a current-thread tokio runtime.
+    struct TokioBlockOn(tokio::runtime::Runtime);
+
+    impl BlockOn for TokioBlockOn {
+        fn block_on<F: Future>(&self, future: F) -> F::Output {
+            self.0.block_on(future)
+        }
+    }
+
+    #[test]
+    fn test_async_and_blocking_adapters_roundtrip() {
+        let key = PreimageKey::new([0xFF; 32], PreimageKeyType::Keccak256);
+        let client =
+            AsyncOracleClient::new(MockClient { preimage: vec![1, 2, 3], ..Default::default() })
+                .unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let client = BlockingOracleClient::new(client, TokioBlockOn(runtime));
+
+        assert_eq!(client.get(key).unwrap(), vec![1, 2, 3]);
+        assert_eq!(client.preimage_len(key).unwrap(), 3);
+        let mut buf = [0u8; 3];
+        client.get_exact(key, &mut buf).unwrap();
+        assert_eq!(buf, [1, 2, 3]);
+        assert!(client.get_exact(key, &mut [0u8; 2]).is_err());
+
+        client.write("l1-block-header 0x00").unwrap();
+        assert_eq!(
+            *client.into_inner().into_inner().unwrap().hints.borrow(),
+            vec!["l1-block-header 0x00"]
+        );
+    }
+
+    #[test]
+    fn test_blocking_client_get_many_over_pipe() {
+        let preimages = (1..=3u8).map(|i| vec![i; 16 * i as usize]).collect::<Vec<_>>();
+        let keys = preimages.iter().map(|p| PreimageKey::new_keccak256(p)).collect::<Vec<_>>();
+        let (client_pipe, host_pipe) = crate::BidirectionalPipe::pair();
+        let host = thread::spawn({
+            let preimages = preimages.clone();
+            move || {
+                let server = crate::OracleServer::new(host_pipe);
+                let get_preimage = |key| {
+                    preimages
+                        .iter()
+                        .find(|p| PreimageKey::new_keccak256(p) == key)
+                        .ok_or_else(|| anyhow!("Preimage not available"))
+                };
+                while server.next_preimage_request(get_preimage).is_ok() {}
+            }
+        });
+
+        // The pipelined keys go through the async adapter and back to a blocking client.
+        let client = AsyncOracleClient::new(crate::OracleReader::new(client_pipe)).unwrap();
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+        let client = BlockingOracleClient::new(client, TokioBlockOn(runtime));
+        assert_eq!(client.get_many(&keys).unwrap(), preimages);
+        let reversed = client.get_many(&[keys[2], keys[0]]).unwrap();
+        assert_eq!(reversed, [preimages[2].clone(), preimages[0].clone()]);
+
+        drop(client.into_inner().into_inner().unwrap());
+        host.join().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_async_oracle_client_is_shareable() {
+        let key = PreimageKey::new([0xFF; 32], PreimageKeyType::Keccak256);
+        let client = std::sync::Arc::new(
+            AsyncOracleClient::new(MockClient { preimage: vec![0xAA; 64], ..Default::default() })
+                .unwrap(),
+        );
+
+        let handle = tokio::spawn({
+            let client = client.clone();
+            async move { client.get(key).await }
+        });
+        assert_eq!(handle.await.unwrap().unwrap(), vec![0xAA; 64]);
+        assert_eq!(client.preimage_len(key).await.unwrap(), 64);
+    }
+
+    #[tokio::test(flavor = "current_thread")]
+    async fn test_async_oracle_client_does_not_block_the_runtime() {
+        /// A blocking client whose requests wait for a preimage sent by another task.
+        struct WaitingClient(std::sync::mpsc::Receiver<Vec<u8>>);
+
+        impl PreimageOracleClient for WaitingClient {
+            fn get(&self, _: PreimageKey) -> Result<Vec<u8>> {
+                self.0.recv().map_err(|e| anyhow!(e))
+            }
+
+            fn get_exact(&self, key: PreimageKey, buf: &mut [u8]) -> Result<()> {
+                buf.copy_from_slice(&self.get(key)?);
+                Ok(())
+            }
+
+            fn preimage_len(&self, key: PreimageKey) -> Result<usize> {
+                self.get(key).map(|preimage| preimage.len())
+            }
+        }
+
+        // The host task runs on the same single-threaded runtime as the client, so it can only
+        // send the preimage if the request does not block the runtime.
+        let (host, preimages) = std::sync::mpsc::channel();
+        let client = AsyncOracleClient::new(WaitingClient(preimages)).unwrap();
+        let host = tokio::spawn(async move {
+            tokio::task::yield_now().await;
+            host.send(vec![1, 2, 3]).unwrap();
+        });
+
+        let key = PreimageKey::new([0xFF; 32], PreimageKeyType::Keccak256);
+        assert_eq!(client.get(key).await.unwrap(), vec![1, 2, 3]);
+        host.await.unwrap();
+    }
 }
@@ -5,7 +5,7 @@ use serde::{Deserialize, Serialize};
 
 use super::{L2BlockInfo, RawTransaction, Withdrawal};
 use alloc::vec::Vec;
-use alloy_primitives::{Address, B256};
+use alloy_primitives::{Address, B256, B64};
 
 /// Payload attributes.
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
@@ -38,9 +38,17 @@ pub struct L2PayloadAttributes {
     /// GasLimit override.
     #[cfg_attr(feature = "serde", serde(rename = "gasLimit"))]
     pub gas_limit: Option<u64>,
+    /// EIP-1559 parameters of the payload, set after Holocene: the base fee max change
+    /// denominator followed by the elasticity multiplier, both as big-endian u32s.
+    #[cfg_attr(
+        feature = "serde",
+        serde(rename = "eip1559Params", default, skip_serializing_if = "Option::is_none")
+    )]
+    pub eip_1559_params: Option<B64>,
 }
 
 /// Payload Attributes with parent block reference.
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[derive(Debug, Clone, PartialEq)]
 pub struct L2AttributesWithParent {
     /// The payload attributes.
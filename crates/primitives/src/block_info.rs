@@ -1,6 +1,6 @@
 //! This module contains the [L1BlockInfoTx] type, and various encoding / decoding methods for it.
 
-use super::{BlockID, DepositSourceDomain, L1InfoDepositSource, RollupConfig, SystemConfig};
+use super::{BlockID, DepositSourceDomain, RollupConfig, SystemConfig};
 use alloc::vec::Vec;
 use alloy_consensus::Header;
 use alloy_primitives::{address, Address, Bytes, TxKind, B256, U256};
@@ -122,9 +122,12 @@ impl L1BlockInfoTx {
     ) -> Result<Self> {
         // In the first block of Ecotone, the L1Block contract has not been upgraded yet due to the
         // upgrade transactions being placed after the L1 info transaction. Because of this,
-        // for the first block of Ecotone, we send a Bedrock style L1 block info transaction
+        // for the first block of Ecotone, we send a Bedrock style L1 block info transaction.
+        //
+        // Fjord does not change the L1 info transaction, so all blocks after the Ecotone
+        // activation block use the Ecotone format.
         if rollup_config.is_ecotone_active(l2_block_time) &&
-            rollup_config.ecotone_time.unwrap_or_default() != l2_block_time
+            !rollup_config.is_ecotone_activation_block(l2_block_time)
         {
             let scalar = system_config.l1_fee_scalar.to_be_bytes::<32>();
             let blob_base_fee_scalar = (scalar[0] == L1_SCALAR_ECOTONE)
@@ -178,15 +181,14 @@ impl L1BlockInfoTx {
     ) -> Result<(L1BlockInfoTx, OpTxEnvelope)> {
         let l1_info =
             Self::try_new(rollup_config, system_config, sequence_number, l1_header, l2_block_time)?;
-        let l1_block_hash = match l1_info {
-            Self::Bedrock(ref tx) => tx.block_hash,
-            Self::Ecotone(ref tx) => tx.block_hash,
-        };
+        let deposit_tx = l1_info.to_deposit_tx(rollup_config, l2_block_time);
+        Ok((l1_info, deposit_tx))
+    }
 
-        let source = DepositSourceDomain::L1Info(L1InfoDepositSource {
-            l1_block_hash,
-            seq_number: sequence_number,
-        });
+    /// Returns the typed [TxDeposit] carrying the [L1BlockInfoTx], to include at the top of the
+    /// L2 block with the given timestamp.
+    pub fn to_deposit_tx(&self, rollup_config: &RollupConfig, l2_block_time: u64) -> OpTxEnvelope {
+        let source = DepositSourceDomain::l1_info(self.id().hash, self.sequence_number());
 
         let mut deposit_tx = TxDeposit {
             source_hash: source.source_hash(),
@@ -196,7 +198,7 @@ impl L1BlockInfoTx {
             value: U256::ZERO,
             gas_limit: 150_000_000,
             is_system_transaction: true,
-            input: l1_info.encode_calldata(),
+            input: self.encode_calldata(),
         };
 
         // With the regolith hardfork, system transactions were deprecated, and we allocate
@@ -206,7 +208,7 @@ impl L1BlockInfoTx {
             deposit_tx.gas_limit = REGOLITH_SYSTEM_TX_GAS;
         }
 
-        Ok((l1_info, OpTxEnvelope::Deposit(deposit_tx)))
+        OpTxEnvelope::Deposit(deposit_tx)
     }
 
     /// Decodes the [L1BlockInfoEcotone] object from ethereum transaction calldata.
@@ -251,6 +253,23 @@ impl L1BlockInfoTx {
         }
     }
 
+    /// Returns the L1 fee scalar for the info transaction. After ecotone, the blob base fee scalar
+    /// and the base fee scalar are packed back into a version 1 scalar.
+    pub fn l1_fee_scalar(&self) -> U256 {
+        match self {
+            Self::Bedrock(L1BlockInfoBedrock { l1_fee_scalar, .. }) => *l1_fee_scalar,
+            Self::Ecotone(L1BlockInfoEcotone { base_fee_scalar, blob_base_fee_scalar, .. }) => {
+                // We do not know if the scalar was derived from a v0 or v1 scalar, but v1 is fine,
+                // a 0 blob base fee scalar has the same effect.
+                let mut buf = B256::ZERO;
+                buf[0] = L1_SCALAR_ECOTONE;
+                buf[24..28].copy_from_slice(blob_base_fee_scalar.to_be_bytes().as_ref());
+                buf[28..32].copy_from_slice(base_fee_scalar.to_be_bytes().as_ref());
+                buf.into()
+            }
+        }
+    }
+
     /// Returns the batcher address for the info transaction
     pub fn batcher_address(&self) -> Address {
         match self {
@@ -373,14 +392,40 @@ impl L1BlockInfoEcotone {
 #[cfg(test)]
 mod test {
     use super::*;
-    use alloc::string::ToString;
-    use alloy_primitives::{address, b256, hex};
+    use crate::OP_MAINNET_CONFIG;
+    use alloc::{string::ToString, vec::Vec};
+    use alloy_eips::eip2718::Encodable2718;
+    use alloy_primitives::{address, b256, hex, keccak256};
 
     extern crate std;
 
     const RAW_BEDROCK_INFO_TX: [u8; L1_INFO_TX_LEN_BEDROCK] = hex!("015d8eb9000000000000000000000000000000000000000000000000000000000117c4eb0000000000000000000000000000000000000000000000000000000065280377000000000000000000000000000000000000000000000000000000026d05d953392012032675be9f94aae5ab442de73c5f4fb1bf30fa7dd0d2442239899a40fc00000000000000000000000000000000000000000000000000000000000000040000000000000000000000006887246668a3b87f54deb3b94ba47a6f63f3298500000000000000000000000000000000000000000000000000000000000000bc00000000000000000000000000000000000000000000000000000000000a6fe0");
     const RAW_ECOTONE_INFO_TX: [u8; L1_INFO_TX_LEN_ECOTONE] = hex!("440a5e2000000558000c5fc5000000000000000500000000661c277300000000012bec20000000000000000000000000000000000000000000000000000000026e9f109900000000000000000000000000000000000000000000000000000000000000011c4c84c50740386c7dc081efddd644405f04cde73e30a2e381737acce9f5add30000000000000000000000006887246668a3b87f54deb3b94ba47a6f63f32985");
 
+    /// The L1 info deposit transaction of OP Mainnet block 118024092, after Ecotone.
+    /// <https://optimistic.etherscan.io/tx/0x88501da5d5ca990347c2193be90a07037af1e3820bb40774c8154871c7669150>
+    const RAW_ECOTONE_DEPOSIT_TX: [u8; 251] = hex!("7ef8f8a0a539eb753df3b13b7e386e147d45822b67cb908c9ddc5618e3dbaa22ed00850b94deaddeaddeaddeaddeaddeaddeaddeaddead00019442000000000000000000000000000000000000158080830f424080b8a4440a5e2000000558000c5fc50000000000000000000000006605a89f00000000012a10d90000000000000000000000000000000000000000000000000000000af39ac3270000000000000000000000000000000000000000000000000000000d5ea528d24e582fa68786f080069bdbfe06a43f8e67bfd31b8e4d8a8837ba41da9a82a54a0000000000000000000000006887246668a3b87f54deb3b94ba47a6f63f32985");
+
+    /// The L1 info deposit transaction of OP Mainnet block 124665056, after Fjord.
+    /// <https://optimistic.etherscan.io/tx/0x312e290cf36df704a2217b015d6455396830b0ce678b860ebfcc30f41403d7b1>
+    const RAW_FJORD_DEPOSIT_TX: [u8; 251] = hex!("7ef8f8a0683079df94aa5b9cf86687d739a60a9b4f0835e520ec4d664e2e415dca17a6df94deaddeaddeaddeaddeaddeaddeaddeaddead00019442000000000000000000000000000000000000158080830f424080b8a4440a5e200000146b000f79c500000000000000040000000066d052e700000000013ad8a3000000000000000000000000000000000000000000000000000000003ef1278700000000000000000000000000000000000000000000000000000000000000012fdf87b89884a61e74b322bbcf60386f543bfae7827725efaaf0ab1de2294a590000000000000000000000006887246668a3b87f54deb3b94ba47a6f63f32985");
+
+    /// Returns the timestamp of the OP Mainnet block with the given number.
+    fn op_mainnet_block_time(number: u64) -> u64 {
+        let genesis = &OP_MAINNET_CONFIG.genesis;
+        genesis.timestamp + (number - genesis.l2.number) * OP_MAINNET_CONFIG.block_time
+    }
+
+    /// Rebuilds the L1 info deposit transaction of the given OP Mainnet block from its L1 info,
+    /// and returns its EIP-2718 encoding.
+    fn rebuild_op_mainnet_deposit_tx(l1_info: L1BlockInfoTx, number: u64) -> Vec<u8> {
+        let time = op_mainnet_block_time(number);
+        assert!(!OP_MAINNET_CONFIG.is_ecotone_activation_block(time));
+        let mut buf = Vec::new();
+        l1_info.to_deposit_tx(&OP_MAINNET_CONFIG, time).encode_2718(&mut buf);
+        buf
+    }
+
     #[test]
     fn bedrock_l1_block_info_invalid_len() {
         let err = L1BlockInfoBedrock::decode_calldata(&[0xde, 0xad]);
@@ -526,4 +571,130 @@ mod test {
         assert_eq!(l1_info.blob_base_fee_scalar, blob_base_fee_scalar);
         assert_eq!(l1_info.base_fee_scalar, base_fee_scalar);
     }
+
+    #[test]
+    fn try_new_ecotone_activation_block_is_bedrock() {
+        // Ecotone activates in between two L2 blocks, so the first block with a timestamp past the
+        // activation time must still carry a Bedrock style L1 info transaction.
+        let rollup_config =
+            RollupConfig { block_time: 2, ecotone_time: Some(9), ..Default::default() };
+        let system_config = SystemConfig::default();
+        let l1_header = Header::default();
+
+        let l1_info =
+            L1BlockInfoTx::try_new(&rollup_config, &system_config, 0, &l1_header, 10).unwrap();
+        assert!(matches!(l1_info, L1BlockInfoTx::Bedrock(_)));
+
+        let l1_info =
+            L1BlockInfoTx::try_new(&rollup_config, &system_config, 0, &l1_header, 12).unwrap();
+        assert!(matches!(l1_info, L1BlockInfoTx::Ecotone(_)));
+    }
+
+    #[test]
+    fn try_new_with_deposit_tx_fjord() {
+        let rollup_config = RollupConfig {
+            block_time: 2,
+            regolith_time: Some(0),
+            ecotone_time: Some(0),
+            fjord_time: Some(10),
+            ..Default::default()
+        };
+        let system_config = SystemConfig::default();
+        let l1_header = Header::default();
+
+        let (l1_info, tx) = L1BlockInfoTx::try_new_with_deposit_tx(
+            &rollup_config,
+            &system_config,
+            1,
+            &l1_header,
+            10,
+        )
+        .unwrap();
+        let L1BlockInfoTx::Ecotone(ref info) = l1_info else {
+            panic!("Wrong fork");
+        };
+        assert_eq!(info.sequence_number, 1);
+
+        let OpTxEnvelope::Deposit(tx) = tx else {
+            panic!("Expected a deposit transaction");
+        };
+        assert_eq!(tx.input.len(), L1_INFO_TX_LEN_ECOTONE);
+        assert_eq!(tx.input[0..4], L1_INFO_TX_SELECTOR_ECOTONE);
+        assert_eq!(tx.input, l1_info.encode_calldata());
+        assert_eq!(tx.gas_limit, REGOLITH_SYSTEM_TX_GAS);
+        assert!(!tx.is_system_transaction);
+    }
+
+    #[test]
+    fn op_mainnet_ecotone_deposit_tx() {
+        let l1_info = L1BlockInfoTx::Ecotone(L1BlockInfoEcotone {
+            number: 19534041,
+            time: 1711646879,
+            base_fee: 47036678951,
+            block_hash: b256!("4e582fa68786f080069bdbfe06a43f8e67bfd31b8e4d8a8837ba41da9a82a54a"),
+            sequence_number: 0,
+            batcher_address: address!("6887246668a3b87f54deb3b94ba47a6f63f32985"),
+            blob_base_fee: 57422457042,
+            blob_base_fee_scalar: 810949,
+            base_fee_scalar: 1368,
+        });
+        let time = op_mainnet_block_time(118024092);
+        assert!(!OP_MAINNET_CONFIG.is_fjord_active(time));
+
+        let encoded = rebuild_op_mainnet_deposit_tx(l1_info, 118024092);
+        assert_eq!(encoded, RAW_ECOTONE_DEPOSIT_TX);
+        assert_eq!(
+            keccak256(&encoded),
+            b256!("88501da5d5ca990347c2193be90a07037af1e3820bb40774c8154871c7669150")
+        );
+    }
+
+    #[test]
+    fn op_mainnet_fjord_deposit_tx() {
+        let l1_info = L1BlockInfoTx::Ecotone(L1BlockInfoEcotone {
+            number: 20633763,
+            time: 1724928743,
+            base_fee: 1055991687,
+            block_hash: b256!("2fdf87b89884a61e74b322bbcf60386f543bfae7827725efaaf0ab1de2294a59"),
+            sequence_number: 4,
+            batcher_address: address!("6887246668a3b87f54deb3b94ba47a6f63f32985"),
+            blob_base_fee: 1,
+            blob_base_fee_scalar: 1014213,
+            base_fee_scalar: 5227,
+        });
+        let time = op_mainnet_block_time(124665056);
+        assert!(OP_MAINNET_CONFIG.is_fjord_active(time));
+
+        let encoded = rebuild_op_mainnet_deposit_tx(l1_info, 124665056);
+        assert_eq!(encoded, RAW_FJORD_DEPOSIT_TX);
+        assert_eq!(
+            keccak256(&encoded),
+            b256!("312e290cf36df704a2217b015d6455396830b0ce678b860ebfcc30f41403d7b1")
+        );
+    }
+
+    #[test]
+    fn l1_fee_scalar_bedrock() {
+        let l1_info = L1BlockInfoTx::decode_calldata(RAW_BEDROCK_INFO_TX.as_ref()).unwrap();
+        assert_eq!(l1_info.l1_fee_scalar(), U256::from(0xa6fe0));
+    }
+
+    #[test]
+    fn l1_fee_scalar_ecotone_roundtrip() {
+        let rollup_config = RollupConfig { ecotone_time: Some(1), ..Default::default() };
+        let mut scalar = B256::ZERO;
+        scalar[0] = L1_SCALAR_ECOTONE;
+        scalar[24..28].copy_from_slice(&810949_u32.to_be_bytes());
+        scalar[28..32].copy_from_slice(&1368_u32.to_be_bytes());
+        let system_config = SystemConfig { l1_fee_scalar: scalar.into(), ..Default::default() };
+
+        let l1_info =
+            L1BlockInfoTx::try_new(&rollup_config, &system_config, 0, &Header::default(), 0xFF)
+                .unwrap();
+        assert!(matches!(l1_info, L1BlockInfoTx::Ecotone(_)));
+        assert_eq!(l1_info.l1_fee_scalar(), system_config.l1_fee_scalar);
+
+        let decoded = L1BlockInfoTx::decode_calldata(RAW_ECOTONE_INFO_TX.as_ref()).unwrap();
+        assert_eq!(decoded.l1_fee_scalar(), U256::from_be_bytes(scalar.0));
+    }
 }
@@ -184,6 +184,24 @@ pub enum DepositSourceDomain {
 }
 
 impl DepositSourceDomain {
+    /// Creates a [DepositSourceDomain::User] source for the deposit log at `log_index` within the
+    /// L1 block.
+    pub fn user(l1_block_hash: B256, log_index: u64) -> Self {
+        Self::User(UserDepositSource::new(l1_block_hash, log_index))
+    }
+
+    /// Creates a [DepositSourceDomain::L1Info] source for the L1 info deposit of the L2 block at
+    /// `seq_number` within the epoch.
+    pub fn l1_info(l1_block_hash: B256, seq_number: u64) -> Self {
+        Self::L1Info(L1InfoDepositSource::new(l1_block_hash, seq_number))
+    }
+
+    /// Creates a [DepositSourceDomain::Upgrade] source for the network upgrade transaction with
+    /// the given human-readable `intent`.
+    pub fn upgrade(intent: impl Into<String>) -> Self {
+        Self::Upgrade(UpgradeDepositSource::new(intent.into()))
+    }
+
     /// Returns the source hash.
     pub fn source_hash(&self) -> B256 {
         match self {
@@ -214,12 +232,7 @@ impl UserDepositSource {
         let mut input = [0u8; 32 * 2];
         input[..32].copy_from_slice(&self.l1_block_hash[..]);
         input[32 * 2 - 8..].copy_from_slice(&self.log_index.to_be_bytes());
-        let deposit_id_hash = keccak256(input);
-        let mut domain_input = [0u8; 32 * 2];
-        let identifier_bytes: [u8; 8] = (DepositSourceDomainIdentifier::User as u64).to_be_bytes();
-        domain_input[32 - 8..32].copy_from_slice(&identifier_bytes);
-        domain_input[32..].copy_from_slice(&deposit_id_hash[..]);
-        keccak256(domain_input)
+        domain_source_hash(DepositSourceDomainIdentifier::User, keccak256(input))
     }
 }
 
@@ -243,13 +256,7 @@ impl L1InfoDepositSource {
         let mut input = [0u8; 32 * 2];
         input[..32].copy_from_slice(&self.l1_block_hash[..]);
         input[32 * 2 - 8..].copy_from_slice(&self.seq_number.to_be_bytes());
-        let deposit_id_hash = keccak256(input);
-        let mut domain_input = [0u8; 32 * 2];
-        let identifier_bytes: [u8; 8] =
-            (DepositSourceDomainIdentifier::L1Info as u64).to_be_bytes();
-        domain_input[32 - 8..32].copy_from_slice(&identifier_bytes);
-        domain_input[32..].copy_from_slice(&deposit_id_hash[..]);
-        keccak256(domain_input)
+        domain_source_hash(DepositSourceDomainIdentifier::L1Info, keccak256(input))
     }
 }
 
@@ -273,17 +280,26 @@ impl UpgradeDepositSource {
 
     /// Returns the source hash.
     pub fn source_hash(&self) -> B256 {
-        let intent_hash = keccak256(self.intent.as_bytes());
-        let mut domain_input = [0u8; 32 * 2];
-        let identifier_bytes: [u8; 8] =
-            (DepositSourceDomainIdentifier::Upgrade as u64).to_be_bytes();
-        domain_input[32 - 8..32].copy_from_slice(&identifier_bytes);
-        domain_input[32..].copy_from_slice(&intent_hash[..]);
-        keccak256(domain_input)
+        domain_source_hash(
+            DepositSourceDomainIdentifier::Upgrade,
+            keccak256(self.intent.as_bytes()),
+        )
     }
 }
 
-/// Derives a deposit transaction from an EVM log event emitted by the deposit contract.
+/// Computes the source hash of a deposit from its domain `identifier` and the hash identifying
+/// the deposit within that domain: `keccak256(bytes32(uint256(identifier)) ++ deposit_id_hash)`.
+fn domain_source_hash(identifier: DepositSourceDomainIdentifier, deposit_id_hash: B256) -> B256 {
+    let mut domain_input = [0u8; 32 * 2];
+    let identifier_bytes: [u8; 8] = (identifier as u64).to_be_bytes();
+    domain_input[32 - 8..32].copy_from_slice(&identifier_bytes);
+    domain_input[32..].copy_from_slice(&deposit_id_hash[..]);
+    keccak256(domain_input)
+}
+
+/// Derives a deposit transaction from an EVM log event emitted by the deposit contract. `index`
+/// is the index of the log within all logs of the L1 block, and is committed to in the
+/// [UserDepositSource] hash of the deposit.
 ///
 /// The emitted log must be in format:
 /// ```solidity
@@ -294,11 +310,14 @@ impl UpgradeDepositSource {
 ///    bytes opaqueData
 /// );
 /// ```
+///
+/// Any malformed log is rejected with a [DepositError]. Per the derivation spec, a single
+/// malformed deposit log invalidates all deposits of the L1 block.
 pub fn decode_deposit(
     block_hash: B256,
     index: usize,
     log: &Log,
-) -> Result<RawTransaction, DepositError> {
+) -> Result<TxDeposit, DepositError> {
     let topics = log.data.topics();
     if topics.len() != 4 {
         return Err(DepositError::UnexpectedTopicsLen(topics.len()));
@@ -306,11 +325,12 @@ pub fn decode_deposit(
     if topics[0] != DEPOSIT_EVENT_ABI_HASH {
         return Err(DepositError::InvalidSelector(DEPOSIT_EVENT_ABI_HASH, topics[0]));
     }
-    if log.data.data.len() < 64 {
-        return Err(DepositError::IncompleteOpaqueData(log.data.data.len()));
+    let data = log.data.data.as_ref();
+    if data.len() < 64 {
+        return Err(DepositError::IncompleteOpaqueData(data.len()));
     }
-    if log.data.data.len() % 32 != 0 {
-        return Err(DepositError::UnalignedData(log.data.data.len()));
+    if data.len() % 32 != 0 {
+        return Err(DepositError::UnalignedData(data.len()));
     }
 
     let from = Address::try_from(&topics[1].as_slice()[12..])
@@ -335,41 +355,49 @@ pub fn decode_deposit(
     // | 32     | [0; 24] . {U64 big endian, hex encoded length}  |
     // ------------------------------------------------------------
 
-    let opaque_content_offset: U64 = U64::try_from_be_slice(&log.data.data[24..32]).ok_or(
-        DepositError::InvalidOpaqueDataOffset(Bytes::copy_from_slice(&log.data.data[24..32])),
-    )?;
-    if opaque_content_offset != U64::from(32) {
-        return Err(DepositError::InvalidOpaqueDataOffset(Bytes::copy_from_slice(
-            &log.data.data[24..32],
-        )));
-    }
-
-    // The next 32 bytes indicate the length of the opaqueData content.
-    let opaque_content_len =
-        u64::from_be_bytes(log.data.data[56..64].try_into().map_err(|_| {
-            DepositError::InvalidOpaqueDataLength(Bytes::copy_from_slice(&log.data.data[56..64]))
-        })?);
-    if opaque_content_len as usize > log.data.data.len() - 64 {
+    // The offset is a uint256, which must fit in a u64 and point directly past itself.
+    let offset_valid = data[..24].iter().all(|b| *b == 0) &&
+        U64::try_from_be_slice(&data[24..32]) == Some(U64::from(32));
+    if !offset_valid {
+        return Err(DepositError::InvalidOpaqueDataOffset(Bytes::copy_from_slice(&data[24..32])));
+    }
+    let opaque_content_start = 64;
+
+    // The next 32 bytes indicate the length of the opaqueData content, which must fit in a u64.
+    if data[32..56].iter().any(|b| *b != 0) {
+        return Err(DepositError::InvalidOpaqueDataLength(Bytes::copy_from_slice(&data[32..64])));
+    }
+    let mut raw_len = [0u8; 8];
+    raw_len.copy_from_slice(&data[56..64]);
+    let opaque_content_len = u64::from_be_bytes(raw_len);
+
+    // Compare lengths as u64, so that the length can not be truncated on 32-bit targets.
+    let available = (data.len() - opaque_content_start) as u64;
+    if opaque_content_len > available {
         return Err(DepositError::OpaqueDataOverflow(
             opaque_content_len as usize,
-            log.data.data.len() - 64,
+            available as usize,
         ));
     }
-    let padded_len = opaque_content_len.checked_add(32).ok_or(DepositError::OpaqueDataOverflow(
-        opaque_content_len as usize,
-        log.data.data.len() - 64,
-    ))?;
-    if padded_len as usize <= log.data.data.len() - 64 {
+    // The opaque data is padded to a multiple of 32 bytes, so there must be less than a full
+    // word of padding.
+    if opaque_content_len + 32 <= available {
         return Err(DepositError::PaddedOpaqueDataOverflow(
-            log.data.data.len() - 64,
+            available as usize,
             opaque_content_len as usize,
         ));
     }
 
+    // Can only handle version 0 for now
+    if !version.is_zero() {
+        return Err(DepositError::InvalidVersion(version));
+    }
+
     // The remaining data is the opaqueData which is tightly packed and then padded to 32 bytes by
     // the EVM.
-    let opaque_data = &log.data.data[64..64 + opaque_content_len as usize];
-    let source = UserDepositSource::new(block_hash, index as u64);
+    let opaque_data =
+        &data[opaque_content_start..opaque_content_start + opaque_content_len as usize];
+    let source = DepositSourceDomain::user(block_hash, index as u64);
 
     let mut deposit_tx = TxDeposit {
         from,
@@ -377,15 +405,18 @@ pub fn decode_deposit(
         source_hash: source.source_hash(),
         ..Default::default()
     };
-
-    // Can only handle version 0 for now
-    if !version.is_zero() {
-        return Err(DepositError::InvalidVersion(version));
-    }
-
     unmarshal_deposit_version0(&mut deposit_tx, to, opaque_data)?;
+    Ok(deposit_tx)
+}
 
-    // Re-encode the deposit transaction and return as a RawTransaction
+/// Derives a deposit transaction with [decode_deposit], and RLP encodes it as a
+/// [RawTransaction].
+pub fn decode_deposit_raw(
+    block_hash: B256,
+    index: usize,
+    log: &Log,
+) -> Result<RawTransaction, DepositError> {
+    let deposit_tx = decode_deposit(block_hash, index, log)?;
     let mut buffer = Vec::<u8>::new();
     deposit_tx.encode(&mut buffer);
     Ok(RawTransaction::from(buffer))
@@ -403,9 +434,12 @@ pub(crate) fn unmarshal_deposit_version0(
 
     let mut offset = 0;
 
-    let raw_mint: [u8; 16] = data[offset + 16..offset + 32].try_into().map_err(|_| {
-        DepositError::MintDecode(Bytes::copy_from_slice(&data[offset + 16..offset + 32]))
-    })?;
+    // uint256 mint, which must fit in a u128.
+    if data[offset..offset + 16].iter().any(|b| *b != 0) {
+        return Err(DepositError::MintDecode(Bytes::copy_from_slice(&data[offset..offset + 32])));
+    }
+    let mut raw_mint = [0u8; 16];
+    raw_mint.copy_from_slice(&data[offset + 16..offset + 32]);
     let mint = u128::from_be_bytes(raw_mint);
 
     // 0 mint is represented as nil to skip minting code
@@ -421,9 +455,8 @@ pub(crate) fn unmarshal_deposit_version0(
     offset += 32;
 
     // uint64 gas
-    let raw_gas: [u8; 8] = data[offset..offset + 8]
-        .try_into()
-        .map_err(|_| DepositError::GasDecode(Bytes::copy_from_slice(&data[offset..offset + 8])))?;
+    let mut raw_gas = [0u8; 8];
+    raw_gas.copy_from_slice(&data[offset..offset + 8]);
     tx.gas_limit = u64::from_be_bytes(raw_gas) as u128;
     offset += 8;
 
@@ -452,6 +485,10 @@ mod tests {
     use super::*;
     use alloc::vec;
     use alloy_primitives::{address, b256, hex, LogData};
+    use proptest::{
+        collection::vec,
+        prelude::{any, proptest},
+    };
 
     #[test]
     fn test_decode_deposit_invalid_topic_len() {
@@ -594,7 +631,7 @@ mod tests {
                 Bytes::from(data),
             ),
         };
-        let tx = decode_deposit(B256::default(), 0, &log).unwrap();
+        let tx = decode_deposit_raw(B256::default(), 0, &log).unwrap();
         let raw_hex = hex!("f887a0ed428e1c45e1d9561b62834e1a2d3015a0caae3bfdc16b4da059ac885b01a14594000000000000000000000000000000000000000094000000000000000000000000000000000000000080808080b700000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000");
         let expected = RawTransaction::from(Bytes::from(raw_hex));
         assert_eq!(tx, expected);
@@ -636,7 +673,7 @@ mod tests {
                 Bytes::from(data),
             ),
         };
-        let tx = decode_deposit(B256::default(), 0, &log).unwrap();
+        let tx = decode_deposit_raw(B256::default(), 0, &log).unwrap();
         let raw_hex = hex!("f875a0ed428e1c45e1d9561b62834e1a2d3015a0caae3bfdc16b4da059ac885b01a145941111111111111111111111111111111111111111800a648203e880b700000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000000");
         let expected = RawTransaction::from(Bytes::from(raw_hex));
         assert_eq!(tx, expected);
@@ -687,4 +724,118 @@ mod tests {
         unmarshal_deposit_version0(&mut tx, to, &data).unwrap();
         assert_eq!(tx.to, TxKind::Call(address!("5555555555555555555555555555555555555555")));
     }
+
+    fn valid_deposit_log_data() -> Vec<u8> {
+        let mut data = vec![0u8; 192];
+        data[24..32].copy_from_slice(&U64::from(32).to_be_bytes::<8>());
+        data[56..64].copy_from_slice(&U64::from(128).to_be_bytes::<8>());
+        data
+    }
+
+    fn deposit_log(data: Vec<u8>) -> Log {
+        Log {
+            address: Address::default(),
+            data: LogData::new_unchecked(
+                vec![DEPOSIT_EVENT_ABI_HASH, B256::default(), B256::default(), B256::default()],
+                Bytes::from(data),
+            ),
+        }
+    }
+
+    #[test]
+    fn test_decode_deposit_offset_high_bytes() {
+        let mut data = valid_deposit_log_data();
+        data[0] = 1;
+        let err = decode_deposit(B256::default(), 0, &deposit_log(data)).unwrap_err();
+        assert_eq!(
+            err,
+            DepositError::InvalidOpaqueDataOffset(Bytes::copy_from_slice(
+                &U64::from(32).to_be_bytes::<8>()
+            ))
+        );
+    }
+
+    #[test]
+    fn test_decode_deposit_length_high_bytes() {
+        let mut data = valid_deposit_log_data();
+        // A length of 2^64 + 128 would be truncated to 128 if only the low bytes were read.
+        data[55] = 1;
+        let err = decode_deposit(B256::default(), 0, &deposit_log(data.clone())).unwrap_err();
+        assert_eq!(
+            err,
+            DepositError::InvalidOpaqueDataLength(Bytes::copy_from_slice(&data[32..64]))
+        );
+    }
+
+    #[test]
+    fn test_decode_deposit_mint_overflow() {
+        let mut data = valid_deposit_log_data();
+        data[64 + 15] = 1;
+        let err = decode_deposit(B256::default(), 0, &deposit_log(data.clone())).unwrap_err();
+        assert_eq!(err, DepositError::MintDecode(Bytes::copy_from_slice(&data[64..96])));
+    }
+
+    #[test]
+    fn test_decode_deposit_source_hash() {
+        let block_hash = B256::repeat_byte(0xAA);
+        let tx = decode_deposit(block_hash, 3, &deposit_log(valid_deposit_log_data())).unwrap();
+        assert_eq!(tx.source_hash, DepositSourceDomain::user(block_hash, 3).source_hash());
+        assert_ne!(tx.source_hash, DepositSourceDomain::user(block_hash, 2).source_hash());
+        assert_ne!(tx.source_hash, DepositSourceDomain::l1_info(block_hash, 3).source_hash());
+    }
+
+    #[test]
+    fn test_deposit_source_domain_constructors() {
+        let block_hash = B256::repeat_byte(0xAA);
+        assert_eq!(
+            DepositSourceDomain::user(block_hash, 1),
+            DepositSourceDomain::User(UserDepositSource {
+                l1_block_hash: block_hash,
+                log_index: 1
+            })
+        );
+        assert_eq!(
+            DepositSourceDomain::l1_info(block_hash, 2),
+            DepositSourceDomain::L1Info(L1InfoDepositSource {
+                l1_block_hash: block_hash,
+                seq_number: 2
+            })
+        );
+        assert_eq!(
+            DepositSourceDomain::upgrade("Ecotone: L1 Block Deployment").source_hash(),
+            b256!("877a6077205782ea15a6dc8699fa5ebcec5e0f4389f09cb8eda09488231346f8")
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn test_decode_deposit_garbage_never_panics(
+            topics_len in 0usize..6,
+            data in vec(any::<u8>(), 0..512),
+        ) {
+            let mut topics = vec![B256::default(); topics_len];
+            if let Some(selector) = topics.first_mut() {
+                *selector = DEPOSIT_EVENT_ABI_HASH;
+            }
+            let log = Log {
+                address: Address::default(),
+                data: LogData::new_unchecked(topics, Bytes::from(data)),
+            };
+            let _ = decode_deposit(B256::default(), 0, &log);
+        }
+
+        #[test]
+        fn test_decode_deposit_truncated_never_panics(
+            len in any::<u64>(),
+            opaque_data in vec(any::<u8>(), 0..256),
+            truncate_words in 0usize..8,
+        ) {
+            let mut data = valid_deposit_log_data();
+            data.truncate(64);
+            data[56..64].copy_from_slice(&len.to_be_bytes());
+            data.extend_from_slice(&opaque_data);
+            data.resize(data.len().div_ceil(32).saturating_sub(truncate_words).max(2) * 32, 0);
+            let _ = decode_deposit(B256::default(), 0, &deposit_log(data));
+        }
+    }
 }
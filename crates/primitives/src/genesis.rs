@@ -3,7 +3,7 @@
 use crate::{block::BlockID, system_config::SystemConfig};
 
 /// Represents the genesis state of the rollup.
-#[derive(Debug, Clone, Copy, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Genesis {
     /// The L1 block that the rollup starts *after* (no derived transactions)
@@ -11,6 +11,7 @@ pub struct Genesis {
     /// The L2 block the rollup starts from (no transactions, pre-configured state)
     pub l2: BlockID,
     /// Timestamp of the L2 block.
+    #[cfg_attr(feature = "serde", serde(rename = "l2_time", alias = "timestamp"))]
     pub timestamp: u64,
     /// Initial system configuration values.
     /// The L2 genesis block may not include transactions, and thus cannot encode the config
@@ -17,8 +17,9 @@ pub use raw_tx::RawTransaction;
 
 pub mod deposits;
 pub use deposits::{
-    decode_deposit, DepositError, DepositSourceDomain, DepositSourceDomainIdentifier,
-    L1InfoDepositSource, UpgradeDepositSource, UserDepositSource, DEPOSIT_EVENT_ABI_HASH,
+    decode_deposit, decode_deposit_raw, DepositError, DepositSourceDomain,
+    DepositSourceDomainIdentifier, L1InfoDepositSource, UpgradeDepositSource, UserDepositSource,
+    DEPOSIT_EVENT_ABI_HASH,
 };
 
 pub mod genesis;
@@ -33,7 +34,12 @@ pub use payload::{
 };
 
 pub mod rollup_config;
-pub use rollup_config::RollupConfig;
+pub use rollup_config::{RollupConfig, RollupConfigError};
+
+#[cfg(any(test, feature = "registry"))]
+pub mod registry;
+#[cfg(any(test, feature = "registry"))]
+pub use registry::{BASE_MAINNET_CONFIG, OP_MAINNET_CONFIG};
 
 pub mod attributes;
 pub use attributes::{L2AttributesWithParent, L2PayloadAttributes};
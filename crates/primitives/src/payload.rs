@@ -14,8 +14,7 @@ pub const PAYLOAD_MEM_FIXED_COST: u64 = 1000;
 pub const PAYLOAD_TX_MEM_OVERHEAD: u64 = 24;
 
 use super::{
-    Block, BlockInfo, L1BlockInfoBedrock, L1BlockInfoEcotone, L1BlockInfoTx, L2BlockInfo, OpBlock,
-    RollupConfig, SystemConfig, Withdrawal,
+    Block, BlockInfo, L1BlockInfoTx, L2BlockInfo, OpBlock, RollupConfig, SystemConfig, Withdrawal,
 };
 use alloy_rlp::{Decodable, Encodable};
 
@@ -120,36 +119,17 @@ impl L2ExecutionPayloadEnvelope {
     pub fn to_l2_block_ref(&self, rollup_config: &RollupConfig) -> Result<L2BlockInfo> {
         let L2ExecutionPayloadEnvelope { execution_payload, .. } = self;
 
-        let (l1_origin, sequence_number) = if execution_payload.block_number ==
-            rollup_config.genesis.l2.number
-        {
-            if execution_payload.block_hash != rollup_config.genesis.l2.hash {
-                anyhow::bail!("Invalid genesis hash");
-            }
-            (rollup_config.genesis.l1, 0)
-        } else {
-            if execution_payload.transactions.is_empty() {
-                anyhow::bail!(
-                    "L2 block is missing L1 info deposit transaction, block hash: {}",
-                    execution_payload.block_hash
-                );
-            }
-
-            let ty = execution_payload.transactions[0][0];
-            if ty != OpTxType::Deposit as u8 {
-                anyhow::bail!("First payload transaction has unexpected type: {:?}", ty);
-            }
-            let tx = OpTxEnvelope::decode(&mut execution_payload.transactions[0][1..].as_ref())
-                .map_err(|e| anyhow::anyhow!(e))?;
-
-            let OpTxEnvelope::Deposit(tx) = tx else {
-                anyhow::bail!("First payload transaction has unexpected type: {:?}", tx.tx_type());
+        let (l1_origin, sequence_number) =
+            if execution_payload.block_number == rollup_config.genesis.l2.number {
+                if execution_payload.block_hash != rollup_config.genesis.l2.hash {
+                    anyhow::bail!("Invalid genesis hash");
+                }
+                (rollup_config.genesis.l1, 0)
+            } else {
+                let l1_info = self.l1_info_tx()?;
+                (l1_info.id(), l1_info.sequence_number())
             };
 
-            let l1_info = L1BlockInfoTx::decode_calldata(tx.input.as_ref())?;
-            (l1_info.id(), l1_info.sequence_number())
-        };
-
         Ok(L2BlockInfo {
             block_info: BlockInfo {
                 hash: execution_payload.block_hash,
@@ -173,48 +153,59 @@ impl L2ExecutionPayloadEnvelope {
             return Ok(rollup_config.genesis.system_config);
         }
 
-        if execution_payload.transactions.is_empty() {
+        let l1_info = self.l1_info_tx()?;
+
+        // After Holocene, the EIP-1559 parameters are stored in the block's extra data, after a
+        // zero version byte.
+        let (eip1559_denominator, eip1559_elasticity) = if rollup_config
+            .is_holocene_active(execution_payload.timestamp) &&
+            execution_payload.extra_data.len() == 9 &&
+            execution_payload.extra_data[0] == 0
+        {
+            let extra_data = execution_payload.extra_data.as_ref();
+            (
+                u32::from_be_bytes(extra_data[1..5].try_into().expect("length checked")),
+                u32::from_be_bytes(extra_data[5..9].try_into().expect("length checked")),
+            )
+        } else {
+            (0, 0)
+        };
+
+        Ok(SystemConfig {
+            batcher_addr: l1_info.batcher_address(),
+            l1_fee_overhead: l1_info.l1_fee_overhead(),
+            l1_fee_scalar: l1_info.l1_fee_scalar(),
+            gas_limit: U256::from(execution_payload.gas_limit),
+            eip1559_denominator,
+            eip1559_elasticity,
+        })
+    }
+
+    /// Decodes the [L1BlockInfoTx] from the calldata of the L1 info deposit transaction, which
+    /// must be the first transaction of every L2 block after genesis.
+    fn l1_info_tx(&self) -> Result<L1BlockInfoTx> {
+        let L2ExecutionPayloadEnvelope { execution_payload, .. } = self;
+
+        let Some(first_tx) = execution_payload.transactions.first() else {
             anyhow::bail!(
                 "L2 block is missing L1 info deposit transaction, block hash: {}",
                 execution_payload.block_hash
             );
-        }
-        let ty = execution_payload.transactions[0][0];
+        };
+        let Some(&ty) = first_tx.first() else {
+            anyhow::bail!("First payload transaction is empty");
+        };
         if ty != OpTxType::Deposit as u8 {
             anyhow::bail!("First payload transaction has unexpected type: {:?}", ty);
         }
-        let tx = OpTxEnvelope::decode(&mut execution_payload.transactions[0][1..].as_ref())
-            .map_err(|e| anyhow::anyhow!(e))?;
+        let tx =
+            OpTxEnvelope::decode(&mut first_tx[1..].as_ref()).map_err(|e| anyhow::anyhow!(e))?;
 
         let OpTxEnvelope::Deposit(tx) = tx else {
             anyhow::bail!("First payload transaction has unexpected type: {:?}", tx.tx_type());
         };
 
-        let l1_info = L1BlockInfoTx::decode_calldata(tx.input.as_ref())?;
-        let l1_fee_scalar = match l1_info {
-            L1BlockInfoTx::Bedrock(L1BlockInfoBedrock { l1_fee_scalar, .. }) => l1_fee_scalar,
-            L1BlockInfoTx::Ecotone(L1BlockInfoEcotone {
-                blob_base_fee,
-                blob_base_fee_scalar,
-                ..
-            }) => {
-                // Translate Ecotone values back into encoded scalar if needed.
-                // We do not know if it was derived from a v0 or v1 scalar,
-                // but v1 is fine, a 0 blob base fee has the same effect.
-                let mut buf = B256::ZERO;
-                buf[0] = 0x01;
-                buf[24..28].copy_from_slice(blob_base_fee_scalar.to_be_bytes().as_ref());
-                buf[28..32].copy_from_slice(blob_base_fee.to_be_bytes().as_ref());
-                buf.into()
-            }
-        };
-
-        Ok(SystemConfig {
-            batcher_addr: l1_info.batcher_address(),
-            l1_fee_overhead: l1_info.l1_fee_overhead(),
-            l1_fee_scalar,
-            gas_limit: U256::from(execution_payload.gas_limit),
-        })
+        L1BlockInfoTx::decode_calldata(tx.input.as_ref())
     }
 }
 
@@ -287,3 +278,69 @@ impl From<OpBlock> for L2ExecutionPayloadEnvelope {
         }
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::OP_MAINNET_CONFIG;
+    use alloc::vec;
+
+    fn payload(
+        block_number: u64,
+        block_hash: B256,
+        transactions: Vec<Bytes>,
+    ) -> L2ExecutionPayloadEnvelope {
+        L2ExecutionPayloadEnvelope {
+            parent_beacon_block_root: None,
+            execution_payload: L2ExecutionPayload {
+                block_number,
+                block_hash,
+                transactions,
+                ..Default::default()
+            },
+        }
+    }
+
+    #[test]
+    fn test_to_system_config_genesis() {
+        let genesis = OP_MAINNET_CONFIG.genesis;
+        let envelope = payload(genesis.l2.number, genesis.l2.hash, vec![]);
+        assert_eq!(envelope.to_system_config(&OP_MAINNET_CONFIG).unwrap(), genesis.system_config);
+
+        let l2_block_ref = envelope.to_l2_block_ref(&OP_MAINNET_CONFIG).unwrap();
+        assert_eq!(l2_block_ref.l1_origin, genesis.l1);
+        assert_eq!(l2_block_ref.seq_num, 0);
+    }
+
+    #[test]
+    fn test_to_system_config_invalid_genesis_hash() {
+        let envelope = payload(OP_MAINNET_CONFIG.genesis.l2.number, B256::ZERO, vec![]);
+        let err = envelope.to_system_config(&OP_MAINNET_CONFIG).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid genesis hash");
+        assert!(envelope.to_l2_block_ref(&OP_MAINNET_CONFIG).is_err());
+    }
+
+    #[test]
+    fn test_to_system_config_missing_deposit() {
+        let envelope = payload(OP_MAINNET_CONFIG.genesis.l2.number + 1, B256::ZERO, vec![]);
+        let err = envelope.to_system_config(&OP_MAINNET_CONFIG).unwrap_err();
+        assert!(err.to_string().starts_with("L2 block is missing L1 info deposit transaction"));
+        assert!(envelope.to_l2_block_ref(&OP_MAINNET_CONFIG).is_err());
+    }
+
+    #[test]
+    fn test_to_system_config_empty_first_tx() {
+        let envelope =
+            payload(OP_MAINNET_CONFIG.genesis.l2.number + 1, B256::ZERO, vec![Bytes::new()]);
+        let err = envelope.to_system_config(&OP_MAINNET_CONFIG).unwrap_err();
+        assert_eq!(err.to_string(), "First payload transaction is empty");
+    }
+
+    #[test]
+    fn test_to_system_config_non_deposit_first_tx() {
+        let tx = Bytes::from_static(&[OpTxType::Eip1559 as u8, 0xc0]);
+        let envelope = payload(OP_MAINNET_CONFIG.genesis.l2.number + 1, B256::ZERO, vec![tx]);
+        let err = envelope.to_system_config(&OP_MAINNET_CONFIG).unwrap_err();
+        assert_eq!(err.to_string(), "First payload transaction has unexpected type: 2");
+    }
+}
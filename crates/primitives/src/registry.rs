@@ -0,0 +1,122 @@
+//! This module contains the [RollupConfig]s of the known chains of the superchain registry. It
+//! is only compiled with the `registry` feature, so that client programs, which are given their
+//! [RollupConfig], do not carry the table.
+
+use crate::{block::BlockID, genesis::Genesis, system_config::SystemConfig, RollupConfig};
+use alloy_primitives::{address, b256, U256};
+
+impl RollupConfig {
+    /// Returns the [RollupConfig] of a known chain from the superchain registry by its L2 chain
+    /// ID, or `None` if the chain is unknown.
+    pub fn from_l2_chain_id(l2_chain_id: u64) -> Option<Self> {
+        match l2_chain_id {
+            10 => Some(OP_MAINNET_CONFIG),
+            8453 => Some(BASE_MAINNET_CONFIG),
+            _ => None,
+        }
+    }
+}
+
+/// The [RollupConfig] for OP Mainnet.
+pub const OP_MAINNET_CONFIG: RollupConfig = RollupConfig {
+    genesis: Genesis {
+        l1: BlockID {
+            hash: b256!("438335a20d98863a4c0c97999eb2481921ccd28553eac6f913af7c12aec04108"),
+            number: 17_422_590,
+        },
+        l2: BlockID {
+            hash: b256!("dbf6a80fef073de06add9b0d14026d6e5a86c85f6d102c36d3d8e9cf89c2afd3"),
+            number: 105_235_063,
+        },
+        timestamp: 1_686_068_903,
+        system_config: SystemConfig {
+            batcher_addr: address!("6887246668a3b87f54deb3b94ba47a6f63f32985"),
+            gas_limit: U256::from_limbs([30_000_000, 0, 0, 0]),
+            l1_fee_overhead: U256::from_limbs([0xbc, 0, 0, 0]),
+            l1_fee_scalar: U256::from_limbs([0xa6fe0, 0, 0, 0]),
+            eip1559_denominator: 0,
+            eip1559_elasticity: 0,
+        },
+    },
+    block_time: 2,
+    max_sequencer_drift: 600,
+    seq_window_size: 3600,
+    channel_timeout: 300,
+    l1_chain_id: 1,
+    l2_chain_id: 10,
+    regolith_time: Some(0),
+    canyon_time: Some(1_704_992_401),
+    delta_time: Some(1_708_560_000),
+    ecotone_time: Some(1_710_374_401),
+    fjord_time: Some(1_720_627_201),
+    holocene_time: None,
+    interop_time: None,
+    batch_inbox_address: address!("ff00000000000000000000000000000000000010"),
+    deposit_contract_address: address!("beb5fc579115071764c7423a4f12edde41f106ed"),
+    l1_system_config_address: address!("229047fed2591dbec1ef1118d64f7af3db9eb290"),
+    protocol_versions_address: address!("8062abc286f5e7d9428a0ccb9abd71e50d93b935"),
+    blobs_enabled_l1_timestamp: None,
+    da_challenge_address: None,
+    max_channel_bank_size: None,
+};
+
+/// The [RollupConfig] for Base Mainnet.
+pub const BASE_MAINNET_CONFIG: RollupConfig = RollupConfig {
+    genesis: Genesis {
+        l1: BlockID {
+            hash: b256!("5c13d307623a926cd31415036c8b7fa14572f9dac64528e857a470511fc30771"),
+            number: 17_481_768,
+        },
+        l2: BlockID {
+            hash: b256!("f712aa9241cc24369b143cf6dce85f0902a9731e70d66818a3a5845b296c73dd"),
+            number: 0,
+        },
+        timestamp: 1_686_789_347,
+        system_config: SystemConfig {
+            batcher_addr: address!("5050f69a9786f081509234f1a7f4684b5e5b76c9"),
+            gas_limit: U256::from_limbs([30_000_000, 0, 0, 0]),
+            l1_fee_overhead: U256::from_limbs([0xbc, 0, 0, 0]),
+            l1_fee_scalar: U256::from_limbs([0xa6fe0, 0, 0, 0]),
+            eip1559_denominator: 0,
+            eip1559_elasticity: 0,
+        },
+    },
+    block_time: 2,
+    max_sequencer_drift: 600,
+    seq_window_size: 3600,
+    channel_timeout: 300,
+    l1_chain_id: 1,
+    l2_chain_id: 8453,
+    regolith_time: Some(0),
+    canyon_time: Some(1_704_992_401),
+    delta_time: Some(1_708_560_000),
+    ecotone_time: Some(1_710_374_401),
+    fjord_time: Some(1_720_627_201),
+    holocene_time: None,
+    interop_time: None,
+    batch_inbox_address: address!("ff00000000000000000000000000000000008453"),
+    deposit_contract_address: address!("49048044d57e1c92a77f79988d21fa8faf74e97e"),
+    l1_system_config_address: address!("73a79fab69143498ed3712e519a88a918e1f4072"),
+    protocol_versions_address: address!("8062abc286f5e7d9428a0ccb9abd71e50d93b935"),
+    blobs_enabled_l1_timestamp: None,
+    da_challenge_address: None,
+    max_channel_bank_size: None,
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_l2_chain_id() {
+        assert_eq!(RollupConfig::from_l2_chain_id(10), Some(OP_MAINNET_CONFIG));
+        assert_eq!(RollupConfig::from_l2_chain_id(8453), Some(BASE_MAINNET_CONFIG));
+        assert_eq!(RollupConfig::from_l2_chain_id(1), None);
+    }
+
+    #[test]
+    fn test_check_known_configs() {
+        assert_eq!(OP_MAINNET_CONFIG.check(), Ok(()));
+        assert_eq!(BASE_MAINNET_CONFIG.check(), Ok(()));
+    }
+}
@@ -2,9 +2,14 @@
 
 use crate::genesis::Genesis;
 use alloy_primitives::Address;
+use core::fmt::Display;
 
 /// The Rollup configuration.
-#[derive(Debug, Clone, Copy, Default)]
+///
+/// With the `serde` feature enabled, this type deserializes from the `rollup.json` format
+/// published by op-node and the superchain registry. Unknown fields are ignored and missing
+/// fork activation times are treated as inactive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct RollupConfig {
     /// The genesis state of the rollup.
@@ -52,6 +57,11 @@ pub struct RollupConfig {
     /// otherwise.
     #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
     pub fjord_time: Option<u64>,
+    /// `holocene_time` sets the activation time of the Holocene network upgrade.
+    /// Active if `holocene_time` != None && L2 block timestamp >= Some(holocene_time), inactive
+    /// otherwise.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub holocene_time: Option<u64>,
     /// `interop_time` sets the activation time for an experimental feature-set, activated like a
     /// hardfork. Active if `interop_time` != None && L2 block timestamp >= Some(interop_time),
     /// inactive otherwise.
@@ -64,6 +74,7 @@ pub struct RollupConfig {
     /// `l1_system_config_address` is the L1 address that the system config is stored at.
     pub l1_system_config_address: Address,
     /// `protocol_versions_address` is the L1 address that the protocol versions are stored at.
+    #[cfg_attr(feature = "serde", serde(default))]
     pub protocol_versions_address: Address,
     /// `blobs_enabled_l1_timestamp` is the timestamp to start reading blobs as a batch data
     /// source. Optional.
@@ -74,8 +85,15 @@ pub struct RollupConfig {
     pub blobs_enabled_l1_timestamp: Option<u64>,
     /// `da_challenge_address` is the L1 address that the data availability challenge contract is
     /// stored at.
-    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(alias = "da_challenge_contract_address", skip_serializing_if = "Option::is_none")
+    )]
     pub da_challenge_address: Option<Address>,
+    /// `max_channel_bank_size` overrides the maximum size of the channel bank, in bytes of
+    /// buffered frame data. Intended for test chains; the protocol default is used when `None`.
+    #[cfg_attr(feature = "serde", serde(skip_serializing_if = "Option::is_none"))]
+    pub max_channel_bank_size: Option<u64>,
 }
 
 impl RollupConfig {
@@ -99,11 +117,32 @@ impl RollupConfig {
         self.ecotone_time.map_or(false, |t| timestamp >= t)
     }
 
+    /// Returns true if the timestamp marks the first block where Ecotone is active, i.e. Ecotone
+    /// is active at the given timestamp but not at the timestamp of the parent block.
+    pub fn is_ecotone_activation_block(&self, timestamp: u64) -> bool {
+        self.is_ecotone_active(timestamp) &&
+            timestamp >= self.block_time &&
+            !self.is_ecotone_active(timestamp - self.block_time)
+    }
+
     /// Returns true if Fjord is active at the given timestamp.
     pub fn is_fjord_active(&self, timestamp: u64) -> bool {
         self.fjord_time.map_or(false, |t| timestamp >= t)
     }
 
+    /// Returns true if the timestamp marks the first block where Fjord is active, i.e. Fjord is
+    /// active at the given timestamp but not at the timestamp of the parent block.
+    pub fn is_fjord_activation_block(&self, timestamp: u64) -> bool {
+        self.is_fjord_active(timestamp) &&
+            timestamp >= self.block_time &&
+            !self.is_fjord_active(timestamp - self.block_time)
+    }
+
+    /// Returns true if Holocene is active at the given timestamp.
+    pub fn is_holocene_active(&self, timestamp: u64) -> bool {
+        self.holocene_time.map_or(false, |t| timestamp >= t)
+    }
+
     /// Returns true if Interop is active at the given timestamp.
     pub fn is_interop_active(&self, timestamp: u64) -> bool {
         self.interop_time.map_or(false, |t| timestamp >= t)
@@ -115,6 +154,56 @@ impl RollupConfig {
         self.da_challenge_address.map_or(false, |addr| !addr.is_zero())
     }
 
+    /// Checks the [RollupConfig] for internal consistency.
+    ///
+    /// Fork activation times must be set in activation order, the batch inbox and batcher
+    /// addresses must be set, the block time, channel timeout and sequencing window must be
+    /// non-zero, and the genesis L1 and L2 blocks must be identified.
+    pub fn check(&self) -> Result<(), RollupConfigError> {
+        if self.block_time == 0 {
+            return Err(RollupConfigError::ZeroBlockTime);
+        }
+        if self.channel_timeout == 0 {
+            return Err(RollupConfigError::ZeroChannelTimeout);
+        }
+        if self.seq_window_size == 0 {
+            return Err(RollupConfigError::ZeroSeqWindowSize);
+        }
+        if self.genesis.l1.hash.is_zero() {
+            return Err(RollupConfigError::MissingGenesisL1Hash);
+        }
+        if self.genesis.l2.hash.is_zero() {
+            return Err(RollupConfigError::MissingGenesisL2Hash);
+        }
+        if self.batch_inbox_address.is_zero() {
+            return Err(RollupConfigError::ZeroBatchInboxAddress);
+        }
+        if self.genesis.system_config.batcher_addr.is_zero() {
+            return Err(RollupConfigError::ZeroBatcherAddress);
+        }
+
+        let forks = [
+            ("regolith", self.regolith_time),
+            ("canyon", self.canyon_time),
+            ("delta", self.delta_time),
+            ("ecotone", self.ecotone_time),
+            ("fjord", self.fjord_time),
+            ("holocene", self.holocene_time),
+        ];
+        for window in forks.windows(2) {
+            let ((prev, prev_time), (next, next_time)) = (window[0], window[1]);
+            match (prev_time, next_time) {
+                (None, Some(_)) => return Err(RollupConfigError::MissingPriorFork(prev, next)),
+                (Some(prev_time), Some(next_time)) if next_time < prev_time => {
+                    return Err(RollupConfigError::ForkOrder(prev, next));
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
     /// Checks the scalar value in Ecotone.
     pub fn check_ecotone_l1_system_config_scalar(scalar: [u8; 32]) -> Result<(), &'static str> {
         let version_byte = scalar[0];
@@ -138,3 +227,188 @@ impl RollupConfig {
         }
     }
 }
+
+/// An error returned by [RollupConfig::check] for an inconsistent [RollupConfig].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RollupConfigError {
+    /// The block time is zero.
+    ZeroBlockTime,
+    /// The channel timeout is zero.
+    ZeroChannelTimeout,
+    /// The sequencing window size is zero.
+    ZeroSeqWindowSize,
+    /// The genesis L1 block hash is not set.
+    MissingGenesisL1Hash,
+    /// The genesis L2 block hash is not set.
+    MissingGenesisL2Hash,
+    /// The batch inbox address is the zero address.
+    ZeroBatchInboxAddress,
+    /// The genesis batcher address is the zero address.
+    ZeroBatcherAddress,
+    /// A fork is scheduled while the fork preceding it is not.
+    /// Prior fork name, scheduled fork name.
+    MissingPriorFork(&'static str, &'static str),
+    /// A fork activates before the fork preceding it.
+    /// Prior fork name, misordered fork name.
+    ForkOrder(&'static str, &'static str),
+}
+
+impl Display for RollupConfigError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            RollupConfigError::ZeroBlockTime => write!(f, "Block time cannot be 0"),
+            RollupConfigError::ZeroChannelTimeout => write!(f, "Channel timeout cannot be 0"),
+            RollupConfigError::ZeroSeqWindowSize => {
+                write!(f, "Sequencing window size cannot be 0")
+            }
+            RollupConfigError::MissingGenesisL1Hash => write!(f, "Genesis L1 hash cannot be empty"),
+            RollupConfigError::MissingGenesisL2Hash => write!(f, "Genesis L2 hash cannot be empty"),
+            RollupConfigError::ZeroBatchInboxAddress => {
+                write!(f, "Batch inbox address cannot be the zero address")
+            }
+            RollupConfigError::ZeroBatcherAddress => {
+                write!(f, "Genesis batcher address cannot be the zero address")
+            }
+            RollupConfigError::MissingPriorFork(prev, next) => {
+                write!(f, "Fork {} is scheduled, but prior fork {} is not", next, prev)
+            }
+            RollupConfigError::ForkOrder(prev, next) => {
+                write!(f, "Fork {} activates before prior fork {}", next, prev)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::OP_MAINNET_CONFIG;
+    use alloy_primitives::U256;
+
+    #[test]
+    fn test_check_zero_block_time() {
+        let cfg = RollupConfig { block_time: 0, ..OP_MAINNET_CONFIG };
+        assert_eq!(cfg.check(), Err(RollupConfigError::ZeroBlockTime));
+    }
+
+    #[test]
+    fn test_check_zero_channel_timeout() {
+        let cfg = RollupConfig { channel_timeout: 0, ..OP_MAINNET_CONFIG };
+        assert_eq!(cfg.check(), Err(RollupConfigError::ZeroChannelTimeout));
+    }
+
+    #[test]
+    fn test_check_zero_seq_window_size() {
+        let cfg = RollupConfig { seq_window_size: 0, ..OP_MAINNET_CONFIG };
+        assert_eq!(cfg.check(), Err(RollupConfigError::ZeroSeqWindowSize));
+    }
+
+    #[test]
+    fn test_check_missing_genesis_l1_hash() {
+        let mut cfg = OP_MAINNET_CONFIG;
+        cfg.genesis.l1.hash = Default::default();
+        assert_eq!(cfg.check(), Err(RollupConfigError::MissingGenesisL1Hash));
+    }
+
+    #[test]
+    fn test_check_missing_genesis_l2_hash() {
+        let mut cfg = OP_MAINNET_CONFIG;
+        cfg.genesis.l2.hash = Default::default();
+        assert_eq!(cfg.check(), Err(RollupConfigError::MissingGenesisL2Hash));
+    }
+
+    #[test]
+    fn test_check_zero_batch_inbox_address() {
+        let cfg = RollupConfig { batch_inbox_address: Address::ZERO, ..OP_MAINNET_CONFIG };
+        assert_eq!(cfg.check(), Err(RollupConfigError::ZeroBatchInboxAddress));
+    }
+
+    #[test]
+    fn test_check_zero_batcher_address() {
+        let mut cfg = OP_MAINNET_CONFIG;
+        cfg.genesis.system_config.batcher_addr = Address::ZERO;
+        assert_eq!(cfg.check(), Err(RollupConfigError::ZeroBatcherAddress));
+    }
+
+    #[test]
+    fn test_check_missing_prior_fork() {
+        let cfg = RollupConfig { ecotone_time: None, ..OP_MAINNET_CONFIG };
+        assert_eq!(cfg.check(), Err(RollupConfigError::MissingPriorFork("ecotone", "fjord")));
+    }
+
+    #[test]
+    fn test_activation_blocks() {
+        let cfg = OP_MAINNET_CONFIG;
+        let ecotone = cfg.ecotone_time.unwrap();
+        assert!(!cfg.is_ecotone_activation_block(ecotone - cfg.block_time));
+        assert!(cfg.is_ecotone_activation_block(ecotone));
+        assert!(!cfg.is_ecotone_activation_block(ecotone + cfg.block_time));
+
+        let fjord = cfg.fjord_time.unwrap();
+        assert!(!cfg.is_fjord_activation_block(fjord - cfg.block_time));
+        assert!(cfg.is_fjord_activation_block(fjord));
+        assert!(!cfg.is_fjord_activation_block(fjord + cfg.block_time));
+
+        let cfg = RollupConfig { fjord_time: Some(0), ..Default::default() };
+        assert!(!cfg.is_fjord_activation_block(0));
+    }
+
+    #[test]
+    fn test_check_fork_order() {
+        let cfg = RollupConfig { delta_time: Some(1), ..OP_MAINNET_CONFIG };
+        assert_eq!(cfg.check(), Err(RollupConfigError::ForkOrder("canyon", "delta")));
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialize_op_mainnet_rollup_config() {
+        let raw = include_str!("../testdata/op_mainnet_rollup.json");
+        let config: RollupConfig = serde_json::from_str(raw).unwrap();
+        assert_eq!(config, OP_MAINNET_CONFIG);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialize_base_mainnet_rollup_config() {
+        let raw = include_str!("../testdata/base_mainnet_rollup.json");
+        let config: RollupConfig = serde_json::from_str(raw).unwrap();
+        assert_eq!(config, BASE_MAINNET_CONFIG);
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_deserialize_missing_forks_and_unknown_fields() {
+        let raw = r#"{
+            "genesis": {
+                "l1": {"hash": "0x0000000000000000000000000000000000000000000000000000000000000000", "number": 1},
+                "l2": {"hash": "0x0000000000000000000000000000000000000000000000000000000000000000", "number": 2},
+                "l2_time": 3,
+                "system_config": {
+                    "batcherAddr": "0x0000000000000000000000000000000000000000",
+                    "overhead": "0x0",
+                    "scalar": "0x0",
+                    "gasLimit": "0x1c9c380"
+                }
+            },
+            "block_time": 2,
+            "max_sequencer_drift": 600,
+            "seq_window_size": 3600,
+            "channel_timeout": 300,
+            "l1_chain_id": 1,
+            "l2_chain_id": 1234,
+            "regolith_time": 0,
+            "batch_inbox_address": "0x0000000000000000000000000000000000000000",
+            "deposit_contract_address": "0x0000000000000000000000000000000000000000",
+            "l1_system_config_address": "0x0000000000000000000000000000000000000000",
+            "unknown_field": {"nested": true}
+        }"#;
+        let config: RollupConfig = serde_json::from_str(raw).unwrap();
+        assert_eq!(config.genesis.timestamp, 3);
+        assert_eq!(config.genesis.system_config.gas_limit, U256::from(30_000_000));
+        assert_eq!(config.regolith_time, Some(0));
+        assert_eq!(config.canyon_time, None);
+        assert_eq!(config.ecotone_time, None);
+        assert_eq!(config.fjord_time, None);
+        assert_eq!(config.protocol_versions_address, Address::ZERO);
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     rollup_config::RollupConfig,
 };
 use alloy_consensus::Receipt;
-use alloy_primitives::{address, Address, Log, U256};
+use alloy_primitives::{address, Address, Log, B64, U256};
 use alloy_sol_types::{sol, SolType};
 use anyhow::{anyhow, bail, Result};
 
@@ -17,6 +17,7 @@ pub struct SystemConfig {
     /// Batch sender address
     pub batcher_addr: Address,
     /// L2 gas limit
+    #[cfg_attr(feature = "serde", serde(deserialize_with = "parse_u256_number_or_string"))]
     pub gas_limit: U256,
     /// Fee overhead
     #[cfg_attr(feature = "serde", serde(rename = "overhead"))]
@@ -24,6 +25,40 @@ pub struct SystemConfig {
     /// Fee scalar
     #[cfg_attr(feature = "serde", serde(rename = "scalar"))]
     pub l1_fee_scalar: U256,
+    /// EIP-1559 base fee max change denominator, configurable after Holocene.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub eip1559_denominator: u32,
+    /// EIP-1559 elasticity multiplier, configurable after Holocene.
+    #[cfg_attr(feature = "serde", serde(default))]
+    pub eip1559_elasticity: u32,
+}
+
+/// Deserializes a [U256] from either a JSON number or a hex / decimal string. Rollup configs
+/// published by op-node encode the gas limit as a plain number.
+#[cfg(feature = "serde")]
+fn parse_u256_number_or_string<'de, D>(de: D) -> Result<U256, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    struct U256Visitor;
+
+    impl<'de> serde::de::Visitor<'de> for U256Visitor {
+        type Value = U256;
+
+        fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.write_str("a number or a hex / decimal string")
+        }
+
+        fn visit_u64<E: serde::de::Error>(self, v: u64) -> Result<U256, E> {
+            Ok(U256::from(v))
+        }
+
+        fn visit_str<E: serde::de::Error>(self, v: &str) -> Result<U256, E> {
+            v.parse().map_err(E::custom)
+        }
+    }
+
+    de.deserialize_any(U256Visitor)
 }
 
 /// Represents type of update to the system config.
@@ -38,6 +73,8 @@ pub enum SystemConfigUpdateType {
     GasLimit = 2,
     /// Unsafe block signer update type
     UnsafeBlockSigner = 3,
+    /// EIP-1559 parameters update type
+    Eip1559 = 4,
 }
 
 impl TryFrom<u64> for SystemConfigUpdateType {
@@ -49,6 +86,7 @@ impl TryFrom<u64> for SystemConfigUpdateType {
             1 => Ok(SystemConfigUpdateType::GasConfig),
             2 => Ok(SystemConfigUpdateType::GasLimit),
             3 => Ok(SystemConfigUpdateType::UnsafeBlockSigner),
+            4 => Ok(SystemConfigUpdateType::Eip1559),
             _ => bail!("Invalid SystemConfigUpdateType value: {}", value),
         }
     }
@@ -56,6 +94,8 @@ impl TryFrom<u64> for SystemConfigUpdateType {
 
 impl SystemConfig {
     /// Filters all L1 receipts to find config updates and applies the config updates.
+    ///
+    /// Malformed config update logs are skipped, and leave the [SystemConfig] unchanged.
     pub fn update_with_receipts(
         &mut self,
         receipts: &[Receipt],
@@ -67,20 +107,31 @@ impl SystemConfig {
                 continue;
             }
 
-            receipt.logs.iter().try_for_each(|log| {
+            receipt.logs.iter().for_each(|log| {
                 let topics = log.topics();
                 if log.address == rollup_config.l1_system_config_address &&
                     !topics.is_empty() &&
                     topics[0] == CONFIG_UPDATE_TOPIC
                 {
-                    self.process_config_update_log(log, rollup_config, l1_time)?;
+                    // Updates are only applied once the whole log is validated, so a malformed
+                    // log cannot partially apply.
+                    let _ = self.process_config_update_log(log, rollup_config, l1_time);
                 }
-                Ok(())
-            })?;
+            });
         }
         Ok(())
     }
 
+    /// Returns the EIP-1559 parameters of the [SystemConfig], as encoded in the `eip1559Params`
+    /// payload attribute after Holocene: the denominator followed by the elasticity, both as
+    /// big-endian u32s.
+    pub fn eip_1559_params(&self) -> B64 {
+        let mut params = [0u8; 8];
+        params[..4].copy_from_slice(&self.eip1559_denominator.to_be_bytes());
+        params[4..].copy_from_slice(&self.eip1559_elasticity.to_be_bytes());
+        B64::from(params)
+    }
+
     /// Decodes an EVM log entry emitted by the system config contract and applies it as a
     /// [SystemConfig] change.
     ///
@@ -99,8 +150,8 @@ impl SystemConfig {
         rollup_config: &RollupConfig,
         l1_time: u64,
     ) -> Result<()> {
-        if log.topics().len() < 3 {
-            bail!("Invalid config update log: not enough topics");
+        if log.topics().len() != 3 {
+            bail!("Invalid config update log: unexpected number of topics");
         }
         if log.topics()[0] != CONFIG_UPDATE_TOPIC {
             bail!("Invalid config update log: invalid topic");
@@ -117,8 +168,17 @@ impl SystemConfig {
                 .map_err(|_| anyhow!("Failed to convert update type to u64"))?,
         );
         let log_data = log.data.data.as_ref();
+        if log_data.len() % 32 != 0 {
+            bail!("Invalid config update log: unaligned data");
+        }
 
-        match update_type.try_into()? {
+        // Unknown update types are ignored, so that new update types introduced on L1 do not halt
+        // derivation.
+        let Ok(update_type) = SystemConfigUpdateType::try_from(update_type) else {
+            return Ok(());
+        };
+
+        match update_type {
             SystemConfigUpdateType::Batcher => {
                 if log_data.len() != 96 {
                     bail!("Invalid config update log: invalid data length");
@@ -201,6 +261,27 @@ impl SystemConfig {
             SystemConfigUpdateType::UnsafeBlockSigner => {
                 // Ignored in derivation
             }
+            SystemConfigUpdateType::Eip1559 => {
+                if log_data.len() != 96 {
+                    bail!("Invalid config update log: invalid data length");
+                }
+
+                let pointer = <sol!(uint64)>::abi_decode(&log_data[0..32], true)
+                    .map_err(|_| anyhow!("Invalid config update log: invalid data pointer"))?;
+                if pointer != 32 {
+                    bail!("Invalid config update log: invalid data pointer");
+                }
+                let length = <sol!(uint64)>::abi_decode(&log_data[32..64], true)
+                    .map_err(|_| anyhow!("Invalid config update log: invalid data length"))?;
+                if length != 32 {
+                    bail!("Invalid config update log: invalid data length");
+                }
+
+                let params = <sol!(uint64)>::abi_decode(&log_data[64..], true)
+                    .map_err(|_| anyhow!("Invalid config update log: invalid EIP-1559 params"))?;
+                self.eip1559_denominator = (params >> 32) as u32;
+                self.eip1559_elasticity = params as u32;
+            }
         }
 
         Ok(())
@@ -258,6 +339,7 @@ mod test {
             delta_time: Some(0),
             ecotone_time: Some(10),
             fjord_time: Some(0),
+            holocene_time: None,
             interop_time: Some(0),
             batch_inbox_address: Address::ZERO,
             deposit_contract_address: Address::ZERO,
@@ -265,6 +347,7 @@ mod test {
             protocol_versions_address: Address::ZERO,
             blobs_enabled_l1_timestamp: Some(0),
             da_challenge_address: Some(Address::ZERO),
+            max_channel_bank_size: None,
         }
     }
 
@@ -376,4 +459,121 @@ mod test {
 
         assert_eq!(system_config.gas_limit, U256::from(0xbeef));
     }
+
+    #[test]
+    fn test_system_config_update_eip1559_params_log() {
+        const UPDATE_TYPE: B256 =
+            b256!("0000000000000000000000000000000000000000000000000000000000000004");
+
+        let mut system_config = SystemConfig::default();
+        let rollup_config = mock_rollup_config(system_config);
+
+        let update_log = Log {
+            address: Address::ZERO,
+            data: LogData::new_unchecked(
+                vec![
+                    CONFIG_UPDATE_TOPIC,
+                    CONFIG_UPDATE_EVENT_VERSION_0,
+                    UPDATE_TYPE,
+                ],
+                hex!("000000000000000000000000000000000000000000000000000000000000002000000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000000000000fa00000006").into()
+            )
+        };
+
+        // Update the EIP-1559 parameters.
+        system_config.process_config_update_log(&update_log, &rollup_config, 0).unwrap();
+
+        assert_eq!(system_config.eip1559_denominator, 250);
+        assert_eq!(system_config.eip1559_elasticity, 6);
+        assert_eq!(system_config.eip_1559_params(), B64::from([0, 0, 0, 0xfa, 0, 0, 0, 6]));
+    }
+
+    #[test]
+    fn test_system_config_update_unknown_type_ignored() {
+        const UPDATE_TYPE: B256 =
+            b256!("00000000000000000000000000000000000000000000000000000000000000ff");
+
+        let mut system_config = SystemConfig::default();
+        let rollup_config = mock_rollup_config(system_config);
+
+        let update_log = Log {
+            address: Address::ZERO,
+            data: LogData::new_unchecked(
+                vec![
+                    CONFIG_UPDATE_TOPIC,
+                    CONFIG_UPDATE_EVENT_VERSION_0,
+                    UPDATE_TYPE,
+                ],
+                hex!("00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000beef").into()
+            )
+        };
+
+        system_config.process_config_update_log(&update_log, &rollup_config, 0).unwrap();
+        assert_eq!(system_config, SystemConfig::default());
+    }
+
+    #[test]
+    fn test_system_config_update_invalid_topics_len() {
+        let mut system_config = SystemConfig::default();
+        let rollup_config = mock_rollup_config(system_config);
+
+        let update_log = Log {
+            address: Address::ZERO,
+            data: LogData::new_unchecked(
+                vec![CONFIG_UPDATE_TOPIC, CONFIG_UPDATE_EVENT_VERSION_0],
+                hex!("00").into(),
+            ),
+        };
+
+        let err = system_config.process_config_update_log(&update_log, &rollup_config, 0);
+        assert!(err.is_err());
+    }
+
+    #[test]
+    fn test_system_config_update_with_receipts_skips_malformed_log() {
+        const UPDATE_TYPE_GAS_LIMIT: B256 =
+            b256!("0000000000000000000000000000000000000000000000000000000000000002");
+        const UPDATE_TYPE_BATCHER: B256 =
+            b256!("0000000000000000000000000000000000000000000000000000000000000000");
+
+        let mut system_config = SystemConfig::default();
+        let rollup_config = mock_rollup_config(system_config);
+
+        // The gas limit update carries unaligned data and must be skipped.
+        let malformed_log = Log {
+            address: Address::ZERO,
+            data: LogData::new_unchecked(
+                vec![
+                    CONFIG_UPDATE_TOPIC,
+                    CONFIG_UPDATE_EVENT_VERSION_0,
+                    UPDATE_TYPE_GAS_LIMIT,
+                ],
+                hex!("00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000beefbeef").into()
+            )
+        };
+        let batcher_log = Log {
+            address: Address::ZERO,
+            data: LogData::new_unchecked(
+                vec![
+                    CONFIG_UPDATE_TOPIC,
+                    CONFIG_UPDATE_EVENT_VERSION_0,
+                    UPDATE_TYPE_BATCHER,
+                ],
+                hex!("00000000000000000000000000000000000000000000000000000000000000200000000000000000000000000000000000000000000000000000000000000020000000000000000000000000000000000000000000000000000000000000beef").into()
+            )
+        };
+        let receipts = vec![Receipt {
+            status: true,
+            logs: vec![malformed_log, batcher_log],
+            ..Default::default()
+        }];
+
+        system_config.update_with_receipts(&receipts, &rollup_config, 0).unwrap();
+
+        assert_eq!(system_config.gas_limit, U256::ZERO);
+        assert_eq!(
+            system_config.batcher_addr,
+            address!("000000000000000000000000000000000000bEEF")
+        );
+    }
 }
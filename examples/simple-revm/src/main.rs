@@ -56,10 +56,8 @@ pub extern "C" fn _start() {
 /// Boot the program and load bootstrap information.
 #[inline]
 fn boot(oracle: &mut OracleReader) -> Result<([u8; 32], Vec<u8>)> {
-    let digest = oracle
-        .get(PreimageKey::new_local(DIGEST_IDENT))?
-        .try_into()
-        .map_err(|_| anyhow!("Failed to convert digest to [u8; 32]"))?;
+    let mut digest = [0u8; 32];
+    oracle.get_exact(PreimageKey::new_local(DIGEST_IDENT), &mut digest)?;
     let code = oracle.get(PreimageKey::new_local(CODE_IDENT))?;
 
     Ok((digest, code))